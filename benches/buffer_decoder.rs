@@ -0,0 +1,94 @@
+use bytes::BytesMut;
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use rustis::{
+    resp::{BufferDecoder, RespBuf, RespDeserializer},
+    Error, Result,
+};
+use serde::{de::IgnoredAny, Deserialize};
+use std::time::Duration;
+use tokio_util::codec::Decoder;
+
+const NUM_KEYS: usize = 10_000;
+const VALUE_LEN: usize = 100;
+/// Size of the reads from the socket
+const CHUNK_LEN: usize = 16 * 1024;
+
+/// Former decoder: each call deserializes the frame from its first byte
+struct FormerDecoder;
+
+impl Decoder for FormerDecoder {
+    type Item = RespBuf;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>> {
+        if src.is_empty() {
+            return Ok(None);
+        }
+
+        let mut deserializer = RespDeserializer::new(src.as_ref());
+        match IgnoredAny::deserialize(&mut deserializer) {
+            Ok(_) => Ok(Some(RespBuf::new(
+                src.split_to(deserializer.get_pos()).freeze(),
+            ))),
+            Err(Error::EOF) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Reply of a `MGET` of `NUM_KEYS` keys
+fn mget_reply() -> Vec<u8> {
+    let value = "x".repeat(VALUE_LEN);
+    let mut reply = format!("*{NUM_KEYS}\r\n").into_bytes();
+    for _ in 0..NUM_KEYS {
+        reply.extend_from_slice(format!("${VALUE_LEN}\r\n{value}\r\n").as_bytes());
+    }
+    reply
+}
+
+/// Decode `reply` received in reads of `chunk_len` bytes
+fn decode_reply<D: Decoder<Item = RespBuf, Error = Error>>(
+    decoder: &mut D,
+    reply: &[u8],
+    chunk_len: usize,
+) -> RespBuf {
+    let mut buf = BytesMut::new();
+    for chunk in reply.chunks(chunk_len) {
+        buf.extend_from_slice(chunk);
+        if let Some(frame) = decoder.decode(&mut buf).unwrap() {
+            return frame;
+        }
+    }
+    panic!("incomplete reply");
+}
+
+fn bench_buffer_decoder(c: &mut Criterion) {
+    let reply = mget_reply();
+
+    let mut group = c.benchmark_group("buffer_decoder_mget_10k");
+    group
+        .measurement_time(Duration::from_secs(10))
+        .throughput(Throughput::Bytes(reply.len() as u64))
+        .bench_function("former_single_read", |b| {
+            b.iter(|| decode_reply(&mut FormerDecoder, black_box(&reply), reply.len()))
+        })
+        .bench_function("single_read", |b| {
+            b.iter(|| {
+                decode_reply(
+                    &mut BufferDecoder::default(),
+                    black_box(&reply),
+                    reply.len(),
+                )
+            })
+        })
+        .bench_function("former_chunked_reads", |b| {
+            b.iter(|| decode_reply(&mut FormerDecoder, black_box(&reply), CHUNK_LEN))
+        })
+        .bench_function("chunked_reads", |b| {
+            b.iter(|| decode_reply(&mut BufferDecoder::default(), black_box(&reply), CHUNK_LEN))
+        });
+    group.finish();
+}
+
+criterion_group!(bench, bench_buffer_decoder);
+criterion_main!(bench);
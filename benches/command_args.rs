@@ -0,0 +1,53 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rustis::resp::{cmd, CommandArgs};
+use smallvec::SmallVec;
+use std::time::Duration;
+
+/// Former layout of `CommandArgs`: one heap allocation per argument
+fn build_heap_args(key: &str, field: &str, value: i64) -> SmallVec<[Vec<u8>; 10]> {
+    let mut buffer = itoa::Buffer::new();
+    let mut args = SmallVec::<[Vec<u8>; 10]>::new();
+    args.push(key.as_bytes().to_vec());
+    args.push(field.as_bytes().to_vec());
+    args.push(buffer.format(value).as_bytes().to_vec());
+    args
+}
+
+fn bench_command_args(c: &mut Criterion) {
+    let mut group = c.benchmark_group("command_args");
+    group
+        .measurement_time(Duration::from_secs(5))
+        .bench_function("heap_small_args", |b| {
+            b.iter(|| build_heap_args(black_box("key"), black_box("field"), black_box(42)))
+        })
+        .bench_function("inline_small_args", |b| {
+            b.iter(|| {
+                CommandArgs::default()
+                    .arg(black_box("key"))
+                    .arg(black_box("field"))
+                    .arg(black_box(42))
+                    .build()
+            })
+        })
+        .bench_function("inline_small_command", |b| {
+            b.iter(|| {
+                cmd("HSET")
+                    .arg(black_box("key"))
+                    .arg(black_box("field"))
+                    .arg(black_box(42))
+            })
+        })
+        .bench_function("large_args", |b| {
+            let value = "x".repeat(1024);
+            b.iter(|| {
+                CommandArgs::default()
+                    .arg(black_box("key"))
+                    .arg(black_box(value.as_str()))
+                    .build()
+            })
+        });
+    group.finish();
+}
+
+criterion_group!(bench, bench_command_args);
+criterion_main!(bench);
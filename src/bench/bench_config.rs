@@ -0,0 +1,133 @@
+use crate::resp::{cmd, Command};
+
+const DEFAULT_REQUESTS: usize = 100_000;
+const DEFAULT_CONNECTIONS: usize = 10;
+const DEFAULT_PIPELINE_DEPTH: usize = 1;
+const DEFAULT_KEY_SPACE: usize = 10_000;
+const DEFAULT_DATA_SIZE: usize = 3;
+const DEFAULT_KEY_PREFIX: &str = "bench:";
+
+/// Placeholder replaced by a random key index in the arguments of a [`BenchCommand::Custom`] command.
+///
+/// Same semantic as the `__rand_int__` placeholder of `redis-benchmark`.
+pub const RAND_INT_PLACEHOLDER: &str = "__rand_int__";
+
+/// Command sent by a benchmark
+#[derive(Debug, Clone)]
+pub enum BenchCommand {
+    /// [`PING`](https://redis.io/commands/ping/)
+    Ping,
+    /// [`SET`](https://redis.io/commands/set/) of a random key with a value of [`data_size`](BenchConfig::data_size) bytes
+    Set,
+    /// [`GET`](https://redis.io/commands/get/) of a random key
+    Get,
+    /// [`INCR`](https://redis.io/commands/incr/) of a random key
+    Incr,
+    /// [`LPUSH`](https://redis.io/commands/lpush/) of a value of [`data_size`](BenchConfig::data_size) bytes in a list
+    LPush,
+    /// [`LPOP`](https://redis.io/commands/lpop/) of a list
+    LPop,
+    /// [`SADD`](https://redis.io/commands/sadd/) of a random member in a set
+    SAdd,
+    /// [`HSET`](https://redis.io/commands/hset/) of a random field of a hash
+    HSet,
+    /// [`ZADD`](https://redis.io/commands/zadd/) of a random member in a sorted set
+    ZAdd,
+    /// Any custom command.
+    ///
+    /// Each argument equal to [`RAND_INT_PLACEHOLDER`] is replaced by a random key index.
+    Custom(Box<Command>),
+}
+
+impl BenchCommand {
+    /// Name of the command, used to group latencies in the [`BenchReport`](crate::bench::BenchReport)
+    pub fn name(&self) -> &'static str {
+        match self {
+            BenchCommand::Ping => "PING",
+            BenchCommand::Set => "SET",
+            BenchCommand::Get => "GET",
+            BenchCommand::Incr => "INCR",
+            BenchCommand::LPush => "LPUSH",
+            BenchCommand::LPop => "LPOP",
+            BenchCommand::SAdd => "SADD",
+            BenchCommand::HSet => "HSET",
+            BenchCommand::ZAdd => "ZADD",
+            BenchCommand::Custom(command) => command.name,
+        }
+    }
+
+    pub(crate) fn to_command(&self, config: &BenchConfig, key_index: usize) -> Command {
+        let key = |key_type: &str| format!("{}{key_type}:{key_index}", config.key_prefix);
+        let value = || vec![b'x'; config.data_size];
+
+        match self {
+            BenchCommand::Ping => cmd("PING"),
+            BenchCommand::Set => cmd("SET").arg(key("key")).arg(value()),
+            BenchCommand::Get => cmd("GET").arg(key("key")),
+            BenchCommand::Incr => cmd("INCR").arg(key("counter")),
+            BenchCommand::LPush => cmd("LPUSH")
+                .arg(format!("{}list", config.key_prefix))
+                .arg(value()),
+            BenchCommand::LPop => cmd("LPOP").arg(format!("{}list", config.key_prefix)),
+            BenchCommand::SAdd => cmd("SADD")
+                .arg(format!("{}set", config.key_prefix))
+                .arg(format!("member:{key_index}")),
+            BenchCommand::HSet => cmd("HSET")
+                .arg(format!("{}hash", config.key_prefix))
+                .arg(format!("field:{key_index}"))
+                .arg(value()),
+            BenchCommand::ZAdd => cmd("ZADD")
+                .arg(format!("{}zset", config.key_prefix))
+                .arg(key_index)
+                .arg(format!("member:{key_index}")),
+            BenchCommand::Custom(command) => {
+                let mut custom = cmd(command.name);
+                for arg in command.args.iter() {
+                    if arg.as_slice() == RAND_INT_PLACEHOLDER.as_bytes() {
+                        custom = custom.arg(key_index);
+                    } else {
+                        custom = custom.arg(arg.as_slice());
+                    }
+                }
+                custom
+            }
+        }
+    }
+}
+
+/// Configuration of a benchmark run by the [`run`](crate::bench::run) function
+#[derive(Debug, Clone)]
+pub struct BenchConfig {
+    /// Command mix as a collection of `(command, weight)` tuples.
+    ///
+    /// Each command is picked randomly with a probability proportional to its weight.
+    ///
+    /// The default is `[(Set, 1), (Get, 1)]`
+    pub workload: Vec<(BenchCommand, u32)>,
+    /// Total number of requests to send (default `100_000`)
+    pub requests: usize,
+    /// Number of parallel connections (default `10`)
+    pub connections: usize,
+    /// Number of requests sent in a single batch (default `1`, no pipelining)
+    pub pipeline_depth: usize,
+    /// Number of distinct random keys (default `10_000`)
+    pub key_space: usize,
+    /// Size in bytes of the values written by the benchmark (default `3`)
+    pub data_size: usize,
+    /// Prefix of all the keys generated by the benchmark (default `bench:`)
+    pub key_prefix: String,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            workload: vec![(BenchCommand::Set, 1), (BenchCommand::Get, 1)],
+            requests: DEFAULT_REQUESTS,
+            connections: DEFAULT_CONNECTIONS,
+            pipeline_depth: DEFAULT_PIPELINE_DEPTH,
+            key_space: DEFAULT_KEY_SPACE,
+            data_size: DEFAULT_DATA_SIZE,
+            key_prefix: DEFAULT_KEY_PREFIX.to_owned(),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use hdrhistogram::Histogram;
+use serde::Serialize;
+use std::{collections::HashMap, time::Duration};
+
+/// Report of a benchmark run by the [`run`](crate::bench::run) function
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchReport {
+    /// Number of requests sent
+    pub requests: usize,
+    /// Number of requests which ended with a Redis error
+    pub errors: usize,
+    /// Total duration of the benchmark
+    pub duration: Duration,
+    /// Number of requests per second
+    pub throughput: f64,
+    /// Latency statistics for all the commands of the workload
+    pub latency: LatencyStats,
+    /// Latency statistics per command name
+    pub commands: HashMap<String, CommandReport>,
+}
+
+/// Report of a single command in a [`BenchReport`]
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandReport {
+    /// Number of requests sent for this command
+    pub requests: usize,
+    /// Number of requests which ended with a Redis error for this command
+    pub errors: usize,
+    /// Latency statistics for this command
+    pub latency: LatencyStats,
+}
+
+/// Latency statistics computed from an [`hdrhistogram`](https://docs.rs/hdrhistogram)
+///
+/// With pipelining, the latency of a command is the latency of the whole batch it belongs to.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct LatencyStats {
+    /// Minimum latency, with a microsecond precision
+    pub min: Duration,
+    /// Mean latency, with a microsecond precision
+    pub mean: Duration,
+    /// Median (50th percentile) latency, with a microsecond precision
+    pub p50: Duration,
+    /// 90th percentile latency, with a microsecond precision
+    pub p90: Duration,
+    /// 99th percentile latency, with a microsecond precision
+    pub p99: Duration,
+    /// 99.9th percentile latency, with a microsecond precision
+    pub p999: Duration,
+    /// Maximum latency, with a microsecond precision
+    pub max: Duration,
+}
+
+impl From<&Histogram<u64>> for LatencyStats {
+    fn from(histogram: &Histogram<u64>) -> Self {
+        if histogram.is_empty() {
+            return Self::default();
+        }
+
+        Self {
+            min: Duration::from_micros(histogram.min()),
+            mean: Duration::from_micros(histogram.mean() as u64),
+            p50: Duration::from_micros(histogram.value_at_quantile(0.5)),
+            p90: Duration::from_micros(histogram.value_at_quantile(0.9)),
+            p99: Duration::from_micros(histogram.value_at_quantile(0.99)),
+            p999: Duration::from_micros(histogram.value_at_quantile(0.999)),
+            max: Duration::from_micros(histogram.max()),
+        }
+    }
+}
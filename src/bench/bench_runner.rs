@@ -0,0 +1,232 @@
+use crate::{
+    bench::{BenchConfig, BenchReport, CommandReport, LatencyStats},
+    client::{Client, IntoConfig},
+    resp::Command,
+    spawn, Error, Result,
+};
+use hdrhistogram::Histogram;
+use rand::{distributions::WeightedIndex, prelude::Distribution, Rng};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+/// Latencies are recorded in microseconds, from 1µs to 1 hour, with 3 significant digits
+const HISTOGRAM_MAX_VALUE: u64 = 3_600_000_000;
+const HISTOGRAM_SIGNIFICANT_DIGITS: u8 = 3;
+
+/// Run a benchmark against the Redis server described by `config`
+///
+/// [`connections`](BenchConfig::connections) clients are connected to the server
+/// and share the [`requests`](BenchConfig::requests) to send,
+/// by batches of [`pipeline_depth`](BenchConfig::pipeline_depth) commands.
+///
+/// # Errors
+/// * a [`Config`](Error::Config) error if the benchmark configuration is invalid
+/// * any Redis driver [`Error`](crate::Error) that occurs during the connection or the send operations.
+///   Errors returned by the Redis server are not fatal and are counted in the report instead.
+pub async fn run(config: impl IntoConfig, bench_config: BenchConfig) -> Result<BenchReport> {
+    let config = config.into_config()?;
+
+    if bench_config.workload.is_empty() {
+        return Err(Error::Config(
+            "Benchmark workload cannot be empty".to_owned(),
+        ));
+    }
+
+    let weights = WeightedIndex::new(bench_config.workload.iter().map(|(_, weight)| *weight))
+        .map_err(|e| Error::Config(format!("Invalid benchmark workload weights: {e}")))?;
+
+    let connections = bench_config.connections.max(1);
+    let mut clients = Vec::with_capacity(connections);
+    for _ in 0..connections {
+        clients.push(Client::connect(config.clone()).await?);
+    }
+
+    let bench_config = Arc::new(bench_config);
+    let remaining_requests = Arc::new(AtomicUsize::new(bench_config.requests));
+
+    let start = Instant::now();
+
+    let join_handles = clients
+        .into_iter()
+        .map(|client| {
+            spawn(run_connection(
+                client,
+                bench_config.clone(),
+                weights.clone(),
+                remaining_requests.clone(),
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    let mut stats = ConnectionStats::new(&bench_config)?;
+    for join_handle in join_handles {
+        stats.merge(join_handle.await??)?;
+    }
+
+    let duration = start.elapsed();
+    let requests = stats.requests.iter().sum();
+
+    // several workload entries can share the same command name
+    let mut command_stats = HashMap::<&'static str, CommandStats>::new();
+    for (idx, (command, _)) in bench_config.workload.iter().enumerate() {
+        let entry = match command_stats.entry(command.name()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(CommandStats::new()?),
+        };
+        entry.requests += stats.requests[idx];
+        entry.errors += stats.errors[idx];
+        entry
+            .histogram
+            .add(&stats.histograms[idx])
+            .map_err(|e| Error::Client(format!("Cannot merge latencies: {e}")))?;
+    }
+
+    let commands = command_stats
+        .into_iter()
+        .map(|(name, stats)| {
+            (
+                name.to_owned(),
+                CommandReport {
+                    requests: stats.requests,
+                    errors: stats.errors,
+                    latency: LatencyStats::from(&stats.histogram),
+                },
+            )
+        })
+        .collect();
+
+    Ok(BenchReport {
+        requests,
+        errors: stats.errors.iter().sum(),
+        duration,
+        throughput: requests as f64 / duration.as_secs_f64(),
+        latency: LatencyStats::from(&stats.histogram),
+        commands,
+    })
+}
+
+async fn run_connection(
+    client: Client,
+    bench_config: Arc<BenchConfig>,
+    weights: WeightedIndex<u32>,
+    remaining_requests: Arc<AtomicUsize>,
+) -> Result<ConnectionStats> {
+    let mut stats = ConnectionStats::new(&bench_config)?;
+    let pipeline_depth = bench_config.pipeline_depth.max(1);
+    let key_space = bench_config.key_space.max(1);
+
+    // reserve a batch of requests among the remaining ones
+    while let Ok(remaining) =
+        remaining_requests.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |remaining| {
+            (remaining > 0).then(|| remaining - remaining.min(pipeline_depth))
+        })
+    {
+        let batch_size = remaining.min(pipeline_depth);
+
+        let (indices, commands): (Vec<usize>, Vec<Command>) = {
+            let mut rng = rand::thread_rng();
+            (0..batch_size)
+                .map(|_| {
+                    let idx = weights.sample(&mut rng);
+                    let key_index = rng.gen_range(0..key_space);
+                    let command = bench_config.workload[idx]
+                        .0
+                        .to_command(&bench_config, key_index);
+                    (idx, command)
+                })
+                .unzip()
+        };
+
+        let start = Instant::now();
+        let results = if batch_size == 1 {
+            vec![
+                client
+                    .send(commands.into_iter().next().unwrap(), None)
+                    .await?,
+            ]
+        } else {
+            client.send_batch(commands, None).await?
+        };
+        let latency = start.elapsed().as_micros() as u64;
+
+        for (idx, result) in indices.into_iter().zip(results) {
+            stats.record(idx, latency, result.is_error())?;
+        }
+    }
+
+    client.close().await?;
+
+    Ok(stats)
+}
+
+struct CommandStats {
+    requests: usize,
+    errors: usize,
+    histogram: Histogram<u64>,
+}
+
+impl CommandStats {
+    fn new() -> Result<Self> {
+        Ok(Self {
+            requests: 0,
+            errors: 0,
+            histogram: new_histogram()?,
+        })
+    }
+}
+
+/// Statistics collected by a single connection, indexed by workload entry
+struct ConnectionStats {
+    requests: Vec<usize>,
+    errors: Vec<usize>,
+    histograms: Vec<Histogram<u64>>,
+    histogram: Histogram<u64>,
+}
+
+impl ConnectionStats {
+    fn new(bench_config: &BenchConfig) -> Result<Self> {
+        let len = bench_config.workload.len();
+        Ok(Self {
+            requests: vec![0; len],
+            errors: vec![0; len],
+            histograms: (0..len).map(|_| new_histogram()).collect::<Result<_>>()?,
+            histogram: new_histogram()?,
+        })
+    }
+
+    fn record(&mut self, idx: usize, latency: u64, is_error: bool) -> Result<()> {
+        self.requests[idx] += 1;
+        if is_error {
+            self.errors[idx] += 1;
+        }
+        self.histograms[idx]
+            .record(latency)
+            .and_then(|_| self.histogram.record(latency))
+            .map_err(|e| Error::Client(format!("Cannot record latency: {e}")))
+    }
+
+    fn merge(&mut self, other: ConnectionStats) -> Result<()> {
+        for (idx, histogram) in other.histograms.iter().enumerate() {
+            self.requests[idx] += other.requests[idx];
+            self.errors[idx] += other.errors[idx];
+            self.histograms[idx]
+                .add(histogram)
+                .map_err(|e| Error::Client(format!("Cannot merge latencies: {e}")))?;
+        }
+
+        self.histogram
+            .add(&other.histogram)
+            .map_err(|e| Error::Client(format!("Cannot merge latencies: {e}")))
+    }
+}
+
+fn new_histogram() -> Result<Histogram<u64>> {
+    Histogram::new_with_max(HISTOGRAM_MAX_VALUE, HISTOGRAM_SIGNIFICANT_DIGITS)
+        .map_err(|e| Error::Client(format!("Cannot create latency histogram: {e}")))
+}
@@ -0,0 +1,46 @@
+/*!
+Benchmark subsystem, equivalent to [`redis-benchmark`](https://redis.io/docs/management/optimization/benchmarks/),
+exposed as a library API.
+
+A benchmark runs a configurable workload (command mix, key space size, pipeline depth, number of connections)
+against a Redis server described by a [`Config`](crate::client::Config), and returns a typed [`BenchReport`]
+with throughput and latency histograms.
+
+This can be used to detect performance regressions of **rustis** itself,
+or to size a Redis deployment with a workload close to the real one.
+
+# Example
+```
+use rustis::{
+    bench::{run, BenchCommand, BenchConfig},
+    Result,
+};
+
+#[cfg_attr(feature = "tokio-runtime", tokio::main)]
+#[cfg_attr(feature = "async-std-runtime", async_std::main)]
+async fn main() -> Result<()> {
+    let bench_config = BenchConfig {
+        workload: vec![(BenchCommand::Get, 8), (BenchCommand::Set, 2)],
+        requests: 10_000,
+        pipeline_depth: 4,
+        ..Default::default()
+    };
+
+    let report = run("127.0.0.1:6379", bench_config).await?;
+    println!(
+        "{:.0} requests per second, p99: {:?}",
+        report.throughput, report.latency.p99
+    );
+
+    Ok(())
+}
+```
+*/
+
+mod bench_config;
+mod bench_report;
+mod bench_runner;
+
+pub use bench_config::*;
+pub use bench_report::*;
+pub use bench_runner::*;
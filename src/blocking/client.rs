@@ -0,0 +1,231 @@
+#[cfg(feature = "mock")]
+use crate::client::MockServerBehavior;
+#[cfg(feature = "redis-graph")]
+use crate::commands::GraphCommands;
+#[cfg(feature = "redis-json")]
+use crate::commands::JsonCommands;
+#[cfg(feature = "redis-search")]
+use crate::commands::SearchCommands;
+#[cfg(feature = "redis-time-series")]
+use crate::commands::TimeSeriesCommands;
+#[cfg(feature = "redis-bloom")]
+use crate::commands::{
+    BloomCommands, CountMinSketchCommands, CuckooCommands, TDigestCommands, TopKCommands,
+};
+use crate::{
+    blocking::{Pipeline, Transaction},
+    client::{self, ClientPreparedCommand, IntoConfig, MonitorStream, PreparedCommand},
+    commands::{
+        BitmapCommands, BlockingCommands, ClusterCommands, ConnectionCommands, GenericCommands,
+        GeoCommands, HashCommands, HyperLogLogCommands, ListCommands, ScriptingCommands,
+        SentinelCommands, ServerCommands, SetCommands, SortedSetCommands, StreamCommands,
+        StringCommands, TransactionCommands, VectorSetCommands,
+    },
+    resp::{Command, RespBuf, Response},
+    Result,
+};
+use serde::de::DeserializeOwned;
+use std::future::{Future, IntoFuture};
+#[cfg(feature = "tokio-runtime")]
+use std::sync::Arc;
+
+/// Runtime driving the async client of a blocking [`Client`]
+#[derive(Clone)]
+pub(crate) struct Runtime {
+    #[cfg(feature = "tokio-runtime")]
+    runtime: Arc<tokio::runtime::Runtime>,
+}
+
+impl Runtime {
+    fn new() -> Result<Self> {
+        #[cfg(feature = "tokio-runtime")]
+        {
+            let runtime = tokio::runtime::Builder::new_multi_thread()
+                .worker_threads(1)
+                .thread_name("rustis-blocking")
+                .enable_all()
+                .build()?;
+            Ok(Self {
+                runtime: Arc::new(runtime),
+            })
+        }
+
+        #[cfg(feature = "async-std-runtime")]
+        Ok(Self {})
+    }
+
+    /// Run `future` to completion, blocking the calling thread
+    pub(crate) fn block_on<F: Future>(&self, future: F) -> F::Output {
+        #[cfg(feature = "tokio-runtime")]
+        return self.runtime.block_on(future);
+
+        #[cfg(feature = "async-std-runtime")]
+        async_std::task::block_on(future)
+    }
+}
+
+/// Blocking client, see the [module-level documentation](crate::blocking)
+///
+/// The internal runtime is shut down when the last clone of the client is dropped,
+/// which must not happen in an async context.
+#[derive(Clone)]
+pub struct Client {
+    // dropped before the runtime
+    inner: client::Client,
+    runtime: Runtime,
+}
+
+impl Client {
+    /// Connects to the Redis server, blocking the calling thread
+    ///
+    /// # Errors
+    /// Any Redis driver [`Error`](crate::Error) that occurs during the connection operation
+    pub fn connect(config: impl IntoConfig) -> Result<Self> {
+        let runtime = Runtime::new()?;
+        let inner = runtime.block_on(client::Client::connect(config))?;
+        Ok(Self { inner, runtime })
+    }
+
+    /// Connects to an in-memory server, see [`Client::connect_mock`](crate::client::Client::connect_mock)
+    ///
+    /// # Errors
+    /// Any Redis driver [`Error`](crate::Error) that occurs during the connection operation
+    #[cfg_attr(docsrs, doc(cfg(feature = "mock")))]
+    #[cfg(feature = "mock")]
+    pub fn connect_mock(behavior: MockServerBehavior) -> Result<Self> {
+        let runtime = Runtime::new()?;
+        let inner = runtime.block_on(client::Client::connect_mock(behavior))?;
+        Ok(Self { inner, runtime })
+    }
+
+    /// The async client, driven by the internal runtime
+    ///
+    /// Gives access to the features of the async client without blocking equivalent,
+    /// e.g. Pub/Sub subscriptions, from an async context.
+    /// The async client stops working once the last clone of the blocking client is dropped.
+    #[must_use]
+    pub fn async_client(&self) -> &client::Client {
+        &self.inner
+    }
+
+    /// Run `future` on the internal runtime, blocking the calling thread until it completes
+    ///
+    /// Typically used to call an async method of the [async client](Client::async_client).
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// Send an arbitrary command and wait for its result,
+    /// see [`Client::send`](crate::client::Client::send)
+    ///
+    /// # Errors
+    /// Any Redis driver [`Error`](crate::Error) that occurs during the send operation
+    pub fn send(&self, command: Command, retry_on_error: Option<bool>) -> Result<RespBuf> {
+        self.block_on(self.inner.send(command, retry_on_error))
+    }
+
+    /// Create a new transaction
+    #[must_use]
+    pub fn create_transaction(&self) -> Transaction {
+        Transaction::new(self.inner.create_transaction(), self.runtime.clone())
+    }
+
+    /// Create a new pipeline
+    #[must_use]
+    pub fn create_pipeline(&self) -> Pipeline<'_> {
+        Pipeline::new(self.inner.create_pipeline(), &self.runtime)
+    }
+
+    /// Close the connection, waiting for the network task to stop
+    ///
+    /// # Errors
+    /// Any Redis driver [`Error`](crate::Error) that occurs during the close operation
+    pub fn close(self) -> Result<()> {
+        let Self { inner, runtime } = self;
+        runtime.block_on(inner.close())
+    }
+}
+
+impl<'a, R> PreparedCommand<'a, &'a Client, R>
+where
+    R: DeserializeOwned + Send + 'a,
+{
+    /// Send the command of a [blocking client](crate::blocking::Client) and wait for its result
+    ///
+    /// # Errors
+    /// Any Redis driver [`Error`](crate::Error) that occurs during the send operation
+    pub fn execute(self) -> Result<R> {
+        let client = self.executor;
+        let mut prepared_command =
+            PreparedCommand::<'a, &'a client::Client, R>::new(&client.inner, self.command);
+        prepared_command.custom_converter = self.custom_converter;
+        prepared_command.retry_on_error = self.retry_on_error;
+        prepared_command.command_timeout = self.command_timeout;
+
+        client.block_on(prepared_command.into_future())
+    }
+}
+
+impl<'a, R: Response> ClientPreparedCommand<'a, R> for PreparedCommand<'a, &'a Client, R> {
+    /// Send command and forget its response
+    ///
+    /// # Errors
+    /// Any Redis driver [`Error`](crate::Error) that occur during the send operation
+    fn forget(self) -> Result<()> {
+        self.executor
+            .inner
+            .send_and_forget(self.command, self.retry_on_error)
+    }
+}
+
+impl<'a> BitmapCommands<'a> for &'a Client {}
+#[cfg_attr(docsrs, doc(cfg(feature = "redis-bloom")))]
+#[cfg(feature = "redis-bloom")]
+impl<'a> BloomCommands<'a> for &'a Client {}
+impl<'a> ClusterCommands<'a> for &'a Client {}
+#[cfg_attr(docsrs, doc(cfg(feature = "redis-bloom")))]
+#[cfg(feature = "redis-bloom")]
+impl<'a> CountMinSketchCommands<'a> for &'a Client {}
+#[cfg_attr(docsrs, doc(cfg(feature = "redis-bloom")))]
+#[cfg(feature = "redis-bloom")]
+impl<'a> CuckooCommands<'a> for &'a Client {}
+impl<'a> ConnectionCommands<'a> for &'a Client {}
+impl<'a> GenericCommands<'a> for &'a Client {}
+impl<'a> GeoCommands<'a> for &'a Client {}
+#[cfg_attr(docsrs, doc(cfg(feature = "redis-graph")))]
+#[cfg(feature = "redis-graph")]
+impl<'a> GraphCommands<'a> for &'a Client {}
+impl<'a> HashCommands<'a> for &'a Client {}
+impl<'a> HyperLogLogCommands<'a> for &'a Client {}
+#[cfg_attr(docsrs, doc(cfg(feature = "redis-json")))]
+#[cfg(feature = "redis-json")]
+impl<'a> JsonCommands<'a> for &'a Client {}
+impl<'a> ListCommands<'a> for &'a Client {}
+impl<'a> ScriptingCommands<'a> for &'a Client {}
+#[cfg_attr(docsrs, doc(cfg(feature = "redis-search")))]
+#[cfg(feature = "redis-search")]
+impl<'a> SearchCommands<'a> for &'a Client {}
+impl<'a> SentinelCommands<'a> for &'a Client {}
+impl<'a> ServerCommands<'a> for &'a Client {}
+impl<'a> SetCommands<'a> for &'a Client {}
+impl<'a> SortedSetCommands<'a> for &'a Client {}
+impl<'a> StreamCommands<'a> for &'a Client {}
+impl<'a> StringCommands<'a> for &'a Client {}
+#[cfg_attr(docsrs, doc(cfg(feature = "redis-bloom")))]
+#[cfg(feature = "redis-bloom")]
+impl<'a> TDigestCommands<'a> for &'a Client {}
+#[cfg_attr(docsrs, doc(cfg(feature = "redis-time-series")))]
+#[cfg(feature = "redis-time-series")]
+impl<'a> TimeSeriesCommands<'a> for &'a Client {}
+impl<'a> TransactionCommands<'a> for &'a Client {}
+#[cfg_attr(docsrs, doc(cfg(feature = "redis-bloom")))]
+#[cfg(feature = "redis-bloom")]
+impl<'a> TopKCommands<'a> for &'a Client {}
+impl<'a> VectorSetCommands<'a> for &'a Client {}
+
+impl<'a> BlockingCommands<'a> for &'a Client {
+    /// Only available from an async context, the monitor stream being async
+    async fn monitor(self) -> Result<MonitorStream> {
+        self.inner.monitor().await
+    }
+}
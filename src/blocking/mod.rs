@@ -0,0 +1,53 @@
+/*!
+Synchronous facade of the [`Client`](crate::client::Client), for applications which are not async,
+like command-line tools or legacy code bases.
+
+The blocking [`Client`] wraps an async client driven by an internal runtime.
+It implements the same [command traits](crate::commands) as the async client:
+commands are prepared the same way and sent with [`execute`](crate::client::PreparedCommand::execute),
+which blocks the calling thread until the result is received.
+
+A blocking `Client` is thread-safe and cheap to clone: all the clones share the same connection
+and can send commands concurrently from different threads.
+
+The blocking API must not be called from an async context:
+it would block a thread of the async runtime, and panics with the `tokio-runtime` feature.
+Pub/Sub subscriptions and [`monitor`](crate::commands::BlockingCommands::monitor) are only available
+through the [async client](Client::async_client).
+
+# Example
+```
+use rustis::{
+    blocking::Client,
+    client::BatchPreparedCommand,
+    commands::{FlushingMode, ServerCommands, StringCommands},
+    Result,
+};
+
+fn main() -> Result<()> {
+    let client = Client::connect("127.0.0.1:6379")?;
+    client.flushdb(FlushingMode::Sync).execute()?;
+
+    client.set("key", "value").execute()?;
+    let value: String = client.get("key").execute()?;
+    assert_eq!("value", value);
+
+    let mut pipeline = client.create_pipeline();
+    pipeline.incr("counter").queue();
+    pipeline.incr("counter").queue();
+    let (c1, c2): (i64, i64) = pipeline.execute()?;
+    assert_eq!((1, 2), (c1, c2));
+
+    Ok(())
+}
+```
+*/
+
+#[allow(clippy::module_inception)]
+mod client;
+mod pipeline;
+mod transaction;
+
+pub use client::*;
+pub use pipeline::*;
+pub use transaction::*;
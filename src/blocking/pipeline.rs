@@ -0,0 +1,41 @@
+use crate::{blocking::Runtime, client, Result};
+use serde::de::DeserializeOwned;
+use std::ops::{Deref, DerefMut};
+
+/// Pipeline of a [blocking client](crate::blocking::Client)
+///
+/// Commands are queued the same way as in an async [`Pipeline`](crate::client::Pipeline),
+/// which this type dereferences to.
+pub struct Pipeline<'a> {
+    inner: client::Pipeline<'a>,
+    runtime: &'a Runtime,
+}
+
+impl<'a> Pipeline<'a> {
+    pub(crate) fn new(inner: client::Pipeline<'a>, runtime: &'a Runtime) -> Self {
+        Self { inner, runtime }
+    }
+
+    /// Execute the pipeline and wait for its results,
+    /// see [`Pipeline::execute`](crate::client::Pipeline::execute)
+    ///
+    /// # Errors
+    /// Any Redis driver [`Error`](crate::Error) that occurs during the send operation
+    pub fn execute<T: DeserializeOwned>(self) -> Result<T> {
+        self.runtime.block_on(self.inner.execute())
+    }
+}
+
+impl<'a> Deref for Pipeline<'a> {
+    type Target = client::Pipeline<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DerefMut for Pipeline<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{blocking::Runtime, client, Result};
+use serde::de::DeserializeOwned;
+use std::ops::{Deref, DerefMut};
+
+/// Transaction of a [blocking client](crate::blocking::Client)
+///
+/// Commands are queued the same way as in an async [`Transaction`](crate::client::Transaction),
+/// which this type dereferences to.
+pub struct Transaction {
+    inner: client::Transaction,
+    runtime: Runtime,
+}
+
+impl Transaction {
+    pub(crate) fn new(inner: client::Transaction, runtime: Runtime) -> Self {
+        Self { inner, runtime }
+    }
+
+    /// Execute the transaction and wait for its results,
+    /// see [`Transaction::execute`](crate::client::Transaction::execute)
+    ///
+    /// # Errors
+    /// Any Redis driver [`Error`](crate::Error) that occurs during the send operation
+    pub fn execute<T: DeserializeOwned>(self) -> Result<T> {
+        self.runtime.block_on(self.inner.execute())
+    }
+
+    /// Abort the transaction, see [`Transaction::abort`](crate::client::Transaction::abort)
+    ///
+    /// # Errors
+    /// Any Redis driver [`Error`](crate::Error) that occurs during the send operation
+    pub fn abort(self) -> Result<()> {
+        self.runtime.block_on(self.inner.abort())
+    }
+}
+
+impl Deref for Transaction {
+    type Target = client::Transaction;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DerefMut for Transaction {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
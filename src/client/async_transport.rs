@@ -0,0 +1,71 @@
+use crate::{client::Config, Future};
+use std::fmt;
+use tokio::io::{AsyncRead, AsyncWrite};
+
+/// Reading half of a connection opened by an [`AsyncTransport`]
+pub type TransportReader = Box<dyn AsyncRead + Send + Unpin>;
+/// Writing half of a connection opened by an [`AsyncTransport`]
+pub type TransportWriter = Box<dyn AsyncWrite + Send + Unpin>;
+
+/// Transport opening the connections of a [`Client`](crate::client::Client) in place of TCP
+///
+/// A transport is registered with [`Config::transport`](crate::client::Config::transport)
+/// and allows to reach a server where raw TCP sockets are not available:
+/// RESP over WebSocket, sockets provided by a WebAssembly host, in-process streams, ...
+///
+/// The transport only carries bytes: RESP encoding, authentication, database selection, ...
+/// are still handled by the client on top of it.
+/// TLS and socket options of [`Config`] (keep-alive, no delay, buffer sizes, ...)
+/// are not applied to the connections of a transport.
+///
+/// # Example
+/// ```
+/// use rustis::{
+///     client::{AsyncTransport, Client, Config, IntoConfig, TransportReader, TransportWriter},
+///     commands::StringCommands,
+///     Future, Result,
+/// };
+/// use std::sync::Arc;
+///
+/// #[derive(Debug)]
+/// struct LoggedTcp;
+///
+/// impl AsyncTransport for LoggedTcp {
+///     fn connect<'a>(
+///         &'a self,
+///         host: &'a str,
+///         port: u16,
+///         _config: &'a Config,
+///     ) -> Future<'a, (TransportReader, TransportWriter)> {
+///         Box::pin(async move {
+///             println!("connecting to {host}:{port}");
+///             let stream = tokio::net::TcpStream::connect((host, port)).await?;
+///             let (reader, writer) = tokio::io::split(stream);
+///             Ok((Box::new(reader) as TransportReader, Box::new(writer) as TransportWriter))
+///         })
+///     }
+/// }
+///
+/// #[tokio::main]
+/// async fn main() -> Result<()> {
+///     let mut config = "127.0.0.1:6379".into_config()?;
+///     config.transport = Some(Arc::new(LoggedTcp));
+///     let client = Client::connect(config).await?;
+///
+///     client.set("key", "value").await?;
+///
+///     Ok(())
+/// }
+/// ```
+pub trait AsyncTransport: fmt::Debug + Send + Sync {
+    /// Open a connection to the server `host`:`port`
+    ///
+    /// Called for each connection of the client, e.g. to each node of a cluster,
+    /// and at each reconnection.
+    fn connect<'a>(
+        &'a self,
+        host: &'a str,
+        port: u16,
+        config: &'a Config,
+    ) -> Future<'a, (TransportReader, TransportWriter)>;
+}
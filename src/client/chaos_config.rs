@@ -0,0 +1,196 @@
+use rand::{rngs::StdRng, Rng};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// Fault injected by a chaos connection, see [`ChaosConfig`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChaosFault {
+    /// The command is sent after an artificial delay
+    Latency(Duration),
+    /// The command is executed by the server, but its reply is lost
+    /// and the connection is closed
+    DropResponse,
+    /// The connection is closed before the command is sent
+    Disconnect,
+    /// The command is not sent and a spurious `MOVED` redirection is received instead
+    Moved,
+    /// The command is not sent and a spurious `ASK` redirection is received instead
+    Ask,
+}
+
+/// Schedule of the faults injected by a client connected with
+/// [`Client::connect_chaos`](crate::client::Client::connect_chaos)
+///
+/// A fault is drawn for each write to the connection (a command or a batch of commands),
+/// with a pseudo-random generator seeded with [`seed`](ChaosConfig::new):
+/// the same sequence of writes always gets the same faults,
+/// which makes resilience tests deterministic.
+/// Faults can also be pinned to a given write with [`fault_at`](ChaosConfig::fault_at).
+///
+/// Spurious redirections are handled like real ones by the connection:
+/// on a cluster, the command is sent again to the node owning its first key,
+/// after a refresh of the cluster topology for `MOVED`, or prefixed with `ASKING` for `ASK`
+/// (commands without key are not redirected).
+/// On a standalone or sentinel connection, the caller receives the redirection error.
+///
+/// Faults are not meant for connections in subscription or monitor mode,
+/// nor with [`CLIENT REPLY OFF`](https://redis.io/commands/client-reply/).
+///
+/// # Example
+/// ```
+/// use rustis::{
+///     client::{ChaosConfig, ChaosFault, Client},
+///     commands::StringCommands,
+///     Result,
+/// };
+/// use std::time::Duration;
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let chaos = ChaosConfig::new(42)
+///         .latency(0.1, Duration::from_millis(1), Duration::from_millis(20))
+///         .fault_at(1, ChaosFault::Disconnect);
+///     let client = Client::connect_chaos(
+///         "redis://127.0.0.1:6379?retry_on_error=true",
+///         chaos.clone(),
+///     )
+///     .await?;
+///
+///     client.set("key", "value").await?;
+///     // the connection is closed before the second command is sent,
+///     // which is sent again once the client has reconnected
+///     let value: String = client.get("key").await?;
+///     assert_eq!("value", value);
+///     assert!(chaos.injected_faults().contains(&(1, ChaosFault::Disconnect)));
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ChaosConfig {
+    pub(crate) seed: u64,
+    latency: Option<(f64, Duration, Duration)>,
+    drop_response: f64,
+    disconnect: f64,
+    moved: f64,
+    ask: f64,
+    pinned_faults: HashMap<u64, ChaosFault>,
+    injected_faults: Arc<Mutex<Vec<(u64, ChaosFault)>>>,
+}
+
+impl ChaosConfig {
+    /// Schedule without any fault, drawn from `seed`
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            latency: None,
+            drop_response: 0.,
+            disconnect: 0.,
+            moved: 0.,
+            ask: 0.,
+            pinned_faults: HashMap::new(),
+            injected_faults: Arc::default(),
+        }
+    }
+
+    /// Delay writes with a `probability` between `0.0` and `1.0`,
+    /// by a duration uniformly drawn between `min` and `max`
+    #[must_use]
+    pub fn latency(mut self, probability: f64, min: Duration, max: Duration) -> Self {
+        self.latency = Some((probability.clamp(0., 1.), min, max.max(min)));
+        self
+    }
+
+    /// Drop the reply of a write, then close the connection,
+    /// with a `probability` between `0.0` and `1.0`
+    #[must_use]
+    pub fn drop_responses(mut self, probability: f64) -> Self {
+        self.drop_response = probability.clamp(0., 1.);
+        self
+    }
+
+    /// Close the connection before a write,
+    /// with a `probability` between `0.0` and `1.0`
+    #[must_use]
+    pub fn disconnects(mut self, probability: f64) -> Self {
+        self.disconnect = probability.clamp(0., 1.);
+        self
+    }
+
+    /// Redirect a write with a spurious `MOVED` error,
+    /// with a `probability` between `0.0` and `1.0`
+    #[must_use]
+    pub fn moved_errors(mut self, probability: f64) -> Self {
+        self.moved = probability.clamp(0., 1.);
+        self
+    }
+
+    /// Redirect a write with a spurious `ASK` error,
+    /// with a `probability` between `0.0` and `1.0`
+    #[must_use]
+    pub fn ask_errors(mut self, probability: f64) -> Self {
+        self.ask = probability.clamp(0., 1.);
+        self
+    }
+
+    /// Inject `fault` on the write of index `write_index` (starting at `0`),
+    /// instead of the drawn fault
+    #[must_use]
+    pub fn fault_at(mut self, write_index: u64, fault: ChaosFault) -> Self {
+        self.pinned_faults.insert(write_index, fault);
+        self
+    }
+
+    /// Faults injected so far, with the index of the write they have been injected on
+    ///
+    /// The faults are shared by the clones of this config.
+    #[must_use]
+    pub fn injected_faults(&self) -> Vec<(u64, ChaosFault)> {
+        self.injected_faults.lock().unwrap().clone()
+    }
+
+    /// Fault of the write of index `write_index`, drawn from `rng`
+    ///
+    /// The same amount of random numbers is drawn for each write,
+    /// so that a pinned fault does not shift the rest of the schedule.
+    pub(crate) fn draw_fault(&self, write_index: u64, rng: &mut StdRng) -> Option<ChaosFault> {
+        let draw: f64 = rng.gen();
+        let latency_draw: f64 = rng.gen();
+
+        if let Some(fault) = self.pinned_faults.get(&write_index) {
+            return Some(fault.clone());
+        }
+
+        let mut threshold = 0.;
+        for (probability, fault) in [
+            (self.disconnect, ChaosFault::Disconnect),
+            (self.drop_response, ChaosFault::DropResponse),
+            (self.moved, ChaosFault::Moved),
+            (self.ask, ChaosFault::Ask),
+        ] {
+            threshold += probability;
+            if draw < threshold {
+                return Some(fault);
+            }
+        }
+
+        match self.latency {
+            Some((probability, min, max)) if draw < threshold + probability => {
+                Some(ChaosFault::Latency(min + (max - min).mul_f64(latency_draw)))
+            }
+            _ => None,
+        }
+    }
+
+    pub(crate) fn record_fault(&self, write_index: u64, fault: ChaosFault) {
+        self.injected_faults
+            .lock()
+            .unwrap()
+            .push((write_index, fault));
+    }
+}
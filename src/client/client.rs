@@ -1,3 +1,5 @@
+#[cfg(feature = "otel-metrics")]
+use crate::client::OtelMetrics;
 #[cfg(test)]
 use crate::commands::DebugCommands;
 #[cfg(feature = "redis-graph")]
@@ -12,44 +14,157 @@ use crate::commands::TimeSeriesCommands;
 use crate::commands::{
     BloomCommands, CountMinSketchCommands, CuckooCommands, TDigestCommands, TopKCommands,
 };
+#[cfg(feature = "chaos")]
+use crate::{client::ChaosConfig, network::ChaosConnection};
+#[cfg(feature = "mock")]
+use crate::{
+    client::MockServerBehavior,
+    network::{MockConnection, RecordingConnection, ReplayConnection},
+};
 use crate::{
     client::{
-        ClientState, ClientTrackingInvalidationStream, IntoConfig, Message, MonitorStream,
-        Pipeline, PreparedCommand, PubSubStream, Transaction,
+        BatchPreparedCommand, ClientState, ClientStats, ClientTrackingInvalidationStream,
+        CoalescedRequest, CoalescingGuard, CommandInfoCache, CommandJournal, CommandLayers,
+        CommandPermission, CommandPermissionProbe, Config, DeleteKeys, DeleteOptions,
+        DeleteProgress, IntoConfig, KeyspaceEvents, KeyspaceNotificationsReport, LegacyShim,
+        LegacyShims, Message, MonitorStream, NodeKeyspaceNotifications, PendingCommands,
+        PermissionsReport, Pipeline, PreparedCommand, PubSubStream, RequestCoalescer, ServerConfig,
+        Transaction,
     },
+    cluster::{extract_hash_tag, hash_slot},
     commands::{
-        BitmapCommands, BlockingCommands, ClusterCommands, ConnectionCommands, GenericCommands,
-        GeoCommands, HashCommands, HyperLogLogCommands, InternalPubSubCommands, ListCommands,
-        PubSubCommands, ScriptingCommands, SentinelCommands, ServerCommands, SetCommands,
-        SortedSetCommands, StreamCommands, StringCommands, TransactionCommands,
+        BitmapCommands, BlockingCommands, ClusterCommands, ClusterShardResult, CommandInfo,
+        ConnectionCommands, ExpireOption, Expiry, GenericCommands, GeoCommands, HashCommands,
+        HyperLogLogCommands, InternalPubSubCommands, ListCommands, PubSubCommands, Role,
+        RoleResult, ScanOptions, ScriptingCommands, SentinelCommands, ServerCommands, SetCommands,
+        SortedSetCommands, StreamCommands, StringCommands, TempHyperLogLog, TransactionCommands,
+        VectorSetCommands,
     },
     network::{
-        timeout, JoinHandle, MsgSender, NetworkHandler, PubSubReceiver, PubSubSender, PushReceiver,
-        PushSender, ReconnectReceiver, ReconnectSender, ResultReceiver, ResultSender,
-        ResultsReceiver, ResultsSender,
+        sleep, spawn, timeout, ClusterConnection, CommandInfoManager, Connection,
+        ConnectionEventReceiver, ConnectionEventSender, JoinHandle, MsgSender, NetworkHandler,
+        PubSubReceiver, PubSubSender, PushReceiver, PushSender, ReconnectReceiver, ReconnectSender,
+        ResultReceiver, ResultSender, ResultsReceiver, ResultsSender, SharedConnectionIdentity,
+        StatsRegistry,
     },
-    resp::{cmd, Command, CommandArgs, RespBuf, Response, SingleArg, SingleArgCollection},
-    Error, Future, Result,
+    resp::{
+        cmd, BulkString, Command, CommandArgs, RespBuf, Response, SingleArg, SingleArgCollection,
+    },
+    Error, ErrorContext, Future, RedisErrorKind, Result, Version,
+};
+#[cfg(any(feature = "redis-search", feature = "redis-time-series"))]
+use crate::{
+    commands::{ModuleConfigKey, ModuleConfigValue},
+    resp::Value,
 };
 use futures_channel::{mpsc, oneshot};
 use futures_util::Stream;
 use log::{info, trace};
-use serde::de::DeserializeOwned;
+use serde::de::{DeserializeOwned, IgnoredAny};
+#[cfg(feature = "mock")]
+use std::path::Path;
 use std::{
+    collections::HashMap,
     future::IntoFuture,
-    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
-    time::Duration,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard, Weak,
+    },
+    time::{Duration, Instant, SystemTime},
 };
 
+/// Polling interval of [`Client::wait_for_role`]
+const WAIT_FOR_ROLE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// TTL of the keys created by [`Client::pfmerge_into_temp`], in milliseconds
+const TEMP_HYPERLOGLOG_TTL: u64 = 60_000;
+
+/// Sequence used to identify each client instance, including clones,
+/// in the network handler
+static CLIENT_ID_SEQUENCE: AtomicUsize = AtomicUsize::new(0);
+
 /// Client with a unique connection to a Redis server.
-#[derive(Clone)]
 pub struct Client {
     msg_sender: Arc<Option<MsgSender>>,
     network_task_join_handle: Arc<Option<JoinHandle<()>>>,
     reconnect_sender: ReconnectSender,
+    event_sender: ConnectionEventSender,
     client_state: Arc<RwLock<ClientState>>,
     command_timeout: Duration,
     retry_on_error: bool,
+    max_batch_commands: usize,
+    max_batch_bytes: usize,
+    client_id: usize,
+    pending_commands: Arc<PendingCommands>,
+    request_coalescer: Option<Arc<RequestCoalescer>>,
+    legacy_shims: Option<Arc<LegacyShims>>,
+    layers: CommandLayers,
+    identity: SharedConnectionIdentity,
+    stats: Arc<StatsRegistry>,
+    journal: Option<CommandJournal>,
+    error_context: bool,
+    /// `true` for a cluster configuration, see [`is_cluster`](Client::is_cluster)
+    /// for a cluster detected with [`Config::auto_detect_cluster`]
+    cluster: bool,
+    shared_pub_sub: Arc<SharedPubSub>,
+    /// Clients on the other databases, see [`with_database`](Client::with_database)
+    databases: Arc<Mutex<HashMap<usize, Client>>>,
+    /// Key specifications of the commands, loaded by the first call to
+    /// [`extract_keys`](Client::extract_keys)
+    command_info_manager: Arc<Mutex<Option<Arc<CommandInfoManager>>>>,
+}
+
+/// Dedicated pub/sub connection of a client, shared by its clones
+///
+/// The connection is created on the first subscription
+/// and closed when the last [`PubSubStream`] using it is dropped.
+struct SharedPubSub {
+    /// `None` when subscriptions are made on the connection of the client itself
+    /// (dedicated pub/sub connections & mock connections)
+    config: Option<Config>,
+    client: Mutex<Weak<Client>>,
+}
+
+impl SharedPubSub {
+    fn new(config: Option<Config>) -> Self {
+        Self {
+            config,
+            client: Mutex::new(Weak::new()),
+        }
+    }
+}
+
+impl Clone for Client {
+    /// Clones the client on the same underlying connection.
+    ///
+    /// Each clone is scheduled independently by the network handler,
+    /// see [`Config::max_in_flight_per_client`](crate::client::Config::max_in_flight_per_client)
+    fn clone(&self) -> Self {
+        Self {
+            msg_sender: self.msg_sender.clone(),
+            network_task_join_handle: self.network_task_join_handle.clone(),
+            reconnect_sender: self.reconnect_sender.clone(),
+            event_sender: self.event_sender.clone(),
+            client_state: self.client_state.clone(),
+            command_timeout: self.command_timeout,
+            retry_on_error: self.retry_on_error,
+            max_batch_commands: self.max_batch_commands,
+            max_batch_bytes: self.max_batch_bytes,
+            client_id: CLIENT_ID_SEQUENCE.fetch_add(1, Ordering::Relaxed),
+            pending_commands: self.pending_commands.clone(),
+            request_coalescer: self.request_coalescer.clone(),
+            legacy_shims: self.legacy_shims.clone(),
+            layers: self.layers.clone(),
+            identity: self.identity.clone(),
+            stats: self.stats.clone(),
+            journal: self.journal.clone(),
+            error_context: self.error_context,
+            cluster: self.cluster,
+            shared_pub_sub: self.shared_pub_sub.clone(),
+            databases: self.databases.clone(),
+            command_info_manager: self.command_info_manager.clone(),
+        }
+    }
 }
 
 impl Drop for Client {
@@ -73,55 +188,1339 @@ impl Drop for Client {
             }
         };
     }
-}
+}
+
+impl Client {
+    /// Connects asynchronously to the Redis server.
+    ///
+    /// # Errors
+    /// Any Redis driver [`Error`](crate::Error) that occurs during the connection operation
+    #[inline]
+    pub async fn connect(config: impl IntoConfig) -> Result<Self> {
+        let config = config.into_config()?;
+        let connection = Connection::connect(config.clone()).await?;
+        Ok(Self::new(config, Some(connection)))
+    }
+
+    /// Creates a client without connecting to the Redis server.
+    ///
+    /// The connection is established by the first command sent with the client,
+    /// or explicitly with [`warm_up`](Client::warm_up).
+    /// Commands sent while the connection is established are queued;
+    /// if the connection fails, they receive the connection error
+    /// and the connection is attempted again with the next command.
+    ///
+    /// Must be called within the context of the async runtime.
+    ///
+    /// # Errors
+    /// An [`Error::Config`](crate::Error::Config) if the configuration is invalid
+    pub fn connect_lazy(config: impl IntoConfig) -> Result<Self> {
+        let config = config.into_config()?;
+        Ok(Self::new(config, None))
+    }
+
+    /// Establishes the connection of a client created with [`connect_lazy`](Client::connect_lazy)
+    /// and resolves once it is ready to serve commands.
+    ///
+    /// The whole connection process is completed: discovery of the master through the sentinels,
+    /// connection to all the nodes of a cluster, TLS handshakes and `HELLO`/`AUTH`/`SELECT` commands,
+    /// which makes it suitable to gate the readiness probe of a service.
+    /// On an already connected client, it only checks that the server replies.
+    ///
+    /// # Errors
+    /// Any Redis driver [`Error`](crate::Error) that occurs during the connection operation
+    ///
+    /// # Example
+    /// ```
+    /// use rustis::{client::Client, Result};
+    ///
+    /// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+    /// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+    /// async fn main() -> Result<()> {
+    ///     // no network access yet
+    ///     let client = Client::connect_lazy("127.0.0.1:6379")?;
+    ///
+    ///     // ready
+    ///     client.warm_up().await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn warm_up(&self) -> Result<()> {
+        self.send(cmd("PING"), Some(false)).await?;
+        Ok(())
+    }
+
+    /// Client on the logical database `database` of the same server
+    ///
+    /// Each database is served by its own connection, created lazily with the configuration
+    /// of this client (see [`connect_lazy`](Client::connect_lazy)) and selected once with
+    /// [`SELECT`](https://redis.io/commands/select/): commands sent through the returned client
+    /// never interfere with the database selected by the other clients.
+    ///
+    /// The connections are shared by the clones of this client,
+    /// and closed once this client, its clones and the returned clients are dropped.
+    /// Calling this function with the database of this client returns a clone of it.
+    ///
+    /// Must be called within the context of the async runtime.
+    ///
+    /// # Errors
+    /// An [`Error::Client`](crate::Error::Client) for a cluster client, which only supports the database `0`,
+    /// or for a client without configuration (mock or replay clients)
+    ///
+    /// # Example
+    /// ```
+    /// use rustis::{client::Client, commands::StringCommands, Result};
+    ///
+    /// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+    /// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+    /// async fn main() -> Result<()> {
+    ///     let client = Client::connect("127.0.0.1:6379").await?;
+    ///     let sessions = client.with_database(1)?;
+    ///
+    ///     sessions.set("session:1", "alice").await?;
+    ///     let value: Option<String> = client.get("session:1").await?;
+    ///     assert_eq!(None, value);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn with_database(&self, database: usize) -> Result<Client> {
+        let Some(config) = &self.shared_pub_sub.config else {
+            return Err(Error::Client(
+                "Cannot select a database without a configuration".to_owned(),
+            ));
+        };
+
+        if database == config.database {
+            return Ok(self.clone());
+        }
+
+        if self.is_cluster() {
+            return Err(Error::Client(format!(
+                "Cannot select the database {database} of a cluster"
+            )));
+        }
+
+        let mut databases = self.databases.lock().unwrap();
+        if let Some(client) = databases.get(&database) {
+            return Ok(client.clone());
+        }
+
+        let client = Client::connect_lazy(Config {
+            database,
+            ..config.clone()
+        })?;
+        databases.insert(database, client.clone());
+
+        Ok(client)
+    }
+
+    /// Client on a network handler started with `connection`, or connecting lazily if `None`
+    fn new(config: Config, connection: Option<Connection>) -> Self {
+        let command_timeout = config.resilience.command_timeout;
+        let retry_on_error = config.resilience.retry_on.retry_on_error();
+        let max_batch_commands = config.max_batch_commands;
+        let max_batch_bytes = config.max_batch_bytes;
+        let pending_commands =
+            PendingCommands::new(config.max_pending_commands, config.fail_on_queue_full);
+        let request_coalescer = config
+            .coalesce_reads
+            .then(|| Arc::new(RequestCoalescer::new()));
+        let legacy_shims = config
+            .legacy_command_shims
+            .then(|| Arc::new(LegacyShims::default()));
+        #[cfg_attr(not(feature = "otel-metrics"), allow(unused_mut))]
+        let mut layers = config.layers.clone();
+        #[cfg(feature = "otel-metrics")]
+        let otel_metrics = config.otel_metrics.then(OtelMetrics::global);
+        #[cfg(feature = "otel-metrics")]
+        if let Some(otel_metrics) = &otel_metrics {
+            layers.push(otel_metrics.clone());
+        }
+        let error_context = config.error_context;
+        let cluster = matches!(config.server, ServerConfig::Cluster(_));
+        let shared_pub_sub = Arc::new(SharedPubSub::new(Some(config.clone())));
+        let (
+            msg_sender,
+            network_task_join_handle,
+            reconnect_sender,
+            event_sender,
+            identity,
+            stats,
+            journal,
+        ) = match connection {
+            Some(connection) => NetworkHandler::start(connection, config),
+            None => NetworkHandler::start_lazy(config),
+        };
+
+        #[cfg(feature = "otel-metrics")]
+        if let Some(otel_metrics) = &otel_metrics {
+            otel_metrics.observe_registry(&stats);
+        }
+
+        Self {
+            msg_sender: Arc::new(Some(msg_sender)),
+            network_task_join_handle: Arc::new(Some(network_task_join_handle)),
+            reconnect_sender,
+            event_sender,
+            client_state: Arc::new(RwLock::new(ClientState::new())),
+            command_timeout,
+            retry_on_error,
+            max_batch_commands,
+            max_batch_bytes,
+            client_id: CLIENT_ID_SEQUENCE.fetch_add(1, Ordering::Relaxed),
+            pending_commands: Arc::new(pending_commands),
+            request_coalescer,
+            legacy_shims,
+            layers,
+            identity,
+            stats,
+            journal,
+            error_context,
+            cluster,
+            shared_pub_sub,
+            databases: Arc::default(),
+            command_info_manager: Arc::default(),
+        }
+    }
+
+    /// Connects to an in-memory server, without any network access.
+    ///
+    /// This is meant to unit test code that depends on **rustis** without a live Redis server.
+    ///
+    /// Common commands are implemented against an in-memory keyspace;
+    /// canned replies and errors can be scripted for the other ones
+    /// with a [`MockServerBehavior`](crate::client::MockServerBehavior).
+    ///
+    /// # Errors
+    /// Any Redis driver [`Error`](crate::Error) that occurs during the connection operation
+    #[cfg_attr(docsrs, doc(cfg(feature = "mock")))]
+    #[cfg(feature = "mock")]
+    pub async fn connect_mock(behavior: MockServerBehavior) -> Result<Self> {
+        let connection = Connection::Mock(MockConnection::new(behavior));
+        Ok(Self::start(connection, Config::default()))
+    }
+
+    /// Connects asynchronously to the Redis server
+    /// and records the whole RESP exchange with the server into the file at `path`.
+    ///
+    /// The recording can later be replayed without any server with [`connect_replay`](Client::connect_replay),
+    /// enabling hermetic tests of applications built on **rustis**.
+    ///
+    /// The recording file is a sequence of RESP frames, each one preceded by a marker line:
+    /// `>` for a command sent to the server, `<` for a frame received from the server.
+    /// It is written when the client is [closed](Client::close) or dropped.
+    ///
+    /// # Errors
+    /// Any Redis driver [`Error`](crate::Error) that occurs during the connection operation
+    /// or an [`IO`](crate::Error::IO) error if the recording file cannot be created.
+    #[cfg_attr(docsrs, doc(cfg(feature = "mock")))]
+    #[cfg(feature = "mock")]
+    pub async fn connect_recording(
+        config: impl IntoConfig,
+        path: impl AsRef<Path>,
+    ) -> Result<Self> {
+        let config = config.into_config()?;
+        let connection = Connection::connect(config.clone()).await?;
+        let connection = Connection::Recording(RecordingConnection::new(connection, path)?);
+        Ok(Self::start(connection, config))
+    }
+
+    /// Serves the responses of a recording made with [`connect_recording`](Client::connect_recording),
+    /// without any server.
+    ///
+    /// Commands must be sent in the same order than during the recording:
+    /// any command which does not match the recorded one will fail with an [`Error::Client`](crate::Error::Client).
+    ///
+    /// # Errors
+    /// An [`IO`](crate::Error::IO) error if the recording file cannot be read
+    /// or an [`Error::Client`](crate::Error::Client) if the recording is malformed.
+    #[cfg_attr(docsrs, doc(cfg(feature = "mock")))]
+    #[cfg(feature = "mock")]
+    pub async fn connect_replay(path: impl AsRef<Path>) -> Result<Self> {
+        let connection = Connection::Replay(ReplayConnection::load(path)?);
+        Ok(Self::start(connection, Config::default()))
+    }
+
+    /// Connects asynchronously to the Redis server through a connection
+    /// which injects faults according to the schedule of `chaos`.
+    ///
+    /// See [`ChaosConfig`](crate::client::ChaosConfig)
+    ///
+    /// # Errors
+    /// Any Redis driver [`Error`](crate::Error) that occurs during the connection operation
+    #[cfg_attr(docsrs, doc(cfg(feature = "chaos")))]
+    #[cfg(feature = "chaos")]
+    pub async fn connect_chaos(config: impl IntoConfig, chaos: ChaosConfig) -> Result<Self> {
+        let config = config.into_config()?;
+        let connection = Connection::connect(config.clone()).await?;
+        let connection = Connection::Chaos(ChaosConnection::new(connection, chaos));
+        Ok(Self::new(config, Some(connection)))
+    }
+
+    #[cfg(feature = "mock")]
+    pub(crate) fn start(connection: Connection, mut config: Config) -> Self {
+        config.fold_deprecated_fields();
+        let command_timeout = config.resilience.command_timeout;
+        let retry_on_error = config.resilience.retry_on.retry_on_error();
+        let max_batch_commands = config.max_batch_commands;
+        let max_batch_bytes = config.max_batch_bytes;
+        let pending_commands =
+            PendingCommands::new(config.max_pending_commands, config.fail_on_queue_full);
+        let request_coalescer = config
+            .coalesce_reads
+            .then(|| Arc::new(RequestCoalescer::new()));
+        let legacy_shims = config
+            .legacy_command_shims
+            .then(|| Arc::new(LegacyShims::default()));
+        #[cfg_attr(not(feature = "otel-metrics"), allow(unused_mut))]
+        let mut layers = config.layers.clone();
+        #[cfg(feature = "otel-metrics")]
+        let otel_metrics = config.otel_metrics.then(OtelMetrics::global);
+        #[cfg(feature = "otel-metrics")]
+        if let Some(otel_metrics) = &otel_metrics {
+            layers.push(otel_metrics.clone());
+        }
+        let error_context = config.error_context;
+        let cluster = matches!(config.server, ServerConfig::Cluster(_));
+        let (
+            msg_sender,
+            network_task_join_handle,
+            reconnect_sender,
+            event_sender,
+            identity,
+            stats,
+            journal,
+        ) = NetworkHandler::start(connection, config);
+
+        #[cfg(feature = "otel-metrics")]
+        if let Some(otel_metrics) = &otel_metrics {
+            otel_metrics.observe_registry(&stats);
+        }
+
+        Self {
+            msg_sender: Arc::new(Some(msg_sender)),
+            network_task_join_handle: Arc::new(Some(network_task_join_handle)),
+            reconnect_sender,
+            event_sender,
+            client_state: Arc::new(RwLock::new(ClientState::new())),
+            command_timeout,
+            retry_on_error,
+            max_batch_commands,
+            max_batch_bytes,
+            client_id: CLIENT_ID_SEQUENCE.fetch_add(1, Ordering::Relaxed),
+            pending_commands: Arc::new(pending_commands),
+            request_coalescer,
+            legacy_shims,
+            layers,
+            identity,
+            stats,
+            journal,
+            error_context,
+            cluster,
+            shared_pub_sub: Arc::new(SharedPubSub::new(None)),
+            databases: Arc::default(),
+            command_info_manager: Arc::default(),
+        }
+    }
+
+    /// if this client is the last client on the shared connection, the channel to send messages
+    /// to the underlying network handler will be closed explicitely.
+    ///
+    /// Then, this function will await for the network handler to be ended
+    pub async fn close(mut self) -> Result<()> {
+        let mut network_task_join_handle: Arc<Option<JoinHandle<()>>> = Arc::new(None);
+        std::mem::swap(
+            &mut network_task_join_handle,
+            &mut self.network_task_join_handle,
+        );
+
+        // stop the network loop if we are the last reference to its handle
+        if let Ok(Some(network_task_join_handle)) = Arc::try_unwrap(network_task_join_handle) {
+            let mut msg_sender: Arc<Option<MsgSender>> = Arc::new(None);
+            std::mem::swap(&mut msg_sender, &mut self.msg_sender);
+
+            if let Ok(Some(msg_sender)) = Arc::try_unwrap(msg_sender) {
+                // the network loop will automatically ends when it detects the sender bound has been closed
+                msg_sender.close_channel();
+                network_task_join_handle.await?;
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Number of commands sent through this client and its clones, and not yet replied.
+    ///
+    /// This is the queue depth bounded by [`Config::max_pending_commands`](crate::client::Config::max_pending_commands)
+    #[inline]
+    pub fn pending_commands(&self) -> usize {
+        self.pending_commands.count()
+    }
+
+    /// Batch limits configured with [`Config::max_batch_commands`](crate::client::Config::max_batch_commands)
+    /// & [`Config::max_batch_bytes`](crate::client::Config::max_batch_bytes)
+    pub(crate) fn batch_limits(&self) -> (usize, usize) {
+        (self.max_batch_commands, self.max_batch_bytes)
+    }
+
+    /// Version of the connected server, as reported by [`HELLO`](https://redis.io/commands/hello/)
+    ///
+    /// The version is refreshed on reconnection.
+    /// Returns `None` if the version is unknown (mock or replay connections).
+    pub fn server_version(&self) -> Option<Version> {
+        self.identity.read().unwrap().server_version
+    }
+
+    /// `true` if the client is connected to a [Redis Cluster](https://redis.io/docs/management/scaling/)
+    ///
+    /// This includes a standalone configuration upgraded to a cluster connection
+    /// with [`Config::auto_detect_cluster`].
+    #[must_use]
+    pub fn is_cluster(&self) -> bool {
+        self.cluster || self.identity.read().unwrap().cluster
+    }
+
+    /// Id of the current connection, as returned by [`CLIENT ID`](https://redis.io/commands/client-id/)
+    /// at connection time
+    ///
+    /// The id is refreshed on reconnection.
+    /// Returns `None` for a cluster client, which holds one connection per node,
+    /// or if the id is unknown (mock or replay connections).
+    pub fn connection_id(&self) -> Option<i64> {
+        self.identity.read().unwrap().connection_id
+    }
+
+    /// `host:port` of the connected server, `None` for a cluster
+    /// or if the address is unknown (mock or replay connections)
+    #[cfg(feature = "patterns")]
+    pub(crate) fn server_address(&self) -> Option<String> {
+        self.identity.read().unwrap().address.clone()
+    }
+
+    /// Snapshot of the counters of each node of the connection:
+    /// commands sent, errors, retries, reconnections, latency percentiles & bytes in/out
+    ///
+    /// Counters are shared by this client and its clones.
+    /// Mock, recording & replay connections do not record any counter.
+    ///
+    /// # Example
+    /// ```
+    /// use rustis::{client::Client, commands::StringCommands, Result};
+    ///
+    /// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+    /// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+    /// async fn main() -> Result<()> {
+    ///     let client = Client::connect("127.0.0.1:6379").await?;
+    ///     client.set("key", "value").await?;
+    ///
+    ///     let stats = client.stats();
+    ///     let node = &stats.nodes[0];
+    ///     assert_eq!("127.0.0.1:6379", node.address);
+    ///     assert!(node.commands_sent > 0);
+    ///     assert!(node.latency_p99.is_some());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn stats(&self) -> ClientStats {
+        self.stats.snapshot()
+    }
+
+    #[cfg(feature = "otel-metrics")]
+    pub(crate) fn stats_registry(&self) -> &Arc<StatsRegistry> {
+        &self.stats
+    }
+
+    /// Journal of the last commands sent on the connection,
+    /// if enabled with [`Config::command_journal_size`](crate::client::Config::command_journal_size)
+    ///
+    /// The journal is shared by this client and its clones.
+    ///
+    /// # Example
+    /// ```
+    /// use rustis::{client::{Client, CommandOutcome}, commands::StringCommands, Result};
+    ///
+    /// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+    /// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+    /// async fn main() -> Result<()> {
+    ///     let client = Client::connect("redis://127.0.0.1:6379?command_journal_size=100").await?;
+    ///     client.set("key", "value").await?;
+    ///
+    ///     let journal = client.command_journal().unwrap();
+    ///     let entry = journal.dump().pop().unwrap();
+    ///     assert_eq!("SET", entry.name);
+    ///     assert_eq!(vec!["key", "value"], entry.args);
+    ///     assert_eq!(CommandOutcome::Ok, entry.outcome);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn command_journal(&self) -> Option<CommandJournal> {
+        self.journal.clone()
+    }
+
+    /// Remaining time to live of a key, based on [`PTTL`](https://redis.io/commands/pttl/).
+    ///
+    /// # Return
+    /// The remaining time to live of the key,
+    /// or `None` if the key does not exist or has no associated expiration time.
+    ///
+    /// # See Also
+    /// [`Expiry`](crate::commands::Expiry) returned by [`expiry`](GenericCommands::expiry)
+    /// to distinguish a missing key from a key without expiration time.
+    pub async fn ttl_duration<K>(&self, key: K) -> Result<Option<Duration>>
+    where
+        K: SingleArg + Send,
+    {
+        let millis = self.pttl(key).await?;
+        Ok(u64::try_from(millis).ok().map(Duration::from_millis))
+    }
+
+    /// [`Expiry`] of each of `keys`, queried with one command per key, sent in a single pipeline.
+    ///
+    /// Based on [`PEXPIRETIME`](https://redis.io/commands/pexpiretime/),
+    /// or on [`PTTL`](https://redis.io/commands/pttl/) before Redis 7.
+    /// On a cluster, each command is routed to the node owning its key:
+    /// the keys do not need to share the same hash slot.
+    ///
+    /// # Return
+    /// The expirations, in the order of `keys`
+    pub async fn ttl_many<K, KK>(&self, keys: KK) -> Result<Vec<Expiry>>
+    where
+        K: SingleArg,
+        KK: SingleArgCollection<K>,
+    {
+        let keys = CommandArgs::default().arg(keys).build();
+        let legacy = self
+            .server_version()
+            .is_some_and(|version| version.major < 7);
+
+        let mut pipeline = self.create_pipeline();
+        for key in &keys {
+            if legacy {
+                pipeline.pttl(key).queue();
+            } else {
+                pipeline.expiry(key).queue();
+            }
+        }
+
+        if !legacy {
+            return Self::execute_each(pipeline, keys.len()).await;
+        }
+
+        let now = SystemTime::now();
+        let ttls: Vec<i64> = Self::execute_each(pipeline, keys.len()).await?;
+        Ok(ttls
+            .into_iter()
+            .map(|millis| match millis {
+                -2 => Expiry::None,
+                -1 => Expiry::NoTtl,
+                millis => Expiry::At(now + Duration::from_millis(millis as u64)),
+            })
+            .collect())
+    }
+
+    /// Set the time to live of each key of `keys_and_ttls`
+    /// with one [`PEXPIRE`](https://redis.io/commands/pexpire/) per key, sent in a single pipeline.
+    ///
+    /// On a cluster, each command is routed to the node owning its key:
+    /// the keys do not need to share the same hash slot.
+    ///
+    /// # Return
+    /// For each key, in the order of `keys_and_ttls`, `true` if the time to live has been set,
+    /// `false` if the key does not exist
+    pub async fn expire_many<K>(
+        &self,
+        keys_and_ttls: impl IntoIterator<Item = (K, Duration)>,
+    ) -> Result<Vec<bool>>
+    where
+        K: SingleArg,
+    {
+        let mut pipeline = self.create_pipeline();
+        let mut num_keys = 0;
+        for (key, ttl) in keys_and_ttls {
+            pipeline
+                .pexpire(key, ttl.as_millis() as u64, ExpireOption::None)
+                .queue();
+            num_keys += 1;
+        }
+
+        Self::execute_each(pipeline, num_keys).await
+    }
+
+    /// Remove the time to live of each of `keys`
+    /// with one [`PERSIST`](https://redis.io/commands/persist/) per key, sent in a single pipeline.
+    ///
+    /// On a cluster, each command is routed to the node owning its key:
+    /// the keys do not need to share the same hash slot.
+    ///
+    /// # Return
+    /// For each key, in the order of `keys`, `true` if the time to live has been removed,
+    /// `false` if the key does not exist or has no associated time to live
+    pub async fn persist_many<K, KK>(&self, keys: KK) -> Result<Vec<bool>>
+    where
+        K: SingleArg,
+        KK: SingleArgCollection<K>,
+    {
+        let keys = CommandArgs::default().arg(keys).build();
+
+        let mut pipeline = self.create_pipeline();
+        for key in &keys {
+            pipeline.persist(key).queue();
+        }
+
+        Self::execute_each(pipeline, keys.len()).await
+    }
+
+    /// Delete a large set of keys by batches, at a limited rate,
+    /// so that the operation does not cause latency spikes on the server.
+    ///
+    /// A [`DeleteKeys::Pattern`] is scanned with [`SCAN`](https://redis.io/commands/scan/) on each master node
+    /// of a cluster, one node after the other, and the keys found on a node are deleted on that node.
+    /// On a cluster, the keys of each batch are grouped by hash slot,
+    /// with one command per hash slot, sent in a single pipeline.
+    ///
+    /// # Arguments
+    /// * `keys` - keys to delete
+    /// * `options` - see [`DeleteOptions`]
+    /// * `progress` - callback called after each batch
+    ///
+    /// # Return
+    /// The number of deleted keys
+    ///
+    /// # Errors
+    /// An [`Error::Client`] if the batch size is `0`, or the first error returned by a node.
+    /// The keys deleted before the error remain deleted.
+    ///
+    /// # Example
+    /// ```
+    /// use rustis::{
+    ///     client::{Client, DeleteKeys, DeleteOptions},
+    ///     commands::{FlushingMode, ServerCommands, StringCommands},
+    ///     Result,
+    /// };
+    ///
+    /// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+    /// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+    /// async fn main() -> Result<()> {
+    ///     let client = Client::connect("127.0.0.1:6379").await?;
+    ///     client.flushdb(FlushingMode::Sync).await?;
+    ///     client.mset([("session:1", "a"), ("session:2", "b"), ("user:1", "c")]).await?;
+    ///
+    ///     let options = DeleteOptions {
+    ///         batch: 500,
+    ///         rate_limit: Some(10_000),
+    ///         ..Default::default()
+    ///     };
+    ///     let deleted = client
+    ///         .delete_keys(DeleteKeys::Pattern("session:*".to_owned()), options, |progress| {
+    ///             println!("{} keys deleted", progress.deleted_keys);
+    ///         })
+    ///         .await?;
+    ///     assert_eq!(2, deleted);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn delete_keys(
+        &self,
+        keys: DeleteKeys,
+        options: DeleteOptions,
+        mut progress: impl FnMut(DeleteProgress),
+    ) -> Result<usize> {
+        if options.batch == 0 {
+            return Err(Error::Client(
+                "Cannot delete keys with an empty batch".to_owned(),
+            ));
+        }
+
+        let started_at = Instant::now();
+        let mut current = DeleteProgress {
+            processed_keys: 0,
+            deleted_keys: 0,
+            total_keys: None,
+            elapsed: Duration::ZERO,
+        };
+
+        let mut report = |current: &mut DeleteProgress, num_keys: usize, deleted_keys: usize| {
+            current.processed_keys += num_keys;
+            current.deleted_keys += deleted_keys;
+            let target = options.rate_limit.map(|rate_limit| {
+                Duration::from_secs_f64(current.processed_keys as f64 / rate_limit.max(1) as f64)
+            });
+            current.elapsed = started_at.elapsed();
+            progress(*current);
+            target.and_then(|target| target.checked_sub(started_at.elapsed()))
+        };
+
+        match keys {
+            DeleteKeys::List(keys) => {
+                current.total_keys = Some(keys.len());
+                let mut keys = keys.into_iter().map(String::into_bytes);
+                loop {
+                    let batch: Vec<Vec<u8>> = keys.by_ref().take(options.batch).collect();
+                    if batch.is_empty() {
+                        break;
+                    }
+
+                    let num_keys = batch.len();
+                    let deleted_keys = self
+                        .delete_batch(batch, options.unlink, self.is_cluster())
+                        .await?;
+                    if let Some(delay) = report(&mut current, num_keys, deleted_keys) {
+                        sleep(delay).await;
+                    }
+                }
+            }
+            DeleteKeys::Pattern(pattern) => {
+                for node in self.master_clients().await? {
+                    let mut cursor = 0;
+                    loop {
+                        let (next_cursor, batch): (u64, Vec<BulkString>) = node
+                            .scan(
+                                cursor,
+                                ScanOptions::default()
+                                    .match_pattern(pattern.as_str())
+                                    .count(options.batch),
+                            )
+                            .await?;
+
+                        if !batch.is_empty() {
+                            let num_keys = batch.len();
+                            let batch = batch.into_iter().map(Vec::from).collect();
+                            let deleted_keys = node
+                                .delete_batch(batch, options.unlink, self.is_cluster())
+                                .await?;
+                            if let Some(delay) = report(&mut current, num_keys, deleted_keys) {
+                                sleep(delay).await;
+                            }
+                        }
+
+                        if next_cursor == 0 {
+                            break;
+                        }
+                        cursor = next_cursor;
+                    }
+                }
+            }
+        }
+
+        Ok(current.deleted_keys)
+    }
+
+    /// Delete a batch of [`delete_keys`](Client::delete_keys),
+    /// with one command per hash slot if `cluster` is `true`
+    async fn delete_batch(&self, keys: Vec<Vec<u8>>, unlink: bool, cluster: bool) -> Result<usize> {
+        if !cluster {
+            return if unlink {
+                self.unlink(keys).await
+            } else {
+                self.del(keys).await
+            };
+        }
+
+        let mut slots = HashMap::<u16, Vec<Vec<u8>>>::new();
+        for key in keys {
+            slots.entry(hash_slot(&key)).or_default().push(key);
+        }
+
+        let mut pipeline = self.create_pipeline();
+        let num_commands = slots.len();
+        for keys in slots.into_values() {
+            if unlink {
+                pipeline.unlink(keys).queue();
+            } else {
+                pipeline.del(keys).queue();
+            }
+        }
+
+        let deleted_keys: Vec<usize> = Self::execute_each(pipeline, num_commands).await?;
+        Ok(deleted_keys.into_iter().sum())
+    }
+
+    /// Results of the `num_commands` commands queued in `pipeline`, one per command
+    async fn execute_each<R: DeserializeOwned>(
+        pipeline: Pipeline<'_>,
+        num_commands: usize,
+    ) -> Result<Vec<R>> {
+        match num_commands {
+            0 => Ok(Vec::new()),
+            // the result of a single command is not wrapped in an array
+            1 => Ok(vec![pipeline.execute().await?]),
+            _ => pipeline.execute().await,
+        }
+    }
+
+    /// Approximated cardinality of each of the HyperLogLogs stored at `keys`,
+    /// queried with one [`PFCOUNT`](https://redis.io/commands/pfcount/) per key, sent in a single pipeline.
+    ///
+    /// Unlike [`pfcount`](HyperLogLogCommands::pfcount) called with several keys,
+    /// which returns the cardinality of their union, each key is counted separately.
+    /// On a cluster, the keys do not need to share the same hash slot.
+    ///
+    /// # Return
+    /// The cardinalities, in the order of `keys`
+    pub async fn pfcount_each<K, KK>(&self, keys: KK) -> Result<Vec<usize>>
+    where
+        K: SingleArg,
+        KK: SingleArgCollection<K>,
+    {
+        let keys = CommandArgs::default().arg(keys).build();
+        match keys.len() {
+            0 => Ok(Vec::new()),
+            1 => Ok(vec![self.pfcount(keys).await?]),
+            _ => {
+                let mut pipeline = self.create_pipeline();
+                for key in &keys {
+                    pipeline.pfcount(key).queue();
+                }
+                pipeline.execute().await
+            }
+        }
+    }
+
+    /// Merge the HyperLogLogs stored at `source_keys` into a temporary key,
+    /// deleted when the returned [`TempHyperLogLog`] is dropped.
+    ///
+    /// The temporary key shares the hash tag of the first source key
+    /// (or is tagged with the first source key itself), so that it is stored in the same hash slot on a cluster.
+    /// It is also created with a TTL, so that it does not outlive a crashed process.
+    ///
+    /// # Errors
+    /// An [`Error::Client`] if `source_keys` is empty.
+    ///
+    /// # Example
+    /// ```
+    /// use rustis::{client::Client, commands::HyperLogLogCommands, Result};
+    ///
+    /// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+    /// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+    /// async fn main() -> Result<()> {
+    ///     let client = Client::connect("127.0.0.1:6379").await?;
+    ///     client.pfadd("{visits}:monday", ["alice", "bob"]).await?;
+    ///     client.pfadd("{visits}:tuesday", ["bob", "carol"]).await?;
+    ///
+    ///     let week = client
+    ///         .pfmerge_into_temp(["{visits}:monday", "{visits}:tuesday"])
+    ///         .await?;
+    ///     assert_eq!(3, week.count().await?);
+    ///     week.delete().await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn pfmerge_into_temp<S, SS>(&self, source_keys: SS) -> Result<TempHyperLogLog>
+    where
+        S: SingleArg,
+        SS: SingleArgCollection<S>,
+    {
+        let source_keys = CommandArgs::default().arg(source_keys).build();
+        let Some(first_key) = source_keys.into_iter().next() else {
+            return Err(Error::Client(
+                "Cannot merge an empty list of HyperLogLogs".to_owned(),
+            ));
+        };
+
+        let tag = extract_hash_tag(first_key).unwrap_or(first_key);
+        let mut key = Vec::with_capacity(tag.len() + 32);
+        key.push(b'{');
+        key.extend_from_slice(tag);
+        key.extend_from_slice(format!("}}:pfmerge:{:016x}", rand::random::<u64>()).as_bytes());
+
+        let mut pipeline = self.create_pipeline();
+        pipeline.pfmerge(key.as_slice(), source_keys).queue();
+        pipeline
+            .pexpire(key.as_slice(), TEMP_HYPERLOGLOG_TTL, ExpireOption::None)
+            .forget();
+        pipeline.execute::<()>().await?;
+
+        Ok(TempHyperLogLog::new(self.clone(), key))
+    }
+
+    /// Poll the [`ROLE`](https://redis.io/commands/role/) command until the instance reaches `role`,
+    /// useful during orchestrated failovers (e.g. after [`replicaof`](ServerCommands::replicaof)).
+    ///
+    /// A replica is considered to have reached its role once its link with the master is up.
+    ///
+    /// # Return
+    /// The last result of the `ROLE` command
+    ///
+    /// # Errors
+    /// [`Error::Timeout`] if the instance has not reached `role` after `timeout`
+    pub async fn wait_for_role(&self, role: Role, timeout: Duration) -> Result<RoleResult> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let role_result = self.role().await?;
+            let reached = match role {
+                Role::Replica => role_result.is_master_link_up(),
+                _ => role_result.role() == role,
+            };
+
+            if reached {
+                return Ok(role_result);
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(Error::Timeout(format!(
+                    "Instance has not reached role {role:?} (current role: {:?})",
+                    role_result.role()
+                )));
+            }
+
+            sleep(WAIT_FOR_ROLE_INTERVAL.min(deadline - now)).await;
+        }
+    }
+
+    /// Command table of the server, retrieved with the [`COMMAND`](https://redis.io/commands/command/) command,
+    /// to be persisted and preloaded with [`Config::command_info_cache`]
+    ///
+    /// # Errors
+    /// An [`Error::Client`] if the version of the server is unknown (mock or replay connections).
+    pub async fn command_info_cache(&self) -> Result<CommandInfoCache> {
+        let Some(version) = self.server_version() else {
+            return Err(Error::Client("Unknown server version".to_owned()));
+        };
+
+        let reply = self.send(cmd("COMMAND"), None).await?;
+        // check the reply before caching it
+        reply.to::<Vec<CommandInfo>>()?;
+
+        Ok(CommandInfoCache::new(
+            &version.to_string(),
+            reply.as_bytes(),
+        ))
+    }
+
+    /// Address of the master node owning the [hash slot](crate::cluster::hash_slot) of `key`
+    ///
+    /// The cluster topology is queried from the server at each call
+    /// with the [`cluster_shards`](ClusterCommands::cluster_shards) command
+    /// (or [`cluster_slots`](ClusterCommands::cluster_slots) before Redis 7).
+    ///
+    /// # Errors
+    /// An error is returned if the client is not connected to a cluster
+    /// or if no master node owns the hash slot of `key`.
+    pub async fn node_for_key(&self, key: impl AsRef<[u8]>) -> Result<(String, u16)> {
+        let slot = hash_slot(key);
+        let shard_info_list = self.shard_info_list().await?;
+
+        let master_info = shard_info_list
+            .into_iter()
+            .find(|s| s.slots.iter().any(|s| s.0 <= slot && slot <= s.1))
+            .and_then(|s| s.nodes.into_iter().find(|n| n.role == "master"))
+            .ok_or_else(|| Error::Client(format!("No master node owns hash slot {slot}")))?;
+
+        let port = master_info.get_port()?;
+        Ok((master_info.ip, port))
+    }
+
+    /// Cluster topology, queried with [`cluster_shards`](ClusterCommands::cluster_shards)
+    /// (or [`cluster_slots`](ClusterCommands::cluster_slots) before Redis 7)
+    pub(crate) async fn shard_info_list(&self) -> Result<Vec<ClusterShardResult>> {
+        // From Redis 7.x CLUSTER SLOTS is deprecated in favor of CLUSTER SHARDS
+        match self.server_version() {
+            Some(version) if version.major < 7 => {
+                Ok(ClusterConnection::convert_from_legacy_shard_description(
+                    self.cluster_slots().await?,
+                ))
+            }
+            _ => self.cluster_shards().await,
+        }
+    }
+
+    /// Typed value of a module configuration parameter
+    ///
+    /// The parameter is read with [`config_get`](ServerCommands::config_get) since Redis 8
+    /// (e.g. `CONFIG GET search-timeout`), or with the module command on older servers
+    /// (e.g. `FT.CONFIG GET TIMEOUT`).
+    ///
+    /// # Return
+    /// The value of the parameter, or `None` if the parameter is not set
+    ///
+    /// # Errors
+    /// An [`Error::Client`] if the parameter cannot be read at runtime before Redis 8,
+    /// or if its value cannot be parsed
+    ///
+    /// # Example
+    /// ```
+    /// use rustis::{client::Client, commands::FtConfigKey, Result};
+    ///
+    /// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+    /// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+    /// async fn main() -> Result<()> {
+    ///     let client = Client::connect("127.0.0.1:6379").await?;
+    ///
+    ///     client.module_config_set(FtConfigKey::Timeout, 500).await?;
+    ///     let timeout = client.module_config_get(FtConfigKey::Timeout).await?;
+    ///     assert_eq!(Some(500), timeout.and_then(|t| t.as_i64()));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(feature = "redis-search", feature = "redis-time-series")))
+    )]
+    #[cfg(any(feature = "redis-search", feature = "redis-time-series"))]
+    pub async fn module_config_get(
+        &self,
+        key: impl ModuleConfigKey,
+    ) -> Result<Option<ModuleConfigValue>> {
+        let (command, name) = self.module_config_command(&key, "GET")?;
+        let values: HashMap<String, Value> = self.send(command, None).await?.to()?;
+
+        let value = values
+            .into_iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value);
+
+        match value {
+            None | Some(Value::Nil) => Ok(None),
+            Some(value) => {
+                let value = value.into_string()?;
+                key.parse_value(&value).map(Some)
+            }
+        }
+    }
+
+    /// Set a module configuration parameter,
+    /// see [`module_config_get`](Client::module_config_get)
+    ///
+    /// # Errors
+    /// An [`Error::Client`] if the parameter cannot be set at runtime before Redis 8
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(feature = "redis-search", feature = "redis-time-series")))
+    )]
+    #[cfg(any(feature = "redis-search", feature = "redis-time-series"))]
+    pub async fn module_config_set(
+        &self,
+        key: impl ModuleConfigKey,
+        value: impl SingleArg,
+    ) -> Result<()> {
+        let (command, _) = self.module_config_command(&key, "SET")?;
+        self.send(command.arg(value), None).await?.to()
+    }
+
+    /// `CONFIG <subcommand> <name>` since Redis 8,
+    /// `<module command> <subcommand> <legacy name>` before
+    #[cfg(any(feature = "redis-search", feature = "redis-time-series"))]
+    fn module_config_command(
+        &self,
+        key: &impl ModuleConfigKey,
+        subcommand: &'static str,
+    ) -> Result<(Command, &'static str)> {
+        match self.server_version() {
+            Some(version) if version.major < 8 => match key.legacy() {
+                Some((command, name)) => Ok((cmd(command).arg(subcommand).arg(name), name)),
+                None => Err(Error::Client(format!(
+                    "Config `{}` cannot be accessed at runtime before Redis 8",
+                    key.name()
+                ))),
+            },
+            _ => Ok((cmd("CONFIG").arg(subcommand).arg(key.name()), key.name())),
+        }
+    }
+
+    /// Make sure that the given [keyspace notifications](https://redis.io/docs/manual/keyspace-notifications/)
+    /// are enabled on the server
+    ///
+    /// The `notify-keyspace-events` parameter is read with [`config_get`](ServerCommands::config_get),
+    /// merged with the `required` events, and written back with [`config_set`](ServerCommands::config_set)
+    /// if some events are missing. Events already enabled are never disabled.
+    ///
+    /// The parameter is local to each node: for a cluster client,
+    /// each master node is reached with a temporary connection.
+    ///
+    /// # Return
+    /// A report of the events enabled on each node.
+    /// A node on which the parameter cannot be set (e.g. `CONFIG SET` denied by an ACL)
+    /// is reported with its error, check [`KeyspaceNotificationsReport::is_enabled`].
+    ///
+    /// # Errors
+    /// An error is returned if a node cannot be reached, or if the parameter cannot be read.
+    ///
+    /// # Example
+    /// ```
+    /// use rustis::{
+    ///     client::{Client, KeyspaceEvents},
+    ///     Result,
+    /// };
+    ///
+    /// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+    /// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+    /// async fn main() -> Result<()> {
+    ///     let client = Client::connect("127.0.0.1:6379").await?;
+    ///
+    ///     let report = client
+    ///         .ensure_keyspace_notifications(KeyspaceEvents::KEYEVENT | KeyspaceEvents::EXPIRED)
+    ///         .await?;
+    ///     assert!(report.is_enabled());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn ensure_keyspace_notifications(
+        &self,
+        required: KeyspaceEvents,
+    ) -> Result<KeyspaceNotificationsReport> {
+        let mut nodes = Vec::new();
+        for client in self.master_clients().await? {
+            nodes.push(client.ensure_node_keyspace_notifications(required).await?);
+        }
+
+        Ok(KeyspaceNotificationsReport { required, nodes })
+    }
+
+    /// Clients on each master node of the cluster,
+    /// or a clone of this client if it is not connected to a cluster
+    ///
+    /// The clients on the nodes are connected when this function is called,
+    /// and disconnected when they are dropped.
+    pub(crate) async fn master_clients(&self) -> Result<Vec<Client>> {
+        if !self.is_cluster() {
+            return Ok(vec![self.clone()]);
+        }
+
+        let Some(config) = &self.shared_pub_sub.config else {
+            return Err(Error::Client(
+                "Cannot reach the nodes of the cluster without a configuration".to_owned(),
+            ));
+        };
+
+        let mut clients = Vec::new();
+        for shard_info in self.shard_info_list().await? {
+            for node_info in shard_info.nodes.into_iter().filter(|n| n.role == "master") {
+                let port = node_info.get_port()?;
+                let node_config = Config {
+                    server: ServerConfig::Standalone {
+                        host: node_info.ip,
+                        port,
+                    },
+                    auto_detect_cluster: false,
+                    ..config.clone()
+                };
+
+                clients.push(Client::connect(node_config).await?);
+            }
+        }
+
+        Ok(clients)
+    }
+
+    /// `notify-keyspace-events` of the connected node,
+    /// see [`ensure_keyspace_notifications`](Client::ensure_keyspace_notifications)
+    async fn ensure_node_keyspace_notifications(
+        &self,
+        required: KeyspaceEvents,
+    ) -> Result<NodeKeyspaceNotifications> {
+        const PARAMETER: &str = "notify-keyspace-events";
+
+        let values: HashMap<String, String> = self.config_get(PARAMETER).await?;
+        let previous = match values.get(PARAMETER) {
+            Some(value) => value.parse()?,
+            None => KeyspaceEvents::NONE,
+        };
+
+        let mut node = NodeKeyspaceNotifications {
+            node: self.identity.read().unwrap().address.clone(),
+            previous,
+            enabled: previous,
+            error: None,
+        };
+
+        if !previous.contains(required) {
+            let enabled = previous | required;
+            match self.config_set((PARAMETER, enabled.to_string())).await {
+                Ok(()) => node.enabled = enabled,
+                Err(e) => node.error = Some(e),
+            }
+        }
+
+        Ok(node)
+    }
+
+    /// Check that the user of the connection is allowed to run `commands`
+    ///
+    /// Each command is checked with [`ACL DRYRUN`](https://redis.io/commands/acl-dryrun/)
+    /// for the user returned by [`ACL WHOAMI`](https://redis.io/commands/acl-whoami/):
+    /// the commands are not executed.
+    /// Commands must be given with representative arguments, since the permissions
+    /// of a user can depend on the keys or channels, and on the subcommands.
+    ///
+    /// Meant to fail fast when a service starts with insufficient permissions.
+    ///
+    /// # Return
+    /// A report of the permission of each command
+    ///
+    /// # Errors
+    /// An [`Error::Client`] if the user is not allowed to run `ACL WHOAMI` or `ACL DRYRUN`
+    /// (e.g. `+acl|whoami +acl|dryrun` missing from its ACL rules).
+    /// `ACL DRYRUN` requires Redis 7.
+    ///
+    /// # Example
+    /// ```
+    /// use rustis::{
+    ///     client::Client,
+    ///     resp::cmd,
+    ///     Error, Result,
+    /// };
+    ///
+    /// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+    /// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+    /// async fn main() -> Result<()> {
+    ///     let client = Client::connect("127.0.0.1:6379").await?;
+    ///
+    ///     let report = client
+    ///         .probe_permissions([
+    ///             cmd("GET").arg("cache:1"),
+    ///             cmd("SET").arg("cache:1").arg("value"),
+    ///             cmd("PUBLISH").arg("events").arg("message"),
+    ///         ])
+    ///         .await?;
+    ///     if !report.is_allowed() {
+    ///         return Err(Error::Client(report.to_string()));
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn probe_permissions(
+        &self,
+        commands: impl IntoIterator<Item = Command>,
+    ) -> Result<PermissionsReport> {
+        fn probe_error(error: Error) -> Error {
+            match error {
+                Error::Redis(e) if e.kind == RedisErrorKind::NoPerm => Error::Client(format!(
+                    "Cannot probe permissions, `ACL WHOAMI` & `ACL DRYRUN` must be allowed: {e}"
+                )),
+                error => error,
+            }
+        }
+
+        let user: String = self.acl_whoami().await.map_err(probe_error)?;
+        let mut report = PermissionsReport {
+            user,
+            commands: Vec::new(),
+        };
+
+        for command in commands {
+            let dryrun = cmd("ACL")
+                .arg("DRYRUN")
+                .arg(report.user.as_str())
+                .arg(command.name)
+                .arg(&command.args);
+
+            let permission = match self.send(dryrun, None).await {
+                Ok(resp_buf) => match resp_buf.to::<String>()? {
+                    reply if reply == "OK" => CommandPermission::Allowed,
+                    reason => CommandPermission::Denied(reason),
+                },
+                Err(Error::Redis(e)) if e.kind != RedisErrorKind::NoPerm => {
+                    CommandPermission::Invalid(e)
+                }
+                Err(e) => return Err(probe_error(e)),
+            };
+
+            report
+                .commands
+                .push(CommandPermissionProbe::new(&command, permission));
+        }
+
+        Ok(report)
+    }
 
-impl Client {
-    /// Connects asynchronously to the Redis server.
+    /// Extract the keys of `command`
+    ///
+    /// Keys are extracted locally from the [key specifications](https://redis.io/docs/reference/key-specs/)
+    /// of the command, as returned by [`COMMAND`](https://redis.io/commands/command/)
+    /// or loaded from the [`command info cache`](crate::client::Config::command_info_cache).
+    /// The command table is loaded by the first call and shared by the clones of the client.
+    /// For commands whose keys cannot be found from their key specifications
+    /// (e.g. `movablekeys` commands on Redis 6), the keys are extracted by the server
+    /// with [`COMMAND GETKEYS`](https://redis.io/commands/command-getkeys/).
+    ///
+    /// Keys of commands registered with
+    /// [`Config::register_command_keys`](crate::client::Config::register_command_keys)
+    /// are extracted from the registered positions.
+    ///
+    /// Meant for middlewares which need the keys of any command,
+    /// like key prefixing, metrics by key pattern or routing of custom commands.
     ///
     /// # Errors
-    /// Any Redis driver [`Error`](crate::Error) that occurs during the connection operation
-    #[inline]
-    pub async fn connect(config: impl IntoConfig) -> Result<Self> {
-        let config = config.into_config()?;
-        let command_timeout = config.command_timeout;
-        let retry_on_error = config.retry_on_error;
-        let (msg_sender, network_task_join_handle, reconnect_sender) =
-            NetworkHandler::connect(config.into_config()?).await?;
+    /// An [`Error::Client`] if the command is unknown to the server
+    ///
+    /// # Example
+    /// ```
+    /// use rustis::{client::Client, resp::cmd, Result};
+    ///
+    /// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+    /// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+    /// async fn main() -> Result<()> {
+    ///     let client = Client::connect("127.0.0.1:6379").await?;
+    ///
+    ///     let keys = client
+    ///         .extract_keys(&cmd("MSET").arg("key1").arg("value1").arg("key2").arg("value2"))
+    ///         .await?;
+    ///     assert_eq!(vec!["key1".to_owned(), "key2".to_owned()], keys);
+    ///
+    ///     let keys = client
+    ///         .extract_keys(&cmd("EVAL").arg("return 1").arg(1).arg("key"))
+    ///         .await?;
+    ///     assert_eq!(vec!["key".to_owned()], keys);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn extract_keys(&self, command: &Command) -> Result<Vec<String>> {
+        let command_info_manager = self.command_info_manager().await?;
 
-        Ok(Self {
-            msg_sender: Arc::new(Some(msg_sender)),
-            network_task_join_handle: Arc::new(Some(network_task_join_handle)),
-            reconnect_sender,
-            client_state: Arc::new(RwLock::new(ClientState::new())),
-            command_timeout,
-            retry_on_error,
-        })
+        match command_info_manager.extract_keys_locally(command)? {
+            Some(keys) => Ok(keys.into_vec()),
+            None => {
+                self.command_getkeys(CommandInfoManager::prepare_command_getkeys_args(command))
+                    .await
+            }
+        }
     }
 
-    /// if this client is the last client on the shared connection, the channel to send messages
-    /// to the underlying network handler will be closed explicitely.
-    ///
-    /// Then, this function will await for the network handler to be ended
-    pub async fn close(mut self) -> Result<()> {
-        let mut network_task_join_handle: Arc<Option<JoinHandle<()>>> = Arc::new(None);
-        std::mem::swap(
-            &mut network_task_join_handle,
-            &mut self.network_task_join_handle,
-        );
-
-        // stop the network loop if we are the last reference to its handle
-        if let Ok(Some(network_task_join_handle)) = Arc::try_unwrap(network_task_join_handle) {
-            let mut msg_sender: Arc<Option<MsgSender>> = Arc::new(None);
-            std::mem::swap(&mut msg_sender, &mut self.msg_sender);
+    /// Command table of the server, loaded on first use
+    async fn command_info_manager(&self) -> Result<Arc<CommandInfoManager>> {
+        if let Some(command_info_manager) = &*self.command_info_manager.lock().unwrap() {
+            return Ok(command_info_manager.clone());
+        }
 
-            if let Ok(Some(msg_sender)) = Arc::try_unwrap(msg_sender) {
-                // the network loop will automatically ends when it detects the sender bound has been closed
-                msg_sender.close_channel();
-                network_task_join_handle.await?;
-            }
+        let config = self.shared_pub_sub.config.as_ref();
+        // key specifications are assumed for an unknown version (mock & replay connections)
+        let version = self.server_version().unwrap_or(Version {
+            major: 7,
+            minor: 0,
+            revision: 0,
+        });
+        let tag = self.identity.read().unwrap().address.clone();
+        let cached_command_infos = CommandInfoManager::cached_command_infos(
+            config.and_then(|config| config.command_info_cache.as_ref()),
+            version,
+            tag.as_deref().unwrap_or_default(),
+        );
+        let command_infos = match cached_command_infos {
+            Some(command_infos) => command_infos,
+            None => self.command().await?,
         };
 
-        Ok(())
+        let command_keys = config.map(|config| &config.command_keys);
+        let command_info_manager = Arc::new(CommandInfoManager::new(
+            command_infos,
+            version,
+            command_keys.unwrap_or(&HashMap::new()),
+        )?);
+        *self.command_info_manager.lock().unwrap() = Some(command_info_manager.clone());
+
+        Ok(command_info_manager)
     }
 
     /// Used to receive notifications when the client reconnects to the Redis server.
@@ -132,6 +1531,15 @@ impl Client {
         self.reconnect_sender.subscribe()
     }
 
+    /// Used to receive [`ConnectionEvent`](crate::client::ConnectionEvent)s, such as cluster topology changes
+    /// or cluster nodes marked as unhealthy.
+    ///
+    /// To turn this receiver into a Stream, you can use the
+    /// [`BroadcastStream`](https://docs.rs/tokio-stream/latest/tokio_stream/wrappers/struct.BroadcastStream.html) wrapper.
+    pub fn on_connection_event(&self) -> ConnectionEventReceiver {
+        self.event_sender.subscribe()
+    }
+
     /// Give an immutable generic access to attach any state to a client instance
     pub fn get_client_state(&self) -> RwLockReadGuard<ClientState> {
         self.client_state.read().unwrap()
@@ -150,7 +1558,7 @@ impl Client {
     /// # Arguments
     /// * `command` - generic [`Command`](crate::resp::Command) meant to be sent to the Redis server.
     /// * `retry_on_error` - retry to send the command on network error.
-    ///   * `None` - default behaviour defined in [`Config::retry_on_error`](crate::client::Config::retry_on_error)
+    ///   * `None` - default behaviour defined in [`ResiliencePolicy::retry_on`](crate::client::ResiliencePolicy::retry_on)
     ///   * `Some(true)` - retry sending command on network error
     ///   * `Some(false)` - do not retry sending command on network error
     ///
@@ -198,19 +1606,170 @@ impl Client {
 
     #[inline]
     pub async fn send(&self, command: Command, retry_on_error: Option<bool>) -> Result<RespBuf> {
+        self.send_with_timeout(command, retry_on_error, self.command_timeout)
+            .await
+    }
+
+    /// Send an arbitrary command to the server,
+    /// overriding [`ResiliencePolicy::command_timeout`](crate::client::ResiliencePolicy::command_timeout)
+    pub(crate) async fn send_with_timeout(
+        &self,
+        mut command: Command,
+        retry_on_error: Option<bool>,
+        command_timeout: Duration,
+    ) -> Result<RespBuf> {
+        if self.layers.is_empty() {
+            return self
+                .send_coalesced(command, retry_on_error, command_timeout)
+                .await;
+        }
+
+        self.layers.on_command(&mut command)?;
+        let observed_command = command.clone();
+        let start = Instant::now();
+        let result = self
+            .send_coalesced(command, retry_on_error, command_timeout)
+            .await;
+        let result = self.layers.on_reply(&observed_command, result);
+        self.layers
+            .on_result(&observed_command, &result, start.elapsed());
+        result
+    }
+
+    async fn send_coalesced(
+        &self,
+        command: Command,
+        retry_on_error: Option<bool>,
+        command_timeout: Duration,
+    ) -> Result<RespBuf> {
+        let Some(request_coalescer) = &self.request_coalescer else {
+            return self
+                .send_single(command, retry_on_error, command_timeout)
+                .await;
+        };
+
+        match request_coalescer.join(&command) {
+            // coalescable commands are never shimmed
+            CoalescedRequest::Leader(coalescing_guard) => {
+                self.send_unshimmed(
+                    command,
+                    retry_on_error,
+                    command_timeout,
+                    Some(coalescing_guard),
+                )
+                .await
+            }
+            CoalescedRequest::Follower(result_receiver) => {
+                let result = if command_timeout != Duration::ZERO {
+                    timeout(command_timeout, result_receiver).await?
+                } else {
+                    result_receiver.await
+                };
+
+                match result {
+                    Ok(result) => result,
+                    // the leader has been cancelled
+                    Err(_) => {
+                        self.send_single(command, retry_on_error, command_timeout)
+                            .await
+                    }
+                }
+            }
+            CoalescedRequest::None => {
+                self.send_single(command, retry_on_error, command_timeout)
+                    .await
+            }
+        }
+    }
+
+    async fn send_single(
+        &self,
+        command: Command,
+        retry_on_error: Option<bool>,
+        command_timeout: Duration,
+    ) -> Result<RespBuf> {
+        if let Some(shim) = self.legacy_shim(&command) {
+            return self
+                .send_legacy_shim(shim, retry_on_error, command_timeout)
+                .await;
+        }
+
+        self.send_unshimmed(command, retry_on_error, command_timeout, None)
+            .await
+    }
+
+    async fn send_unshimmed(
+        &self,
+        command: Command,
+        retry_on_error: Option<bool>,
+        command_timeout: Duration,
+        coalescing_guard: Option<CoalescingGuard>,
+    ) -> Result<RespBuf> {
+        let command_name = command.name;
+        let start = Instant::now();
+        let pending_commands_guard = self.pending_commands.acquire(1, command_timeout).await?;
         let (result_sender, result_receiver): (ResultSender, ResultReceiver) = oneshot::channel();
-        let message = Message::single(
+        let mut message = Message::single(
             command,
             result_sender,
             retry_on_error.unwrap_or(self.retry_on_error),
         );
+        message.pending_commands_guard = Some(pending_commands_guard);
+        message.coalescing_guard = coalescing_guard;
         self.send_message(message)?;
 
-        if self.command_timeout != Duration::ZERO {
-            timeout(self.command_timeout, result_receiver).await??
-        } else {
-            result_receiver.await?
+        let result = async {
+            if command_timeout != Duration::ZERO {
+                timeout(command_timeout, result_receiver).await??
+            } else {
+                result_receiver.await?
+            }
+        }
+        .await;
+        self.journaled(self.contextualized(result, command_name, start))
+    }
+
+    fn legacy_shim(&self, command: &Command) -> Option<LegacyShim> {
+        let legacy_shims = self.legacy_shims.as_ref()?;
+        legacy_shims.shim(command, self.server_version()?)
+    }
+
+    async fn send_legacy_shim(
+        &self,
+        shim: LegacyShim,
+        retry_on_error: Option<bool>,
+        command_timeout: Duration,
+    ) -> Result<RespBuf> {
+        let (commands, reply_index) = match shim {
+            LegacyShim::Command(command) => {
+                return self
+                    .send_unshimmed(*command, retry_on_error, command_timeout, None)
+                    .await
+            }
+            LegacyShim::Transaction {
+                commands,
+                reply_index,
+            } => (commands, reply_index),
+        };
+
+        let mut batch = Vec::with_capacity(commands.len() + 2);
+        batch.push(cmd("MULTI"));
+        batch.extend(commands);
+        batch.push(cmd("EXEC"));
+
+        let mut results = self
+            .send_batch_unlayered(batch, retry_on_error, command_timeout)
+            .await?;
+        let exec_reply = results
+            .pop()
+            .ok_or_else(|| Error::Client("Unexpected result for legacy command shim".to_owned()))?;
+
+        // MULTI + QUEUED commands
+        for result in results {
+            result.to::<()>()?;
         }
+
+        LegacyShim::transaction_reply(&exec_reply, reply_index)
     }
 
     /// Send command to the Redis server and forget its response.
@@ -218,16 +1777,23 @@ impl Client {
     /// # Arguments
     /// * `command` - generic [`Command`](crate::resp::Command) meant to be sent to the Redis server.
     /// * `retry_on_error` - retry to send the command on network error.
-    ///   * `None` - default behaviour defined in [`Config::retry_on_error`](crate::client::Config::retry_on_error)
+    ///   * `None` - default behaviour defined in [`ResiliencePolicy::retry_on`](crate::client::ResiliencePolicy::retry_on)
     ///   * `Some(true)` - retry sending command on network error
     ///   * `Some(false)` - do not retry sending command on network error
     ///
     /// # Errors
     /// Any Redis driver [`Error`](crate::Error) that occurs during the send operation
     #[inline]
-    pub fn send_and_forget(&self, command: Command, retry_on_error: Option<bool>) -> Result<()> {
-        let message =
+    pub fn send_and_forget(
+        &self,
+        mut command: Command,
+        retry_on_error: Option<bool>,
+    ) -> Result<()> {
+        self.layers.on_command(&mut command)?;
+        let pending_commands_guard = self.pending_commands.try_acquire(1)?;
+        let mut message =
             Message::single_forget(command, retry_on_error.unwrap_or(self.retry_on_error));
+        message.pending_commands_guard = Some(pending_commands_guard);
         self.send_message(message)?;
         Ok(())
     }
@@ -237,7 +1803,7 @@ impl Client {
     /// # Arguments
     /// * `commands` - batch of generic [`Command`](crate::resp::Command)s meant to be sent to the Redis server.
     /// * `retry_on_error` - retry to send the command batch on network error.
-    ///   * `None` - default behaviour defined in [`Config::retry_on_error`](crate::client::Config::retry_on_error)
+    ///   * `None` - default behaviour defined in [`ResiliencePolicy::retry_on`](crate::client::ResiliencePolicy::retry_on)
     ///   * `Some(true)` - retry sending batch on network error
     ///   * `Some(false)` - do not retry sending batch on network error
     ///
@@ -245,28 +1811,253 @@ impl Client {
     /// Any Redis driver [`Error`](crate::Error) that occurs during the send operation
     #[inline]
     pub async fn send_batch(
+        &self,
+        mut commands: Vec<Command>,
+        retry_on_error: Option<bool>,
+    ) -> Result<Vec<RespBuf>> {
+        if self.layers.is_empty() {
+            return self
+                .send_batch_unlayered(commands, retry_on_error, self.command_timeout)
+                .await;
+        }
+
+        for command in &mut commands {
+            self.layers.on_command(command)?;
+        }
+        let observed_commands = commands.clone();
+        let start = Instant::now();
+        let results = self
+            .send_batch_unlayered(commands, retry_on_error, self.command_timeout)
+            .await
+            .and_then(|results| {
+                observed_commands
+                    .iter()
+                    .zip(results)
+                    .map(|(command, result)| self.layers.on_reply(command, Ok(result)))
+                    .collect::<Result<Vec<_>>>()
+            });
+        let elapsed = start.elapsed();
+
+        match &results {
+            Ok(results) => {
+                for (command, result) in observed_commands.iter().zip(results) {
+                    self.layers.on_result(command, &Ok(result.clone()), elapsed);
+                }
+            }
+            Err(e) => {
+                for command in &observed_commands {
+                    self.layers.on_result(command, &Err(e.clone()), elapsed);
+                }
+            }
+        }
+
+        results
+    }
+
+    async fn send_batch_unlayered(
         &self,
         commands: Vec<Command>,
         retry_on_error: Option<bool>,
+        command_timeout: Duration,
     ) -> Result<Vec<RespBuf>> {
+        let command_name = commands.first().map_or("", |command| command.name);
+        let start = Instant::now();
+        let pending_commands_guard = self
+            .pending_commands
+            .acquire(commands.len(), command_timeout)
+            .await?;
         let (results_sender, results_receiver): (ResultsSender, ResultsReceiver) =
             oneshot::channel();
-        let message = Message::batch(
+        let mut message = Message::batch(
             commands,
             results_sender,
             retry_on_error.unwrap_or(self.retry_on_error),
         );
+        message.pending_commands_guard = Some(pending_commands_guard);
+        self.send_message(message)?;
+
+        let results = async {
+            if command_timeout != Duration::ZERO {
+                timeout(command_timeout, results_receiver).await??
+            } else {
+                results_receiver.await?
+            }
+        }
+        .await;
+        self.journaled(self.contextualized(results, command_name, start))
+    }
+
+    /// Attach the command journal to the client-side failures of a command,
+    /// see [`Error::Journaled`]
+    fn journaled<T>(&self, result: Result<T>) -> Result<T> {
+        match (result, &self.journal) {
+            (Err(error), Some(journal))
+                if matches!(error.inner(), Error::Timeout(_) | Error::IO(_)) =>
+            {
+                Err(Error::Journaled {
+                    error: Box::new(error),
+                    journal: journal.clone(),
+                })
+            }
+            (result, _) => result,
+        }
+    }
+
+    /// Attach the command name, the node & the elapsed time to the failures of a command,
+    /// see [`Error::Context`]
+    fn contextualized<T>(
+        &self,
+        result: Result<T>,
+        command_name: &str,
+        start: Instant,
+    ) -> Result<T> {
+        match result {
+            Err(error) if self.error_context => Err(error.with_context(ErrorContext {
+                command: Some(command_name.to_owned()),
+                node: self.identity.read().unwrap().address.clone(),
+                attempt: None,
+                elapsed: Some(start.elapsed()),
+            })),
+            result => result,
+        }
+    }
+
+    /// Send command to the Redis server without any reply from the server.
+    ///
+    /// Contrary to [`send_and_forget`](Client::send_and_forget), the command is preceded by
+    /// [`CLIENT REPLY SKIP`](https://redis.io/commands/client-reply/),
+    /// so the server does not even send back the reply of the command.
+    ///
+    /// Errors returned by the server are also discarded.
+    ///
+    /// # Arguments
+    /// * `command` - generic [`Command`](crate::resp::Command) meant to be sent to the Redis server.
+    /// * `retry_on_error` - retry to send the command on network error.
+    ///   * `None` - default behaviour defined in [`ResiliencePolicy::retry_on`](crate::client::ResiliencePolicy::retry_on)
+    ///   * `Some(true)` - retry sending command on network error
+    ///   * `Some(false)` - do not retry sending command on network error
+    ///
+    /// # Errors
+    /// An [`Error::Client`](crate::Error::Client) if the client is connected to a cluster,
+    /// or any Redis driver [`Error`](crate::Error) that occurs during the send operation
+    #[inline]
+    pub fn send_without_reply(
+        &self,
+        mut command: Command,
+        retry_on_error: Option<bool>,
+    ) -> Result<()> {
+        self.check_reply_mode_support()?;
+        self.layers.on_command(&mut command)?;
+        let pending_commands_guard = self.pending_commands.try_acquire(1)?;
+        // no reply is expected for this batch: the receiver is never awaited
+        let (results_sender, _): (ResultsSender, ResultsReceiver) = oneshot::channel();
+        let mut message = Message::batch(
+            vec![cmd("CLIENT").arg("REPLY").arg("SKIP"), command],
+            results_sender,
+            retry_on_error.unwrap_or(self.retry_on_error),
+        );
+        message.pending_commands_guard = Some(pending_commands_guard);
+        self.send_message(message)?;
+        Ok(())
+    }
+
+    /// Send a burst of commands to the Redis server without any reply from the server.
+    ///
+    /// The commands are surrounded by
+    /// [`CLIENT REPLY OFF`](https://redis.io/commands/client-reply/) and `CLIENT REPLY ON`,
+    /// so the only reply sent back by the server is the acknowledgement of `CLIENT REPLY ON`.
+    /// Awaiting the returned future ensures that the whole burst has been processed by the server.
+    ///
+    /// Errors returned by the server for the commands of the burst are discarded.
+    ///
+    /// # Arguments
+    /// * `commands` - batch of generic [`Command`](crate::resp::Command)s meant to be sent to the Redis server.
+    /// * `retry_on_error` - retry to send the command batch on network error.
+    ///   * `None` - default behaviour defined in [`ResiliencePolicy::retry_on`](crate::client::ResiliencePolicy::retry_on)
+    ///   * `Some(true)` - retry sending batch on network error
+    ///   * `Some(false)` - do not retry sending batch on network error
+    ///
+    /// # Errors
+    /// An [`Error::Client`](crate::Error::Client) if the client is connected to a cluster,
+    /// or any Redis driver [`Error`](crate::Error) that occurs during the send operation
+    ///
+    /// # Example
+    /// ```
+    /// use rustis::{client::Client, commands::StringCommands, resp::cmd, Result};
+    ///
+    /// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+    /// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+    /// async fn main() -> Result<()> {
+    ///     let client = Client::connect("127.0.0.1:6379").await?;
+    ///
+    ///     let commands = (0..100)
+    ///         .map(|i| cmd("SET").arg(format!("key{i}")).arg(i))
+    ///         .collect();
+    ///     client.send_batch_without_replies(commands, None).await?;
+    ///
+    ///     let value: i64 = client.get("key99").await?;
+    ///     assert_eq!(99, value);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn send_batch_without_replies(
+        &self,
+        mut commands: Vec<Command>,
+        retry_on_error: Option<bool>,
+    ) -> Result<()> {
+        self.check_reply_mode_support()?;
+        for command in &mut commands {
+            self.layers.on_command(command)?;
+        }
+        let pending_commands_guard = self
+            .pending_commands
+            .acquire(commands.len(), self.command_timeout)
+            .await?;
+        let mut batch = Vec::with_capacity(commands.len() + 2);
+        batch.push(cmd("CLIENT").arg("REPLY").arg("OFF"));
+        batch.extend(commands);
+        batch.push(cmd("CLIENT").arg("REPLY").arg("ON"));
+
+        let (results_sender, results_receiver): (ResultsSender, ResultsReceiver) =
+            oneshot::channel();
+        let mut message = Message::batch(
+            batch,
+            results_sender,
+            retry_on_error.unwrap_or(self.retry_on_error),
+        );
+        message.pending_commands_guard = Some(pending_commands_guard);
         self.send_message(message)?;
 
-        if self.command_timeout != Duration::ZERO {
+        let results = if self.command_timeout != Duration::ZERO {
             timeout(self.command_timeout, results_receiver).await??
         } else {
             results_receiver.await?
+        }?;
+
+        // only the acknowledgement of CLIENT REPLY ON is expected
+        match results.into_iter().next() {
+            Some(result) => result.to::<()>(),
+            None => Err(Error::Client(
+                "Missing acknowledgement of CLIENT REPLY ON".to_owned(),
+            )),
+        }
+    }
+
+    /// `CLIENT REPLY` only applies to the connection of a node,
+    /// so it cannot be used with a cluster client
+    fn check_reply_mode_support(&self) -> Result<()> {
+        if self.is_cluster() {
+            return Err(Error::Client(
+                "Commands without reply are not supported on a cluster".to_owned(),
+            ));
         }
+        Ok(())
     }
 
     #[inline]
-    fn send_message(&self, message: Message) -> Result<()> {
+    fn send_message(&self, mut message: Message) -> Result<()> {
+        message.client_id = self.client_id;
         if let Some(msg_sender) = &self.msg_sender as &Option<MsgSender> {
             trace!("Will enqueue message: {message:?}");
             Ok(msg_sender.unbounded_send(message).map_err(|e| {
@@ -293,6 +2084,8 @@ impl Client {
     }
 
     /// Create a new pub sub stream with no upfront subscription
+    ///
+    /// The dedicated pub/sub connection of the client is acquired on the first subscription.
     #[inline]
     pub fn create_pub_sub(&self) -> PubSubStream {
         let (pub_sub_sender, pub_sub_receiver): (PubSubSender, PubSubReceiver) = mpsc::unbounded();
@@ -308,11 +2101,38 @@ impl Client {
         Ok(ClientTrackingInvalidationStream::new(push_receiver))
     }
 
+    /// Dedicated pub/sub connection of the client, shared by its clones
+    /// and created if no pub/sub stream currently holds it
+    pub(crate) async fn get_pub_sub_client(&self) -> Result<Arc<Client>> {
+        let Some(config) = &self.shared_pub_sub.config else {
+            return Ok(Arc::new(self.clone()));
+        };
+
+        if let Some(client) = self.shared_pub_sub.client.lock().unwrap().upgrade() {
+            return Ok(client);
+        }
+
+        let mut client = Client::connect(config.clone()).await?;
+        client.shared_pub_sub = Arc::new(SharedPubSub::new(None));
+        // subscriptions are tracked in the state of the client they have been made from
+        client.client_state = self.client_state.clone();
+        let client = Arc::new(client);
+
+        let mut shared_client = self.shared_pub_sub.client.lock().unwrap();
+        // another clone may have created the connection concurrently
+        if let Some(client) = shared_client.upgrade() {
+            return Ok(client);
+        }
+        *shared_client = Arc::downgrade(&client);
+
+        Ok(client)
+    }
+
     pub(crate) async fn subscribe_from_pub_sub_sender(
         &self,
         channels: &CommandArgs,
         pub_sub_sender: &PubSubSender,
-    ) -> Result<()> {
+    ) -> Result<usize> {
         let (result_sender, result_receiver): (ResultSender, ResultReceiver) = oneshot::channel();
 
         let pub_sub_senders = channels
@@ -328,14 +2148,15 @@ impl Client {
 
         self.send_message(message)?;
 
-        result_receiver.await??.to::<()>()
+        self.subscription_confirmation(result_receiver, cmd("UNSUBSCRIBE").arg(channels.clone()))
+            .await
     }
 
     pub(crate) async fn psubscribe_from_pub_sub_sender(
         &self,
         patterns: &CommandArgs,
         pub_sub_sender: &PubSubSender,
-    ) -> Result<()> {
+    ) -> Result<usize> {
         let (result_sender, result_receiver): (ResultSender, ResultReceiver) = oneshot::channel();
 
         let pub_sub_senders = patterns
@@ -351,14 +2172,15 @@ impl Client {
 
         self.send_message(message)?;
 
-        result_receiver.await??.to::<()>()
+        self.subscription_confirmation(result_receiver, cmd("PUNSUBSCRIBE").arg(patterns.clone()))
+            .await
     }
 
     pub(crate) async fn ssubscribe_from_pub_sub_sender(
         &self,
         shardchannels: &CommandArgs,
         pub_sub_sender: &PubSubSender,
-    ) -> Result<()> {
+    ) -> Result<usize> {
         let (result_sender, result_receiver): (ResultSender, ResultReceiver) = oneshot::channel();
 
         let pub_sub_senders = shardchannels
@@ -374,8 +2196,105 @@ impl Client {
 
         self.send_message(message)?;
 
-        result_receiver.await??.to::<()>()
+        let subscription_count = self
+            .subscription_confirmation(
+                result_receiver,
+                cmd("SUNSUBSCRIBE").arg(shardchannels.clone()),
+            )
+            .await?;
+        self.get_client_state_mut()
+            .shard_subscriptions_mut()
+            .subscribe(shardchannels);
+
+        Ok(subscription_count)
     }
+
+    /// Wait for the confirmation of the last subscription of a subscribe command
+    /// and return the number of active subscriptions it reports
+    ///
+    /// If the confirmation is not awaited till the end, the subscription is cancelled
+    /// with `unsubscribe_command`, see [`PendingConfirmation`].
+    async fn subscription_confirmation(
+        &self,
+        result_receiver: ResultReceiver,
+        unsubscribe_command: Command,
+    ) -> Result<usize> {
+        let result = PendingConfirmation::new(self, result_receiver, unsubscribe_command)
+            .wait(self.command_timeout)
+            .await?;
+
+        subscription_count(&result)
+    }
+
+    /// Send an unsubscribe command on a pub/sub connection
+    /// and return the number of active subscriptions reported by its last confirmation
+    pub(crate) async fn unsubscribe_command(&self, command: Command) -> Result<usize> {
+        let result = self.send(command, None).await?;
+        subscription_count(&result)
+    }
+}
+
+/// Confirmation of a command switching the connection to a subscribed or monitoring state.
+///
+/// Connection reads are owned by the network task, so cancelling the future of a command
+/// never corrupts the connection. However, a subscribe or monitor future cancelled
+/// (or timed out) before its confirmation would leave the connection in a state nobody listens to.
+/// When dropped before the end of [`wait`](PendingConfirmation::wait), the confirmation
+/// is awaited in the background and the state is then left with `cancel_command`.
+struct PendingConfirmation {
+    client: Client,
+    result_receiver: Option<ResultReceiver>,
+    cancel_command: Option<Command>,
+}
+
+impl PendingConfirmation {
+    fn new(client: &Client, result_receiver: ResultReceiver, cancel_command: Command) -> Self {
+        Self {
+            client: client.clone(),
+            result_receiver: Some(result_receiver),
+            cancel_command: Some(cancel_command),
+        }
+    }
+
+    async fn wait(mut self, command_timeout: Duration) -> Result<RespBuf> {
+        let Some(result_receiver) = &mut self.result_receiver else {
+            return Err(Error::Client("Confirmation already received".to_owned()));
+        };
+
+        let result = if command_timeout != Duration::ZERO {
+            timeout(command_timeout, result_receiver).await??
+        } else {
+            result_receiver.await?
+        };
+
+        self.result_receiver = None;
+        result
+    }
+}
+
+impl Drop for PendingConfirmation {
+    fn drop(&mut self) {
+        let (Some(result_receiver), Some(cancel_command)) =
+            (self.result_receiver.take(), self.cancel_command.take())
+        else {
+            return;
+        };
+
+        let client = self.client.clone();
+        spawn(async move {
+            if let Ok(Ok(_)) = result_receiver.await {
+                let _result = client.send_and_forget(cancel_command, None);
+            }
+        });
+    }
+}
+
+/// Number of active subscriptions held by a subscribe/unsubscribe confirmation:
+/// `[kind, channel or pattern, count]`
+fn subscription_count(confirmation: &RespBuf) -> Result<usize> {
+    let (_kind, _channel_or_pattern, count) =
+        confirmation.to::<(IgnoredAny, IgnoredAny, usize)>()?;
+    Ok(count)
 }
 
 /// Extension trait dedicated to [`PreparedCommand`](crate::client::PreparedCommand)
@@ -412,13 +2331,23 @@ where
                 let command_for_result = self.command.clone();
                 let result = self
                     .executor
-                    .send(self.command, self.retry_on_error)
+                    .send_with_timeout(
+                        self.command,
+                        self.retry_on_error,
+                        self.command_timeout
+                            .unwrap_or(self.executor.command_timeout),
+                    )
                     .await?;
                 custom_converter(result, command_for_result, self.executor).await
             } else {
                 let result = self
                     .executor
-                    .send(self.command, self.retry_on_error)
+                    .send_with_timeout(
+                        self.command,
+                        self.retry_on_error,
+                        self.command_timeout
+                            .unwrap_or(self.executor.command_timeout),
+                    )
                     .await?;
                 result.to()
             }
@@ -472,6 +2401,7 @@ impl<'a> TransactionCommands<'a> for &'a Client {}
 #[cfg_attr(docsrs, doc(cfg(feature = "redis-bloom")))]
 #[cfg(feature = "redis-bloom")]
 impl<'a> TopKCommands<'a> for &'a Client {}
+impl<'a> VectorSetCommands<'a> for &'a Client {}
 
 impl<'a> PubSubCommands<'a> for &'a Client {
     #[inline]
@@ -484,7 +2414,9 @@ impl<'a> PubSubCommands<'a> for &'a Client {
 
         let (pub_sub_sender, pub_sub_receiver): (PubSubSender, PubSubReceiver) = mpsc::unbounded();
 
-        self.subscribe_from_pub_sub_sender(&channels, &pub_sub_sender)
+        let pub_sub_client = self.get_pub_sub_client().await?;
+        pub_sub_client
+            .subscribe_from_pub_sub_sender(&channels, &pub_sub_sender)
             .await?;
 
         Ok(PubSubStream::from_channels(
@@ -492,6 +2424,7 @@ impl<'a> PubSubCommands<'a> for &'a Client {
             pub_sub_sender,
             pub_sub_receiver,
             self.clone(),
+            pub_sub_client,
         ))
     }
 
@@ -505,7 +2438,9 @@ impl<'a> PubSubCommands<'a> for &'a Client {
 
         let (pub_sub_sender, pub_sub_receiver): (PubSubSender, PubSubReceiver) = mpsc::unbounded();
 
-        self.psubscribe_from_pub_sub_sender(&patterns, &pub_sub_sender)
+        let pub_sub_client = self.get_pub_sub_client().await?;
+        pub_sub_client
+            .psubscribe_from_pub_sub_sender(&patterns, &pub_sub_sender)
             .await?;
 
         Ok(PubSubStream::from_patterns(
@@ -513,6 +2448,7 @@ impl<'a> PubSubCommands<'a> for &'a Client {
             pub_sub_sender,
             pub_sub_receiver,
             self.clone(),
+            pub_sub_client,
         ))
     }
 
@@ -526,7 +2462,9 @@ impl<'a> PubSubCommands<'a> for &'a Client {
 
         let (pub_sub_sender, pub_sub_receiver): (PubSubSender, PubSubReceiver) = mpsc::unbounded();
 
-        self.ssubscribe_from_pub_sub_sender(&shardchannels, &pub_sub_sender)
+        let pub_sub_client = self.get_pub_sub_client().await?;
+        pub_sub_client
+            .ssubscribe_from_pub_sub_sender(&shardchannels, &pub_sub_sender)
             .await?;
 
         Ok(PubSubStream::from_shardchannels(
@@ -534,6 +2472,7 @@ impl<'a> PubSubCommands<'a> for &'a Client {
             pub_sub_sender,
             pub_sub_receiver,
             self.clone(),
+            pub_sub_client,
         ))
     }
 }
@@ -547,7 +2486,9 @@ impl<'a> BlockingCommands<'a> for &'a Client {
 
         self.send_message(message)?;
 
-        let _bytes = result_receiver.await??;
+        let _bytes = PendingConfirmation::new(self, result_receiver, cmd("RESET"))
+            .wait(Duration::ZERO)
+            .await?;
         Ok(MonitorStream::new(push_receiver, self.clone()))
     }
 }
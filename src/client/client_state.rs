@@ -1,24 +1,37 @@
-use crate::{Error, Result};
+use crate::{cluster::hash_slot, resp::CommandArgs, Error, Result};
 use std::{
     any::Any,
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, BTreeMap, HashMap},
 };
 
 /// A struct which goal is to give a generic access to attach any state to a client instance
 ///
-/// It is internally used to cache [RedisGraph](crate::commands::GraphCommands) metadata.
+/// It is internally used to cache [RedisGraph](crate::commands::GraphCommands) metadata
+/// and to track [shard channel subscriptions](ShardSubscriptions).
 #[derive(Default)]
 pub struct ClientState {
     cache: HashMap<String, Box<dyn Any + Send + Sync>>,
+    shard_subscriptions: ShardSubscriptions,
 }
 
 impl ClientState {
     pub(crate) fn new() -> ClientState {
         ClientState {
             cache: HashMap::new(),
+            shard_subscriptions: ShardSubscriptions::default(),
         }
     }
 
+    /// Shard channels the client is subscribed to
+    #[must_use]
+    pub fn shard_subscriptions(&self) -> &ShardSubscriptions {
+        &self.shard_subscriptions
+    }
+
+    pub(crate) fn shard_subscriptions_mut(&mut self) -> &mut ShardSubscriptions {
+        &mut self.shard_subscriptions
+    }
+
     /// Get state with a specific type `S` for a specific `key`
     ///
     /// # Return
@@ -69,3 +82,63 @@ impl ClientState {
         cache_entry
     }
 }
+
+/// Shard channels subscribed to with [`ssubscribe`](crate::commands::PubSubCommands::ssubscribe),
+/// grouped by hash slot
+///
+/// In a cluster, the messages of a shard channel are only published on the shard owning its hash slot:
+/// when the slot is migrated, the server unsubscribes the clients from the shard channel.
+///
+/// See [`ClientState::shard_subscriptions`]
+#[derive(Debug, Default)]
+pub struct ShardSubscriptions {
+    slots: BTreeMap<u16, Vec<Vec<u8>>>,
+}
+
+impl ShardSubscriptions {
+    /// Hash slots of the subscribed shard channels, in ascending order
+    pub fn slots(&self) -> impl Iterator<Item = u16> + '_ {
+        self.slots.keys().copied()
+    }
+
+    /// Subscribed shard channels of the hash slot `slot`
+    pub fn shard_channels(&self, slot: u16) -> impl Iterator<Item = &[u8]> {
+        self.slots
+            .get(&slot)
+            .into_iter()
+            .flat_map(|shard_channels| shard_channels.iter().map(Vec::as_slice))
+    }
+
+    /// Number of subscribed shard channels
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.slots.values().map(Vec::len).sum()
+    }
+
+    /// Returns `true` if no shard channel is subscribed
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    pub(crate) fn subscribe(&mut self, shard_channels: &CommandArgs) {
+        for shard_channel in shard_channels {
+            let shard_channels = self.slots.entry(hash_slot(shard_channel)).or_default();
+            if !shard_channels.iter().any(|sc| sc == shard_channel) {
+                shard_channels.push(shard_channel.to_vec());
+            }
+        }
+    }
+
+    pub(crate) fn unsubscribe(&mut self, shard_channels: &CommandArgs) {
+        for shard_channel in shard_channels {
+            let slot = hash_slot(shard_channel);
+            if let Some(subscribed) = self.slots.get_mut(&slot) {
+                subscribed.retain(|sc| sc != shard_channel);
+                if subscribed.is_empty() {
+                    self.slots.remove(&slot);
+                }
+            }
+        }
+    }
+}
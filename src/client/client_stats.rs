@@ -0,0 +1,44 @@
+use serde::Serialize;
+use std::time::Duration;
+
+/// Snapshot of the counters of a [`Client`](crate::client::Client),
+/// returned by [`Client::stats`](crate::client::Client::stats)
+///
+/// Serializable to be exposed as is by a health endpoint.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ClientStats {
+    /// Counters of each node the client has been connected to, sorted by address
+    ///
+    /// A standalone connection has a single node,
+    /// a sentinel connection has a node for each master it has been connected to,
+    /// a cluster connection has a node for each master and replica of the cluster.
+    pub nodes: Vec<NodeStats>,
+}
+
+/// Counters of a node, see [`ClientStats`]
+///
+/// Counters are cumulative since the creation of the client,
+/// and are shared by the clones of the client.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NodeStats {
+    /// Address of the node (`host:port`)
+    pub address: String,
+    /// Number of commands sent to the node
+    pub commands_sent: u64,
+    /// Number of error replies and I/O errors received from the node,
+    /// excluding redirections
+    pub errors: u64,
+    /// Number of redirections (`MOVED`, `ASK`, `TRYAGAIN`) received from the node,
+    /// each of them leading to a retry of the command
+    pub retries: u64,
+    /// Number of reconnections to the node
+    pub reconnects: u64,
+    /// Median latency over the sliding window of the last replies
+    pub latency_p50: Option<Duration>,
+    /// 99th percentile latency over the sliding window of the last replies
+    pub latency_p99: Option<Duration>,
+    /// Number of bytes received from the node
+    pub bytes_in: u64,
+    /// Number of bytes sent to the node
+    pub bytes_out: u64,
+}
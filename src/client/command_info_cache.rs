@@ -0,0 +1,103 @@
+use crate::{commands::CommandInfo, resp::RespBuf, Error, Result, Version};
+use bytes::{BufMut, Bytes, BytesMut};
+use std::fmt;
+
+/// Command table of a Redis server, as returned by the [`COMMAND`](https://redis.io/commands/command/) command,
+/// serialized to bytes to be persisted and preloaded with [`Config::command_info_cache`](crate::client::Config::command_info_cache).
+///
+/// A cluster client retrieves the command table of the cluster at connection time,
+/// to extract the keys of the commands. Preloading the table saves this round trip,
+/// which returns a large reply.
+/// The table is only used if the version of the connected server is the version of the server it has been retrieved from,
+/// and is retrieved again otherwise.
+///
+/// The bytes hold the version of the server on the first line, followed by the raw RESP reply of the `COMMAND` command.
+///
+/// # Example
+/// ```
+/// use rustis::{
+///     client::{Client, CommandInfoCache, Config, IntoConfig},
+///     Result,
+/// };
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let uri = "redis+cluster://127.0.0.1:7000,127.0.0.1:7001,127.0.0.1:7002";
+///
+///     // retrieve the command table once, e.g. at build time
+///     let client = Client::connect(uri).await?;
+///     let bytes = client.command_info_cache().await?.as_bytes().to_vec();
+///
+///     // preload it
+///     let mut config = uri.into_config()?;
+///     config.command_info_cache = Some(CommandInfoCache::from_bytes(bytes));
+///     let client = Client::connect(config).await?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct CommandInfoCache {
+    bytes: Bytes,
+}
+
+impl CommandInfoCache {
+    pub(crate) fn new(version: &str, reply: &[u8]) -> Self {
+        let mut bytes = BytesMut::with_capacity(version.len() + 1 + reply.len());
+        bytes.put_slice(version.as_bytes());
+        bytes.put_u8(b'\n');
+        bytes.put_slice(reply);
+        Self {
+            bytes: bytes.freeze(),
+        }
+    }
+
+    /// Load a command table previously serialized with [`as_bytes`](CommandInfoCache::as_bytes)
+    ///
+    /// The bytes are not validated: an invalid command table is ignored at connection time
+    /// and retrieved from the server.
+    #[must_use]
+    pub fn from_bytes(bytes: impl Into<Bytes>) -> Self {
+        Self {
+            bytes: bytes.into(),
+        }
+    }
+
+    /// Serialized command table
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Version of the server the command table has been retrieved from,
+    /// `None` if the bytes are invalid
+    #[must_use]
+    pub fn version(&self) -> Option<Version> {
+        let (version, _) = self.split()?;
+        version.try_into().ok()
+    }
+
+    /// Deserialized command table
+    pub(crate) fn command_infos(&self) -> Result<Vec<CommandInfo>> {
+        let (_, reply) = self
+            .split()
+            .ok_or_else(|| Error::Client("Invalid command info cache".to_owned()))?;
+        RespBuf::from_slice(reply).to()
+    }
+
+    fn split(&self) -> Option<(&str, &[u8])> {
+        let separator = self.bytes.iter().position(|b| *b == b'\n')?;
+        let version = std::str::from_utf8(&self.bytes[..separator]).ok()?;
+        Some((version, &self.bytes[separator + 1..]))
+    }
+}
+
+impl fmt::Debug for CommandInfoCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CommandInfoCache")
+            .field("version", &self.version())
+            .field("len", &self.bytes.len())
+            .finish()
+    }
+}
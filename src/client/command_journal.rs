@@ -0,0 +1,232 @@
+use crate::{
+    resp::{Command, RespBuf},
+    Error, Result,
+};
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Commands whose arguments are never recorded, as they may hold credentials
+const REDACTED_COMMANDS: [&str; 5] = ["AUTH", "HELLO", "MIGRATE", "ACL", "CONFIG"];
+/// Maximum number of bytes recorded for each argument
+const MAX_ARG_LEN: usize = 64;
+const REDACTED: &str = "***";
+
+/// `true` if the arguments of the command `name` may hold credentials, whatever its case
+pub(crate) fn has_sensitive_args(name: &str) -> bool {
+    REDACTED_COMMANDS
+        .iter()
+        .any(|redacted| redacted.eq_ignore_ascii_case(name))
+}
+
+/// Outcome of a command recorded in a [`CommandJournal`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandOutcome {
+    /// The command has been sent and is waiting for its reply
+    Pending,
+    /// The server has replied with a value
+    Ok,
+    /// The server has replied with an error, or the command has failed on the client side
+    Error(String),
+    /// No reply is expected for the command, e.g. while replies are disabled with `CLIENT REPLY OFF`
+    NoReply,
+}
+
+/// Command recorded in a [`CommandJournal`]
+#[derive(Debug, Clone)]
+pub struct JournalEntry {
+    /// Name of the command
+    pub name: &'static str,
+    /// Arguments of the command, truncated to 64 bytes each.
+    ///
+    /// The arguments of `AUTH`, `HELLO`, `MIGRATE`, `ACL` & `CONFIG` are redacted.
+    pub args: Vec<String>,
+    /// Time at which the command has been written to the connection
+    pub sent_at: SystemTime,
+    /// Time elapsed between the write of the command and its reply, `None` while pending
+    pub duration: Option<Duration>,
+    /// Outcome of the command
+    pub outcome: CommandOutcome,
+}
+
+impl fmt::Display for JournalEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sent_at = self.sent_at.duration_since(UNIX_EPOCH).unwrap_or_default();
+        write!(
+            f,
+            "[{}.{:03}] {}",
+            sent_at.as_secs(),
+            sent_at.subsec_millis(),
+            self.name
+        )?;
+        for arg in &self.args {
+            write!(f, " {arg}")?;
+        }
+
+        match &self.outcome {
+            CommandOutcome::Pending => f.write_str(" => pending"),
+            CommandOutcome::Ok => f.write_str(" => ok"),
+            CommandOutcome::Error(e) => write!(f, " => error: {e}"),
+            CommandOutcome::NoReply => f.write_str(" => no reply"),
+        }?;
+
+        if let Some(duration) = self.duration {
+            write!(f, " ({duration:?})")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Ring buffer of the last commands sent on the connection of a [`Client`](crate::client::Client),
+/// see [`Config::command_journal_size`](crate::client::Config::command_journal_size)
+///
+/// This is a handle on the journal of the connection, shared by the clones of the client:
+/// [`dump`](CommandJournal::dump) always returns the current content of the journal.
+#[derive(Clone)]
+pub struct CommandJournal {
+    state: Arc<Mutex<JournalState>>,
+}
+
+struct JournalState {
+    capacity: usize,
+    entries: VecDeque<JournalEntry>,
+    /// id of the first entry of `entries`
+    first_id: u64,
+    /// ids of the commands waiting for their reply, in the order of their replies
+    pending: VecDeque<(u64, Instant)>,
+}
+
+impl CommandJournal {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(JournalState {
+                capacity,
+                entries: VecDeque::with_capacity(capacity),
+                first_id: 0,
+                pending: VecDeque::new(),
+            })),
+        }
+    }
+
+    /// Entries of the journal, from the oldest to the most recent
+    #[must_use]
+    pub fn dump(&self) -> Vec<JournalEntry> {
+        self.state.lock().unwrap().entries.iter().cloned().collect()
+    }
+
+    /// Record a command written to the connection
+    pub(crate) fn record_sent(&self, command: &Command, expect_reply: bool) {
+        let args = if has_sensitive_args(command.name) {
+            command
+                .args
+                .into_iter()
+                .map(|_| REDACTED.to_owned())
+                .collect()
+        } else {
+            command
+                .args
+                .into_iter()
+                .map(|arg| {
+                    let truncated = &arg[..arg.len().min(MAX_ARG_LEN)];
+                    let mut arg_str = String::from_utf8_lossy(truncated).into_owned();
+                    if truncated.len() < arg.len() {
+                        arg_str.push_str("...");
+                    }
+                    arg_str
+                })
+                .collect()
+        };
+
+        let mut state = self.state.lock().unwrap();
+        if state.entries.len() == state.capacity {
+            state.entries.pop_front();
+            state.first_id += 1;
+        }
+
+        let id = state.first_id + state.entries.len() as u64;
+        state.entries.push_back(JournalEntry {
+            name: command.name,
+            args,
+            sent_at: SystemTime::now(),
+            duration: None,
+            outcome: if expect_reply {
+                CommandOutcome::Pending
+            } else {
+                CommandOutcome::NoReply
+            },
+        });
+
+        if expect_reply {
+            state.pending.push_back((id, Instant::now()));
+        }
+    }
+
+    /// Record the reply of the oldest pending command
+    pub(crate) fn record_reply(&self, result: &Result<RespBuf>) {
+        let outcome = match result {
+            Ok(resp_buf) if resp_buf.is_error() => CommandOutcome::Error(
+                resp_buf
+                    .to::<()>()
+                    .err()
+                    .map_or_else(String::new, |e| e.to_string()),
+            ),
+            Ok(_) => CommandOutcome::Ok,
+            Err(e) => CommandOutcome::Error(e.to_string()),
+        };
+
+        let mut state = self.state.lock().unwrap();
+        if let Some((id, sent_at)) = state.pending.pop_front() {
+            state.complete(id, outcome, Some(sent_at.elapsed()));
+        }
+    }
+
+    /// Fail all the pending commands, e.g. on a disconnection
+    pub(crate) fn fail_pending(&self, error: &Error) {
+        let mut state = self.state.lock().unwrap();
+        while let Some((id, sent_at)) = state.pending.pop_front() {
+            state.complete(
+                id,
+                CommandOutcome::Error(error.to_string()),
+                Some(sent_at.elapsed()),
+            );
+        }
+    }
+}
+
+impl JournalState {
+    fn complete(&mut self, id: u64, outcome: CommandOutcome, duration: Option<Duration>) {
+        // the entry may have been evicted in the meantime
+        let Some(index) = id.checked_sub(self.first_id) else {
+            return;
+        };
+
+        if let Some(entry) = self.entries.get_mut(index as usize) {
+            entry.outcome = outcome;
+            entry.duration = duration;
+        }
+    }
+}
+
+impl fmt::Display for CommandJournal {
+    /// One line per entry, from the oldest to the most recent
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in self.dump() {
+            writeln!(f, "{entry}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for CommandJournal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock().unwrap();
+        f.debug_struct("CommandJournal")
+            .field("capacity", &state.capacity)
+            .field("len", &state.entries.len())
+            .finish()
+    }
+}
@@ -0,0 +1,144 @@
+use crate::{
+    resp::{Command, RespBuf},
+    Result,
+};
+use std::{fmt, sync::Arc, time::Duration};
+
+/// Middleware which intercepts the commands sent by a [`Client`](crate::client::Client)
+///
+/// Layers are registered with [`Config::layer`](crate::client::Config::layer)
+/// and enable cross-cutting features without patching every command trait:
+/// forbidden-command policies, automatic key prefixing, audit logging, ...
+///
+/// Layers intercept the commands sent with [`Client::send`](crate::client::Client::send),
+/// [`Client::send_and_forget`](crate::client::Client::send_and_forget),
+/// [`Client::send_batch`](crate::client::Client::send_batch) and their variants,
+/// which includes all the command traits, pipelines and transactions.
+/// Subscriptions of [`PubSubStream`](crate::client::PubSubStream) and
+/// [`MonitorStream`](crate::client::MonitorStream) are not intercepted.
+///
+/// # Example
+/// ```
+/// use rustis::{
+///     client::{Client, CommandLayer, Config, IntoConfig},
+///     commands::StringCommands,
+///     resp::Command,
+///     Error, Result,
+/// };
+///
+/// struct ForbidFlush;
+///
+/// impl CommandLayer for ForbidFlush {
+///     fn on_command(&self, command: &mut Command) -> Result<()> {
+///         if command.name == "FLUSHALL" || command.name == "FLUSHDB" {
+///             return Err(Error::Client(format!("Forbidden command {}", command.name)));
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let mut config = "127.0.0.1:6379".into_config()?;
+///     config.layer(ForbidFlush);
+///     let client = Client::connect(config).await?;
+///
+///     client.set("key", "value").await?;
+///     assert!(client.send(rustis::resp::cmd("FLUSHALL"), None).await.is_err());
+///
+///     Ok(())
+/// }
+/// ```
+pub trait CommandLayer: Send + Sync {
+    /// Called before a command is sent, in the registration order of the layers
+    ///
+    /// The command can be modified, or rejected with an error:
+    /// the command is not sent and the error is returned to the caller.
+    fn on_command(&self, command: &mut Command) -> Result<()> {
+        let _ = command;
+        Ok(())
+    }
+
+    /// Called with the reply of a command before it is returned to the caller,
+    /// in the reverse registration order of the layers
+    ///
+    /// The reply can be rewritten (e.g. to decompress values), or replaced by an error.
+    /// Not called for commands sent without waiting for their reply.
+    ///
+    /// Defaults to the reply unchanged.
+    fn on_reply(&self, command: &Command, reply: RespBuf) -> Result<RespBuf> {
+        let _ = command;
+        Ok(reply)
+    }
+
+    /// Called with the result of a command, in the reverse registration order of the layers
+    ///
+    /// Not called for commands sent without waiting for their reply,
+    /// like with [`Client::send_and_forget`](crate::client::Client::send_and_forget).
+    fn on_result(&self, command: &Command, result: &Result<RespBuf>) {
+        let _ = (command, result);
+    }
+
+    /// Called with the result of a command and the time elapsed since it has been sent,
+    /// instead of [`on_result`](CommandLayer::on_result)
+    ///
+    /// For a batch of commands, `elapsed` is the time taken by the whole batch.
+    ///
+    /// Defaults to [`on_result`](CommandLayer::on_result).
+    fn on_timed_result(&self, command: &Command, result: &Result<RespBuf>, elapsed: Duration) {
+        let _ = elapsed;
+        self.on_result(command, result);
+    }
+}
+
+/// Ordered collection of [`CommandLayer`]s,
+/// see [`Config::layers`](crate::client::Config::layers)
+#[derive(Clone, Default)]
+pub struct CommandLayers(Vec<Arc<dyn CommandLayer>>);
+
+impl CommandLayers {
+    /// Add a layer after the already registered ones
+    pub fn push(&mut self, layer: impl CommandLayer + 'static) {
+        self.0.push(Arc::new(layer));
+    }
+
+    /// Number of layers
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// `true` if there is no layer
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub(crate) fn on_command(&self, command: &mut Command) -> Result<()> {
+        self.0
+            .iter()
+            .try_for_each(|layer| layer.on_command(command))
+    }
+
+    pub(crate) fn on_reply(&self, command: &Command, result: Result<RespBuf>) -> Result<RespBuf> {
+        self.0
+            .iter()
+            .rev()
+            .try_fold(result?, |reply, layer| layer.on_reply(command, reply))
+    }
+
+    pub(crate) fn on_result(&self, command: &Command, result: &Result<RespBuf>, elapsed: Duration) {
+        for layer in self.0.iter().rev() {
+            layer.on_timed_result(command, result, elapsed);
+        }
+    }
+}
+
+impl fmt::Debug for CommandLayers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CommandLayers")
+            .field("len", &self.0.len())
+            .finish()
+    }
+}
@@ -0,0 +1,399 @@
+use crate::{
+    client::CommandLayer,
+    resp::{Command, RespBuf},
+    Error, Result,
+};
+use log::debug;
+use memchr::memmem;
+
+/// Header prepended to the compressed values, followed by the [codec](CompressionCodec) id
+///
+/// `0xFF` cannot start an UTF-8 string, so text values (e.g. JSON) cannot be mistaken for compressed ones.
+const MAGIC: &[u8] = b"\xFFRZ";
+/// Default maximum size of a decompressed value, the default `proto-max-bulk-len` of Redis
+const DEFAULT_MAX_DECOMPRESSED_SIZE: usize = 512 * 1024 * 1024;
+
+/// Compression algorithm of a [`CompressionLayer`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionCodec {
+    /// [gzip](https://www.gzip.org/), best ratio, slowest
+    #[cfg_attr(docsrs, doc(cfg(feature = "compression-gzip")))]
+    #[cfg(feature = "compression-gzip")]
+    Gzip,
+    /// [Zstandard](https://facebook.github.io/zstd/), good ratio and speed
+    #[cfg_attr(docsrs, doc(cfg(feature = "compression-zstd")))]
+    #[cfg(feature = "compression-zstd")]
+    Zstd,
+    /// [LZ4](https://lz4.org/), fastest
+    #[cfg_attr(docsrs, doc(cfg(feature = "compression-lz4")))]
+    #[cfg(feature = "compression-lz4")]
+    Lz4,
+}
+
+impl CompressionCodec {
+    fn id(self) -> u8 {
+        match self {
+            #[cfg(feature = "compression-gzip")]
+            CompressionCodec::Gzip => 1,
+            #[cfg(feature = "compression-zstd")]
+            CompressionCodec::Zstd => 2,
+            #[cfg(feature = "compression-lz4")]
+            CompressionCodec::Lz4 => 3,
+        }
+    }
+
+    fn from_id(id: u8) -> Option<Self> {
+        match id {
+            #[cfg(feature = "compression-gzip")]
+            1 => Some(CompressionCodec::Gzip),
+            #[cfg(feature = "compression-zstd")]
+            2 => Some(CompressionCodec::Zstd),
+            #[cfg(feature = "compression-lz4")]
+            3 => Some(CompressionCodec::Lz4),
+            _ => None,
+        }
+    }
+
+    fn compress(self, data: &[u8], out: &mut Vec<u8>) -> std::io::Result<()> {
+        match self {
+            #[cfg(feature = "compression-gzip")]
+            CompressionCodec::Gzip => {
+                use std::io::Write;
+                let mut encoder =
+                    flate2::write::GzEncoder::new(out, flate2::Compression::default());
+                encoder.write_all(data)?;
+                encoder.finish()?;
+            }
+            #[cfg(feature = "compression-zstd")]
+            CompressionCodec::Zstd => zstd::stream::copy_encode(data, out, 0)?,
+            #[cfg(feature = "compression-lz4")]
+            CompressionCodec::Lz4 => out.extend(lz4_flex::compress_prepend_size(data)),
+        }
+        Ok(())
+    }
+
+    /// Decompress `data`, or return `None` if the decompressed value exceeds `max_size` bytes
+    fn decompress(self, data: &[u8], max_size: usize) -> std::io::Result<Option<Vec<u8>>> {
+        #[cfg(any(feature = "compression-gzip", feature = "compression-zstd"))]
+        fn read_bounded(
+            reader: impl std::io::Read,
+            max_size: usize,
+        ) -> std::io::Result<Option<Vec<u8>>> {
+            use std::io::Read;
+            let mut out = Vec::new();
+            reader.take(max_size as u64 + 1).read_to_end(&mut out)?;
+            Ok((out.len() <= max_size).then_some(out))
+        }
+
+        match self {
+            #[cfg(feature = "compression-gzip")]
+            CompressionCodec::Gzip => read_bounded(flate2::read::GzDecoder::new(data), max_size),
+            #[cfg(feature = "compression-zstd")]
+            CompressionCodec::Zstd => {
+                read_bounded(zstd::stream::read::Decoder::new(data)?, max_size)
+            }
+            #[cfg(feature = "compression-lz4")]
+            CompressionCodec::Lz4 => {
+                // the decompressed size is prepended, check it before allocating
+                let size = data
+                    .get(..4)
+                    .map(|size| u32::from_le_bytes([size[0], size[1], size[2], size[3]]) as usize);
+                if size.is_some_and(|size| size > max_size) {
+                    return Ok(None);
+                }
+                lz4_flex::decompress_size_prepended(data)
+                    .map(Some)
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+            }
+        }
+    }
+}
+
+/// Compression of the values of the keys starting with a prefix
+#[derive(Debug, Clone)]
+struct CompressionRule {
+    prefix: Vec<u8>,
+    codec: Option<CompressionCodec>,
+    min_size: usize,
+}
+
+/// [Layer](CommandLayer) compressing the values written to Redis, and decompressing them on read,
+/// without touching the call sites
+///
+/// Values of at least `min_size` bytes written by `SET`, `SETNX`, `SETEX`, `PSETEX`, `GETSET`,
+/// `MSET`, `MSETNX`, `HSET`, `HSETNX`, `HMSET`, `LPUSH`, `RPUSH`, `LPUSHX`, `RPUSHX` & `LSET`
+/// are compressed with the [codec](CompressionCodec) of the longest matching key prefix,
+/// and stored with a small header identifying the codec.
+/// A value is stored as is if its compression does not save any space.
+///
+/// Any value of a reply starting with this header is decompressed,
+/// whatever the command which read it (`GET`, `MGET`, `HGETALL`, `LRANGE`, transactions, ...),
+/// so that values compressed with different rules, or before a rule was removed, can still be read.
+/// A value starting with the header but which cannot be decompressed (unknown codec, invalid data)
+/// is returned as is.
+/// The size of a decompressed value is bounded by
+/// [`max_decompressed_size`](CompressionLayer::max_decompressed_size).
+///
+/// Commands operating on the stored bytes (`APPEND`, `STRLEN`, `GETRANGE`, `SETRANGE`, ...)
+/// see the compressed values, and must not be used on the keys of a compression rule.
+///
+/// # Example
+/// ```
+/// use rustis::{
+///     client::{Client, CompressionCodec, CompressionLayer, IntoConfig},
+///     commands::StringCommands,
+///     Result,
+/// };
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let mut config = "127.0.0.1:6379".into_config()?;
+///     config.layer(
+///         CompressionLayer::new()
+///             .rule("doc:", CompressionCodec::Zstd, 1024)
+///             .exclude("doc:raw:"),
+///     );
+///     let client = Client::connect(config).await?;
+///
+///     let json = format!("[{}]", vec!["{\"name\":\"value\"}"; 1000].join(","));
+///     client.set("doc:1", json.as_str()).await?;
+///     let value: String = client.get("doc:1").await?;
+///     assert_eq!(json, value);
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CompressionLayer {
+    rules: Vec<CompressionRule>,
+    max_decompressed_size: usize,
+}
+
+impl Default for CompressionLayer {
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            max_decompressed_size: DEFAULT_MAX_DECOMPRESSED_SIZE,
+        }
+    }
+}
+
+impl CompressionLayer {
+    /// Create a layer without any rule, which only decompresses values
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maximum size of a decompressed value (default 512 MB),
+    /// a larger value failing its command with an [`Error::Client`]
+    /// instead of exhausting the memory of the client
+    #[must_use]
+    pub fn max_decompressed_size(mut self, max_decompressed_size: usize) -> Self {
+        self.max_decompressed_size = max_decompressed_size;
+        self
+    }
+
+    /// Compress the values of at least `min_size` bytes of the keys starting with `prefix`
+    /// (`""` for all the keys) with `codec`
+    #[must_use]
+    pub fn rule(
+        mut self,
+        prefix: impl Into<String>,
+        codec: CompressionCodec,
+        min_size: usize,
+    ) -> Self {
+        self.add_rule(prefix.into(), Some(codec), min_size);
+        self
+    }
+
+    /// Do not compress the values of the keys starting with `prefix`,
+    /// e.g. to exclude a sub-namespace of a [rule](CompressionLayer::rule)
+    #[must_use]
+    pub fn exclude(mut self, prefix: impl Into<String>) -> Self {
+        self.add_rule(prefix.into(), None, 0);
+        self
+    }
+
+    fn add_rule(&mut self, prefix: String, codec: Option<CompressionCodec>, min_size: usize) {
+        self.rules.retain(|rule| rule.prefix != prefix.as_bytes());
+        self.rules.push(CompressionRule {
+            prefix: prefix.into_bytes(),
+            codec,
+            min_size,
+        });
+        // longest prefixes first
+        self.rules
+            .sort_by_key(|rule| std::cmp::Reverse(rule.prefix.len()));
+    }
+
+    fn find_rule(&self, key: &[u8]) -> Option<&CompressionRule> {
+        self.rules.iter().find(|rule| key.starts_with(&rule.prefix))
+    }
+
+    /// Compress the value at `value_index` of `command`, following the rule of the key at `key_index`
+    fn compress_arg(
+        &self,
+        command: &mut Command,
+        key_index: usize,
+        value_index: usize,
+    ) -> Result<()> {
+        let (Some(key), Some(value)) = (command.args.get(key_index), command.args.get(value_index))
+        else {
+            return Ok(());
+        };
+        let Some(rule) = self.find_rule(key) else {
+            return Ok(());
+        };
+        let Some(codec) = rule.codec else {
+            return Ok(());
+        };
+        if value.len() < rule.min_size || command.get_streamed_arg(value_index).is_some() {
+            return Ok(());
+        }
+
+        let mut compressed = Vec::with_capacity(value.len() / 2);
+        compressed.extend_from_slice(MAGIC);
+        compressed.push(codec.id());
+        codec
+            .compress(value, &mut compressed)
+            .map_err(|e| Error::Client(format!("Cannot compress value: {e}")))?;
+
+        if compressed.len() < value.len() {
+            command.args.replace_arg(value_index, compressed);
+        }
+
+        Ok(())
+    }
+
+    /// Copy the RESP element starting at `pos` to `out`, decompressing its values,
+    /// and return the position of the next element
+    fn decompress_element(&self, buf: &[u8], pos: usize, out: &mut Vec<u8>) -> Result<usize> {
+        let line_end = buf
+            .get(pos..)
+            .and_then(|b| memmem::find(b, b"\r\n"))
+            .map(|i| pos + i)
+            .ok_or_else(|| Error::Client("Cannot decompress reply: invalid RESP".to_owned()))?;
+        let header = &buf[pos..line_end + 2];
+        let next = line_end + 2;
+        let len = || {
+            atoi::atoi::<usize>(&buf[pos + 1..line_end])
+                .ok_or_else(|| Error::Client("Cannot decompress reply: invalid RESP".to_owned()))
+        };
+
+        match buf[pos] {
+            b'$' | b'!' | b'=' if buf[pos + 1] != b'-' => {
+                let len = len()?;
+                let data = buf.get(next..next + len).ok_or_else(|| {
+                    Error::Client("Cannot decompress reply: invalid RESP".to_owned())
+                })?;
+                match (
+                    buf[pos],
+                    decompress_value(data, self.max_decompressed_size)?,
+                ) {
+                    (b'$', Some(value)) => {
+                        out.push(b'$');
+                        out.extend_from_slice(itoa::Buffer::new().format(value.len()).as_bytes());
+                        out.extend_from_slice(b"\r\n");
+                        out.extend_from_slice(&value);
+                        out.extend_from_slice(b"\r\n");
+                    }
+                    _ => out.extend_from_slice(&buf[pos..next + len + 2]),
+                }
+                Ok(next + len + 2)
+            }
+            b'*' | b'~' | b'>' | b'%' | b'|' if buf[pos + 1] != b'-' => {
+                let mut num_elements = len()?;
+                if matches!(buf[pos], b'%' | b'|') {
+                    num_elements *= 2;
+                }
+                // attributes are followed by the element they describe
+                if buf[pos] == b'|' {
+                    num_elements += 1;
+                }
+                out.extend_from_slice(header);
+                let mut pos = next;
+                for _ in 0..num_elements {
+                    pos = self.decompress_element(buf, pos, out)?;
+                }
+                Ok(pos)
+            }
+            _ => {
+                out.extend_from_slice(header);
+                Ok(next)
+            }
+        }
+    }
+}
+
+/// Decompress `data` if it starts with the compression header
+///
+/// `None` is returned if `data` is not compressed, or cannot be decompressed:
+/// it may then be a value of the user starting with the same bytes as the header.
+fn decompress_value(data: &[u8], max_size: usize) -> Result<Option<Vec<u8>>> {
+    let Some(rest) = data.strip_prefix(MAGIC) else {
+        return Ok(None);
+    };
+    let Some((codec, compressed)) = rest
+        .split_first()
+        .and_then(|(id, compressed)| Some((CompressionCodec::from_id(*id)?, compressed)))
+    else {
+        debug!("Value starting with the compression header left as is: unknown codec, check the compression features");
+        return Ok(None);
+    };
+    match codec.decompress(compressed, max_size) {
+        Ok(Some(value)) => Ok(Some(value)),
+        Ok(None) => Err(Error::Client(format!(
+            "Cannot decompress value: decompressed size exceeds {max_size} bytes"
+        ))),
+        Err(e) => {
+            debug!("Value starting with the compression header left as is: {e}");
+            Ok(None)
+        }
+    }
+}
+
+impl CommandLayer for CompressionLayer {
+    fn on_command(&self, command: &mut Command) -> Result<()> {
+        if self.rules.is_empty() {
+            return Ok(());
+        }
+
+        let num_args = command.args.len();
+        let name = command.name.to_ascii_uppercase();
+        match name.as_str() {
+            "SET" | "SETNX" | "GETSET" => self.compress_arg(command, 0, 1)?,
+            "SETEX" | "PSETEX" | "LSET" | "HSETNX" => self.compress_arg(command, 0, 2)?,
+            "MSET" | "MSETNX" => {
+                for key_index in (0..num_args).step_by(2) {
+                    self.compress_arg(command, key_index, key_index + 1)?;
+                }
+            }
+            "HSET" | "HMSET" => {
+                for value_index in (2..num_args).step_by(2) {
+                    self.compress_arg(command, 0, value_index)?;
+                }
+            }
+            "LPUSH" | "RPUSH" | "LPUSHX" | "RPUSHX" => {
+                for value_index in 1..num_args {
+                    self.compress_arg(command, 0, value_index)?;
+                }
+            }
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    fn on_reply(&self, _command: &Command, reply: RespBuf) -> Result<RespBuf> {
+        let bytes = reply.as_bytes();
+        if memmem::find(bytes, MAGIC).is_none() {
+            return Ok(reply);
+        }
+
+        let mut out = Vec::with_capacity(bytes.len() * 2);
+        self.decompress_element(bytes, 0, &mut out)?;
+        Ok(RespBuf::new(out.into()))
+    }
+}
@@ -1,10 +1,16 @@
-use crate::{Error, Result};
+use crate::{
+    client::{AsyncTransport, CommandInfoCache, CommandLayer, CommandLayers, DefaultOptions},
+    Error, Result,
+};
 #[cfg(feature = "tls")]
 use native_tls::{Certificate, Identity, Protocol, TlsConnector, TlsConnectorBuilder};
 use std::{
     collections::HashMap,
     fmt::{self, Display, Write},
+    net::IpAddr,
+    path::{Path, PathBuf},
     str::FromStr,
+    sync::Arc,
     time::Duration,
 };
 use url::Url;
@@ -12,13 +18,33 @@ use url::Url;
 const DEFAULT_PORT: u16 = 6379;
 const DEFAULT_DATABASE: usize = 0;
 const DEFAULT_WAIT_BETWEEN_FAILURES: u64 = 250;
+const DEFAULT_PREFER_LAST_GOOD_SENTINEL: bool = false;
+const DEFAULT_STALE_MASTER_GUARD: bool = false;
+const DEFAULT_NODE_FAILURE_THRESHOLD: usize = 0;
+const DEFAULT_NODE_COOLDOWN: u64 = 30_000;
 const DEFAULT_CONNECT_TIMEOUT: u64 = 10_000;
 const DEFAULT_COMMAND_TIMEOUT: u64 = 0;
 const DEFAULT_AUTO_RESUBSCRTBE: bool = true;
 const DEFAULT_AUTO_REMONITOR: bool = true;
 const DEFAULT_KEEP_ALIVE: Option<Duration> = None;
 const DEFAULT_NO_DELAY: bool = true;
-const DEFAULT_RETRY_ON_ERROR: bool = false;
+const DEFAULT_RETRIES: u32 = 0;
+const DEFAULT_MAX_IN_FLIGHT_PER_CLIENT: usize = 0;
+const DEFAULT_MAX_PENDING_COMMANDS: usize = 0;
+const DEFAULT_FAIL_ON_QUEUE_FULL: bool = false;
+const DEFAULT_STRICT_SERVER_VERSION: bool = false;
+const DEFAULT_WARN_DEPRECATED_COMMANDS: bool = false;
+const DEFAULT_LEGACY_COMMAND_SHIMS: bool = false;
+const DEFAULT_MAX_BATCH_COMMANDS: usize = 0;
+const DEFAULT_MAX_BATCH_BYTES: usize = 0;
+const DEFAULT_COMMAND_JOURNAL_SIZE: usize = 0;
+const DEFAULT_COALESCE_READS: bool = false;
+const DEFAULT_ERROR_CONTEXT: bool = false;
+const DEFAULT_LIB_NAME: &str = "rustis";
+const DEFAULT_AUTO_DETECT_CLUSTER: bool = false;
+#[cfg(feature = "otel-metrics")]
+const DEFAULT_OTEL_METRICS: bool = false;
+const REDACTED: &str = "***";
 
 type Uri<'a> = (
     &'a str,
@@ -47,6 +73,12 @@ pub struct Config {
     /// * [`ACL`](https://redis.io/docs/management/security/acl/)
     /// * [`Authentication`](https://redis.io/docs/management/security/#authentication)
     pub password: Option<String>,
+    /// An optional file containing the password for authentication,
+    /// taking precedence over [`password`](Config::password).
+    ///
+    /// The file is read at each connection attempt, so that a password rotated
+    /// by remounting a secret (e.g. Kubernetes secrets) is taken into account at reconnection.
+    pub password_file: Option<PathBuf>,
     /// The default database for this connection.
     ///
     /// If `database` is not set to `0`, a [`SELECT`](https://redis.io/commands/select/)
@@ -56,7 +88,14 @@ pub struct Config {
     #[cfg_attr(docsrs, doc(cfg(feature = "tls")))]
     #[cfg(feature = "tls")]
     pub tls_config: Option<TlsConfig>,
+    /// Timeouts, retries & backoff between reconnection attempts,
+    /// shared by standalone, sentinel & cluster connections
+    pub resilience: ResiliencePolicy,
     /// The time to attempt a connection before timing out. The default is 10 seconds
+    ///
+    /// When set to a value other than the default, overrides
+    /// [`ResiliencePolicy::connect_timeout`] once the client connects.
+    #[deprecated(note = "use `resilience.connect_timeout` instead")]
     pub connect_timeout: Duration,
     /// If a command does not return a reply within a set number of milliseconds,
     /// a timeout error will be thrown.
@@ -64,7 +103,22 @@ pub struct Config {
     /// If set to 0, no timeout is apply
     ///
     /// The default is 0
+    ///
+    /// When set to a value other than the default, overrides
+    /// [`ResiliencePolicy::command_timeout`] once the client connects.
+    #[deprecated(note = "use `resilience.command_timeout` instead")]
     pub command_timeout: Duration,
+    /// Defines the default strategy for retries on network error (default `false`)
+    ///
+    /// When `true`, overrides [`ResiliencePolicy::retry_on`] once the client connects.
+    #[deprecated(note = "use `resilience.retry_on` instead")]
+    pub retry_on_error: bool,
+    /// Reconnection policy configuration (Constant, Linear or Exponential)
+    ///
+    /// When set to a value other than the default, overrides [`ResiliencePolicy::retries`]
+    /// & [`ResiliencePolicy::backoff`] once the client connects.
+    #[deprecated(note = "use `resilience.retries` & `resilience.backoff` instead")]
+    pub reconnection: ReconnectionConfig,
     /// When the client reconnects, channels subscribed in the previous connection will be
     /// resubscribed automatically if `auto_resubscribe` is `true`.
     ///
@@ -80,6 +134,22 @@ pub struct Config {
     ///
     /// See [`client_setname`](crate::commands::ConnectionCommands::client_setname)
     pub connection_name: String,
+    /// Metadata tags of the connections, as `(key, value)` pairs (default none),
+    /// to identify which service or team owns which connections in
+    /// [`CLIENT LIST`](crate::commands::ConnectionCommands::client_list).
+    ///
+    /// The tags are rendered as `key1=value1,key2=value2` between parentheses
+    /// after the [connection name](Config::connection_name), e.g. `api(team=payments)`,
+    /// and after the [library name](Config::lib_name) set with `CLIENT SETINFO` (Redis 7.2+),
+    /// e.g. `rustis(team=payments)`.
+    ///
+    /// Keys and values must not be empty and must only contain printable ASCII characters,
+    /// except `=`, `,`, `:`, `(` and `)`.
+    pub connection_metadata: Vec<(String, String)>,
+    /// Name of the client library set with
+    /// [`CLIENT SETINFO LIB-NAME`](crate::commands::ConnectionCommands::client_setinfo) (Redis 7.2+),
+    /// e.g. to identify a framework built on top of rustis (default `rustis`)
+    pub lib_name: String,
     /// Enable/disable keep-alive functionality (default `None`)
     ///
     /// See [`TcpKeepAlive::with_time`](https://docs.rs/socket2/latest/socket2/struct.TcpKeepalive.html#method.with_time)
@@ -88,41 +158,224 @@ pub struct Config {
     ///
     /// See [`TcpStream::set_nodelay`](https://docs.rs/tokio/latest/tokio/net/struct.TcpStream.html#method.set_nodelay)    
     pub no_delay: bool,
-    /// Defines the default strategy for retries on network error (default `false`):
-    /// * `true` - retry sending the command/batch of commands on network error
-    /// * `false` - do not retry sending the command/batch of commands on network error
-    ///
-    /// This strategy can be overriden for each command/batch
-    /// of commands in the following functions:
-    /// * [`PreparedCommand::retry_on_error`](crate::client::PreparedCommand::retry_on_error)
-    /// * [`Pipeline::retry_on_error`](crate::client::Pipeline::retry_on_error)
-    /// * [`Transaction::retry_on_error`](crate::client::Transaction::retry_on_error)
-    /// * [`Client::send`](crate::client::Client::send)
-    /// * [`Client::send_and_forget`](crate::client::Client::send_and_forget)
-    /// * [`Client::send_batch`](crate::client::Client::send_batch)
-    pub retry_on_error: bool,
-    /// Reconnection policy configuration (Constant, Linear or Exponential)
-    pub reconnection: ReconnectionConfig,
+    /// Size of the socket send buffer, `SO_SNDBUF` (default `None`, system default)
+    pub send_buffer_size: Option<usize>,
+    /// Size of the socket receive buffer, `SO_RCVBUF` (default `None`, system default)
+    pub recv_buffer_size: Option<usize>,
+    /// Maximum time transmitted data may remain unacknowledged before the connection is closed,
+    /// `TCP_USER_TIMEOUT` (default `None`)
+    ///
+    /// Only supported on Linux & Android.
+    pub tcp_user_timeout: Option<Duration>,
+    /// IP type of service (TOS) / DSCP of the outgoing packets,
+    /// `IP_TOS` for IPv4 or `IPV6_TCLASS` for IPv6 (default `None`)
+    pub tos: Option<u32>,
+    /// Local address the socket is bound to before connecting (default `None`)
+    ///
+    /// Allows to select the outgoing network on a multi-homed host.
+    pub local_address: Option<IpAddr>,
+    /// Network interface the socket is bound to, `SO_BINDTODEVICE` (default `None`)
+    ///
+    /// Only supported on Linux & Android.
+    pub bind_interface: Option<String>,
+    /// Maximum number of commands sent by a single [`Client`](crate::client::Client) instance
+    /// and waiting for their replies on the shared connection (default `0`, unlimited).
+    ///
+    /// Each clone of a [`Client`](crate::client::Client) is limited independently,
+    /// preventing a clone issuing huge pipelines from starving the other clones
+    /// of the same multiplexed connection.
+    /// A batch of commands larger than this limit is still sent, once the clone has no more command in flight.
+    ///
+    /// Regardless of this limit, pending messages of the different clones are sent in a round-robin fashion.
+    pub max_in_flight_per_client: usize,
+    /// Maximum number of commands sent through a [`Client`](crate::client::Client) and its clones,
+    /// and not yet replied (default `0`, unlimited).
+    ///
+    /// This bounds the memory used by the commands queued for the shared connection,
+    /// when producers are faster than the Redis server.
+    /// Once the bound is reached, depending on [`fail_on_queue_full`](Config::fail_on_queue_full),
+    /// sending a command waits for a free slot (backpressure) or fails with [`Error::QueueFull`](crate::Error::QueueFull).
+    ///
+    /// The current number of pending commands is available through [`Client::pending_commands`](crate::client::Client::pending_commands).
+    pub max_pending_commands: usize,
+    /// Defines the strategy when [`max_pending_commands`](Config::max_pending_commands) is reached (default `false`):
+    /// * `true` - fail with [`Error::QueueFull`](crate::Error::QueueFull)
+    /// * `false` - wait for a free slot, at most for the [`command_timeout`](ResiliencePolicy::command_timeout)
+    ///
+    /// [`Client::send_and_forget`](crate::client::Client::send_and_forget) always fails when the queue is full.
+    pub fail_on_queue_full: bool,
+    /// Fail locally the commands introduced after the version of the connected server (default `false`).
+    ///
+    /// When enabled, such commands fail with [`Error::UnsupportedByServer`](crate::Error::UnsupportedByServer)
+    /// without being sent, instead of an `unknown command` error returned by the server.
+    ///
+    /// The server version is available through [`Client::server_version`](crate::client::Client::server_version).
+    pub strict_server_version: bool,
+    /// Log a warning the first time a deprecated command is sent on a connection,
+    /// with its documented replacement (default `false`)
+    ///
+    /// Deprecated commands are retrieved with [`COMMAND DOCS`](https://redis.io/commands/command-docs/)
+    /// at connection time.
+    pub warn_deprecated_commands: bool,
+    /// Emulate some commands introduced in Redis 6.2 when the connected server is older (default `false`)
+    ///
+    /// Emulated commands are translated into equivalent legacy commands, in a transaction
+    /// or a Lua script when several commands are needed: `GETDEL`, `GETEX`, `SET` with the `GET` option,
+    /// `COPY` without the `DB` option & `LMOVE`.
+    ///
+    /// A warning is logged the first time each command is emulated.
+    pub legacy_command_shims: bool,
+    /// Maximum number of commands sent in a single batch by [`Pipeline::execute`](crate::client::Pipeline::execute)
+    /// (default `0`, unlimited)
+    ///
+    /// Bigger pipelines are split into several sequential batches.
+    pub max_batch_commands: usize,
+    /// Maximum size in bytes of the encoded commands sent in a single batch by
+    /// [`Pipeline::execute`](crate::client::Pipeline::execute) (default `0`, unlimited)
+    ///
+    /// Bigger pipelines are split into several sequential batches,
+    /// to stay below the server query buffer limit (`client-query-buffer-limit`).
+    pub max_batch_bytes: usize,
+    /// Share a single server round trip among identical read-only commands
+    /// sent concurrently through a [`Client`](crate::client::Client) and its clones (default `false`).
+    ///
+    /// While a read-only command (e.g. `GET key`) is waiting for its reply,
+    /// identical commands do not reach the server but await the same reply.
+    /// This prevents cache stampedes, when many tasks read the same key at the same time.
+    ///
+    /// Only single commands sent with [`Client::send`](crate::client::Client::send)
+    /// (which includes awaited prepared commands) are coalesced, batches are never.
+    pub coalesce_reads: bool,
+    /// Number of the last commands of the connection retained in a [`CommandJournal`](crate::client::CommandJournal)
+    /// (default `0`, disabled)
+    ///
+    /// Each entry holds the name of the command, its redacted arguments, its outcome & timing.
+    /// The journal is available through [`Client::command_journal`](crate::client::Client::command_journal)
+    /// and is attached to the client-side failures of the commands,
+    /// see [`Error::Journaled`](crate::Error::Journaled).
+    pub command_journal_size: usize,
+    /// Attach the command name, the target node, the attempt number & the elapsed time
+    /// to the failures of the commands (default `false`)
+    ///
+    /// Failures are then wrapped in an [`Error::Context`](crate::Error::Context),
+    /// errors returned by the Redis server excepted.
+    /// The actual error is available through [`Error::inner`](crate::Error::inner).
+    pub error_context: bool,
+    /// (Cluster only) Close the connections to the nodes which have been idle for longer than this duration
+    /// (default `None`, never closed)
+    ///
+    /// Closed connections are re-opened on their next use.
+    /// This reduces the number of file descriptors used by clients of large clusters,
+    /// where some nodes, typically replicas, are rarely used.
+    ///
+    /// Idle connections are closed when a command is sent.
+    /// The connections on which a subscription has been made are never closed,
+    /// nor the last open connection of the cluster.
+    pub idle_connection_timeout: Option<Duration>,
+    /// (Cluster only) Command table preloaded instead of being retrieved
+    /// with the [`COMMAND`](https://redis.io/commands/command/) command at connection time (default `None`)
+    ///
+    /// The table is retrieved from the server anyway if the server version has changed,
+    /// see [`CommandInfoCache`].
+    ///
+    /// The command table cannot be expressed in an URL and is ignored by [`to_url`](Config::to_url).
+    pub command_info_cache: Option<CommandInfoCache>,
+    /// (Cluster only) Positions of the keys of commands, by uppercase command name,
+    /// overriding the key specifications returned by the server (default empty)
+    ///
+    /// See [`register_command_keys`](Config::register_command_keys).
+    ///
+    /// Command keys cannot be expressed in an URL and are ignored by [`to_url`](Config::to_url).
+    pub command_keys: HashMap<String, CommandKeys>,
+    /// Send a [`PING`](https://redis.io/commands/ping/) on a subscribed connection
+    /// which has not received anything for this duration (default `None`, disabled)
+    ///
+    /// Quiet subscriptions can be silently dropped by NAT gateways or firewalls:
+    /// the periodic `PING` keeps the connection alive and checks that it is still up.
+    /// If nothing is received within the same duration after the `PING`,
+    /// the connection is considered lost and is re-established,
+    /// the subscriptions being restored with [`auto_resubscribe`](Config::auto_resubscribe).
+    pub pub_sub_keepalive: Option<Duration>,
+    /// (Standalone only) Detect that the server is a node of a cluster
+    /// and upgrade transparently the connection to a cluster connection (default `false`)
+    ///
+    /// The server is detected as a cluster node when `INFO cluster` reports `cluster_enabled:1`
+    /// at connection time, or when a command is redirected with a `MOVED` error.
+    /// In this last case, the connection is re-established as a cluster connection,
+    /// the redirected command being retried according to the [`retry_on`](ResiliencePolicy::retry_on) policy.
+    ///
+    /// The cluster topology is then discovered from the configured host,
+    /// as if it was the only node of a [`ClusterConfig`].
+    pub auto_detect_cluster: bool,
+    /// Export the metrics of the client to OpenTelemetry (default `false`)
+    ///
+    /// When enabled, the [`OtelMetrics::global`](crate::client::OtelMetrics::global) layer is registered
+    /// and the counters of the client are observed by it, see [`OtelMetrics`](crate::client::OtelMetrics).
+    #[cfg_attr(docsrs, doc(cfg(feature = "otel-metrics")))]
+    #[cfg(feature = "otel-metrics")]
+    pub otel_metrics: bool,
+    /// Middleware layers intercepting the commands sent by a [`Client`](crate::client::Client),
+    /// see [`CommandLayer`].
+    ///
+    /// Layers cannot be expressed in an URL and are ignored by [`to_url`](Config::to_url).
+    pub layers: CommandLayers,
+    /// Transport opening the connections in place of TCP (default `None`),
+    /// see [`AsyncTransport`].
+    ///
+    /// Required on targets without TCP sockets, like WebAssembly.
+    /// A transport cannot be expressed in an URL and is ignored by [`to_url`](Config::to_url).
+    pub transport: Option<Arc<dyn AsyncTransport>>,
 }
 
 impl Default for Config {
+    #[allow(deprecated)]
     fn default() -> Self {
         Self {
             server: Default::default(),
             username: Default::default(),
             password: Default::default(),
+            password_file: Default::default(),
             database: Default::default(),
             #[cfg(feature = "tls")]
             tls_config: Default::default(),
+            resilience: Default::default(),
             connect_timeout: Duration::from_millis(DEFAULT_CONNECT_TIMEOUT),
             command_timeout: Duration::from_millis(DEFAULT_COMMAND_TIMEOUT),
+            retry_on_error: false,
+            reconnection: Default::default(),
             auto_resubscribe: DEFAULT_AUTO_RESUBSCRTBE,
             auto_remonitor: DEFAULT_AUTO_REMONITOR,
             connection_name: String::from(""),
+            connection_metadata: Vec::new(),
+            lib_name: DEFAULT_LIB_NAME.to_owned(),
             keep_alive: DEFAULT_KEEP_ALIVE,
             no_delay: DEFAULT_NO_DELAY,
-            retry_on_error: DEFAULT_RETRY_ON_ERROR,
-            reconnection: Default::default(),
+            send_buffer_size: None,
+            recv_buffer_size: None,
+            tcp_user_timeout: None,
+            tos: None,
+            local_address: None,
+            bind_interface: None,
+            max_in_flight_per_client: DEFAULT_MAX_IN_FLIGHT_PER_CLIENT,
+            max_pending_commands: DEFAULT_MAX_PENDING_COMMANDS,
+            fail_on_queue_full: DEFAULT_FAIL_ON_QUEUE_FULL,
+            strict_server_version: DEFAULT_STRICT_SERVER_VERSION,
+            warn_deprecated_commands: DEFAULT_WARN_DEPRECATED_COMMANDS,
+            legacy_command_shims: DEFAULT_LEGACY_COMMAND_SHIMS,
+            max_batch_commands: DEFAULT_MAX_BATCH_COMMANDS,
+            max_batch_bytes: DEFAULT_MAX_BATCH_BYTES,
+            coalesce_reads: DEFAULT_COALESCE_READS,
+            command_journal_size: DEFAULT_COMMAND_JOURNAL_SIZE,
+            error_context: DEFAULT_ERROR_CONTEXT,
+            idle_connection_timeout: None,
+            command_info_cache: None,
+            command_keys: HashMap::new(),
+            pub_sub_keepalive: None,
+            auto_detect_cluster: DEFAULT_AUTO_DETECT_CLUSTER,
+            #[cfg(feature = "otel-metrics")]
+            otel_metrics: DEFAULT_OTEL_METRICS,
+            layers: CommandLayers::default(),
+            transport: None,
         }
     }
 }
@@ -149,7 +402,7 @@ impl Config {
     }
 
     /// Parse address in the standard formart `host`:`port`
-    fn parse_addr(str: &str) -> Option<(&str, u16)> {
+    pub(crate) fn parse_addr(str: &str) -> Option<(&str, u16)> {
         let mut iter = str.split(':');
 
         match (iter.next(), iter.next(), iter.next()) {
@@ -242,8 +495,30 @@ impl Config {
                         }
                     }
 
-                    sentinel_config.username = query.remove("sentinel_username");
-                    sentinel_config.password = query.remove("sentinel_password");
+                    if let Some(prefer_last_good_sentinel) =
+                        query.remove("prefer_last_good_sentinel")
+                    {
+                        if let Ok(prefer_last_good_sentinel) =
+                            prefer_last_good_sentinel.parse::<bool>()
+                        {
+                            sentinel_config.prefer_last_good_sentinel = prefer_last_good_sentinel;
+                        }
+                    }
+
+                    if let Some(stale_master_guard) = query.remove("stale_master_guard") {
+                        if let Ok(stale_master_guard) = stale_master_guard.parse::<bool>() {
+                            sentinel_config.stale_master_guard = stale_master_guard;
+                        }
+                    }
+
+                    sentinel_config.username = query
+                        .remove("sentinel_username")
+                        .map(|u| Self::percent_decode(&u));
+                    sentinel_config.password = query
+                        .remove("sentinel_password")
+                        .map(|p| Self::percent_decode(&p));
+                    sentinel_config.password_file =
+                        query.remove("sentinel_password_file").map(PathBuf::from);
                 }
 
                 ServerConfig::Sentinel(sentinel_config)
@@ -270,8 +545,8 @@ impl Config {
 
         let mut config = Config {
             server,
-            username: username.map(|u| u.to_owned()),
-            password: password.map(|p| p.to_owned()),
+            username: username.map(Self::percent_decode),
+            password: password.map(Self::percent_decode),
             database,
             #[cfg(feature = "tls")]
             tls_config,
@@ -281,13 +556,31 @@ impl Config {
         if let Some(ref mut query) = query {
             if let Some(millis) = query.remove("connect_timeout") {
                 if let Ok(millis) = millis.parse::<u64>() {
-                    config.connect_timeout = Duration::from_millis(millis);
+                    config.resilience.connect_timeout = Duration::from_millis(millis);
                 }
             }
 
             if let Some(millis) = query.remove("command_timeout") {
                 if let Ok(millis) = millis.parse::<u64>() {
-                    config.command_timeout = Duration::from_millis(millis);
+                    config.resilience.command_timeout = Duration::from_millis(millis);
+                }
+            }
+
+            if let Some(retries) = query.remove("retries") {
+                if let Ok(retries) = retries.parse::<u32>() {
+                    config.resilience.retries = retries;
+                }
+            }
+
+            if let Some(threshold) = query.remove("node_failure_threshold") {
+                if let Ok(threshold) = threshold.parse::<usize>() {
+                    config.resilience.node_failure_threshold = threshold;
+                }
+            }
+
+            if let Some(millis) = query.remove("node_cooldown") {
+                if let Ok(millis) = millis.parse::<u64>() {
+                    config.resilience.node_cooldown = Duration::from_millis(millis);
                 }
             }
 
@@ -307,6 +600,21 @@ impl Config {
                 config.connection_name = connection_name;
             }
 
+            if let Some(connection_metadata) = query.remove("connection_metadata") {
+                config.connection_metadata = Self::parse_connection_metadata(&connection_metadata)?;
+            }
+
+            if let Some(lib_name) = query.remove("lib_name") {
+                if !Self::is_client_info(&lib_name) {
+                    return None;
+                }
+                config.lib_name = lib_name;
+            }
+
+            if let Some(password_file) = query.remove("password_file") {
+                config.password_file = Some(PathBuf::from(password_file));
+            }
+
             if let Some(keep_alive) = query.remove("keep_alive") {
                 if let Ok(keep_alive) = keep_alive.parse::<u64>() {
                     config.keep_alive = Some(Duration::from_millis(keep_alive));
@@ -319,9 +627,135 @@ impl Config {
                 }
             }
 
+            if let Some(send_buffer_size) = query.remove("send_buffer_size") {
+                if let Ok(send_buffer_size) = send_buffer_size.parse::<usize>() {
+                    config.send_buffer_size = Some(send_buffer_size);
+                }
+            }
+
+            if let Some(recv_buffer_size) = query.remove("recv_buffer_size") {
+                if let Ok(recv_buffer_size) = recv_buffer_size.parse::<usize>() {
+                    config.recv_buffer_size = Some(recv_buffer_size);
+                }
+            }
+
+            if let Some(tcp_user_timeout) = query.remove("tcp_user_timeout") {
+                if let Ok(tcp_user_timeout) = tcp_user_timeout.parse::<u64>() {
+                    config.tcp_user_timeout = Some(Duration::from_millis(tcp_user_timeout));
+                }
+            }
+
+            if let Some(tos) = query.remove("tos") {
+                if let Ok(tos) = tos.parse::<u32>() {
+                    config.tos = Some(tos);
+                }
+            }
+
+            if let Some(local_address) = query.remove("local_address") {
+                if let Ok(local_address) = local_address.parse::<IpAddr>() {
+                    config.local_address = Some(local_address);
+                }
+            }
+
+            if let Some(bind_interface) = query.remove("bind_interface") {
+                config.bind_interface = Some(bind_interface);
+            }
+
             if let Some(retry_on_error) = query.remove("retry_on_error") {
                 if let Ok(retry_on_error) = retry_on_error.parse::<bool>() {
-                    config.retry_on_error = retry_on_error;
+                    config.resilience.retry_on = RetryOn::from_retry_on_error(retry_on_error);
+                }
+            }
+
+            if let Some(max_in_flight_per_client) = query.remove("max_in_flight_per_client") {
+                if let Ok(max_in_flight_per_client) = max_in_flight_per_client.parse::<usize>() {
+                    config.max_in_flight_per_client = max_in_flight_per_client;
+                }
+            }
+
+            if let Some(max_pending_commands) = query.remove("max_pending_commands") {
+                if let Ok(max_pending_commands) = max_pending_commands.parse::<usize>() {
+                    config.max_pending_commands = max_pending_commands;
+                }
+            }
+
+            if let Some(fail_on_queue_full) = query.remove("fail_on_queue_full") {
+                if let Ok(fail_on_queue_full) = fail_on_queue_full.parse::<bool>() {
+                    config.fail_on_queue_full = fail_on_queue_full;
+                }
+            }
+
+            if let Some(strict_server_version) = query.remove("strict_server_version") {
+                if let Ok(strict_server_version) = strict_server_version.parse::<bool>() {
+                    config.strict_server_version = strict_server_version;
+                }
+            }
+
+            if let Some(warn_deprecated_commands) = query.remove("warn_deprecated_commands") {
+                if let Ok(warn_deprecated_commands) = warn_deprecated_commands.parse::<bool>() {
+                    config.warn_deprecated_commands = warn_deprecated_commands;
+                }
+            }
+
+            if let Some(legacy_command_shims) = query.remove("legacy_command_shims") {
+                if let Ok(legacy_command_shims) = legacy_command_shims.parse::<bool>() {
+                    config.legacy_command_shims = legacy_command_shims;
+                }
+            }
+
+            if let Some(max_batch_commands) = query.remove("max_batch_commands") {
+                if let Ok(max_batch_commands) = max_batch_commands.parse::<usize>() {
+                    config.max_batch_commands = max_batch_commands;
+                }
+            }
+
+            if let Some(max_batch_bytes) = query.remove("max_batch_bytes") {
+                if let Ok(max_batch_bytes) = max_batch_bytes.parse::<usize>() {
+                    config.max_batch_bytes = max_batch_bytes;
+                }
+            }
+
+            if let Some(coalesce_reads) = query.remove("coalesce_reads") {
+                if let Ok(coalesce_reads) = coalesce_reads.parse::<bool>() {
+                    config.coalesce_reads = coalesce_reads;
+                }
+            }
+
+            if let Some(command_journal_size) = query.remove("command_journal_size") {
+                if let Ok(command_journal_size) = command_journal_size.parse::<usize>() {
+                    config.command_journal_size = command_journal_size;
+                }
+            }
+
+            if let Some(error_context) = query.remove("error_context") {
+                if let Ok(error_context) = error_context.parse::<bool>() {
+                    config.error_context = error_context;
+                }
+            }
+
+            if let Some(idle_connection_timeout) = query.remove("idle_connection_timeout") {
+                if let Ok(idle_connection_timeout) = idle_connection_timeout.parse::<u64>() {
+                    config.idle_connection_timeout =
+                        Some(Duration::from_millis(idle_connection_timeout));
+                }
+            }
+
+            if let Some(pub_sub_keepalive) = query.remove("pub_sub_keepalive") {
+                if let Ok(pub_sub_keepalive) = pub_sub_keepalive.parse::<u64>() {
+                    config.pub_sub_keepalive = Some(Duration::from_millis(pub_sub_keepalive));
+                }
+            }
+
+            if let Some(auto_detect_cluster) = query.remove("auto_detect_cluster") {
+                if let Ok(auto_detect_cluster) = auto_detect_cluster.parse::<bool>() {
+                    config.auto_detect_cluster = auto_detect_cluster;
+                }
+            }
+
+            #[cfg(feature = "otel-metrics")]
+            if let Some(otel_metrics) = query.remove("otel_metrics") {
+                if let Ok(otel_metrics) = otel_metrics.parse::<bool>() {
+                    config.otel_metrics = otel_metrics;
                 }
             }
         }
@@ -426,10 +860,265 @@ impl Config {
 
         (lout, rout)
     }
+
+    /// Decodes the `%XX` sequences of a credential of an URI,
+    /// a `%` not followed by two hexadecimal digits being kept as is
+    fn percent_decode(s: &str) -> String {
+        fn hex_value(digit: u8) -> u8 {
+            (digit as char).to_digit(16).unwrap_or_default() as u8
+        }
+
+        let bytes = s.as_bytes();
+        let mut decoded = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes.get(i..i + 3) {
+                Some([b'%', h, l]) if h.is_ascii_hexdigit() && l.is_ascii_hexdigit() => {
+                    decoded.push(hex_value(*h) << 4 | hex_value(*l));
+                    i += 3;
+                }
+                _ => {
+                    decoded.push(bytes[i]);
+                    i += 1;
+                }
+            }
+        }
+        String::from_utf8_lossy(&decoded).into_owned()
+    }
+
+    /// Writes a credential in an URI, percent-encoding all but its unreserved characters
+    fn write_percent_encoded<W: Write>(f: &mut W, s: &str) -> fmt::Result {
+        for b in s.bytes() {
+            if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+                f.write_char(b as char)?;
+            } else {
+                f.write_fmt(format_args!("%{b:02X}"))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Config {
+    /// Add a middleware [layer](CommandLayer) after the already registered ones
+    pub fn layer(&mut self, layer: impl CommandLayer + 'static) -> &mut Self {
+        self.layers.push(layer);
+        self
+    }
+
+    /// Register [default options](DefaultOptions) of a family of commands,
+    /// appended by a built-in [layer](CommandLayer) unless the options are explicitly given
+    pub fn default_options(&mut self, default_options: DefaultOptions) -> &mut Self {
+        self.layer(default_options)
+    }
+
+    /// (Cluster only) Register the positions of the keys of a command,
+    /// to route it to the node serving its keys
+    ///
+    /// Commands of in-house modules often come without key specifications,
+    /// or with key specifications the client cannot interpret:
+    /// such commands are sent to a random node of the cluster.
+    /// The registered positions take precedence over the information
+    /// returned by the [`COMMAND`](https://redis.io/commands/command/) command.
+    ///
+    /// Positions follow the conventions of `COMMAND`:
+    /// * `first_key`: position of the first key, `1` being the first argument after the command name,
+    ///   `0` for a command without keys
+    /// * `last_key`: position of the last key, negative values counting from the last argument (`-1`)
+    /// * `step`: increment between the positions of two consecutive keys
+    ///
+    /// For a command with keys, `step` must be positive, and `last_key` must not be `0`
+    /// nor a position before `first_key`, otherwise
+    /// the configuration is rejected with an [`Error::Config`](crate::Error::Config)
+    /// when the command table of the client is loaded.
+    ///
+    /// # Example
+    /// ```
+    /// use rustis::client::{Config, IntoConfig};
+    ///
+    /// # fn main() -> rustis::Result<()> {
+    /// let mut config = "redis+cluster://127.0.0.1:7000".into_config()?;
+    /// // MYMOD.DO key1 key2 option
+    /// config.register_command_keys("MYMOD.DO", 1, 2, 1);
+    /// // MYMOD.MSET key1 value1 key2 value2 ...
+    /// config.register_command_keys("MYMOD.MSET", 1, -1, 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn register_command_keys(
+        &mut self,
+        command_name: impl AsRef<str>,
+        first_key: usize,
+        last_key: isize,
+        step: usize,
+    ) -> &mut Self {
+        self.command_keys.insert(
+            command_name.as_ref().to_uppercase(),
+            CommandKeys {
+                first_key,
+                last_key,
+                step,
+            },
+        );
+        self
+    }
+
+    /// Parse connection metadata in the format `key1:value1,key2:value2`
+    pub(crate) fn parse_connection_metadata(metadata: &str) -> Option<Vec<(String, String)>> {
+        metadata
+            .split(',')
+            .map(|tag| {
+                let (key, value) = tag.split_once(':')?;
+                (Self::is_client_info(key) && Self::is_client_info(value))
+                    .then(|| (key.to_owned(), value.to_owned()))
+            })
+            .collect()
+    }
+
+    /// `true` if `info` can be rendered in a connection name or a library name:
+    /// not empty, printable ASCII characters only, without separators
+    pub(crate) fn is_client_info(info: &str) -> bool {
+        !info.is_empty()
+            && info
+                .chars()
+                .all(|c| c.is_ascii_graphic() && !matches!(c, '=' | ',' | ':' | '(' | ')'))
+    }
+
+    /// [`connection_metadata`](Config::connection_metadata) rendered as `(key1=value1,key2=value2)`,
+    /// empty without metadata
+    fn rendered_connection_metadata(&self) -> String {
+        if self.connection_metadata.is_empty() {
+            return String::new();
+        }
+
+        let tags = self
+            .connection_metadata
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect::<Vec<_>>()
+            .join(",");
+        format!("({tags})")
+    }
+
+    /// Name of the connections, with their metadata
+    pub(crate) fn client_name(&self) -> String {
+        format!(
+            "{}{}",
+            self.connection_name,
+            self.rendered_connection_metadata()
+        )
+    }
+
+    /// Library name of the connections, with their metadata
+    pub(crate) fn client_lib_name(&self) -> String {
+        format!("{}{}", self.lib_name, self.rendered_connection_metadata())
+    }
+
+    /// Password for authentication, read from [`password_file`](Config::password_file) if set
+    pub(crate) fn resolve_password(&self) -> Result<Option<String>> {
+        match &self.password_file {
+            Some(password_file) => read_secret_file(password_file).map(Some),
+            None => Ok(self.password.clone()),
+        }
+    }
+}
+
+/// Positions of the keys of a command, see [`Config::register_command_keys`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandKeys {
+    /// Position of the first key, `0` for a command without keys
+    pub first_key: usize,
+    /// Position of the last key, negative values counting from the last argument
+    pub last_key: isize,
+    /// Increment between the positions of two consecutive keys
+    pub step: usize,
+}
+
+impl CommandKeys {
+    /// Check that the positions of the keys of `command_name` can be used to extract its keys
+    pub(crate) fn validate(&self, command_name: &str) -> Result<()> {
+        if self.first_key == 0 {
+            return Ok(());
+        }
+
+        if self.step == 0 {
+            return Err(Error::Config(format!(
+                "Invalid key positions of {command_name}: step must be positive"
+            )));
+        }
+
+        if self.last_key == 0 || (self.last_key > 0 && (self.last_key as usize) < self.first_key) {
+            return Err(Error::Config(format!(
+                "Invalid key positions of {command_name}: last key {} before first key {}",
+                self.last_key, self.first_key
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Read a secret from a file, without its trailing line break
+pub(crate) fn read_secret_file(path: &Path) -> Result<String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| Error::Config(format!("Cannot read secret file '{}': {e}", path.display())))?;
+    Ok(content.trim_end_matches(['\r', '\n']).to_owned())
 }
 
 impl Display for Config {
+    /// Write the config as an URL, with credentials redacted
+    ///
+    /// Use [`to_url`](Config::to_url) to get the actual URL.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_url(f, true)
+    }
+}
+
+impl Config {
+    /// Build an URL from this config, including credentials,
+    /// which can be parsed back with [`into_config`](IntoConfig::into_config).
+    ///
+    /// The [`Display`] implementation produces the same URL with credentials redacted,
+    /// and should be preferred for logging.
+    pub fn to_url(&self) -> String {
+        let mut url = String::new();
+        // writing to a String cannot fail
+        let _ = self.write_url(&mut url, false);
+        url
+    }
+
+    /// [`resilience`](Config::resilience) policy overridden by the deprecated fields
+    /// set to a value other than their default
+    #[allow(deprecated)]
+    pub(crate) fn effective_resilience(&self) -> ResiliencePolicy {
+        let mut resilience = self.resilience.clone();
+        if self.connect_timeout != Duration::from_millis(DEFAULT_CONNECT_TIMEOUT) {
+            resilience.connect_timeout = self.connect_timeout;
+        }
+        if self.command_timeout != Duration::from_millis(DEFAULT_COMMAND_TIMEOUT) {
+            resilience.command_timeout = self.command_timeout;
+        }
+        if self.retry_on_error {
+            resilience.retry_on = RetryOn::from_retry_on_error(true);
+        }
+        if self.reconnection != ReconnectionConfig::default() {
+            resilience.set_reconnection(self.reconnection.clone());
+        }
+        resilience
+    }
+
+    /// Move the values of the deprecated fields into [`resilience`](Config::resilience)
+    #[allow(deprecated)]
+    pub(crate) fn fold_deprecated_fields(&mut self) {
+        self.resilience = self.effective_resilience();
+        self.connect_timeout = Duration::from_millis(DEFAULT_CONNECT_TIMEOUT);
+        self.command_timeout = Duration::from_millis(DEFAULT_COMMAND_TIMEOUT);
+        self.retry_on_error = false;
+        self.reconnection = Default::default();
+    }
+
+    fn write_url<W: Write>(&self, f: &mut W, redact: bool) -> fmt::Result {
+        let resilience = self.effective_resilience();
         #[cfg(feature = "tls")]
         if self.tls_config.is_some() {
             match &self.server {
@@ -453,12 +1142,19 @@ impl Display for Config {
         }
 
         if let Some(username) = &self.username {
-            f.write_str(username)?;
+            Self::write_percent_encoded(f, username)?;
         }
 
         if let Some(password) = &self.password {
             f.write_char(':')?;
-            f.write_str(password)?;
+            if redact {
+                f.write_str(REDACTED)?;
+            } else {
+                Self::write_percent_encoded(f, password)?;
+            }
+        }
+
+        if self.username.is_some() || self.password.is_some() {
             f.write_char('@')?;
         }
 
@@ -474,8 +1170,11 @@ impl Display for Config {
                 instances,
                 service_name,
                 wait_between_failures: _,
+                prefer_last_good_sentinel: _,
+                stale_master_guard: _,
                 password: _,
                 username: _,
+                password_file: _,
             }) => {
                 f.write_str(
                     &instances
@@ -487,7 +1186,7 @@ impl Display for Config {
                 f.write_char('/')?;
                 f.write_str(service_name)?;
             }
-            ServerConfig::Cluster(ClusterConfig { nodes }) => {
+            ServerConfig::Cluster(ClusterConfig { nodes, .. }) => {
                 f.write_str(
                     &nodes
                         .iter()
@@ -507,7 +1206,7 @@ impl Display for Config {
 
         let mut query_separator = false;
 
-        let connect_timeout = self.connect_timeout.as_millis() as u64;
+        let connect_timeout = resilience.connect_timeout.as_millis() as u64;
         if connect_timeout != DEFAULT_CONNECT_TIMEOUT {
             if !query_separator {
                 query_separator = true;
@@ -518,7 +1217,7 @@ impl Display for Config {
             f.write_fmt(format_args!("connect_timeout={connect_timeout}"))?;
         }
 
-        let command_timeout = self.command_timeout.as_millis() as u64;
+        let command_timeout = resilience.command_timeout.as_millis() as u64;
         if command_timeout != DEFAULT_COMMAND_TIMEOUT {
             if !query_separator {
                 query_separator = true;
@@ -529,6 +1228,16 @@ impl Display for Config {
             f.write_fmt(format_args!("command_timeout={command_timeout}"))?;
         }
 
+        if resilience.retries != DEFAULT_RETRIES {
+            if !query_separator {
+                query_separator = true;
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_fmt(format_args!("retries={}", resilience.retries))?;
+        }
+
         if self.auto_resubscribe != DEFAULT_AUTO_RESUBSCRTBE {
             if !query_separator {
                 query_separator = true;
@@ -559,6 +1268,42 @@ impl Display for Config {
             f.write_fmt(format_args!("connection_name={}", self.connection_name))?;
         }
 
+        if !self.connection_metadata.is_empty() {
+            if !query_separator {
+                query_separator = true;
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_str("connection_metadata=")?;
+            for (i, (key, value)) in self.connection_metadata.iter().enumerate() {
+                if i > 0 {
+                    f.write_char(',')?;
+                }
+                f.write_fmt(format_args!("{key}:{value}"))?;
+            }
+        }
+
+        if self.lib_name != DEFAULT_LIB_NAME {
+            if !query_separator {
+                query_separator = true;
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_fmt(format_args!("lib_name={}", self.lib_name))?;
+        }
+
+        if let Some(password_file) = &self.password_file {
+            if !query_separator {
+                query_separator = true;
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_fmt(format_args!("password_file={}", password_file.display()))?;
+        }
+
         if let Some(keep_alive) = self.keep_alive {
             if !query_separator {
                 query_separator = true;
@@ -579,22 +1324,275 @@ impl Display for Config {
             f.write_fmt(format_args!("no_delay={}", self.no_delay))?;
         }
 
-        if self.retry_on_error != DEFAULT_RETRY_ON_ERROR {
+        if let Some(send_buffer_size) = self.send_buffer_size {
             if !query_separator {
                 query_separator = true;
                 f.write_char('?')?;
             } else {
                 f.write_char('&')?;
             }
-            f.write_fmt(format_args!("retry_on_error={}", self.retry_on_error))?;
+            f.write_fmt(format_args!("send_buffer_size={send_buffer_size}"))?;
         }
 
-        if let ServerConfig::Sentinel(SentinelConfig {
-            instances: _,
-            service_name: _,
-            wait_between_failures: wait_beetween_failures,
-            password,
-            username,
+        if let Some(recv_buffer_size) = self.recv_buffer_size {
+            if !query_separator {
+                query_separator = true;
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_fmt(format_args!("recv_buffer_size={recv_buffer_size}"))?;
+        }
+
+        if let Some(tcp_user_timeout) = self.tcp_user_timeout {
+            if !query_separator {
+                query_separator = true;
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_fmt(format_args!(
+                "tcp_user_timeout={}",
+                tcp_user_timeout.as_millis()
+            ))?;
+        }
+
+        if let Some(tos) = self.tos {
+            if !query_separator {
+                query_separator = true;
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_fmt(format_args!("tos={tos}"))?;
+        }
+
+        if let Some(local_address) = self.local_address {
+            if !query_separator {
+                query_separator = true;
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_fmt(format_args!("local_address={local_address}"))?;
+        }
+
+        if let Some(bind_interface) = &self.bind_interface {
+            if !query_separator {
+                query_separator = true;
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_fmt(format_args!("bind_interface={bind_interface}"))?;
+        }
+
+        if resilience.retry_on != RetryOn::default() {
+            if !query_separator {
+                query_separator = true;
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_fmt(format_args!(
+                "retry_on_error={}",
+                resilience.retry_on.retry_on_error()
+            ))?;
+        }
+
+        if self.max_in_flight_per_client != DEFAULT_MAX_IN_FLIGHT_PER_CLIENT {
+            if !query_separator {
+                query_separator = true;
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_fmt(format_args!(
+                "max_in_flight_per_client={}",
+                self.max_in_flight_per_client
+            ))?;
+        }
+
+        if self.max_pending_commands != DEFAULT_MAX_PENDING_COMMANDS {
+            if !query_separator {
+                query_separator = true;
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_fmt(format_args!(
+                "max_pending_commands={}",
+                self.max_pending_commands
+            ))?;
+        }
+
+        if self.fail_on_queue_full != DEFAULT_FAIL_ON_QUEUE_FULL {
+            if !query_separator {
+                query_separator = true;
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_fmt(format_args!(
+                "fail_on_queue_full={}",
+                self.fail_on_queue_full
+            ))?;
+        }
+
+        if self.strict_server_version != DEFAULT_STRICT_SERVER_VERSION {
+            if !query_separator {
+                query_separator = true;
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_fmt(format_args!(
+                "strict_server_version={}",
+                self.strict_server_version
+            ))?;
+        }
+
+        if self.warn_deprecated_commands != DEFAULT_WARN_DEPRECATED_COMMANDS {
+            if !query_separator {
+                query_separator = true;
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_fmt(format_args!(
+                "warn_deprecated_commands={}",
+                self.warn_deprecated_commands
+            ))?;
+        }
+
+        if self.legacy_command_shims != DEFAULT_LEGACY_COMMAND_SHIMS {
+            if !query_separator {
+                query_separator = true;
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_fmt(format_args!(
+                "legacy_command_shims={}",
+                self.legacy_command_shims
+            ))?;
+        }
+
+        if self.max_batch_commands != DEFAULT_MAX_BATCH_COMMANDS {
+            if !query_separator {
+                query_separator = true;
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_fmt(format_args!(
+                "max_batch_commands={}",
+                self.max_batch_commands
+            ))?;
+        }
+
+        if self.max_batch_bytes != DEFAULT_MAX_BATCH_BYTES {
+            if !query_separator {
+                query_separator = true;
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_fmt(format_args!("max_batch_bytes={}", self.max_batch_bytes))?;
+        }
+
+        if self.coalesce_reads != DEFAULT_COALESCE_READS {
+            if !query_separator {
+                query_separator = true;
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_fmt(format_args!("coalesce_reads={}", self.coalesce_reads))?;
+        }
+
+        if self.command_journal_size != DEFAULT_COMMAND_JOURNAL_SIZE {
+            if !query_separator {
+                query_separator = true;
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_fmt(format_args!(
+                "command_journal_size={}",
+                self.command_journal_size
+            ))?;
+        }
+
+        if self.error_context != DEFAULT_ERROR_CONTEXT {
+            if !query_separator {
+                query_separator = true;
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_fmt(format_args!("error_context={}", self.error_context))?;
+        }
+
+        if let Some(idle_connection_timeout) = self.idle_connection_timeout {
+            if !query_separator {
+                query_separator = true;
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_fmt(format_args!(
+                "idle_connection_timeout={}",
+                idle_connection_timeout.as_millis()
+            ))?;
+        }
+
+        if let Some(pub_sub_keepalive) = self.pub_sub_keepalive {
+            if !query_separator {
+                query_separator = true;
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_fmt(format_args!(
+                "pub_sub_keepalive={}",
+                pub_sub_keepalive.as_millis()
+            ))?;
+        }
+
+        if self.auto_detect_cluster != DEFAULT_AUTO_DETECT_CLUSTER {
+            if !query_separator {
+                query_separator = true;
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_fmt(format_args!(
+                "auto_detect_cluster={}",
+                self.auto_detect_cluster
+            ))?;
+        }
+
+        #[cfg(feature = "otel-metrics")]
+        if self.otel_metrics != DEFAULT_OTEL_METRICS {
+            if !query_separator {
+                query_separator = true;
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_fmt(format_args!("otel_metrics={}", self.otel_metrics))?;
+        }
+
+        if let ServerConfig::Sentinel(SentinelConfig {
+            instances: _,
+            service_name: _,
+            wait_between_failures: wait_beetween_failures,
+            prefer_last_good_sentinel,
+            stale_master_guard,
+            password,
+            username,
+            password_file,
         }) = &self.server
         {
             let wait_between_failures = wait_beetween_failures.as_millis() as u64;
@@ -609,6 +1607,26 @@ impl Display for Config {
                     "wait_between_failures={wait_between_failures}"
                 ))?;
             }
+            if *prefer_last_good_sentinel != DEFAULT_PREFER_LAST_GOOD_SENTINEL {
+                if !query_separator {
+                    query_separator = true;
+                    f.write_char('?')?;
+                } else {
+                    f.write_char('&')?;
+                }
+                f.write_fmt(format_args!(
+                    "prefer_last_good_sentinel={prefer_last_good_sentinel}"
+                ))?;
+            }
+            if *stale_master_guard != DEFAULT_STALE_MASTER_GUARD {
+                if !query_separator {
+                    query_separator = true;
+                    f.write_char('?')?;
+                } else {
+                    f.write_char('&')?;
+                }
+                f.write_fmt(format_args!("stale_master_guard={stale_master_guard}"))?;
+            }
             if let Some(username) = username {
                 if !query_separator {
                     query_separator = true;
@@ -617,19 +1635,58 @@ impl Display for Config {
                     f.write_char('&')?;
                 }
                 f.write_str("sentinel_username=")?;
-                f.write_str(username)?;
+                Self::write_percent_encoded(f, username)?;
             }
             if let Some(password) = password {
                 if !query_separator {
+                    query_separator = true;
                     f.write_char('?')?;
                 } else {
                     f.write_char('&')?;
                 }
                 f.write_str("sentinel_password=")?;
-                f.write_str(password)?;
+                if redact {
+                    f.write_str(REDACTED)?;
+                } else {
+                    Self::write_percent_encoded(f, password)?;
+                }
+            }
+            if let Some(password_file) = password_file {
+                if !query_separator {
+                    f.write_char('?')?;
+                } else {
+                    f.write_char('&')?;
+                }
+                f.write_fmt(format_args!(
+                    "sentinel_password_file={}",
+                    password_file.display()
+                ))?;
             }
         }
 
+        if resilience.node_failure_threshold != DEFAULT_NODE_FAILURE_THRESHOLD {
+            if !query_separator {
+                query_separator = true;
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_fmt(format_args!(
+                "node_failure_threshold={}",
+                resilience.node_failure_threshold
+            ))?;
+        }
+
+        let node_cooldown = resilience.node_cooldown.as_millis() as u64;
+        if node_cooldown != DEFAULT_NODE_COOLDOWN {
+            if !query_separator {
+                f.write_char('?')?;
+            } else {
+                f.write_char('&')?;
+            }
+            f.write_fmt(format_args!("node_cooldown={node_cooldown}"))?;
+        }
+
         Ok(())
     }
 }
@@ -663,6 +1720,9 @@ impl Default for ServerConfig {
 #[derive(Debug, Clone)]
 pub struct SentinelConfig {
     /// An array of `(host, port)` tuples for each known sentinel instance.
+    ///
+    /// Instances are tried in this order, except the ones which have failed
+    /// in a row, which are tried last, by number of consecutive failures.
     pub instances: Vec<(String, u16)>,
 
     /// The service name
@@ -671,11 +1731,29 @@ pub struct SentinelConfig {
     /// Waiting time after failing before connecting to the next Sentinel instance (default 250ms).
     pub wait_between_failures: Duration,
 
+    /// Try first the last Sentinel instance which has given the master address,
+    /// to speed up reconnections (default `false`).
+    pub prefer_last_good_sentinel: bool,
+
+    /// Handle a `READONLY` error received from the master as a disconnection (default `false`).
+    ///
+    /// Such an error means that the master has been demoted to replica by a failover.
+    /// The master is then discovered again through the Sentinel instances, and its role is
+    /// checked before any other command is sent, so that no write reaches a stale master.
+    /// The commands sent in the meantime are retried on the new master,
+    /// as after any disconnection (see [`retry_on`](ResiliencePolicy::retry_on)).
+    pub stale_master_guard: bool,
+
     /// Sentinel username
     pub username: Option<String>,
 
     /// Sentinel password
     pub password: Option<String>,
+
+    /// File containing the sentinel password, taking precedence over [`password`](SentinelConfig::password)
+    ///
+    /// The file is read at each connection attempt.
+    pub password_file: Option<PathBuf>,
 }
 
 impl Default for SentinelConfig {
@@ -684,8 +1762,11 @@ impl Default for SentinelConfig {
             instances: Default::default(),
             service_name: Default::default(),
             wait_between_failures: Duration::from_millis(DEFAULT_WAIT_BETWEEN_FAILURES),
+            prefer_last_good_sentinel: DEFAULT_PREFER_LAST_GOOD_SENTINEL,
+            stale_master_guard: DEFAULT_STALE_MASTER_GUARD,
             password: None,
             username: None,
+            password_file: None,
         }
     }
 }
@@ -825,7 +1906,8 @@ pub trait IntoConfig {
 }
 
 impl IntoConfig for Config {
-    fn into_config(self) -> Result<Config> {
+    fn into_config(mut self) -> Result<Config> {
+        self.fold_deprecated_fields();
         Ok(self)
     }
 }
@@ -862,7 +1944,11 @@ impl IntoConfig for Url {
 
 /// The type of reconnection policy to use. This will apply to every connection used by the client.
 /// This code has been mostly inpisred by [fred ReconnectPolicy](https://docs.rs/fred/latest/fred/types/enum.ReconnectPolicy.html)
-#[derive(Debug, Clone)]
+///
+/// Superseded by [`ResiliencePolicy::retries`] & [`ResiliencePolicy::backoff`],
+/// only used by the deprecated [`ConfigBuilder::reconnection`](crate::client::ConfigBuilder::reconnection)
+/// & [`Config::reconnection`].
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ReconnectionConfig {
     /// Wait a constant amount of time between reconnection attempts, in ms.
     Constant {
@@ -968,3 +2054,235 @@ impl ReconnectionConfig {
         }
     }
 }
+
+/// Timeouts, retries & backoff policy of a [`Client`](crate::client::Client),
+/// shared by standalone, sentinel & cluster connections
+///
+/// The command related knobs ([`command_timeout`](ResiliencePolicy::command_timeout)
+/// & [`retry_on`](ResiliencePolicy::retry_on)) can be overridden for each command with
+/// [`PreparedCommand::command_timeout`](crate::client::PreparedCommand::command_timeout)
+/// & [`PreparedCommand::retry_on`](crate::client::PreparedCommand::retry_on).
+///
+/// # Example
+/// ```
+/// use rustis::{
+///     client::{Backoff, Config, ResiliencePolicy, RetryOn},
+///     Result,
+/// };
+/// use std::time::Duration;
+///
+/// fn main() -> Result<()> {
+///     let config = Config::builder()
+///         .standalone("127.0.0.1", 6379)
+///         .resilience(ResiliencePolicy {
+///             command_timeout: Duration::from_secs(1),
+///             retries: 10,
+///             backoff: Backoff::exponential(100, 5_000, 2),
+///             retry_on: RetryOn::NetworkError,
+///             ..Default::default()
+///         })
+///         .build()?;
+///
+///     assert_eq!(
+///         "redis://127.0.0.1?command_timeout=1000&retries=10&retry_on_error=true",
+///         config.to_url()
+///     );
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResiliencePolicy {
+    /// The time to attempt a connection before timing out (default `10,000` ms)
+    pub connect_timeout: Duration,
+    /// If a command does not return a reply within this duration,
+    /// a [`Timeout`](crate::Error::Timeout) error is returned (default `0`, no timeout)
+    pub command_timeout: Duration,
+    /// Maximum number of consecutive reconnection attempts, `0` to retry forever (default `0`)
+    ///
+    /// Once reached, the pending commands fail and the client is disconnected for good.
+    pub retries: u32,
+    /// Delay between two reconnection attempts (default constant `1,000` ms)
+    pub backoff: Backoff,
+    /// Failures after which a command is sent again, once reconnected (default [`RetryOn::Never`])
+    pub retry_on: RetryOn,
+    /// (Cluster only) Number of consecutive failures (connection failures or closed connections)
+    /// after which a node is marked as unhealthy (default `0`, disabled)
+    ///
+    /// An unhealthy node is not routed to during [`node_cooldown`](ResiliencePolicy::node_cooldown):
+    /// the other shards keep being served, the commands targeting the shard of the node fail fast,
+    /// and the cluster topology is refreshed to route to a promoted replica as soon as possible.
+    /// A master node which cannot be connected or whose connection is closed only makes
+    /// its own shard unavailable, instead of failing or reconnecting the whole cluster connection.
+    ///
+    /// See [`ConnectionEvent`](crate::client::ConnectionEvent)
+    pub node_failure_threshold: usize,
+    /// (Cluster only) Cool-down period of an unhealthy node (default 30 seconds)
+    pub node_cooldown: Duration,
+}
+
+impl Default for ResiliencePolicy {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_millis(DEFAULT_CONNECT_TIMEOUT),
+            command_timeout: Duration::from_millis(DEFAULT_COMMAND_TIMEOUT),
+            retries: DEFAULT_RETRIES,
+            backoff: Default::default(),
+            retry_on: Default::default(),
+            node_failure_threshold: DEFAULT_NODE_FAILURE_THRESHOLD,
+            node_cooldown: Duration::from_millis(DEFAULT_NODE_COOLDOWN),
+        }
+    }
+}
+
+impl ResiliencePolicy {
+    /// Set [`retries`](ResiliencePolicy::retries) & [`backoff`](ResiliencePolicy::backoff)
+    /// from a legacy reconnection config
+    pub(crate) fn set_reconnection(&mut self, reconnection: ReconnectionConfig) {
+        (self.retries, self.backoff) = match reconnection {
+            ReconnectionConfig::Constant {
+                max_attempts,
+                delay,
+                jitter,
+            } => (max_attempts, Backoff::Constant { delay, jitter }),
+            ReconnectionConfig::Linear {
+                max_attempts,
+                max_delay,
+                delay,
+                jitter,
+            } => (
+                max_attempts,
+                Backoff::Linear {
+                    max_delay,
+                    delay,
+                    jitter,
+                },
+            ),
+            ReconnectionConfig::Exponential {
+                max_attempts,
+                min_delay,
+                max_delay,
+                multiplicative_factor,
+                jitter,
+            } => (
+                max_attempts,
+                Backoff::Exponential {
+                    min_delay,
+                    max_delay,
+                    multiplicative_factor,
+                    jitter,
+                },
+            ),
+        };
+    }
+}
+
+/// Delay between two reconnection attempts of a [`ResiliencePolicy`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backoff {
+    /// Wait a constant amount of time between reconnection attempts, in ms.
+    Constant {
+        /// Delay in ms to wait between reconnection attempts
+        delay: u32,
+        /// Add jitter in ms to each delay
+        jitter: u32,
+    },
+    /// Backoff reconnection attempts linearly, adding `delay` each time.
+    Linear {
+        /// Maximum delay in ms
+        max_delay: u32,
+        /// Delay in ms to add to the total waiting time at each attemp
+        delay: u32,
+        /// Add jitter in ms to each delay
+        jitter: u32,
+    },
+    /// Backoff reconnection attempts exponentially, multiplying the last delay by `multiplicative_factor` each time.
+    ///
+    /// see <https://en.wikipedia.org/wiki/Exponential_backoff>
+    Exponential {
+        /// Minimum delay in ms
+        min_delay: u32,
+        /// Maximum delay in ms
+        max_delay: u32,
+        /// Multiplicative factor
+        multiplicative_factor: u32,
+        /// Add jitter in ms to each delay
+        jitter: u32,
+    },
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::constant(DEFAULT_DELAY_MS)
+    }
+}
+
+impl Backoff {
+    /// Create a new constant backoff.
+    #[must_use]
+    pub fn constant(delay: u32) -> Self {
+        Self::Constant {
+            delay,
+            jitter: DEFAULT_JITTER_MS,
+        }
+    }
+
+    /// Create a new linear backoff.
+    #[must_use]
+    pub fn linear(max_delay: u32, delay: u32) -> Self {
+        Self::Linear {
+            max_delay,
+            delay,
+            jitter: DEFAULT_JITTER_MS,
+        }
+    }
+
+    /// Create a new exponential backoff.
+    #[must_use]
+    pub fn exponential(min_delay: u32, max_delay: u32, multiplicative_factor: u32) -> Self {
+        Self::Exponential {
+            min_delay,
+            max_delay,
+            multiplicative_factor,
+            jitter: DEFAULT_JITTER_MS,
+        }
+    }
+
+    /// Set the amount of jitter to add to each reconnection delay.
+    ///
+    /// Default: 100 ms
+    #[must_use]
+    pub fn jitter(mut self, jitter_ms: u32) -> Self {
+        match &mut self {
+            Self::Constant { jitter, .. }
+            | Self::Linear { jitter, .. }
+            | Self::Exponential { jitter, .. } => *jitter = jitter_ms,
+        }
+        self
+    }
+}
+
+/// Failures after which a command is sent again, once reconnected
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RetryOn {
+    /// Never send a command again, it fails with the error
+    #[default]
+    Never,
+    /// Send again the commands interrupted by a network error
+    /// (sent but not replied, or not yet sent) once reconnected
+    NetworkError,
+}
+
+impl RetryOn {
+    pub(crate) fn from_retry_on_error(retry_on_error: bool) -> Self {
+        if retry_on_error {
+            Self::NetworkError
+        } else {
+            Self::Never
+        }
+    }
+
+    pub(crate) fn retry_on_error(self) -> bool {
+        self == Self::NetworkError
+    }
+}
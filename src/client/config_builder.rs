@@ -0,0 +1,415 @@
+#[cfg(feature = "tls")]
+use crate::client::TlsConfig;
+use crate::{
+    client::{
+        AsyncTransport, ClusterConfig, CommandInfoCache, CommandLayer, Config, DefaultOptions,
+        ReconnectionConfig, ResiliencePolicy, RetryOn, SentinelConfig, ServerConfig,
+    },
+    Error, Result,
+};
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+/// Builder of a [`Config`](Config) with validation
+///
+/// # Example
+/// ```
+/// use rustis::{client::Config, Result};
+///
+/// fn main() -> Result<()> {
+///     let config = Config::builder()
+///         .sentinel([("127.0.0.1", 26379), ("127.0.0.1", 26380)], "myservice")
+///         .password("pwd")
+///         .database(1)
+///         .build()?;
+///
+///     assert_eq!(
+///         "redis+sentinel://:pwd@127.0.0.1:26379,127.0.0.1:26380/myservice/1",
+///         config.to_url()
+///     );
+///     // credentials are redacted for safe logging
+///     assert_eq!(
+///         "redis+sentinel://:***@127.0.0.1:26379,127.0.0.1:26380/myservice/1",
+///         config.to_string()
+///     );
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct ConfigBuilder {
+    config: Config,
+}
+
+impl Config {
+    /// Create a new [`ConfigBuilder`](ConfigBuilder) with default values
+    #[must_use]
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::default()
+    }
+}
+
+impl ConfigBuilder {
+    /// Validate an existing config with [`build`](ConfigBuilder::build)
+    pub(crate) fn with_config(config: Config) -> Self {
+        Self { config }
+    }
+
+    /// Connect to a standalone server
+    #[must_use]
+    pub fn standalone(mut self, host: impl Into<String>, port: u16) -> Self {
+        self.config.server = ServerConfig::Standalone {
+            host: host.into(),
+            port,
+        };
+        self
+    }
+
+    /// Connect to the master of `service_name`, through the given sentinel instances
+    #[must_use]
+    pub fn sentinel<H: Into<String>>(
+        mut self,
+        instances: impl IntoIterator<Item = (H, u16)>,
+        service_name: impl Into<String>,
+    ) -> Self {
+        self.config.server = ServerConfig::Sentinel(SentinelConfig {
+            instances: to_nodes(instances),
+            service_name: service_name.into(),
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Connect to a cluster, through the given nodes
+    #[must_use]
+    pub fn cluster<H: Into<String>>(mut self, nodes: impl IntoIterator<Item = (H, u16)>) -> Self {
+        self.config.server = ServerConfig::Cluster(ClusterConfig {
+            nodes: to_nodes(nodes),
+        });
+        self
+    }
+
+    /// ACL username for authentication
+    #[must_use]
+    pub fn username(mut self, username: impl Into<String>) -> Self {
+        self.config.username = Some(username.into());
+        self
+    }
+
+    /// Password for authentication
+    #[must_use]
+    pub fn password(mut self, password: impl Into<String>) -> Self {
+        self.config.password = Some(password.into());
+        self
+    }
+
+    /// File containing the password for authentication, read at each connection attempt
+    ///
+    /// See [`Config::password_file`](Config::password_file)
+    #[must_use]
+    pub fn password_file(mut self, password_file: impl Into<PathBuf>) -> Self {
+        self.config.password_file = Some(password_file.into());
+        self
+    }
+
+    /// Username & password for authentication against sentinel instances
+    ///
+    /// Must be called after [`sentinel`](ConfigBuilder::sentinel)
+    #[must_use]
+    pub fn sentinel_credentials(
+        mut self,
+        username: Option<impl Into<String>>,
+        password: impl Into<String>,
+    ) -> Self {
+        if let ServerConfig::Sentinel(sentinel_config) = &mut self.config.server {
+            sentinel_config.username = username.map(Into::into);
+            sentinel_config.password = Some(password.into());
+        }
+        self
+    }
+
+    /// File containing the password for authentication against sentinel instances,
+    /// read at each connection attempt
+    ///
+    /// Must be called after [`sentinel`](ConfigBuilder::sentinel)
+    #[must_use]
+    pub fn sentinel_password_file(mut self, password_file: impl Into<PathBuf>) -> Self {
+        if let ServerConfig::Sentinel(sentinel_config) = &mut self.config.server {
+            sentinel_config.password_file = Some(password_file.into());
+        }
+        self
+    }
+
+    /// Try first the last sentinel instance which has given the master address
+    ///
+    /// Must be called after [`sentinel`](ConfigBuilder::sentinel)
+    #[must_use]
+    pub fn prefer_last_good_sentinel(mut self) -> Self {
+        if let ServerConfig::Sentinel(sentinel_config) = &mut self.config.server {
+            sentinel_config.prefer_last_good_sentinel = true;
+        }
+        self
+    }
+
+    /// Handle a `READONLY` error received from the master as a disconnection,
+    /// to discover the new master after a failover
+    ///
+    /// See [`SentinelConfig::stale_master_guard`](crate::client::SentinelConfig::stale_master_guard)
+    ///
+    /// Must be called after [`sentinel`](ConfigBuilder::sentinel)
+    #[must_use]
+    pub fn stale_master_guard(mut self) -> Self {
+        if let ServerConfig::Sentinel(sentinel_config) = &mut self.config.server {
+            sentinel_config.stale_master_guard = true;
+        }
+        self
+    }
+
+    /// Database to select after connection
+    #[must_use]
+    pub fn database(mut self, database: usize) -> Self {
+        self.config.database = database;
+        self
+    }
+
+    /// Enable TLS
+    #[cfg_attr(docsrs, doc(cfg(feature = "tls")))]
+    #[cfg(feature = "tls")]
+    #[must_use]
+    pub fn tls(mut self, tls_config: TlsConfig) -> Self {
+        self.config.tls_config = Some(tls_config);
+        self
+    }
+
+    /// See [`Config::resilience`](Config::resilience)
+    #[must_use]
+    pub fn resilience(mut self, resilience: ResiliencePolicy) -> Self {
+        self.config.resilience = resilience;
+        self
+    }
+
+    /// See [`ResiliencePolicy::connect_timeout`](ResiliencePolicy::connect_timeout)
+    #[deprecated(note = "use `resilience` instead")]
+    #[must_use]
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.config.resilience.connect_timeout = connect_timeout;
+        self
+    }
+
+    /// See [`ResiliencePolicy::command_timeout`](ResiliencePolicy::command_timeout)
+    #[deprecated(note = "use `resilience` instead")]
+    #[must_use]
+    pub fn command_timeout(mut self, command_timeout: Duration) -> Self {
+        self.config.resilience.command_timeout = command_timeout;
+        self
+    }
+
+    /// See [`Config::connection_name`](Config::connection_name)
+    #[must_use]
+    pub fn connection_name(mut self, connection_name: impl Into<String>) -> Self {
+        self.config.connection_name = connection_name.into();
+        self
+    }
+
+    /// See [`Config::connection_metadata`](Config::connection_metadata)
+    #[must_use]
+    pub fn connection_metadata<K: Into<String>, V: Into<String>>(
+        mut self,
+        connection_metadata: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        self.config.connection_metadata = connection_metadata
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.into()))
+            .collect();
+        self
+    }
+
+    /// See [`Config::lib_name`](Config::lib_name)
+    #[must_use]
+    pub fn lib_name(mut self, lib_name: impl Into<String>) -> Self {
+        self.config.lib_name = lib_name.into();
+        self
+    }
+
+    /// See [`ResiliencePolicy::retry_on`](ResiliencePolicy::retry_on):
+    /// `true` for [`RetryOn::NetworkError`], `false` for [`RetryOn::Never`]
+    #[deprecated(note = "use `resilience` instead")]
+    #[must_use]
+    pub fn retry_on_error(mut self, retry_on_error: bool) -> Self {
+        self.config.resilience.retry_on = RetryOn::from_retry_on_error(retry_on_error);
+        self
+    }
+
+    /// See [`Config::layer`](Config::layer)
+    #[must_use]
+    pub fn layer(mut self, layer: impl CommandLayer + 'static) -> Self {
+        self.config.layer(layer);
+        self
+    }
+
+    /// See [`Config::transport`](Config::transport)
+    #[must_use]
+    pub fn transport(mut self, transport: impl AsyncTransport + 'static) -> Self {
+        self.config.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// See [`Config::default_options`](Config::default_options)
+    #[must_use]
+    pub fn default_options(mut self, default_options: DefaultOptions) -> Self {
+        self.config.default_options(default_options);
+        self
+    }
+
+    /// See [`Config::coalesce_reads`](Config::coalesce_reads)
+    #[must_use]
+    pub fn coalesce_reads(mut self, coalesce_reads: bool) -> Self {
+        self.config.coalesce_reads = coalesce_reads;
+        self
+    }
+
+    /// See [`Config::error_context`](Config::error_context)
+    #[must_use]
+    pub fn error_context(mut self, error_context: bool) -> Self {
+        self.config.error_context = error_context;
+        self
+    }
+
+    /// See [`Config::idle_connection_timeout`](Config::idle_connection_timeout)
+    #[must_use]
+    pub fn idle_connection_timeout(mut self, idle_connection_timeout: Duration) -> Self {
+        self.config.idle_connection_timeout = Some(idle_connection_timeout);
+        self
+    }
+
+    /// See [`Config::command_info_cache`](Config::command_info_cache)
+    #[must_use]
+    pub fn command_info_cache(mut self, command_info_cache: CommandInfoCache) -> Self {
+        self.config.command_info_cache = Some(command_info_cache);
+        self
+    }
+
+    /// See [`Config::register_command_keys`](Config::register_command_keys)
+    #[must_use]
+    pub fn register_command_keys(
+        mut self,
+        command_name: impl AsRef<str>,
+        first_key: usize,
+        last_key: isize,
+        step: usize,
+    ) -> Self {
+        self.config
+            .register_command_keys(command_name, first_key, last_key, step);
+        self
+    }
+
+    /// See [`Config::pub_sub_keepalive`](Config::pub_sub_keepalive)
+    #[must_use]
+    pub fn pub_sub_keepalive(mut self, pub_sub_keepalive: Duration) -> Self {
+        self.config.pub_sub_keepalive = Some(pub_sub_keepalive);
+        self
+    }
+
+    /// See [`Config::auto_detect_cluster`](Config::auto_detect_cluster)
+    #[must_use]
+    pub fn auto_detect_cluster(mut self, auto_detect_cluster: bool) -> Self {
+        self.config.auto_detect_cluster = auto_detect_cluster;
+        self
+    }
+
+    /// See [`Config::otel_metrics`](Config::otel_metrics)
+    #[cfg_attr(docsrs, doc(cfg(feature = "otel-metrics")))]
+    #[cfg(feature = "otel-metrics")]
+    #[must_use]
+    pub fn otel_metrics(mut self, otel_metrics: bool) -> Self {
+        self.config.otel_metrics = otel_metrics;
+        self
+    }
+
+    /// See [`ResiliencePolicy::retries`](ResiliencePolicy::retries)
+    /// & [`ResiliencePolicy::backoff`](ResiliencePolicy::backoff)
+    #[deprecated(note = "use `resilience` instead")]
+    #[must_use]
+    pub fn reconnection(mut self, reconnection: ReconnectionConfig) -> Self {
+        self.config.resilience.set_reconnection(reconnection);
+        self
+    }
+
+    /// Validate and build the config
+    ///
+    /// # Errors
+    /// [`Error::Config`](crate::Error::Config) if the config is not consistent:
+    /// - a standalone config requires a host
+    /// - a sentinel config requires at least one instance and a service name
+    /// - a cluster config requires at least one node and forbids any database other than 0
+    /// - a password (or a password file) is required along with a username
+    pub fn build(self) -> Result<Config> {
+        let config = self.config;
+
+        match &config.server {
+            ServerConfig::Standalone { host, port: _ } => {
+                if host.is_empty() {
+                    return Err(Error::Config(
+                        "Standalone config requires a host".to_owned(),
+                    ));
+                }
+            }
+            ServerConfig::Sentinel(sentinel_config) => {
+                if sentinel_config.instances.is_empty() {
+                    return Err(Error::Config(
+                        "Sentinel config requires at least one instance".to_owned(),
+                    ));
+                }
+                if sentinel_config.service_name.is_empty() {
+                    return Err(Error::Config(
+                        "Sentinel config requires a service name".to_owned(),
+                    ));
+                }
+            }
+            ServerConfig::Cluster(cluster_config) => {
+                if cluster_config.nodes.is_empty() {
+                    return Err(Error::Config(
+                        "Cluster config requires at least one node".to_owned(),
+                    ));
+                }
+                if config.database != 0 {
+                    return Err(Error::Config(
+                        "Cluster config does not support database selection".to_owned(),
+                    ));
+                }
+            }
+        }
+
+        if config.username.is_some() && config.password.is_none() && config.password_file.is_none()
+        {
+            return Err(Error::Config("A username requires a password".to_owned()));
+        }
+
+        if !Config::is_client_info(&config.lib_name) {
+            return Err(Error::Config(format!(
+                "Invalid library name `{}`",
+                config.lib_name
+            )));
+        }
+
+        for (key, value) in &config.connection_metadata {
+            if !Config::is_client_info(key) || !Config::is_client_info(value) {
+                return Err(Error::Config(format!(
+                    "Invalid connection metadata `{key}:{value}`"
+                )));
+            }
+        }
+
+        for (command_name, command_keys) in &config.command_keys {
+            command_keys.validate(command_name)?;
+        }
+
+        Ok(config)
+    }
+}
+
+fn to_nodes<H: Into<String>>(nodes: impl IntoIterator<Item = (H, u16)>) -> Vec<(String, u16)> {
+    nodes
+        .into_iter()
+        .map(|(host, port)| (host.into(), port))
+        .collect()
+}
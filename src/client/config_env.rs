@@ -0,0 +1,382 @@
+#[cfg(feature = "tls")]
+use crate::client::TlsConfig;
+use crate::{
+    client::{
+        read_secret_file, ClusterConfig, Config, ConfigBuilder, SentinelConfig, ServerConfig,
+    },
+    Error, Result,
+};
+use std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+/// Environment variable holding an URL, read when the prefixed `URL` variable is not set
+const REDIS_URL: &str = "REDIS_URL";
+
+/// Supported environment variables, without prefix, with their description
+const ENV_VARS: &[(&str, &str)] = &[
+    (
+        "URL",
+        "Base URL of the config, in the format `redis[s]://[[username]:password@]host[:port]/[database]`, \
+         overridden by the other variables. Falls back to `REDIS_URL`",
+    ),
+    (
+        "HOSTS",
+        "Comma separated list of `host[:port]`: \
+         a standalone server, sentinel instances or cluster nodes",
+    ),
+    (
+        "CLUSTER",
+        "`true` to connect to a cluster, implied by multiple hosts without sentinel service",
+    ),
+    (
+        "SENTINEL_SERVICE",
+        "Service name monitored by the sentinel instances given in `HOSTS`",
+    ),
+    (
+        "SENTINEL_USERNAME",
+        "ACL username for authentication against sentinel instances",
+    ),
+    (
+        "SENTINEL_PASSWORD",
+        "Password for authentication against sentinel instances",
+    ),
+    (
+        "SENTINEL_PASSWORD_FILE",
+        "File containing the password for authentication against sentinel instances, \
+         read at each connection attempt",
+    ),
+    ("USERNAME", "ACL username for authentication"),
+    (
+        "USERNAME_FILE",
+        "File containing the ACL username for authentication",
+    ),
+    ("PASSWORD", "Password for authentication"),
+    (
+        "PASSWORD_FILE",
+        "File containing the password for authentication, read at each connection attempt",
+    ),
+    ("DATABASE", "Database to select after connection"),
+    ("TLS", "`true` to enable TLS (requires the `tls` feature)"),
+    (
+        "TLS_CA_FILE",
+        "PEM file of an additional root certificate (requires the `tls` feature)",
+    ),
+    (
+        "TLS_INSECURE",
+        "`true` to accept invalid certificates and hostnames (requires the `tls` feature)",
+    ),
+    ("CONNECT_TIMEOUT", "Connection timeout in milliseconds"),
+    (
+        "COMMAND_TIMEOUT",
+        "Command timeout in milliseconds, `0` for no timeout",
+    ),
+    (
+        "CONNECTION_NAME",
+        "Connection name, set with `CLIENT SETNAME`",
+    ),
+    (
+        "CONNECTION_METADATA",
+        "Comma separated list of `key:value` tags of the connections, \
+         rendered into the connection name and the library name",
+    ),
+    ("LIB_NAME", "Library name, set with `CLIENT SETINFO`"),
+];
+
+impl Config {
+    /// Build a config from environment variables, as favored by [twelve-factor apps](https://12factor.net/config)
+    ///
+    /// `{prefix}_URL` (or `REDIS_URL` when not set) provides the base config,
+    /// overridden by the granular `{prefix}_*` variables, such as `{prefix}_HOSTS` or `{prefix}_PASSWORD_FILE`.
+    ///
+    /// See [`to_env_docs`](Config::to_env_docs) for the list of supported variables.
+    ///
+    /// # Errors
+    /// [`Error::Config`](crate::Error::Config) naming the offending variable
+    /// if a variable cannot be parsed or a credentials file cannot be read,
+    /// or if the resulting config is not consistent (see [`ConfigBuilder::build`](crate::client::ConfigBuilder::build))
+    ///
+    /// # Example
+    /// ```
+    /// use rustis::{client::Config, Result};
+    ///
+    /// fn main() -> Result<()> {
+    ///     // RUSTIS_HOSTS=127.0.0.1:6379
+    ///     // RUSTIS_DATABASE=1
+    ///     let config = Config::from_env("RUSTIS")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn from_env(prefix: &str) -> Result<Config> {
+        Self::from_env_vars(prefix, |name| std::env::var(name).ok())
+    }
+
+    /// Markdown documentation of the environment variables supported by [`from_env`](Config::from_env)
+    #[must_use]
+    pub fn to_env_docs(prefix: &str) -> String {
+        let mut docs = String::new();
+        for (suffix, description) in ENV_VARS {
+            let _ = writeln!(docs, "* `{prefix}_{suffix}` - {description}");
+        }
+        docs
+    }
+
+    pub(crate) fn from_env_vars(
+        prefix: &str,
+        var: impl Fn(&str) -> Option<String>,
+    ) -> Result<Config> {
+        let env = Env { prefix, var };
+
+        let mut config = match env.get("URL").map(|url| (env.name("URL"), url)) {
+            Some((name, url)) => parse_var(&name, &url, Config::from_str)?,
+            None => match (env.var)(REDIS_URL) {
+                Some(url) => parse_var(REDIS_URL, &url, Config::from_str)?,
+                None => Config::default(),
+            },
+        };
+
+        let hosts = env.parse("HOSTS", parse_hosts)?;
+        let cluster = env.parse("CLUSTER", bool::from_str)?;
+        let sentinel_service = env.get("SENTINEL_SERVICE");
+
+        match (hosts, sentinel_service) {
+            (Some(hosts), Some(service_name)) => {
+                config.server = ServerConfig::Sentinel(SentinelConfig {
+                    instances: hosts,
+                    service_name,
+                    ..Default::default()
+                });
+            }
+            (None, Some(service_name)) => match &mut config.server {
+                ServerConfig::Sentinel(sentinel_config) => {
+                    sentinel_config.service_name = service_name;
+                }
+                _ => {
+                    return Err(Error::Config(format!(
+                        "Environment variable {} requires {}",
+                        env.name("SENTINEL_SERVICE"),
+                        env.name("HOSTS")
+                    )))
+                }
+            },
+            (Some(mut hosts), None) => {
+                if cluster.unwrap_or(hosts.len() > 1) {
+                    config.server = ServerConfig::Cluster(ClusterConfig { nodes: hosts });
+                } else if hosts.len() == 1 {
+                    let (host, port) = hosts.remove(0);
+                    config.server = ServerConfig::Standalone { host, port };
+                } else {
+                    return Err(Error::Config(format!(
+                        "Invalid environment variable {}: a standalone server requires a single host",
+                        env.name("HOSTS")
+                    )));
+                }
+            }
+            (None, None) => (),
+        }
+
+        if let Some(username) = env.get_or_file("USERNAME")? {
+            config.username = Some(username);
+        }
+
+        if let Some(password) = env.get("PASSWORD") {
+            config.password = Some(password);
+        }
+
+        // password files are read at each connection attempt, to support password rotation
+        if let Some(password_file) = env.get_file("PASSWORD_FILE")? {
+            config.password_file = Some(password_file);
+        }
+
+        let sentinel_username = env.get("SENTINEL_USERNAME");
+        let sentinel_password = env.get("SENTINEL_PASSWORD");
+        let sentinel_password_file = env.get_file("SENTINEL_PASSWORD_FILE")?;
+        if sentinel_username.is_some()
+            || sentinel_password.is_some()
+            || sentinel_password_file.is_some()
+        {
+            let ServerConfig::Sentinel(sentinel_config) = &mut config.server else {
+                return Err(Error::Config(format!(
+                    "Environment variables {}_SENTINEL_* require a sentinel config",
+                    env.prefix
+                )));
+            };
+            if sentinel_username.is_some() {
+                sentinel_config.username = sentinel_username;
+            }
+            if sentinel_password.is_some() {
+                sentinel_config.password = sentinel_password;
+            }
+            if sentinel_password_file.is_some() {
+                sentinel_config.password_file = sentinel_password_file;
+            }
+        }
+
+        if let Some(database) = env.parse("DATABASE", usize::from_str)? {
+            config.database = database;
+        }
+
+        env.apply_tls(&mut config)?;
+
+        if let Some(connect_timeout) = env.parse("CONNECT_TIMEOUT", u64::from_str)? {
+            config.resilience.connect_timeout = Duration::from_millis(connect_timeout);
+        }
+
+        if let Some(command_timeout) = env.parse("COMMAND_TIMEOUT", u64::from_str)? {
+            config.resilience.command_timeout = Duration::from_millis(command_timeout);
+        }
+
+        if let Some(connection_name) = env.get("CONNECTION_NAME") {
+            config.connection_name = connection_name;
+        }
+
+        if let Some(connection_metadata) = env.parse("CONNECTION_METADATA", |metadata| {
+            Config::parse_connection_metadata(metadata).ok_or("expected `key:value` tags")
+        })? {
+            config.connection_metadata = connection_metadata;
+        }
+
+        if let Some(lib_name) = env.get("LIB_NAME") {
+            config.lib_name = lib_name;
+        }
+
+        ConfigBuilder::with_config(config).build()
+    }
+}
+
+struct Env<'a, F: Fn(&str) -> Option<String>> {
+    prefix: &'a str,
+    var: F,
+}
+
+impl<'a, F: Fn(&str) -> Option<String>> Env<'a, F> {
+    fn name(&self, suffix: &str) -> String {
+        format!("{}_{suffix}", self.prefix)
+    }
+
+    fn get(&self, suffix: &str) -> Option<String> {
+        (self.var)(&self.name(suffix)).filter(|value| !value.is_empty())
+    }
+
+    fn parse<T, E: ToString>(
+        &self,
+        suffix: &str,
+        parse: impl FnOnce(&str) -> std::result::Result<T, E>,
+    ) -> Result<Option<T>> {
+        self.get(suffix)
+            .map(|value| parse_var(&self.name(suffix), &value, parse))
+            .transpose()
+    }
+
+    /// Read `{prefix}_{suffix}`, or the content of the file `{prefix}_{suffix}_FILE`
+    fn get_or_file(&self, suffix: &str) -> Result<Option<String>> {
+        if let Some(value) = self.get(suffix) {
+            return Ok(Some(value));
+        }
+
+        let file_suffix = format!("{suffix}_FILE");
+        let Some(path) = self.get(&file_suffix) else {
+            return Ok(None);
+        };
+
+        self.read_file(&file_suffix, Path::new(&path)).map(Some)
+    }
+
+    /// Path of the secret file `{prefix}_{suffix}`, checked to be readable
+    fn get_file(&self, suffix: &str) -> Result<Option<PathBuf>> {
+        let Some(path) = self.get(suffix).map(PathBuf::from) else {
+            return Ok(None);
+        };
+
+        self.read_file(suffix, &path)?;
+        Ok(Some(path))
+    }
+
+    fn read_file(&self, suffix: &str, path: &Path) -> Result<String> {
+        read_secret_file(path).map_err(|e| {
+            Error::Config(format!(
+                "Invalid environment variable {}: {e}",
+                self.name(suffix)
+            ))
+        })
+    }
+
+    #[cfg(feature = "tls")]
+    fn apply_tls(&self, config: &mut Config) -> Result<()> {
+        if let Some(tls) = self.parse("TLS", bool::from_str)? {
+            config.tls_config = tls.then(TlsConfig::default);
+        }
+
+        if let Some(ca_file) = self.get("TLS_CA_FILE") {
+            let name = self.name("TLS_CA_FILE");
+            let pem = std::fs::read(&ca_file).map_err(|e| {
+                Error::Config(format!(
+                    "Cannot read file '{ca_file}' of environment variable {name}: {e}"
+                ))
+            })?;
+            let certificate = native_tls::Certificate::from_pem(&pem)
+                .map_err(|e| Error::Config(format!("Invalid environment variable {name}: {e}")))?;
+            config
+                .tls_config
+                .get_or_insert_with(TlsConfig::default)
+                .root_certificates(vec![certificate]);
+        }
+
+        if let Some(insecure) = self.parse("TLS_INSECURE", bool::from_str)? {
+            if let Some(tls_config) = &mut config.tls_config {
+                tls_config
+                    .danger_accept_invalid_certs(insecure)
+                    .danger_accept_invalid_hostnames(insecure);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "tls"))]
+    fn apply_tls(&self, _config: &mut Config) -> Result<()> {
+        let suffix = if self.parse("TLS", bool::from_str)? == Some(true) {
+            "TLS"
+        } else if self.get("TLS_CA_FILE").is_some() {
+            "TLS_CA_FILE"
+        } else if self.get("TLS_INSECURE").is_some() {
+            "TLS_INSECURE"
+        } else {
+            return Ok(());
+        };
+
+        Err(Error::Config(format!(
+            "Environment variable {} requires the `tls` feature",
+            self.name(suffix)
+        )))
+    }
+}
+
+fn parse_var<T, E: ToString>(
+    name: &str,
+    value: &str,
+    parse: impl FnOnce(&str) -> std::result::Result<T, E>,
+) -> Result<T> {
+    parse(value).map_err(|e| {
+        Error::Config(format!(
+            "Invalid environment variable {name}: {}",
+            e.to_string()
+        ))
+    })
+}
+
+fn parse_hosts(hosts: &str) -> std::result::Result<Vec<(String, u16)>, String> {
+    hosts
+        .split(',')
+        .map(str::trim)
+        .filter(|host| !host.is_empty())
+        .map(|host| {
+            Config::parse_addr(host)
+                .map(|(host, port)| (host.to_owned(), port))
+                .ok_or_else(|| format!("cannot parse host '{host}'"))
+        })
+        .collect()
+}
@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+/// Event emitted by the connection of a [`Client`](crate::client::Client),
+/// received with [`Client::on_connection_event`](crate::client::Client::on_connection_event)
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConnectionEvent {
+    /// A node has been added to the cluster topology at runtime
+    ClusterNodeAdded {
+        /// Id of the node
+        id: String,
+        /// Address of the node
+        address: (String, u16),
+        /// `true` for a master node
+        is_master: bool,
+    },
+    /// A node has been removed from the cluster topology at runtime
+    ClusterNodeRemoved {
+        /// Id of the node
+        id: String,
+        /// Address of the node
+        address: (String, u16),
+    },
+    /// A cluster node has been marked as unhealthy after consecutive failures
+    /// and is not routed to anymore until the end of its cool-down period
+    ///
+    /// See [`ResiliencePolicy::node_failure_threshold`](crate::client::ResiliencePolicy::node_failure_threshold)
+    ClusterNodeUnhealthy {
+        /// Address of the node
+        address: (String, u16),
+        /// Number of consecutive failures
+        failures: usize,
+        /// Cool-down period
+        cooldown: Duration,
+    },
+    /// A cluster node previously marked as unhealthy is reachable again
+    ClusterNodeHealthy {
+        /// Address of the node
+        address: (String, u16),
+    },
+}
@@ -0,0 +1,135 @@
+use crate::{
+    client::CommandLayer,
+    resp::{Command, CommandArgs, ToArgs},
+    Result,
+};
+use std::time::Duration;
+
+/// Built-in [layer](CommandLayer) appending default options to a family of commands,
+/// unless the options are explicitly given
+///
+/// Default options are registered with [`Config::default_options`](crate::client::Config::default_options),
+/// e.g. to enforce policies like "no keys without TTL".
+///
+/// # Example
+/// ```
+/// use rustis::{
+///     client::{Client, DefaultOptions, IntoConfig},
+///     commands::{GenericCommands, SetCondition, SetExpiration, StringCommands},
+///     Result,
+/// };
+/// use std::time::Duration;
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let mut config = "127.0.0.1:6379".into_config()?;
+///     config.default_options(DefaultOptions::set_ttl(Duration::from_secs(60)));
+///     let client = Client::connect(config).await?;
+///
+///     // `SET key1 value PX 60000`
+///     client.set("key1", "value").await?;
+///     assert!(client.pttl("key1").await? > 0);
+///
+///     // an explicit expiration overrides the default one
+///     client
+///         .set_with_options("key2", "value", SetCondition::None, SetExpiration::Ex(10), false)
+///         .await?;
+///     assert!(client.ttl("key2").await? <= 10);
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DefaultOptions {
+    /// names of the commands of the family, with their number of leading positional arguments
+    commands: Vec<(&'static str, usize)>,
+    options: CommandArgs,
+    overridden_by: Vec<String>,
+}
+
+impl DefaultOptions {
+    /// Default `options` of the command `command`
+    ///
+    /// `num_positional_args` is the number of leading arguments of the command
+    /// which cannot be options (e.g. 2 for the key & value of `SET`).
+    ///
+    /// By default, the options are not appended if the first of them is already
+    /// an argument of the command (e.g. `COUNT` for `COUNT 100`),
+    /// see [`overridden_by`](DefaultOptions::overridden_by).
+    #[must_use]
+    pub fn new(command: &'static str, num_positional_args: usize, options: impl ToArgs) -> Self {
+        let options = CommandArgs::default().arg(options).build();
+        let overridden_by = options
+            .first()
+            .map(|option| String::from_utf8_lossy(option).to_uppercase())
+            .into_iter()
+            .collect();
+
+        Self {
+            commands: vec![(command, num_positional_args)],
+            options,
+            overridden_by,
+        }
+    }
+
+    /// Apply the default options to another command of the family
+    #[must_use]
+    pub fn command(mut self, command: &'static str, num_positional_args: usize) -> Self {
+        self.commands.push((command, num_positional_args));
+        self
+    }
+
+    /// Options which, when given explicitly, prevent the default options from being appended
+    ///
+    /// Options are compared case-insensitively.
+    #[must_use]
+    pub fn overridden_by<'a>(mut self, options: impl IntoIterator<Item = &'a str>) -> Self {
+        self.overridden_by = options.into_iter().map(str::to_uppercase).collect();
+        self
+    }
+
+    /// Default expiration of the keys written with [`SET`](https://redis.io/commands/set/)
+    ///
+    /// Overridden by the `EX`, `PX`, `EXAT`, `PXAT` & `KEEPTTL` options.
+    #[must_use]
+    pub fn set_ttl(ttl: Duration) -> Self {
+        Self::new("SET", 2, ("PX", ttl.as_millis() as u64))
+            .overridden_by(["EX", "PX", "EXAT", "PXAT", "KEEPTTL"])
+    }
+
+    /// Default `COUNT` option of [`SCAN`](https://redis.io/commands/scan/),
+    /// [`SSCAN`](https://redis.io/commands/sscan/), [`HSCAN`](https://redis.io/commands/hscan/)
+    /// & [`ZSCAN`](https://redis.io/commands/zscan/)
+    #[must_use]
+    pub fn scan_count(count: usize) -> Self {
+        Self::new("SCAN", 1, ("COUNT", count))
+            .command("SSCAN", 2)
+            .command("HSCAN", 2)
+            .command("ZSCAN", 2)
+    }
+}
+
+impl CommandLayer for DefaultOptions {
+    fn on_command(&self, command: &mut Command) -> Result<()> {
+        let Some((_, num_positional_args)) = self
+            .commands
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(command.name))
+        else {
+            return Ok(());
+        };
+
+        let overridden = command.args.iter().skip(*num_positional_args).any(|arg| {
+            self.overridden_by
+                .iter()
+                .any(|option| option.as_bytes().eq_ignore_ascii_case(arg))
+        });
+
+        if !overridden {
+            command.args.arg_ref(&self.options);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use std::time::Duration;
+
+/// Keys deleted by [`Client::delete_keys`](crate::client::Client::delete_keys)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeleteKeys {
+    /// An explicit list of keys, deleted in order
+    List(Vec<String>),
+    /// The keys matching a glob-style pattern, as returned by [`SCAN`](https://redis.io/commands/scan/)
+    /// on each master node
+    Pattern(String),
+}
+
+/// Options of [`Client::delete_keys`](crate::client::Client::delete_keys)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeleteOptions {
+    /// Delete the keys with [`UNLINK`](https://redis.io/commands/unlink/), reclaiming their memory
+    /// in a background thread, instead of [`DEL`](https://redis.io/commands/del/) (default `true`)
+    pub unlink: bool,
+    /// Number of keys deleted by each batch, and `COUNT` hint of each `SCAN` (default `1000`)
+    pub batch: usize,
+    /// Maximum number of keys processed per second (default unlimited)
+    pub rate_limit: Option<usize>,
+}
+
+impl Default for DeleteOptions {
+    fn default() -> Self {
+        Self {
+            unlink: true,
+            batch: 1000,
+            rate_limit: None,
+        }
+    }
+}
+
+/// Progress of a [`Client::delete_keys`](crate::client::Client::delete_keys) operation,
+/// reported after each batch of keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeleteProgress {
+    /// Number of keys processed so far
+    ///
+    /// `SCAN` may return a key more than once: such a key is counted several times.
+    pub processed_keys: usize,
+    /// Number of processed keys which existed and have been deleted
+    pub deleted_keys: usize,
+    /// Total number of keys to process, `None` for a [`DeleteKeys::Pattern`]
+    pub total_keys: Option<usize>,
+    /// Time elapsed since the start of the operation
+    pub elapsed: Duration,
+}
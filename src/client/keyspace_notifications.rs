@@ -0,0 +1,207 @@
+use crate::{Error, Result};
+use std::{
+    fmt,
+    ops::{BitOr, BitOrAssign},
+    str::FromStr,
+};
+
+/// Set of [keyspace notifications](https://redis.io/docs/manual/keyspace-notifications/)
+/// classes, as configured with the `notify-keyspace-events` parameter
+///
+/// Events are only published when [`KEYSPACE`](KeyspaceEvents::KEYSPACE)
+/// and/or [`KEYEVENT`](KeyspaceEvents::KEYEVENT) is enabled.
+///
+/// See [`Client::ensure_keyspace_notifications`](crate::client::Client::ensure_keyspace_notifications)
+///
+/// # Example
+/// ```
+/// use rustis::client::KeyspaceEvents;
+///
+/// let events = KeyspaceEvents::KEYEVENT | KeyspaceEvents::EXPIRED | KeyspaceEvents::EVICTED;
+/// assert_eq!("Exe", events.to_string());
+/// assert_eq!(events, "Exe".parse().unwrap());
+/// assert!("AKE".parse::<KeyspaceEvents>().unwrap().contains(events));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct KeyspaceEvents(u16);
+
+/// Flag characters of the `notify-keyspace-events` parameter, in canonical order
+const FLAGS: [(char, KeyspaceEvents); 14] = [
+    ('K', KeyspaceEvents::KEYSPACE),
+    ('E', KeyspaceEvents::KEYEVENT),
+    ('g', KeyspaceEvents::GENERIC),
+    ('$', KeyspaceEvents::STRING),
+    ('l', KeyspaceEvents::LIST),
+    ('s', KeyspaceEvents::SET),
+    ('h', KeyspaceEvents::HASH),
+    ('z', KeyspaceEvents::SORTED_SET),
+    ('x', KeyspaceEvents::EXPIRED),
+    ('e', KeyspaceEvents::EVICTED),
+    ('t', KeyspaceEvents::STREAM),
+    ('m', KeyspaceEvents::KEY_MISS),
+    ('d', KeyspaceEvents::MODULE),
+    ('n', KeyspaceEvents::NEW_KEY),
+];
+
+impl KeyspaceEvents {
+    /// No notification (empty set)
+    pub const NONE: KeyspaceEvents = KeyspaceEvents(0);
+    /// `K`: keyspace events, published on `__keyspace@<db>__:<key>` channels
+    pub const KEYSPACE: KeyspaceEvents = KeyspaceEvents(1);
+    /// `E`: keyevent events, published on `__keyevent@<db>__:<event>` channels
+    pub const KEYEVENT: KeyspaceEvents = KeyspaceEvents(1 << 1);
+    /// `g`: generic commands (non-type specific) like `DEL`, `EXPIRE`, `RENAME`, ...
+    pub const GENERIC: KeyspaceEvents = KeyspaceEvents(1 << 2);
+    /// `$`: string commands
+    pub const STRING: KeyspaceEvents = KeyspaceEvents(1 << 3);
+    /// `l`: list commands
+    pub const LIST: KeyspaceEvents = KeyspaceEvents(1 << 4);
+    /// `s`: set commands
+    pub const SET: KeyspaceEvents = KeyspaceEvents(1 << 5);
+    /// `h`: hash commands
+    pub const HASH: KeyspaceEvents = KeyspaceEvents(1 << 6);
+    /// `z`: sorted set commands
+    pub const SORTED_SET: KeyspaceEvents = KeyspaceEvents(1 << 7);
+    /// `x`: expired events (events generated every time a key expires)
+    pub const EXPIRED: KeyspaceEvents = KeyspaceEvents(1 << 8);
+    /// `e`: evicted events (events generated when a key is evicted for `maxmemory`)
+    pub const EVICTED: KeyspaceEvents = KeyspaceEvents(1 << 9);
+    /// `t`: stream commands
+    pub const STREAM: KeyspaceEvents = KeyspaceEvents(1 << 10);
+    /// `m`: key-miss events (events generated when a key that doesn't exist is accessed)
+    pub const KEY_MISS: KeyspaceEvents = KeyspaceEvents(1 << 11);
+    /// `d`: module key type events
+    pub const MODULE: KeyspaceEvents = KeyspaceEvents(1 << 12);
+    /// `n`: new key events (not included in [`ALL`](KeyspaceEvents::ALL))
+    pub const NEW_KEY: KeyspaceEvents = KeyspaceEvents(1 << 13);
+    /// `A`: alias for `g$lshzxetd`
+    pub const ALL: KeyspaceEvents = KeyspaceEvents(
+        Self::GENERIC.0
+            | Self::STRING.0
+            | Self::LIST.0
+            | Self::SET.0
+            | Self::HASH.0
+            | Self::SORTED_SET.0
+            | Self::EXPIRED.0
+            | Self::EVICTED.0
+            | Self::STREAM.0
+            | Self::MODULE.0,
+    );
+
+    /// `true` if all the events of `other` are in `self`
+    #[must_use]
+    pub fn contains(self, other: KeyspaceEvents) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// `true` if no event is in the set
+    #[must_use]
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Events of `self` which are not in `other`
+    #[must_use]
+    pub fn difference(self, other: KeyspaceEvents) -> KeyspaceEvents {
+        KeyspaceEvents(self.0 & !other.0)
+    }
+}
+
+impl BitOr for KeyspaceEvents {
+    type Output = KeyspaceEvents;
+
+    fn bitor(self, rhs: KeyspaceEvents) -> KeyspaceEvents {
+        KeyspaceEvents(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for KeyspaceEvents {
+    fn bitor_assign(&mut self, rhs: KeyspaceEvents) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Value of the `notify-keyspace-events` parameter
+impl fmt::Display for KeyspaceEvents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (c, flag) in FLAGS {
+            if self.contains(flag) {
+                f.write_fmt(format_args!("{c}"))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parse the value of the `notify-keyspace-events` parameter
+impl FromStr for KeyspaceEvents {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut events = KeyspaceEvents::NONE;
+        for c in s.chars() {
+            if c == 'A' {
+                events |= KeyspaceEvents::ALL;
+            } else if let Some((_, flag)) = FLAGS.iter().find(|(flag_char, _)| *flag_char == c) {
+                events |= *flag;
+            } else {
+                return Err(Error::Client(format!(
+                    "Unknown keyspace notification class `{c}` in `{s}`"
+                )));
+            }
+        }
+        Ok(events)
+    }
+}
+
+/// Keyspace notifications of a node, reported by
+/// [`Client::ensure_keyspace_notifications`](crate::client::Client::ensure_keyspace_notifications)
+#[derive(Debug)]
+pub struct NodeKeyspaceNotifications {
+    /// `host:port` of the node, `None` if unknown
+    pub node: Option<String>,
+    /// Events enabled before the call
+    pub previous: KeyspaceEvents,
+    /// Events enabled after the call
+    pub enabled: KeyspaceEvents,
+    /// Error returned by `CONFIG SET`, when the parameter could not be set
+    /// (e.g. `CONFIG` command renamed or denied by an ACL)
+    pub error: Option<Error>,
+}
+
+impl NodeKeyspaceNotifications {
+    /// `true` if the parameter has been changed on the node
+    #[must_use]
+    pub fn updated(&self) -> bool {
+        self.previous != self.enabled
+    }
+}
+
+/// Report of [`Client::ensure_keyspace_notifications`](crate::client::Client::ensure_keyspace_notifications)
+#[derive(Debug)]
+pub struct KeyspaceNotificationsReport {
+    /// Required events
+    pub required: KeyspaceEvents,
+    /// Events of each node: the connected server, or each master node of a cluster
+    pub nodes: Vec<NodeKeyspaceNotifications>,
+}
+
+impl KeyspaceNotificationsReport {
+    /// `true` if the required events are enabled on all the nodes
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.nodes
+            .iter()
+            .all(|node| node.enabled.contains(self.required))
+    }
+
+    /// Required events which are missing on at least one node
+    #[must_use]
+    pub fn missing(&self) -> KeyspaceEvents {
+        self.nodes
+            .iter()
+            .fold(KeyspaceEvents::NONE, |missing, node| {
+                missing | self.required.difference(node.enabled)
+            })
+    }
+}
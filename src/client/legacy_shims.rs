@@ -0,0 +1,196 @@
+use crate::{
+    resp::{cmd, Command, RespBuf, RespSerializer, Value},
+    Error, Result, Version,
+};
+use log::warn;
+use serde::Serialize;
+use std::{collections::HashSet, sync::Mutex};
+
+/// Version which introduced the commands emulated by the shims
+const SHIMS_VERSION: Version = Version::new(6, 2, 0);
+
+/// `COPY source destination [REPLACE]` with `DUMP` & `RESTORE`
+const COPY_SCRIPT: &str = r#"
+local value = redis.call('DUMP', KEYS[1])
+if not value then return 0 end
+if ARGV[1] ~= 'REPLACE' and redis.call('EXISTS', KEYS[2]) == 1 then return 0 end
+local ttl = redis.call('PTTL', KEYS[1])
+if ttl < 0 then ttl = 0 end
+redis.call('RESTORE', KEYS[2], ttl, value, 'REPLACE')
+return 1
+"#;
+
+/// `LMOVE source destination LEFT|RIGHT LEFT|RIGHT` with `LPOP`/`RPOP` & `LPUSH`/`RPUSH`
+const LMOVE_SCRIPT: &str = r#"
+local value = redis.call(ARGV[1], KEYS[1])
+if value then redis.call(ARGV[2], KEYS[2], value) end
+return value
+"#;
+
+/// Legacy translation of a command unsupported by the connected server
+#[derive(Debug)]
+pub(crate) enum LegacyShim {
+    /// Equivalent single command
+    Command(Box<Command>),
+    /// Equivalent commands, sent in a `MULTI`/`EXEC` transaction.
+    /// The reply of the command at `reply_index` is the reply of the emulated command.
+    Transaction {
+        commands: Vec<Command>,
+        reply_index: usize,
+    },
+}
+
+impl LegacyShim {
+    /// Extract the reply of the emulated command from the reply of `EXEC`
+    pub fn transaction_reply(exec_reply: &RespBuf, reply_index: usize) -> Result<RespBuf> {
+        let Some(replies) = exec_reply.to::<Option<Vec<Value>>>()? else {
+            return Err(Error::Aborted);
+        };
+
+        match replies.into_iter().nth(reply_index) {
+            Some(Value::Error(e)) => Err(Error::Redis(e)),
+            Some(reply) => {
+                let mut serializer = RespSerializer::new();
+                reply.serialize(&mut serializer)?;
+                Ok(RespBuf::new(serializer.get_output().freeze()))
+            }
+            None => Err(Error::Client(
+                "Unexpected result for legacy command shim".to_owned(),
+            )),
+        }
+    }
+}
+
+/// Emulation of commands introduced in Redis 6.2 for older servers,
+/// see [`Config::legacy_command_shims`](crate::client::Config::legacy_command_shims)
+///
+/// A warning is logged the first time each command is emulated.
+#[derive(Default)]
+pub(crate) struct LegacyShims {
+    warned: Mutex<HashSet<&'static str>>,
+}
+
+impl LegacyShims {
+    /// Legacy translation of `command` if it is not supported by `server_version`
+    pub fn shim(&self, command: &Command, server_version: Version) -> Option<LegacyShim> {
+        if server_version >= SHIMS_VERSION {
+            return None;
+        }
+
+        let shim = translate(command)?;
+
+        if self.warned.lock().unwrap().insert(command.name) {
+            warn!(
+                "Command {} is not supported by Redis {server_version}, emulated with legacy commands",
+                command.name
+            );
+        }
+
+        Some(shim)
+    }
+}
+
+fn translate(command: &Command) -> Option<LegacyShim> {
+    let args: Vec<&[u8]> = command.args.into_iter().collect();
+    let is = |arg: &[u8], name: &str| arg.eq_ignore_ascii_case(name.as_bytes());
+
+    match command.name.to_ascii_uppercase().as_str() {
+        "GETDEL" => match args[..] {
+            [key] => Some(LegacyShim::Transaction {
+                commands: vec![cmd("GET").arg(key), cmd("DEL").arg(key)],
+                reply_index: 0,
+            }),
+            _ => None,
+        },
+        "GETEX" => {
+            let expire = match args[..] {
+                [key] => return Some(LegacyShim::Command(Box::new(cmd("GET").arg(key)))),
+                [key, option] if is(option, "PERSIST") => cmd("PERSIST").arg(key),
+                [key, option, value] => {
+                    let name = if is(option, "EX") {
+                        "EXPIRE"
+                    } else if is(option, "PX") {
+                        "PEXPIRE"
+                    } else if is(option, "EXAT") {
+                        "EXPIREAT"
+                    } else if is(option, "PXAT") {
+                        "PEXPIREAT"
+                    } else {
+                        return None;
+                    };
+                    cmd(name).arg(key).arg(value)
+                }
+                _ => return None,
+            };
+
+            Some(LegacyShim::Transaction {
+                commands: vec![cmd("GET").arg(args[0]), expire],
+                reply_index: 0,
+            })
+        }
+        // `SET key value [options] GET`, formerly `GETSET`
+        "SET" if args.iter().skip(2).any(|arg| is(arg, "GET")) => {
+            let mut set = cmd("SET");
+            for (i, arg) in args.iter().enumerate() {
+                if i < 2 || !is(arg, "GET") {
+                    set = set.arg(*arg);
+                }
+            }
+
+            Some(LegacyShim::Transaction {
+                commands: vec![cmd("GET").arg(args[0]), set],
+                reply_index: 0,
+            })
+        }
+        "COPY" => {
+            let replace = match args[..] {
+                [_, _] => "",
+                [_, _, option] if is(option, "REPLACE") => "REPLACE",
+                _ => return None,
+            };
+
+            Some(LegacyShim::Command(Box::new(
+                cmd("EVAL")
+                    .arg(COPY_SCRIPT)
+                    .arg(2)
+                    .arg(args[0])
+                    .arg(args[1])
+                    .arg(replace),
+            )))
+        }
+        "LMOVE" => {
+            let [source, destination, from, to] = args[..] else {
+                return None;
+            };
+
+            let side = |arg: &[u8], left: &'static str, right: &'static str| {
+                if is(arg, "LEFT") {
+                    Some(left)
+                } else if is(arg, "RIGHT") {
+                    Some(right)
+                } else {
+                    None
+                }
+            };
+            let pop = side(from, "LPOP", "RPOP")?;
+            let push = side(to, "LPUSH", "RPUSH")?;
+
+            if pop == "RPOP" && push == "LPUSH" {
+                return Some(LegacyShim::Command(Box::new(
+                    cmd("RPOPLPUSH").arg(source).arg(destination),
+                )));
+            }
+
+            Some(LegacyShim::Command(Box::new(
+                cmd("EVAL")
+                    .arg(LMOVE_SCRIPT)
+                    .arg(2)
+                    .arg(source)
+                    .arg(destination)
+                    .arg(pop)
+                    .arg(push),
+            )))
+        }
+        _ => None,
+    }
+}
@@ -1,17 +1,23 @@
-use log::warn;
+use log::debug;
 use smallvec::SmallVec;
 
 use crate::{
-    network::{ResultSender, ResultsSender},
+    client::{CoalescingGuard, PendingCommandsGuard},
+    network::{InFlightGuard, ResultSender, ResultsSender},
     resp::Command,
     Error, PubSubSender, PushSender, RetryReason,
 };
 
-#[cfg(debug_assertions)]
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicU64, Ordering};
 
-#[cfg(debug_assertions)]
-static MESSAGE_SEQUENCE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+/// Sequence of the identifiers of the messages, shared by all the clients
+static MESSAGE_ID_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// Next message identifier
+#[inline(always)]
+fn next_message_id() -> u64 {
+    MESSAGE_ID_SEQUENCE.fetch_add(1, Ordering::Relaxed)
+}
 
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
@@ -34,15 +40,15 @@ impl Commands {
         match self {
             Commands::Single(_, Some(result_sender)) => {
                 if let Err(e) = result_sender.send(Err(error)) {
-                    warn!(
-                    "[{tag}] Cannot send value to caller because receiver is not there anymore: {e:?}",
+                    debug!(
+                    "[{tag}] Cannot send error to caller because receiver is not there anymore: {e:?}",
                 );
                 }
             }
             Commands::Batch(_, results_sender) => {
                 if let Err(e) = results_sender.send(Err(error)) {
-                    warn!(
-                    "[{tag}] Cannot send value to caller because receiver is not there anymore: {e:?}",
+                    debug!(
+                    "[{tag}] Cannot send error to caller because receiver is not there anymore: {e:?}",
                 );
                 }
             }
@@ -146,9 +152,14 @@ pub(crate) struct Message {
     pub push_sender: Option<PushSender>,
     pub retry_reasons: Option<SmallVec<[RetryReason; 10]>>,
     pub retry_on_error: bool,
-    #[cfg(debug_assertions)]
-    #[allow(unused)]
-    pub(crate) message_seq: usize,
+    /// Identifier of the client instance which sent the message
+    pub client_id: usize,
+    pub in_flight_guard: Option<InFlightGuard>,
+    pub pending_commands_guard: Option<PendingCommandsGuard>,
+    /// Guard of a coalesced command, completed when its reply is delivered
+    pub coalescing_guard: Option<CoalescingGuard>,
+    /// Identifier of the message, correlating its request with its reply in logs
+    pub id: u64,
 }
 
 impl Message {
@@ -160,8 +171,11 @@ impl Message {
             push_sender: None,
             retry_reasons: None,
             retry_on_error,
-            #[cfg(debug_assertions)]
-            message_seq: MESSAGE_SEQUENCE_COUNTER.fetch_add(1, Ordering::SeqCst),
+            client_id: 0,
+            in_flight_guard: None,
+            pending_commands_guard: None,
+            coalescing_guard: None,
+            id: next_message_id(),
         }
     }
 
@@ -173,8 +187,11 @@ impl Message {
             push_sender: None,
             retry_reasons: None,
             retry_on_error,
-            #[cfg(debug_assertions)]
-            message_seq: MESSAGE_SEQUENCE_COUNTER.fetch_add(1, Ordering::SeqCst),
+            client_id: 0,
+            in_flight_guard: None,
+            pending_commands_guard: None,
+            coalescing_guard: None,
+            id: next_message_id(),
         }
     }
 
@@ -190,8 +207,11 @@ impl Message {
             push_sender: None,
             retry_reasons: None,
             retry_on_error,
-            #[cfg(debug_assertions)]
-            message_seq: MESSAGE_SEQUENCE_COUNTER.fetch_add(1, Ordering::SeqCst),
+            client_id: 0,
+            in_flight_guard: None,
+            pending_commands_guard: None,
+            coalescing_guard: None,
+            id: next_message_id(),
         }
     }
 
@@ -207,8 +227,11 @@ impl Message {
             push_sender: None,
             retry_reasons: None,
             retry_on_error: true,
-            #[cfg(debug_assertions)]
-            message_seq: MESSAGE_SEQUENCE_COUNTER.fetch_add(1, Ordering::SeqCst),
+            client_id: 0,
+            in_flight_guard: None,
+            pending_commands_guard: None,
+            coalescing_guard: None,
+            id: next_message_id(),
         }
     }
 
@@ -220,8 +243,11 @@ impl Message {
             push_sender: Some(push_sender),
             retry_reasons: None,
             retry_on_error: true,
-            #[cfg(debug_assertions)]
-            message_seq: MESSAGE_SEQUENCE_COUNTER.fetch_add(1, Ordering::SeqCst),
+            client_id: 0,
+            in_flight_guard: None,
+            pending_commands_guard: None,
+            coalescing_guard: None,
+            id: next_message_id(),
         }
     }
 
@@ -233,8 +259,11 @@ impl Message {
             push_sender: Some(push_sender),
             retry_reasons: None,
             retry_on_error: false,
-            #[cfg(debug_assertions)]
-            message_seq: MESSAGE_SEQUENCE_COUNTER.fetch_add(1, Ordering::SeqCst),
+            client_id: 0,
+            in_flight_guard: None,
+            pending_commands_guard: None,
+            coalescing_guard: None,
+            id: next_message_id(),
         }
     }
 }
@@ -0,0 +1,88 @@
+use crate::{resp::Value, RedisError, RedisErrorKind, Version};
+use std::collections::{HashMap, VecDeque};
+
+/// Behavior of the in-memory server behind a client created with
+/// [`Client::connect_mock`](crate::client::Client::connect_mock).
+///
+/// The in-memory server implements the most common commands
+/// (strings, hashes, lists, sets, generic & transaction commands) against an in-memory keyspace.
+///
+/// For any other command, or to simulate a specific server reply,
+/// canned replies and errors can be scripted by command name.
+/// Scripted replies of a command are consumed in order, one per call,
+/// then the command falls back to the in-memory keyspace.
+///
+/// # Example
+/// ```
+/// use rustis::{
+///     client::{Client, MockServerBehavior},
+///     commands::{ServerCommands, StringCommands},
+///     resp::Value,
+///     RedisErrorKind, Result,
+/// };
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let behavior = MockServerBehavior::new()
+///         .reply("DBSIZE", Value::Integer(12))
+///         .error("GET", RedisErrorKind::Readonly, "You can't write against a read only replica.");
+///     let client = Client::connect_mock(behavior).await?;
+///
+///     client.set("key", "value").await?;
+///     assert_eq!(12, client.dbsize().await?);
+///     assert!(client.get::<_, String>("key").await.is_err());
+///     let value: String = client.get("key").await?;
+///     assert_eq!("value", value);
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct MockServerBehavior {
+    pub(crate) replies: HashMap<String, VecDeque<Value>>,
+    pub(crate) server_version: Option<Version>,
+}
+
+impl MockServerBehavior {
+    /// Creates a new behavior without any scripted reply
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Script a canned reply for the next call of the command `command_name`
+    #[must_use]
+    pub fn reply(mut self, command_name: impl AsRef<str>, reply: Value) -> Self {
+        self.replies
+            .entry(command_name.as_ref().to_ascii_uppercase())
+            .or_default()
+            .push_back(reply);
+        self
+    }
+
+    /// Version reported by the in-memory server,
+    /// see [`Client::server_version`](crate::client::Client::server_version)
+    #[must_use]
+    pub fn server_version(mut self, server_version: Version) -> Self {
+        self.server_version = Some(server_version);
+        self
+    }
+
+    /// Script a canned error for the next call of the command `command_name`
+    #[must_use]
+    pub fn error(
+        self,
+        command_name: impl AsRef<str>,
+        kind: RedisErrorKind,
+        description: impl Into<String>,
+    ) -> Self {
+        self.reply(
+            command_name,
+            Value::Error(RedisError {
+                kind,
+                description: description.into(),
+            }),
+        )
+    }
+}
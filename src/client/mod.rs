@@ -72,6 +72,21 @@ would be to connect two multiplexed clients to the Redis server:
 * 1 for the subscriptions
 * 1 for the regular commands
 
+### Cancellation safety
+
+The futures of the commands can be dropped at any point, typically when they lose a `select!`
+or when they are wrapped in a timeout.
+The connection is only read by the network task, which correlates each request with its reply
+by its identifier and position in the pipeline of the connection:
+the reply of a cancelled command is still received, then discarded,
+and the following replies are delivered to their own callers.
+
+A [`subscribe`](crate::commands::PubSubCommands::subscribe) or [`monitor`](crate::commands::BlockingCommands::monitor)
+future cancelled before its confirmation is undone as soon as the confirmation is received.
+
+[`SelectChurn`](crate::test_util::SelectChurn) (`test-util` feature)
+checks these guarantees against an actual server.
+
 ### See also
 [Multiplexing Explained](https://redis.com/blog/multiplexing-explained/)
 
@@ -119,6 +134,10 @@ It is an alternative to multiplexing, for managing **rustis** within a Web appli
 
 The manager can be configured via [bb8](https://docs.rs/bb8/latest/bb8/) with a various of options like maximum size, maximum lifetime, etc.
 
+For long-running services, the connections leaked on the server by the pool can be closed
+with [`PooledClientManager::sweep_orphans`], and the duplicate ones
+with [`PooledClientManager::sweep_duplicates`].
+
 For you convenience, [bb8](https://docs.rs/bb8/latest/bb8/) is reexported from the **rustis** crate.
 
 ```
@@ -169,6 +188,10 @@ A [`Client`] instance can be configured with the [`Config`] struct:
 
 The **rustis** [`Config`] can also be built from an URL
 
+Usernames and passwords holding reserved characters (e.g. `@`, `:` or `/`) must be
+[percent-encoded](https://developer.mozilla.org/en-US/docs/Glossary/Percent-encoding),
+as done by [`Config::to_url`].
+
 ### Standalone
 
 ```text
@@ -184,11 +207,13 @@ redis|rediss[+cluster]://[[<username>]:<password>@]<host1>[:<port1>][,<host2>:[<
 ### Sentinel
 
 ```text
-redis|rediss[+sentinel]://[[<username>]:<password>@]<host>[:<port>]/<service>[/<database>]
-                          [?wait_between_failures=<250>[&sentinel_username=<username>][&sentinel_password=<password>]]
+redis|rediss[+sentinel]://[[<username>]:<password>@]<host1>[:<port1>][,<host2>:[<port2>][,<hostN>:[<portN>]]]/<service>[/<database>]
+                          [?wait_between_failures=<250>[&prefer_last_good_sentinel=<false>][&stale_master_guard=<false>]
+                          [&sentinel_username=<username>][&sentinel_password=<password>]]
 ```
 
-`service` is the required name of the sentinel service
+`service` is the required name of the sentinel service.
+Sentinel instances are tried in the order of the URL, the ones which have failed in a row last.
 
 ### Schemes
 The URL scheme is used to detect the server type:
@@ -202,23 +227,73 @@ The URL scheme is used to detect the server type:
 ### QueryParameters
 Query parameters match perfectly optional configuration fields
 of the struct [`Config`] or its dependencies:
-* [`connect_timeout`](Config::connect_timeout) - The time to attempt a connection before timing out (default `10,000` ms).
-* [`command_timeout`](Config::command_timeout) - If a command does not return a reply within a set number of milliseconds,
+* [`connect_timeout`](ResiliencePolicy::connect_timeout) - The time to attempt a connection before timing out (default `10,000` ms).
+* [`command_timeout`](ResiliencePolicy::command_timeout) - If a command does not return a reply within a set number of milliseconds,
    a timeout error will be thrown. If set to 0, no timeout is apply (default `0`).
+* [`retries`](ResiliencePolicy::retries) - Maximum number of consecutive reconnection attempts, `0` to retry forever (default `0`).
 * [`auto_resubscribe`](Config::auto_resubscribe) - When the client reconnects, channels subscribed in the previous connection will be
   resubscribed automatically if `auto_resubscribe` is `true` (default `true`).
 * [`auto_remonitor`](Config::auto_remonitor) - When the client reconnects, if in `monitor` mode, the
   [`monitor`](crate::commands::BlockingCommands::monitor) command will be resent automatically
 * [`connection_name`](Config::connection_name) - Set the name of the connection to make
   it easier to identity the connection in client list.
+* [`connection_metadata`](Config::connection_metadata) - Metadata tags of the connections,
+  in the format `key1:value1,key2:value2`, rendered into the connection name and the library name (default none).
+* [`lib_name`](Config::lib_name) - Name of the client library set with `CLIENT SETINFO` (default `rustis`).
+* [`password_file`](Config::password_file) - File containing the password, read at each connection attempt (default `None`)
 * [`keep_alive`](Config::keep_alive) - Enable/disable keep-alive functionality (default `None`)
 * [`no_delay`](Config::no_delay) - Enable/disable the use of Nagle's algorithm (default `true`)
-* [`retry_on_error`](Config::retry_on_error) - Defines the default strategy for retries on network error (default `false`).
-* [`reconnection`](Config::reconnection) - Reconnection policy configuration: Constant, Linear or Exponential (default `Constant`)
+* [`send_buffer_size`](Config::send_buffer_size) - Size of the socket send buffer (default `None`)
+* [`recv_buffer_size`](Config::recv_buffer_size) - Size of the socket receive buffer (default `None`)
+* [`tcp_user_timeout`](Config::tcp_user_timeout) - `TCP_USER_TIMEOUT` socket option in milliseconds, Linux only (default `None`)
+* [`tos`](Config::tos) - IP type of service (TOS) / DSCP of the outgoing packets (default `None`)
+* [`local_address`](Config::local_address) - Local IP address the socket is bound to (default `None`)
+* [`bind_interface`](Config::bind_interface) - Network interface the socket is bound to, Linux only (default `None`)
+* [`retry_on_error`](ResiliencePolicy::retry_on) - Send again the commands interrupted by a network error,
+  once reconnected (default `false`).
+* [`max_in_flight_per_client`](Config::max_in_flight_per_client) - Maximum number of commands of a single client instance
+  waiting for their replies on the shared connection (default `0`, unlimited).
+* [`max_pending_commands`](Config::max_pending_commands) - Maximum number of commands sent through a client and its clones
+  and not yet replied (default `0`, unlimited).
+* [`fail_on_queue_full`](Config::fail_on_queue_full) - When [`max_pending_commands`](Config::max_pending_commands) is reached,
+  fail with [`Error::QueueFull`](crate::Error::QueueFull) instead of waiting for a free slot (default `false`).
+* [`strict_server_version`](Config::strict_server_version) - Fail locally the commands introduced after the version
+  of the connected server with [`Error::UnsupportedByServer`](crate::Error::UnsupportedByServer) (default `false`).
+* [`warn_deprecated_commands`](Config::warn_deprecated_commands) - Log a warning the first time a deprecated command
+  is sent, with its documented replacement (default `false`).
+* [`legacy_command_shims`](Config::legacy_command_shims) - Emulate some commands introduced in Redis 6.2
+  when the connected server is older (default `false`).
+* [`max_batch_commands`](Config::max_batch_commands) - Maximum number of commands sent in a single batch
+  by a [`Pipeline`], bigger pipelines being split (default `0`, unlimited).
+* [`max_batch_bytes`](Config::max_batch_bytes) - Maximum size in bytes of a single batch
+  sent by a [`Pipeline`], bigger pipelines being split (default `0`, unlimited).
+* [`coalesce_reads`](Config::coalesce_reads) - Share a single server round trip among identical
+  read-only commands sent concurrently (default `false`).
+* [`command_journal_size`](Config::command_journal_size) - Number of the last commands retained
+  in a [`CommandJournal`] for debugging purpose (default `0`, disabled).
+* [`error_context`](Config::error_context) - Attach the command name, the node, the attempt number
+  & the elapsed time to the failures of the commands (default `false`).
+* [`idle_connection_timeout`](Config::idle_connection_timeout) - (Cluster only) Idle time in milliseconds
+  after which the connection to a node is closed, until its next use (default `None`).
+* [`pub_sub_keepalive`](Config::pub_sub_keepalive) - Quiet time in milliseconds after which a `PING`
+  is sent on a subscribed connection, which is re-established if it stays quiet (default `None`).
+* [`auto_detect_cluster`](Config::auto_detect_cluster) - (Standalone only) Upgrade the connection
+  to a cluster connection when the server is a cluster node (default `false`).
+* [`otel_metrics`](Config::otel_metrics) - (`otel-metrics` feature only) Export the metrics
+  of the client to OpenTelemetry, see [`OtelMetrics`] (default `false`).
 * [`wait_between_failures`](SentinelConfig::wait_between_failures) - (Sentinel only) Waiting time after
   failing before connecting to the next Sentinel instance (default `250` ms).
+* [`prefer_last_good_sentinel`](SentinelConfig::prefer_last_good_sentinel) - (Sentinel only) Try first
+  the last Sentinel instance which has given the master address (default `false`).
+* [`stale_master_guard`](SentinelConfig::stale_master_guard) - (Sentinel only) Discover the master again
+  when it replies with a `READONLY` error, after a failover (default `false`).
 * [`sentinel_username`](SentinelConfig::username) - (Sentinel only) Sentinel username
 * [`sentinel_password`](SentinelConfig::password) - (Sentinel only) Sentinel password
+* [`sentinel_password_file`](SentinelConfig::password_file) - (Sentinel only) File containing the Sentinel password,
+  read at each connection attempt
+* [`node_failure_threshold`](ResiliencePolicy::node_failure_threshold) - (Cluster only) Number of consecutive failures
+  after which a node is marked as unhealthy (default `0`, disabled)
+* [`node_cooldown`](ResiliencePolicy::node_cooldown) - (Cluster only) Cool-down period of an unhealthy node (default `30,000` ms)
 
 ### Example
 
@@ -235,6 +310,12 @@ async fn main() -> Result<()> {
 }
 ```
 
+## Environment variables
+
+[`Config::from_env`] builds a config from `REDIS_URL` and granular prefixed variables
+(`{prefix}_HOSTS`, `{prefix}_PASSWORD_FILE`, `{prefix}_TLS`, ...).
+[`Config::to_env_docs`] generates the documentation of the supported variables.
+
 # Pipelining
 
 One of the most performant Redis feature is [pipelining](https://redis.io/docs/manual/pipelining/).
@@ -355,10 +436,14 @@ To publish a message, you can call the [`publish`](crate::commands::PubSubComman
 associated function on its dedicated trait.
 
 It also possible to use the sharded flavor of the publish function: [`spublish`](crate::commands::PubSubCommands::spublish).
+On a cluster client, `spublish` is routed to the master node owning the hash slot of the shard channel,
+like a command on a key.
+The shard channels subscribed to with [`ssubscribe`](crate::commands::PubSubCommands::ssubscribe)
+are tracked by hash slot in the [`ShardSubscriptions`] of the [client state](Client::get_client_state).
 
 ### Subscribing
 
-Subscribing will block the current client connection, in order to let the client wait for incoming messages.
+Subscribing will block a connection, in order to let the client wait for incoming messages.
 Consequently, **rustis** implements subsribing through an async [`Stream`](https://docs.rs/futures/latest/futures/stream/trait.Stream.html).
 
 You can create a [`PubSubStream`] by calling [`subscribe`](crate::commands::PubSubCommands::subscribe),
@@ -369,10 +454,20 @@ wait for an incoming message in the form of the struct [`PubSubMessage`].
 
 You can also create a [`PubSubStream`] without an upfront subscription by calling [`create_pub_sub`](crate::client::Client::create_pub_sub).
 
-### Warning!
+### Dedicated connection
+
+Subscriptions are not made on the connection of the [`Client`] but on a dedicated Pub/Sub connection,
+created with the same configuration on the first subscription.
+This connection is shared by the client, its clones and all their pub/sub streams,
+and is closed when the last pub/sub stream is dropped or closed.
+
+Consequently, a multiplexed or pooled client keeps serving regular commands while subscribed.
 
-Multiplexed [`Client`] instances must be dedicated to Pub/Sub once a subscribing function has been called.
-Because subscription blocks the multiplexed client shared connection other callers would be blocked when sending regular commands.
+Messages published while the dedicated connection is down are lost, even with
+[`auto_resubscribe`](Config::auto_resubscribe). A stream created with
+[`notify_interruptions(true)`](PubSubStream::notify_interruptions) yields an
+[`Error::SubscriptionInterrupted`](crate::Error::SubscriptionInterrupted) item once its subscriptions are restored,
+so that the application can catch up from a durable source.
 
 ### Simple Example
 
@@ -458,31 +553,100 @@ async fn main() -> Result<()> {
 ```
 */
 
+mod async_transport;
+#[cfg_attr(docsrs, doc(cfg(feature = "chaos")))]
+#[cfg(feature = "chaos")]
+mod chaos_config;
 #[allow(clippy::module_inception)]
 mod client;
 mod client_state;
+mod client_stats;
 mod client_tracking_invalidation_stream;
+mod command_info_cache;
+mod command_journal;
+mod command_layer;
+#[cfg(any(
+    feature = "compression-gzip",
+    feature = "compression-zstd",
+    feature = "compression-lz4"
+))]
+mod compression_layer;
 mod config;
+mod config_builder;
+mod config_env;
+mod connection_event;
+mod default_options;
+mod delete_keys;
+mod keyspace_notifications;
+mod legacy_shims;
 mod message;
+#[cfg_attr(docsrs, doc(cfg(feature = "mock")))]
+#[cfg(feature = "mock")]
+mod mock_server_behavior;
 mod monitor_stream;
+#[cfg_attr(docsrs, doc(cfg(feature = "otel-metrics")))]
+#[cfg(feature = "otel-metrics")]
+mod otel_metrics;
+mod pending_commands;
+mod permissions_report;
 mod pipeline;
 #[cfg_attr(docsrs, doc(cfg(feature = "pool")))]
 #[cfg(feature = "pool")]
 mod pooled_client_manager;
 mod prepared_command;
 mod pub_sub_stream;
+mod request_coalescer;
 mod transaction;
+mod typed_pub_sub_stream;
 
+pub use async_transport::*;
+#[cfg_attr(docsrs, doc(cfg(feature = "chaos")))]
+#[cfg(feature = "chaos")]
+pub use chaos_config::*;
 pub use client::*;
 pub use client_state::*;
+pub use client_stats::*;
 pub(crate) use client_tracking_invalidation_stream::*;
+pub use command_info_cache::*;
+pub use command_journal::*;
+pub use command_layer::*;
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(
+        feature = "compression-gzip",
+        feature = "compression-zstd",
+        feature = "compression-lz4"
+    )))
+)]
+#[cfg(any(
+    feature = "compression-gzip",
+    feature = "compression-zstd",
+    feature = "compression-lz4"
+))]
+pub use compression_layer::*;
 pub use config::*;
+pub use config_builder::*;
+pub use connection_event::*;
+pub use default_options::*;
+pub use delete_keys::*;
+pub use keyspace_notifications::*;
+pub(crate) use legacy_shims::*;
 pub(crate) use message::*;
+#[cfg_attr(docsrs, doc(cfg(feature = "mock")))]
+#[cfg(feature = "mock")]
+pub use mock_server_behavior::*;
 pub use monitor_stream::*;
+#[cfg_attr(docsrs, doc(cfg(feature = "otel-metrics")))]
+#[cfg(feature = "otel-metrics")]
+pub use otel_metrics::*;
+pub(crate) use pending_commands::*;
+pub use permissions_report::*;
 pub use pipeline::*;
 #[cfg_attr(docsrs, doc(cfg(feature = "pool")))]
 #[cfg(feature = "pool")]
 pub use pooled_client_manager::*;
 pub use prepared_command::*;
 pub use pub_sub_stream::*;
+pub(crate) use request_coalescer::*;
 pub use transaction::*;
+pub use typed_pub_sub_stream::*;
@@ -0,0 +1,317 @@
+#[cfg(feature = "pool")]
+use crate::client::PooledClientManager;
+use crate::{
+    client::{Client, CommandLayer},
+    network::StatsRegistry,
+    resp::{Command, RespBuf},
+    Error, Result,
+};
+#[cfg(feature = "pool")]
+use opentelemetry::metrics::ObservableGauge;
+use opentelemetry::{
+    global,
+    metrics::{Counter, Histogram, Meter, ObservableCounter},
+    KeyValue,
+};
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex, OnceLock, Weak},
+    time::Duration,
+};
+
+/// Name of the meter of [`OtelMetrics::global`]
+const METER_NAME: &str = "rustis";
+
+/// Export of the metrics of **rustis** clients to [OpenTelemetry](https://opentelemetry.io/docs/specs/otel/metrics/api/)
+///
+/// As a [`CommandLayer`], it records for each command sent:
+/// * `rustis.commands` - counter of the commands sent
+/// * `rustis.errors` - counter of the commands which have failed, with an `error.type` attribute
+/// * `rustis.command.duration` - histogram of the duration of the commands, in seconds
+///
+/// These instruments have a `db.operation.name` attribute holding the name of the command.
+///
+/// The counters of the clients observed with [`observe_client`](OtelMetrics::observe_client)
+/// are exported with a `server.address` attribute:
+/// * `rustis.node.reconnects` - counter of the reconnections to a node
+/// * `rustis.node.redirections` - counter of the redirections (`MOVED`, `ASK`, `TRYAGAIN`) received from a node
+/// * `rustis.node.bytes_received` - counter of the bytes received from a node
+/// * `rustis.node.bytes_sent` - counter of the bytes sent to a node
+///
+/// The connections of the pools observed with [`observe_pool`](OtelMetrics::observe_pool)
+/// are exported as the `rustis.pool.connections` & `rustis.pool.idle_connections` gauges.
+///
+/// The simplest way to export the metrics of a client is to enable [`Config::otel_metrics`](crate::client::Config::otel_metrics),
+/// which registers the [`global`](OtelMetrics::global) instance as a layer and observes the client.
+///
+/// # Example
+/// ```
+/// use rustis::{
+///     client::Client,
+///     commands::StringCommands,
+///     Result,
+/// };
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     // the global meter provider of OpenTelemetry must be set beforehand
+///     let client = Client::connect("127.0.0.1:6379?otel_metrics=true").await?;
+///     client.set("key", "value").await?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct OtelMetrics {
+    inner: Arc<OtelMetricsInner>,
+}
+
+struct OtelMetricsInner {
+    commands: Counter<u64>,
+    errors: Counter<u64>,
+    duration: Histogram<f64>,
+    registries: Arc<Mutex<Vec<Weak<StatsRegistry>>>>,
+    _node_counters: Vec<ObservableCounter<u64>>,
+    #[cfg(feature = "pool")]
+    pools: Arc<Mutex<Vec<bb8::Pool<PooledClientManager>>>>,
+    #[cfg(feature = "pool")]
+    _pool_gauges: Vec<ObservableGauge<u64>>,
+}
+
+impl OtelMetrics {
+    /// Registers the instruments of **rustis** with `meter`
+    pub fn new(meter: Meter) -> Self {
+        let commands = meter
+            .u64_counter("rustis.commands")
+            .with_description("Number of commands sent")
+            .build();
+        let errors = meter
+            .u64_counter("rustis.errors")
+            .with_description("Number of commands which have failed")
+            .build();
+        let duration = meter
+            .f64_histogram("rustis.command.duration")
+            .with_description("Duration of the commands")
+            .with_unit("s")
+            .build();
+
+        let registries = Arc::new(Mutex::new(Vec::<Weak<StatsRegistry>>::new()));
+        let node_counters = [
+            (
+                "rustis.node.reconnects",
+                "Number of reconnections to a node",
+                "",
+                NodeCounter::Reconnects,
+            ),
+            (
+                "rustis.node.redirections",
+                "Number of redirections received from a node",
+                "",
+                NodeCounter::Redirections,
+            ),
+            (
+                "rustis.node.bytes_received",
+                "Number of bytes received from a node",
+                "By",
+                NodeCounter::BytesReceived,
+            ),
+            (
+                "rustis.node.bytes_sent",
+                "Number of bytes sent to a node",
+                "By",
+                NodeCounter::BytesSent,
+            ),
+        ]
+        .into_iter()
+        .map(|(name, description, unit, counter)| {
+            let registries = registries.clone();
+            meter
+                .u64_observable_counter(name)
+                .with_description(description)
+                .with_unit(unit)
+                .with_callback(move |observer| {
+                    for (address, value) in collect_node_counter(&registries, counter) {
+                        observer.observe(value, &[KeyValue::new("server.address", address)]);
+                    }
+                })
+                .build()
+        })
+        .collect();
+
+        #[cfg(feature = "pool")]
+        let pools = Arc::new(Mutex::new(Vec::<bb8::Pool<PooledClientManager>>::new()));
+        #[cfg(feature = "pool")]
+        let pool_gauges = [
+            (
+                "rustis.pool.connections",
+                "Number of connections of the pools",
+                false,
+            ),
+            (
+                "rustis.pool.idle_connections",
+                "Number of idle connections of the pools",
+                true,
+            ),
+        ]
+        .into_iter()
+        .map(|(name, description, idle)| {
+            let pools = pools.clone();
+            meter
+                .u64_observable_gauge(name)
+                .with_description(description)
+                .with_callback(move |observer| {
+                    let value = pools
+                        .lock()
+                        .unwrap()
+                        .iter()
+                        .map(|pool| {
+                            let state = pool.state();
+                            u64::from(if idle {
+                                state.idle_connections
+                            } else {
+                                state.connections
+                            })
+                        })
+                        .sum();
+                    observer.observe(value, &[]);
+                })
+                .build()
+        })
+        .collect();
+
+        Self {
+            inner: Arc::new(OtelMetricsInner {
+                commands,
+                errors,
+                duration,
+                registries,
+                _node_counters: node_counters,
+                #[cfg(feature = "pool")]
+                pools,
+                #[cfg(feature = "pool")]
+                _pool_gauges: pool_gauges,
+            }),
+        }
+    }
+
+    /// Process-wide instance, registered with the `rustis` meter of the global meter provider
+    ///
+    /// The global meter provider must be set with
+    /// [`opentelemetry::global::set_meter_provider`] before the first call.
+    pub fn global() -> Self {
+        static GLOBAL: OnceLock<OtelMetrics> = OnceLock::new();
+        GLOBAL
+            .get_or_init(|| OtelMetrics::new(global::meter(METER_NAME)))
+            .clone()
+    }
+
+    /// Exports the counters of the nodes `client` is connected to
+    ///
+    /// The counters are shared by the clones of `client`
+    /// and stop being exported once all of them are dropped.
+    pub fn observe_client(&self, client: &Client) {
+        self.observe_registry(client.stats_registry());
+    }
+
+    pub(crate) fn observe_registry(&self, registry: &Arc<StatsRegistry>) {
+        let mut registries = self.inner.registries.lock().unwrap();
+        registries.retain(|registry| registry.strong_count() > 0);
+        registries.push(Arc::downgrade(registry));
+    }
+
+    /// Exports the number of connections of `pool`
+    ///
+    /// The gauges report the sum of the connections of all the observed pools.
+    /// A clone of `pool` is kept alive as long as this instance.
+    #[cfg_attr(docsrs, doc(cfg(feature = "pool")))]
+    #[cfg(feature = "pool")]
+    pub fn observe_pool(&self, pool: &bb8::Pool<PooledClientManager>) {
+        self.inner.pools.lock().unwrap().push(pool.clone());
+    }
+}
+
+impl CommandLayer for OtelMetrics {
+    fn on_timed_result(&self, command: &Command, result: &Result<RespBuf>, elapsed: Duration) {
+        let attributes = [KeyValue::new("db.operation.name", command.name)];
+        self.inner.commands.add(1, &attributes);
+        self.inner
+            .duration
+            .record(elapsed.as_secs_f64(), &attributes);
+
+        let error = match result {
+            Ok(resp_buf) if resp_buf.is_error() => Some("redis"),
+            Ok(_) => None,
+            Err(e) => Some(error_type(e)),
+        };
+        if let Some(error) = error {
+            self.inner.errors.add(
+                1,
+                &[
+                    KeyValue::new("db.operation.name", command.name),
+                    KeyValue::new("error.type", error),
+                ],
+            );
+        }
+    }
+}
+
+impl fmt::Debug for OtelMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OtelMetrics").finish_non_exhaustive()
+    }
+}
+
+/// Counter of a node exported by an observable counter
+#[derive(Clone, Copy)]
+enum NodeCounter {
+    Reconnects,
+    Redirections,
+    BytesReceived,
+    BytesSent,
+}
+
+/// Values of `counter` for each node address, summed over the live registries
+fn collect_node_counter(
+    registries: &Mutex<Vec<Weak<StatsRegistry>>>,
+    counter: NodeCounter,
+) -> HashMap<String, u64> {
+    let mut values = HashMap::<String, u64>::new();
+    for registry in registries.lock().unwrap().iter() {
+        let Some(registry) = registry.upgrade() else {
+            continue;
+        };
+
+        for node in registry.snapshot().nodes {
+            let value = match counter {
+                NodeCounter::Reconnects => node.reconnects,
+                NodeCounter::Redirections => node.retries,
+                NodeCounter::BytesReceived => node.bytes_in,
+                NodeCounter::BytesSent => node.bytes_out,
+            };
+            *values.entry(node.address).or_default() += value;
+        }
+    }
+    values
+}
+
+/// Low-cardinality type of an error, for the `error.type` attribute
+fn error_type(error: &Error) -> &'static str {
+    match error {
+        Error::Client(_) => "client",
+        Error::Config(_) => "config",
+        Error::Aborted => "aborted",
+        Error::Sentinel(_) => "sentinel",
+        Error::Redis(_) => "redis",
+        Error::IO(_) => "io",
+        #[cfg(feature = "tls")]
+        Error::Tls(_) => "tls",
+        Error::Timeout(_) => "timeout",
+        Error::QueueFull => "queue_full",
+        Error::UnsupportedByServer { .. } => "unsupported_by_server",
+        Error::SubscriptionInterrupted { .. } => "subscription_interrupted",
+        Error::Journaled { error, .. } | Error::Context { error, .. } => error_type(error),
+        _ => "other",
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{timeout, Error, Result};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore, TryAcquireError};
+
+/// Tracks the commands sent by a client and its clones, and not yet replied.
+///
+/// When bounded, see [`Config::max_pending_commands`](crate::client::Config::max_pending_commands),
+/// senders wait for a slot or fail with [`Error::QueueFull`].
+#[derive(Debug)]
+pub(crate) struct PendingCommands {
+    count: Arc<AtomicUsize>,
+    semaphore: Option<Arc<Semaphore>>,
+    max_pending_commands: usize,
+    fail_on_queue_full: bool,
+}
+
+impl PendingCommands {
+    pub fn new(max_pending_commands: usize, fail_on_queue_full: bool) -> Self {
+        Self {
+            count: Arc::new(AtomicUsize::new(0)),
+            semaphore: (max_pending_commands > 0)
+                .then(|| Arc::new(Semaphore::new(max_pending_commands))),
+            max_pending_commands,
+            fail_on_queue_full,
+        }
+    }
+
+    /// Number of commands sent and not yet replied
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// Reserves slots for `num_commands` commands, waiting for free slots if needed
+    ///
+    /// The wait is bounded by `command_timeout` ([`Error::Timeout`]), unless it is zero.
+    pub async fn acquire(
+        &self,
+        num_commands: usize,
+        command_timeout: Duration,
+    ) -> Result<PendingCommandsGuard> {
+        let permit = match &self.semaphore {
+            Some(semaphore) if !self.fail_on_queue_full => {
+                let acquire = semaphore
+                    .clone()
+                    .acquire_many_owned(self.num_permits(num_commands));
+                let permit = if command_timeout != Duration::ZERO {
+                    timeout(command_timeout, acquire).await?
+                } else {
+                    acquire.await
+                };
+                Some(permit.map_err(|e| Error::Client(e.to_string()))?)
+            }
+            _ => return self.try_acquire(num_commands),
+        };
+
+        Ok(self.guard(num_commands, permit))
+    }
+
+    /// Reserves slots for `num_commands` commands, failing with [`Error::QueueFull`] if there is not enough free slots
+    pub fn try_acquire(&self, num_commands: usize) -> Result<PendingCommandsGuard> {
+        let permit = match &self.semaphore {
+            Some(semaphore) => match semaphore
+                .clone()
+                .try_acquire_many_owned(self.num_permits(num_commands))
+            {
+                Ok(permit) => Some(permit),
+                Err(TryAcquireError::NoPermits) => return Err(Error::QueueFull),
+                Err(e) => return Err(Error::Client(e.to_string())),
+            },
+            None => None,
+        };
+
+        Ok(self.guard(num_commands, permit))
+    }
+
+    /// a batch larger than the bound takes the whole queue
+    fn num_permits(&self, num_commands: usize) -> u32 {
+        num_commands.clamp(1, self.max_pending_commands) as u32
+    }
+
+    fn guard(
+        &self,
+        num_commands: usize,
+        permit: Option<OwnedSemaphorePermit>,
+    ) -> PendingCommandsGuard {
+        self.count.fetch_add(num_commands, Ordering::Relaxed);
+        PendingCommandsGuard {
+            count: self.count.clone(),
+            num_commands,
+            _permit: permit,
+        }
+    }
+}
+
+/// Releases the slots of a message when it is dropped,
+/// whatever the outcome of the message (reply, error, disconnection).
+#[derive(Debug)]
+pub(crate) struct PendingCommandsGuard {
+    count: Arc<AtomicUsize>,
+    num_commands: usize,
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl Drop for PendingCommandsGuard {
+    fn drop(&mut self) {
+        self.count.fetch_sub(self.num_commands, Ordering::Relaxed);
+    }
+}
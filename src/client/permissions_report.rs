@@ -0,0 +1,111 @@
+use crate::{client::has_sensitive_args, resp::Command, RedisError};
+use std::fmt;
+
+const REDACTED: &str = "***";
+
+/// Permission of the current user to run a command,
+/// as reported by [`ACL DRYRUN`](https://redis.io/commands/acl-dryrun/)
+#[derive(Debug)]
+pub enum CommandPermission {
+    /// The command can be run
+    Allowed,
+    /// The command is denied, with the reason given by the server
+    Denied(String),
+    /// The command cannot be checked (e.g. unknown command or wrong number of arguments)
+    Invalid(RedisError),
+}
+
+/// Permission of the current user to run a command of a [`PermissionsReport`]
+#[derive(Debug)]
+pub struct CommandPermissionProbe {
+    /// Probed command with its arguments, e.g. `SET cache:1 value`.
+    ///
+    /// The arguments of `AUTH`, `HELLO`, `MIGRATE`, `ACL` & `CONFIG` are redacted.
+    pub command: String,
+    /// Permission of the user to run the command
+    pub permission: CommandPermission,
+}
+
+impl CommandPermissionProbe {
+    pub(crate) fn new(command: &Command, permission: CommandPermission) -> Self {
+        let redact = has_sensitive_args(command.name);
+        let mut text = command.name.to_owned();
+        for arg in &command.args {
+            text.push(' ');
+            if redact {
+                text.push_str(REDACTED);
+            } else {
+                text.push_str(&String::from_utf8_lossy(arg));
+            }
+        }
+
+        Self {
+            command: text,
+            permission,
+        }
+    }
+}
+
+/// Report of [`Client::probe_permissions`](crate::client::Client::probe_permissions)
+///
+/// Its [`Display`](fmt::Display) implementation lists the commands which cannot be run,
+/// which makes it suitable for the error message of a failed startup.
+#[derive(Debug)]
+pub struct PermissionsReport {
+    /// User of the connection, as returned by [`ACL WHOAMI`](https://redis.io/commands/acl-whoami/)
+    pub user: String,
+    /// Permission of each probed command, in the probing order
+    pub commands: Vec<CommandPermissionProbe>,
+}
+
+impl PermissionsReport {
+    /// `true` if all the probed commands can be run
+    #[must_use]
+    pub fn is_allowed(&self) -> bool {
+        self.commands
+            .iter()
+            .all(|probe| matches!(probe.permission, CommandPermission::Allowed))
+    }
+
+    /// Probed commands which cannot be run: denied or invalid commands
+    pub fn denied(&self) -> impl Iterator<Item = &CommandPermissionProbe> {
+        self.commands
+            .iter()
+            .filter(|probe| !matches!(probe.permission, CommandPermission::Allowed))
+    }
+}
+
+impl fmt::Display for PermissionsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let num_denied = self.denied().count();
+        if num_denied == 0 {
+            return write!(
+                f,
+                "user `{}` can run the {} probed commands",
+                self.user,
+                self.commands.len()
+            );
+        }
+
+        write!(
+            f,
+            "user `{}` cannot run {num_denied} of the {} probed commands",
+            self.user,
+            self.commands.len()
+        )?;
+
+        for probe in self.denied() {
+            match &probe.permission {
+                CommandPermission::Denied(reason) => {
+                    write!(f, "\n- `{}`: {reason}", probe.command)?
+                }
+                CommandPermission::Invalid(error) => {
+                    write!(f, "\n- `{}`: invalid probe: {error}", probe.command)?
+                }
+                CommandPermission::Allowed => (),
+            }
+        }
+
+        Ok(())
+    }
+}
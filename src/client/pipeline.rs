@@ -15,9 +15,9 @@ use crate::{
     commands::{
         BitmapCommands, ClusterCommands, ConnectionCommands, GenericCommands, GeoCommands,
         HashCommands, HyperLogLogCommands, ListCommands, ScriptingCommands, ServerCommands,
-        SetCommands, SortedSetCommands, StreamCommands, StringCommands,
+        SetCommands, SortedSetCommands, StreamCommands, StringCommands, VectorSetCommands,
     },
-    resp::{Command, RespBatchDeserializer, Response},
+    resp::{calculate_buf_size, Command, RespBatchDeserializer, RespBuf, Response},
     Result,
 };
 use serde::de::DeserializeOwned;
@@ -42,7 +42,7 @@ impl<'a> Pipeline<'a> {
     }
     /// Set a flag to override default `retry_on_error` behavior.
     ///
-    /// See [Config::retry_on_error](crate::client::Config::retry_on_error)
+    /// See [ResiliencePolicy::retry_on](crate::client::ResiliencePolicy::retry_on)
     pub fn retry_on_error(&mut self, retry_on_error: bool) {
         self.retry_on_error = Some(retry_on_error);
     }
@@ -62,6 +62,11 @@ impl<'a> Pipeline<'a> {
     /// Execute the pipeline by the sending the queued command
     /// as a whole batch to the Redis server.
     ///
+    /// If [`Config::max_batch_commands`](crate::client::Config::max_batch_commands)
+    /// or [`Config::max_batch_bytes`](crate::client::Config::max_batch_bytes) are set,
+    /// the queued commands are split into several batches, like with
+    /// [`execute_chunked`](Pipeline::execute_chunked).
+    ///
     /// # Return
     /// It is the caller responsability to use the right type to cast the server response
     /// to the right tuple or collection depending on which command has been
@@ -96,27 +101,121 @@ impl<'a> Pipeline<'a> {
     /// }
     /// ```    
     pub async fn execute<T: DeserializeOwned>(self) -> Result<T> {
+        let (max_commands, max_bytes) = self.client.batch_limits();
+        self.execute_chunked(max_commands, max_bytes).await
+    }
+
+    /// Execute the pipeline by sending the queued commands
+    /// in several sequential batches to the Redis server.
+    ///
+    /// Each batch holds at most `max_commands` commands and `max_bytes` bytes of encoded commands,
+    /// so that huge pipelines do not exceed the server query buffer limit.
+    /// `0` means no limit. A single command bigger than `max_bytes` is sent in its own batch.
+    ///
+    /// Results are returned in the order of the queued commands, exactly as with [`execute`](Pipeline::execute).
+    ///
+    /// # Example
+    /// ```
+    /// use rustis::{
+    ///     client::{Client, Pipeline, BatchPreparedCommand},
+    ///     commands::StringCommands,
+    ///     Result,
+    /// };
+    ///
+    /// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+    /// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+    /// async fn main() -> Result<()> {
+    ///     let client = Client::connect("127.0.0.1:6379").await?;
+    ///
+    ///     let mut pipeline = client.create_pipeline();
+    ///     for i in 0..1000 {
+    ///         pipeline.set(format!("key{i}"), i).forget();
+    ///     }
+    ///     pipeline.get::<_, u32>("key999").queue();
+    ///
+    ///     let value: u32 = pipeline.execute_chunked(100, 0).await?;
+    ///     assert_eq!(999, value);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn execute_chunked<T: DeserializeOwned>(
+        self,
+        max_commands: usize,
+        max_bytes: usize,
+    ) -> Result<T> {
         let num_commands = self.commands.len();
-        let results = self
-            .client
-            .send_batch(self.commands, self.retry_on_error)
-            .await?;
-
-        if num_commands > 1 {
-            let mut filtered_results = zip(results, self.forget_flags.iter())
-                .filter_map(|(value, forget_flag)| if *forget_flag { None } else { Some(value) })
-                .collect::<Vec<_>>();
-
-            if filtered_results.len() == 1 {
-                let result = filtered_results.pop().unwrap();
-                result.to()
-            } else {
-                let deserializer = RespBatchDeserializer::new(&filtered_results);
-                T::deserialize(&deserializer)
+        let results = if (max_commands == 0 || num_commands <= max_commands) && max_bytes == 0 {
+            self.client
+                .send_batch(self.commands, self.retry_on_error)
+                .await?
+        } else {
+            let mut results = Vec::with_capacity(num_commands);
+            for chunk in split_commands(self.commands, max_commands, max_bytes) {
+                results.extend(self.client.send_batch(chunk, self.retry_on_error).await?);
             }
+            results
+        };
+
+        deserialize_results(results, &self.forget_flags)
+    }
+}
+
+/// Split `commands` into batches of at most `max_commands` commands & `max_bytes` encoded bytes
+pub(crate) fn split_commands(
+    commands: Vec<Command>,
+    max_commands: usize,
+    max_bytes: usize,
+) -> Vec<Vec<Command>> {
+    let mut chunks = Vec::new();
+    let mut chunk = Vec::new();
+    let mut chunk_bytes = 0;
+
+    for command in commands {
+        let command_bytes = if max_bytes > 0 {
+            calculate_buf_size(&command, false)
+        } else {
+            0
+        };
+
+        let chunk_full = !chunk.is_empty()
+            && ((max_commands > 0 && chunk.len() >= max_commands)
+                || (max_bytes > 0 && chunk_bytes + command_bytes > max_bytes));
+
+        if chunk_full {
+            chunks.push(std::mem::take(&mut chunk));
+            chunk_bytes = 0;
+        }
+
+        chunk_bytes += command_bytes;
+        chunk.push(command);
+    }
+
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+
+    chunks
+}
+
+fn deserialize_results<T: DeserializeOwned>(
+    mut results: Vec<RespBuf>,
+    forget_flags: &[bool],
+) -> Result<T> {
+    if results.len() > 1 {
+        let mut filtered_results = zip(results, forget_flags.iter())
+            .filter_map(|(value, forget_flag)| if *forget_flag { None } else { Some(value) })
+            .collect::<Vec<_>>();
+
+        if filtered_results.len() == 1 {
+            let result = filtered_results.pop().unwrap();
+            result.to()
         } else {
-            results[0].to()
+            let deserializer = RespBatchDeserializer::new(&filtered_results);
+            T::deserialize(&deserializer)
         }
+    } else {
+        results.remove(0).to()
     }
 }
 
@@ -186,3 +285,4 @@ impl<'a, 'b> TimeSeriesCommands<'a> for &'a mut Pipeline<'b> {}
 #[cfg_attr(docsrs, doc(cfg(feature = "redis-bloom")))]
 #[cfg(feature = "redis-bloom")]
 impl<'a, 'b> TopKCommands<'a> for &'a mut Pipeline<'b> {}
+impl<'a, 'b> VectorSetCommands<'a> for &'a mut Pipeline<'b> {}
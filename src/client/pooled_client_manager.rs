@@ -1,9 +1,11 @@
 use crate::{
-    client::{Client, Config, IntoConfig},
-    commands::ConnectionCommands,
+    client::{Client, Config, IntoConfig, ServerConfig},
+    commands::{ClientInfo, ClientKillOptions, ClientListOptions, ClientType, ConnectionCommands},
     Error, Future, Result,
 };
 use bb8::ManageConnection;
+use log::info;
+use std::{collections::HashMap, time::Duration};
 
 /// An object which manages a pool of clients, based on [bb8](https://docs.rs/bb8/latest/bb8/)
 pub struct PooledClientManager {
@@ -16,6 +18,128 @@ impl PooledClientManager {
             config: config.into_config()?,
         })
     }
+
+    /// Close the leaked connections of the pool on the server
+    ///
+    /// The connections of the pool are the normal connections (see [`ClientType::Normal`])
+    /// named after the [`connection_name`](Config::connection_name) of the manager,
+    /// followed or not by the [`connection_metadata`](Config::connection_metadata),
+    /// which are neither subscribed nor blocked.
+    /// Such a connection is considered as leaked when it has been idle for `min_idle` at least.
+    ///
+    /// `min_idle` should be greater than the `idle_timeout` of the pool and than the longest idle time
+    /// of a connection in use, so that the live connections of the pool are not closed.
+    /// All the instances of a service configured with the same connection name are swept.
+    ///
+    /// # Return
+    /// The number of closed connections.
+    ///
+    /// # Errors
+    /// An error is returned if no connection name is configured, or for a cluster configuration.
+    pub async fn sweep_orphans(&self, min_idle: Duration) -> Result<usize> {
+        let min_idle = min_idle.as_secs();
+        self.sweep(|connections| {
+            connections
+                .into_iter()
+                .filter(|client_info| u64::from(client_info.idle) >= min_idle)
+                .collect()
+        })
+        .await
+    }
+
+    /// Close the duplicate connections of the pool on the server
+    ///
+    /// The connections of the pool are matched as in [`sweep_orphans`](PooledClientManager::sweep_orphans),
+    /// and grouped by client host: a host holding more than `max_connections_per_host` connections,
+    /// e.g. the `max_size` of its pool, has duplicate connections.
+    /// The most recently active connections of each host are kept, the most idle ones are closed.
+    ///
+    /// # Return
+    /// The number of closed connections.
+    ///
+    /// # Errors
+    /// An error is returned if no connection name is configured, or for a cluster configuration.
+    pub async fn sweep_duplicates(&self, max_connections_per_host: usize) -> Result<usize> {
+        self.sweep(|connections| {
+            let mut connections_by_host = HashMap::<&str, Vec<&ClientInfo>>::new();
+            for client_info in connections {
+                let host = client_info
+                    .addr
+                    .rsplit_once(':')
+                    .map_or(client_info.addr.as_str(), |(host, _port)| host);
+                connections_by_host
+                    .entry(host)
+                    .or_default()
+                    .push(client_info);
+            }
+
+            connections_by_host
+                .into_values()
+                .flat_map(|mut connections| {
+                    connections.sort_by_key(|client_info| client_info.idle);
+                    connections.into_iter().skip(max_connections_per_host)
+                })
+                .collect()
+        })
+        .await
+    }
+
+    /// Close the connections of the pool picked by `select`
+    async fn sweep(
+        &self,
+        select: impl for<'a> FnOnce(Vec<&'a ClientInfo>) -> Vec<&'a ClientInfo>,
+    ) -> Result<usize> {
+        if self.config.connection_name.is_empty() {
+            return Err(Error::Client(
+                "Cannot sweep connections without a connection name".to_owned(),
+            ));
+        }
+
+        if let ServerConfig::Cluster(_) = &self.config.server {
+            return Err(Error::Client(
+                "Cannot sweep connections of a cluster".to_owned(),
+            ));
+        }
+
+        let client = Client::connect(self.config.clone()).await?;
+        let client_id = client.client_id().await?;
+        let client_list = client
+            .client_list(ClientListOptions::default().client_type(ClientType::Normal))
+            .await?;
+
+        let connections = client_list
+            .client_infos
+            .iter()
+            .filter(|client_info| {
+                client_info.id != client_id
+                    && self.is_pool_connection(&client_info.name)
+                    && client_info.sub + client_info.psub + client_info.ssub == 0
+                    && !client_info.flags.contains('b')
+            })
+            .collect();
+
+        let mut num_killed = 0;
+        for client_info in select(connections) {
+            info!(
+                "Closing connection {} from {} (idle for {}s)",
+                client_info.name, client_info.addr, client_info.idle
+            );
+            num_killed += client
+                .client_kill(ClientKillOptions::default().id(client_info.id))
+                .await?;
+        }
+
+        client.close().await?;
+
+        Ok(num_killed)
+    }
+
+    /// `true` if `name` is the connection name of the manager, followed or not by metadata,
+    /// so that the connections of `api2` are not matched by the connection name `api`
+    fn is_pool_connection(&self, name: &str) -> bool {
+        name.strip_prefix(self.config.connection_name.as_str())
+            .is_some_and(|metadata| metadata.is_empty() || metadata.starts_with('('))
+    }
 }
 
 impl ManageConnection for PooledClientManager {
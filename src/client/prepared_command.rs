@@ -1,9 +1,9 @@
 use crate::{
-    client::Client,
+    client::{Client, RetryOn},
     resp::{Command, RespBuf, Response},
     Future,
 };
-use std::marker::PhantomData;
+use std::{marker::PhantomData, time::Duration};
 
 type CustomConverter<'a, R> = dyn Fn(RespBuf, Command, &'a Client) -> Future<'a, R> + Send + Sync;
 
@@ -24,6 +24,8 @@ where
     pub custom_converter: Option<Box<CustomConverter<'a, R>>>,
     /// Flag to retry sending the command on network error.
     pub retry_on_error: Option<bool>,
+    /// Timeout of the command, overriding the one of the client.
+    pub(crate) command_timeout: Option<Duration>,
 }
 
 impl<'a, E, R> PreparedCommand<'a, E, R>
@@ -39,6 +41,7 @@ where
             command,
             custom_converter: None,
             retry_on_error: None,
+            command_timeout: None,
         }
     }
 
@@ -50,12 +53,29 @@ where
 
     /// Set a flag to override default `retry_on_error` behavior.
     ///
-    /// See [Config::retry_on_error](crate::client::Config::retry_on_error)
+    /// See [ResiliencePolicy::retry_on](crate::client::ResiliencePolicy::retry_on)
     pub fn retry_on_error(mut self, retry_on_error: bool) -> Self {
         self.retry_on_error = Some(retry_on_error);
         self
     }
 
+    /// Override the [`retry_on`](crate::client::ResiliencePolicy::retry_on) policy of the client
+    /// for this command.
+    pub fn retry_on(mut self, retry_on: RetryOn) -> Self {
+        self.retry_on_error = Some(retry_on.retry_on_error());
+        self
+    }
+
+    /// Override the [`command_timeout`](crate::client::ResiliencePolicy::command_timeout)
+    /// of the client for this command, `Duration::ZERO` for no timeout.
+    ///
+    /// Only applies to a command sent directly by a [`Client`],
+    /// the commands of a pipeline or a transaction share the timeout of the client.
+    pub fn command_timeout(mut self, command_timeout: Duration) -> Self {
+        self.command_timeout = Some(command_timeout);
+        self
+    }
+
     /// Get a reference to the command to send
     pub fn command(&self) -> &Command {
         &self.command
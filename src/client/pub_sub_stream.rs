@@ -2,10 +2,11 @@ use crate::{
     client::{Client, ClientPreparedCommand},
     commands::InternalPubSubCommands,
     network::PubSubSender,
-    resp::{ByteBufSeed, CommandArgs, SingleArg, SingleArgCollection},
-    Error, PubSubReceiver, Result,
+    resp::{cmd, ByteBufSeed, CommandArgs, SingleArg, SingleArgCollection},
+    spawn, Error, PubSubReceiver, Result,
 };
-use futures_util::{Stream, StreamExt};
+use futures_util::{select, FutureExt, Stream, StreamExt};
+use log::debug;
 use serde::{
     de::{self, Visitor},
     Deserialize,
@@ -13,11 +14,13 @@ use serde::{
 use std::{
     fmt,
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
 };
+use tokio::sync::broadcast;
 
 /// Pub/Sub Message that can be streamed from [`PubSubStream`](PubSubStream)
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PubSubMessage {
     pub pattern: Vec<u8>,
     pub channel: Vec<u8>,
@@ -86,6 +89,11 @@ impl<'de> Deserialize<'de> for PubSubMessage {
 
 /// A pub sub `Sink` part of the [`split`](PubSubStream::split) pair.
 /// It allows to subscribe/unsubscribe to/from channels or patterns
+///
+/// Each subscription or unsubscription resolves once the server has confirmed it,
+/// within the [`command_timeout`](crate::client::ResiliencePolicy::command_timeout) of the client,
+/// and returns the number of active subscriptions reported by the confirmation.
+/// This count covers the whole pub/sub connection of the client, which is shared by all its pub/sub streams.
 pub struct PubSubSplitSink {
     closed: bool,
     channels: CommandArgs,
@@ -93,11 +101,23 @@ pub struct PubSubSplitSink {
     shardchannels: CommandArgs,
     sender: PubSubSender,
     client: Client,
+    /// dedicated pub/sub connection of `client`, acquired on the first subscription
+    pub_sub_client: Option<Arc<Client>>,
 }
 
 impl PubSubSplitSink {
-    /// Subscribe to additional channels
-    pub async fn subscribe<C, CC>(&mut self, channels: CC) -> Result<()>
+    async fn pub_sub_client(&mut self) -> Result<Arc<Client>> {
+        if let Some(pub_sub_client) = &self.pub_sub_client {
+            return Ok(pub_sub_client.clone());
+        }
+
+        let pub_sub_client = self.client.get_pub_sub_client().await?;
+        self.pub_sub_client = Some(pub_sub_client.clone());
+        Ok(pub_sub_client)
+    }
+
+    /// Subscribe to additional channels and return the number of active subscriptions
+    pub async fn subscribe<C, CC>(&mut self, channels: CC) -> Result<usize>
     where
         C: SingleArg + Send,
         CC: SingleArgCollection<C>,
@@ -113,17 +133,19 @@ impl PubSubSplitSink {
             }
         }
 
-        self.client
+        let subscription_count = self
+            .pub_sub_client()
+            .await?
             .subscribe_from_pub_sub_sender(&channels, &self.sender)
             .await?;
 
         self.channels = self.channels.arg(channels).build();
 
-        Ok(())
+        Ok(subscription_count)
     }
 
-    /// Subscribe to additional patterns
-    pub async fn psubscribe<P, PP>(&mut self, patterns: PP) -> Result<()>
+    /// Subscribe to additional patterns and return the number of active subscriptions
+    pub async fn psubscribe<P, PP>(&mut self, patterns: PP) -> Result<usize>
     where
         P: SingleArg + Send,
         PP: SingleArgCollection<P>,
@@ -139,17 +161,19 @@ impl PubSubSplitSink {
             }
         }
 
-        self.client
+        let subscription_count = self
+            .pub_sub_client()
+            .await?
             .psubscribe_from_pub_sub_sender(&patterns, &self.sender)
             .await?;
 
         self.patterns = self.patterns.arg(patterns).build();
 
-        Ok(())
+        Ok(subscription_count)
     }
 
-    /// Subscribe to additional shardchannels
-    pub async fn ssubscribe<C, CC>(&mut self, shardchannels: CC) -> Result<()>
+    /// Subscribe to additional shardchannels and return the number of active subscriptions
+    pub async fn ssubscribe<C, CC>(&mut self, shardchannels: CC) -> Result<usize>
     where
         C: SingleArg + Send,
         CC: SingleArgCollection<C>,
@@ -165,17 +189,19 @@ impl PubSubSplitSink {
             }
         }
 
-        self.client
+        let subscription_count = self
+            .pub_sub_client()
+            .await?
             .ssubscribe_from_pub_sub_sender(&shardchannels, &self.sender)
             .await?;
 
         self.shardchannels = self.shardchannels.arg(shardchannels).build();
 
-        Ok(())
+        Ok(subscription_count)
     }
 
-    /// Unsubscribe from the given channels
-    pub async fn unsubscribe<C, CC>(&mut self, channels: CC) -> Result<()>
+    /// Unsubscribe from the given channels and return the number of active subscriptions
+    pub async fn unsubscribe<C, CC>(&mut self, channels: CC) -> Result<usize>
     where
         C: SingleArg + Send,
         CC: SingleArgCollection<C>,
@@ -183,13 +209,17 @@ impl PubSubSplitSink {
         let channels = CommandArgs::default().arg(channels).build();
         self.channels
             .retain(|channel| channels.iter().all(|c| c != channel));
-        self.client.unsubscribe(channels).await?;
+        let Some(pub_sub_client) = &self.pub_sub_client else {
+            return Ok(0);
+        };
 
-        Ok(())
+        pub_sub_client
+            .unsubscribe_command(cmd("UNSUBSCRIBE").arg(channels))
+            .await
     }
 
-    /// Unsubscribe from the given patterns
-    pub async fn punsubscribe<C, CC>(&mut self, patterns: CC) -> Result<()>
+    /// Unsubscribe from the given patterns and return the number of active subscriptions
+    pub async fn punsubscribe<C, CC>(&mut self, patterns: CC) -> Result<usize>
     where
         C: SingleArg + Send,
         CC: SingleArgCollection<C>,
@@ -197,23 +227,35 @@ impl PubSubSplitSink {
         let patterns = CommandArgs::default().arg(patterns).build();
         self.patterns
             .retain(|pattern| patterns.iter().all(|p| p != pattern));
-        self.client.punsubscribe(patterns).await?;
+        let Some(pub_sub_client) = &self.pub_sub_client else {
+            return Ok(0);
+        };
 
-        Ok(())
+        pub_sub_client
+            .unsubscribe_command(cmd("PUNSUBSCRIBE").arg(patterns))
+            .await
     }
 
-    /// Unsubscribe from the given patterns
-    pub async fn sunsubscribe<C, CC>(&mut self, shardchannels: CC) -> Result<()>
+    /// Unsubscribe from the given shardchannels and return the number of active subscriptions
+    pub async fn sunsubscribe<C, CC>(&mut self, shardchannels: CC) -> Result<usize>
     where
         C: SingleArg + Send,
         CC: SingleArgCollection<C>,
     {
         let shardchannels = CommandArgs::default().arg(shardchannels).build();
         self.shardchannels
-            .retain(|shardchannel| shardchannels.iter().all(|sc: &Vec<u8>| sc != shardchannel));
-        self.client.punsubscribe(shardchannels).await?;
-
-        Ok(())
+            .retain(|shardchannel| shardchannels.iter().all(|sc| sc != shardchannel));
+        let Some(pub_sub_client) = &self.pub_sub_client else {
+            return Ok(0);
+        };
+
+        pub_sub_client
+            .get_client_state_mut()
+            .shard_subscriptions_mut()
+            .unsubscribe(&shardchannels);
+        pub_sub_client
+            .unsubscribe_command(cmd("SUNSUBSCRIBE").arg(shardchannels))
+            .await
     }
 
     /// Close the stream by cancelling all subscriptions
@@ -224,22 +266,31 @@ impl PubSubSplitSink {
             return Ok(());
         }
 
+        let Some(pub_sub_client) = self.pub_sub_client.clone() else {
+            self.closed = true;
+            return Ok(());
+        };
+
         let mut channels = CommandArgs::default();
         std::mem::swap(&mut channels, &mut self.channels);
         if !channels.is_empty() {
-            self.client.unsubscribe(channels).await?;
+            pub_sub_client.unsubscribe(channels).await?;
         }
 
         let mut patterns = CommandArgs::default();
         std::mem::swap(&mut patterns, &mut self.patterns);
         if !patterns.is_empty() {
-            self.client.punsubscribe(patterns).await?;
+            pub_sub_client.punsubscribe(patterns).await?;
         }
 
         let mut shardchannels = CommandArgs::default();
         std::mem::swap(&mut shardchannels, &mut self.shardchannels);
         if !shardchannels.is_empty() {
-            self.client.sunsubscribe(shardchannels).await?;
+            pub_sub_client
+                .get_client_state_mut()
+                .shard_subscriptions_mut()
+                .unsubscribe(&shardchannels);
+            pub_sub_client.sunsubscribe(shardchannels).await?;
         }
 
         self.closed = true;
@@ -255,22 +306,30 @@ impl Drop for PubSubSplitSink {
             return;
         }
 
+        let Some(pub_sub_client) = self.pub_sub_client.take() else {
+            return;
+        };
+
         let mut channels = CommandArgs::default();
         std::mem::swap(&mut channels, &mut self.channels);
         if !channels.is_empty() {
-            let _result = self.client.unsubscribe(channels).forget();
+            let _result = pub_sub_client.unsubscribe(channels).forget();
         }
 
         let mut patterns = CommandArgs::default();
         std::mem::swap(&mut patterns, &mut self.patterns);
         if !patterns.is_empty() {
-            let _result = self.client.punsubscribe(patterns).forget();
+            let _result = pub_sub_client.punsubscribe(patterns).forget();
         }
 
         let mut shardchannels = CommandArgs::default();
         std::mem::swap(&mut shardchannels, &mut self.shardchannels);
         if !shardchannels.is_empty() {
-            let _result = self.client.sunsubscribe(shardchannels).forget();
+            pub_sub_client
+                .get_client_state_mut()
+                .shard_subscriptions_mut()
+                .unsubscribe(&shardchannels);
+            let _result = pub_sub_client.sunsubscribe(shardchannels).forget();
         }
     }
 }
@@ -279,17 +338,42 @@ impl Drop for PubSubSplitSink {
 /// It allows to get messages from the channels or patterns subscribed to
 pub struct PubSubSplitStream {
     receiver: PubSubReceiver,
+    notify_interruptions: bool,
+}
+
+impl PubSubSplitStream {
+    fn new(receiver: PubSubReceiver) -> Self {
+        Self {
+            receiver,
+            notify_interruptions: false,
+        }
+    }
+
+    /// See [`PubSubStream::notify_interruptions`]
+    #[must_use]
+    pub fn notify_interruptions(mut self, notify: bool) -> Self {
+        self.notify_interruptions = notify;
+        self
+    }
 }
 
 impl Stream for PubSubSplitStream {
     type Item = Result<PubSubMessage>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
-        match self.get_mut().receiver.poll_next_unpin(cx) {
-            Poll::Ready(Some(Ok(message))) => Poll::Ready(Some(message.to())),
-            Poll::Ready(None) => Poll::Ready(None),
-            Poll::Ready(Some(Err(e))) => Poll::Ready(Some(Err(e))),
-            Poll::Pending => Poll::Pending,
+        let this = self.get_mut();
+        loop {
+            match this.receiver.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(message))) => return Poll::Ready(Some(message.to())),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Ready(Some(Err(Error::SubscriptionInterrupted { .. })))
+                    if !this.notify_interruptions =>
+                {
+                    continue
+                }
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+                Poll::Pending => return Poll::Pending,
+            }
         }
     }
 }
@@ -335,6 +419,7 @@ pub struct PubSubStream {
 
 impl PubSubStream {
     pub(crate) fn new(sender: PubSubSender, receiver: PubSubReceiver, client: Client) -> Self {
+        let pub_sub_client = None;
         Self {
             split_sink: PubSubSplitSink {
                 closed: false,
@@ -343,8 +428,9 @@ impl PubSubStream {
                 shardchannels: CommandArgs::default(),
                 sender,
                 client,
+                pub_sub_client,
             },
-            split_stream: PubSubSplitStream { receiver },
+            split_stream: PubSubSplitStream::new(receiver),
         }
     }
 
@@ -353,7 +439,9 @@ impl PubSubStream {
         sender: PubSubSender,
         receiver: PubSubReceiver,
         client: Client,
+        pub_sub_client: Arc<Client>,
     ) -> Self {
+        let pub_sub_client = Some(pub_sub_client);
         Self {
             split_sink: PubSubSplitSink {
                 closed: false,
@@ -362,8 +450,9 @@ impl PubSubStream {
                 shardchannels: CommandArgs::default(),
                 sender,
                 client,
+                pub_sub_client,
             },
-            split_stream: PubSubSplitStream { receiver },
+            split_stream: PubSubSplitStream::new(receiver),
         }
     }
 
@@ -372,7 +461,9 @@ impl PubSubStream {
         sender: PubSubSender,
         receiver: PubSubReceiver,
         client: Client,
+        pub_sub_client: Arc<Client>,
     ) -> Self {
+        let pub_sub_client = Some(pub_sub_client);
         Self {
             split_sink: PubSubSplitSink {
                 closed: false,
@@ -381,8 +472,9 @@ impl PubSubStream {
                 shardchannels: CommandArgs::default(),
                 sender,
                 client,
+                pub_sub_client,
             },
-            split_stream: PubSubSplitStream { receiver },
+            split_stream: PubSubSplitStream::new(receiver),
         }
     }
 
@@ -391,7 +483,9 @@ impl PubSubStream {
         sender: PubSubSender,
         receiver: PubSubReceiver,
         client: Client,
+        pub_sub_client: Arc<Client>,
     ) -> Self {
+        let pub_sub_client = Some(pub_sub_client);
         Self {
             split_sink: PubSubSplitSink {
                 closed: false,
@@ -400,13 +494,14 @@ impl PubSubStream {
                 shardchannels,
                 sender,
                 client,
+                pub_sub_client,
             },
-            split_stream: PubSubSplitStream { receiver },
+            split_stream: PubSubSplitStream::new(receiver),
         }
     }
 
-    /// Subscribe to additional channels
-    pub async fn subscribe<C, CC>(&mut self, channels: CC) -> Result<()>
+    /// Subscribe to additional channels and return the number of active subscriptions
+    pub async fn subscribe<C, CC>(&mut self, channels: CC) -> Result<usize>
     where
         C: SingleArg + Send,
         CC: SingleArgCollection<C>,
@@ -414,8 +509,8 @@ impl PubSubStream {
         self.split_sink.subscribe(channels).await
     }
 
-    /// Subscribe to additional patterns
-    pub async fn psubscribe<P, PP>(&mut self, patterns: PP) -> Result<()>
+    /// Subscribe to additional patterns and return the number of active subscriptions
+    pub async fn psubscribe<P, PP>(&mut self, patterns: PP) -> Result<usize>
     where
         P: SingleArg + Send,
         PP: SingleArgCollection<P>,
@@ -423,8 +518,8 @@ impl PubSubStream {
         self.split_sink.psubscribe(patterns).await
     }
 
-    /// Subscribe to additional shardchannels
-    pub async fn ssubscribe<C, CC>(&mut self, shardchannels: CC) -> Result<()>
+    /// Subscribe to additional shardchannels and return the number of active subscriptions
+    pub async fn ssubscribe<C, CC>(&mut self, shardchannels: CC) -> Result<usize>
     where
         C: SingleArg + Send,
         CC: SingleArgCollection<C>,
@@ -432,8 +527,8 @@ impl PubSubStream {
         self.split_sink.ssubscribe(shardchannels).await
     }
 
-    /// Unsubscribe from the given channels
-    pub async fn unsubscribe<C, CC>(&mut self, channels: CC) -> Result<()>
+    /// Unsubscribe from the given channels and return the number of active subscriptions
+    pub async fn unsubscribe<C, CC>(&mut self, channels: CC) -> Result<usize>
     where
         C: SingleArg + Send,
         CC: SingleArgCollection<C>,
@@ -441,8 +536,8 @@ impl PubSubStream {
         self.split_sink.unsubscribe(channels).await
     }
 
-    /// Unsubscribe from the given patterns
-    pub async fn punsubscribe<C, CC>(&mut self, patterns: CC) -> Result<()>
+    /// Unsubscribe from the given patterns and return the number of active subscriptions
+    pub async fn punsubscribe<C, CC>(&mut self, patterns: CC) -> Result<usize>
     where
         C: SingleArg + Send,
         CC: SingleArgCollection<C>,
@@ -450,8 +545,8 @@ impl PubSubStream {
         self.split_sink.punsubscribe(patterns).await
     }
 
-    /// Unsubscribe from the given patterns
-    pub async fn sunsubscribe<C, CC>(&mut self, shardchannels: CC) -> Result<()>
+    /// Unsubscribe from the given shardchannels and return the number of active subscriptions
+    pub async fn sunsubscribe<C, CC>(&mut self, shardchannels: CC) -> Result<usize>
     where
         C: SingleArg + Send,
         CC: SingleArgCollection<C>,
@@ -459,6 +554,100 @@ impl PubSubStream {
         self.split_sink.sunsubscribe(shardchannels).await
     }
 
+    /// Yield an [`Error::SubscriptionInterrupted`] item each time the subscriptions
+    /// are automatically restored after a reconnection (default `false`)
+    ///
+    /// Messages published while the connection was down are lost:
+    /// the item lets applications trigger a catch-up read from a durable source
+    /// (e.g. a stream or a database) for the `since`..`resumed_at` window.
+    /// The stream keeps delivering messages after the item.
+    ///
+    /// See [`Config::auto_resubscribe`](crate::client::Config::auto_resubscribe)
+    #[must_use]
+    pub fn notify_interruptions(mut self, notify: bool) -> Self {
+        self.split_stream.notify_interruptions = notify;
+        self
+    }
+
+    /// Forward the messages of this stream to a [broadcast channel](broadcast) of `capacity` messages,
+    /// received by as many tasks as needed through [`subscribe`](broadcast::Sender::subscribe)
+    ///
+    /// Delivery is at-most-once: messages received while no receiver is subscribed are dropped,
+    /// and a receiver lagging more than `capacity` messages behind misses the oldest ones,
+    /// reported by [`RecvError::Lagged`](broadcast::error::RecvError::Lagged).
+    ///
+    /// Messages are forwarded by a background task which closes the subscriptions
+    /// as soon as all the receivers are dropped, once the returned sender is dropped too.
+    /// While the returned sender is alive without any subscribed receiver,
+    /// the subscriptions are kept until the next message is received.
+    ///
+    /// # Example
+    /// ```
+    /// use rustis::{
+    ///     client::Client,
+    ///     commands::{FlushingMode, PubSubCommands, ServerCommands},
+    ///     Result,
+    /// };
+    ///
+    /// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+    /// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+    /// async fn main() -> Result<()> {
+    ///     let pub_sub_client = Client::connect("127.0.0.1:6379").await?;
+    ///     let regular_client = Client::connect("127.0.0.1:6379").await?;
+    ///
+    ///     regular_client.flushdb(FlushingMode::Sync).await?;
+    ///
+    ///     let sender = pub_sub_client.subscribe("mychannel").await?.into_broadcast(100);
+    ///     let mut receiver1 = sender.subscribe();
+    ///     let mut receiver2 = sender.subscribe();
+    ///
+    ///     regular_client.publish("mychannel", "mymessage").await?;
+    ///
+    ///     let message = receiver1.recv().await.unwrap()?;
+    ///     assert_eq!(b"mymessage".to_vec(), message.payload);
+    ///     let message = receiver2.recv().await.unwrap()?;
+    ///     assert_eq!(b"mymessage".to_vec(), message.payload);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn into_broadcast(mut self, capacity: usize) -> broadcast::Sender<Result<PubSubMessage>> {
+        let (sender, _) = broadcast::channel(capacity);
+        let task_sender = sender.clone();
+
+        spawn(async move {
+            let sender = task_sender;
+            loop {
+                let message = if sender.receiver_count() == 0 && sender.strong_count() > 1 {
+                    // receivers can still subscribe through the returned sender
+                    self.next().await
+                } else {
+                    select! {
+                        message = self.next().fuse() => message,
+                        _ = sender.closed().fuse() => {
+                            if sender.strong_count() > 1 {
+                                continue;
+                            }
+                            break;
+                        }
+                    }
+                };
+
+                let Some(message) = message else {
+                    break;
+                };
+                // without any subscribed receiver, the message is dropped
+                let _ = sender.send(message);
+            }
+
+            if let Err(e) = self.close().await {
+                debug!("Cannot close the broadcast pub/sub stream: {e}");
+            }
+        });
+
+        sender
+    }
+
     /// Splits this object into separate [`Sink`](PubSubSplitSink) and [`Stream`](PubSubSplitStream) objects.
     /// This can be useful when you want to split ownership between tasks.
     pub fn split(self) -> (PubSubSplitSink, PubSubSplitStream) {
@@ -0,0 +1,157 @@
+use crate::{
+    network::{ResultReceiver, ResultSender},
+    resp::{Command, CommandArg, RespBuf},
+    Result,
+};
+use futures_channel::oneshot;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+/// Read-only commands which can be coalesced, sorted by name
+pub(crate) const COALESCABLE_COMMANDS: &[&str] = &[
+    "BITCOUNT",
+    "BITPOS",
+    "DUMP",
+    "EXISTS",
+    "EXPIRETIME",
+    "GEODIST",
+    "GEOHASH",
+    "GEOPOS",
+    "GET",
+    "GETBIT",
+    "GETRANGE",
+    "HEXISTS",
+    "HGET",
+    "HGETALL",
+    "HKEYS",
+    "HLEN",
+    "HMGET",
+    "HSTRLEN",
+    "HVALS",
+    "LINDEX",
+    "LLEN",
+    "LPOS",
+    "LRANGE",
+    "MGET",
+    "PEXPIRETIME",
+    "PTTL",
+    "SCARD",
+    "SISMEMBER",
+    "SMEMBERS",
+    "SMISMEMBER",
+    "STRLEN",
+    "TTL",
+    "TYPE",
+    "XLEN",
+    "XRANGE",
+    "XREVRANGE",
+    "ZCARD",
+    "ZCOUNT",
+    "ZMSCORE",
+    "ZRANGE",
+    "ZRANGEBYLEX",
+    "ZRANGEBYSCORE",
+    "ZRANK",
+    "ZREVRANGE",
+    "ZREVRANGEBYLEX",
+    "ZREVRANGEBYSCORE",
+    "ZREVRANK",
+    "ZSCORE",
+];
+
+type CoalescingKey = (&'static str, Vec<CommandArg>);
+
+/// Coalesces identical read-only commands sent concurrently by a client and its clones,
+/// see [`Config::coalesce_reads`](crate::client::Config::coalesce_reads).
+///
+/// The first caller of a command (the leader) sends it to the server,
+/// the following callers (the followers) wait for the reply of the leader.
+#[derive(Debug, Default)]
+pub(crate) struct RequestCoalescer {
+    /// followers of the in-flight commands, by command
+    in_flight: Mutex<HashMap<CoalescingKey, Vec<ResultSender>>>,
+}
+
+pub(crate) enum CoalescedRequest {
+    /// The command must be sent along with the guard,
+    /// which is completed when its reply is delivered
+    Leader(CoalescingGuard),
+    /// An identical command is in flight, its result will be received here.
+    ///
+    /// If the leader is cancelled before its completion, the receiver is cancelled
+    /// and the command must be sent again.
+    Follower(ResultReceiver),
+    /// The command cannot be coalesced
+    None,
+}
+
+impl RequestCoalescer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn join(self: &Arc<Self>, command: &Command) -> CoalescedRequest {
+        if !command.streamed_args.is_empty()
+            || COALESCABLE_COMMANDS.binary_search(&command.name).is_err()
+        {
+            return CoalescedRequest::None;
+        }
+
+        let key: CoalescingKey = (command.name, command.args.to_vec());
+        let mut in_flight = self.in_flight.lock().unwrap();
+
+        if let Some(followers) = in_flight.get_mut(&key) {
+            let (result_sender, result_receiver) = oneshot::channel();
+            followers.push(result_sender);
+            CoalescedRequest::Follower(result_receiver)
+        } else {
+            in_flight.insert(key.clone(), Vec::new());
+            CoalescedRequest::Leader(CoalescingGuard {
+                coalescer: self.clone(),
+                key: Some(key),
+            })
+        }
+    }
+
+    fn remove(&self, key: &CoalescingKey) -> Vec<ResultSender> {
+        self.in_flight
+            .lock()
+            .unwrap()
+            .remove(key)
+            .unwrap_or_default()
+    }
+}
+
+/// Held by the message of the leader of a coalesced command.
+///
+/// Completed by the network handler when the reply is delivered, so that a command sent
+/// once a previous write has been acknowledged never joins a read sent before this write.
+///
+/// Dropped without completion, the followers are released with a cancelled receiver.
+#[derive(Debug)]
+pub(crate) struct CoalescingGuard {
+    coalescer: Arc<RequestCoalescer>,
+    key: Option<CoalescingKey>,
+}
+
+impl CoalescingGuard {
+    /// Share the result of the leader with the followers
+    pub fn complete(mut self, result: &Result<RespBuf>) {
+        if let Some(key) = self.key.take() {
+            for follower in self.coalescer.remove(&key) {
+                // the follower may have been cancelled
+                let _ = follower.send(result.clone());
+            }
+        }
+    }
+}
+
+impl Drop for CoalescingGuard {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.coalescer.remove(&key);
+        }
+    }
+}
@@ -20,14 +20,22 @@ use crate::{
     commands::{
         BitmapCommands, GenericCommands, GeoCommands, HashCommands, HyperLogLogCommands,
         ListCommands, ScriptingCommands, ServerCommands, SetCommands, SortedSetCommands,
-        StreamCommands, StringCommands,
+        StreamCommands, StringCommands, TransactionCommands, VectorSetCommands,
     },
     resp::{cmd, Command, RespDeserializer, Response},
     Error, Result,
 };
+use log::debug;
 use std::{fmt, marker::PhantomData};
 
 /// Represents an on-going [`transaction`](https://redis.io/docs/manual/transactions/) on a specific client instance.
+///
+/// Commands are queued locally: `MULTI`, the queued commands and `EXEC` are only sent
+/// by [`execute`](Transaction::execute), as a single batch.
+/// If the batch fails without a reply from the server (disconnection, timeout, write error),
+/// the connection may have been left between `MULTI` and `EXEC`:
+/// a `DISCARD` is then sent before the error is returned,
+/// so that the next commands of the client are not queued in a dangling transaction.
 pub struct Transaction {
     client: Client,
     commands: Vec<Command>,
@@ -47,7 +55,7 @@ impl Transaction {
 
     /// Set a flag to override default `retry_on_error` behavior.
     ///
-    /// See [Config::retry_on_error](crate::client::Config::retry_on_error)
+    /// See [ResiliencePolicy::retry_on](crate::client::ResiliencePolicy::retry_on)
     pub fn retry_on_error(&mut self, retry_on_error: bool) {
         self.retry_on_error = Some(retry_on_error);
     }
@@ -64,6 +72,15 @@ impl Transaction {
         self.forget_flags.push(true);
     }
 
+    /// Abort the transaction: the queued commands are dropped without being sent,
+    /// and the keys watched with [`watch`](TransactionCommands::watch) are unwatched.
+    ///
+    /// Dropping a transaction also drops its queued commands,
+    /// but the keys stay watched until the next transaction of the client is executed.
+    pub async fn abort(self) -> Result<()> {
+        self.client.unwatch().await
+    }
+
     /// Execute the transaction by the sending the queued command
     /// as a whole batch to the Redis server.
     ///
@@ -104,10 +121,17 @@ impl Transaction {
 
         let num_commands = self.commands.len();
 
-        let results = self
+        let results = match self
             .client
             .send_batch(self.commands, self.retry_on_error)
-            .await?;
+            .await
+        {
+            Ok(results) => results,
+            Err(e) => {
+                discard(&self.client).await;
+                return Err(e);
+            }
+        };
 
         let mut iter = results.into_iter();
 
@@ -134,6 +158,14 @@ impl Transaction {
     }
 }
 
+/// Best-effort `DISCARD` after a failed execution,
+/// which fails harmlessly if the connection is not in a transaction
+async fn discard(client: &Client) {
+    if let Err(e) = client.send(cmd("DISCARD"), Some(false)).await {
+        debug!("DISCARD after a failed transaction: {e}");
+    }
+}
+
 struct TransactionResultSeed<T: DeserializeOwned> {
     phantom: PhantomData<T>,
     forget_flags: Vec<bool>,
@@ -313,3 +345,4 @@ impl<'a> TimeSeriesCommands<'a> for &'a mut Transaction {}
 #[cfg_attr(docsrs, doc(cfg(feature = "redis-bloom")))]
 #[cfg(feature = "redis-bloom")]
 impl<'a> TopKCommands<'a> for &'a mut Transaction {}
+impl<'a> VectorSetCommands<'a> for &'a mut Transaction {}
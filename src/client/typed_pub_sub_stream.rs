@@ -0,0 +1,166 @@
+use crate::{
+    client::{PubSubMessage, PubSubStream},
+    Result,
+};
+use futures_util::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use std::{
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Codec used by a [`TypedPubSubStream`] to deserialize the payload of Pub/Sub messages
+///
+/// Implement this trait to plug any serialization format (CBOR, MessagePack, ...).
+pub trait PubSubCodec {
+    /// Deserialize a message payload
+    ///
+    /// # Errors
+    /// Any deserialization error,
+    /// reported as an item of the stream without terminating the stream
+    fn decode<T: DeserializeOwned>(&self, payload: &[u8]) -> Result<T>;
+}
+
+/// JSON [codec](PubSubCodec), default codec of [`PubSubStream::into_typed`]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonCodec;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+#[cfg(feature = "json")]
+impl PubSubCodec for JsonCodec {
+    fn decode<T: DeserializeOwned>(&self, payload: &[u8]) -> Result<T> {
+        serde_json::from_slice(payload).map_err(|e| crate::Error::Client(e.to_string()))
+    }
+}
+
+/// Pub/Sub Message with a deserialized payload,
+/// that can be streamed from [`TypedPubSubStream`]
+#[derive(Debug)]
+pub struct TypedPubSubMessage<T> {
+    pub pattern: Vec<u8>,
+    pub channel: Vec<u8>,
+    pub payload: T,
+}
+
+/// Stream of Pub/Sub messages which payload is deserialized with a [codec](PubSubCodec)
+///
+/// Built with [`PubSubStream::into_typed`] or [`PubSubStream::into_typed_with`].
+///
+/// A payload which cannot be deserialized produces an error item,
+/// the stream goes on with the next messages.
+pub struct TypedPubSubStream<T, C> {
+    stream: PubSubStream,
+    codec: C,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T, C> TypedPubSubStream<T, C> {
+    /// Mutable access to the underlying [`PubSubStream`],
+    /// to subscribe/unsubscribe to/from channels or patterns
+    pub fn get_mut(&mut self) -> &mut PubSubStream {
+        &mut self.stream
+    }
+
+    /// Get back the underlying [`PubSubStream`]
+    pub fn into_inner(self) -> PubSubStream {
+        self.stream
+    }
+
+    /// Close the stream by cancelling all subscriptions
+    ///
+    /// See [`PubSubStream::close`]
+    pub async fn close(self) -> Result<()> {
+        self.stream.close().await
+    }
+}
+
+impl<T, C> Stream for TypedPubSubStream<T, C>
+where
+    T: DeserializeOwned,
+    C: PubSubCodec + Unpin,
+{
+    type Item = Result<TypedPubSubMessage<T>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        match this.stream.poll_next_unpin(cx) {
+            Poll::Ready(Some(Ok(PubSubMessage {
+                pattern,
+                channel,
+                payload,
+            }))) => Poll::Ready(Some(this.codec.decode(&payload).map(|payload| {
+                TypedPubSubMessage {
+                    pattern,
+                    channel,
+                    payload,
+                }
+            }))),
+            Poll::Ready(Some(Err(e))) => Poll::Ready(Some(Err(e))),
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl PubSubStream {
+    /// Turn this stream into a stream of messages which JSON payload is deserialized into `T`
+    ///
+    /// # Example
+    /// ```
+    /// use rustis::{
+    ///     client::{Client, TypedPubSubMessage},
+    ///     commands::PubSubCommands,
+    ///     Result,
+    /// };
+    /// use futures_util::StreamExt;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Event {
+    ///     id: u64,
+    /// }
+    ///
+    /// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+    /// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+    /// async fn main() -> Result<()> {
+    ///     let pub_sub_client = Client::connect("127.0.0.1:6379").await?;
+    ///     let regular_client = Client::connect("127.0.0.1:6379").await?;
+    ///
+    ///     let mut stream = pub_sub_client
+    ///         .subscribe("events")
+    ///         .await?
+    ///         .into_typed::<Event>();
+    ///
+    ///     regular_client.publish("events", r#"{"id":12}"#).await?;
+    ///
+    ///     let message: TypedPubSubMessage<Event> = stream.next().await.unwrap()?;
+    ///     assert_eq!(12, message.payload.id);
+    ///
+    ///     stream.close().await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    #[cfg(feature = "json")]
+    pub fn into_typed<T: DeserializeOwned>(self) -> TypedPubSubStream<T, JsonCodec> {
+        self.into_typed_with(JsonCodec)
+    }
+
+    /// Turn this stream into a stream of messages which payload is deserialized into `T`
+    /// with a custom [codec](PubSubCodec)
+    pub fn into_typed_with<T, C>(self, codec: C) -> TypedPubSubStream<T, C>
+    where
+        T: DeserializeOwned,
+        C: PubSubCodec,
+    {
+        TypedPubSubStream {
+            stream: self,
+            codec,
+            phantom: PhantomData,
+        }
+    }
+}
@@ -0,0 +1,67 @@
+/*!
+Redis Cluster utilities
+
+* Computation of the [hash slot](https://redis.io/docs/reference/cluster-spec/#key-distribution-model)
+  of a key, the same way the Redis Cluster and the [`Client`](crate::client::Client) do.
+
+  Useful to build co-location aware data models, where related keys must share the same hash slot
+  (to be used by multi-key commands or transactions), or to debug `CROSSSLOT` errors.
+* [`drain_slot`] - Migration of the keys of a hash slot to another node,
+  the building block of custom resharding tools.
+
+# Example
+```
+use rustis::cluster::{extract_hash_tag, hash_slot};
+
+assert_eq!(Some(&b"user:1000"[..]), extract_hash_tag("{user:1000}.following"));
+assert_eq!(
+    hash_slot("{user:1000}.following"),
+    hash_slot("{user:1000}.followers")
+);
+```
+*/
+
+mod slot_drain;
+
+pub use slot_drain::*;
+
+/// Number of hash slots of a Redis Cluster
+pub const HASH_SLOT_COUNT: u16 = 16384;
+
+/// Compute the hash slot of a key
+///
+/// If the key contains a [hash tag](extract_hash_tag), only the hash tag is hashed.
+///
+/// The result is the same as the one of the
+/// [`cluster_keyslot`](crate::commands::ClusterCommands::cluster_keyslot) command,
+/// without a roundtrip to the server.
+#[must_use]
+pub fn hash_slot(key: impl AsRef<[u8]>) -> u16 {
+    let key = key.as_ref();
+    let hashed = extract_hash_tag(key).unwrap_or(key);
+    crc16::State::<crc16::XMODEM>::calculate(hashed) % HASH_SLOT_COUNT
+}
+
+/// Extract the hash tag of a key
+///
+/// The hash tag is the substring between the first occurrence of `{`
+/// and the first occurrence of `}` after it.
+///
+/// # Return
+/// The hash tag, or `None` if the key has no hash tag or if the hash tag is empty (e.g. `foo{}bar`),
+/// in which case the whole key is hashed.
+#[must_use]
+pub fn extract_hash_tag<K>(key: &K) -> Option<&[u8]>
+where
+    K: AsRef<[u8]> + ?Sized,
+{
+    let key = key.as_ref();
+    let start = key.iter().position(|b| *b == b'{')? + 1;
+    let len = key[start..].iter().position(|b| *b == b'}')?;
+
+    if len == 0 {
+        None
+    } else {
+        Some(&key[start..start + len])
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{
+    client::Client,
+    commands::{ClusterCommands, GenericCommands, MigrateOptions},
+    resp::BulkString,
+    Error, Result,
+};
+
+/// Timeout of each `MIGRATE` command of [`drain_slot`], in milliseconds
+const MIGRATE_TIMEOUT: u64 = 5000;
+
+/// Progress of a [`drain_slot`] operation, reported after each migrated batch of keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotDrainProgress {
+    /// Drained hash slot
+    pub slot: u16,
+    /// Number of keys migrated since the beginning of the operation
+    pub migrated_keys: usize,
+    /// Number of keys of the slot still stored on the source node
+    pub remaining_keys: usize,
+}
+
+/// Migrate all the keys of a hash slot from the node `source` is connected to,
+/// to the node `target_node`, the way [`redis-cli --cluster reshard`](https://redis.io/docs/management/scaling/) does it.
+///
+/// Keys are listed with [`cluster_keys_in_slot`](ClusterCommands::cluster_keys_in_slot)
+/// and moved with [`migrate`](GenericCommands::migrate), `batch` keys at a time.
+/// After each batch, the number of keys left in the slot is queried with
+/// [`cluster_countkeysinslot`](ClusterCommands::cluster_countkeysinslot)
+/// and reported to `progress`.
+///
+/// The states of the slot are not modified: the slot must be set in importing state on the target node
+/// and in migrating state on the source node beforehand, and assigned to the target node afterwards,
+/// with [`cluster_setslot`](ClusterCommands::cluster_setslot).
+///
+/// # Arguments
+/// * `source` - client connected directly to the node owning the slot (not a cluster client)
+/// * `slot` - hash slot to drain
+/// * `target_node` - host & port of the node the keys are migrated to
+/// * `batch` - maximum number of keys moved by each `MIGRATE` command
+/// * `progress` - callback called after each batch
+///
+/// # Return
+/// The total number of migrated keys
+///
+/// # Errors
+/// An [`Error::Client`] if `batch` is `0`, or the first error returned by the source node.
+///
+/// # Example
+/// ```
+/// use rustis::{
+///     client::Client,
+///     cluster::{drain_slot, hash_slot},
+///     commands::{ClusterCommands, ClusterSetSlotSubCommand},
+///     Result,
+/// };
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let slot = hash_slot("key");
+///     let source = Client::connect("127.0.0.1:7000").await?;
+///     let target = Client::connect("127.0.0.1:7001").await?;
+///     let source_id: String = source.cluster_myid().await?;
+///     let target_id: String = target.cluster_myid().await?;
+///
+///     target
+///         .cluster_setslot(slot, ClusterSetSlotSubCommand::Importing { node_id: source_id })
+///         .await?;
+///     source
+///         .cluster_setslot(slot, ClusterSetSlotSubCommand::Migrating { node_id: target_id.clone() })
+///         .await?;
+///
+///     drain_slot(&source, slot, ("127.0.0.1", 7001), 100, |progress| {
+///         println!("{} keys migrated, {} remaining", progress.migrated_keys, progress.remaining_keys);
+///     })
+///     .await?;
+///
+///     target
+///         .cluster_setslot(slot, ClusterSetSlotSubCommand::Node { node_id: target_id.clone() })
+///         .await?;
+///     source
+///         .cluster_setslot(slot, ClusterSetSlotSubCommand::Node { node_id: target_id })
+///         .await?;
+///
+///     Ok(())
+/// }
+/// ```
+pub async fn drain_slot(
+    source: &Client,
+    slot: u16,
+    target_node: (&str, u16),
+    batch: usize,
+    mut progress: impl FnMut(SlotDrainProgress),
+) -> Result<usize> {
+    if batch == 0 {
+        return Err(Error::Client(
+            "Cannot drain a hash slot with an empty batch".to_owned(),
+        ));
+    }
+
+    let (host, port) = target_node;
+    let mut migrated_keys = 0;
+
+    loop {
+        let keys: Vec<BulkString> = source.cluster_keys_in_slot(slot, batch).await?;
+        if keys.is_empty() {
+            return Ok(migrated_keys);
+        }
+
+        migrated_keys += keys.len();
+        source
+            .migrate(
+                host,
+                port,
+                "",
+                0,
+                MIGRATE_TIMEOUT,
+                MigrateOptions::default().keys(keys),
+            )
+            .await?;
+
+        let remaining_keys = source.cluster_countkeysinslot(slot as usize).await?;
+        progress(SlotDrainProgress {
+            slot,
+            migrated_keys,
+            remaining_keys,
+        });
+    }
+}
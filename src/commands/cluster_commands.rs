@@ -152,7 +152,7 @@ pub trait ClusterCommands<'a> {
     /// forces the replica to start a manual failover of its master instance.
     ///
     /// # Errors
-    /// An error cann occured if the operation cannot be executed,
+    /// An error can occur if the operation cannot be executed,
     /// for example if we are talking with a node which is already a master.
     ///
     /// # See Also
@@ -201,6 +201,7 @@ pub trait ClusterCommands<'a> {
     /// # See Also
     /// [<https://redis.io/commands/cluster-getkeysinslot/>](https://redis.io/commands/cluster-getkeysinslot/)
     #[must_use]
+    #[deprecated(note = "use `cluster_keys_in_slot` to get the keys back")]
     fn cluster_getkeysinslot(self, slot: u16, count: usize) -> PreparedCommand<'a, Self, ()>
     where
         Self: Sized,
@@ -211,6 +212,30 @@ pub trait ClusterCommands<'a> {
         )
     }
 
+    /// The command returns an array of keys names stored in
+    /// the contacted node and hashing to the specified hash slot.
+    ///
+    /// The maximum number of keys to return is specified via the count argument,
+    /// so that it is possible for the user of this API to batch-processing keys.
+    ///
+    /// # Return
+    /// The collection of the keys of the hash slot, up to `count` keys
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/cluster-getkeysinslot/>](https://redis.io/commands/cluster-getkeysinslot/)
+    #[must_use]
+    fn cluster_keys_in_slot<K, A>(self, slot: u16, count: usize) -> PreparedCommand<'a, Self, A>
+    where
+        Self: Sized,
+        K: PrimitiveResponse + DeserializeOwned,
+        A: CollectionResponse<K> + DeserializeOwned,
+    {
+        prepare_command(
+            self,
+            cmd("CLUSTER").arg("GETKEYSINSLOT").arg(slot).arg(count),
+        )
+    }
+
     /// This command provides [`info`](crate::commands::ServerCommands::info) style information about Redis Cluster vital parameters.
     ///
     /// # Return
@@ -265,8 +290,8 @@ pub trait ClusterCommands<'a> {
 
     /// This command is used in order to connect different Redis nodes with cluster support enabled, into a working cluster.
     ///
-    /// # Return
-    /// An array of structs where each struct contains various attributes and their values of a cluster link.
+    /// `cluster_bus_port` is only needed when the cluster bus port of the node to meet
+    /// is not the default one (`port` + 10000).
     ///
     /// # See Also
     /// [<https://redis.io/commands/cluster-meet/>](https://redis.io/commands/cluster-meet/)
@@ -487,6 +512,7 @@ pub enum ClusterBumpEpochResult {
 }
 
 /// Options for the [`cluster_failover`](ClusterCommands::cluster_failover) command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ClusterFailoverOption {
     /// No option
     Default,
@@ -694,6 +720,7 @@ impl ToArgs for ClusterResetType {
 }
 
 /// Subcommand for the [`cluster_setslot`](ClusterCommands::cluster_setslot) command.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ClusterSetSlotSubCommand {
     /// Set a hash slot in importing state.
     Importing { node_id: String },
@@ -705,6 +732,32 @@ pub enum ClusterSetSlotSubCommand {
     Stable,
 }
 
+impl ClusterSetSlotSubCommand {
+    /// Set a hash slot in importing state from the node `node_id`.
+    #[must_use]
+    pub fn importing(node_id: impl Into<String>) -> Self {
+        Self::Importing {
+            node_id: node_id.into(),
+        }
+    }
+
+    /// Set a hash slot in migrating state to the node `node_id`.
+    #[must_use]
+    pub fn migrating(node_id: impl Into<String>) -> Self {
+        Self::Migrating {
+            node_id: node_id.into(),
+        }
+    }
+
+    /// Bind the hash slot to the node `node_id`.
+    #[must_use]
+    pub fn node(node_id: impl Into<String>) -> Self {
+        Self::Node {
+            node_id: node_id.into(),
+        }
+    }
+}
+
 impl ToArgs for ClusterSetSlotSubCommand {
     fn write_args(&self, args: &mut CommandArgs) {
         match self {
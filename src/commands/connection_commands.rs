@@ -551,6 +551,12 @@ pub struct ClientInfo {
     /// client RESP protocol version
     pub resp: i32,
 
+    /// client library name, set with [`client_setinfo`](ConnectionCommands::client_setinfo). Added in Redis 7.2
+    pub lib_name: String,
+
+    /// client library version, set with [`client_setinfo`](ConnectionCommands::client_setinfo). Added in Redis 7.2
+    pub lib_ver: String,
+
     /// additional arguments that may be added in future versions of Redis
     pub additional_arguments: HashMap<String, String>,
 }
@@ -562,11 +568,10 @@ impl ClientInfo {
             .trim_end()
             .split(' ')
             .map(|kvp| {
-                let mut iter = kvp.split('=');
-                match (iter.next(), iter.next()) {
-                    (Some(key), None) => (key.to_owned(), "".to_owned()),
-                    (Some(key), Some(value)) => (key.to_owned(), value.to_owned()),
-                    _ => ("".to_owned(), "".to_owned()),
+                // values may contain '=', e.g. names with connection metadata
+                match kvp.split_once('=') {
+                    Some((key, value)) => (key.to_owned(), value.to_owned()),
+                    None => (kvp.to_owned(), "".to_owned()),
                 }
             })
             .collect();
@@ -655,6 +660,8 @@ impl ClientInfo {
                 .remove("resp")
                 .map(|id| id.parse::<i32>().unwrap_or_default())
                 .unwrap_or_default(),
+            lib_name: values.remove("lib-name").unwrap_or_default(),
+            lib_ver: values.remove("lib-ver").unwrap_or_default(),
             additional_arguments: values,
         })
     }
@@ -787,6 +794,14 @@ impl ClientKillOptions {
         }
     }
 
+    /// Kill all clients connected for more than `max_age` seconds (Redis 7.4+).
+    #[must_use]
+    pub fn max_age(mut self, max_age: u64) -> Self {
+        Self {
+            command_args: self.command_args.arg("MAXAGE").arg(max_age).build(),
+        }
+    }
+
     /// By default this option is set to yes, that is, the client calling the command will not get killed,
     /// however setting this option to no will have the effect of also killing the client calling the command.
     #[must_use]
@@ -2,10 +2,11 @@ use crate::{
     client::{prepare_command, PreparedCommand},
     resp::{
         cmd, deserialize_byte_buf, CollectionResponse, CommandArgs, PrimitiveResponse, SingleArg,
-        SingleArgCollection, ToArgs,
+        SingleArgCollection, StreamedArg, ToArgs,
     },
 };
-use serde::{de::DeserializeOwned, Deserialize};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer};
+use std::time::{Duration, SystemTime};
 
 /// A group of generic Redis commands
 ///
@@ -161,6 +162,25 @@ pub trait GenericCommands<'a> {
         prepare_command(self, cmd("EXPIRETIME").arg(key))
     }
 
+    /// Returns the expiration of the given key as an [`Expiry`](Expiry),
+    /// based on [`PEXPIRETIME`](https://redis.io/commands/pexpiretime/).
+    ///
+    /// # Return
+    /// - [`Expiry::None`](Expiry::None) if the key does not exist.
+    /// - [`Expiry::NoTtl`](Expiry::NoTtl) if the key exists but has no associated expiration time.
+    /// - [`Expiry::At`](Expiry::At) with the absolute expiration time otherwise.
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/pexpiretime/>](https://redis.io/commands/pexpiretime/)
+    #[must_use]
+    fn expiry<K>(self, key: K) -> PreparedCommand<'a, Self, Expiry>
+    where
+        Self: Sized,
+        K: SingleArg,
+    {
+        prepare_command(self, cmd("PEXPIRETIME").arg(key))
+    }
+
     /// Returns all keys matching pattern.
     ///
     /// # Return
@@ -514,6 +534,34 @@ pub trait GenericCommands<'a> {
         )
     }
 
+    /// Same as [`restore`](GenericCommands::restore), with the serialized value
+    /// streamed to the server instead of being loaded in memory,
+    /// for multi-hundred-MB dumps.
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/restore/>](https://redis.io/commands/restore/)
+    #[must_use]
+    fn restore_streamed<K>(
+        self,
+        key: K,
+        ttl: u64,
+        serialized_value: StreamedArg,
+        options: RestoreOptions,
+    ) -> PreparedCommand<'a, Self, ()>
+    where
+        Self: Sized,
+        K: SingleArg,
+    {
+        prepare_command(
+            self,
+            cmd("RESTORE")
+                .arg(key)
+                .arg(ttl)
+                .streamed_arg(serialized_value)
+                .arg(options),
+        )
+    }
+
     /// Iterates the set of keys in the currently selected Redis database.
     ///
     /// # Return
@@ -891,6 +939,47 @@ impl ToArgs for SortOptions {
 #[derive(Deserialize)]
 pub struct DumpResult(#[serde(deserialize_with = "deserialize_byte_buf")] pub Vec<u8>);
 
+/// Expiration of a key, result of the [`expiry`](GenericCommands::expiry) command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expiry {
+    /// The key does not exist
+    None,
+    /// The key exists but has no associated expiration time
+    NoTtl,
+    /// Absolute expiration time of the key
+    At(SystemTime),
+}
+
+impl Expiry {
+    /// Remaining time to live of the key, relatively to now
+    ///
+    /// Returns `None` if the key does not exist or has no associated expiration time.
+    #[must_use]
+    pub fn ttl_duration(&self) -> Option<Duration> {
+        match self {
+            Expiry::At(time) => Some(
+                time.duration_since(SystemTime::now())
+                    .unwrap_or(Duration::ZERO),
+            ),
+            _ => None,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Expiry {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let millis = i64::deserialize(deserializer)?;
+        Ok(match millis {
+            -2 => Expiry::None,
+            -1 => Expiry::NoTtl,
+            millis => Expiry::At(SystemTime::UNIX_EPOCH + Duration::from_millis(millis as u64)),
+        })
+    }
+}
+
 /// Options for the [`scan`](GenericCommands::scan) command
 #[derive(Default)]
 pub struct ScanOptions {
@@ -912,6 +1001,7 @@ impl ScanOptions {
         }
     }
 
+    /// Only return keys of the given type (`string`, `list`, `set`, `zset`, `hash`, `stream`...)
     #[must_use]
     pub fn type_<TY: SingleArg>(mut self, type_: TY) -> Self {
         Self {
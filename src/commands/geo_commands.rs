@@ -4,6 +4,7 @@ use crate::{
         cmd, CollectionResponse, CommandArgs, MultipleArgsCollection, PrimitiveResponse, SingleArg,
         SingleArgCollection, ToArgs,
     },
+    Error,
 };
 use serde::{
     de::{
@@ -13,7 +14,7 @@ use serde::{
     },
     Deserialize, Deserializer,
 };
-use std::{fmt, marker::PhantomData};
+use std::{fmt, marker::PhantomData, ops::RangeInclusive};
 
 /// A group of Redis commands related to [`Geospatial`](https://redis.io/docs/data-types/geospatial/) indices
 ///
@@ -52,6 +53,40 @@ pub trait GeoCommands<'a> {
         )
     }
 
+    /// Adds the specified geospatial items ([`GeoPoint`], name) to the specified key.
+    ///
+    /// Same as [geoadd](GeoCommands::geoadd), with positions checked by [`GeoPoint::new`].
+    ///
+    /// # Return
+    /// * When used without optional arguments, the number of elements added to the sorted set (excluding score updates).
+    /// * If the CH option is specified, the number of elements that were changed (added or updated).
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/geoadd/>](https://redis.io/commands/geoadd/)
+    #[must_use]
+    fn geoadd_points<K, M, I>(
+        self,
+        key: K,
+        condition: GeoAddCondition,
+        change: bool,
+        items: I,
+    ) -> PreparedCommand<'a, Self, usize>
+    where
+        Self: Sized,
+        K: SingleArg,
+        M: SingleArg,
+        I: MultipleArgsCollection<(GeoPoint, M)>,
+    {
+        prepare_command(
+            self,
+            cmd("GEOADD")
+                .arg(key)
+                .arg(condition)
+                .arg_if(change, "CH")
+                .arg(items),
+        )
+    }
+
     /// Return the distance between two members in the geospatial index
     /// represented by the sorted set.
     ///
@@ -123,6 +158,111 @@ pub trait GeoCommands<'a> {
         prepare_command(self, cmd("GEOPOS").arg(key).arg(members))
     }
 
+    /// Return the positions of all the specified members
+    /// of the geospatial index represented by the sorted set at key.
+    ///
+    /// # Return
+    /// The [`GeoCoordinates`] of each member name passed as argument to the command,
+    /// [`GeoCoordinates::Missing`] for non existing members.
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/geopos/>](https://redis.io/commands/geopos/)
+    #[must_use]
+    fn geopos_points<K, M, C>(
+        self,
+        key: K,
+        members: C,
+    ) -> PreparedCommand<'a, Self, Vec<GeoCoordinates>>
+    where
+        Self: Sized,
+        K: SingleArg,
+        M: SingleArg,
+        C: SingleArgCollection<M>,
+    {
+        prepare_command(self, cmd("GEOPOS").arg(key).arg(members))
+    }
+
+    /// Read-only variant of the `GEORADIUS` command:
+    /// return the members of a sorted set populated with geospatial information using [geoadd](GeoCommands::geoadd),
+    /// which are within `radius` of the point at `longitude` & `latitude`.
+    ///
+    /// It can safely be used in read-only replicas.
+    /// Since Redis 6.2, [geosearch](GeoCommands::geosearch) should be preferred.
+    ///
+    /// # Return
+    /// An array of members + additional information depending
+    /// on which `with_xyz` options have been selected
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/georadius_ro/>](https://redis.io/commands/georadius_ro/)
+    #[must_use]
+    fn georadius_readonly<K, M, A>(
+        self,
+        key: K,
+        longitude: f64,
+        latitude: f64,
+        radius: f64,
+        unit: GeoUnit,
+        options: GeoSearchOptions,
+    ) -> PreparedCommand<'a, Self, A>
+    where
+        Self: Sized,
+        K: SingleArg,
+        M: PrimitiveResponse + DeserializeOwned,
+        A: CollectionResponse<GeoSearchResult<M>> + DeserializeOwned,
+    {
+        prepare_command(
+            self,
+            cmd("GEORADIUS_RO")
+                .arg(key)
+                .arg(longitude)
+                .arg(latitude)
+                .arg(radius)
+                .arg(unit)
+                .arg(options),
+        )
+    }
+
+    /// Read-only variant of the `GEORADIUSBYMEMBER` command:
+    /// like [georadius_readonly](GeoCommands::georadius_readonly),
+    /// but the center of the area is the position of an existing `member` of the sorted set.
+    ///
+    /// It can safely be used in read-only replicas.
+    /// Since Redis 6.2, [geosearch](GeoCommands::geosearch) should be preferred.
+    ///
+    /// # Return
+    /// An array of members + additional information depending
+    /// on which `with_xyz` options have been selected
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/georadiusbymember_ro/>](https://redis.io/commands/georadiusbymember_ro/)
+    #[must_use]
+    fn georadiusbymember_readonly<K, M1, M2, A>(
+        self,
+        key: K,
+        member: M1,
+        radius: f64,
+        unit: GeoUnit,
+        options: GeoSearchOptions,
+    ) -> PreparedCommand<'a, Self, A>
+    where
+        Self: Sized,
+        K: SingleArg,
+        M1: SingleArg,
+        M2: PrimitiveResponse + DeserializeOwned,
+        A: CollectionResponse<GeoSearchResult<M2>> + DeserializeOwned,
+    {
+        prepare_command(
+            self,
+            cmd("GEORADIUSBYMEMBER_RO")
+                .arg(key)
+                .arg(member)
+                .arg(radius)
+                .arg(unit)
+                .arg(options),
+        )
+    }
+
     /// Return the members of a sorted set populated with geospatial information using [geoadd](GeoCommands::geoadd),
     /// which are within the borders of the area specified by a given shape.
     ///
@@ -187,6 +327,127 @@ pub trait GeoCommands<'a> {
     }
 }
 
+/// Longitudes of the positions which can be indexed by Redis
+const LONGITUDE_RANGE: RangeInclusive<f64> = -180.0..=180.0;
+/// Latitudes of the positions which can be indexed by Redis (EPSG:3857)
+const LATITUDE_RANGE: RangeInclusive<f64> = -85.05112878..=85.05112878;
+
+/// Position of a member of a geospatial index, which can be indexed by Redis
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoPoint {
+    longitude: f64,
+    latitude: f64,
+}
+
+impl GeoPoint {
+    /// Create a new position, checking that it can be indexed by Redis
+    ///
+    /// # Errors
+    /// An [`Error::Client`] if `longitude` is not between -180 and 180 degrees,
+    /// or if `latitude` is not between -85.05112878 and 85.05112878 degrees.
+    pub fn new(longitude: f64, latitude: f64) -> crate::Result<Self> {
+        if !LONGITUDE_RANGE.contains(&longitude) {
+            return Err(Error::Client(format!(
+                "Invalid longitude {longitude}: must be between {} and {}",
+                LONGITUDE_RANGE.start(),
+                LONGITUDE_RANGE.end()
+            )));
+        }
+
+        if !LATITUDE_RANGE.contains(&latitude) {
+            return Err(Error::Client(format!(
+                "Invalid latitude {latitude}: must be between {} and {}",
+                LATITUDE_RANGE.start(),
+                LATITUDE_RANGE.end()
+            )));
+        }
+
+        Ok(Self {
+            longitude,
+            latitude,
+        })
+    }
+
+    /// Longitude, in degrees
+    #[must_use]
+    pub fn longitude(&self) -> f64 {
+        self.longitude
+    }
+
+    /// Latitude, in degrees
+    #[must_use]
+    pub fn latitude(&self) -> f64 {
+        self.latitude
+    }
+}
+
+impl TryFrom<(f64, f64)> for GeoPoint {
+    type Error = Error;
+
+    /// Convert a (longitude, latitude) pair, see [`GeoPoint::new`]
+    fn try_from((longitude, latitude): (f64, f64)) -> crate::Result<Self> {
+        Self::new(longitude, latitude)
+    }
+}
+
+impl From<GeoPoint> for (f64, f64) {
+    fn from(point: GeoPoint) -> Self {
+        (point.longitude, point.latitude)
+    }
+}
+
+impl ToArgs for GeoPoint {
+    fn write_args(&self, args: &mut CommandArgs) {
+        args.arg(self.longitude).arg(self.latitude);
+    }
+}
+
+impl<'de> Deserialize<'de> for GeoPoint {
+    /// Positions returned by the server are already indexed and not checked again
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (longitude, latitude) = <(f64, f64)>::deserialize(deserializer)?;
+        Ok(Self {
+            longitude,
+            latitude,
+        })
+    }
+}
+
+/// Position of a member returned by the [`geopos_points`](GeoCommands::geopos_points) command
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GeoCoordinates {
+    /// Position of an existing member
+    Position(GeoPoint),
+    /// The member does not exist
+    Missing,
+}
+
+impl GeoCoordinates {
+    /// Position of the member, `None` if the member does not exist
+    #[must_use]
+    pub fn point(&self) -> Option<GeoPoint> {
+        match self {
+            GeoCoordinates::Position(point) => Some(*point),
+            GeoCoordinates::Missing => None,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for GeoCoordinates {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(match Option::<GeoPoint>::deserialize(deserializer)? {
+            Some(point) => GeoCoordinates::Position(point),
+            None => GeoCoordinates::Missing,
+        })
+    }
+}
+
 /// Condition for the [`geoadd`](GeoCommands::geoadd) command
 #[derive(Default)]
 pub enum GeoAddCondition {
@@ -258,6 +519,19 @@ where
     }
 }
 
+impl<M> From<GeoPoint> for GeoSearchFrom<M>
+where
+    M: SingleArg,
+{
+    /// [`FromLonLat`](GeoSearchFrom::FromLonLat) the position of `point`
+    fn from(point: GeoPoint) -> Self {
+        GeoSearchFrom::FromLonLat {
+            longitude: point.longitude,
+            latitude: point.latitude,
+        }
+    }
+}
+
 /// The query's shape is provided by one of these mandatory options:
 pub enum GeoSearchBy {
     /// Search inside circular area according to given `radius` in the specified `unit`.
@@ -380,6 +654,20 @@ where
     pub coordinates: Option<(f64, f64)>,
 }
 
+impl<M> GeoSearchResult<M>
+where
+    M: PrimitiveResponse,
+{
+    /// Position of the matched member, if requested with [`with_coord`](GeoSearchOptions::with_coord)
+    #[must_use]
+    pub fn point(&self) -> Option<GeoPoint> {
+        self.coordinates.map(|(longitude, latitude)| GeoPoint {
+            longitude,
+            latitude,
+        })
+    }
+}
+
 impl<'de, M> Deserialize<'de> for GeoSearchResult<M>
 where
     M: PrimitiveResponse + DeserializeOwned,
@@ -266,6 +266,35 @@ pub trait HashCommands<'a> {
         prepare_command(self, cmd("HSCAN").arg(key).arg(cursor).arg(options))
     }
 
+    /// Iterates fields of Hash types, without their associated values.
+    ///
+    /// # Return
+    /// the cursor and a list of fields of the Hash.
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/hscan/>](https://redis.io/commands/hscan/)
+    #[must_use]
+    fn hscan_novalues<K, F>(
+        self,
+        key: K,
+        cursor: u64,
+        options: HScanOptions,
+    ) -> PreparedCommand<'a, Self, (u64, Vec<F>)>
+    where
+        Self: Sized,
+        K: SingleArg,
+        F: PrimitiveResponse + DeserializeOwned,
+    {
+        prepare_command(
+            self,
+            cmd("HSCAN")
+                .arg(key)
+                .arg(cursor)
+                .arg(options)
+                .arg("NOVALUES"),
+        )
+    }
+
     /// Sets field in the hash stored at key to value.
     ///
     /// # Return
@@ -341,7 +370,7 @@ pub trait HashCommands<'a> {
     }
 }
 
-/// Options for the [`hscan`](HashCommands::hscan) command
+/// Options for the [`hscan`](HashCommands::hscan) and [`hscan_novalues`](HashCommands::hscan_novalues) commands
 #[derive(Default)]
 pub struct HScanOptions {
     command_args: CommandArgs,
@@ -1,6 +1,8 @@
 use crate::{
-    client::{prepare_command, PreparedCommand},
+    client::{prepare_command, Client, PreparedCommand},
+    commands::GenericCommands,
     resp::{cmd, SingleArg, SingleArgCollection},
+    Result,
 };
 
 /// A group of Redis commands related to [`HyperLogLog`](https://redis.io/docs/data-types/hyperloglogs/)
@@ -33,7 +35,8 @@ pub trait HyperLogLogCommands<'a> {
     /// The approximated number of unique elements observed via PFADD.
     ///
     /// # See Also
-    /// [<https://redis.io/commands/pfcount/>](https://redis.io/commands/pfcount/)
+    /// * [<https://redis.io/commands/pfcount/>](https://redis.io/commands/pfcount/)
+    /// * [`Client::pfcount_each`] to count several HyperLogLogs separately
     fn pfcount<K, KK>(self, keys: KK) -> PreparedCommand<'a, Self, usize>
     where
         Self: Sized,
@@ -46,7 +49,8 @@ pub trait HyperLogLogCommands<'a> {
     /// Merge N different HyperLogLogs into a single one.
     ///
     /// # See Also
-    /// [<https://redis.io/commands/pfmerge/>](https://redis.io/commands/pfmerge/)
+    /// * [<https://redis.io/commands/pfmerge/>](https://redis.io/commands/pfmerge/)
+    /// * [`Client::pfmerge_into_temp`] to merge into a temporary key
     fn pfmerge<D, S, SS>(self, dest_key: D, source_keys: SS) -> PreparedCommand<'a, Self, ()>
     where
         Self: Sized,
@@ -57,3 +61,46 @@ pub trait HyperLogLogCommands<'a> {
         prepare_command(self, cmd("PFMERGE").arg(dest_key).arg(source_keys))
     }
 }
+
+/// Temporary HyperLogLog created by [`Client::pfmerge_into_temp`]
+///
+/// The key is deleted when this guard is dropped,
+/// or explicitly with [`delete`](TempHyperLogLog::delete) to wait for the deletion.
+pub struct TempHyperLogLog {
+    client: Client,
+    key: Vec<u8>,
+}
+
+impl TempHyperLogLog {
+    pub(crate) fn new(client: Client, key: Vec<u8>) -> Self {
+        Self { client, key }
+    }
+
+    /// Key of the temporary HyperLogLog
+    #[must_use]
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    /// Approximated cardinality of the merged HyperLogLogs
+    pub async fn count(&self) -> Result<usize> {
+        self.client.pfcount(self.key.as_slice()).await
+    }
+
+    /// Delete the temporary key
+    pub async fn delete(mut self) -> Result<()> {
+        let key = std::mem::take(&mut self.key);
+        self.client.del(key).await?;
+        Ok(())
+    }
+}
+
+impl Drop for TempHyperLogLog {
+    fn drop(&mut self) {
+        if !self.key.is_empty() {
+            let _ = self
+                .client
+                .send_and_forget(cmd("DEL").arg(std::mem::take(&mut self.key)), None);
+        }
+    }
+}
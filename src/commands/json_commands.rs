@@ -6,7 +6,11 @@ use crate::{
         ToArgs, Value,
     },
 };
-use serde::de::DeserializeOwned;
+use serde::{
+    de::{self, DeserializeOwned, IgnoredAny, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use std::{fmt, marker::PhantomData, ops::Deref};
 
 /// A group of Redis commands related to [`RedisJson`](https://redis.io/docs/stack/json/)
 ///
@@ -325,6 +329,35 @@ pub trait JsonCommands<'a> {
         prepare_command(self, cmd("JSON.GET").arg(key).arg(options))
     }
 
+    /// Return the single value matched by `path`, deserialized from its JSON serialization
+    ///
+    /// Shortcut for [`json_get`](JsonCommands::json_get) with a single JSONPath,
+    /// unwrapping the array in which RedisJSON v2 returns the values matched by a `$` path.
+    ///
+    /// # Arguments
+    /// * `key` - The key to parse.
+    /// * `path`- The JSONPath to specify.
+    ///
+    /// # Return
+    /// The value matched by `path`, see [`JsonSingle`]
+    ///
+    /// # Errors
+    /// An [`Error::Client`](crate::Error::Client) if `path` matches several values,
+    /// or no value while `V` is not an `Option`.
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/json.get/>](https://redis.io/commands/json.get/)
+    #[must_use]
+    fn json_get_single<K, P, V>(self, key: K, path: P) -> PreparedCommand<'a, Self, JsonSingle<V>>
+    where
+        Self: Sized,
+        K: SingleArg,
+        P: SingleArg,
+        V: DeserializeOwned,
+    {
+        prepare_command(self, cmd("JSON.GET").arg(key).arg(path))
+    }
+
     /// Return the values at `path` from multiple `key` arguments
     ///
     /// # Arguments
@@ -686,3 +719,163 @@ impl ToArgs for JsonArrIndexOptions {
         args.arg(&self.command_args);
     }
 }
+
+/// Response unwrapping the single value matched by a JSONPath
+///
+/// RedisJSON v2 returns the values matched by a `$` path in an array,
+/// even when the path can only match one value.
+/// `JsonSingle<T>` can be used as the response of the JSON commands
+/// ([`json_get`](JsonCommands::json_get), [`json_strlen`](JsonCommands::json_strlen),
+/// [`json_type`](JsonCommands::json_type), ...) in place of a collection of `T`
+/// and deserializes the only element of this array.
+///
+/// When `path` matches no value (or when the key does not exist),
+/// `T` is deserialized from `null`, i.e. `None` if `T` is an `Option`.
+///
+/// # Errors
+/// The deserialization fails with an [`Error::Client`](crate::Error::Client)
+/// if `path` matches several values, or no value while `T` is not an `Option`.
+///
+/// # Example
+/// ```
+/// use rustis::{
+///     client::Client,
+///     commands::{JsonCommands, JsonSingle},
+///     Result,
+/// };
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let client = Client::connect("127.0.0.1:6379").await?;
+///     client
+///         .json_set("key", "$", r#"{"name":"John","age":42}"#, Default::default())
+///         .await?;
+///
+///     let JsonSingle(age): JsonSingle<u32> = client.json_get_single("key", "$.age").await?;
+///     assert_eq!(42, age);
+///
+///     let JsonSingle(len) = client.json_strlen("key", "$.name").await?;
+///     assert_eq!(Some(4), len);
+///
+///     let missing: JsonSingle<Option<String>> = client.json_get_single("key", "$.email").await?;
+///     assert_eq!(None, missing.into_inner());
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JsonSingle<T>(pub T);
+
+impl<T> JsonSingle<T> {
+    /// Unwrap the matched value
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for JsonSingle<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: DeserializeOwned> PrimitiveResponse for JsonSingle<T> {}
+impl<T: DeserializeOwned> CollectionResponse<T> for JsonSingle<T> {}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for JsonSingle<T> {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct JsonSingleVisitor<T> {
+            phantom: PhantomData<T>,
+        }
+
+        impl<T: DeserializeOwned> JsonSingleVisitor<T> {
+            fn no_match<E: de::Error>() -> std::result::Result<JsonSingle<T>, E> {
+                T::deserialize(serde_json::Value::Null)
+                    .map(JsonSingle)
+                    .map_err(|_| E::custom("JSONPath matched no value"))
+            }
+        }
+
+        fn multiple_matches<E: de::Error>(len: usize) -> E {
+            E::custom(format!(
+                "JSONPath matched {len} values, expected a single one"
+            ))
+        }
+
+        impl<'de, T: DeserializeOwned> Visitor<'de> for JsonSingleVisitor<T> {
+            type Value = JsonSingle<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a single value matched by a JSONPath")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> std::result::Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let Some(value) = seq.next_element::<T>()? else {
+                    return Self::no_match();
+                };
+
+                let mut len = 1;
+                while seq.next_element::<IgnoredAny>()?.is_some() {
+                    len += 1;
+                }
+
+                if len > 1 {
+                    return Err(multiple_matches(len));
+                }
+
+                Ok(JsonSingle(value))
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> std::result::Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                let mut values: Vec<serde_json::Value> =
+                    serde_json::from_slice(v).map_err(E::custom)?;
+
+                match values.len() {
+                    0 => Self::no_match(),
+                    1 => T::deserialize(values.swap_remove(0))
+                        .map(JsonSingle)
+                        .map_err(E::custom),
+                    len => Err(multiple_matches(len)),
+                }
+            }
+
+            fn visit_str<E>(self, v: &str) -> std::result::Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                self.visit_bytes(v.as_bytes())
+            }
+
+            fn visit_none<E>(self) -> std::result::Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Self::no_match()
+            }
+
+            fn visit_unit<E>(self) -> std::result::Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Self::no_match()
+            }
+        }
+
+        deserializer.deserialize_any(JsonSingleVisitor {
+            phantom: PhantomData,
+        })
+    }
+}
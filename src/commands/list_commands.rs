@@ -140,6 +140,9 @@ pub trait ListCommands<'a> {
     /// # Return
     /// The list of popped elements, or empty collection when key does not exist.
     ///
+    /// [`lpop_one`](ListCommands::lpop_one) and [`lpop_count`](ListCommands::lpop_count)
+    /// should be preferred, the shape of their response not depending on the caller's type annotation.
+    ///
     /// # See Also
     /// [<https://redis.io/commands/lpop/>](https://redis.io/commands/lpop/)
     #[must_use]
@@ -153,6 +156,40 @@ pub trait ListCommands<'a> {
         prepare_command(self, cmd("LPOP").arg(key).arg(count))
     }
 
+    /// Removes and returns the first element of the list stored at key.
+    ///
+    /// # Return
+    /// The popped element, or `None` when key does not exist.
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/lpop/>](https://redis.io/commands/lpop/)
+    #[must_use]
+    fn lpop_one<K, E>(self, key: K) -> PreparedCommand<'a, Self, Option<E>>
+    where
+        Self: Sized,
+        K: SingleArg,
+        E: PrimitiveResponse + DeserializeOwned,
+    {
+        prepare_command(self, cmd("LPOP").arg(key))
+    }
+
+    /// Removes and returns up to `count` first elements of the list stored at key.
+    ///
+    /// # Return
+    /// The list of popped elements, or an empty list when key does not exist.
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/lpop/>](https://redis.io/commands/lpop/)
+    #[must_use]
+    fn lpop_count<K, E>(self, key: K, count: usize) -> PreparedCommand<'a, Self, Vec<E>>
+    where
+        Self: Sized,
+        K: SingleArg,
+        E: PrimitiveResponse + DeserializeOwned,
+    {
+        prepare_command(self, cmd("LPOP").arg(key).arg(count))
+    }
+
     /// Returns the index of matching elements inside a Redis list.
     ///
     /// # Return
@@ -317,11 +354,14 @@ pub trait ListCommands<'a> {
         prepare_command(self, cmd("LTRIM").arg(key).arg(start).arg(stop))
     }
 
-    /// Removes and returns the first elements of the list stored at key.
+    /// Removes and returns the last elements of the list stored at key.
     ///
     /// # Return
     /// The list of popped elements, or empty collection when key does not exist.
     ///
+    /// [`rpop_one`](ListCommands::rpop_one) and [`rpop_count`](ListCommands::rpop_count)
+    /// should be preferred, the shape of their response not depending on the caller's type annotation.
+    ///
     /// # See Also
     /// [<https://redis.io/commands/rpop/>](https://redis.io/commands/rpop/)
     #[must_use]
@@ -335,6 +375,40 @@ pub trait ListCommands<'a> {
         prepare_command(self, cmd("RPOP").arg(key).arg(count))
     }
 
+    /// Removes and returns the last element of the list stored at key.
+    ///
+    /// # Return
+    /// The popped element, or `None` when key does not exist.
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/rpop/>](https://redis.io/commands/rpop/)
+    #[must_use]
+    fn rpop_one<K, E>(self, key: K) -> PreparedCommand<'a, Self, Option<E>>
+    where
+        Self: Sized,
+        K: SingleArg,
+        E: PrimitiveResponse + DeserializeOwned,
+    {
+        prepare_command(self, cmd("RPOP").arg(key))
+    }
+
+    /// Removes and returns up to `count` last elements of the list stored at key.
+    ///
+    /// # Return
+    /// The list of popped elements, or an empty list when key does not exist.
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/rpop/>](https://redis.io/commands/rpop/)
+    #[must_use]
+    fn rpop_count<K, E>(self, key: K, count: usize) -> PreparedCommand<'a, Self, Vec<E>>
+    where
+        Self: Sized,
+        K: SingleArg,
+        E: PrimitiveResponse + DeserializeOwned,
+    {
+        prepare_command(self, cmd("RPOP").arg(key).arg(count))
+    }
+
     /// Insert all the specified values at the tail of the list stored at key
     ///
     /// # Return
@@ -389,6 +463,7 @@ impl ToArgs for LInsertWhere {
 }
 
 /// Where option for the [`lmove`](ListCommands::lmove) command.
+#[derive(Clone, Copy)]
 pub enum LMoveWhere {
     Left,
     Right,
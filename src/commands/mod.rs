@@ -30,6 +30,7 @@ These is the list of existing command traits:
 * [`StreamCommands`]: [Streams](https://redis.io/docs/data-types/streams/)
 * [`StringCommands`]: [Strings](https://redis.io/docs/data-types/strings/)
 * [`TransactionCommands`]: [Transactions](https://redis.io/docs/manual/transactions/)
+* [`VectorSetCommands`]: [Vector sets](https://redis.io/docs/data-types/vector-sets/)
 
 Redis Stack commands:
 * [`BloomCommands`]: [Bloom filters](https://redis.io/docs/stack/bloom/)
@@ -115,6 +116,12 @@ mod internal_pub_sub_commands;
 #[cfg(feature = "redis-json")]
 mod json_commands;
 mod list_commands;
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "redis-search", feature = "redis-time-series")))
+)]
+#[cfg(any(feature = "redis-search", feature = "redis-time-series"))]
+mod module_config;
 mod pub_sub_commands;
 mod scripting_commands;
 #[cfg_attr(docsrs, doc(cfg(feature = "redis-search")))]
@@ -136,6 +143,7 @@ mod time_series_commands;
 #[cfg(feature = "redis-bloom")]
 mod top_k_commands;
 mod transaction_commands;
+mod vector_set_commands;
 
 pub use bitmap_commands::*;
 pub use blocking_commands::*;
@@ -170,6 +178,12 @@ pub(crate) use internal_pub_sub_commands::*;
 #[cfg(feature = "redis-json")]
 pub use json_commands::*;
 pub use list_commands::*;
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "redis-search", feature = "redis-time-series")))
+)]
+#[cfg(any(feature = "redis-search", feature = "redis-time-series"))]
+pub use module_config::*;
 pub use pub_sub_commands::*;
 pub use scripting_commands::*;
 #[cfg_attr(docsrs, doc(cfg(feature = "redis-search")))]
@@ -191,3 +205,4 @@ pub use time_series_commands::*;
 #[cfg(feature = "redis-bloom")]
 pub use top_k_commands::*;
 pub use transaction_commands::*;
+pub use vector_set_commands::*;
@@ -0,0 +1,126 @@
+use crate::{Error, Result};
+
+/// Value of a module configuration parameter,
+/// parsed according to its [key](ModuleConfigKey)
+///
+/// See [`Client::module_config_get`](crate::client::Client::module_config_get)
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModuleConfigValue {
+    Integer(i64),
+    Double(f64),
+    Boolean(bool),
+    /// One of the known values of the parameter, in lower case (e.g. `fail` for `search-on-timeout`)
+    Enum(String),
+    String(String),
+}
+
+impl ModuleConfigValue {
+    /// Integer of a [`ModuleConfigValue::Integer`]
+    #[must_use]
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            ModuleConfigValue::Integer(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    /// Double of a [`ModuleConfigValue::Double`] or of a [`ModuleConfigValue::Integer`]
+    #[must_use]
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            ModuleConfigValue::Double(d) => Some(*d),
+            ModuleConfigValue::Integer(i) => Some(*i as f64),
+            _ => None,
+        }
+    }
+
+    /// Boolean of a [`ModuleConfigValue::Boolean`]
+    #[must_use]
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            ModuleConfigValue::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// String of a [`ModuleConfigValue::Enum`] or of a [`ModuleConfigValue::String`]
+    #[must_use]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            ModuleConfigValue::Enum(s) | ModuleConfigValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+/// Known configuration parameter of a Redis module
+///
+/// Since Redis 8, module parameters are read & written with
+/// [`config_get`](crate::commands::ServerCommands::config_get) &
+/// [`config_set`](crate::commands::ServerCommands::config_set)
+/// under a prefixed name (e.g. `search-timeout`),
+/// while older servers expose them through a module command (e.g. `FT.CONFIG GET TIMEOUT`).
+///
+/// See [`Client::module_config_get`](crate::client::Client::module_config_get)
+pub trait ModuleConfigKey {
+    /// Name of the parameter since Redis 8, e.g. `search-timeout`
+    fn name(&self) -> &'static str;
+
+    /// Module command & name of the parameter before Redis 8, e.g. `("FT.CONFIG", "TIMEOUT")`
+    ///
+    /// `None` if the parameter cannot be read at runtime before Redis 8.
+    fn legacy(&self) -> Option<(&'static str, &'static str)>;
+
+    /// Parse a value of the parameter, as returned by the server
+    ///
+    /// # Errors
+    /// An [`Error::Client`] if the value does not match the type of the parameter
+    fn parse_value(&self, value: &str) -> Result<ModuleConfigValue>;
+}
+
+/// Type of the values of a [`ModuleConfigKey`]
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ModuleConfigKind {
+    Integer,
+    #[cfg(feature = "redis-time-series")]
+    Double,
+    #[cfg(feature = "redis-search")]
+    Boolean,
+    Enum(&'static [&'static str]),
+    #[cfg(feature = "redis-time-series")]
+    String,
+}
+
+impl ModuleConfigKind {
+    pub fn parse(self, name: &str, value: &str) -> Result<ModuleConfigValue> {
+        let invalid = || Error::Client(format!("Invalid value `{value}` for config `{name}`"));
+
+        match self {
+            ModuleConfigKind::Integer => value
+                .parse()
+                .map(ModuleConfigValue::Integer)
+                .map_err(|_| invalid()),
+            #[cfg(feature = "redis-time-series")]
+            ModuleConfigKind::Double => value
+                .parse()
+                .map(ModuleConfigValue::Double)
+                .map_err(|_| invalid()),
+            #[cfg(feature = "redis-search")]
+            ModuleConfigKind::Boolean => match value.to_ascii_lowercase().as_str() {
+                "yes" | "true" | "on" | "1" => Ok(ModuleConfigValue::Boolean(true)),
+                "no" | "false" | "off" | "0" => Ok(ModuleConfigValue::Boolean(false)),
+                _ => Err(invalid()),
+            },
+            ModuleConfigKind::Enum(values) => {
+                let value = value.to_ascii_lowercase();
+                if values.contains(&value.as_str()) {
+                    Ok(ModuleConfigValue::Enum(value))
+                } else {
+                    Err(invalid())
+                }
+            }
+            #[cfg(feature = "redis-time-series")]
+            ModuleConfigKind::String => Ok(ModuleConfigValue::String(value.to_owned())),
+        }
+    }
+}
@@ -166,8 +166,11 @@ pub trait PubSubCommands<'a> {
 
     /// Posts a message to the given shard channel.
     ///
+    /// On a cluster client, the command is sent to the master node owning
+    /// the hash slot of `shardchannel`, without relying on a `MOVED` redirection.
+    ///
     /// # Return
-    /// The number of clients that received the message.
+    /// The number of clients that received the message on the master node owning the shard channel.
     ///
     /// # See Also
     /// [<https://redis.io/commands/spublish/>](https://redis.io/commands/spublish/)
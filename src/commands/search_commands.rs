@@ -1,17 +1,22 @@
+#[cfg(feature = "redis-json")]
+use crate::Error;
 use crate::{
     client::{prepare_command, PreparedCommand},
-    commands::{GeoUnit, SortOrder},
+    commands::{GeoUnit, ModuleConfigKey, ModuleConfigKind, ModuleConfigValue, SortOrder},
     resp::{
         cmd, deserialize_vec_of_pairs, CollectionResponse, Command, CommandArgs,
         KeyValueCollectionResponse, MultipleArgsCollection, PrimitiveResponse, RespDeserializer,
         SingleArg, SingleArgCollection, ToArgs, Value, VecOfPairsSeed,
     },
+    Result,
 };
 use serde::{
-    de::{self, value::SeqAccessDeserializer, DeserializeOwned, DeserializeSeed, Visitor},
+    de::{
+        self, value::SeqAccessDeserializer, DeserializeOwned, DeserializeSeed, IgnoredAny, Visitor,
+    },
     Deserialize, Deserializer,
 };
-use std::{collections::HashMap, fmt, future};
+use std::{collections::HashMap, fmt, future, time::Duration};
 
 /// A group of Redis commands related to [`RedisSearch`](https://redis.io/docs/stack/search/)
 ///
@@ -144,6 +149,12 @@ pub trait SearchCommands<'a> {
     ///
     /// # Arguments
     /// * `option` - name of the configuration option, or '*' for all.
+    ///   A known option can be given as a [`FtConfigKey`].
+    ///
+    /// Since Redis 8, this command is deprecated in favor of `CONFIG GET search-*`,
+    /// but is still supported with the legacy option names.
+    /// [`Client::module_config_get`](crate::client::Client::module_config_get)
+    /// picks the right command for the connected server and parses the value.
     ///
     /// # Return
     /// Key/value collection holding names & values of the requested configs
@@ -2187,6 +2198,38 @@ pub struct FtSearchResultRow {
     pub values: Vec<(String, String)>,
 }
 
+impl FtSearchResultRow {
+    /// Convert the attribute/value pairs of the row to a Rust type `T` by using serde deserialization
+    ///
+    /// Each attribute is mapped to a field of `T` with the same name.
+    /// For documents indexed [`on JSON`](FtIndexDataType::Json) and returned as a single `$` attribute,
+    /// the JSON payload is deserialized directly (requires the `redis-json` feature).
+    ///
+    /// # Errors
+    /// Any parsing error ([`Error::Client`](crate::Error::Client)) due to incompatibility between the attributes and the target type
+    pub fn to<T: DeserializeOwned>(&self) -> Result<T> {
+        #[cfg(feature = "redis-json")]
+        if let [(attribute, json)] = self.values.as_slice() {
+            if attribute == "$" {
+                return serde_json::from_str(json).map_err(|e| Error::Client(e.to_string()));
+            }
+        }
+
+        let values = self
+            .values
+            .iter()
+            .map(|(attribute, value)| {
+                (
+                    Value::BulkString(attribute.as_bytes().to_vec()),
+                    Value::BulkString(value.as_bytes().to_vec()),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+
+        Value::Map(values).into()
+    }
+}
+
 impl<'de> Deserialize<'de> for FtSearchResult {
     #[inline]
     fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
@@ -2569,14 +2612,18 @@ pub struct FtIndexDefinition {
 /// Result for the [`ft_profile_search`](SearchCommands::ft_profile_search) command.
 #[derive(Debug, Deserialize)]
 pub struct FtProfileSearchResult {
+    #[serde(alias = "Results")]
     pub results: FtSearchResult,
+    #[serde(alias = "Profile")]
     pub profile_details: FtProfileDetails,
 }
 
 /// Result for the [`ft_profile_aggregate`](SearchCommands::ft_profile_aggregate) command.
 #[derive(Debug, Deserialize)]
 pub struct FtProfileAggregateResult {
+    #[serde(alias = "Results")]
     pub results: FtAggregateResult,
+    #[serde(alias = "Profile")]
     pub profile_details: FtProfileDetails,
 }
 
@@ -2600,6 +2647,103 @@ pub struct FtProfileDetails {
     pub result_processors_profile: Vec<FtResultProcessorsProfile>,
 }
 
+impl FtProfileDetails {
+    /// The total runtime of the query
+    #[must_use]
+    pub fn total_time(&self) -> Duration {
+        Duration::from_secs_f64(self.total_profile_time.max(0.) / 1000.)
+    }
+
+    /// Typed tree of the index iterators,
+    /// `None` if [`iterators_profile`](FtProfileDetails::iterators_profile) is empty
+    #[must_use]
+    pub fn iterators(&self) -> Option<FtIteratorProfile> {
+        FtIteratorProfile::from_fields(&self.iterators_profile)
+    }
+
+    /// The index iterator which spent the most time on its own,
+    /// see [`self_time`](FtIteratorProfile::self_time)
+    #[must_use]
+    pub fn slowest_iterator(&self) -> Option<FtIteratorProfile> {
+        fn slowest(iterator: FtIteratorProfile) -> FtIteratorProfile {
+            let self_time = iterator.self_time();
+            let slowest_child = iterator
+                .child_iterators
+                .iter()
+                .cloned()
+                .map(slowest)
+                .max_by(|a, b| a.self_time().total_cmp(&b.self_time()));
+
+            match slowest_child {
+                Some(child) if child.self_time() > self_time => child,
+                _ => iterator,
+            }
+        }
+
+        self.iterators().map(slowest)
+    }
+
+    /// Flattened representation of the profile, suitable for logging:
+    /// the index iterators (depth first), followed by the result processors chain
+    #[must_use]
+    pub fn rows(&self) -> Vec<FtProfileRow> {
+        fn push_iterator(rows: &mut Vec<FtProfileRow>, iterator: &FtIteratorProfile, depth: usize) {
+            rows.push(FtProfileRow {
+                stage: FtProfileStage::Iterator,
+                depth,
+                _type: iterator._type.clone(),
+                detail: iterator
+                    .term
+                    .clone()
+                    .or_else(|| iterator.query_type.clone()),
+                time: iterator.time,
+                counter: iterator.counter,
+            });
+
+            for child in &iterator.child_iterators {
+                push_iterator(rows, child, depth + 1);
+            }
+        }
+
+        let mut rows = Vec::new();
+        if let Some(iterator) = self.iterators() {
+            push_iterator(&mut rows, &iterator, 0);
+        }
+
+        rows.extend(
+            self.result_processors_profile
+                .iter()
+                .map(|processor| FtProfileRow {
+                    stage: FtProfileStage::ResultProcessor,
+                    depth: 0,
+                    _type: processor._type.clone(),
+                    detail: None,
+                    time: processor.time,
+                    counter: processor.counter,
+                }),
+        );
+
+        rows
+    }
+}
+
+/// Multi-line table of the profile, see [`rows`](FtProfileDetails::rows)
+impl fmt::Display for FtProfileDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "total: {:.3}ms, parsing: {:.3}ms, pipeline creation: {:.3}ms",
+            self.total_profile_time, self.parsing_time, self.pipeline_creation_time
+        )?;
+
+        for row in self.rows() {
+            write!(f, "\n{row}")?;
+        }
+
+        Ok(())
+    }
+}
+
 impl<'de> Deserialize<'de> for FtProfileDetails {
     #[inline]
     fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
@@ -2612,6 +2756,7 @@ impl<'de> Deserialize<'de> for FtProfileDetails {
             PipelineCreationTime(f64),
             IteratorsProfile(HashMap<String, Value>),
             ResultProcessorsProfile(Vec<FtResultProcessorsProfile>),
+            Ignored,
         }
 
         struct FtProfileDetailsFieldVisitor;
@@ -2654,6 +2799,7 @@ impl<'de> Deserialize<'de> for FtProfileDetails {
                         let Some(value) = seq.next_element()? else {
                             return Err(de::Error::invalid_length(1, &"more elements in sequence"));
                         };
+                        let value = iterator_fields(value).map_err(de::Error::custom)?;
                         Ok(FtProfileDetailsField::IteratorsProfile(value))
                     }
                     "Result processors profile" => {
@@ -2669,7 +2815,11 @@ impl<'de> Deserialize<'de> for FtProfileDetails {
 
                         Ok(FtProfileDetailsField::ResultProcessorsProfile(results))
                     }
-                    _ => Err(de::Error::unknown_field(field, &[])),
+                    // e.g. `Warning` since RediSearch 2.8
+                    _ => {
+                        while seq.next_element::<IgnoredAny>()?.is_some() {}
+                        Ok(FtProfileDetailsField::Ignored)
+                    }
                 }
             }
         }
@@ -2688,6 +2838,58 @@ impl<'de> Deserialize<'de> for FtProfileDetails {
             }
         }
 
+        #[derive(Default)]
+        struct FtProfileDetailsBuilder {
+            total_profile_time: Option<f64>,
+            parsing_time: Option<f64>,
+            pipeline_creation_time: Option<f64>,
+            iterators_profile: Option<HashMap<String, Value>>,
+            result_processors_profile: Option<Vec<FtResultProcessorsProfile>>,
+        }
+
+        impl FtProfileDetailsBuilder {
+            fn set(&mut self, field: FtProfileDetailsField) {
+                match field {
+                    FtProfileDetailsField::TotalProfileTime(v) => self.total_profile_time = Some(v),
+                    FtProfileDetailsField::ParsingTime(v) => self.parsing_time = Some(v),
+                    FtProfileDetailsField::PipelineCreationTime(v) => {
+                        self.pipeline_creation_time = Some(v)
+                    }
+                    FtProfileDetailsField::IteratorsProfile(v) => self.iterators_profile = Some(v),
+                    FtProfileDetailsField::ResultProcessorsProfile(v) => {
+                        self.result_processors_profile = Some(v)
+                    }
+                    FtProfileDetailsField::Ignored => (),
+                }
+            }
+
+            fn build<E: de::Error>(self) -> std::result::Result<FtProfileDetails, E> {
+                let total_profile_time = self
+                    .total_profile_time
+                    .ok_or_else(|| de::Error::missing_field("total_profile_time"))?;
+                let parsing_time = self
+                    .parsing_time
+                    .ok_or_else(|| de::Error::missing_field("parsing_time"))?;
+                let pipeline_creation_time = self
+                    .pipeline_creation_time
+                    .ok_or_else(|| de::Error::missing_field("pipeline_creation_time"))?;
+                let iterators_profile = self
+                    .iterators_profile
+                    .ok_or_else(|| de::Error::missing_field("iterators_profile"))?;
+                let result_processors_profile = self
+                    .result_processors_profile
+                    .ok_or_else(|| de::Error::missing_field("result_processors_profile"))?;
+
+                Ok(FtProfileDetails {
+                    total_profile_time,
+                    parsing_time,
+                    pipeline_creation_time,
+                    iterators_profile,
+                    result_processors_profile,
+                })
+            }
+        }
+
         struct FtProfileDetailsVisitor;
 
         impl<'de> Visitor<'de> for FtProfileDetailsVisitor {
@@ -2697,53 +2899,125 @@ impl<'de> Deserialize<'de> for FtProfileDetails {
                 formatter.write_str("FtProfileDetails")
             }
 
+            /// RESP2: sequence of `[name, value]` pairs
             fn visit_seq<A>(self, mut seq: A) -> std::result::Result<Self::Value, A::Error>
             where
                 A: de::SeqAccess<'de>,
             {
-                let mut total_profile_time = None;
-                let mut parsing_time = None;
-                let mut pipeline_creation_time = None;
-                let mut iterators_profile = None;
-                let mut result_processors_profile = None;
+                let mut builder = FtProfileDetailsBuilder::default();
 
                 while let Some(field) = seq.next_element_seed(FtProfileDetailsFieldSeed)? {
-                    match field {
-                        FtProfileDetailsField::TotalProfileTime(v) => total_profile_time = Some(v),
-                        FtProfileDetailsField::ParsingTime(v) => parsing_time = Some(v),
-                        FtProfileDetailsField::PipelineCreationTime(v) => {
-                            pipeline_creation_time = Some(v)
+                    builder.set(field);
+                }
+
+                builder.build()
+            }
+
+            /// RESP3 (RediSearch 2.8+): map of the profile fields,
+            /// or map of the profile of each shard (RediSearch 8+)
+            fn visit_map<A>(self, mut map: A) -> std::result::Result<Self::Value, A::Error>
+            where
+                A: de::MapAccess<'de>,
+            {
+                let mut builder = FtProfileDetailsBuilder::default();
+                let mut shard = None;
+
+                while let Some(key) = map.next_key::<String>()? {
+                    let field = match key.as_str() {
+                        "Total profile time" => {
+                            FtProfileDetailsField::TotalProfileTime(map.next_value()?)
                         }
-                        FtProfileDetailsField::IteratorsProfile(v) => iterators_profile = Some(v),
-                        FtProfileDetailsField::ResultProcessorsProfile(v) => {
-                            result_processors_profile = Some(v)
+                        "Parsing time" => FtProfileDetailsField::ParsingTime(map.next_value()?),
+                        "Pipeline creation time" => {
+                            FtProfileDetailsField::PipelineCreationTime(map.next_value()?)
                         }
-                    }
+                        "Iterators profile" => FtProfileDetailsField::IteratorsProfile(
+                            iterator_fields(map.next_value()?).map_err(de::Error::custom)?,
+                        ),
+                        "Result processors profile" => {
+                            FtProfileDetailsField::ResultProcessorsProfile(map.next_value()?)
+                        }
+                        "Shards" => {
+                            let shards: Vec<FtProfileDetails> = map.next_value()?;
+                            shard = shards.into_iter().next();
+                            FtProfileDetailsField::Ignored
+                        }
+                        _ => {
+                            map.next_value::<IgnoredAny>()?;
+                            FtProfileDetailsField::Ignored
+                        }
+                    };
+                    builder.set(field);
                 }
 
-                let total_profile_time = total_profile_time
-                    .ok_or_else(|| de::Error::missing_field("total_profile_time"))?;
-                let parsing_time =
-                    parsing_time.ok_or_else(|| de::Error::missing_field("parsing_time"))?;
-                let pipeline_creation_time = pipeline_creation_time
-                    .ok_or_else(|| de::Error::missing_field("pipeline_creation_time"))?;
-                let iterators_profile = iterators_profile
-                    .ok_or_else(|| de::Error::missing_field("iterators_profile"))?;
-                let result_processors_profile = result_processors_profile
-                    .ok_or_else(|| de::Error::missing_field("result_processors_profile"))?;
+                match shard {
+                    Some(shard) => Ok(shard),
+                    None => builder.build(),
+                }
+            }
+        }
 
-                Ok(FtProfileDetails {
-                    total_profile_time,
-                    parsing_time,
-                    pipeline_creation_time,
-                    iterators_profile,
-                    result_processors_profile,
-                })
+        deserializer.deserialize_any(FtProfileDetailsVisitor)
+    }
+}
+
+/// Fields of an iterator profile
+///
+/// Iterators are flat sequences of names & values, in which the child iterators
+/// are the trailing elements (RESP2), or maps, with the root iterator wrapped
+/// in an array (RESP3, since RediSearch 2.8).
+/// Child iterators are normalized into maps, whatever the protocol.
+fn iterator_fields(value: Value) -> Result<HashMap<String, Value>> {
+    const CHILD_ITERATORS: &str = "Child iterators";
+
+    let mut fields: HashMap<String, Value> = match value {
+        Value::Nil => HashMap::new(),
+        Value::Array(mut values)
+            if values.len() == 1 && matches!(values[0], Value::Array(_) | Value::Map(_)) =>
+        {
+            return iterator_fields(values.pop().unwrap_or_default());
+        }
+        Value::Array(values) => {
+            let mut fields = HashMap::new();
+            let mut values = values.into_iter();
+
+            while let Some(name) = values.next() {
+                let name: String = name.into()?;
+                if name == CHILD_ITERATORS {
+                    let mut children: Vec<Value> = values.collect();
+                    // children wrapped in a single array
+                    if let [Value::Array(wrapped)] = children.as_mut_slice() {
+                        if matches!(wrapped.first(), Some(Value::Array(_) | Value::Map(_))) {
+                            children = std::mem::take(wrapped);
+                        }
+                    }
+                    fields.insert(name, Value::Array(children));
+                    break;
+                }
+
+                fields.insert(name, values.next().unwrap_or(Value::Nil));
             }
+
+            fields
         }
+        value => value.into()?,
+    };
 
-        deserializer.deserialize_seq(FtProfileDetailsVisitor)
+    if let Some(Value::Array(children)) = fields.remove(CHILD_ITERATORS) {
+        let children = children
+            .into_iter()
+            .map(|child| {
+                let fields = iterator_fields(child)?
+                    .into_iter()
+                    .map(|(name, value)| (Value::BulkString(name.into_bytes()), value))
+                    .collect();
+                Ok(Value::Map(fields))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        fields.insert(CHILD_ITERATORS.to_owned(), Value::Array(children));
     }
+
+    Ok(fields)
 }
 
 /// Result processors profile for the [`ft_profile_search`](SearchCommands::ft_profile_search)
@@ -2758,6 +3032,136 @@ pub struct FtResultProcessorsProfile {
     pub counter: usize,
 }
 
+/// Index iterator profile for the [`ft_profile_search`](SearchCommands::ft_profile_search)
+/// or [`ft_profile_aggregate`](SearchCommands::ft_profile_aggregate) command,
+/// see [`FtProfileDetails::iterators`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FtIteratorProfile {
+    /// Type of the iterator, e.g. `UNION`, `TEXT`, `TAG`, `NUMERIC`, `WILDCARD`
+    pub _type: String,
+    /// Type of the query of a union or intersection iterator
+    pub query_type: Option<String>,
+    /// Term of a reader iterator
+    pub term: Option<String>,
+    /// Time spent by the iterator, including its child iterators, in milliseconds
+    pub time: f64,
+    /// Number of read operations
+    pub counter: usize,
+    /// Number of elements of an inverted-index iterator
+    pub size: Option<usize>,
+    /// Child iterators of a union or intersection iterator
+    pub child_iterators: Vec<FtIteratorProfile>,
+}
+
+impl FtIteratorProfile {
+    /// Time spent by the iterator itself, excluding its child iterators, in milliseconds
+    #[must_use]
+    pub fn self_time(&self) -> f64 {
+        let children_time: f64 = self.child_iterators.iter().map(|c| c.time).sum();
+        (self.time - children_time).max(0.)
+    }
+
+    fn from_fields(fields: &HashMap<String, Value>) -> Option<Self> {
+        Self::from_lookup(&|name| fields.get(name))
+    }
+
+    fn from_lookup<'a>(get: &dyn Fn(&str) -> Option<&'a Value>) -> Option<Self> {
+        fn to_string(value: &Value) -> Option<String> {
+            match value {
+                Value::SimpleString(s) => Some(s.clone()),
+                Value::BulkString(s) | Value::VerbatimString { text: s, .. } => {
+                    String::from_utf8(s.clone()).ok()
+                }
+                Value::Integer(i) => Some(i.to_string()),
+                Value::Double(d) => Some(d.to_string()),
+                _ => None,
+            }
+        }
+
+        fn to_f64(value: &Value) -> Option<f64> {
+            match value {
+                Value::Integer(i) => Some(*i as f64),
+                Value::Double(d) => Some(*d),
+                value => to_string(value)?.parse().ok(),
+            }
+        }
+
+        let string = |name: &str| get(name).and_then(to_string);
+        let number = |name: &str| get(name).and_then(to_f64);
+
+        // child iterators are normalized into maps, see `iterator_fields`
+        let child_iterators = match get("Child iterators") {
+            Some(Value::Array(children)) => children
+                .iter()
+                .filter_map(|child| match child {
+                    Value::Map(fields) => Self::from_lookup(&|name| {
+                        fields
+                            .iter()
+                            .find(|(key, _)| to_string(key).as_deref() == Some(name))
+                            .map(|(_, value)| value)
+                    }),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        Some(Self {
+            _type: string("Type")?,
+            query_type: string("Query type"),
+            term: string("Term"),
+            time: number("Time").unwrap_or_default(),
+            counter: number("Counter").unwrap_or_default() as usize,
+            size: number("Size").map(|size| size as usize),
+            child_iterators,
+        })
+    }
+}
+
+/// Stage of a [`FtProfileRow`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FtProfileStage {
+    /// Index iterator
+    Iterator,
+    /// Result processor
+    ResultProcessor,
+}
+
+/// Row of the flattened representation of a [`FtProfileDetails`],
+/// see [`FtProfileDetails::rows`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FtProfileRow {
+    pub stage: FtProfileStage,
+    /// Depth of an iterator in the iterators tree, `0` for the root iterator and the result processors
+    pub depth: usize,
+    /// Type of the iterator or of the result processor
+    pub _type: String,
+    /// Term or query type of an iterator
+    pub detail: Option<String>,
+    /// Time, in milliseconds
+    pub time: f64,
+    pub counter: usize,
+}
+
+impl fmt::Display for FtProfileRow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stage = match self.stage {
+            FtProfileStage::Iterator => "iterator",
+            FtProfileStage::ResultProcessor => "processor",
+        };
+        let name = match &self.detail {
+            Some(detail) => format!("{}{} {detail}", "  ".repeat(self.depth), self._type),
+            None => format!("{}{}", "  ".repeat(self.depth), self._type),
+        };
+
+        write!(
+            f,
+            "{stage:<9} {name:<32} time: {:.3}ms, counter: {}",
+            self.time, self.counter
+        )
+    }
+}
+
 /// Options for the [`ft_search`](SearchCommands::ft_search) command.
 #[derive(Default)]
 pub struct FtSearchOptions {
@@ -3585,3 +3989,135 @@ impl FtSuggestion {
         deserializer.deserialize_seq(FtSuggestionVecVisitor { command })
     }
 }
+
+/// Known configuration parameters of [`RedisSearch`](https://redis.io/docs/stack/search/)
+///
+/// Written as their legacy name by [`ft_config_get`](SearchCommands::ft_config_get)
+/// & [`ft_config_set`](SearchCommands::ft_config_set),
+/// and read with their typed value by [`Client::module_config_get`](crate::client::Client::module_config_get)
+/// on both servers before and since Redis 8.
+///
+/// # See Also
+/// [Configuration parameters](https://redis.io/docs/latest/develop/interact/search-and-query/basic-constructs/configuration-parameters/)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FtConfigKey {
+    /// Minimum number of characters allowed for prefix queries (integer)
+    MinPrefix,
+    /// Maximum number of expansions allowed for query prefixes (integer)
+    MaxExpansions,
+    /// Maximum amount of time in milliseconds that a search query is allowed to run (integer)
+    Timeout,
+    /// Response policy for queries that exceed the timeout (`return` or `fail`)
+    OnTimeout,
+    /// Minimum word length to stem (integer)
+    MinStemLen,
+    /// Default query dialect (integer)
+    DefaultDialect,
+    /// Maximum number of results returned by `FT.SEARCH` (integer)
+    MaxSearchResults,
+    /// Maximum number of results returned by `FT.AGGREGATE` (integer)
+    MaxAggregateResults,
+    /// Maximum idle time in milliseconds of a cursor (integer)
+    CursorMaxIdle,
+    /// Number of worker threads executing queries (integer)
+    Workers,
+    /// Disable the garbage collection of indexes (boolean)
+    NoGc,
+    /// Number of entries processed by each garbage collection run (integer)
+    GcScanSize,
+    /// Interval in seconds between two runs of the fork garbage collector (integer)
+    ForkGcRunInterval,
+    /// Minimum number of dirty entries cleaned by the fork garbage collector (integer)
+    ForkGcCleanThreshold,
+    /// Maximum number of iterators of a union loaded in a heap (integer)
+    UnionIteratorHeap,
+}
+
+impl FtConfigKey {
+    fn spec(self) -> (&'static str, &'static str, ModuleConfigKind) {
+        match self {
+            FtConfigKey::MinPrefix => ("search-min-prefix", "MINPREFIX", ModuleConfigKind::Integer),
+            FtConfigKey::MaxExpansions => (
+                "search-max-prefix-expansions",
+                "MAXEXPANSIONS",
+                ModuleConfigKind::Integer,
+            ),
+            FtConfigKey::Timeout => ("search-timeout", "TIMEOUT", ModuleConfigKind::Integer),
+            FtConfigKey::OnTimeout => (
+                "search-on-timeout",
+                "ON_TIMEOUT",
+                ModuleConfigKind::Enum(&["return", "fail"]),
+            ),
+            FtConfigKey::MinStemLen => (
+                "search-min-stem-len",
+                "MINSTEMLEN",
+                ModuleConfigKind::Integer,
+            ),
+            FtConfigKey::DefaultDialect => (
+                "search-default-dialect",
+                "DEFAULT_DIALECT",
+                ModuleConfigKind::Integer,
+            ),
+            FtConfigKey::MaxSearchResults => (
+                "search-max-search-results",
+                "MAXSEARCHRESULTS",
+                ModuleConfigKind::Integer,
+            ),
+            FtConfigKey::MaxAggregateResults => (
+                "search-max-aggregate-results",
+                "MAXAGGREGATERESULTS",
+                ModuleConfigKind::Integer,
+            ),
+            FtConfigKey::CursorMaxIdle => (
+                "search-cursor-max-idle",
+                "CURSOR_MAX_IDLE",
+                ModuleConfigKind::Integer,
+            ),
+            FtConfigKey::Workers => ("search-workers", "WORKERS", ModuleConfigKind::Integer),
+            FtConfigKey::NoGc => ("search-no-gc", "NOGC", ModuleConfigKind::Boolean),
+            FtConfigKey::GcScanSize => (
+                "search-gc-scan-size",
+                "GC_SCANSIZE",
+                ModuleConfigKind::Integer,
+            ),
+            FtConfigKey::ForkGcRunInterval => (
+                "search-fork-gc-run-interval",
+                "FORK_GC_RUN_INTERVAL",
+                ModuleConfigKind::Integer,
+            ),
+            FtConfigKey::ForkGcCleanThreshold => (
+                "search-fork-gc-clean-threshold",
+                "FORK_GC_CLEAN_THRESHOLD",
+                ModuleConfigKind::Integer,
+            ),
+            FtConfigKey::UnionIteratorHeap => (
+                "search-union-iterator-heap",
+                "UNION_ITERATOR_HEAP",
+                ModuleConfigKind::Integer,
+            ),
+        }
+    }
+}
+
+impl ModuleConfigKey for FtConfigKey {
+    fn name(&self) -> &'static str {
+        self.spec().0
+    }
+
+    fn legacy(&self) -> Option<(&'static str, &'static str)> {
+        Some(("FT.CONFIG", self.spec().1))
+    }
+
+    fn parse_value(&self, value: &str) -> Result<ModuleConfigValue> {
+        let (name, _, kind) = self.spec();
+        kind.parse(name, value)
+    }
+}
+
+impl ToArgs for FtConfigKey {
+    fn write_args(&self, args: &mut CommandArgs) {
+        args.arg(self.spec().1);
+    }
+}
+
+impl SingleArg for FtConfigKey {}
@@ -1573,7 +1573,11 @@ pub struct CommandDoc {
     #[serde(default)]
     pub history: Vec<HistoricalNote>,
     /// an array of [`command arguments`](https://redis.io/docs/reference/command-arguments/)
+    #[serde(default)]
     pub arguments: Vec<CommandArgument>,
+    /// documentation of the subcommands of a container command, by `command|subcommand` name.
+    #[serde(default)]
+    pub subcommands: HashMap<String, CommandDoc>,
 }
 
 /// Command documenation flag
@@ -2197,6 +2201,38 @@ pub enum RoleResult {
     },
 }
 
+impl RoleResult {
+    /// The role of the instance
+    pub fn role(&self) -> Role {
+        match self {
+            RoleResult::Master { .. } => Role::Master,
+            RoleResult::Replica { .. } => Role::Replica,
+            RoleResult::Sentinel { .. } => Role::Sentinel,
+        }
+    }
+
+    /// The replicas connected to a master, empty for other roles
+    pub fn replicas(&self) -> &[ReplicaInfo] {
+        match self {
+            RoleResult::Master { replica_infos, .. } => replica_infos,
+            _ => &[],
+        }
+    }
+
+    /// The state of the link with the master of a replica, `None` for other roles
+    pub fn master_link_state(&self) -> Option<ReplicationState> {
+        match self {
+            RoleResult::Replica { state, .. } => Some(*state),
+            _ => None,
+        }
+    }
+
+    /// `true` if the instance is a replica online with its master
+    pub fn is_master_link_up(&self) -> bool {
+        self.master_link_state() == Some(ReplicationState::Connected)
+    }
+}
+
 impl<'de> Deserialize<'de> for RoleResult {
     fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
     where
@@ -2272,6 +2308,19 @@ impl<'de> Deserialize<'de> for RoleResult {
     }
 }
 
+/// Role of a Redis instance in the context of replication
+///
+/// See [`RoleResult::role`] & [`Client::wait_for_role`](crate::client::Client::wait_for_role)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// The instance accepts writes and may be replicated by replicas
+    Master,
+    /// The instance replicates a master
+    Replica,
+    /// The instance is a Sentinel monitoring masters
+    Sentinel,
+}
+
 /// Represents a connected replicas to a master
 ///
 /// returned by the [`role`](ServerCommands::role) command.
@@ -2288,7 +2337,7 @@ pub struct ReplicaInfo {
 /// The state of the replication from the point of view of the master,
 ///
 /// returned by the [`role`](ServerCommands::role) command.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum ReplicationState {
     /// the instance is in handshake with its master
@@ -223,6 +223,9 @@ pub trait SetCommands<'a> {
     /// # Return
     /// the list of popped elements
     ///
+    /// [`spop_one`](SetCommands::spop_one) and [`spop_count`](SetCommands::spop_count)
+    /// should be preferred, the shape of their response not depending on the caller's type annotation.
+    ///
     /// # See Also
     /// [<https://redis.io/commands/spop/>](https://redis.io/commands/spop/)
     #[must_use]
@@ -236,6 +239,40 @@ pub trait SetCommands<'a> {
         prepare_command(self, cmd("SPOP").arg(key).arg(count))
     }
 
+    /// Removes and returns a random member from the set value store at key.
+    ///
+    /// # Return
+    /// The popped member, or `None` when key does not exist.
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/spop/>](https://redis.io/commands/spop/)
+    #[must_use]
+    fn spop_one<K, M>(self, key: K) -> PreparedCommand<'a, Self, Option<M>>
+    where
+        Self: Sized,
+        K: SingleArg,
+        M: PrimitiveResponse + DeserializeOwned,
+    {
+        prepare_command(self, cmd("SPOP").arg(key))
+    }
+
+    /// Removes and returns up to `count` random members from the set value store at key.
+    ///
+    /// # Return
+    /// The list of popped members, or an empty list when key does not exist.
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/spop/>](https://redis.io/commands/spop/)
+    #[must_use]
+    fn spop_count<K, M>(self, key: K, count: usize) -> PreparedCommand<'a, Self, Vec<M>>
+    where
+        Self: Sized,
+        K: SingleArg,
+        M: PrimitiveResponse + DeserializeOwned,
+    {
+        prepare_command(self, cmd("SPOP").arg(key).arg(count))
+    }
+
     /// Removes and returns one or more random members from the set value store at key.
     ///
     /// # Return
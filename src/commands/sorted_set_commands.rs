@@ -6,6 +6,7 @@ use crate::{
     },
 };
 use serde::{de::DeserializeOwned, Deserialize};
+use std::marker::PhantomData;
 
 /// A group of Redis commands related to [`Sorted Sets`](https://redis.io/docs/data-types/sorted-sets/)
 ///
@@ -22,11 +23,11 @@ pub trait SortedSetCommands<'a> {
     /// # See Also
     /// [<https://redis.io/commands/zadd/>](https://redis.io/commands/zadd/)
     #[must_use]
-    fn zadd<K, M, I>(
+    fn zadd<K, M, I, S>(
         self,
         key: K,
         items: I,
-        options: ZAddOptions,
+        options: ZAddOptions<S>,
     ) -> PreparedCommand<'a, Self, usize>
     where
         Self: Sized,
@@ -42,11 +43,13 @@ pub trait SortedSetCommands<'a> {
     ///
     /// # Return
     /// The new score of member (a double precision floating point number),
-    /// or nil if the operation was aborted (when called with either the XX or the NX option).
+    /// or `None` if the operation was aborted because of the `NX`, `XX`, `GT` or `LT` options.
     ///
     /// # See Also
     /// [<https://redis.io/commands/zadd/>](https://redis.io/commands/zadd/)
     #[must_use]
+    #[deprecated(note = "use `zadd_incr_with_options` instead")]
+    #[allow(deprecated)]
     fn zadd_incr<K, M>(
         self,
         key: K,
@@ -68,6 +71,40 @@ pub trait SortedSetCommands<'a> {
                 .arg(condition)
                 .arg(comparison)
                 .arg_if(change, "CH")
+                .arg("INCR")
+                .arg(score)
+                .arg(member),
+        )
+    }
+
+    /// In this mode ZADD acts like ZINCRBY.
+    /// Only one score-element pair can be specified in this mode.
+    ///
+    /// # Return
+    /// The new score of member (a double precision floating point number),
+    /// or `None` if the operation was aborted because of the `NX`, `XX`, `GT` or `LT` options.
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/zadd/>](https://redis.io/commands/zadd/)
+    #[must_use]
+    fn zadd_incr_with_options<K, M, S>(
+        self,
+        key: K,
+        score: f64,
+        member: M,
+        options: ZAddOptions<S>,
+    ) -> PreparedCommand<'a, Self, Option<f64>>
+    where
+        Self: Sized,
+        K: SingleArg,
+        M: SingleArg,
+    {
+        prepare_command(
+            self,
+            cmd("ZADD")
+                .arg(key)
+                .arg(options)
+                .arg("INCR")
                 .arg(score)
                 .arg(member),
         )
@@ -92,6 +129,8 @@ pub trait SortedSetCommands<'a> {
 
     /// Returns the number of elements in the sorted set at key with a score between min and max.
     ///
+    /// `min` & `max` can be given as [`ZRangeBound<f64>`](ZRangeBound) to express exclusive or infinite bounds.
+    ///
     /// # Return
     /// The number of elements in the specified score range.
     ///
@@ -322,6 +361,8 @@ pub trait SortedSetCommands<'a> {
     /// in order to force lexicographical ordering, this command returns the number
     /// of elements in the sorted set at key with a value between min and max.
     ///
+    /// `min` & `max` can be given as [`ZRangeBound`] of a [lexicographical value](ZLexValue).
+    ///
     /// # Return
     /// the number of elements in the specified score range.
     ///
@@ -410,6 +451,23 @@ pub trait SortedSetCommands<'a> {
         prepare_command(self, cmd("ZPOPMAX").arg(key).arg(count))
     }
 
+    /// Removes and returns the member with the highest score in the sorted set stored at key.
+    ///
+    /// # Return
+    /// The popped member and its score, or `None` when key does not exist.
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/zpopmax/>](https://redis.io/commands/zpopmax/)
+    #[must_use]
+    fn zpopmax_one<K, M>(self, key: K) -> PreparedCommand<'a, Self, Option<(M, f64)>>
+    where
+        Self: Sized,
+        K: SingleArg,
+        M: PrimitiveResponse + DeserializeOwned,
+    {
+        prepare_command(self, cmd("ZPOPMAX").arg(key))
+    }
+
     /// Removes and returns up to count members with the lowest scores in the sorted set stored at key.
     ///
     /// # Return
@@ -427,6 +485,23 @@ pub trait SortedSetCommands<'a> {
         prepare_command(self, cmd("ZPOPMIN").arg(key).arg(count))
     }
 
+    /// Removes and returns the member with the lowest score in the sorted set stored at key.
+    ///
+    /// # Return
+    /// The popped member and its score, or `None` when key does not exist.
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/zpopmin/>](https://redis.io/commands/zpopmin/)
+    #[must_use]
+    fn zpopmin_one<K, M>(self, key: K) -> PreparedCommand<'a, Self, Option<(M, f64)>>
+    where
+        Self: Sized,
+        K: SingleArg,
+        M: PrimitiveResponse + DeserializeOwned,
+    {
+        prepare_command(self, cmd("ZPOPMIN").arg(key))
+    }
+
     /// Return a random element from the sorted set value stored at key.
     ///
     /// # Return
@@ -586,6 +661,99 @@ pub trait SortedSetCommands<'a> {
         )
     }
 
+    /// Returns the range of elements described by `query` in the sorted set stored at `key`.
+    ///
+    /// Typed alternative to [zrange](SortedSetCommands::zrange)
+    ///
+    /// # Return
+    /// A collection of elements in the specified range
+    ///
+    /// # Example
+    /// ```
+    /// # use rustis::{
+    /// #    client::Client,
+    /// #    commands::{GenericCommands, SortedSetCommands, ZAddOptions, ZRangeBound, ZRangeQuery},
+    /// #    Result,
+    /// # };
+    /// # #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+    /// # #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+    /// # async fn main() -> Result<()> {
+    /// # let client = Client::connect("127.0.0.1:6379").await?;
+    /// # client.del("key").await?;
+    /// client
+    ///     .zadd("key", [(1.0, "one"), (2.0, "two"), (3.0, "three")], ZAddOptions::default())
+    ///     .await?;
+    ///
+    /// // ZRANGE key +inf (1 BYSCORE REV
+    /// let values: Vec<String> = client
+    ///     .zrange_query(
+    ///         "key",
+    ///         ZRangeQuery::by_score(ZRangeBound::Exclusive(1.0), ZRangeBound::Max).reverse(),
+    ///     )
+    ///     .await?;
+    /// assert_eq!(vec!["three".to_owned(), "two".to_owned()], values);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/zrange/>](https://redis.io/commands/zrange/)
+    #[must_use]
+    fn zrange_query<K, E>(self, key: K, query: ZRangeQuery) -> PreparedCommand<'a, Self, Vec<E>>
+    where
+        Self: Sized,
+        K: SingleArg,
+        E: PrimitiveResponse + DeserializeOwned,
+    {
+        prepare_command(self, cmd("ZRANGE").arg(key).arg(query))
+    }
+
+    /// Returns the range of elements described by `query` in the sorted set stored at `key`.
+    ///
+    /// Typed alternative to [zrange_with_scores](SortedSetCommands::zrange_with_scores)
+    ///
+    /// # Return
+    /// A collection of elements and their scores in the specified range
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/zrange/>](https://redis.io/commands/zrange/)
+    #[must_use]
+    fn zrange_query_with_scores<K, E>(
+        self,
+        key: K,
+        query: ZRangeQuery,
+    ) -> PreparedCommand<'a, Self, Vec<(E, f64)>>
+    where
+        Self: Sized,
+        K: SingleArg,
+        E: PrimitiveResponse + DeserializeOwned,
+    {
+        prepare_command(self, cmd("ZRANGE").arg(key).arg(query).arg("WITHSCORES"))
+    }
+
+    /// This command is like [zrange_query](SortedSetCommands::zrange_query),
+    /// but stores the result in the `dst` destination key.
+    ///
+    /// # Return
+    /// The number of elements in the resulting sorted set.
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/zrangestore/>](https://redis.io/commands/zrangestore/)
+    #[must_use]
+    fn zrangestore_query<D, S>(
+        self,
+        dst: D,
+        src: S,
+        query: ZRangeQuery,
+    ) -> PreparedCommand<'a, Self, usize>
+    where
+        Self: Sized,
+        D: SingleArg,
+        S: SingleArg,
+    {
+        prepare_command(self, cmd("ZRANGESTORE").arg(dst).arg(src).arg(query))
+    }
+
     /// Returns the rank of member in the sorted set stored at key,
     /// with the scores ordered from low to high.
     ///
@@ -628,6 +796,8 @@ pub trait SortedSetCommands<'a> {
     /// this command removes all elements in the sorted set stored at key
     /// between the lexicographical range specified by min and max.
     ///
+    /// `start` & `stop` can be given as [`ZRangeBound`] of a [lexicographical value](ZLexValue).
+    ///
     /// # Return
     /// the number of elements removed.
     ///
@@ -666,6 +836,8 @@ pub trait SortedSetCommands<'a> {
 
     /// Removes all elements in the sorted set stored at key with a score between min and max (inclusive).
     ///
+    /// `start` & `stop` can be given as [`ZRangeBound<f64>`](ZRangeBound) to express exclusive or infinite bounds.
+    ///
     /// # Return
     /// the number of elements removed.
     ///
@@ -840,62 +1012,6 @@ pub trait SortedSetCommands<'a> {
     }
 }
 
-/// Condition option for the [`zadd`](SortedSetCommands::zadd) command
-#[derive(Default)]
-pub enum ZAddCondition {
-    /// No condition
-    #[default]
-    None,
-    /// Only update elements that already exist. Don't add new elements.
-    NX,
-    /// Only add new elements. Don't update already existing elements.
-    XX,
-}
-
-impl ToArgs for ZAddCondition {
-    fn write_args(&self, args: &mut CommandArgs) {
-        match self {
-            ZAddCondition::None => {}
-            ZAddCondition::NX => {
-                args.arg("NX");
-            }
-            ZAddCondition::XX => {
-                args.arg("XX");
-            }
-        }
-    }
-}
-
-/// Comparison option for the [`zadd`](SortedSetCommands::zadd) command
-#[derive(Default)]
-pub enum ZAddComparison {
-    /// No comparison
-    #[default]
-    None,
-    /// Only update existing elements if the new score is greater than the current score.
-    ///
-    /// This flag doesn't prevent adding new elements.
-    GT,
-    /// Only update existing elements if the new score is less than the current score.
-    ///
-    /// This flag doesn't prevent adding new elements.
-    LT,
-}
-
-impl ToArgs for ZAddComparison {
-    fn write_args(&self, args: &mut CommandArgs) {
-        match self {
-            ZAddComparison::None => {}
-            ZAddComparison::GT => {
-                args.arg("GT");
-            }
-            ZAddComparison::LT => {
-                args.arg("LT");
-            }
-        }
-    }
-}
-
 /// sort by option of the [`zrange`](SortedSetCommands::zrange) command
 #[derive(Default)]
 pub enum ZRangeSortBy {
@@ -979,38 +1095,228 @@ impl ToArgs for ZWhere {
     }
 }
 
-/// Options for the [`zadd`](SortedSetCommands::zadd) command.
-#[derive(Default)]
-pub struct ZAddOptions {
-    command_args: CommandArgs,
+/// Condition option for the [`zadd_incr`](SortedSetCommands::zadd_incr) command
+#[deprecated(note = "use `ZAddOptions::nx` or `ZAddOptions::xx` instead")]
+pub enum ZAddCondition {
+    /// No condition
+    None,
+    /// Only add new elements. Don't update already existing elements.
+    NX,
+    /// Only update elements that already exist. Don't add new elements.
+    XX,
 }
 
-impl ZAddOptions {
-    #[must_use]
-    pub fn condition(mut self, condition: ZAddCondition) -> Self {
-        Self {
-            command_args: self.command_args.arg(condition).build(),
+#[allow(deprecated, clippy::derivable_impls)]
+impl Default for ZAddCondition {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+#[allow(deprecated)]
+impl ToArgs for ZAddCondition {
+    fn write_args(&self, args: &mut CommandArgs) {
+        match self {
+            ZAddCondition::None => {}
+            ZAddCondition::NX => {
+                args.arg("NX");
+            }
+            ZAddCondition::XX => {
+                args.arg("XX");
+            }
         }
     }
+}
 
-    #[must_use]
-    pub fn comparison(mut self, comparison: ZAddComparison) -> Self {
+/// Comparison option for the [`zadd_incr`](SortedSetCommands::zadd_incr) command
+#[deprecated(note = "use `ZAddOptions::gt` or `ZAddOptions::lt` instead")]
+pub enum ZAddComparison {
+    /// No comparison
+    None,
+    /// Only update existing elements if the new score is greater than the current score.
+    ///
+    /// This flag doesn't prevent adding new elements.
+    GT,
+    /// Only update existing elements if the new score is less than the current score.
+    ///
+    /// This flag doesn't prevent adding new elements.
+    LT,
+}
+
+#[allow(deprecated, clippy::derivable_impls)]
+impl Default for ZAddComparison {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+#[allow(deprecated)]
+impl ToArgs for ZAddComparison {
+    fn write_args(&self, args: &mut CommandArgs) {
+        match self {
+            ZAddComparison::None => {}
+            ZAddComparison::GT => {
+                args.arg("GT");
+            }
+            ZAddComparison::LT => {
+                args.arg("LT");
+            }
+        }
+    }
+}
+
+/// Options for the [`zadd`](SortedSetCommands::zadd)
+/// and [`zadd_incr_with_options`](SortedSetCommands::zadd_incr_with_options) commands.
+///
+/// The type parameter tracks the options already set, so that the combinations rejected by Redis
+/// (`NX` with `XX`, `GT` or `LT`) do not compile:
+/// ```compile_fail
+/// # use rustis::commands::ZAddOptions;
+/// let options = ZAddOptions::default().nx().gt();
+/// ```
+/// ```compile_fail
+/// # use rustis::commands::ZAddOptions;
+/// let options = ZAddOptions::default().xx().nx();
+/// ```
+pub struct ZAddOptions<S = ZAddNoCondition> {
+    condition: Option<&'static str>,
+    comparison: Option<&'static str>,
+    change: bool,
+    phantom: PhantomData<S>,
+}
+
+/// State of [`ZAddOptions`] without `NX`, `GT` or `LT` option
+pub struct ZAddNoCondition;
+
+/// State of [`ZAddOptions`] with the `NX` option
+pub struct ZAddNx;
+
+/// State of [`ZAddOptions`] with the `XX` option
+pub struct ZAddXx;
+
+/// State of [`ZAddOptions`] with the `GT` or `LT` option
+pub struct ZAddGtLt;
+
+impl Default for ZAddOptions<ZAddNoCondition> {
+    fn default() -> Self {
         Self {
-            command_args: self.command_args.arg(comparison).build(),
+            condition: None,
+            comparison: None,
+            change: false,
+            phantom: PhantomData,
         }
     }
+}
 
+impl<S> ZAddOptions<S> {
+    /// Modify the return value from the number of new elements added,
+    /// to the total number of elements changed (new elements added and existing elements updated).
     #[must_use]
     pub fn change(mut self) -> Self {
-        Self {
-            command_args: self.command_args.arg("CH").build(),
+        self.change = true;
+        self
+    }
+
+    /// Sets the condition option without checking its compatibility with the other options
+    #[must_use]
+    #[deprecated(note = "use `nx` or `xx` instead")]
+    #[allow(deprecated)]
+    pub fn condition(mut self, condition: ZAddCondition) -> Self {
+        self.condition = match condition {
+            ZAddCondition::None => None,
+            ZAddCondition::NX => Some("NX"),
+            ZAddCondition::XX => Some("XX"),
+        };
+        self
+    }
+
+    /// Sets the comparison option without checking its compatibility with the other options
+    #[must_use]
+    #[deprecated(note = "use `gt` or `lt` instead")]
+    #[allow(deprecated)]
+    pub fn comparison(mut self, comparison: ZAddComparison) -> Self {
+        self.comparison = match comparison {
+            ZAddComparison::None => None,
+            ZAddComparison::GT => Some("GT"),
+            ZAddComparison::LT => Some("LT"),
+        };
+        self
+    }
+
+    fn into_state<T>(self) -> ZAddOptions<T> {
+        ZAddOptions {
+            condition: self.condition,
+            comparison: self.comparison,
+            change: self.change,
+            phantom: PhantomData,
         }
     }
 }
 
-impl ToArgs for ZAddOptions {
+impl ZAddOptions<ZAddNoCondition> {
+    /// Only add new elements. Don't update already existing elements.
+    #[must_use]
+    pub fn nx(mut self) -> ZAddOptions<ZAddNx> {
+        self.condition = Some("NX");
+        self.into_state()
+    }
+
+    /// Only update elements that already exist. Don't add new elements.
+    #[must_use]
+    pub fn xx(mut self) -> ZAddOptions<ZAddXx> {
+        self.condition = Some("XX");
+        self.into_state()
+    }
+
+    /// Only update existing elements if the new score is greater than the current score.
+    ///
+    /// This flag doesn't prevent adding new elements.
+    #[must_use]
+    pub fn gt(mut self) -> ZAddOptions<ZAddGtLt> {
+        self.comparison = Some("GT");
+        self.into_state()
+    }
+
+    /// Only update existing elements if the new score is less than the current score.
+    ///
+    /// This flag doesn't prevent adding new elements.
+    #[must_use]
+    pub fn lt(mut self) -> ZAddOptions<ZAddGtLt> {
+        self.comparison = Some("LT");
+        self.into_state()
+    }
+}
+
+impl ZAddOptions<ZAddXx> {
+    /// Only update existing elements if the new score is greater than the current score.
+    #[must_use]
+    pub fn gt(mut self) -> ZAddOptions<ZAddGtLt> {
+        self.comparison = Some("GT");
+        self.into_state()
+    }
+
+    /// Only update existing elements if the new score is less than the current score.
+    #[must_use]
+    pub fn lt(mut self) -> ZAddOptions<ZAddGtLt> {
+        self.comparison = Some("LT");
+        self.into_state()
+    }
+}
+
+impl ZAddOptions<ZAddGtLt> {
+    /// Only update elements that already exist. Don't add new elements.
+    #[must_use]
+    pub fn xx(mut self) -> Self {
+        self.condition = Some("XX");
+        self
+    }
+}
+
+impl<S> ToArgs for ZAddOptions<S> {
     fn write_args(&self, args: &mut CommandArgs) {
-        self.command_args.write_args(args);
+        args.arg(self.condition)
+            .arg(self.comparison)
+            .arg_if(self.change, "CH");
     }
 }
 
@@ -1058,6 +1364,174 @@ impl ToArgs for ZRangeOptions {
     }
 }
 
+/// Bound of a score or lexicographical range of a sorted set
+///
+/// Score bounds are [`ZRangeBound<f64>`](ZRangeBound),
+/// lexicographical bounds are [`ZRangeBound<L>`](ZRangeBound) where `L` is a [`ZLexValue`].
+///
+/// # See Also
+/// [`ZRangeQuery`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ZRangeBound<T> {
+    /// Values equal to the bound are in the range (e.g. `1.5` or `[a`)
+    Inclusive(T),
+    /// Values equal to the bound are not in the range (e.g. `(1.5` or `(a`)
+    Exclusive(T),
+    /// Lowest possible bound (`-inf` or `-`)
+    Min,
+    /// Highest possible bound (`+inf` or `+`)
+    Max,
+}
+
+/// Value of a [`ZRangeBound`]
+pub trait ZRangeValue: SingleArg {
+    /// Argument of [`ZRangeBound::Min`]
+    const MIN: &'static str;
+    /// Argument of [`ZRangeBound::Max`]
+    const MAX: &'static str;
+    /// Prefix of the argument of [`ZRangeBound::Inclusive`]
+    const INCLUSIVE_PREFIX: &'static str;
+}
+
+impl ZRangeValue for f64 {
+    const MIN: &'static str = "-inf";
+    const MAX: &'static str = "+inf";
+    const INCLUSIVE_PREFIX: &'static str = "";
+}
+
+/// Value of a lexicographical [`ZRangeBound`]
+pub trait ZLexValue: SingleArg {}
+
+impl ZLexValue for &str {}
+impl ZLexValue for String {}
+impl ZLexValue for &[u8] {}
+impl ZLexValue for Vec<u8> {}
+
+impl<T: ZLexValue> ZRangeValue for T {
+    const MIN: &'static str = "-";
+    const MAX: &'static str = "+";
+    const INCLUSIVE_PREFIX: &'static str = "[";
+}
+
+impl<T: ZRangeValue> ToArgs for ZRangeBound<T> {
+    fn write_args(&self, args: &mut CommandArgs) {
+        let (prefix, value) = match self {
+            ZRangeBound::Inclusive(value) => (T::INCLUSIVE_PREFIX, value),
+            ZRangeBound::Exclusive(value) => ("(", value),
+            ZRangeBound::Min => {
+                args.arg(T::MIN);
+                return;
+            }
+            ZRangeBound::Max => {
+                args.arg(T::MAX);
+                return;
+            }
+        };
+
+        let value_args = CommandArgs::default().arg_ref(value).build();
+        let mut bound = prefix.as_bytes().to_vec();
+        if let Some(value) = value_args.into_iter().next() {
+            bound.extend_from_slice(value);
+        }
+        args.write_arg(&bound);
+    }
+}
+
+impl<T: ZRangeValue> SingleArg for ZRangeBound<T> {}
+
+/// Typed range of a [`zrange_query`](SortedSetCommands::zrange_query)
+/// or [`zrangestore_query`](SortedSetCommands::zrangestore_query) command
+///
+/// The kind of range (index, score or lexicographical) is chosen by the constructor,
+/// so that both bounds are always of the same kind.
+///
+/// # See Also
+/// [<https://redis.io/commands/zrange/>](https://redis.io/commands/zrange/)
+#[derive(Clone)]
+pub struct ZRangeQuery {
+    start: CommandArgs,
+    stop: CommandArgs,
+    sort_by: Option<&'static str>,
+    reverse: bool,
+    limit: Option<(usize, isize)>,
+}
+
+impl ZRangeQuery {
+    /// Range of elements between the ranks `start` and `stop` (inclusive)
+    ///
+    /// Negative ranks are offsets from the end of the sorted set (e.g. `-1` for the last element).
+    #[must_use]
+    pub fn by_index(start: isize, stop: isize) -> Self {
+        Self::new(start, stop, None)
+    }
+
+    /// Range of elements with a score between `min` and `max` (`BYSCORE`)
+    #[must_use]
+    pub fn by_score(min: ZRangeBound<f64>, max: ZRangeBound<f64>) -> Self {
+        Self::new(min, max, Some("BYSCORE"))
+    }
+
+    /// Range of elements between `min` and `max` in lexicographical order (`BYLEX`)
+    ///
+    /// All the elements of the sorted set are expected to have the same score.
+    #[must_use]
+    pub fn by_lex<L: ZLexValue>(min: ZRangeBound<L>, max: ZRangeBound<L>) -> Self {
+        Self::new(min, max, Some("BYLEX"))
+    }
+
+    fn new(start: impl ToArgs, stop: impl ToArgs, sort_by: Option<&'static str>) -> Self {
+        Self {
+            start: CommandArgs::default().arg(start).build(),
+            stop: CommandArgs::default().arg(stop).build(),
+            sort_by,
+            reverse: false,
+            limit: None,
+        }
+    }
+
+    /// Return the elements ordered from the highest to the lowest (`REV`)
+    ///
+    /// Score & lexicographical bounds are still given as `min` then `max`:
+    /// they are swapped as expected by the server.
+    #[must_use]
+    pub fn reverse(mut self) -> Self {
+        self.reverse = true;
+        self
+    }
+
+    /// Return only `count` elements starting at `offset` (`LIMIT`)
+    ///
+    /// A negative `count` returns all the elements from `offset`.
+    /// Ignored for index ranges, which do not support it.
+    #[must_use]
+    pub fn limit(mut self, offset: usize, count: isize) -> Self {
+        self.limit = Some((offset, count));
+        self
+    }
+}
+
+impl ToArgs for ZRangeQuery {
+    fn write_args(&self, args: &mut CommandArgs) {
+        if self.reverse && self.sort_by.is_some() {
+            args.arg_ref(&self.stop).arg_ref(&self.start);
+        } else {
+            args.arg_ref(&self.start).arg_ref(&self.stop);
+        }
+
+        if let Some(sort_by) = self.sort_by {
+            args.arg(sort_by);
+        }
+
+        if self.reverse {
+            args.arg("REV");
+        }
+
+        if let (Some((offset, count)), Some(_)) = (self.limit, self.sort_by) {
+            args.arg("LIMIT").arg(offset).arg(count);
+        }
+    }
+}
+
 /// Options for the [`zscan`](SortedSetCommands::zscan) command
 #[derive(Default)]
 pub struct ZScanOptions {
@@ -5,7 +5,10 @@ use crate::{
         SingleArg, SingleArgCollection, ToArgs,
     },
 };
-use serde::{de::DeserializeOwned, Deserialize};
+use serde::{
+    de::{self, DeserializeOwned},
+    Deserialize, Deserializer,
+};
 use std::collections::HashMap;
 
 /// A group of Redis commands related to [`Streams`](https://redis.io/docs/data-types/streams/)
@@ -34,6 +37,44 @@ pub trait StreamCommands<'a> {
         prepare_command(self, cmd("XACK").arg(key).arg(group).arg(ids))
     }
 
+    /// The XACKDEL command acknowledges one or multiple messages for a stream consumer group
+    /// and deletes them from the stream, in a single atomic operation.
+    ///
+    /// # Arguments
+    /// * `policy` - How the references of the messages in the Pending Entries List (PEL)
+    ///   of the other consumer groups are handled, see [`XRefPolicy`].
+    ///
+    /// # Return
+    /// A result code for each message id, in the order of the ids, see [`XDelExResult`].
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/xackdel/>](https://redis.io/commands/xackdel/)
+    fn xackdel<K, G, I, II>(
+        self,
+        key: K,
+        group: G,
+        policy: XRefPolicy,
+        ids: II,
+    ) -> PreparedCommand<'a, Self, Vec<XDelExResult>>
+    where
+        Self: Sized,
+        K: SingleArg,
+        G: SingleArg,
+        I: SingleArg,
+        II: SingleArgCollection<I>,
+    {
+        prepare_command(
+            self,
+            cmd("XACKDEL")
+                .arg(key)
+                .arg(group)
+                .arg(policy)
+                .arg("IDS")
+                .arg(ids.num_args())
+                .arg(ids),
+        )
+    }
+
     /// Appends the specified stream entry to the stream at the specified key.
     ///
     /// # Return
@@ -164,6 +205,41 @@ pub trait StreamCommands<'a> {
         prepare_command(self, cmd("XDEL").arg(key).arg(ids))
     }
 
+    /// Removes the specified entries from a stream,
+    /// with a control over the references of the entries held by consumer groups.
+    ///
+    /// # Arguments
+    /// * `policy` - How the references of the entries in the Pending Entries List (PEL)
+    ///   of the consumer groups are handled, see [`XRefPolicy`].
+    ///
+    /// # Return
+    /// A result code for each entry id, in the order of the ids, see [`XDelExResult`].
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/xdelex/>](https://redis.io/commands/xdelex/)
+    fn xdelex<K, I, II>(
+        self,
+        key: K,
+        policy: XRefPolicy,
+        ids: II,
+    ) -> PreparedCommand<'a, Self, Vec<XDelExResult>>
+    where
+        Self: Sized,
+        K: SingleArg,
+        I: SingleArg,
+        II: SingleArgCollection<I>,
+    {
+        prepare_command(
+            self,
+            cmd("XDELEX")
+                .arg(key)
+                .arg(policy)
+                .arg("IDS")
+                .arg(ids.num_args())
+                .arg(ids),
+        )
+    }
+
     /// This command creates a new consumer group uniquely identified by `groupname` for the stream stored at `key`.
     ///
     /// # Return
@@ -539,6 +615,25 @@ pub trait StreamCommands<'a> {
         )
     }
 
+    /// Set the last ID of the stream stored at `key`, an internal command used in replication and
+    /// stream maintenance jobs.
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/xsetid/>](https://redis.io/commands/xsetid/)
+    fn xsetid<K, I>(
+        self,
+        key: K,
+        last_id: I,
+        options: XSetIdOptions,
+    ) -> PreparedCommand<'a, Self, ()>
+    where
+        Self: Sized,
+        K: SingleArg,
+        I: SingleArg,
+    {
+        prepare_command(self, cmd("XSETID").arg(key).arg(last_id).arg(options))
+    }
+
     /// XTRIM trims the stream by evicting older entries (entries with lower IDs) if needed.
     ///
     /// # Return
@@ -583,6 +678,62 @@ impl ToArgs for XAddOptions {
     }
 }
 
+/// Policy for the references of the deleted entries held by the consumer groups,
+/// for the [`xdelex`](StreamCommands::xdelex) and [`xackdel`](StreamCommands::xackdel) commands
+#[derive(Default)]
+pub enum XRefPolicy {
+    /// Delete the entries from the stream, but keep their references
+    /// in the Pending Entries List (PEL) of the consumer groups
+    #[default]
+    KeepRef,
+    /// Delete the entries from the stream and their references
+    /// from the Pending Entries List (PEL) of all the consumer groups
+    DelRef,
+    /// Only delete the entries which have been read and acknowledged by all the consumer groups
+    Acked,
+}
+
+impl ToArgs for XRefPolicy {
+    fn write_args(&self, args: &mut CommandArgs) {
+        args.arg(match self {
+            XRefPolicy::KeepRef => "KEEPREF",
+            XRefPolicy::DelRef => "DELREF",
+            XRefPolicy::Acked => "ACKED",
+        });
+    }
+}
+
+/// Result code for each id of the [`xdelex`](StreamCommands::xdelex)
+/// and [`xackdel`](StreamCommands::xackdel) commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XDelExResult {
+    /// The entry does not exist in the stream
+    /// (or, for [`xackdel`](StreamCommands::xackdel), in the Pending Entries List of the group)
+    NotFound,
+    /// The entry has been deleted (and acknowledged for [`xackdel`](StreamCommands::xackdel))
+    Deleted,
+    /// The entry has not been deleted (but acknowledged for [`xackdel`](StreamCommands::xackdel)),
+    /// because it is still referenced by a consumer group with the [`Acked`](XRefPolicy::Acked) policy
+    NotDeleted,
+}
+
+impl<'de> Deserialize<'de> for XDelExResult {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match i64::deserialize(deserializer)? {
+            -1 => Ok(XDelExResult::NotFound),
+            1 => Ok(XDelExResult::Deleted),
+            2 => Ok(XDelExResult::NotDeleted),
+            code => Err(de::Error::invalid_value(
+                de::Unexpected::Signed(code),
+                &"-1, 1 or 2",
+            )),
+        }
+    }
+}
+
 /// Stream Trim operator for the [`xadd`](StreamCommands::xadd)
 /// and [`xtrim`](StreamCommands::xtrim) commands
 #[derive(Default)]
@@ -812,6 +963,46 @@ impl ToArgs for XGroupCreateOptions {
     }
 }
 
+/// Options for the [`xsetid`](StreamCommands::xsetid) command
+#[derive(Default)]
+pub struct XSetIdOptions {
+    command_args: CommandArgs,
+}
+
+impl XSetIdOptions {
+    /// Set the number of entries added to the stream since its creation
+    /// (`entries_added` field of [`xinfo_stream`](StreamCommands::xinfo_stream))
+    #[must_use]
+    pub fn entries_added(mut self, entries_added: usize) -> Self {
+        Self {
+            command_args: self
+                .command_args
+                .arg("ENTRIESADDED")
+                .arg(entries_added)
+                .build(),
+        }
+    }
+
+    /// Set the maximal ID deleted from the stream
+    /// (`max_deleted_entry_id` field of [`xinfo_stream`](StreamCommands::xinfo_stream))
+    #[must_use]
+    pub fn max_deleted_id<I: SingleArg>(mut self, max_deleted_id: I) -> Self {
+        Self {
+            command_args: self
+                .command_args
+                .arg("MAXDELETEDID")
+                .arg(max_deleted_id)
+                .build(),
+        }
+    }
+}
+
+impl ToArgs for XSetIdOptions {
+    fn write_args(&self, args: &mut CommandArgs) {
+        args.arg(&self.command_args);
+    }
+}
+
 /// Result entry for the [`xinfo_consumers`](StreamCommands::xinfo_consumers) command.
 #[derive(Deserialize)]
 pub struct XConsumerInfo {
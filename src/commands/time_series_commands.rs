@@ -1,9 +1,11 @@
 use crate::{
     client::{prepare_command, PreparedCommand},
+    commands::{ModuleConfigKey, ModuleConfigKind, ModuleConfigValue},
     resp::{
         cmd, CollectionResponse, CommandArgs, KeyValueArgsCollection, MultipleArgsCollection,
         PrimitiveResponse, SingleArg, SingleArgCollection, ToArgs, Value,
     },
+    Result,
 };
 use serde::{de::DeserializeOwned, Deserialize};
 use std::collections::HashMap;
@@ -1589,3 +1591,81 @@ impl ToArgs for TsRangeOptions {
         args.arg(&self.command_args);
     }
 }
+
+/// Known configuration parameters of [`Time Series`](https://redis.io/docs/stack/timeseries/)
+///
+/// Before Redis 8, these parameters can only be set at module load time
+/// and cannot be read at runtime.
+///
+/// Written as their Redis 8 name (e.g. `ts-retention-policy`),
+/// to be used with [`config_get`](crate::commands::ServerCommands::config_get)
+/// or with [`Client::module_config_get`](crate::client::Client::module_config_get) to get a typed value.
+///
+/// # See Also
+/// [Configuration parameters](https://redis.io/docs/latest/develop/data-types/timeseries/configuration/)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TsConfigKey {
+    /// Initial allocation size, in bytes, of the data part of each new chunk (integer)
+    ChunkSizeBytes,
+    /// Compaction rules automatically created for new time series (string)
+    CompactionPolicy,
+    /// Default policy for handling duplicate samples
+    /// (`block`, `first`, `last`, `min`, `max` or `sum`)
+    DuplicatePolicy,
+    /// Default chunk encoding of new time series (`compressed` or `uncompressed`)
+    Encoding,
+    /// Default maximum time difference, in milliseconds, to consider a sample as a duplicate (integer)
+    IgnoreMaxTimeDiff,
+    /// Default maximum value difference to consider a sample as a duplicate (double)
+    IgnoreMaxValDiff,
+    /// Maximum number of threads per shard for cluster queries (integer)
+    NumThreads,
+    /// Default retention period, in milliseconds, of new time series (integer)
+    RetentionPolicy,
+}
+
+impl TsConfigKey {
+    fn spec(self) -> (&'static str, ModuleConfigKind) {
+        match self {
+            TsConfigKey::ChunkSizeBytes => ("ts-chunk-size-bytes", ModuleConfigKind::Integer),
+            TsConfigKey::CompactionPolicy => ("ts-compaction-policy", ModuleConfigKind::String),
+            TsConfigKey::DuplicatePolicy => (
+                "ts-duplicate-policy",
+                ModuleConfigKind::Enum(&["block", "first", "last", "min", "max", "sum"]),
+            ),
+            TsConfigKey::Encoding => (
+                "ts-encoding",
+                ModuleConfigKind::Enum(&["compressed", "uncompressed"]),
+            ),
+            TsConfigKey::IgnoreMaxTimeDiff => {
+                ("ts-ignore-max-time-diff", ModuleConfigKind::Integer)
+            }
+            TsConfigKey::IgnoreMaxValDiff => ("ts-ignore-max-val-diff", ModuleConfigKind::Double),
+            TsConfigKey::NumThreads => ("ts-num-threads", ModuleConfigKind::Integer),
+            TsConfigKey::RetentionPolicy => ("ts-retention-policy", ModuleConfigKind::Integer),
+        }
+    }
+}
+
+impl ModuleConfigKey for TsConfigKey {
+    fn name(&self) -> &'static str {
+        self.spec().0
+    }
+
+    fn legacy(&self) -> Option<(&'static str, &'static str)> {
+        None
+    }
+
+    fn parse_value(&self, value: &str) -> Result<ModuleConfigValue> {
+        let (name, kind) = self.spec();
+        kind.parse(name, value)
+    }
+}
+
+impl ToArgs for TsConfigKey {
+    fn write_args(&self, args: &mut CommandArgs) {
+        args.arg(self.spec().0);
+    }
+}
+
+impl SingleArg for TsConfigKey {}
@@ -0,0 +1,617 @@
+use crate::{
+    client::{prepare_command, PreparedCommand},
+    resp::{
+        cmd, CollectionResponse, CommandArgs, KeyValueCollectionResponse, PrimitiveResponse,
+        SingleArg, ToArgs,
+    },
+};
+use serde::de::DeserializeOwned;
+use std::{
+    fmt::{self, Display, Write},
+    ops::Not,
+};
+
+/// A group of Redis commands related to [`Vector sets`](https://redis.io/docs/data-types/vector-sets/)
+///
+/// Vectors are passed as `&[f32]` and are sent to the server
+/// as binary blobs of little-endian 32-bit floats (`FP32`).
+///
+/// # See Also
+/// [Redis Vector Set Commands](https://redis.io/commands/?group=vector_set)
+pub trait VectorSetCommands<'a> {
+    /// Adds an element and its vector to the vector set stored at `key`,
+    /// creating the vector set if it does not exist.
+    ///
+    /// # Return
+    /// * `true` - if the element has been added
+    /// * `false` - if the element was already in the vector set and its vector has been updated
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/vadd/>](https://redis.io/commands/vadd/)
+    #[must_use]
+    fn vadd<K, E>(
+        self,
+        key: K,
+        vector: &[f32],
+        element: E,
+        options: VAddOptions,
+    ) -> PreparedCommand<'a, Self, bool>
+    where
+        Self: Sized,
+        K: SingleArg,
+        E: SingleArg,
+    {
+        prepare_command(
+            self,
+            cmd("VADD")
+                .arg(key)
+                .arg(options.reduce.map(|dim| ("REDUCE", dim)))
+                .arg("FP32")
+                .arg(fp32_blob(vector))
+                .arg(element)
+                .arg(options.command_args),
+        )
+    }
+
+    /// Returns the number of elements in the vector set stored at `key`.
+    ///
+    /// # Return
+    /// The number of elements, or `0` if the key does not exist.
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/vcard/>](https://redis.io/commands/vcard/)
+    #[must_use]
+    fn vcard<K>(self, key: K) -> PreparedCommand<'a, Self, usize>
+    where
+        Self: Sized,
+        K: SingleArg,
+    {
+        prepare_command(self, cmd("VCARD").arg(key))
+    }
+
+    /// Returns the number of dimensions of the vectors of the vector set stored at `key`.
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/vdim/>](https://redis.io/commands/vdim/)
+    #[must_use]
+    fn vdim<K>(self, key: K) -> PreparedCommand<'a, Self, usize>
+    where
+        Self: Sized,
+        K: SingleArg,
+    {
+        prepare_command(self, cmd("VDIM").arg(key))
+    }
+
+    /// Returns the approximate vector associated with an element of the vector set stored at `key`.
+    ///
+    /// # Return
+    /// The components of the vector (dequantized if the vector set is quantized),
+    /// or an empty collection if the key or the element does not exist.
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/vemb/>](https://redis.io/commands/vemb/)
+    #[must_use]
+    fn vemb<K, E, R>(self, key: K, element: E) -> PreparedCommand<'a, Self, R>
+    where
+        Self: Sized,
+        K: SingleArg,
+        E: SingleArg,
+        R: CollectionResponse<f32>,
+    {
+        prepare_command(self, cmd("VEMB").arg(key).arg(element))
+    }
+
+    /// Returns the JSON attributes associated with an element of the vector set stored at `key`.
+    ///
+    /// # Return
+    /// The JSON attributes, or `None` if the key or the element does not exist,
+    /// or if the element has no attributes.
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/vgetattr/>](https://redis.io/commands/vgetattr/)
+    #[must_use]
+    fn vgetattr<K, E>(self, key: K, element: E) -> PreparedCommand<'a, Self, Option<String>>
+    where
+        Self: Sized,
+        K: SingleArg,
+        E: SingleArg,
+    {
+        prepare_command(self, cmd("VGETATTR").arg(key).arg(element))
+    }
+
+    /// Associates JSON attributes with an element of the vector set stored at `key`,
+    /// to be used by the [`filter`](VSimOptions::filter) of the [`vsim`](VectorSetCommands::vsim) command.
+    ///
+    /// An empty string removes the attributes of the element.
+    ///
+    /// # Return
+    /// `true` if the attributes have been set, `false` if the key or the element does not exist.
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/vsetattr/>](https://redis.io/commands/vsetattr/)
+    #[must_use]
+    fn vsetattr<K, E, A>(self, key: K, element: E, attributes: A) -> PreparedCommand<'a, Self, bool>
+    where
+        Self: Sized,
+        K: SingleArg,
+        E: SingleArg,
+        A: SingleArg,
+    {
+        prepare_command(self, cmd("VSETATTR").arg(key).arg(element).arg(attributes))
+    }
+
+    /// Removes an element from the vector set stored at `key`.
+    ///
+    /// # Return
+    /// `true` if the element has been removed, `false` if the key or the element does not exist.
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/vrem/>](https://redis.io/commands/vrem/)
+    #[must_use]
+    fn vrem<K, E>(self, key: K, element: E) -> PreparedCommand<'a, Self, bool>
+    where
+        Self: Sized,
+        K: SingleArg,
+        E: SingleArg,
+    {
+        prepare_command(self, cmd("VREM").arg(key).arg(element))
+    }
+
+    /// Returns the elements of the vector set stored at `key` which are the most similar
+    /// to a vector or to an existing element.
+    ///
+    /// # Return
+    /// A collection of elements, from the most to the least similar.
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/vsim/>](https://redis.io/commands/vsim/)
+    #[must_use]
+    fn vsim<K, E, R>(
+        self,
+        key: K,
+        query: VSimQuery,
+        options: VSimOptions,
+    ) -> PreparedCommand<'a, Self, R>
+    where
+        Self: Sized,
+        K: SingleArg,
+        E: PrimitiveResponse + DeserializeOwned,
+        R: CollectionResponse<E>,
+    {
+        prepare_command(self, cmd("VSIM").arg(key).arg(query).arg(options))
+    }
+
+    /// Returns the elements of the vector set stored at `key` which are the most similar
+    /// to a vector or to an existing element, with their similarity scores.
+    ///
+    /// # Return
+    /// A collection of elements and their similarity scores, between `0` and `1`,
+    /// from the most to the least similar.
+    ///
+    /// # See Also
+    /// [<https://redis.io/commands/vsim/>](https://redis.io/commands/vsim/)
+    #[must_use]
+    fn vsim_with_scores<K, E, R>(
+        self,
+        key: K,
+        query: VSimQuery,
+        options: VSimOptions,
+    ) -> PreparedCommand<'a, Self, R>
+    where
+        Self: Sized,
+        K: SingleArg,
+        E: PrimitiveResponse,
+        R: KeyValueCollectionResponse<E, f32>,
+    {
+        prepare_command(
+            self,
+            cmd("VSIM")
+                .arg(key)
+                .arg(query)
+                .arg("WITHSCORES")
+                .arg(options),
+        )
+    }
+}
+
+/// Encode a vector as a blob of little-endian 32-bit floats
+fn fp32_blob(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|f| f.to_le_bytes()).collect()
+}
+
+/// Quantization of the vectors of a vector set, for the [`vadd`](VectorSetCommands::vadd) command
+pub enum VQuantization {
+    /// 32-bit floats, without quantization
+    NoQuant,
+    /// 8-bit signed integers (default)
+    Q8,
+    /// Binary quantization
+    Bin,
+}
+
+impl ToArgs for VQuantization {
+    fn write_args(&self, args: &mut CommandArgs) {
+        args.arg(match self {
+            VQuantization::NoQuant => "NOQUANT",
+            VQuantization::Q8 => "Q8",
+            VQuantization::Bin => "BIN",
+        });
+    }
+}
+
+/// Options for the [`vadd`](VectorSetCommands::vadd) command
+#[derive(Default)]
+pub struct VAddOptions {
+    reduce: Option<usize>,
+    command_args: CommandArgs,
+}
+
+impl VAddOptions {
+    /// Reduce the dimensions of the vectors with a random projection
+    #[must_use]
+    pub fn reduce(mut self, dim: usize) -> Self {
+        self.reduce = Some(dim);
+        self
+    }
+
+    /// Collect the candidates of the insertion in a background thread,
+    /// the element being inserted only if the graph has not changed meanwhile (check-and-set)
+    #[must_use]
+    pub fn cas(mut self) -> Self {
+        Self {
+            reduce: self.reduce,
+            command_args: self.command_args.arg("CAS").build(),
+        }
+    }
+
+    /// Quantization of the vectors, when the vector set is created
+    #[must_use]
+    pub fn quantization(mut self, quantization: VQuantization) -> Self {
+        Self {
+            reduce: self.reduce,
+            command_args: self.command_args.arg(quantization).build(),
+        }
+    }
+
+    /// Exploration factor used when adding the element to the graph (default `200`)
+    #[must_use]
+    pub fn ef(mut self, build_exploration_factor: usize) -> Self {
+        Self {
+            reduce: self.reduce,
+            command_args: self
+                .command_args
+                .arg("EF")
+                .arg(build_exploration_factor)
+                .build(),
+        }
+    }
+
+    /// JSON attributes associated with the element, see [`vsetattr`](VectorSetCommands::vsetattr)
+    #[must_use]
+    pub fn setattr<A: SingleArg>(mut self, attributes: A) -> Self {
+        Self {
+            reduce: self.reduce,
+            command_args: self.command_args.arg("SETATTR").arg(attributes).build(),
+        }
+    }
+
+    /// Maximum number of connections of each node of the graph (default `16`)
+    #[must_use]
+    pub fn m(mut self, num_links: usize) -> Self {
+        Self {
+            reduce: self.reduce,
+            command_args: self.command_args.arg("M").arg(num_links).build(),
+        }
+    }
+}
+
+/// Query of the [`vsim`](VectorSetCommands::vsim) command
+pub struct VSimQuery {
+    command_args: CommandArgs,
+}
+
+impl VSimQuery {
+    /// Elements similar to `vector`
+    #[must_use]
+    pub fn vector(vector: &[f32]) -> Self {
+        Self {
+            command_args: CommandArgs::default()
+                .arg("FP32")
+                .arg(fp32_blob(vector))
+                .build(),
+        }
+    }
+
+    /// Elements similar to the vector of an existing `element`
+    #[must_use]
+    pub fn element<E: SingleArg>(element: E) -> Self {
+        Self {
+            command_args: CommandArgs::default().arg("ELE").arg(element).build(),
+        }
+    }
+}
+
+impl ToArgs for VSimQuery {
+    fn write_args(&self, args: &mut CommandArgs) {
+        args.arg(&self.command_args);
+    }
+}
+
+/// Options for the [`vsim`](VectorSetCommands::vsim) command
+#[derive(Default)]
+pub struct VSimOptions {
+    command_args: CommandArgs,
+}
+
+impl VSimOptions {
+    /// Maximum number of returned elements (default `10`)
+    #[must_use]
+    pub fn count(mut self, count: usize) -> Self {
+        Self {
+            command_args: self.command_args.arg("COUNT").arg(count).build(),
+        }
+    }
+
+    /// Only return the elements whose distance is below `delta`, between `0` and `1`
+    #[must_use]
+    pub fn epsilon(mut self, delta: f64) -> Self {
+        Self {
+            command_args: self.command_args.arg("EPSILON").arg(delta).build(),
+        }
+    }
+
+    /// Exploration factor of the search
+    #[must_use]
+    pub fn ef(mut self, search_exploration_factor: usize) -> Self {
+        Self {
+            command_args: self
+                .command_args
+                .arg("EF")
+                .arg(search_exploration_factor)
+                .build(),
+        }
+    }
+
+    /// Only return the elements whose attributes match `filter`
+    #[must_use]
+    pub fn filter(mut self, filter: VFilter) -> Self {
+        Self {
+            command_args: self
+                .command_args
+                .arg("FILTER")
+                .arg(filter.expression)
+                .build(),
+        }
+    }
+
+    /// Maximum number of candidates inspected to match the [`filter`](VSimOptions::filter)
+    /// (default `count * 100`, `0` for unlimited)
+    #[must_use]
+    pub fn filter_ef(mut self, max_filtering_effort: usize) -> Self {
+        Self {
+            command_args: self
+                .command_args
+                .arg("FILTER-EF")
+                .arg(max_filtering_effort)
+                .build(),
+        }
+    }
+
+    /// Perform an exact linear scan instead of an approximate graph search
+    #[must_use]
+    pub fn truth(mut self) -> Self {
+        Self {
+            command_args: self.command_args.arg("TRUTH").build(),
+        }
+    }
+
+    /// Run the search in the main thread
+    #[must_use]
+    pub fn no_thread(mut self) -> Self {
+        Self {
+            command_args: self.command_args.arg("NOTHREAD").build(),
+        }
+    }
+}
+
+impl ToArgs for VSimOptions {
+    fn write_args(&self, args: &mut CommandArgs) {
+        args.arg(&self.command_args);
+    }
+}
+
+/// Filter expression on the JSON attributes of the elements,
+/// for the [`vsim`](VectorSetCommands::vsim) command
+///
+/// # Example
+/// ```
+/// use rustis::commands::VFilter;
+///
+/// let filter = VFilter::gte("year", 1980)
+///     .and(VFilter::is_in("genre", ["action", "drama"]))
+///     .and(!VFilter::eq("available", true));
+/// assert_eq!(
+///     r#"(.year >= 1980) and (.genre in ["action", "drama"]) and (!(.available == true))"#,
+///     filter.to_string()
+/// );
+/// ```
+///
+/// # See Also
+/// [Filter expressions](https://redis.io/docs/data-types/vector-sets/filtered-search/)
+#[derive(Debug, Clone, PartialEq)]
+pub struct VFilter {
+    expression: String,
+    /// operator of the top-level chain of `and` or `or`
+    operator: Option<&'static str>,
+}
+
+impl VFilter {
+    /// Filter from a raw expression, e.g. `.year > 1980 and .rating >= 4`
+    #[must_use]
+    pub fn raw(expression: impl Into<String>) -> Self {
+        Self {
+            expression: expression.into(),
+            operator: None,
+        }
+    }
+
+    /// `.attribute == value`
+    #[must_use]
+    pub fn eq(attribute: &str, value: impl Into<VFilterValue>) -> Self {
+        Self::compare(attribute, "==", value.into())
+    }
+
+    /// `.attribute != value`
+    #[must_use]
+    pub fn ne(attribute: &str, value: impl Into<VFilterValue>) -> Self {
+        Self::compare(attribute, "!=", value.into())
+    }
+
+    /// `.attribute > value`
+    #[must_use]
+    pub fn gt(attribute: &str, value: impl Into<VFilterValue>) -> Self {
+        Self::compare(attribute, ">", value.into())
+    }
+
+    /// `.attribute >= value`
+    #[must_use]
+    pub fn gte(attribute: &str, value: impl Into<VFilterValue>) -> Self {
+        Self::compare(attribute, ">=", value.into())
+    }
+
+    /// `.attribute < value`
+    #[must_use]
+    pub fn lt(attribute: &str, value: impl Into<VFilterValue>) -> Self {
+        Self::compare(attribute, "<", value.into())
+    }
+
+    /// `.attribute <= value`
+    #[must_use]
+    pub fn lte(attribute: &str, value: impl Into<VFilterValue>) -> Self {
+        Self::compare(attribute, "<=", value.into())
+    }
+
+    /// `.attribute in [value1, value2, ...]`
+    #[must_use]
+    pub fn is_in<V: Into<VFilterValue>>(
+        attribute: &str,
+        values: impl IntoIterator<Item = V>,
+    ) -> Self {
+        let mut expression = format!(".{attribute} in [");
+        for (i, value) in values.into_iter().enumerate() {
+            if i > 0 {
+                expression.push_str(", ");
+            }
+            let _ = write!(expression, "{}", value.into());
+        }
+        expression.push(']');
+        Self::raw(expression)
+    }
+
+    /// `(self) and (other)`
+    #[must_use]
+    pub fn and(self, other: VFilter) -> Self {
+        self.combine("and", other)
+    }
+
+    /// `(self) or (other)`
+    #[must_use]
+    pub fn or(self, other: VFilter) -> Self {
+        self.combine("or", other)
+    }
+
+    fn compare(attribute: &str, operator: &str, value: VFilterValue) -> Self {
+        Self::raw(format!(".{attribute} {operator} {value}"))
+    }
+
+    fn combine(self, operator: &'static str, other: VFilter) -> Self {
+        // a chain of the same operator is not nested
+        let left = if self.operator == Some(operator) {
+            self.expression
+        } else {
+            format!("({})", self.expression)
+        };
+
+        Self {
+            expression: format!("{left} {operator} ({})", other.expression),
+            operator: Some(operator),
+        }
+    }
+}
+
+/// `!(filter)`
+impl Not for VFilter {
+    type Output = VFilter;
+
+    fn not(self) -> Self::Output {
+        Self::raw(format!("!({})", self.expression))
+    }
+}
+
+impl Display for VFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+/// Value compared to an attribute in a [`VFilter`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum VFilterValue {
+    /// Number, written as is (e.g. `1.5`)
+    Number(f64),
+    /// String, written between double quotes with `"` and `\` escaped
+    String(String),
+    /// Boolean, written as `true` or `false`
+    Bool(bool),
+}
+
+impl Display for VFilterValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VFilterValue::Number(n) => write!(f, "{n}"),
+            VFilterValue::String(s) => {
+                f.write_char('"')?;
+                for c in s.chars() {
+                    if c == '"' || c == '\\' {
+                        f.write_char('\\')?;
+                    }
+                    f.write_char(c)?;
+                }
+                f.write_char('"')
+            }
+            VFilterValue::Bool(b) => write!(f, "{b}"),
+        }
+    }
+}
+
+macro_rules! impl_from_number {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for VFilterValue {
+                fn from(n: $t) -> Self {
+                    VFilterValue::Number(n as f64)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_number!(i8, u8, i16, u16, i32, u32, i64, u64, isize, usize, f32, f64);
+
+impl From<&str> for VFilterValue {
+    fn from(s: &str) -> Self {
+        VFilterValue::String(s.to_owned())
+    }
+}
+
+impl From<String> for VFilterValue {
+    fn from(s: String) -> Self {
+        VFilterValue::String(s)
+    }
+}
+
+impl From<bool> for VFilterValue {
+    fn from(b: bool) -> Self {
+        VFilterValue::Bool(b)
+    }
+}
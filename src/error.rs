@@ -1,4 +1,4 @@
-use crate::Result;
+use crate::{client::CommandJournal, Result, Version};
 use futures_channel::{
     mpsc::{self},
     oneshot,
@@ -9,6 +9,7 @@ use std::{
     num::{ParseFloatError, ParseIntError},
     str::{FromStr, Utf8Error},
     string::FromUtf8Error,
+    time::{Duration, SystemTime},
 };
 
 /// `Internal Use`
@@ -50,6 +51,53 @@ pub enum Error {
     Tls(String),
     /// The I/O operation’s timeout expired
     Timeout(String),
+    /// The maximum number of pending commands has been reached
+    ///
+    /// See [`Config::max_pending_commands`](crate::client::Config::max_pending_commands)
+    QueueFull,
+    /// The command has been introduced after the version of the connected server
+    ///
+    /// See [`Config::strict_server_version`](crate::client::Config::strict_server_version)
+    UnsupportedByServer {
+        /// Name of the command
+        command: String,
+        /// Server version which introduced the command
+        since: Version,
+    },
+    /// The subscriptions of a [`PubSubStream`](crate::client::PubSubStream) have been restored
+    /// after a reconnection: the messages published between `since` and `resumed_at` have been missed
+    ///
+    /// Only yielded by streams which [notify interruptions](crate::client::PubSubStream::notify_interruptions).
+    /// The stream is still usable after this item.
+    SubscriptionInterrupted {
+        /// Number of reconnections of the pub/sub connection, this one included
+        epoch: u64,
+        /// Time at which the connection has been lost
+        since: SystemTime,
+        /// Time at which the subscriptions have been restored
+        resumed_at: SystemTime,
+    },
+    /// A [`Timeout`](Error::Timeout) or [`IO`](Error::IO) failure of a command,
+    /// along with the journal of the last commands of the connection
+    ///
+    /// Only raised when [`Config::command_journal_size`](crate::client::Config::command_journal_size) is set.
+    Journaled {
+        /// Actual error
+        error: Box<Error>,
+        /// Journal of the connection, to be dumped with [`CommandJournal::dump`]
+        journal: CommandJournal,
+    },
+    /// A failure of a command, along with the command name, the target node,
+    /// the attempt number & the elapsed time
+    ///
+    /// Only raised when [`Config::error_context`](crate::client::Config::error_context) is set.
+    /// Errors returned by the Redis server are not wrapped.
+    Context {
+        /// Actual error
+        error: Box<Error>,
+        /// Context of the failure
+        context: ErrorContext,
+    },
     /// Internal error to trigger retry sending the command
     #[doc(hidden)]
     Retry(SmallVec<[RetryReason; 1]>),
@@ -71,11 +119,117 @@ impl std::fmt::Display for Error {
             Error::Tls(e) => f.write_fmt(format_args!("Tls error: {}", e)),
             Error::Retry(r) => f.write_fmt(format_args!("Retry: {:?}", r)),
             Error::Timeout(e) => f.write_fmt(format_args!("Timeout error: {}", e)),
+            Error::QueueFull => f.write_str("Queue full"),
+            Error::UnsupportedByServer { command, since } => f.write_fmt(format_args!(
+                "Command {command} is not supported by the server (since {since})"
+            )),
+            Error::SubscriptionInterrupted {
+                epoch,
+                since,
+                resumed_at,
+            } => f.write_fmt(format_args!(
+                "Subscription interrupted for {:?} (epoch {epoch})",
+                resumed_at.duration_since(*since).unwrap_or_default()
+            )),
+            Error::Journaled { error, .. } => error.fmt(f),
+            Error::Context { error, context } => f.write_fmt(format_args!("{error} ({context})")),
             Error::EOF => f.write_str("EOF error"),
         }
     }
 }
 
+impl Error {
+    /// Journal of the last commands of the connection attached to the error,
+    /// see [`Error::Journaled`]
+    #[must_use]
+    pub fn journal(&self) -> Option<&CommandJournal> {
+        match self {
+            Error::Journaled { journal, .. } => Some(journal),
+            Error::Context { error, .. } => error.journal(),
+            _ => None,
+        }
+    }
+
+    /// Context of the failure attached to the error,
+    /// see [`Error::Context`]
+    #[must_use]
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Error::Context { context, .. } => Some(context),
+            Error::Journaled { error, .. } => error.context(),
+            _ => None,
+        }
+    }
+
+    /// Actual error, without the [`Context`](Error::Context)
+    /// & [`Journaled`](Error::Journaled) wrappers
+    #[must_use]
+    pub fn inner(&self) -> &Error {
+        match self {
+            Error::Context { error, .. } | Error::Journaled { error, .. } => error.inner(),
+            error => error,
+        }
+    }
+
+    /// Wrap a failure in an [`Error::Context`], or complete the existing context
+    pub(crate) fn with_context(self, context: ErrorContext) -> Error {
+        match self {
+            Error::Redis(_) => self,
+            Error::Context {
+                error,
+                context: existing,
+            } => Error::Context {
+                error,
+                context: ErrorContext {
+                    command: existing.command.or(context.command),
+                    node: existing.node.or(context.node),
+                    attempt: existing.attempt.or(context.attempt),
+                    elapsed: existing.elapsed.or(context.elapsed),
+                },
+            },
+            error => Error::Context {
+                error: Box::new(error),
+                context,
+            },
+        }
+    }
+}
+
+/// Context of the failure of a command, see [`Error::Context`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// Name of the command, the first one for a batch
+    pub command: Option<String>,
+    /// `host:port` of the node the command was sent to, if known
+    pub node: Option<String>,
+    /// Number of the attempt to send the command, starting at `1`
+    pub attempt: Option<usize>,
+    /// Time elapsed since the command has been sent
+    pub elapsed: Option<Duration>,
+}
+
+impl Display for ErrorContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut separator = "";
+        if let Some(command) = &self.command {
+            f.write_fmt(format_args!("command: {command}"))?;
+            separator = ", ";
+        }
+        if let Some(node) = &self.node {
+            f.write_fmt(format_args!("{separator}node: {node}"))?;
+            separator = ", ";
+        }
+        if let Some(attempt) = self.attempt {
+            f.write_fmt(format_args!("{separator}attempt: {attempt}"))?;
+            separator = ", ";
+        }
+        if let Some(elapsed) = self.elapsed {
+            f.write_fmt(format_args!("{separator}elapsed: {elapsed:?}"))?;
+        }
+        Ok(())
+    }
+}
+
 impl serde::de::Error for Error {
     fn custom<T>(msg: T) -> Self
     where
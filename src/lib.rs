@@ -20,6 +20,7 @@ rustis is a Redis client for Rust.
 * [Pipelining](https://redis.io/docs/manual/pipelining/) support
 * Configuration with Redis URL or dedicated builder
 * [TLS](https://redis.io/docs/manual/security/encryption/) support
+* Pluggable [transport](crate::client::AsyncTransport) (WebSocket, WebAssembly host sockets, ...)
 * [Transaction](https://redis.io/docs/manual/transactions/) support
 * [Pub/sub](https://redis.io/docs/manual/pubsub/) support
 * [Sentinel](https://redis.io/docs/manual/sentinel/) support
@@ -45,6 +46,18 @@ rustis is a Redis client for Rust.
 | `redis-graph` | [RedisGraph v2.10](https://redis.io/docs/stack/graph/) support (optional) |
 | `redis-bloom` | [RedisBloom v2.4](https://redis.io/docs/stack/bloom/) support (optional) |
 | `redis-time-series` | [RedisTimeSeries v1.8](https://redis.io/docs/stack/timeseries/) support (optional) |
+| `blocking` | [Synchronous facade](crate::blocking) of the client, for applications which are not async (optional) |
+| `mock` | [In-memory server](crate::client::Client::connect_mock) and [record](crate::client::Client::connect_recording)/[replay](crate::client::Client::connect_replay) connections for tests (optional) |
+| `patterns` | [Ready-to-use implementations](crate::patterns) of common Redis patterns (optional) |
+| `replication` | [Replication client](crate::replication) receiving the RDB snapshot & the command stream of a master, as a replica (optional) |
+| `bench` | [Benchmark](crate::bench) subsystem, equivalent to `redis-benchmark` (optional) |
+| `chaos` | [Fault injection](crate::client::ChaosConfig) in the connection for resilience tests: latency, dropped replies, disconnections, spurious redirections (optional) |
+| `test-util` | [Test utilities](crate::test_util), e.g. cluster slot migration or `select!` churn (optional) |
+| `compression-gzip` | gzip codec of the [value compression layer](crate::client::CompressionLayer) (optional) |
+| `compression-zstd` | zstd codec of the [value compression layer](crate::client::CompressionLayer) (optional) |
+| `compression-lz4` | LZ4 codec of the [value compression layer](crate::client::CompressionLayer) (optional) |
+| `otel-metrics` | Export of the client metrics to [OpenTelemetry](crate::client::OtelMetrics) (optional) |
+| `json` | JSON [codec](crate::client::JsonCodec) of [typed Pub/Sub streams](crate::client::PubSubStream::into_typed) & conversions between [`Value`](crate::resp::Value) and `serde_json::Value` (optional) |
 | `redis-stack` | activate `redis-json`, `redis-search`, `redis-graph`, `redis-bloom` & `redis-time-series` at the same time (optional) |
 
 # Basic Usage
@@ -155,15 +168,32 @@ async fn main() -> Result<()> {
 ```
 */
 
+#[cfg(feature = "bench")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bench")))]
+pub mod bench;
+#[cfg(feature = "blocking")]
+#[cfg_attr(docsrs, doc(cfg(feature = "blocking")))]
+pub mod blocking;
 pub mod client;
+pub mod cluster;
 pub mod commands;
 mod error;
 mod network;
+#[cfg(feature = "patterns")]
+#[cfg_attr(docsrs, doc(cfg(feature = "patterns")))]
+pub mod patterns;
+#[cfg(feature = "replication")]
+#[cfg_attr(docsrs, doc(cfg(feature = "replication")))]
+pub mod replication;
 pub mod resp;
+#[cfg(feature = "test-util")]
+#[cfg_attr(docsrs, doc(cfg(feature = "test-util")))]
+pub mod test_util;
 
 #[cfg(feature = "pool")]
 pub use bb8;
 pub use error::*;
+pub use network::Version;
 use network::*;
 
 /// Library general result type.
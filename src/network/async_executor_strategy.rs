@@ -3,7 +3,10 @@ use crate::client::TlsConfig;
 use crate::{client::Config, Error, Result};
 use futures_util::{Future, FutureExt};
 use log::{debug, info};
-use socket2::{SockRef, TcpKeepalive};
+#[cfg(not(target_family = "wasm"))]
+use socket2::{Domain, Protocol, SockRef, Socket, TcpKeepalive, Type};
+#[cfg(not(target_family = "wasm"))]
+use std::net::SocketAddr;
 use std::{
     pin::Pin,
     task::{Context, Poll},
@@ -36,6 +39,11 @@ pub(crate) type TcpTlsStreamWriter = tokio_util::compat::Compat<
     futures::io::WriteHalf<async_native_tls::TlsStream<async_std::net::TcpStream>>,
 >;
 
+#[cfg(feature = "tokio-runtime")]
+type TcpStream = tokio::net::TcpStream;
+#[cfg(feature = "async-std-runtime")]
+type TcpStream = async_std::net::TcpStream;
+
 pub(crate) async fn tcp_connect(
     host: &str,
     port: u16,
@@ -43,28 +51,16 @@ pub(crate) async fn tcp_connect(
 ) -> Result<(TcpStreamReader, TcpStreamWriter)> {
     debug!(
         "Connecting to {host}:{port} with timeout {:?}...",
-        config.connect_timeout
+        config.resilience.connect_timeout
     );
 
     let reader: TcpStreamReader;
     let writer: TcpStreamWriter;
 
+    let stream = connect_stream(host, port, config).await?;
+
     #[cfg(feature = "tokio-runtime")]
     {
-        let stream = timeout(
-            config.connect_timeout,
-            tokio::net::TcpStream::connect((host, port)),
-        )
-        .await??;
-
-        if let Some(keep_alive) = config.keep_alive {
-            SockRef::from(&stream).set_tcp_keepalive(&TcpKeepalive::new().with_time(keep_alive))?;
-        }
-
-        if config.no_delay {
-            stream.set_nodelay(true)?;
-        }
-
         (reader, writer) = tokio::io::split(stream);
     }
     #[cfg(feature = "async-std-runtime")]
@@ -72,20 +68,6 @@ pub(crate) async fn tcp_connect(
         use futures::AsyncReadExt;
         use tokio_util::compat::{FuturesAsyncReadCompatExt, FuturesAsyncWriteCompatExt};
 
-        let stream = timeout(
-            config.connect_timeout,
-            async_std::net::TcpStream::connect((host, port)),
-        )
-        .await??;
-
-        if let Some(keep_alive) = config.keep_alive {
-            SockRef::from(&stream).set_tcp_keepalive(&TcpKeepalive::new().with_time(keep_alive))?;
-        }
-
-        if config.no_delay {
-            stream.set_nodelay(true)?;
-        }
-
         let (r, w) = stream.split();
         reader = r.compat();
         writer = w.compat_write();
@@ -101,22 +83,22 @@ pub(crate) async fn tcp_tls_connect(
     host: &str,
     port: u16,
     tls_config: &TlsConfig,
-    connect_timeout: Duration,
+    config: &Config,
 ) -> Result<(TcpTlsStreamReader, TcpTlsStreamWriter)> {
-    debug!("Connecting to {host}:{port} with timeout {connect_timeout:?}...");
+    debug!(
+        "Connecting to {host}:{port} with timeout {:?}...",
+        config.resilience.connect_timeout
+    );
 
     let reader: TcpTlsStreamReader;
     let writer: TcpTlsStreamWriter;
     let builder = tls_config.into_tls_connector_builder();
 
+    let stream = connect_stream(host, port, config).await?;
+
     #[cfg(feature = "tokio-runtime")]
     #[cfg(feature = "tokio-tls")]
     {
-        let stream = timeout(
-            connect_timeout,
-            tokio::net::TcpStream::connect((host, port)),
-        )
-        .await??;
         let tls_connector: native_tls::TlsConnector = builder.build()?;
         let tls_connector = tokio_native_tls::TlsConnector::from(tls_connector);
         let tls_stream = tls_connector.connect(host, stream).await?;
@@ -128,11 +110,6 @@ pub(crate) async fn tcp_tls_connect(
         use futures::AsyncReadExt;
         use tokio_util::compat::{FuturesAsyncReadCompatExt, FuturesAsyncWriteCompatExt};
 
-        let stream = timeout(
-            connect_timeout,
-            async_std::net::TcpStream::connect((host, port)),
-        )
-        .await??;
         let tls_connector: async_native_tls::TlsConnector = builder.into();
         let tls_stream = tls_connector.connect(host, stream).await?;
         let (r, w) = tls_stream.split();
@@ -145,6 +122,186 @@ pub(crate) async fn tcp_tls_connect(
     Ok((reader, writer))
 }
 
+/// Connect a TCP stream and apply the socket options of `config`
+#[cfg(not(target_family = "wasm"))]
+async fn connect_stream(host: &str, port: u16, config: &Config) -> Result<TcpStream> {
+    // these options must be set before connecting
+    let stream = if config.local_address.is_some()
+        || config.bind_interface.is_some()
+        || config.send_buffer_size.is_some()
+        || config.recv_buffer_size.is_some()
+    {
+        timeout(
+            config.resilience.connect_timeout,
+            connect_socket(host, port, config),
+        )
+        .await??
+    } else {
+        timeout(
+            config.resilience.connect_timeout,
+            TcpStream::connect((host, port)),
+        )
+        .await??
+    };
+
+    let socket = SockRef::from(&stream);
+
+    if let Some(keep_alive) = config.keep_alive {
+        socket.set_tcp_keepalive(&TcpKeepalive::new().with_time(keep_alive))?;
+    }
+
+    if let Some(tcp_user_timeout) = config.tcp_user_timeout {
+        set_tcp_user_timeout(&socket, tcp_user_timeout)?;
+    }
+
+    if let Some(tos) = config.tos {
+        set_tos(&socket, tos)?;
+    }
+
+    if config.no_delay {
+        stream.set_nodelay(true)?;
+    }
+
+    Ok(stream)
+}
+
+/// Raw TCP sockets are not available on WebAssembly targets,
+/// connections must be opened by a [transport](crate::client::AsyncTransport)
+#[cfg(target_family = "wasm")]
+async fn connect_stream(host: &str, port: u16, _config: &Config) -> Result<TcpStream> {
+    Err(Error::Config(format!(
+        "Cannot connect to {host}:{port}: TCP is not supported on this target, \
+         a transport must be set with Config::transport"
+    )))
+}
+
+/// Connect a TCP stream through a socket configured before connecting,
+/// trying each resolved address of `host` in turn
+#[cfg(not(target_family = "wasm"))]
+async fn connect_socket(host: &str, port: u16, config: &Config) -> Result<TcpStream> {
+    #[cfg(feature = "tokio-runtime")]
+    let addrs = tokio::net::lookup_host((host, port)).await?;
+    #[cfg(feature = "async-std-runtime")]
+    let addrs = async_std::net::ToSocketAddrs::to_socket_addrs(&(host, port)).await?;
+
+    let mut last_error = None;
+
+    for addr in addrs {
+        let socket = match new_socket(addr, config) {
+            Ok(socket) => socket,
+            Err(e) => {
+                last_error = Some(e);
+                continue;
+            }
+        };
+
+        #[cfg(feature = "tokio-runtime")]
+        let result = async {
+            socket.set_nonblocking(true)?;
+            let socket = tokio::net::TcpSocket::from_std_stream(socket.into());
+            Ok::<_, Error>(socket.connect(addr).await?)
+        }
+        .await;
+
+        #[cfg(feature = "async-std-runtime")]
+        let result = {
+            let connect_timeout = config.resilience.connect_timeout;
+            async_std::task::spawn_blocking(move || {
+                socket.connect_timeout(&addr.into(), connect_timeout)?;
+                socket.set_nonblocking(true)?;
+                Ok::<_, Error>(TcpStream::from(std::net::TcpStream::from(socket)))
+            })
+            .await
+        };
+
+        match result {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_error = Some(e),
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| Error::Client(format!("Cannot resolve {host}:{port}"))))
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn new_socket(addr: SocketAddr, config: &Config) -> Result<Socket> {
+    let socket = Socket::new(Domain::for_address(addr), Type::STREAM, Some(Protocol::TCP))?;
+
+    if let Some(send_buffer_size) = config.send_buffer_size {
+        socket.set_send_buffer_size(send_buffer_size)?;
+    }
+
+    if let Some(recv_buffer_size) = config.recv_buffer_size {
+        socket.set_recv_buffer_size(recv_buffer_size)?;
+    }
+
+    if let Some(bind_interface) = &config.bind_interface {
+        bind_interface_to(&socket, bind_interface)?;
+    }
+
+    if let Some(local_address) = config.local_address {
+        socket.bind(&SocketAddr::new(local_address, 0).into())?;
+    }
+
+    Ok(socket)
+}
+
+#[cfg(all(
+    not(target_family = "wasm"),
+    any(target_os = "android", target_os = "linux")
+))]
+fn bind_interface_to(socket: &Socket, interface: &str) -> Result<()> {
+    Ok(socket.bind_device(Some(interface.as_bytes()))?)
+}
+
+#[cfg(all(
+    not(target_family = "wasm"),
+    not(any(target_os = "android", target_os = "linux"))
+))]
+fn bind_interface_to(_socket: &Socket, _interface: &str) -> Result<()> {
+    Err(Error::Config(
+        "bind_interface is only supported on Linux & Android".to_owned(),
+    ))
+}
+
+#[cfg(all(
+    not(target_family = "wasm"),
+    any(target_os = "android", target_os = "linux")
+))]
+fn set_tcp_user_timeout(socket: &SockRef, tcp_user_timeout: Duration) -> Result<()> {
+    Ok(socket.set_tcp_user_timeout(Some(tcp_user_timeout))?)
+}
+
+#[cfg(all(
+    not(target_family = "wasm"),
+    not(any(target_os = "android", target_os = "linux"))
+))]
+fn set_tcp_user_timeout(_socket: &SockRef, _tcp_user_timeout: Duration) -> Result<()> {
+    Err(Error::Config(
+        "tcp_user_timeout is only supported on Linux & Android".to_owned(),
+    ))
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn set_tos(socket: &SockRef, tos: u32) -> Result<()> {
+    let is_ipv6 = socket
+        .local_addr()?
+        .as_socket()
+        .is_some_and(|addr| addr.is_ipv6());
+
+    if !is_ipv6 {
+        return Ok(socket.set_tos(tos)?);
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux", target_os = "macos"))]
+    return Ok(socket.set_tclass_v6(tos)?);
+
+    #[cfg(not(any(target_os = "android", target_os = "linux", target_os = "macos")))]
+    Err(Error::Config(
+        "tos is not supported for IPv6 on this platform".to_owned(),
+    ))
+}
+
 pub enum JoinHandle<T> {
     #[cfg(feature = "tokio-runtime")]
     Tokio(tokio::task::JoinHandle<T>),
@@ -0,0 +1,245 @@
+use crate::{
+    client::{ChaosConfig, ChaosFault},
+    cluster::hash_slot,
+    network::sleep,
+    resp::{Command, RespBuf},
+    Connection, ConnectionEventSender, Error, Result, RetryReason, StatsRegistry, Version,
+};
+use log::debug;
+use rand::{rngs::StdRng, SeedableRng};
+use smallvec::{smallvec, SmallVec};
+use std::{collections::VecDeque, sync::Arc};
+
+/// Reads planned for a write, in the order of the writes
+enum PlannedRead {
+    /// Number of replies still expected from the inner connection
+    Forwarded(usize),
+    /// The replies are read & discarded, then the connection is closed
+    Dropped(usize),
+    /// The connection is closed
+    Disconnection,
+    /// Replies served in place of the inner connection
+    Injected(VecDeque<Result<RespBuf>>),
+}
+
+/// Connection wrapper which injects faults according to a [`ChaosConfig`]
+pub struct ChaosConnection {
+    inner: Box<Connection>,
+    config: ChaosConfig,
+    rng: StdRng,
+    num_writes: u64,
+    planned_reads: VecDeque<PlannedRead>,
+}
+
+impl ChaosConnection {
+    pub fn new(inner: Connection, config: ChaosConfig) -> Self {
+        Self {
+            inner: Box::new(inner),
+            rng: StdRng::seed_from_u64(config.seed),
+            config,
+            num_writes: 0,
+            planned_reads: VecDeque::new(),
+        }
+    }
+
+    #[inline]
+    pub async fn write(&mut self, command: &Command) -> Result<()> {
+        if !self.inject_fault(&[command]).await? {
+            return Ok(());
+        }
+
+        let result = Box::pin(self.inner.write(command)).await;
+        if result.is_err() {
+            self.planned_reads.pop_back();
+        }
+        result
+    }
+
+    #[inline]
+    pub async fn write_batch(
+        &mut self,
+        commands: SmallVec<[&mut Command; 10]>,
+        retry_reasons: &[RetryReason],
+    ) -> Result<()> {
+        let forward = {
+            let commands: SmallVec<[&Command; 10]> = commands.iter().map(|c| &**c).collect();
+            self.inject_fault(&commands).await?
+        };
+        if !forward {
+            return Ok(());
+        }
+
+        let result = Box::pin(self.inner.write_batch(commands, retry_reasons)).await;
+        if result.is_err() {
+            self.planned_reads.pop_back();
+        }
+        result
+    }
+
+    pub async fn read(&mut self) -> Option<Result<RespBuf>> {
+        match self.planned_reads.front_mut() {
+            // out-of-band frames
+            None => Box::pin(self.inner.read()).await,
+            Some(PlannedRead::Forwarded(_)) => {
+                let result = Box::pin(self.inner.read()).await;
+                if matches!(&result, Some(Ok(resp_buf)) if resp_buf.is_push_message()) {
+                    return result;
+                }
+
+                if let Some(PlannedRead::Forwarded(num_replies)) = self.planned_reads.front_mut() {
+                    *num_replies -= 1;
+                    if *num_replies == 0 {
+                        self.planned_reads.pop_front();
+                    }
+                }
+                result
+            }
+            Some(PlannedRead::Dropped(num_replies)) => {
+                let num_replies = *num_replies;
+                self.planned_reads.pop_front();
+                let mut num_dropped = 0;
+                while num_dropped < num_replies {
+                    match Box::pin(self.inner.read()).await {
+                        Some(Ok(resp_buf)) if resp_buf.is_push_message() => (),
+                        Some(result) => {
+                            debug!("[{}] Chaos: reply dropped: {result:?}", self.tag());
+                            num_dropped += 1;
+                        }
+                        None => break,
+                    }
+                }
+                None
+            }
+            Some(PlannedRead::Disconnection) => {
+                self.planned_reads.pop_front();
+                None
+            }
+            Some(PlannedRead::Injected(replies)) => {
+                let reply = replies.pop_front();
+                if replies.is_empty() {
+                    self.planned_reads.pop_front();
+                }
+                reply
+            }
+        }
+    }
+
+    #[inline]
+    pub async fn reconnect(&mut self) -> Result<()> {
+        self.planned_reads.clear();
+        Box::pin(self.inner.reconnect()).await
+    }
+
+    pub(crate) fn id(&self) -> Option<i64> {
+        self.inner.id()
+    }
+
+    pub(crate) fn version(&self) -> Option<Version> {
+        self.inner.version()
+    }
+
+    pub(crate) fn address(&self) -> Option<String> {
+        self.inner.address()
+    }
+
+    pub(crate) fn is_cluster(&self) -> bool {
+        self.inner.is_cluster()
+    }
+
+    pub(crate) fn tag(&self) -> &str {
+        self.inner.tag()
+    }
+
+    pub(crate) fn set_stats(&mut self, stats: Arc<StatsRegistry>) {
+        self.inner.set_stats(stats);
+    }
+
+    pub(crate) fn set_event_sender(&mut self, event_sender: ConnectionEventSender) {
+        self.inner.set_event_sender(event_sender);
+    }
+
+    /// Draw the fault of the next write and plan its reads
+    ///
+    /// # Return
+    /// `true` if `commands` must be written to the inner connection
+    async fn inject_fault(&mut self, commands: &[&Command]) -> Result<bool> {
+        let write_index = self.num_writes;
+        self.num_writes += 1;
+
+        let fault = self.config.draw_fault(write_index, &mut self.rng);
+        let (planned_read, forward) = match &fault {
+            None => (PlannedRead::Forwarded(commands.len()), true),
+            Some(ChaosFault::Latency(latency)) => {
+                sleep(*latency).await;
+                (PlannedRead::Forwarded(commands.len()), true)
+            }
+            Some(ChaosFault::DropResponse) => (PlannedRead::Dropped(commands.len()), true),
+            Some(ChaosFault::Disconnect) => (PlannedRead::Disconnection, false),
+            Some(ChaosFault::Moved | ChaosFault::Ask) => {
+                let is_ask = matches!(fault, Some(ChaosFault::Ask));
+                match self.redirections(commands, is_ask).await? {
+                    Some(replies) => (PlannedRead::Injected(replies), false),
+                    None => {
+                        self.planned_reads
+                            .push_back(PlannedRead::Forwarded(commands.len()));
+                        return Ok(true);
+                    }
+                }
+            }
+        };
+
+        if let Some(fault) = fault {
+            debug!("[{}] Chaos: injecting {fault:?}", self.tag());
+            self.config.record_fault(write_index, fault);
+        }
+
+        self.planned_reads.push_back(planned_read);
+        Ok(forward)
+    }
+
+    /// Replies redirecting `commands`, `None` if they cannot be redirected
+    async fn redirections(
+        &mut self,
+        commands: &[&Command],
+        is_ask: bool,
+    ) -> Result<Option<VecDeque<Result<RespBuf>>>> {
+        if let Connection::Cluster(cluster_connection) = &mut *self.inner {
+            // redirections are reported by cluster connections as retry reasons,
+            // for the whole batch
+            for command in commands {
+                if let Some((hash_slot, address)) = cluster_connection.slot_owner(command).await? {
+                    let reason = if is_ask {
+                        RetryReason::Ask { hash_slot, address }
+                    } else {
+                        RetryReason::Moved { hash_slot, address }
+                    };
+                    return Ok(Some(VecDeque::from([Err(Error::Retry(smallvec![reason]))])));
+                }
+            }
+            return Ok(None);
+        }
+
+        let Some(address) = self.inner.address() else {
+            return Ok(None);
+        };
+
+        let redirection = if is_ask { "ASK" } else { "MOVED" };
+        Ok(Some(
+            commands
+                .iter()
+                .map(|command| {
+                    // slot of the first argument, usually the first key
+                    let slot = command
+                        .args
+                        .into_iter()
+                        .next()
+                        .map(hash_slot)
+                        .unwrap_or_default();
+                    Ok(RespBuf::from_slice(
+                        format!("-{redirection} {slot} {address}\r\n").as_bytes(),
+                    ))
+                })
+                .collect(),
+        ))
+    }
+}
@@ -1,10 +1,13 @@
 use crate::{
-    client::{ClusterConfig, Config},
+    client::{ClusterConfig, Config, ConnectionEvent},
+    cluster::hash_slot,
     commands::{
         ClusterCommands, ClusterHealthStatus, ClusterNodeResult, ClusterShardResult, CommandTip,
         LegacyClusterShardResult, RequestPolicy, ResponsePolicy,
     },
-    network::{CommandInfoManager, Version},
+    network::{
+        CommandInfoManager, ConnectionEventSender, NodeHealthTracker, StatsRegistry, Version,
+    },
     resp::{Command, RespBuf, RespDeserializer, RespSerializer},
     Error, RedisError, RedisErrorKind, Result, RetryReason, StandaloneConnection,
 };
@@ -22,8 +25,12 @@ use std::{
     fmt::{self, Debug, Formatter},
     iter::zip,
     sync::Arc,
+    time::{Duration, Instant},
 };
 
+/// Minimum interval between two topology refreshes triggered by an unhealthy master node
+const UNHEALTHY_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
 #[derive(Clone, PartialEq, Eq, Debug, PartialOrd, Ord)]
 #[repr(transparent)]
 struct NodeId(Arc<str>);
@@ -44,7 +51,76 @@ struct Node {
     pub id: NodeId,
     pub is_master: bool,
     pub address: (String, u16),
-    pub connection: StandaloneConnection,
+    /// `None` once closed for being idle, re-opened on next use
+    /// see [`Config::idle_connection_timeout`]
+    pub connection: Option<StandaloneConnection>,
+    /// Last time the connection has been used to write a command or read a reply
+    pub last_used: Instant,
+    /// A subscription has been made on the connection, which must not be closed when idle
+    pub subscribed: bool,
+}
+
+impl Node {
+    fn new(
+        id: NodeId,
+        is_master: bool,
+        address: (String, u16),
+        connection: StandaloneConnection,
+    ) -> Self {
+        Self {
+            id,
+            is_master,
+            address,
+            connection: Some(connection),
+            last_used: Instant::now(),
+            subscribed: false,
+        }
+    }
+
+    /// Node whose connection has failed, isolated by the node health tracker,
+    /// the connection being re-opened on next use
+    fn disconnected(id: NodeId, is_master: bool, address: (String, u16)) -> Self {
+        Self {
+            id,
+            is_master,
+            address,
+            connection: None,
+            last_used: Instant::now(),
+            subscribed: false,
+        }
+    }
+
+    /// Connection to the node, re-opened if it has been closed for being idle
+    async fn connection(
+        &mut self,
+        config: &Config,
+        stats: Option<&Arc<StatsRegistry>>,
+    ) -> Result<&mut StandaloneConnection> {
+        let connection = match self.connection.take() {
+            Some(connection) => connection,
+            None => {
+                debug!(
+                    "Re-opening idle connection to node ({}:{})",
+                    self.address.0, self.address.1
+                );
+                let mut connection =
+                    StandaloneConnection::connect(&self.address.0, self.address.1, config).await?;
+                if let Some(stats) = stats {
+                    connection.set_stats(stats);
+                }
+                connection
+            }
+        };
+
+        self.last_used = Instant::now();
+        Ok(self.connection.insert(connection))
+    }
+
+    fn tag(&self) -> &str {
+        self.connection
+            .as_ref()
+            .map_or("", |connection| connection.tag())
+    }
 }
 
 impl Debug for Node {
@@ -52,7 +128,7 @@ impl Debug for Node {
         f.debug_struct("Node")
             .field("id", &self.id)
             .field("is_master", &self.is_master)
-            .field("tag", &self.connection.tag())
+            .field("tag", &self.tag())
             .finish()
     }
 }
@@ -103,6 +179,9 @@ pub struct ClusterConnection {
     command_info_manager: CommandInfoManager,
     pending_requests: VecDeque<RequestInfo>,
     tag: String,
+    node_health: NodeHealthTracker,
+    last_unhealthy_refresh: Option<Instant>,
+    stats: Option<Arc<StatsRegistry>>,
 }
 
 impl ClusterConnection {
@@ -110,14 +189,26 @@ impl ClusterConnection {
         cluster_config: &ClusterConfig,
         config: &Config,
     ) -> Result<ClusterConnection> {
-        let (mut nodes, slot_ranges) = Self::connect_to_cluster(cluster_config, config).await?;
+        let mut node_health = NodeHealthTracker::new(
+            config.resilience.node_failure_threshold,
+            config.resilience.node_cooldown,
+        );
+        let (mut nodes, slot_ranges) =
+            Self::connect_to_cluster(cluster_config, config, &mut node_health).await?;
         let first_node = nodes
-            .get_mut(0)
+            .iter_mut()
+            .find(|n| n.connection.is_some())
             .ok_or_else(|| Error::Client("No cluster nodes".to_owned()))?;
 
-        let command_info_manager =
-            CommandInfoManager::initialize(&mut first_node.connection).await?;
-        let tag = first_node.connection.tag().to_owned();
+        let first_connection = first_node.connection(config, None).await?;
+        let command_info_manager = CommandInfoManager::initialize(
+            first_connection,
+            config.command_info_cache.as_ref(),
+            &config.command_keys,
+        )
+        .await?;
+        let tag = first_connection.tag().to_owned();
+        node_health.set_tag(&tag);
 
         Ok(ClusterConnection {
             cluster_config: cluster_config.clone(),
@@ -127,13 +218,66 @@ impl ClusterConnection {
             command_info_manager,
             pending_requests: VecDeque::new(),
             tag,
+            node_health,
+            last_unhealthy_refresh: None,
+            stats: None,
         })
     }
 
+    pub(crate) fn set_event_sender(&mut self, event_sender: ConnectionEventSender) {
+        self.node_health.set_event_sender(event_sender);
+    }
+
+    pub(crate) fn set_stats(&mut self, stats: Arc<StatsRegistry>) {
+        self.stats = Some(stats);
+        self.attach_stats();
+    }
+
+    /// Record the counters of the connections of the nodes, new nodes included
+    fn attach_stats(&mut self) {
+        if let Some(stats) = &self.stats {
+            for node in &mut self.nodes {
+                if let Some(connection) = &mut node.connection {
+                    connection.set_stats(stats);
+                }
+            }
+        }
+    }
+
     pub async fn write(&mut self, command: &Command) -> Result<()> {
+        self.close_idle_connections();
         self.internal_write(command, &[]).await
     }
 
+    /// Hash slot of the first key of `command` and address of the master node owning it,
+    /// `None` for a command without key
+    #[cfg(feature = "chaos")]
+    pub(crate) async fn slot_owner(
+        &mut self,
+        command: &Command,
+    ) -> Result<Option<(u16, (String, u16))>> {
+        let keys = if let Some(shard_channels) = Self::shard_channels(command) {
+            shard_channels
+        } else {
+            let node_idx = self.get_random_node_index();
+            let connection = self.nodes[node_idx]
+                .connection(&self.config, self.stats.as_ref())
+                .await?;
+            self.command_info_manager
+                .extract_keys(command, connection)
+                .await?
+        };
+
+        let Some(key) = keys.first() else {
+            return Ok(None);
+        };
+
+        let slot = hash_slot(key);
+        Ok(self
+            .get_master_node_index_by_slot(slot, &[])
+            .map(|(node_idx, _)| (slot, self.nodes[node_idx].address.clone())))
+    }
+
     async fn internal_write(
         &mut self,
         command: &Command,
@@ -154,11 +298,17 @@ impl ClusterConnection {
 
         let command_name = command_info.name.clone();
 
-        let node_idx = self.get_random_node_index();
-        let keys = self
-            .command_info_manager
-            .extract_keys(command, &mut self.nodes[node_idx].connection)
-            .await?;
+        let keys = if let Some(shard_channels) = Self::shard_channels(command) {
+            shard_channels
+        } else {
+            let node_idx = self.get_random_node_index();
+            let connection = self.nodes[node_idx]
+                .connection(&self.config, self.stats.as_ref())
+                .await?;
+            self.command_info_manager
+                .extract_keys(command, connection)
+                .await?
+        };
         let slots = Self::hash_slots(&keys);
 
         debug!("[{}] keys: {keys:?}, slots: {slots:?}", self.tag);
@@ -208,6 +358,8 @@ impl ClusterConnection {
         commands: SmallVec<[&mut Command; 10]>,
         retry_reasons: &[RetryReason],
     ) -> Result<()> {
+        self.close_idle_connections();
+
         if retry_reasons.iter().any(|r| {
             matches!(
                 r,
@@ -233,9 +385,12 @@ impl ClusterConnection {
 
         if commands.len() > 1 && commands[0].name == "MULTI" {
             let node_idx = self.get_random_node_index();
+            let connection = self.nodes[node_idx]
+                .connection(&self.config, self.stats.as_ref())
+                .await?;
             let keys = self
                 .command_info_manager
-                .extract_keys(commands[1], &mut self.nodes[node_idx].connection)
+                .extract_keys(commands[1], connection)
                 .await?;
             let slots = Self::hash_slots(&keys);
             if slots.is_empty() || !slots.windows(2).all(|s| s[0] == s[1]) {
@@ -247,9 +402,12 @@ impl ClusterConnection {
             let ref_slot = slots[0];
 
             for command in commands {
+                let connection = self.nodes[node_idx]
+                    .connection(&self.config, self.stats.as_ref())
+                    .await?;
                 let keys = self
                     .command_info_manager
-                    .extract_keys(command, &mut self.nodes[node_idx].connection)
+                    .extract_keys(command, connection)
                     .await?;
                 self.no_request_policy(
                     command,
@@ -281,7 +439,10 @@ impl ClusterConnection {
         let mut sub_requests = SmallVec::<[SubRequest; 10]>::new();
 
         for node in self.nodes.iter_mut().filter(|n| n.is_master) {
-            node.connection.write(command).await?;
+            node.connection(&self.config, self.stats.as_ref())
+                .await?
+                .write(command)
+                .await?;
             sub_requests.push(SubRequest {
                 node_id: node.id.clone(),
                 keys: smallvec![],
@@ -318,7 +479,10 @@ impl ClusterConnection {
         let mut sub_requests = SmallVec::<[SubRequest; 10]>::new();
 
         for node in self.nodes.iter_mut() {
-            node.connection.write(command).await?;
+            node.connection(&self.config, self.stats.as_ref())
+                .await?
+                .write(command)
+                .await?;
             sub_requests.push(SubRequest {
                 node_id: node.id.clone(),
                 keys: smallvec![],
@@ -351,14 +515,13 @@ impl ClusterConnection {
         slots: SmallVec<[u16; 10]>,
         ask_reasons: &[(u16, (String, u16))],
     ) -> Result<()> {
-        let mut node_slot_keys_ask = (0..keys.len())
-            .map(|i| {
-                let (node_index, should_ask) = self
-                    .get_master_node_index_by_slot(slots[i], ask_reasons)
-                    .ok_or_else(|| Error::Client("Cluster misconfiguration".to_owned()))?;
-                Ok((node_index, slots[i], keys[i].clone(), should_ask))
-            })
-            .collect::<Result<Vec<_>>>()?;
+        let mut node_slot_keys_ask = Vec::with_capacity(keys.len());
+        for (slot, key) in zip(&slots, &keys) {
+            let (node_index, should_ask) = self
+                .get_healthy_master_node_index_by_slot(*slot, ask_reasons)
+                .await?;
+            node_slot_keys_ask.push((node_index, *slot, key.clone(), should_ask));
+        }
 
         node_slot_keys_ask.sort();
         trace!("[{}] shard_slot_keys_ask: {node_slot_keys_ask:?}", self.tag);
@@ -374,14 +537,15 @@ impl ClusterConnection {
         for (node_index, slot, key, should_ask) in &node_slot_keys_ask {
             if *slot != last_slot {
                 if !current_slot_keys.is_empty() {
+                    let connection = node.connection(&self.config, self.stats.as_ref()).await?;
                     if last_should_ask {
-                        node.connection.asking().await?;
+                        connection.asking().await?;
                     }
 
                     let shard_command = self
                         .command_info_manager
                         .prepare_command_for_shard(command, current_slot_keys.iter())?;
-                    node.connection.write(&shard_command).await?;
+                    connection.write(&shard_command).await?;
                     sub_requests.push(SubRequest {
                         node_id: node.id.clone(),
                         keys: current_slot_keys.clone(),
@@ -403,15 +567,16 @@ impl ClusterConnection {
             }
         }
 
+        let connection = node.connection(&self.config, self.stats.as_ref()).await?;
         if last_should_ask {
-            node.connection.asking().await?;
+            connection.asking().await?;
         }
 
         let shard_command = self
             .command_info_manager
             .prepare_command_for_shard(command, current_slot_keys.iter())?;
 
-        node.connection.write(&shard_command).await?;
+        connection.write(&shard_command).await?;
 
         sub_requests.push(SubRequest {
             node_id: node.id.clone(),
@@ -447,17 +612,29 @@ impl ClusterConnection {
             let (node_idx, should_ask) = if slots.is_empty() {
                 (self.get_random_node_index(), false)
             } else {
-                self.get_master_node_index_by_slot(slots[0], ask_reasons)
-                    .ok_or_else(|| Error::Client("Cluster misconfiguration".to_owned()))?
+                self.get_healthy_master_node_index_by_slot(slots[0], ask_reasons)
+                    .await?
             };
 
             let node = &mut self.nodes[node_idx];
-            let connection = &mut node.connection;
+            let connection = match node.connection(&self.config, self.stats.as_ref()).await {
+                Ok(connection) => connection,
+                Err(e) => {
+                    self.node_health.record_failure(&node.address);
+                    return Err(e);
+                }
+            };
 
             if should_ask {
                 connection.asking().await?;
             }
-            connection.write(command).await?;
+            if let Err(e) = connection.write(command).await {
+                self.node_health.record_failure(&node.address);
+                return Err(e);
+            }
+            if matches!(command.name, "SUBSCRIBE" | "PSUBSCRIBE" | "SSUBSCRIBE") {
+                node.subscribed = true;
+            }
 
             let request_info = RequestInfo {
                 command_name: command_name.to_string(),
@@ -506,8 +683,31 @@ impl ClusterConnection {
                 }
             }
 
-            let read_futures = self.nodes.iter_mut().map(|n| n.connection.read().boxed());
-            let (result, node_idx, _) = future::select_all(read_futures).await;
+            let (node_indices, read_futures): (SmallVec<[usize; 10]>, Vec<_>) = self
+                .nodes
+                .iter_mut()
+                .enumerate()
+                .filter_map(|(idx, n)| Some((idx, n.connection.as_mut()?.read().boxed())))
+                .unzip();
+            if read_futures.is_empty() {
+                return None;
+            }
+            let (result, future_idx, _) = future::select_all(read_futures).await;
+            let node_idx = node_indices[future_idx];
+            self.nodes[node_idx].last_used = Instant::now();
+
+            let address = &self.nodes[node_idx].address;
+            match &result {
+                None => {
+                    self.node_health.record_failure(address);
+                    if self.node_health.is_enabled() && !self.nodes[node_idx].subscribed {
+                        self.isolate_node(node_idx);
+                        continue;
+                    }
+                }
+                Some(Ok(_)) => self.node_health.record_success(address),
+                Some(Err(_)) => (),
+            }
 
             result.as_ref()?;
 
@@ -534,7 +734,7 @@ impl ClusterConnection {
                 log::error!(
                     "[{}] Received unexpected message: {result:?} from {}",
                     self.tag,
-                    self.nodes[node_idx].connection.tag()
+                    self.nodes[node_idx].tag()
                 );
                 return Some(Err(Error::Client(format!(
                     "[{}] Received unexpected message",
@@ -869,11 +1069,36 @@ impl ClusterConnection {
     pub async fn reconnect(&mut self) -> Result<()> {
         info!("[{}] Reconnecting to cluster...", self.tag);
         let (nodes, slot_ranges) =
-            Self::connect_to_cluster(&self.cluster_config, &self.config).await?;
+            Self::connect_to_cluster(&self.cluster_config, &self.config, &mut self.node_health)
+                .await?;
         info!("[{}] Reconnected to cluster!", self.tag);
 
+        for node in &self.nodes {
+            if !nodes.iter().any(|n| n.id == node.id) {
+                self.node_health.send(ConnectionEvent::ClusterNodeRemoved {
+                    id: node.id.as_ref().to_owned(),
+                    address: node.address.clone(),
+                });
+            }
+        }
+        for node in &nodes {
+            if !self.nodes.iter().any(|n| n.id == node.id) {
+                self.node_health.send(ConnectionEvent::ClusterNodeAdded {
+                    id: node.id.as_ref().to_owned(),
+                    address: node.address.clone(),
+                    is_master: node.is_master,
+                });
+            }
+        }
+
         self.nodes = nodes;
         self.slot_ranges = slot_ranges;
+        self.attach_stats();
+        for node in &mut self.nodes {
+            if let Some(connection) = &mut node.connection {
+                connection.record_reconnect();
+            }
+        }
 
         Ok(())
 
@@ -883,14 +1108,20 @@ impl ClusterConnection {
     async fn connect_to_cluster(
         cluster_config: &ClusterConfig,
         config: &Config,
+        node_health: &mut NodeHealthTracker,
     ) -> Result<(Vec<Node>, Vec<SlotRange>)> {
         debug!("Discovering cluster shard and slots...");
 
         let mut shard_info_list: Option<Vec<ClusterShardResult>> = None;
 
-        for node_config in &cluster_config.nodes {
+        // try unhealthy seed nodes last
+        let mut seed_nodes = cluster_config.nodes.iter().collect::<Vec<_>>();
+        seed_nodes.sort_by_key(|node_config| node_health.is_unhealthy(node_config));
+
+        for node_config in seed_nodes {
             match StandaloneConnection::connect(&node_config.0, node_config.1, config).await {
                 Ok(mut connection) => {
+                    node_health.record_success(node_config);
                     let version: Result<Version> = connection.get_version().try_into();
                     let Ok(version) = version else {
                         warn!("[{}] Cannot execute get Redis version", connection.tag());
@@ -927,10 +1158,13 @@ impl ClusterConnection {
                         }
                     }
                 }
-                Err(e) => warn!(
-                    "Cannot connect to node ({}:{}): {}",
-                    node_config.0, node_config.1, e
-                ),
+                Err(e) => {
+                    warn!(
+                        "Cannot connect to node ({}:{}): {}",
+                        node_config.0, node_config.1, e
+                    );
+                    node_health.record_failure(node_config);
+                }
             }
         }
 
@@ -952,20 +1186,40 @@ impl ClusterConnection {
             let master_id: NodeId = master_info.id.as_str().into();
 
             let port = master_info.get_port()?;
+            let address = (master_info.ip, port);
 
-            let connection = StandaloneConnection::connect(&master_info.ip, port, config).await?;
+            let node = match StandaloneConnection::connect(&address.0, port, config).await {
+                Ok(connection) => {
+                    node_health.record_success(&address);
+                    Node::new(master_id.clone(), true, address, connection)
+                }
+                // only the shard of the node is unavailable
+                Err(e) if node_health.is_enabled() => {
+                    warn!(
+                        "Cannot connect to master node ({}:{}): {e}",
+                        address.0, address.1
+                    );
+                    node_health.record_failure(&address);
+                    Node::disconnected(master_id.clone(), true, address)
+                }
+                Err(e) => {
+                    node_health.record_failure(&address);
+                    return Err(e);
+                }
+            };
 
             slot_ranges.extend(shard_info.slots.iter().map(|s| SlotRange {
                 slot_range: *s,
                 node_ids: smallvec![master_id.clone()],
             }));
 
-            nodes.push(Node {
-                id: master_id.clone(),
-                is_master: true,
-                address: (master_info.ip, port),
-                connection,
-            });
+            nodes.push(node);
+        }
+
+        if nodes.iter().all(|n| n.connection.is_none()) {
+            return Err(Error::Client(
+                "Cannot connect to any cluster master node".to_owned(),
+            ));
         }
 
         slot_ranges.sort_by_key(|s| s.slot_range.0);
@@ -979,7 +1233,10 @@ impl ClusterConnection {
     async fn connect_replicas(&mut self) -> Result<()> {
         debug!("[{}] Connecting replicas...", self.tag);
 
-        let connection = &mut self.get_random_node_mut().connection;
+        let node_idx = self.get_random_node_index();
+        let connection = self.nodes[node_idx]
+            .connection(&self.config, self.stats.as_ref())
+            .await?;
         let version: Version = connection.get_version().try_into()?;
 
         // From Redis 7.x CLUSTER SLOTS is deprecated in favor of CLUSTER SHARDS
@@ -1005,17 +1262,19 @@ impl ClusterConnection {
                     }
                 }
 
-                self.nodes.push(Node {
-                    id: node_id,
-                    is_master: false,
-                    address: (node_info.ip.clone(), port),
+                self.nodes.push(Node::new(
+                    node_id,
+                    false,
+                    (node_info.ip.clone(), port),
                     connection,
-                });
+                ));
             }
         }
 
         self.nodes.sort_by(|n1, n2| n1.id.cmp(&n2.id));
 
+        self.attach_stats();
+
         debug!(
             "[{}] Cluster replicas connected: nodes={:?}, slot_ranges={:?}",
             self.tag, self.nodes, self.slot_ranges
@@ -1029,7 +1288,10 @@ impl ClusterConnection {
     async fn refresh_nodes_and_slot_ranges(&mut self) -> Result<()> {
         debug!("[{}] Reloading slot ranges", self.tag);
 
-        let connection = &mut self.get_random_node_mut().connection;
+        let node_idx = self.get_random_node_index();
+        let connection = self.nodes[node_idx]
+            .connection(&self.config, self.stats.as_ref())
+            .await?;
         let version: Version = connection.get_version().try_into()?;
 
         // From Redis 7.x CLUSTER SLOTS is deprecated in favor of CLUSTER SHARDS
@@ -1045,10 +1307,18 @@ impl ClusterConnection {
             .flat_map(|s| s.nodes.iter().map(|n| n.id.as_str()))
             .collect::<Vec<_>>();
         node_ids.sort();
+        let node_health = &self.node_health;
         self.nodes.retain(|node| {
-            node_ids
+            let retain = node_ids
                 .binary_search_by(|n| (*n).cmp(node.id.as_ref()))
-                .is_ok()
+                .is_ok();
+            if !retain {
+                node_health.send(ConnectionEvent::ClusterNodeRemoved {
+                    id: node.id.as_ref().to_owned(),
+                    address: node.address.clone(),
+                });
+            }
+            retain
         });
 
         // create slot_ranges from scratch
@@ -1087,16 +1357,37 @@ impl ClusterConnection {
                 } else {
                     // add missing node
                     let port = node_info.get_port()?;
+                    let address = (node_info.ip, port);
+
+                    let is_master = node_info.role == "master";
+                    let node =
+                        match StandaloneConnection::connect(&address.0, port, &self.config).await {
+                            Ok(connection) => {
+                                self.node_health.record_success(&address);
+                                Node::new(node_id, is_master, address, connection)
+                            }
+                            // only the shard of the node is unavailable
+                            Err(e) if self.node_health.is_enabled() => {
+                                warn!(
+                                    "[{}] Cannot connect to node ({}:{}): {e}",
+                                    self.tag, address.0, address.1
+                                );
+                                self.node_health.record_failure(&address);
+                                Node::disconnected(node_id, is_master, address)
+                            }
+                            Err(e) => {
+                                self.node_health.record_failure(&address);
+                                return Err(e);
+                            }
+                        };
 
-                    let connection =
-                        StandaloneConnection::connect(&node_info.ip, port, &self.config).await?;
-
-                    self.nodes.push(Node {
-                        id: node_id,
-                        is_master: node_info.role == "master",
-                        address: (node_info.ip, port),
-                        connection,
+                    self.node_health.send(ConnectionEvent::ClusterNodeAdded {
+                        id: node.id.as_ref().to_owned(),
+                        address: node.address.clone(),
+                        is_master,
                     });
+
+                    self.nodes.push(node);
                 }
             }
         }
@@ -1104,6 +1395,8 @@ impl ClusterConnection {
         self.slot_ranges.sort_by_key(|s| s.slot_range.0);
         self.nodes.sort_by(|n1, n2| n1.id.cmp(&n2.id));
 
+        self.attach_stats();
+
         debug!(
             "[{}] Cluster new setup: nodes={:?}, slot_ranges={:?}",
             self.tag, self.nodes, self.slot_ranges
@@ -1117,15 +1410,72 @@ impl ClusterConnection {
         self.nodes.binary_search_by_key(&id, |n| &n.id).ok()
     }
 
-    #[inline]
+    /// Random node index, unhealthy nodes and nodes which idle connection has been closed
+    /// being avoided when possible
     fn get_random_node_index(&self) -> usize {
+        let has_closed_connections = self.nodes.iter().any(|n| n.connection.is_none());
+        if self.node_health.has_unhealthy_nodes() || has_closed_connections {
+            let node_indices = |check_health: bool| {
+                self.nodes
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, n)| {
+                        n.connection.is_some()
+                            && !(check_health && self.node_health.is_unhealthy(&n.address))
+                    })
+                    .map(|(idx, _)| idx)
+                    .collect::<SmallVec<[usize; 10]>>()
+            };
+
+            let mut candidate_indices = node_indices(true);
+            if candidate_indices.is_empty() {
+                candidate_indices = node_indices(false);
+            }
+            if !candidate_indices.is_empty() {
+                let idx = rand::thread_rng().gen_range(0..candidate_indices.len());
+                return candidate_indices[idx];
+            }
+        }
+
         rand::thread_rng().gen_range(0..self.nodes.len())
     }
 
-    #[inline]
-    fn get_random_node_mut(&mut self) -> &mut Node {
-        let node_idx = self.get_random_node_index();
-        &mut self.nodes[node_idx]
+    /// Close the connections of the nodes which have been idle
+    /// for longer than [`Config::idle_connection_timeout`]
+    ///
+    /// Connections with pending requests or subscriptions are kept open,
+    /// as well as the last open connection.
+    fn close_idle_connections(&mut self) {
+        let Some(idle_connection_timeout) = self.config.idle_connection_timeout else {
+            return;
+        };
+
+        let mut num_open_connections = self.nodes.iter().filter(|n| n.connection.is_some()).count();
+
+        for node in &mut self.nodes {
+            if num_open_connections <= 1 {
+                break;
+            }
+
+            if node.connection.is_none()
+                || node.subscribed
+                || node.last_used.elapsed() < idle_connection_timeout
+                || self.pending_requests.iter().any(|r| {
+                    r.sub_requests
+                        .iter()
+                        .any(|sr| sr.node_id == node.id && sr.result.is_none())
+                })
+            {
+                continue;
+            }
+
+            debug!(
+                "[{}] Closing idle connection to node ({}:{})",
+                self.tag, node.address.0, node.address.1
+            );
+            node.connection = None;
+            num_open_connections -= 1;
+        }
     }
 
     #[inline]
@@ -1175,33 +1525,87 @@ impl ClusterConnection {
         }
     }
 
-    fn hash_slots(keys: &[String]) -> SmallVec<[u16; 10]> {
-        keys.iter().map(|k| Self::hash_slot(k)).collect()
+    /// Drop the connection of a node closed by the server, instead of reconnecting to the whole cluster.
+    ///
+    /// The requests waiting for a reply of the node receive a `CLUSTERDOWN` error,
+    /// so that the replies of the other nodes are still delivered in order.
+    fn isolate_node(&mut self, node_idx: usize) {
+        let node = &mut self.nodes[node_idx];
+        let (ip, port) = &node.address;
+        warn!("[{}] Connection to node ({ip}:{port}) closed", self.tag);
+        node.connection = None;
+
+        let error = RespBuf::from_slice(
+            format!("-CLUSTERDOWN Connection to node {ip}:{port} closed\r\n").as_bytes(),
+        );
+        for request_info in &mut self.pending_requests {
+            for sub_request in &mut request_info.sub_requests {
+                if sub_request.node_id == node.id && sub_request.result.is_none() {
+                    sub_request.result = Some(Some(Ok(error.clone())));
+                }
+            }
+        }
     }
 
-    /// Implement hash_slot algorithm
-    /// see. https://redis.io/docs/reference/cluster-spec/#hash-tags
-    fn hash_slot(key: &str) -> u16 {
-        let mut key = key;
-
-        // { found
-        if let Some(s) = key.find('{') {
-            // } found
-            if let Some(e) = key[s + 1..].find('}') {
-                // hash tag non empty
-                if e != 0 {
-                    key = &key[s + 1..s + 1 + e];
-                }
+    /// Same as `get_master_node_index_by_slot` but if the master node is unhealthy,
+    /// the cluster topology is refreshed, to route to a replica promoted in the meantime.
+    /// Fails fast if the master node is still unhealthy.
+    async fn get_healthy_master_node_index_by_slot(
+        &mut self,
+        slot: u16,
+        ask_reasons: &[(u16, (String, u16))],
+    ) -> Result<(usize, bool)> {
+        let (node_idx, should_ask) = self
+            .get_master_node_index_by_slot(slot, ask_reasons)
+            .ok_or_else(|| Error::Client("Cluster misconfiguration".to_owned()))?;
+        if !self.node_health.is_unhealthy(&self.nodes[node_idx].address) {
+            return Ok((node_idx, should_ask));
+        }
+
+        if self
+            .last_unhealthy_refresh
+            .is_none_or(|last| last.elapsed() >= UNHEALTHY_REFRESH_INTERVAL)
+        {
+            self.last_unhealthy_refresh = Some(Instant::now());
+            self.refresh_nodes_and_slot_ranges().await?;
+
+            let (node_idx, should_ask) = self
+                .get_master_node_index_by_slot(slot, ask_reasons)
+                .ok_or_else(|| Error::Client("Cluster misconfiguration".to_owned()))?;
+            if !self.node_health.is_unhealthy(&self.nodes[node_idx].address) {
+                return Ok((node_idx, should_ask));
             }
         }
 
-        Self::crc16(key) % 16384
+        let (ip, port) = &self.nodes[node_idx].address;
+        Err(Error::Client(format!(
+            "[{}] Cluster node {ip}:{port} is unhealthy",
+            self.tag
+        )))
     }
 
-    fn crc16(str: &str) -> u16 {
-        crc16::State::<crc16::XMODEM>::calculate(str.as_bytes())
+    fn hash_slots(keys: &[String]) -> SmallVec<[u16; 10]> {
+        keys.iter().map(hash_slot).collect()
     }
 
+    /// Shard channels of sharded pub/sub commands, hashed like keys
+    /// to route the commands to the shard owning them
+    fn shard_channels(command: &Command) -> Option<SmallVec<[String; 10]>> {
+        let shard_channels = match command.name {
+            "SPUBLISH" => command.args.into_iter().take(1),
+            "SSUBSCRIBE" | "SUNSUBSCRIBE" => command.args.into_iter().take(usize::MAX),
+            _ => return None,
+        };
+
+        Some(
+            shard_channels
+                .map(|sc| String::from_utf8_lossy(sc).into_owned())
+                .collect(),
+        )
+    }
+
+    /// Convert the result of the legacy `CLUSTER SLOTS` command to shards,
+    /// the ranges of slots of a same master being grouped in one shard
     pub(crate) fn convert_from_legacy_shard_description(
         mut legacy_shards: Vec<LegacyClusterShardResult>,
     ) -> Vec<ClusterShardResult> {
@@ -1244,6 +1648,14 @@ impl ClusterConnection {
         shards
     }
 
+    /// Version of the first known node
+    pub fn get_version(&self) -> Option<&str> {
+        self.nodes
+            .iter()
+            .find_map(|node| node.connection.as_ref())
+            .map(|connection| connection.get_version())
+    }
+
     pub(crate) fn tag(&self) -> &str {
         &self.tag
     }
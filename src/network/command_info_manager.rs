@@ -1,9 +1,11 @@
 use crate::{
-    commands::{BeginSearch, CommandInfo, FindKeys, ServerCommands},
+    client::{CommandInfoCache, CommandKeys},
+    commands::{BeginSearch, CommandInfo, FindKeys, KeySpecification, ServerCommands},
     network::Version,
-    resp::{cmd, Command, CommandArgs},
+    resp::{cmd, Command, CommandArg, CommandArgs},
     Error, Result, StandaloneConnection,
 };
+use log::{debug, warn};
 use smallvec::SmallVec;
 use std::collections::HashMap;
 
@@ -13,8 +15,52 @@ pub(crate) struct CommandInfoManager {
 }
 
 impl CommandInfoManager {
-    pub async fn initialize(connection: &mut StandaloneConnection) -> Result<CommandInfoManager> {
-        let mut command_info_result = connection.command().await?;
+    pub async fn initialize(
+        connection: &mut StandaloneConnection,
+        cache: Option<&CommandInfoCache>,
+        command_keys: &HashMap<String, CommandKeys>,
+    ) -> Result<CommandInfoManager> {
+        let version: Version = connection.get_version().try_into()?;
+
+        let command_infos = match Self::cached_command_infos(cache, version, connection.tag()) {
+            Some(command_infos) => command_infos,
+            None => connection.command().await?,
+        };
+
+        Self::new(command_infos, version, command_keys)
+    }
+
+    /// Command table of `cache`, `None` if there is no cache,
+    /// if it has been made for another server version or if it cannot be loaded
+    pub fn cached_command_infos(
+        cache: Option<&CommandInfoCache>,
+        version: Version,
+        tag: &str,
+    ) -> Option<Vec<CommandInfo>> {
+        match cache {
+            Some(cache) if cache.version() == Some(version) => match cache.command_infos() {
+                Ok(command_infos) => Some(command_infos),
+                Err(e) => {
+                    warn!("[{tag}] Cannot load command info cache: {e}");
+                    None
+                }
+            },
+            Some(cache) => {
+                debug!(
+                    "[{tag}] Command info cache of version {:?} ignored for server version {version}",
+                    cache.version()
+                );
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn new(
+        mut command_info_result: Vec<CommandInfo>,
+        version: Version,
+        command_keys: &HashMap<String, CommandKeys>,
+    ) -> Result<CommandInfoManager> {
         let sub_commands = command_info_result
             .iter()
             .filter_map(|c| {
@@ -28,20 +74,72 @@ impl CommandInfoManager {
             .collect::<Vec<_>>();
         command_info_result.extend(sub_commands);
 
-        let version: Version = connection.get_version().try_into()?;
+        let mut command_info_map: HashMap<String, CommandInfo> = command_info_result
+            .into_iter()
+            .map(|mut c| {
+                c.name = c.name.to_uppercase();
+                (c.name.to_string(), c)
+            })
+            .collect();
+
+        for (command_name, keys) in command_keys {
+            keys.validate(command_name)?;
+            let command_info = command_info_map
+                .entry(command_name.clone())
+                .or_insert_with(|| Self::registered_command_info(command_name));
+            Self::override_keys(command_info, keys);
+        }
 
         Ok(CommandInfoManager {
-            command_info_map: command_info_result
-                .into_iter()
-                .map(|mut c| {
-                    c.name = c.name.to_uppercase();
-                    (c.name.to_string(), c)
-                })
-                .collect(),
+            command_info_map,
             legacy: version.major < 7,
         })
     }
 
+    /// Command info of a command unknown to the server,
+    /// registered with [`Config::register_command_keys`](crate::client::Config::register_command_keys)
+    fn registered_command_info(command_name: &str) -> CommandInfo {
+        CommandInfo {
+            name: command_name.to_owned(),
+            arity: -1,
+            flags: Vec::new(),
+            first_key: 0,
+            last_key: 0,
+            step: 0,
+            acl_categories: Vec::new(),
+            command_tips: Vec::new(),
+            key_specifications: Vec::new(),
+            sub_commands: Vec::new(),
+        }
+    }
+
+    /// Replace the key positions & key specifications of a command by the registered ones
+    fn override_keys(command_info: &mut CommandInfo, keys: &CommandKeys) {
+        command_info.first_key = keys.first_key;
+        command_info.last_key = keys.last_key;
+        command_info.step = keys.step;
+        command_info.flags.retain(|f| f != "movablekeys");
+        command_info.key_specifications.clear();
+
+        if keys.first_key > 0 {
+            command_info.key_specifications.push(KeySpecification {
+                begin_search: BeginSearch::Index(keys.first_key),
+                find_keys: FindKeys::Range {
+                    // relative to the first key in key specifications
+                    last_key: if keys.last_key >= 0 {
+                        keys.last_key - keys.first_key as isize
+                    } else {
+                        keys.last_key
+                    },
+                    key_step: keys.step,
+                    limit: 0,
+                },
+                flags: Vec::new(),
+                notes: String::new(),
+            });
+        }
+    }
+
     pub fn get_command_info_by_name(&self, command_name: &str) -> Option<&CommandInfo> {
         self.command_info_map.get(command_name)
     }
@@ -68,27 +166,66 @@ impl CommandInfoManager {
         command: &Command,
         connection: &mut StandaloneConnection,
     ) -> Result<SmallVec<[String; 10]>> {
+        match self.extract_keys_locally(command)? {
+            Some(keys) => Ok(keys),
+            None => {
+                let args = Self::prepare_command_getkeys_args(command);
+                connection.command_getkeys(args).await
+            }
+        }
+    }
+
+    /// Extract the keys of `command` from its key specifications
+    ///
+    /// # Return
+    /// `None` if the keys can only be extracted by the server with `COMMAND GETKEYS`
+    /// (commands with movable keys)
+    pub fn extract_keys_locally(
+        &self,
+        command: &Command,
+    ) -> Result<Option<SmallVec<[String; 10]>>> {
         let command_info = if let Some(command_info) = self.command_info_map.get(command.name) {
             command_info
         } else {
             return Err(Error::Client(format!("Unknown command {}", command.name)));
         };
 
+        let missing_keys = || {
+            Error::Client(format!(
+                "Cannot find the keys of {} in its {} arguments",
+                command.name,
+                command.args.len()
+            ))
+        };
+        let invalid_step = || {
+            Error::Client(format!(
+                "Cannot extract the keys of {} with a key step of 0",
+                command.name
+            ))
+        };
+
         if self.legacy {
             if command_info.first_key == 0 || command_info.last_key == 0 {
-                return Ok(SmallVec::new());
+                return Ok(Some(SmallVec::new()));
             } else if command_info.flags.iter().any(|f| f == "movablekeys") {
-                let args = Self::prepare_command_getkeys_args(command);
-                let keys: SmallVec<[String; 10]> = connection.command_getkeys(args).await?;
-                return Ok(keys);
+                return Ok(None);
             } else {
-                let mut slice: &[Vec<u8>] = &command.args[command_info.first_key - 1..];
+                if command_info.step == 0 {
+                    return Err(invalid_step());
+                }
+                let args: &[CommandArg] = &command.args;
+                let slice = args
+                    .get(command_info.first_key - 1..)
+                    .ok_or_else(missing_keys)?;
+                // positions are absolute, `-1` being the last argument
                 let stop_index = if command_info.last_key >= 0 {
-                    command_info.last_key as usize
+                    (command_info.last_key as usize + 1).checked_sub(command_info.first_key)
                 } else {
-                    slice.len() - (-command_info.last_key as usize) + 1
+                    (slice.len() + 1).checked_sub(-command_info.last_key as usize)
                 };
-                slice = &slice[..stop_index];
+                let slice = stop_index
+                    .and_then(|stop_index| slice.get(..stop_index))
+                    .ok_or_else(missing_keys)?;
 
                 let keys = slice
                     .iter()
@@ -97,22 +234,32 @@ impl CommandInfoManager {
                         if bs.is_empty() {
                             None
                         } else {
-                            String::from_utf8(bs.clone()).ok()
+                            String::from_utf8(bs.to_vec()).ok()
                         }
                     })
                     .collect();
-                return Ok(keys);
+                return Ok(Some(keys));
             }
         }
 
         let mut keys = SmallVec::<[String; 10]>::new();
 
         for key_spec in &command_info.key_specifications {
-            let mut slice: &[Vec<u8>] = &command.args;
+            let mut slice: &[CommandArg] = &command.args;
 
             // begin_search
             match &key_spec.begin_search {
-                BeginSearch::Index(i) => slice = &slice[*i - 1..],
+                BeginSearch::Index(i) => {
+                    slice = i
+                        .checked_sub(1)
+                        .and_then(|i| slice.get(i..))
+                        .ok_or_else(|| {
+                            Error::Client(format!(
+                                "Cannot find the first key of {} at position {i}",
+                                command.name
+                            ))
+                        })?;
+                }
                 BeginSearch::Keyword {
                     keyword,
                     start_from,
@@ -142,9 +289,7 @@ impl CommandInfoManager {
                     }
                 }
                 BeginSearch::Unknown => {
-                    let args = Self::prepare_command_getkeys_args(command);
-                    let keys: SmallVec<[String; 10]> = connection.command_getkeys(args).await?;
-                    return Ok(keys);
+                    return Ok(None);
                 }
             }
 
@@ -156,14 +301,16 @@ impl CommandInfoManager {
                     limit,
                 } => {
                     let stop_index = if *last_key >= 0 {
-                        *last_key as usize
+                        Some(*last_key as usize)
                     } else if *last_key == -1 && *limit >= 2 {
-                        slice.len() / limit - 1
+                        (slice.len() / limit).checked_sub(1)
                     } else {
-                        slice.len() - (-*last_key as usize)
+                        slice.len().checked_sub(-*last_key as usize)
                     };
 
-                    slice = &slice[..stop_index + 1];
+                    slice = stop_index
+                        .and_then(|stop_index| slice.get(..stop_index + 1))
+                        .ok_or_else(missing_keys)?;
                     *key_step
                 }
                 FindKeys::KeyNum {
@@ -171,7 +318,7 @@ impl CommandInfoManager {
                     first_key,
                     key_step,
                 } => {
-                    let num_keys = slice[*key_num_idx].as_slice();
+                    let num_keys = slice.get(*key_num_idx).ok_or_else(missing_keys)?.as_slice();
                     let num_keys: usize = atoi::atoi(num_keys).ok_or_else(|| {
                         Error::Client(format!(
                             "Cannot parse integer from {}",
@@ -179,26 +326,37 @@ impl CommandInfoManager {
                         ))
                     })?;
 
-                    slice = &slice[*first_key..num_keys + 1];
+                    // the last key is at `first_key + (num_keys - 1) * key_step`
+                    let stop_index = match num_keys {
+                        0 => Some(*first_key),
+                        _ => (num_keys - 1)
+                            .checked_mul(*key_step)
+                            .and_then(|offset| offset.checked_add(*first_key + 1)),
+                    };
+                    slice = stop_index
+                        .and_then(|stop_index| slice.get(*first_key..stop_index))
+                        .ok_or_else(missing_keys)?;
                     *key_step
                 }
                 FindKeys::Unknown {} => {
-                    let args = Self::prepare_command_getkeys_args(command);
-                    let keys: SmallVec<[String; 10]> = connection.command_getkeys(args).await?;
-                    return Ok(keys);
+                    return Ok(None);
                 }
             };
 
+            if key_step == 0 {
+                return Err(invalid_step());
+            }
+
             keys.extend(slice.iter().step_by(key_step).filter_map(|bs| {
                 if bs.is_empty() {
                     None
                 } else {
-                    String::from_utf8(bs.clone()).ok()
+                    String::from_utf8(bs.to_vec()).ok()
                 }
             }));
         }
 
-        Ok(keys)
+        Ok(Some(keys))
     }
 
     /// Function used for commands which RequestPolicy is MultiShard
@@ -219,7 +377,7 @@ impl CommandInfoManager {
         };
 
         if let Some(key_spec) = command_info.key_specifications.first() {
-            let slice: &[Vec<u8>] = &command.args;
+            let slice: &[CommandArg] = &command.args;
             let mut shard_command = cmd(command.name);
 
             // begin_search
@@ -290,7 +448,7 @@ impl CommandInfoManager {
         unreachable!();
     }
 
-    fn prepare_command_getkeys_args(command: &Command) -> CommandArgs {
+    pub fn prepare_command_getkeys_args(command: &Command) -> CommandArgs {
         let mut args = CommandArgs::default();
         args.arg(command.name);
         args.arg(&command.args);
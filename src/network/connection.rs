@@ -1,28 +1,47 @@
+#[cfg(feature = "chaos")]
+use crate::ChaosConnection;
 use crate::{
     client::{Config, PreparedCommand, ServerConfig},
     commands::InternalPubSubCommands,
     resp::{Command, RespBuf},
-    ClusterConnection, Error, Future, Result, RetryReason, SentinelConnection,
-    StandaloneConnection,
+    ClusterConnection, ConnectionEventSender, Error, Future, RedisError, RedisErrorKind, Result,
+    RetryReason, SentinelConnection, StandaloneConnection, StatsRegistry, Version,
 };
+#[cfg(feature = "mock")]
+use crate::{MockConnection, RecordingConnection, ReplayConnection};
+use log::warn;
 use serde::de::DeserializeOwned;
 use smallvec::SmallVec;
-use std::future::IntoFuture;
+use std::{future::IntoFuture, sync::Arc};
 
 #[allow(clippy::large_enum_variant)]
 pub enum Connection {
     Standalone(StandaloneConnection),
     Sentinel(SentinelConnection),
     Cluster(ClusterConnection),
+    #[cfg(feature = "mock")]
+    Mock(MockConnection),
+    #[cfg(feature = "mock")]
+    Recording(RecordingConnection),
+    #[cfg(feature = "mock")]
+    Replay(ReplayConnection),
+    #[cfg(feature = "chaos")]
+    Chaos(ChaosConnection),
 }
 
 impl Connection {
     #[inline]
     pub async fn connect(config: Config) -> Result<Self> {
         match &config.server {
-            ServerConfig::Standalone { host, port } => Ok(Connection::Standalone(
-                StandaloneConnection::connect(host, *port, &config).await?,
-            )),
+            ServerConfig::Standalone { host, port } => {
+                let mut connection = StandaloneConnection::connect(host, *port, &config).await?;
+                if config.auto_detect_cluster {
+                    if let Some(cluster_connection) = connection.connect_to_cluster().await? {
+                        return Ok(Connection::Cluster(cluster_connection));
+                    }
+                }
+                Ok(Connection::Standalone(connection))
+            }
             ServerConfig::Sentinel(sentinel_config) => Ok(Connection::Sentinel(
                 SentinelConnection::connect(sentinel_config, &config).await?,
             )),
@@ -38,6 +57,14 @@ impl Connection {
             Connection::Standalone(connection) => connection.write(command).await,
             Connection::Sentinel(connection) => connection.write(command).await,
             Connection::Cluster(connection) => connection.write(command).await,
+            #[cfg(feature = "mock")]
+            Connection::Mock(connection) => connection.write(command).await,
+            #[cfg(feature = "mock")]
+            Connection::Recording(connection) => connection.write(command).await,
+            #[cfg(feature = "mock")]
+            Connection::Replay(connection) => connection.write(command).await,
+            #[cfg(feature = "chaos")]
+            Connection::Chaos(connection) => connection.write(command).await,
         }
     }
 
@@ -57,24 +84,70 @@ impl Connection {
             Connection::Cluster(connection) => {
                 connection.write_batch(commands, retry_reasons).await
             }
+            #[cfg(feature = "mock")]
+            Connection::Mock(connection) => connection.write_batch(commands, retry_reasons).await,
+            #[cfg(feature = "mock")]
+            Connection::Recording(connection) => {
+                connection.write_batch(commands, retry_reasons).await
+            }
+            #[cfg(feature = "mock")]
+            Connection::Replay(connection) => connection.write_batch(commands, retry_reasons).await,
+            #[cfg(feature = "chaos")]
+            Connection::Chaos(connection) => connection.write_batch(commands, retry_reasons).await,
         }
     }
 
     #[inline]
     pub async fn read(&mut self) -> Option<Result<RespBuf>> {
         match self {
-            Connection::Standalone(connection) => connection.read().await,
+            Connection::Standalone(connection) => {
+                let result = connection.read().await;
+                if connection.config().auto_detect_cluster && is_moved(&result) {
+                    // the server has become a cluster node:
+                    // reconnect as a cluster connection
+                    warn!(
+                        "[{}] Command redirected with MOVED, reconnecting as a cluster connection",
+                        connection.tag()
+                    );
+                    return None;
+                }
+                result
+            }
             Connection::Sentinel(connection) => connection.read().await,
             Connection::Cluster(connection) => connection.read().await,
+            #[cfg(feature = "mock")]
+            Connection::Mock(connection) => connection.read().await,
+            #[cfg(feature = "mock")]
+            Connection::Recording(connection) => connection.read().await,
+            #[cfg(feature = "mock")]
+            Connection::Replay(connection) => connection.read().await,
+            #[cfg(feature = "chaos")]
+            Connection::Chaos(connection) => connection.read().await,
         }
     }
 
     #[inline]
     pub async fn reconnect(&mut self) -> Result<()> {
         match self {
-            Connection::Standalone(connection) => connection.reconnect().await,
+            Connection::Standalone(connection) => {
+                connection.reconnect().await?;
+                if connection.config().auto_detect_cluster {
+                    if let Some(cluster_connection) = connection.connect_to_cluster().await? {
+                        *self = Connection::Cluster(cluster_connection);
+                    }
+                }
+                Ok(())
+            }
             Connection::Sentinel(connection) => connection.reconnect().await,
             Connection::Cluster(connection) => connection.reconnect().await,
+            #[cfg(feature = "mock")]
+            Connection::Mock(connection) => connection.reconnect().await,
+            #[cfg(feature = "mock")]
+            Connection::Recording(connection) => connection.reconnect().await,
+            #[cfg(feature = "mock")]
+            Connection::Replay(connection) => connection.reconnect().await,
+            #[cfg(feature = "chaos")]
+            Connection::Chaos(connection) => connection.reconnect().await,
         }
     }
 
@@ -86,13 +159,127 @@ impl Connection {
             .ok_or_else(|| Error::Client("Disconnected by peer".to_owned()))?
     }
 
+    /// Id of the connection, as reported by `HELLO` during the connection,
+    /// `None` for a cluster
+    pub(crate) fn id(&self) -> Option<i64> {
+        match self {
+            Connection::Standalone(connection) => Some(connection.get_id()),
+            Connection::Sentinel(connection) => Some(connection.inner_connection.get_id()),
+            Connection::Cluster(_) => None,
+            #[cfg(feature = "mock")]
+            Connection::Mock(_) => None,
+            #[cfg(feature = "mock")]
+            Connection::Recording(connection) => connection.id(),
+            #[cfg(feature = "mock")]
+            Connection::Replay(_) => None,
+            #[cfg(feature = "chaos")]
+            Connection::Chaos(connection) => connection.id(),
+        }
+    }
+
+    /// `host:port` of the connected server, `None` for a cluster
+    pub(crate) fn address(&self) -> Option<String> {
+        match self {
+            Connection::Standalone(connection) => Some(connection.address()),
+            Connection::Sentinel(connection) => Some(connection.inner_connection.address()),
+            Connection::Cluster(_) => None,
+            #[cfg(feature = "mock")]
+            Connection::Mock(_) => None,
+            #[cfg(feature = "mock")]
+            Connection::Recording(connection) => connection.address(),
+            #[cfg(feature = "mock")]
+            Connection::Replay(_) => None,
+            #[cfg(feature = "chaos")]
+            Connection::Chaos(connection) => connection.address(),
+        }
+    }
+
+    /// `true` for a cluster connection,
+    /// including a standalone connection upgraded with [`Config::auto_detect_cluster`]
+    pub(crate) fn is_cluster(&self) -> bool {
+        match self {
+            Connection::Cluster(_) => true,
+            Connection::Standalone(_) | Connection::Sentinel(_) => false,
+            #[cfg(feature = "mock")]
+            Connection::Mock(_) | Connection::Replay(_) => false,
+            #[cfg(feature = "mock")]
+            Connection::Recording(connection) => connection.is_cluster(),
+            #[cfg(feature = "chaos")]
+            Connection::Chaos(connection) => connection.is_cluster(),
+        }
+    }
+
+    /// Version of the server, as reported by `HELLO` during the connection
+    pub(crate) fn version(&self) -> Option<Version> {
+        let version = match self {
+            Connection::Standalone(connection) => connection.get_version(),
+            Connection::Sentinel(connection) => connection.inner_connection.get_version(),
+            Connection::Cluster(connection) => connection.get_version()?,
+            #[cfg(feature = "mock")]
+            Connection::Mock(connection) => return connection.version(),
+            #[cfg(feature = "mock")]
+            Connection::Recording(connection) => return connection.version(),
+            #[cfg(feature = "mock")]
+            Connection::Replay(_) => return None,
+            #[cfg(feature = "chaos")]
+            Connection::Chaos(connection) => return connection.version(),
+        };
+
+        version.try_into().ok()
+    }
+
     pub(crate) fn tag(&self) -> &str {
         match self {
             Connection::Standalone(connection) => connection.tag(),
             Connection::Sentinel(connection) => connection.tag(),
             Connection::Cluster(connection) => connection.tag(),
+            #[cfg(feature = "mock")]
+            Connection::Mock(connection) => connection.tag(),
+            #[cfg(feature = "mock")]
+            Connection::Recording(connection) => connection.tag(),
+            #[cfg(feature = "mock")]
+            Connection::Replay(connection) => connection.tag(),
+            #[cfg(feature = "chaos")]
+            Connection::Chaos(connection) => connection.tag(),
         }
     }
+
+    /// Mock connections do not record stats
+    pub(crate) fn set_stats(&mut self, stats: Arc<StatsRegistry>) {
+        match self {
+            Connection::Standalone(connection) => connection.set_stats(&stats),
+            Connection::Sentinel(connection) => connection.set_stats(stats),
+            Connection::Cluster(connection) => connection.set_stats(stats),
+            #[cfg(feature = "mock")]
+            Connection::Mock(_) | Connection::Recording(_) | Connection::Replay(_) => (),
+            #[cfg(feature = "chaos")]
+            Connection::Chaos(connection) => connection.set_stats(stats),
+        }
+    }
+
+    /// Only cluster connections emit [`ConnectionEvent`](crate::client::ConnectionEvent)s for now
+    pub(crate) fn set_event_sender(&mut self, event_sender: ConnectionEventSender) {
+        match self {
+            Connection::Cluster(connection) => connection.set_event_sender(event_sender),
+            #[cfg(feature = "chaos")]
+            Connection::Chaos(connection) => connection.set_event_sender(event_sender),
+            _ => (),
+        }
+    }
+}
+
+/// `true` if `result` is a `MOVED` redirection
+fn is_moved(result: &Option<Result<RespBuf>>) -> bool {
+    match result {
+        Some(Ok(resp_buf)) if resp_buf.is_error() => matches!(
+            resp_buf.to::<()>(),
+            Err(Error::Redis(RedisError {
+                kind: RedisErrorKind::Moved { .. },
+                ..
+            }))
+        ),
+        _ => false,
+    }
 }
 
 impl<'a, R> IntoFuture for PreparedCommand<'a, &'a mut Connection, R>
@@ -0,0 +1,221 @@
+use crate::{
+    client::{ClientStats, NodeStats},
+    network::ReplyMode,
+    resp::Command,
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+    time::{Duration, Instant},
+};
+
+/// Number of replies of the sliding window used to compute latency percentiles
+const LATENCY_WINDOW: usize = 1024;
+/// Maximum number of commands tracked while waiting for their reply
+const MAX_TRACKED_COMMANDS: usize = 16 * 1024;
+/// Commands acknowledged by push messages instead of regular replies
+const PUSH_REPLY_COMMANDS: &[&str] = &[
+    "PSUBSCRIBE",
+    "PUNSUBSCRIBE",
+    "SSUBSCRIBE",
+    "SUBSCRIBE",
+    "SUNSUBSCRIBE",
+    "UNSUBSCRIBE",
+];
+
+/// Counters of all the nodes of a client, shared by the connections of the nodes
+#[derive(Debug, Default)]
+pub(crate) struct StatsRegistry {
+    nodes: RwLock<HashMap<String, Arc<NodeCounters>>>,
+}
+
+impl StatsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counters of the node at `address`, created on first use
+    pub fn node(&self, address: &str) -> Arc<NodeCounters> {
+        if let Some(counters) = self.nodes.read().unwrap().get(address) {
+            return counters.clone();
+        }
+
+        self.nodes
+            .write()
+            .unwrap()
+            .entry(address.to_owned())
+            .or_default()
+            .clone()
+    }
+
+    pub fn snapshot(&self) -> ClientStats {
+        let mut nodes = self
+            .nodes
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(address, counters)| counters.snapshot(address))
+            .collect::<Vec<_>>();
+        nodes.sort_by(|n1, n2| n1.address.cmp(&n2.address));
+
+        ClientStats { nodes }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct NodeCounters {
+    commands_sent: AtomicU64,
+    errors: AtomicU64,
+    retries: AtomicU64,
+    reconnects: AtomicU64,
+    bytes_in: AtomicU64,
+    bytes_out: AtomicU64,
+    /// ring buffer of the latencies of the last replies, in microseconds
+    latencies: Box<[AtomicU64]>,
+    /// number of latencies recorded since the creation of the counters
+    num_latencies: AtomicU64,
+}
+
+impl Default for NodeCounters {
+    fn default() -> Self {
+        Self {
+            commands_sent: Default::default(),
+            errors: Default::default(),
+            retries: Default::default(),
+            reconnects: Default::default(),
+            bytes_in: Default::default(),
+            bytes_out: Default::default(),
+            latencies: (0..LATENCY_WINDOW).map(|_| AtomicU64::new(0)).collect(),
+            num_latencies: Default::default(),
+        }
+    }
+}
+
+impl NodeCounters {
+    pub fn record_reconnect(&self) {
+        self.reconnects.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_latency(&self, latency: Duration) {
+        let idx = self.num_latencies.fetch_add(1, Ordering::Relaxed) as usize % LATENCY_WINDOW;
+        self.latencies[idx].store(latency.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self, address: &str) -> NodeStats {
+        let num_latencies =
+            (self.num_latencies.load(Ordering::Relaxed) as usize).min(LATENCY_WINDOW);
+        let mut latencies = self.latencies[..num_latencies]
+            .iter()
+            .map(|latency| latency.load(Ordering::Relaxed))
+            .collect::<Vec<_>>();
+        latencies.sort_unstable();
+
+        NodeStats {
+            address: address.to_owned(),
+            commands_sent: self.commands_sent.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+            retries: self.retries.load(Ordering::Relaxed),
+            reconnects: self.reconnects.load(Ordering::Relaxed),
+            latency_p50: percentile(&latencies, 50),
+            latency_p99: percentile(&latencies, 99),
+            bytes_in: self.bytes_in.load(Ordering::Relaxed),
+            bytes_out: self.bytes_out.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted latencies
+pub(crate) fn percentile(sorted_latencies: &[u64], percentile: usize) -> Option<Duration> {
+    if sorted_latencies.is_empty() {
+        return None;
+    }
+
+    let rank = (sorted_latencies.len() * percentile).div_ceil(100).max(1);
+    Some(Duration::from_micros(sorted_latencies[rank - 1]))
+}
+
+/// Recording of the counters of a node by its connection
+///
+/// Replies are matched with their commands in order to measure latencies,
+/// the commands without reply (Pub/Sub subscriptions, `CLIENT REPLY OFF|SKIP`) being skipped.
+#[derive(Debug)]
+pub(crate) struct NodeStatsProbe {
+    counters: Arc<NodeCounters>,
+    /// send time of the commands waiting for their reply
+    pub(crate) in_flight: VecDeque<Instant>,
+    reply_mode: ReplyMode,
+}
+
+impl NodeStatsProbe {
+    pub fn new(counters: Arc<NodeCounters>) -> Self {
+        Self {
+            counters,
+            in_flight: VecDeque::new(),
+            reply_mode: ReplyMode::On,
+        }
+    }
+
+    pub fn record_write<'c>(
+        &mut self,
+        commands: impl IntoIterator<Item = &'c Command>,
+        num_bytes: usize,
+    ) {
+        self.counters
+            .bytes_out
+            .fetch_add(num_bytes as u64, Ordering::Relaxed);
+
+        let now = Instant::now();
+        for command in commands {
+            self.counters.commands_sent.fetch_add(1, Ordering::Relaxed);
+
+            if !self.reply_mode.expect_reply(command)
+                || PUSH_REPLY_COMMANDS.binary_search(&command.name).is_ok()
+            {
+                continue;
+            }
+
+            if self.in_flight.len() == MAX_TRACKED_COMMANDS {
+                self.in_flight.pop_front();
+            }
+            self.in_flight.push_back(now);
+        }
+    }
+
+    /// Record a reply
+    ///
+    /// `reply` is the raw RESP reply,
+    /// push messages (e.g. Pub/Sub messages) are not matched with a command
+    pub fn record_reply(&mut self, reply: &[u8], is_push_message: bool) {
+        self.counters
+            .bytes_in
+            .fetch_add(reply.len() as u64, Ordering::Relaxed);
+
+        if reply.starts_with(b"-MOVED ")
+            || reply.starts_with(b"-ASK ")
+            || reply.starts_with(b"-TRYAGAIN ")
+        {
+            self.counters.retries.fetch_add(1, Ordering::Relaxed);
+        } else if reply.starts_with(b"-") || reply.starts_with(b"!") {
+            self.counters.errors.fetch_add(1, Ordering::Relaxed);
+        }
+
+        if !is_push_message {
+            if let Some(sent) = self.in_flight.pop_front() {
+                self.counters.record_latency(sent.elapsed());
+            }
+        }
+    }
+
+    pub fn record_error(&mut self) {
+        self.counters.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_reconnect(&mut self) {
+        self.in_flight.clear();
+        self.reply_mode = ReplyMode::On;
+        self.counters.record_reconnect();
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{
+    commands::{CommandDoc, ServerCommands},
+    resp::Command,
+    Result, StandaloneConnection,
+};
+use log::warn;
+use std::collections::{HashMap, HashSet};
+
+struct DeprecatedCommand {
+    since: String,
+    replaced_by: String,
+}
+
+/// Deprecated commands of the connected server, based on [`COMMAND DOCS`](https://redis.io/commands/command-docs/)
+///
+/// A warning is logged the first time a deprecated command is sent.
+pub(crate) struct DeprecatedCommands {
+    /// deprecated commands by lowercase name (`command` or `command|subcommand`)
+    commands: HashMap<String, DeprecatedCommand>,
+    warned: HashSet<String>,
+}
+
+impl DeprecatedCommands {
+    pub async fn fetch(connection: &mut StandaloneConnection) -> Result<Self> {
+        let docs: HashMap<String, CommandDoc> =
+            connection.command_docs(Vec::<String>::new()).await?;
+        Ok(Self::from_docs(docs))
+    }
+
+    pub fn from_docs(docs: HashMap<String, CommandDoc>) -> Self {
+        let mut commands = HashMap::new();
+        for (name, doc) in docs {
+            for (sub_name, sub_doc) in doc.subcommands {
+                if !sub_doc.deprecated_since.is_empty() {
+                    commands.insert(
+                        sub_name.to_ascii_lowercase(),
+                        DeprecatedCommand {
+                            since: sub_doc.deprecated_since,
+                            replaced_by: sub_doc.replaced_by,
+                        },
+                    );
+                }
+            }
+
+            if !doc.deprecated_since.is_empty() {
+                commands.insert(
+                    name.to_ascii_lowercase(),
+                    DeprecatedCommand {
+                        since: doc.deprecated_since,
+                        replaced_by: doc.replaced_by,
+                    },
+                );
+            }
+        }
+
+        Self {
+            commands,
+            warned: HashSet::new(),
+        }
+    }
+
+    /// Log a warning the first time `command` is sent, if it is deprecated
+    ///
+    /// # Return
+    /// `true` if a warning has been logged
+    pub fn check(&mut self, command: &Command, tag: &str) -> bool {
+        if self.commands.is_empty() {
+            return false;
+        }
+
+        let mut name = command.name.to_ascii_lowercase();
+
+        if let Some(subcommand) = command.args.into_iter().next() {
+            let full_name = format!(
+                "{name}|{}",
+                String::from_utf8_lossy(subcommand).to_ascii_lowercase()
+            );
+            if self.commands.contains_key(&full_name) {
+                name = full_name;
+            }
+        }
+
+        let Some(deprecated) = self.commands.get(&name) else {
+            return false;
+        };
+
+        let display_name = name.replace('|', " ").to_ascii_uppercase();
+        if !self.warned.insert(name) {
+            return false;
+        }
+
+        if deprecated.replaced_by.is_empty() {
+            warn!(
+                "[{tag}] Command {display_name} is deprecated since Redis {}",
+                deprecated.since
+            );
+        } else {
+            warn!(
+                "[{tag}] Command {display_name} is deprecated since Redis {}, replaced by {}",
+                deprecated.since, deprecated.replaced_by
+            );
+        }
+
+        true
+    }
+}
@@ -0,0 +1,127 @@
+use crate::client::Message;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+/// Commands of a client instance which have been sent and are waiting for their replies.
+///
+/// The in-flight counter of the client is decremented when the message is dropped,
+/// whatever the outcome of the message (reply, error, disconnection).
+#[derive(Debug)]
+pub(crate) struct InFlightGuard {
+    counter: Arc<AtomicUsize>,
+    num_commands: usize,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.counter.fetch_sub(self.num_commands, Ordering::Relaxed);
+    }
+}
+
+/// Schedules the messages of the different client instances sharing the same connection.
+///
+/// Messages are handled in a round-robin fashion between client instances,
+/// preserving the order of the messages of each instance.
+/// When a maximum number of in-flight commands per client instance is configured,
+/// the messages of an instance which reached this limit stay queued until enough replies are received.
+pub(crate) struct MessageScheduler {
+    max_in_flight_per_client: usize,
+    queues: HashMap<usize, VecDeque<Message>>,
+    order: VecDeque<usize>,
+    in_flight: HashMap<usize, Arc<AtomicUsize>>,
+}
+
+impl MessageScheduler {
+    pub fn new(max_in_flight_per_client: usize) -> Self {
+        Self {
+            max_in_flight_per_client,
+            queues: HashMap::new(),
+            order: VecDeque::new(),
+            in_flight: HashMap::new(),
+        }
+    }
+
+    pub fn push(&mut self, message: Message) {
+        let client_id = message.client_id;
+        let queue = self.queues.entry(client_id).or_default();
+        if queue.is_empty() {
+            self.order.push_back(client_id);
+        }
+        queue.push_back(message);
+    }
+
+    /// Next message to handle, or `None` if all the queued messages
+    /// belong to client instances which reached their in-flight limit
+    pub fn pop(&mut self) -> Option<Message> {
+        for _ in 0..self.order.len() {
+            let client_id = self.order.pop_front()?;
+            let queue = self.queues.get_mut(&client_id)?;
+
+            let Some(message) = queue.front_mut() else {
+                continue;
+            };
+
+            if !Self::try_acquire(self.max_in_flight_per_client, &mut self.in_flight, message) {
+                self.order.push_back(client_id);
+                continue;
+            }
+
+            let message = queue.pop_front();
+            if queue.is_empty() {
+                self.queues.remove(&client_id);
+            } else {
+                self.order.push_back(client_id);
+            }
+
+            return message;
+        }
+
+        // forget counters of client instances without in-flight commands
+        self.in_flight
+            .retain(|_, counter| Arc::strong_count(counter) > 1);
+
+        None
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queues.is_empty()
+    }
+
+    pub fn drain(&mut self) -> impl Iterator<Item = Message> + '_ {
+        self.order.clear();
+        self.queues.drain().flat_map(|(_, queue)| queue)
+    }
+
+    fn try_acquire(
+        max_in_flight_per_client: usize,
+        in_flight: &mut HashMap<usize, Arc<AtomicUsize>>,
+        message: &mut Message,
+    ) -> bool {
+        // unlimited or already acquired (retry)
+        if max_in_flight_per_client == 0 || message.in_flight_guard.is_some() {
+            return true;
+        }
+
+        let num_commands = message.commands.len();
+        let counter = in_flight.entry(message.client_id).or_default();
+        let current = counter.load(Ordering::Relaxed);
+
+        // a batch larger than the limit is sent alone
+        if current != 0 && current + num_commands > max_in_flight_per_client {
+            return false;
+        }
+
+        counter.fetch_add(num_commands, Ordering::Relaxed);
+        message.in_flight_guard = Some(InFlightGuard {
+            counter: counter.clone(),
+            num_commands,
+        });
+
+        true
+    }
+}
@@ -0,0 +1,722 @@
+use crate::{
+    client::MockServerBehavior,
+    resp::{Command, CommandArg, RespBuf, RespSerializer, Value},
+    RedisError, RedisErrorKind, Result, RetryReason, Version,
+};
+use serde::Serialize;
+use smallvec::SmallVec;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+type MockResult = std::result::Result<Value, Value>;
+
+enum MockValue {
+    String(Vec<u8>),
+    List(VecDeque<Vec<u8>>),
+    Set(HashSet<Vec<u8>>),
+    Hash(HashMap<Vec<u8>, Vec<u8>>),
+}
+
+impl MockValue {
+    fn type_name(&self) -> &'static str {
+        match self {
+            MockValue::String(_) => "string",
+            MockValue::List(_) => "list",
+            MockValue::Set(_) => "set",
+            MockValue::Hash(_) => "hash",
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        match self {
+            MockValue::String(_) => false,
+            MockValue::List(l) => l.is_empty(),
+            MockValue::Set(s) => s.is_empty(),
+            MockValue::Hash(h) => h.is_empty(),
+        }
+    }
+}
+
+/// Generates typed accessors to the keyspace.
+/// Accessing a key of another type returns a `WRONGTYPE` error.
+macro_rules! typed_accessors {
+    ($get:ident, $get_mut:ident, $get_or_insert:ident, $variant:ident, $type:ty) => {
+        fn $get(&self, key: &[u8]) -> std::result::Result<Option<&$type>, Value> {
+            match self.keyspace.get(key) {
+                Some(MockValue::$variant(v)) => Ok(Some(v)),
+                Some(_) => Err(wrong_type()),
+                None => Ok(None),
+            }
+        }
+
+        fn $get_mut(&mut self, key: &[u8]) -> std::result::Result<Option<&mut $type>, Value> {
+            match self.keyspace.get_mut(key) {
+                Some(MockValue::$variant(v)) => Ok(Some(v)),
+                Some(_) => Err(wrong_type()),
+                None => Ok(None),
+            }
+        }
+
+        fn $get_or_insert(&mut self, key: &[u8]) -> std::result::Result<&mut $type, Value> {
+            match self
+                .keyspace
+                .entry(key.to_vec())
+                .or_insert_with(|| MockValue::$variant(Default::default()))
+            {
+                MockValue::$variant(v) => Ok(v),
+                _ => Err(wrong_type()),
+            }
+        }
+    };
+}
+
+/// In-memory connection used by [`Client::connect_mock`](crate::client::Client::connect_mock)
+pub struct MockConnection {
+    behavior: MockServerBehavior,
+    keyspace: HashMap<Vec<u8>, MockValue>,
+    transaction: Option<Vec<Command>>,
+    replies: VecDeque<RespBuf>,
+    /// `CLIENT REPLY OFF`
+    reply_off: bool,
+    /// `CLIENT REPLY SKIP`
+    skip_next_reply: bool,
+}
+
+impl MockConnection {
+    pub fn new(behavior: MockServerBehavior) -> Self {
+        Self {
+            behavior,
+            keyspace: HashMap::new(),
+            transaction: None,
+            replies: VecDeque::new(),
+            reply_off: false,
+            skip_next_reply: false,
+        }
+    }
+
+    #[inline]
+    pub async fn write(&mut self, command: &Command) -> Result<()> {
+        let skip_reply = std::mem::take(&mut self.skip_next_reply);
+        let reply = self.process(command);
+
+        let send_reply = match client_reply_mode(command).as_deref() {
+            Some(b"ON") => {
+                self.reply_off = false;
+                true
+            }
+            Some(b"OFF") => {
+                self.reply_off = true;
+                false
+            }
+            Some(b"SKIP") => {
+                self.skip_next_reply = !self.reply_off;
+                false
+            }
+            _ => !skip_reply && !self.reply_off,
+        };
+
+        if !send_reply {
+            return Ok(());
+        }
+
+        let mut serializer = RespSerializer::new();
+        reply.serialize(&mut serializer)?;
+        self.replies
+            .push_back(RespBuf::new(serializer.get_output().freeze()));
+        Ok(())
+    }
+
+    #[inline]
+    pub async fn write_batch(
+        &mut self,
+        commands: SmallVec<[&mut Command; 10]>,
+        _retry_reasons: &[RetryReason],
+    ) -> Result<()> {
+        for command in commands {
+            self.write(command).await?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub async fn read(&mut self) -> Option<Result<RespBuf>> {
+        match self.replies.pop_front() {
+            Some(reply) => Some(Ok(reply)),
+            // like a real server, wait for the next command
+            None => futures_util::future::pending().await,
+        }
+    }
+
+    #[inline]
+    pub async fn reconnect(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    pub(crate) fn version(&self) -> Option<Version> {
+        self.behavior.server_version
+    }
+
+    pub(crate) fn tag(&self) -> &str {
+        "mock"
+    }
+
+    fn process(&mut self, command: &Command) -> Value {
+        let name = command.name.to_ascii_uppercase();
+
+        match (name.as_str(), &mut self.transaction) {
+            ("MULTI", Some(_)) => err("MULTI calls can not be nested"),
+            ("MULTI", None) => {
+                self.transaction = Some(Vec::new());
+                ok()
+            }
+            ("EXEC", None) => err("EXEC without MULTI"),
+            ("EXEC", Some(_)) => {
+                let commands = self.transaction.take().unwrap_or_default();
+                Value::Array(
+                    commands
+                        .iter()
+                        .map(|command| self.execute(command))
+                        .collect(),
+                )
+            }
+            ("DISCARD", None) => err("DISCARD without MULTI"),
+            ("DISCARD", Some(_)) => {
+                self.transaction = None;
+                ok()
+            }
+            (_, Some(commands)) => {
+                commands.push(command.clone());
+                Value::SimpleString("QUEUED".to_owned())
+            }
+            (_, None) => self.execute(command),
+        }
+    }
+
+    fn execute(&mut self, command: &Command) -> Value {
+        let name = command.name.to_ascii_uppercase();
+
+        if let Some(reply) = self
+            .behavior
+            .replies
+            .get_mut(&name)
+            .and_then(|replies| replies.pop_front())
+        {
+            return reply;
+        }
+
+        let args = command
+            .args
+            .iter()
+            .map(CommandArg::to_vec)
+            .collect::<Vec<_>>();
+        self.execute_in_memory(&name, &args).unwrap_or_else(|e| e)
+    }
+
+    fn execute_in_memory(&mut self, name: &str, args: &[Vec<u8>]) -> MockResult {
+        match name {
+            // connection & server
+            "PING" => {
+                check_arity(name, args, 0, Some(1))?;
+                Ok(args
+                    .first()
+                    .map(|message| bulk(message))
+                    .unwrap_or_else(|| Value::SimpleString("PONG".to_owned())))
+            }
+            "ECHO" => {
+                check_arity(name, args, 1, Some(1))?;
+                Ok(bulk(&args[0]))
+            }
+            "SELECT" | "WATCH" | "UNWATCH" => Ok(ok()),
+            "CLIENT" => match args.first().map(|arg| arg.to_ascii_uppercase()).as_deref() {
+                Some(b"REPLY") => {
+                    check_arity(name, args, 2, Some(2))?;
+                    Ok(ok())
+                }
+                _ => Err(err("unknown CLIENT subcommand")),
+            },
+            "FLUSHDB" | "FLUSHALL" => {
+                self.keyspace.clear();
+                Ok(ok())
+            }
+            "DBSIZE" => Ok(Value::Integer(self.keyspace.len() as i64)),
+            // generic
+            "DEL" | "UNLINK" => {
+                check_arity(name, args, 1, None)?;
+                Ok(Value::Integer(
+                    args.iter()
+                        .filter(|key| self.keyspace.remove(key.as_slice()).is_some())
+                        .count() as i64,
+                ))
+            }
+            "EXISTS" => {
+                check_arity(name, args, 1, None)?;
+                Ok(Value::Integer(
+                    args.iter()
+                        .filter(|key| self.keyspace.contains_key(key.as_slice()))
+                        .count() as i64,
+                ))
+            }
+            "TYPE" => {
+                check_arity(name, args, 1, Some(1))?;
+                Ok(Value::SimpleString(
+                    self.keyspace
+                        .get(&args[0])
+                        .map(MockValue::type_name)
+                        .unwrap_or("none")
+                        .to_owned(),
+                ))
+            }
+            // strings
+            "GET" => {
+                check_arity(name, args, 1, Some(1))?;
+                Ok(self
+                    .get_string(&args[0])?
+                    .map(|v| bulk(v))
+                    .unwrap_or(Value::Nil))
+            }
+            "GETDEL" => {
+                check_arity(name, args, 1, Some(1))?;
+                let value = self.get_string(&args[0])?.map(|v| bulk(v));
+                if value.is_some() {
+                    self.keyspace.remove(&args[0]);
+                }
+                Ok(value.unwrap_or(Value::Nil))
+            }
+            "SET" => self.set(args),
+            "MGET" => {
+                check_arity(name, args, 1, None)?;
+                Ok(Value::Array(
+                    args.iter()
+                        .map(|key| match self.keyspace.get(key) {
+                            Some(MockValue::String(v)) => bulk(v),
+                            _ => Value::Nil,
+                        })
+                        .collect(),
+                ))
+            }
+            "MSET" => {
+                check_arity(name, args, 2, None)?;
+                let pairs = args.chunks_exact(2);
+                if !pairs.remainder().is_empty() {
+                    return Err(wrong_arity(name));
+                }
+                for pair in pairs {
+                    self.keyspace
+                        .insert(pair[0].clone(), MockValue::String(pair[1].clone()));
+                }
+                Ok(ok())
+            }
+            "APPEND" => {
+                check_arity(name, args, 2, Some(2))?;
+                let value = self.get_or_insert_string(&args[0])?;
+                value.extend_from_slice(&args[1]);
+                Ok(Value::Integer(value.len() as i64))
+            }
+            "STRLEN" => {
+                check_arity(name, args, 1, Some(1))?;
+                Ok(Value::Integer(
+                    self.get_string(&args[0])?.map(Vec::len).unwrap_or(0) as i64,
+                ))
+            }
+            "INCR" => {
+                check_arity(name, args, 1, Some(1))?;
+                self.incr_by(&args[0], 1)
+            }
+            "DECR" => {
+                check_arity(name, args, 1, Some(1))?;
+                self.incr_by(&args[0], -1)
+            }
+            "INCRBY" => {
+                check_arity(name, args, 2, Some(2))?;
+                self.incr_by(&args[0], parse_integer(&args[1])?)
+            }
+            "DECRBY" => {
+                check_arity(name, args, 2, Some(2))?;
+                self.incr_by(&args[0], -parse_integer(&args[1])?)
+            }
+            // hashes
+            "HSET" => {
+                check_arity(name, args, 3, None)?;
+                let pairs = args[1..].chunks_exact(2);
+                if !pairs.remainder().is_empty() {
+                    return Err(wrong_arity(name));
+                }
+                let hash = self.get_or_insert_hash(&args[0])?;
+                Ok(Value::Integer(
+                    pairs
+                        .filter(|pair| hash.insert(pair[0].clone(), pair[1].clone()).is_none())
+                        .count() as i64,
+                ))
+            }
+            "HGET" => {
+                check_arity(name, args, 2, Some(2))?;
+                Ok(self
+                    .get_hash(&args[0])?
+                    .and_then(|hash| hash.get(&args[1]))
+                    .map(|v| bulk(v))
+                    .unwrap_or(Value::Nil))
+            }
+            "HMGET" => {
+                check_arity(name, args, 2, None)?;
+                let hash = self.get_hash(&args[0])?;
+                Ok(Value::Array(
+                    args[1..]
+                        .iter()
+                        .map(|field| {
+                            hash.and_then(|hash| hash.get(field))
+                                .map(|v| bulk(v))
+                                .unwrap_or(Value::Nil)
+                        })
+                        .collect(),
+                ))
+            }
+            "HGETALL" => {
+                check_arity(name, args, 1, Some(1))?;
+                Ok(Value::Map(
+                    self.get_hash(&args[0])?
+                        .map(|hash| hash.iter().map(|(f, v)| (bulk(f), bulk(v))).collect())
+                        .unwrap_or_default(),
+                ))
+            }
+            "HKEYS" | "HVALS" => {
+                check_arity(name, args, 1, Some(1))?;
+                Ok(Value::Array(
+                    self.get_hash(&args[0])?
+                        .map(|hash| {
+                            hash.iter()
+                                .map(|(f, v)| if name == "HKEYS" { bulk(f) } else { bulk(v) })
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                ))
+            }
+            "HLEN" => {
+                check_arity(name, args, 1, Some(1))?;
+                Ok(Value::Integer(
+                    self.get_hash(&args[0])?.map(HashMap::len).unwrap_or(0) as i64,
+                ))
+            }
+            "HEXISTS" => {
+                check_arity(name, args, 2, Some(2))?;
+                Ok(Value::Integer(
+                    self.get_hash(&args[0])?
+                        .is_some_and(|hash| hash.contains_key(&args[1])) as i64,
+                ))
+            }
+            "HDEL" => {
+                check_arity(name, args, 2, None)?;
+                let removed = match self.get_hash_mut(&args[0])? {
+                    Some(hash) => args[1..]
+                        .iter()
+                        .filter(|field| hash.remove(field.as_slice()).is_some())
+                        .count(),
+                    None => 0,
+                };
+                self.remove_if_empty(&args[0]);
+                Ok(Value::Integer(removed as i64))
+            }
+            "HINCRBY" => {
+                check_arity(name, args, 3, Some(3))?;
+                let increment = parse_integer(&args[2])?;
+                let hash = self.get_or_insert_hash(&args[0])?;
+                let value = hash.entry(args[1].clone()).or_insert_with(|| b"0".to_vec());
+                let result = parse_integer(value)?
+                    .checked_add(increment)
+                    .ok_or_else(|| err("increment or decrement would overflow"))?;
+                *value = result.to_string().into_bytes();
+                Ok(Value::Integer(result))
+            }
+            // lists
+            "LPUSH" | "RPUSH" => {
+                check_arity(name, args, 2, None)?;
+                let list = self.get_or_insert_list(&args[0])?;
+                for element in &args[1..] {
+                    if name == "LPUSH" {
+                        list.push_front(element.clone());
+                    } else {
+                        list.push_back(element.clone());
+                    }
+                }
+                Ok(Value::Integer(list.len() as i64))
+            }
+            "LPOP" | "RPOP" => {
+                check_arity(name, args, 1, Some(2))?;
+                let count = args.get(1).map(|c| parse_integer(c)).transpose()?;
+                let Some(list) = self.get_list_mut(&args[0])? else {
+                    return Ok(Value::Nil);
+                };
+                let mut pop = || {
+                    if name == "LPOP" {
+                        list.pop_front()
+                    } else {
+                        list.pop_back()
+                    }
+                };
+                let result = match count {
+                    Some(count) => Value::Array(
+                        (0..count)
+                            .map_while(|_| pop())
+                            .map(Value::BulkString)
+                            .collect(),
+                    ),
+                    None => pop().map(Value::BulkString).unwrap_or(Value::Nil),
+                };
+                self.remove_if_empty(&args[0]);
+                Ok(result)
+            }
+            "LLEN" => {
+                check_arity(name, args, 1, Some(1))?;
+                Ok(Value::Integer(
+                    self.get_list(&args[0])?.map(VecDeque::len).unwrap_or(0) as i64,
+                ))
+            }
+            "LINDEX" => {
+                check_arity(name, args, 2, Some(2))?;
+                let index = parse_integer(&args[1])?;
+                Ok(self
+                    .get_list(&args[0])?
+                    .and_then(|list| {
+                        let index = if index < 0 {
+                            list.len() as i64 + index
+                        } else {
+                            index
+                        };
+                        usize::try_from(index).ok().and_then(|i| list.get(i))
+                    })
+                    .map(|v| bulk(v))
+                    .unwrap_or(Value::Nil))
+            }
+            "LRANGE" => {
+                check_arity(name, args, 3, Some(3))?;
+                let start = parse_integer(&args[1])?;
+                let stop = parse_integer(&args[2])?;
+                let Some(list) = self.get_list(&args[0])? else {
+                    return Ok(Value::Array(Vec::new()));
+                };
+                let len = list.len() as i64;
+                let start = if start < 0 {
+                    (len + start).max(0)
+                } else {
+                    start
+                };
+                let stop = if stop < 0 {
+                    len + stop
+                } else {
+                    stop.min(len - 1)
+                };
+                if start > stop || start >= len {
+                    return Ok(Value::Array(Vec::new()));
+                }
+                Ok(Value::Array(
+                    list.range(start as usize..=stop as usize)
+                        .map(|v| bulk(v))
+                        .collect(),
+                ))
+            }
+            // sets
+            "SADD" => {
+                check_arity(name, args, 2, None)?;
+                let set = self.get_or_insert_set(&args[0])?;
+                Ok(Value::Integer(
+                    args[1..]
+                        .iter()
+                        .filter(|member| set.insert(member.to_vec()))
+                        .count() as i64,
+                ))
+            }
+            "SREM" => {
+                check_arity(name, args, 2, None)?;
+                let removed = match self.get_set_mut(&args[0])? {
+                    Some(set) => args[1..]
+                        .iter()
+                        .filter(|member| set.remove(member.as_slice()))
+                        .count(),
+                    None => 0,
+                };
+                self.remove_if_empty(&args[0]);
+                Ok(Value::Integer(removed as i64))
+            }
+            "SMEMBERS" => {
+                check_arity(name, args, 1, Some(1))?;
+                Ok(Value::Set(
+                    self.get_set(&args[0])?
+                        .map(|set| set.iter().map(|m| bulk(m)).collect())
+                        .unwrap_or_default(),
+                ))
+            }
+            "SISMEMBER" => {
+                check_arity(name, args, 2, Some(2))?;
+                Ok(Value::Integer(
+                    self.get_set(&args[0])?
+                        .is_some_and(|set| set.contains(&args[1])) as i64,
+                ))
+            }
+            "SCARD" => {
+                check_arity(name, args, 1, Some(1))?;
+                Ok(Value::Integer(
+                    self.get_set(&args[0])?.map(HashSet::len).unwrap_or(0) as i64,
+                ))
+            }
+            _ => Err(err(&format!("unknown command '{name}'"))),
+        }
+    }
+
+    /// SET key value [NX | XX] [GET] [EX seconds | PX milliseconds | EXAT unix-time-seconds | PXAT unix-time-milliseconds | KEEPTTL]
+    ///
+    /// Expiration options are accepted but ignored.
+    fn set(&mut self, args: &[Vec<u8>]) -> MockResult {
+        check_arity("SET", args, 2, None)?;
+
+        let (mut nx, mut xx, mut get) = (false, false, false);
+        let mut options = args[2..].iter();
+        while let Some(option) = options.next() {
+            match option.to_ascii_uppercase().as_slice() {
+                b"NX" => nx = true,
+                b"XX" => xx = true,
+                b"GET" => get = true,
+                b"KEEPTTL" => (),
+                b"EX" | b"PX" | b"EXAT" | b"PXAT" => {
+                    options
+                        .next()
+                        .ok_or_else(|| err("syntax error"))
+                        .and_then(|v| parse_integer(v))?;
+                }
+                _ => return Err(err("syntax error")),
+            }
+        }
+
+        if nx && xx {
+            return Err(err("syntax error"));
+        }
+
+        let old_value = self.get_string(&args[0])?.map(|v| bulk(v));
+        let exists = self.keyspace.contains_key(&args[0]);
+        let reply = if get {
+            old_value.unwrap_or(Value::Nil)
+        } else {
+            ok()
+        };
+
+        if (nx && exists) || (xx && !exists) {
+            return Ok(if get { reply } else { Value::Nil });
+        }
+
+        self.keyspace
+            .insert(args[0].clone(), MockValue::String(args[1].clone()));
+        Ok(reply)
+    }
+
+    fn incr_by(&mut self, key: &[u8], increment: i64) -> MockResult {
+        let value = self.get_or_insert_string(key)?;
+        let current = if value.is_empty() {
+            0
+        } else {
+            parse_integer(value)?
+        };
+        let result = current
+            .checked_add(increment)
+            .ok_or_else(|| err("increment or decrement would overflow"))?;
+        *value = result.to_string().into_bytes();
+        Ok(Value::Integer(result))
+    }
+
+    fn remove_if_empty(&mut self, key: &[u8]) {
+        if self.keyspace.get(key).is_some_and(MockValue::is_empty) {
+            self.keyspace.remove(key);
+        }
+    }
+
+    typed_accessors!(
+        get_string,
+        _get_string_mut,
+        get_or_insert_string,
+        String,
+        Vec<u8>
+    );
+    typed_accessors!(
+        get_list,
+        get_list_mut,
+        get_or_insert_list,
+        List,
+        VecDeque<Vec<u8>>
+    );
+    typed_accessors!(
+        get_set,
+        get_set_mut,
+        get_or_insert_set,
+        Set,
+        HashSet<Vec<u8>>
+    );
+    typed_accessors!(
+        get_hash,
+        get_hash_mut,
+        get_or_insert_hash,
+        Hash,
+        HashMap<Vec<u8>, Vec<u8>>
+    );
+}
+
+/// Uppercase mode of a `CLIENT REPLY` command
+fn client_reply_mode(command: &Command) -> Option<Vec<u8>> {
+    if !command.name.eq_ignore_ascii_case("CLIENT") {
+        return None;
+    }
+
+    let mut args = command.args.into_iter();
+    match (args.next(), args.next()) {
+        (Some(subcommand), Some(mode)) if subcommand.eq_ignore_ascii_case(b"REPLY") => {
+            Some(mode.to_ascii_uppercase())
+        }
+        _ => None,
+    }
+}
+
+fn check_arity(
+    name: &str,
+    args: &[Vec<u8>],
+    min: usize,
+    max: Option<usize>,
+) -> std::result::Result<(), Value> {
+    if args.len() < min || max.is_some_and(|max| args.len() > max) {
+        Err(wrong_arity(name))
+    } else {
+        Ok(())
+    }
+}
+
+fn parse_integer(arg: &[u8]) -> std::result::Result<i64, Value> {
+    std::str::from_utf8(arg)
+        .ok()
+        .and_then(|s| s.parse::<i64>().ok())
+        .ok_or_else(|| err("value is not an integer or out of range"))
+}
+
+#[inline]
+fn bulk(value: &[u8]) -> Value {
+    Value::BulkString(value.to_vec())
+}
+
+#[inline]
+fn ok() -> Value {
+    Value::SimpleString("OK".to_owned())
+}
+
+fn err(description: &str) -> Value {
+    Value::Error(RedisError {
+        kind: RedisErrorKind::Err,
+        description: description.to_owned(),
+    })
+}
+
+fn wrong_arity(name: &str) -> Value {
+    err(&format!(
+        "wrong number of arguments for '{}' command",
+        name.to_ascii_lowercase()
+    ))
+}
+
+fn wrong_type() -> Value {
+    Value::Error(RedisError {
+        kind: RedisErrorKind::WrongType,
+        description: "Operation against a key holding the wrong kind of value".to_owned(),
+    })
+}
@@ -1,20 +1,44 @@
 mod async_executor_strategy;
+#[cfg(feature = "chaos")]
+mod chaos_connection;
 mod cluster_connection;
 mod command_info_manager;
 mod connection;
+mod connection_stats;
+mod deprecated_commands;
+mod message_scheduler;
+#[cfg(feature = "mock")]
+mod mock_connection;
 mod network_handler;
+mod node_health;
 mod reconnection_state;
+#[cfg(feature = "mock")]
+mod recording_connection;
+#[cfg(feature = "mock")]
+mod replay_connection;
 mod sentinel_connection;
 mod standalone_connection;
 mod util;
 mod version;
 
 pub(crate) use async_executor_strategy::*;
+#[cfg(feature = "chaos")]
+pub(crate) use chaos_connection::*;
 pub(crate) use cluster_connection::*;
 pub(crate) use command_info_manager::*;
 pub(crate) use connection::*;
+pub(crate) use connection_stats::*;
+pub(crate) use deprecated_commands::*;
+pub(crate) use message_scheduler::*;
+#[cfg(feature = "mock")]
+pub(crate) use mock_connection::*;
 pub(crate) use network_handler::*;
+pub(crate) use node_health::*;
 pub(crate) use reconnection_state::*;
+#[cfg(feature = "mock")]
+pub(crate) use recording_connection::*;
+#[cfg(feature = "mock")]
+pub(crate) use replay_connection::*;
 pub(crate) use sentinel_connection::*;
 pub(crate) use standalone_connection::*;
-pub(crate) use version::*;
+pub use version::*;
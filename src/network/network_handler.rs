@@ -1,20 +1,25 @@
 use super::util::RefPubSubMessage;
 use crate::{
-    client::{Commands, Config, Message},
+    client::{CommandJournal, Commands, Config, ConnectionEvent, Message},
     commands::InternalPubSubCommands,
+    network::command_since,
     resp::{cmd, Command, RespBuf},
-    spawn, timeout, Connection, Error, JoinHandle, ReconnectionState, Result, RetryReason,
+    sleep, spawn, timeout, Connection, Error, ErrorContext, JoinHandle, MessageScheduler,
+    ReconnectionState, Result, RetryReason, StatsRegistry, Version,
 };
 use futures_channel::{mpsc, oneshot};
-use futures_util::{select, FutureExt, SinkExt, StreamExt};
+use futures_util::{future, select, FutureExt, SinkExt, StreamExt};
 use log::{debug, error, info, log_enabled, trace, warn, Level};
 use smallvec::SmallVec;
 use std::{
     collections::{HashMap, VecDeque},
-    time::Duration,
+    sync::{Arc, RwLock},
+    time::{Duration, SystemTime},
 };
 use tokio::{sync::broadcast, time::Instant};
 
+pub(crate) type ConnectionEventSender = broadcast::Sender<ConnectionEvent>;
+pub(crate) type ConnectionEventReceiver = broadcast::Receiver<ConnectionEvent>;
 pub(crate) type MsgSender = mpsc::UnboundedSender<Message>;
 pub(crate) type MsgReceiver = mpsc::UnboundedReceiver<Message>;
 pub(crate) type ResultSender = oneshot::Sender<Result<RespBuf>>;
@@ -27,6 +32,32 @@ pub(crate) type PushSender = mpsc::UnboundedSender<Result<RespBuf>>;
 pub(crate) type PushReceiver = mpsc::UnboundedReceiver<Result<RespBuf>>;
 pub(crate) type ReconnectSender = broadcast::Sender<()>;
 pub(crate) type ReconnectReceiver = broadcast::Receiver<()>;
+/// Identity of the current connection, shared with clients
+pub(crate) type SharedConnectionIdentity = Arc<RwLock<ConnectionIdentity>>;
+
+/// Identity of the current connection, refreshed on reconnection
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ConnectionIdentity {
+    /// Version of the connected server
+    pub server_version: Option<Version>,
+    /// Connection id, as returned by [`CLIENT ID`](https://redis.io/commands/client-id/)
+    pub connection_id: Option<i64>,
+    /// `host:port` of the connected server
+    pub address: Option<String>,
+    /// `true` once connected to a cluster, see [`Config::auto_detect_cluster`]
+    pub cluster: bool,
+}
+
+impl ConnectionIdentity {
+    fn of(connection: &Connection) -> Self {
+        Self {
+            server_version: connection.version(),
+            connection_id: connection.id(),
+            address: connection.address(),
+            cluster: connection.is_cluster(),
+        }
+    }
+}
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum Status {
@@ -39,6 +70,53 @@ enum Status {
     LeavingMonitor,
 }
 
+/// Reply mode of the connection, driven by `CLIENT REPLY`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ReplyMode {
+    On,
+    Off,
+    /// the reply of the next command will be skipped
+    SkipNext,
+}
+
+impl ReplyMode {
+    /// Track the `CLIENT REPLY` state of the connection
+    /// and indicate if a reply is expected for `command`
+    pub(crate) fn expect_reply(&mut self, command: &Command) -> bool {
+        let reply_mode = if command.name == "CLIENT" {
+            let mut args = command.args.into_iter();
+            match (args.next(), args.next()) {
+                (Some(b"REPLY"), Some(b"OFF")) => Some(ReplyMode::Off),
+                (Some(b"REPLY"), Some(b"SKIP")) => Some(ReplyMode::SkipNext),
+                (Some(b"REPLY"), Some(b"ON")) => Some(ReplyMode::On),
+                _ => None,
+            }
+        } else {
+            None
+        };
+
+        match (*self, reply_mode) {
+            // CLIENT REPLY ON is always acknowledged
+            (_, Some(ReplyMode::On)) => {
+                *self = ReplyMode::On;
+                true
+            }
+            // CLIENT REPLY SKIP is ignored when replies are off
+            (ReplyMode::Off, Some(ReplyMode::SkipNext)) => false,
+            (_, Some(reply_mode)) => {
+                *self = reply_mode;
+                false
+            }
+            (ReplyMode::On, None) => true,
+            (ReplyMode::Off, None) => false,
+            (ReplyMode::SkipNext, None) => {
+                *self = ReplyMode::On;
+                false
+            }
+        }
+    }
+}
+
 #[derive(Clone, Copy)]
 enum SubscriptionType {
     Channel,
@@ -76,6 +154,22 @@ impl MessageToReceive {
     }
 }
 
+/// Shared state attached to the connection of a client,
+/// when established and when upgraded with [`Config::auto_detect_cluster`]
+struct ConnectionSetup {
+    event_sender: ConnectionEventSender,
+    stats: Arc<StatsRegistry>,
+    identity: SharedConnectionIdentity,
+}
+
+impl ConnectionSetup {
+    fn apply(&self, connection: &mut Connection) {
+        connection.set_event_sender(self.event_sender.clone());
+        connection.set_stats(self.stats.clone());
+        *self.identity.write().unwrap() = ConnectionIdentity::of(connection);
+    }
+}
+
 struct PendingSubscription {
     pub channel_or_pattern: Vec<u8>,
     pub subscription_type: SubscriptionType,
@@ -90,12 +184,13 @@ pub(crate) struct NetworkHandler {
     /// for retries
     msg_sender: MsgSender,
     msg_receiver: MsgReceiver,
+    message_scheduler: MessageScheduler,
     messages_to_send: VecDeque<MessageToSend>,
     messages_to_receive: VecDeque<MessageToReceive>,
     pending_subscriptions: VecDeque<PendingSubscription>,
     pending_unsubscriptions: VecDeque<HashMap<Vec<u8>, SubscriptionType>>,
     subscriptions: HashMap<Vec<u8>, (SubscriptionType, PubSubSender)>,
-    is_reply_on: bool,
+    reply_mode: ReplyMode,
     push_sender: Option<PushSender>,
     pending_replies: Option<Vec<RespBuf>>,
     reconnect_sender: ReconnectSender,
@@ -103,57 +198,245 @@ pub(crate) struct NetworkHandler {
     auto_remonitor: bool,
     tag: String,
     reconnection_state: ReconnectionState,
+    setup: ConnectionSetup,
+    strict_server_version: bool,
+    /// number of reconnections, see [`Error::SubscriptionInterrupted`]
+    epoch: u64,
+    journal: Option<CommandJournal>,
+    /// see [`Config::error_context`]
+    error_context: bool,
+    /// see [`Config::pub_sub_keepalive`]
+    pub_sub_keepalive: Option<Duration>,
+    /// time at which the last result has been read from the connection
+    last_read_at: Instant,
+    /// time at which a keepalive `PING` has been sent, if no result has been read since
+    keepalive_sent_at: Option<Instant>,
 }
 
 impl NetworkHandler {
-    pub async fn connect(config: Config) -> Result<(MsgSender, JoinHandle<()>, ReconnectSender)> {
-        // options
-        let auto_resubscribe = config.auto_resubscribe;
-        let auto_remonitor = config.auto_remonitor;
-        let reconnection_config = config.reconnection.clone();
-
-        let connection = Connection::connect(config).await?;
-        let (msg_sender, msg_receiver): (MsgSender, MsgReceiver) = mpsc::unbounded();
+    pub fn start(
+        connection: Connection,
+        config: Config,
+    ) -> (
+        MsgSender,
+        JoinHandle<()>,
+        ReconnectSender,
+        ConnectionEventSender,
+        SharedConnectionIdentity,
+        Arc<StatsRegistry>,
+        Option<CommandJournal>,
+    ) {
+        Self::spawn(Some(connection), config)
+    }
+
+    /// Start a network handler which connects on its first message
+    pub fn start_lazy(
+        config: Config,
+    ) -> (
+        MsgSender,
+        JoinHandle<()>,
+        ReconnectSender,
+        ConnectionEventSender,
+        SharedConnectionIdentity,
+        Arc<StatsRegistry>,
+        Option<CommandJournal>,
+    ) {
+        Self::spawn(None, config)
+    }
+
+    fn spawn(
+        connection: Option<Connection>,
+        config: Config,
+    ) -> (
+        MsgSender,
+        JoinHandle<()>,
+        ReconnectSender,
+        ConnectionEventSender,
+        SharedConnectionIdentity,
+        Arc<StatsRegistry>,
+        Option<CommandJournal>,
+    ) {
+        let journal = (config.command_journal_size > 0)
+            .then(|| CommandJournal::new(config.command_journal_size));
+
+        let (msg_sender, mut msg_receiver): (MsgSender, MsgReceiver) = mpsc::unbounded();
         let (reconnect_sender, _): (ReconnectSender, ReconnectReceiver) = broadcast::channel(32);
+        let (event_sender, _): (ConnectionEventSender, ConnectionEventReceiver) =
+            broadcast::channel(32);
+        let stats = Arc::new(StatsRegistry::new());
+        let identity: SharedConnectionIdentity =
+            Arc::new(RwLock::new(ConnectionIdentity::default()));
+
+        let setup = ConnectionSetup {
+            event_sender: event_sender.clone(),
+            stats: stats.clone(),
+            identity: identity.clone(),
+        };
+
+        // an established connection is set up before the client is returned
+        let connection = connection.map(|mut connection| {
+            setup.apply(&mut connection);
+            connection
+        });
+
+        let join_handle = {
+            let msg_sender = msg_sender.clone();
+            let reconnect_sender = reconnect_sender.clone();
+            let journal = journal.clone();
+
+            spawn(async move {
+                let (connection, first_msg) = match connection {
+                    Some(connection) => (connection, None),
+                    None => {
+                        let Some((mut connection, msg)) =
+                            Self::connect_lazily(&config, &mut msg_receiver).await
+                        else {
+                            return;
+                        };
+                        setup.apply(&mut connection);
+                        (connection, Some(msg))
+                    }
+                };
+
+                let mut network_handler = NetworkHandler::new(
+                    connection,
+                    &config,
+                    msg_sender,
+                    msg_receiver,
+                    reconnect_sender,
+                    setup,
+                    journal,
+                );
+
+                if first_msg.is_some() && !network_handler.try_handle_message(first_msg).await {
+                    return;
+                }
+
+                if let Err(e) = network_handler.network_loop().await {
+                    error!("[{}] network loop ended in error: {e}", network_handler.tag);
+                }
+            })
+        };
+
+        (
+            msg_sender,
+            join_handle,
+            reconnect_sender,
+            event_sender,
+            identity,
+            stats,
+            journal,
+        )
+    }
+
+    fn new(
+        connection: Connection,
+        config: &Config,
+        msg_sender: MsgSender,
+        msg_receiver: MsgReceiver,
+        reconnect_sender: ReconnectSender,
+        setup: ConnectionSetup,
+        journal: Option<CommandJournal>,
+    ) -> Self {
         let tag = connection.tag().to_owned();
 
-        let mut network_handler = NetworkHandler {
+        NetworkHandler {
             status: Status::Connected,
             connection,
-            msg_sender: msg_sender.clone(),
+            msg_sender,
             msg_receiver,
+            message_scheduler: MessageScheduler::new(config.max_in_flight_per_client),
             messages_to_send: VecDeque::new(),
             messages_to_receive: VecDeque::new(),
             pending_subscriptions: VecDeque::new(),
             pending_unsubscriptions: VecDeque::new(),
             subscriptions: HashMap::new(),
-            is_reply_on: true,
+            reply_mode: ReplyMode::On,
             push_sender: None,
             pending_replies: None,
-            reconnect_sender: reconnect_sender.clone(),
-            auto_resubscribe,
-            auto_remonitor,
+            reconnect_sender,
+            auto_resubscribe: config.auto_resubscribe,
+            auto_remonitor: config.auto_remonitor,
             tag,
-            reconnection_state: ReconnectionState::new(reconnection_config),
-        };
+            reconnection_state: ReconnectionState::new(
+                config.resilience.retries,
+                config.resilience.backoff,
+            ),
+            setup,
+            strict_server_version: config.strict_server_version,
+            epoch: 0,
+            journal,
+            error_context: config.error_context,
+            pub_sub_keepalive: config.pub_sub_keepalive,
+            last_read_at: Instant::now(),
+            keepalive_sent_at: None,
+        }
+    }
 
-        let join_handle = spawn(async move {
-            if let Err(e) = network_handler.network_loop().await {
-                error!("[{}] network loop ended in error: {e}", network_handler.tag);
-            }
-        });
+    /// Attach the node & the attempt number to a failure,
+    /// if enabled with [`Config::error_context`]
+    fn contextualized(&self, error: Error, attempts: usize) -> Error {
+        if self.error_context {
+            error.with_context(ErrorContext {
+                command: None,
+                node: self.connection.address(),
+                attempt: Some(attempts + 1),
+                elapsed: None,
+            })
+        } else {
+            error
+        }
+    }
 
-        Ok((msg_sender, join_handle, reconnect_sender))
+    /// Wait for the first message of a lazy client, then connect.
+    ///
+    /// On connection failure, the error is sent to the messages queued so far
+    /// and the connection is attempted again on the next message.
+    /// `None` if the channel is closed before a connection could be established.
+    async fn connect_lazily(
+        config: &Config,
+        msg_receiver: &mut MsgReceiver,
+    ) -> Option<(Connection, Message)> {
+        loop {
+            let msg = msg_receiver.next().await?;
+
+            match Connection::connect(config.clone()).await {
+                Ok(connection) => return Some((connection, msg)),
+                Err(e) => {
+                    error!("[lazy] Failed to connect: {e}");
+                    msg.commands.send_error("lazy", e.clone());
+                    while let Ok(msg) = msg_receiver.try_recv() {
+                        msg.commands.send_error("lazy", e.clone());
+                    }
+                }
+            }
+        }
     }
 
+    /// Connection reads are only ever polled here, never by the futures of the callers,
+    /// which can therefore be cancelled at any point without corrupting the connection:
+    /// their results are just discarded when received.
+    ///
+    /// [`Connection::read`] must be cancel-safe, since it is dropped whenever another branch
+    /// of the `select!` completes first: partially received frames stay buffered in the codec.
     async fn network_loop(&mut self) -> Result<()> {
         loop {
+            let keepalive_delay = self.keepalive_delay();
             select! {
                 msg = self.msg_receiver.next().fuse() => {
                     if !self.try_handle_message(msg).await { break; }
                 } ,
                 result = self.connection.read().fuse() => {
+                    self.last_read_at = Instant::now();
+                    self.keepalive_sent_at = None;
                     if !self.handle_result(result).await { break; }
+                    // replies may have released in-flight capacity for queued messages
+                    if !self.message_scheduler.is_empty() {
+                        self.schedule_messages().await;
+                    }
+                }
+                _ = wait_keepalive(keepalive_delay).fuse() => {
+                    if !self.keepalive().await { break; }
                 }
             }
         }
@@ -162,12 +445,52 @@ impl NetworkHandler {
         Ok(())
     }
 
+    /// Time left before the next keepalive action on a subscribed connection,
+    /// `None` if keepalive is disabled or not applicable
+    fn keepalive_delay(&self) -> Option<Duration> {
+        let pub_sub_keepalive = self.pub_sub_keepalive?;
+        if self.status != Status::Subscribed {
+            return None;
+        }
+
+        let deadline = self.keepalive_sent_at.unwrap_or(self.last_read_at) + pub_sub_keepalive;
+        Some(deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Send a keepalive `PING` on a quiet subscribed connection,
+    /// or reconnect if the previous one has not been answered
+    async fn keepalive(&mut self) -> bool {
+        if self.keepalive_sent_at.take().is_some() {
+            warn!(
+                "[{}] No reply to keepalive PING, reconnecting subscribed connection",
+                self.tag
+            );
+            let reconnected = self.reconnect().await;
+            self.last_read_at = Instant::now();
+            return reconnected;
+        }
+
+        debug!("[{}] Sending keepalive PING", self.tag);
+        self.keepalive_sent_at = Some(Instant::now());
+        self.messages_to_send
+            .push_back(MessageToSend::new(Message::single_forget(
+                cmd("PING"),
+                false,
+            )));
+        self.send_messages().await;
+        true
+    }
+
     async fn try_handle_message(&mut self, mut msg: Option<Message>) -> bool {
         let is_channel_closed: bool;
 
         loop {
             if let Some(msg) = msg {
-                self.handle_message(msg).await;
+                if let Err(e) = self.check_server_version(&msg) {
+                    msg.commands.send_error(&self.tag, e);
+                } else {
+                    self.message_scheduler.push(msg);
+                }
             } else {
                 is_channel_closed = true;
                 break;
@@ -183,11 +506,43 @@ impl NetworkHandler {
             }
         }
 
+        self.schedule_messages().await;
+
+        !is_channel_closed
+    }
+
+    /// In strict mode, reject messages containing commands introduced after the server version
+    fn check_server_version(&self, msg: &Message) -> Result<()> {
+        if !self.strict_server_version {
+            return Ok(());
+        }
+
+        let Some(server_version) = self.setup.identity.read().unwrap().server_version else {
+            return Ok(());
+        };
+
+        for command in &msg.commands {
+            if let Some(since) = command_since(command) {
+                if since > server_version {
+                    return Err(Error::UnsupportedByServer {
+                        command: command.name.to_owned(),
+                        since,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn schedule_messages(&mut self) {
+        while let Some(msg) = self.message_scheduler.pop() {
+            self.handle_message(msg).await;
+        }
+
         if self.status != Status::Disconnected {
             self.send_messages().await
         }
-
-        !is_channel_closed
     }
 
     async fn handle_message(&mut self, mut msg: Message) {
@@ -341,19 +696,13 @@ impl NetworkHandler {
             let mut num_commands_to_receive: usize = 0;
 
             for command in commands.into_iter() {
-                if command.name == "CLIENT" {
-                    let mut args = command.args.into_iter();
-
-                    match (args.next(), args.next()) {
-                        (Some(b"REPLY"), Some(b"OFF")) => self.is_reply_on = false,
-                        (Some(b"REPLY"), Some(b"SKIP")) => self.is_reply_on = false,
-                        (Some(b"REPLY"), Some(b"ON")) => self.is_reply_on = true,
-                        _ => (),
-                    }
+                let expect_reply = self.reply_mode.expect_reply(command);
+                if expect_reply {
+                    num_commands_to_receive += 1;
                 }
 
-                if self.is_reply_on {
-                    num_commands_to_receive += 1;
+                if let Some(journal) = &self.journal {
+                    journal.record_sent(command, expect_reply);
                 }
 
                 commands_to_write.push(command);
@@ -373,11 +722,15 @@ impl NetworkHandler {
             .await
         {
             error!("[{}] Error while writing batch: {e}", self.tag);
+            if let Some(journal) = &self.journal {
+                journal.fail_pending(&e);
+            }
 
             let mut idx: usize = 0;
             while let Some(msg) = self.messages_to_send.pop_front() {
                 if commands_to_receive[idx] > 0 {
-                    msg.message.commands.send_error(&self.tag, e.clone());
+                    let e = self.contextualized(e.clone(), msg.attempts);
+                    msg.message.commands.send_error(&self.tag, e);
                 }
                 idx += 1;
             }
@@ -474,6 +827,10 @@ impl NetworkHandler {
     }
 
     fn receive_result(&mut self, result: Result<RespBuf>) {
+        if let Some(journal) = &self.journal {
+            journal.record_reply(&result);
+        }
+
         match self.messages_to_receive.front_mut() {
             Some(message_to_receive) => {
                 if message_to_receive.num_commands == 1 || result.is_err() {
@@ -509,34 +866,37 @@ impl NetworkHandler {
                                 self.tag,
                                 message_to_receive.message
                             );
+                            // the reply is consumed even if the caller has gone away
+                            // (e.g. its future lost a `select!`), so that the following
+                            // replies are still delivered to their own callers
+                            let id = message_to_receive.message.id;
+                            // followers are released in the order of the replies,
+                            // before the caller of any following command
+                            if let Some(coalescing_guard) =
+                                message_to_receive.message.coalescing_guard.take()
+                            {
+                                coalescing_guard.complete(&result);
+                            }
                             match message_to_receive.message.commands {
                                 Commands::Single(_, Some(result_sender)) => {
-                                    if let Err(e) = result_sender.send(result) {
-                                        warn!("[{}] Cannot send value to caller because receiver is not there anymore: {e:?}", self.tag);
+                                    if result_sender.send(result).is_err() {
+                                        debug!("[{}] Reply of message #{id} discarded, its caller is gone", self.tag);
                                     }
                                 }
-                                Commands::Batch(_, results_sender) => match result {
-                                    Ok(resp_buf) => {
-                                        let pending_replies = self.pending_replies.take();
-
-                                        if let Some(mut pending_replies) = pending_replies {
+                                Commands::Batch(_, results_sender) => {
+                                    let results = match result {
+                                        Ok(resp_buf) => {
+                                            let mut pending_replies =
+                                                self.pending_replies.take().unwrap_or_default();
                                             pending_replies.push(resp_buf);
-                                            if let Err(e) = results_sender.send(Ok(pending_replies))
-                                            {
-                                                warn!("[{}] Cannot send value to caller because receiver is not there anymore: {e:?}", self.tag);
-                                            }
-                                        } else if let Err(e) =
-                                            results_sender.send(Ok(vec![resp_buf]))
-                                        {
-                                            warn!("[{}] Cannot send value to caller because receiver is not there anymore: {e:?}", self.tag);
-                                        }
-                                    }
-                                    Err(e) => {
-                                        if let Err(e) = results_sender.send(Err(e)) {
-                                            warn!("[{}] Cannot send value to caller because receiver is not there anymore: {e:?}", self.tag);
+                                            Ok(pending_replies)
                                         }
+                                        Err(e) => Err(e),
+                                    };
+                                    if results_sender.send(results).is_err() {
+                                        debug!("[{}] Replies of message #{id} discarded, its caller is gone", self.tag);
                                     }
-                                },
+                                }
                                 Commands::None | Commands::Single(_, None) => {
                                     debug!("[{}] forget value {result:?}", self.tag)
                                     // fire & forget
@@ -658,7 +1018,8 @@ impl NetworkHandler {
                                 String::from_utf8_lossy(channel_or_pattern)
                             );
                         }
-                        Some(Ok(RespBuf::ok()))
+                        // the confirmation holds the number of active subscriptions
+                        Some(value)
                     }
                     RefPubSubMessage::Unsubscribe(channel_or_pattern)
                     | RefPubSubMessage::PUnsubscribe(channel_or_pattern)
@@ -693,7 +1054,7 @@ impl NetworkHandler {
                                     );
                                     return None;
                                 }
-                                Some(Ok(RespBuf::ok()))
+                                Some(value)
                             }
                         } else {
                             Some(value)
@@ -733,14 +1094,20 @@ impl NetworkHandler {
         debug!("[{}] reconnecting...", self.tag);
         let old_status = self.status;
         self.status = Status::Disconnected;
+        let disconnected_at = SystemTime::now();
+
+        if let Some(journal) = &self.journal {
+            journal.fail_pending(&Error::Client("Disconnected from server".to_string()));
+        }
 
         while let Some(message_to_receive) = self.messages_to_receive.front() {
             if !message_to_receive.message.retry_on_error {
                 if let Some(message_to_receive) = self.messages_to_receive.pop_front() {
-                    message_to_receive.message.commands.send_error(
-                        &self.tag,
+                    let e = self.contextualized(
                         Error::Client("Disconnected from server".to_string()),
+                        message_to_receive.attempts,
                     );
+                    message_to_receive.message.commands.send_error(&self.tag, e);
                 }
             } else {
                 break;
@@ -750,10 +1117,11 @@ impl NetworkHandler {
         while let Some(message_to_send) = self.messages_to_send.front() {
             if !message_to_send.message.retry_on_error {
                 if let Some(message_to_send) = self.messages_to_send.pop_front() {
-                    message_to_send.message.commands.send_error(
-                        &self.tag,
+                    let e = self.contextualized(
                         Error::Client("Disconnected from server".to_string()),
+                        message_to_send.attempts,
                     );
+                    message_to_send.message.commands.send_error(&self.tag, e);
                 }
             } else {
                 break;
@@ -782,13 +1150,21 @@ impl NetworkHandler {
             } else {
                 warn!("[{}] Max reconnection attempts reached", self.tag);
                 while let Some(message_to_receive) = self.messages_to_receive.pop_front() {
-                    message_to_receive.message.commands.send_error(
-                        &self.tag,
+                    let e = self.contextualized(
                         Error::Client("Disconnected from server".to_string()),
+                        message_to_receive.attempts,
                     );
+                    message_to_receive.message.commands.send_error(&self.tag, e);
                 }
                 while let Some(message_to_send) = self.messages_to_send.pop_front() {
-                    message_to_send.message.commands.send_error(
+                    let e = self.contextualized(
+                        Error::Client("Disconnected from server".to_string()),
+                        message_to_send.attempts,
+                    );
+                    message_to_send.message.commands.send_error(&self.tag, e);
+                }
+                for message in self.message_scheduler.drain() {
+                    message.commands.send_error(
                         &self.tag,
                         Error::Client("Disconnected from server".to_string()),
                     );
@@ -796,16 +1172,31 @@ impl NetworkHandler {
                 return false;
             }
 
+            let was_cluster = matches!(self.connection, Connection::Cluster(_));
             if let Err(e) = self.connection.reconnect().await {
                 error!("[{}] Failed to reconnect: {e:?}", self.tag);
                 continue;
             }
 
+            // a new connection always starts with replies on
+            self.reply_mode = ReplyMode::On;
+            if !was_cluster && matches!(self.connection, Connection::Cluster(_)) {
+                // upgraded to a cluster connection with `auto_detect_cluster`
+                self.setup.apply(&mut self.connection);
+                self.tag = self.connection.tag().to_owned();
+            } else {
+                // the server may have been upgraded or replaced
+                *self.setup.identity.write().unwrap() = ConnectionIdentity::of(&self.connection);
+            }
+
+            self.epoch += 1;
+
             if self.auto_resubscribe {
                 if let Err(e) = self.auto_resubscribe().await {
                     error!("[{}] Failed to reconnect: {e:?}", self.tag);
                     continue;
                 }
+                self.notify_subscription_interruption(disconnected_at);
             }
 
             if self.auto_remonitor {
@@ -825,7 +1216,7 @@ impl NetworkHandler {
             while let Some(message_to_receive) = self.messages_to_receive.pop_back() {
                 self.messages_to_send.push_front(MessageToSend {
                     message: message_to_receive.message,
-                    attempts: message_to_receive.attempts,
+                    attempts: message_to_receive.attempts + 1,
                 });
             }
 
@@ -926,6 +1317,26 @@ impl NetworkHandler {
         Ok(())
     }
 
+    /// Let each pub/sub stream know that messages may have been missed while disconnected
+    fn notify_subscription_interruption(&self, since: SystemTime) {
+        let resumed_at = SystemTime::now();
+        let mut notified_senders: Vec<&PubSubSender> = Vec::new();
+
+        for (_, sender) in self.subscriptions.values() {
+            // a stream subscribed to several channels or patterns is notified once
+            if notified_senders.iter().any(|s| s.same_receiver(sender)) {
+                continue;
+            }
+            notified_senders.push(sender);
+
+            let _result = sender.unbounded_send(Err(Error::SubscriptionInterrupted {
+                epoch: self.epoch,
+                since,
+                resumed_at,
+            }));
+        }
+    }
+
     async fn auto_remonitor(&mut self, old_status: Status) -> Result<()> {
         if let Status::Monitor | Status::EnteringMonitor = old_status {
             self.connection.send(&cmd("MONITOR")).await?;
@@ -934,3 +1345,11 @@ impl NetworkHandler {
         Ok(())
     }
 }
+
+/// Wait for `delay`, or forever if `None`
+async fn wait_keepalive(delay: Option<Duration>) {
+    match delay {
+        Some(delay) => sleep(delay).await,
+        None => future::pending().await,
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{client::ConnectionEvent, network::ConnectionEventSender};
+use log::{info, warn};
+use std::{collections::HashMap, time::Duration};
+use tokio::time::Instant;
+
+#[derive(Default)]
+struct NodeHealth {
+    consecutive_failures: usize,
+    unhealthy_until: Option<Instant>,
+}
+
+/// Circuit breaker of the nodes of a cluster
+///
+/// A node is marked as unhealthy after `failure_threshold` consecutive failures,
+/// and should not be routed to until the end of its cool-down period.
+/// Once the cool-down period is over, the node is tried again:
+/// a single failure marks it as unhealthy again, a success marks it as healthy.
+pub(crate) struct NodeHealthTracker {
+    failure_threshold: usize,
+    cooldown: Duration,
+    /// health of the nodes with at least one failure, by address
+    nodes: HashMap<(String, u16), NodeHealth>,
+    event_sender: Option<ConnectionEventSender>,
+    tag: String,
+}
+
+impl NodeHealthTracker {
+    pub fn new(failure_threshold: usize, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold,
+            cooldown,
+            nodes: HashMap::new(),
+            event_sender: None,
+            tag: String::new(),
+        }
+    }
+
+    pub fn set_event_sender(&mut self, event_sender: ConnectionEventSender) {
+        self.event_sender = Some(event_sender);
+    }
+
+    pub fn set_tag(&mut self, tag: &str) {
+        tag.clone_into(&mut self.tag);
+    }
+
+    /// `false` if the circuit breaker is disabled
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.failure_threshold > 0
+    }
+
+    /// `true` if the node at `address` must not be routed to
+    pub fn is_unhealthy(&self, address: &(String, u16)) -> bool {
+        self.nodes
+            .get(address)
+            .and_then(|health| health.unhealthy_until)
+            .is_some_and(|until| Instant::now() < until)
+    }
+
+    /// `true` if at least one node is unhealthy
+    pub fn has_unhealthy_nodes(&self) -> bool {
+        let now = Instant::now();
+        self.nodes
+            .values()
+            .any(|health| health.unhealthy_until.is_some_and(|until| now < until))
+    }
+
+    /// Record a failure of the node at `address`
+    ///
+    /// # Return
+    /// `true` if the node has just been marked as unhealthy
+    pub fn record_failure(&mut self, address: &(String, u16)) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+
+        let health = self.nodes.entry(address.clone()).or_default();
+        health.consecutive_failures += 1;
+
+        // once the cool-down period is over, a single failure is enough
+        let threshold_reached = health.consecutive_failures >= self.failure_threshold
+            || health.unhealthy_until.is_some();
+        if !threshold_reached {
+            return false;
+        }
+
+        health.unhealthy_until = Some(Instant::now() + self.cooldown);
+        let failures = health.consecutive_failures;
+
+        warn!(
+            "[{}] Cluster node {}:{} marked as unhealthy after {failures} consecutive failures, cool-down: {:?}",
+            self.tag, address.0, address.1, self.cooldown
+        );
+        self.send(ConnectionEvent::ClusterNodeUnhealthy {
+            address: address.clone(),
+            failures,
+            cooldown: self.cooldown,
+        });
+
+        true
+    }
+
+    /// Record a success of the node at `address`
+    pub fn record_success(&mut self, address: &(String, u16)) {
+        if self.nodes.is_empty() {
+            return;
+        }
+
+        if let Some(health) = self.nodes.remove(address) {
+            if health.unhealthy_until.is_some() {
+                info!(
+                    "[{}] Cluster node {}:{} is healthy again",
+                    self.tag, address.0, address.1
+                );
+                self.send(ConnectionEvent::ClusterNodeHealthy {
+                    address: address.clone(),
+                });
+            }
+        }
+    }
+
+    pub fn send(&self, event: ConnectionEvent) {
+        if let Some(event_sender) = &self.event_sender {
+            // no receiver is not an error
+            let _ = event_sender.send(event);
+        }
+    }
+}
@@ -1,16 +1,18 @@
-use crate::client::ReconnectionConfig;
+use crate::client::Backoff;
 use rand::{thread_rng, Rng};
 use std::cmp;
 
 pub(crate) struct ReconnectionState {
-    config: ReconnectionConfig,
+    retries: u32,
+    backoff: Backoff,
     attempts: u32,
 }
 
 impl ReconnectionState {
-    pub fn new(config: ReconnectionConfig) -> Self {
+    pub fn new(retries: u32, backoff: Backoff) -> Self {
         Self {
-            config,
+            retries,
+            backoff,
             attempts: 0,
         }
     }
@@ -22,49 +24,30 @@ impl ReconnectionState {
 
     /// Calculate the next delay, incrementing `attempts` in the process.
     pub fn next_delay(&mut self) -> Option<u64> {
-        match &self.config {
-            ReconnectionConfig::Constant {
-                delay,
-                max_attempts,
-                jitter,
-            } => {
-                self.attempts = match incr_with_max(self.attempts, *max_attempts) {
-                    Some(a) => a,
-                    None => return None,
-                };
+        self.attempts = incr_with_max(self.attempts, self.retries)?;
 
-                Some(add_jitter(*delay as u64, *jitter))
-            }
-            ReconnectionConfig::Linear {
+        match self.backoff {
+            Backoff::Constant { delay, jitter } => Some(add_jitter(delay as u64, jitter)),
+            Backoff::Linear {
                 max_delay,
-                max_attempts,
                 delay,
                 jitter,
             } => {
-                self.attempts = match incr_with_max(self.attempts, *max_attempts) {
-                    Some(a) => a,
-                    None => return None,
-                };
-                let delay = (*delay as u64).saturating_mul(self.attempts as u64);
+                let delay = (delay as u64).saturating_mul(self.attempts as u64);
 
-                Some(cmp::min(*max_delay as u64, add_jitter(delay, *jitter)))
+                Some(cmp::min(max_delay as u64, add_jitter(delay, jitter)))
             }
-            ReconnectionConfig::Exponential {
+            Backoff::Exponential {
                 min_delay,
                 max_delay,
-                max_attempts,
                 multiplicative_factor,
                 jitter,
             } => {
-                self.attempts = match incr_with_max(self.attempts, *max_attempts) {
-                    Some(a) => a,
-                    None => return None,
-                };
-                let delay = (*multiplicative_factor as u64)
+                let delay = (multiplicative_factor as u64)
                     .saturating_pow(self.attempts - 1)
-                    .saturating_mul(*min_delay as u64);
+                    .saturating_mul(min_delay as u64);
 
-                Some(cmp::min(*max_delay as u64, add_jitter(delay, *jitter)))
+                Some(cmp::min(max_delay as u64, add_jitter(delay, jitter)))
             }
         }
     }
@@ -0,0 +1,102 @@
+use crate::{
+    resp::{Command, CommandEncoder, RespBuf},
+    Connection, Result, RetryReason, Version,
+};
+use bytes::{BufMut, BytesMut};
+use log::warn;
+use smallvec::SmallVec;
+use std::{fs::File, io::Write, path::Path};
+use tokio_util::codec::Encoder;
+
+/// Marker line preceding a command sent to the server in a recording file
+pub(crate) const REQUEST_MARKER: &[u8] = b">\r\n";
+/// Marker line preceding a frame received from the server in a recording file
+pub(crate) const RESPONSE_MARKER: &[u8] = b"<\r\n";
+
+/// Connection wrapper which records the RESP exchange with the server into a file
+///
+/// Frames are recorded in memory, so that the network task never blocks on the file,
+/// and written to the file when the connection is dropped.
+pub struct RecordingConnection {
+    inner: Box<Connection>,
+    file: File,
+    /// Frames recorded since the connection was established
+    recording: BytesMut,
+}
+
+impl RecordingConnection {
+    pub fn new(inner: Connection, path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self {
+            inner: Box::new(inner),
+            file: File::create(path)?,
+            recording: BytesMut::new(),
+        })
+    }
+
+    #[inline]
+    pub async fn write(&mut self, command: &Command) -> Result<()> {
+        self.record_command(command)?;
+        Box::pin(self.inner.write(command)).await
+    }
+
+    #[inline]
+    pub async fn write_batch(
+        &mut self,
+        commands: SmallVec<[&mut Command; 10]>,
+        retry_reasons: &[RetryReason],
+    ) -> Result<()> {
+        for command in &commands {
+            self.record_command(command)?;
+        }
+        Box::pin(self.inner.write_batch(commands, retry_reasons)).await
+    }
+
+    #[inline]
+    pub async fn read(&mut self) -> Option<Result<RespBuf>> {
+        let result = Box::pin(self.inner.read()).await;
+        if let Some(Ok(resp_buf)) = &result {
+            self.recording.put_slice(RESPONSE_MARKER);
+            self.recording.put_slice(resp_buf.as_bytes());
+        }
+        result
+    }
+
+    #[inline]
+    pub async fn reconnect(&mut self) -> Result<()> {
+        Box::pin(self.inner.reconnect()).await
+    }
+
+    pub(crate) fn id(&self) -> Option<i64> {
+        self.inner.id()
+    }
+
+    pub(crate) fn version(&self) -> Option<Version> {
+        self.inner.version()
+    }
+
+    pub(crate) fn address(&self) -> Option<String> {
+        self.inner.address()
+    }
+
+    pub(crate) fn is_cluster(&self) -> bool {
+        self.inner.is_cluster()
+    }
+
+    pub(crate) fn tag(&self) -> &str {
+        self.inner.tag()
+    }
+
+    fn record_command(&mut self, command: &Command) -> Result<()> {
+        self.recording.put_slice(REQUEST_MARKER);
+        CommandEncoder.encode(command, &mut self.recording)?;
+        Ok(())
+    }
+}
+
+impl Drop for RecordingConnection {
+    fn drop(&mut self) {
+        if let Err(e) = self.file.write_all(&self.recording) {
+            warn!("[{}] Cannot write the recording file: {e}", self.tag());
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{
+    network::{REQUEST_MARKER, RESPONSE_MARKER},
+    resp::{BufferDecoder, Command, CommandEncoder, RespBuf},
+    Error, Result, RetryReason,
+};
+use bytes::BytesMut;
+use smallvec::SmallVec;
+use std::{collections::VecDeque, path::Path};
+use tokio_util::codec::{Decoder, Encoder};
+
+enum Frame {
+    Request(RespBuf),
+    Response(RespBuf),
+}
+
+/// Connection which serves the responses of a recording file, without any server
+pub struct ReplayConnection {
+    frames: VecDeque<Frame>,
+    replies: VecDeque<RespBuf>,
+}
+
+impl ReplayConnection {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let mut buf = BytesMut::from(std::fs::read(path)?.as_slice());
+        let mut frames = VecDeque::new();
+        let mut decoder = BufferDecoder::default();
+        // recorded requests may be inline commands
+        decoder.accept_inline_replies();
+
+        while !buf.is_empty() {
+            let marker = buf.split_to(REQUEST_MARKER.len().min(buf.len()));
+            let Some(frame) = decoder.decode(&mut buf)? else {
+                return Err(Error::Client("Truncated recording".to_owned()));
+            };
+
+            match marker.as_ref() {
+                REQUEST_MARKER => frames.push_back(Frame::Request(frame)),
+                RESPONSE_MARKER => frames.push_back(Frame::Response(frame)),
+                _ => return Err(Error::Client("Malformed recording".to_owned())),
+            }
+        }
+
+        Ok(Self {
+            frames,
+            replies: VecDeque::new(),
+        })
+    }
+
+    #[inline]
+    pub async fn write(&mut self, command: &Command) -> Result<()> {
+        let mut buf = BytesMut::new();
+        CommandEncoder.encode(command, &mut buf)?;
+        let request = buf.freeze();
+
+        match self.frames.pop_front() {
+            Some(Frame::Request(expected)) if expected.as_bytes() == request.as_ref() => (),
+            Some(Frame::Request(expected)) => {
+                return Err(Error::Client(format!(
+                    "Replay mismatch: expected {expected}, got {}",
+                    RespBuf::new(request)
+                )))
+            }
+            Some(Frame::Response(_)) | None => {
+                return Err(Error::Client(format!(
+                    "Replay mismatch: unexpected command {}",
+                    RespBuf::new(request)
+                )))
+            }
+        }
+
+        // queue the responses recorded after this command
+        while let Some(Frame::Response(_)) = self.frames.front() {
+            if let Some(Frame::Response(response)) = self.frames.pop_front() {
+                self.replies.push_back(response);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub async fn write_batch(
+        &mut self,
+        commands: SmallVec<[&mut Command; 10]>,
+        _retry_reasons: &[RetryReason],
+    ) -> Result<()> {
+        for command in commands {
+            self.write(command).await?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub async fn read(&mut self) -> Option<Result<RespBuf>> {
+        match self.replies.pop_front() {
+            Some(reply) => Some(Ok(reply)),
+            // like a real server, wait for the next command
+            None => futures_util::future::pending().await,
+        }
+    }
+
+    #[inline]
+    pub async fn reconnect(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    pub(crate) fn tag(&self) -> &str {
+        "replay"
+    }
+}
@@ -2,15 +2,64 @@ use crate::{
     client::{Config, SentinelConfig},
     commands::{RoleResult, SentinelCommands, ServerCommands},
     resp::{Command, RespBuf},
-    sleep, Error, Result, RetryReason, StandaloneConnection,
+    sleep, Error, RedisError, RedisErrorKind, Result, RetryReason, StandaloneConnection,
+    StatsRegistry,
 };
-use log::debug;
+use log::{debug, warn};
 use smallvec::SmallVec;
+use std::{collections::HashMap, sync::Arc};
+
+/// Consecutive failures of the Sentinel instances, so that failing instances are tried last
+#[derive(Default)]
+pub(crate) struct SentinelHealth {
+    /// consecutive failures of the instances with at least one failure, by address
+    failures: HashMap<(String, u16), usize>,
+    /// last instance which has given the master address
+    last_good: Option<(String, u16)>,
+}
+
+impl SentinelHealth {
+    /// Order in which to try `instances`: by number of consecutive failures,
+    /// then in configuration order, the last good instance first if `prefer_last_good`
+    pub fn order<'a>(
+        &self,
+        instances: &'a [(String, u16)],
+        prefer_last_good: bool,
+    ) -> Vec<&'a (String, u16)> {
+        let mut ordered = instances.iter().collect::<Vec<_>>();
+        // stable sort
+        ordered.sort_by_key(|instance| {
+            let is_last_good = prefer_last_good && self.last_good.as_ref() == Some(*instance);
+            (
+                !is_last_good,
+                self.failures.get(*instance).copied().unwrap_or_default(),
+            )
+        });
+        ordered
+    }
+
+    /// Record a failure of `instance`
+    ///
+    /// # Return
+    /// The number of consecutive failures of `instance`
+    pub fn record_failure(&mut self, instance: &(String, u16)) -> usize {
+        let failures = self.failures.entry(instance.clone()).or_default();
+        *failures += 1;
+        *failures
+    }
+
+    pub fn record_success(&mut self, instance: &(String, u16)) {
+        self.failures.remove(instance);
+        self.last_good = Some(instance.clone());
+    }
+}
 
 pub struct SentinelConnection {
     sentinel_config: SentinelConfig,
     config: Config,
+    sentinel_health: SentinelHealth,
     pub inner_connection: StandaloneConnection,
+    stats: Option<Arc<StatsRegistry>>,
 }
 
 impl SentinelConnection {
@@ -32,17 +81,54 @@ impl SentinelConnection {
 
     #[inline]
     pub async fn read(&mut self) -> Option<Result<RespBuf>> {
-        self.inner_connection.read().await
+        let result = self.inner_connection.read().await;
+
+        if self.sentinel_config.stale_master_guard {
+            if let Some(Ok(resp_buf)) = &result {
+                if resp_buf.is_error()
+                    && matches!(
+                        resp_buf.to::<()>(),
+                        Err(Error::Redis(RedisError {
+                            kind: RedisErrorKind::Readonly,
+                            ..
+                        }))
+                    )
+                {
+                    warn!(
+                        "[{}] Master {} has been demoted to replica, discovering the new master",
+                        self.tag(),
+                        self.inner_connection.address()
+                    );
+                    // handled as a disconnection, to reconnect to a confirmed master
+                    return None;
+                }
+            }
+        }
+
+        result
     }
 
     #[inline]
     pub async fn reconnect(&mut self) -> Result<()> {
-        self.inner_connection =
-            Self::connect_to_sentinel(&self.sentinel_config, &self.config).await?;
+        self.inner_connection = Self::connect_to_sentinel(
+            &self.sentinel_config,
+            &self.config,
+            &mut self.sentinel_health,
+        )
+        .await?;
+        if let Some(stats) = &self.stats {
+            self.inner_connection.set_stats(stats);
+            self.inner_connection.record_reconnect();
+        }
 
         Ok(())
     }
 
+    pub(crate) fn set_stats(&mut self, stats: Arc<StatsRegistry>) {
+        self.inner_connection.set_stats(&stats);
+        self.stats = Some(stats);
+    }
+
     /// Follow `Redis service discovery via Sentinel` documentation
     /// #See <https://redis.io/docs/reference/sentinel-clients/#redis-service-discovery-via-sentinel>
     ///
@@ -53,21 +139,27 @@ impl SentinelConnection {
         sentinel_config: &SentinelConfig,
         config: &Config,
     ) -> Result<SentinelConnection> {
-        let inner_connection = Self::connect_to_sentinel(sentinel_config, config).await?;
+        let mut sentinel_health = SentinelHealth::default();
+        let inner_connection =
+            Self::connect_to_sentinel(sentinel_config, config, &mut sentinel_health).await?;
 
         Ok(SentinelConnection {
             sentinel_config: sentinel_config.clone(),
             config: config.clone(),
+            sentinel_health,
             inner_connection,
+            stats: None,
         })
     }
 
     async fn connect_to_sentinel(
         sentinel_config: &SentinelConfig,
         config: &Config,
+        sentinel_health: &mut SentinelHealth,
     ) -> Result<StandaloneConnection> {
         let mut restart = false;
         let mut unreachable_sentinel = true;
+        let mut master_error = None;
 
         let mut sentinel_node_config = config.clone();
         sentinel_node_config
@@ -76,28 +168,46 @@ impl SentinelConnection {
         sentinel_node_config
             .password
             .clone_from(&sentinel_config.password);
+        sentinel_node_config
+            .password_file
+            .clone_from(&sentinel_config.password_file);
 
         loop {
-            for sentinel_instance in &sentinel_config.instances {
+            let sentinel_instances = sentinel_health.order(
+                &sentinel_config.instances,
+                sentinel_config.prefer_last_good_sentinel,
+            );
+
+            for sentinel_instance in sentinel_instances {
                 // Step 1: connecting to Sentinel
                 let (host, port) = sentinel_instance;
 
-                let mut sentinel_connection =
-                    match StandaloneConnection::connect(host, *port, &sentinel_node_config).await {
-                        Ok(sentinel_connection) => sentinel_connection,
-                        Err(e) => {
-                            debug!("Cannot connect to Sentinel {}:{} : {}", *host, *port, e);
-                            continue;
-                        }
-                    };
+                let mut sentinel_connection = match StandaloneConnection::connect(
+                    host,
+                    *port,
+                    &sentinel_node_config,
+                )
+                .await
+                {
+                    Ok(sentinel_connection) => sentinel_connection,
+                    Err(e) => {
+                        let failures = sentinel_health.record_failure(sentinel_instance);
+                        debug!("Cannot connect to Sentinel {}:{} ({failures} consecutive failures): {}", *host, *port, e);
+                        continue;
+                    }
+                };
 
                 // Step 2: ask for master address
                 let (master_host, master_port) = match sentinel_connection
                     .sentinel_get_master_addr_by_name(sentinel_config.service_name.clone())
                     .await
                 {
-                    Ok(Some((master_host, master_port))) => (master_host, master_port),
+                    Ok(Some((master_host, master_port))) => {
+                        sentinel_health.record_success(sentinel_instance);
+                        (master_host, master_port)
+                    }
                     Ok(None) => {
+                        sentinel_health.record_failure(sentinel_instance);
                         debug!(
                             "Sentinel {}:{} does not know master `{}`",
                             *host, *port, sentinel_config.service_name
@@ -106,16 +216,32 @@ impl SentinelConnection {
                         continue;
                     }
                     Err(e) => {
+                        sentinel_health.record_failure(sentinel_instance);
                         debug!("Cannot execute command `SENTINEL get-master-addr-by-name` with Sentinel {}:{}: {}", *host, *port, e);
                         continue;
                     }
                 };
 
                 // Step 3: call the ROLE command in the target instance
-                let mut master_connection =
-                    StandaloneConnection::connect(&master_host, master_port, config).await?;
+                let result = async {
+                    let mut master_connection =
+                        StandaloneConnection::connect(&master_host, master_port, config).await?;
+                    let role: RoleResult = master_connection.role().await?;
+                    Ok::<_, Error>((master_connection, role))
+                }
+                .await;
 
-                let role: RoleResult = master_connection.role().await?;
+                let (master_connection, role) = match result {
+                    Ok(result) => result,
+                    Err(e) => {
+                        debug!(
+                            "Cannot check the role of master {master_host}:{master_port} given by Sentinel {}:{}: {e}",
+                            *host, *port
+                        );
+                        master_error = Some(e);
+                        continue;
+                    }
+                };
 
                 if let RoleResult::Master {
                     master_replication_offset: _,
@@ -124,6 +250,10 @@ impl SentinelConnection {
                 {
                     return Ok(master_connection);
                 } else {
+                    debug!(
+                        "Instance {master_host}:{master_port} given by Sentinel {}:{} is not a master, restarting discovery",
+                        *host, *port
+                    );
                     sleep(sentinel_config.wait_between_failures).await;
                     // restart from the beginning
                     restart = true;
@@ -138,7 +268,9 @@ impl SentinelConnection {
             }
         }
 
-        if unreachable_sentinel {
+        if let Some(e) = master_error {
+            Err(e)
+        } else if unreachable_sentinel {
             Err(Error::Sentinel(
                 "All Sentinel instances are unreachable".to_owned(),
             ))
@@ -1,21 +1,28 @@
 use crate::{
-    client::{Config, PreparedCommand},
+    client::{
+        AsyncTransport, ClusterConfig, Config, PreparedCommand, TransportReader, TransportWriter,
+    },
     commands::{
-        ClusterCommands, ConnectionCommands, HelloOptions, SentinelCommands, ServerCommands,
+        ClientInfoAttribute, ClusterCommands, ConnectionCommands, HelloOptions, InfoSection,
+        SentinelCommands, ServerCommands,
+    },
+    resp::{
+        io_slices, is_encoded_inline, write_all_streamed, BufferDecoder, Command, CommandEncoder,
+        LargeArg, LargeArgs, RespBuf,
     },
-    resp::{BufferDecoder, Command, CommandEncoder, RespBuf},
-    tcp_connect, Error, Future, Result, RetryReason, TcpStreamReader, TcpStreamWriter,
+    tcp_connect, timeout, ClusterConnection, DeprecatedCommands, Error, Future, NodeStatsProbe,
+    Result, RetryReason, StatsRegistry, TcpStreamReader, TcpStreamWriter, Version,
 };
 #[cfg(feature = "tls")]
 use crate::{tcp_tls_connect, TcpTlsStreamReader, TcpTlsStreamWriter};
 use bytes::BytesMut;
-use futures_util::{SinkExt, StreamExt};
-use log::{debug, log_enabled, Level};
+use futures_util::StreamExt;
+use log::{debug, info, log_enabled, warn, Level};
 use serde::de::DeserializeOwned;
 use smallvec::SmallVec;
-use std::future::IntoFuture;
-use tokio::io::AsyncWriteExt;
-use tokio_util::codec::{Encoder, FramedRead, FramedWrite};
+use std::{future::IntoFuture, io::IoSlice};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio_util::codec::{FramedRead, FramedWrite};
 
 pub(crate) enum Streams {
     Tcp(
@@ -27,15 +34,22 @@ pub(crate) enum Streams {
         FramedRead<TcpTlsStreamReader, BufferDecoder>,
         FramedWrite<TcpTlsStreamWriter, CommandEncoder>,
     ),
+    Transport(
+        FramedRead<TransportReader, BufferDecoder>,
+        FramedWrite<TransportWriter, CommandEncoder>,
+    ),
 }
 
 impl Streams {
     pub async fn connect(host: &str, port: u16, config: &Config) -> Result<Self> {
+        if let Some(transport) = &config.transport {
+            return Self::connect_transport(transport.as_ref(), host, port, config).await;
+        }
+
         #[cfg(feature = "tls")]
         if let Some(tls_config) = &config.tls_config {
-            let (reader, writer) =
-                tcp_tls_connect(host, port, tls_config, config.connect_timeout).await?;
-            let framed_read = FramedRead::new(reader, BufferDecoder);
+            let (reader, writer) = tcp_tls_connect(host, port, tls_config, config).await?;
+            let framed_read = FramedRead::new(reader, BufferDecoder::default());
             let framed_write = FramedWrite::new(writer, CommandEncoder);
             Ok(Streams::TcpTls(framed_read, framed_write))
         } else {
@@ -48,12 +62,46 @@ impl Streams {
 
     pub async fn connect_non_secure(host: &str, port: u16, config: &Config) -> Result<Self> {
         let (reader, writer) = tcp_connect(host, port, config).await?;
-        let framed_read = FramedRead::new(reader, BufferDecoder);
+        let framed_read = FramedRead::new(reader, BufferDecoder::default());
         let framed_write = FramedWrite::new(writer, CommandEncoder);
         Ok(Streams::Tcp(framed_read, framed_write))
     }
+
+    async fn connect_transport(
+        transport: &dyn AsyncTransport,
+        host: &str,
+        port: u16,
+        config: &Config,
+    ) -> Result<Self> {
+        debug!(
+            "Connecting to {host}:{port} through {transport:?} with timeout {:?}...",
+            config.resilience.connect_timeout
+        );
+        let (reader, writer) = timeout(
+            config.resilience.connect_timeout,
+            transport.connect(host, port, config),
+        )
+        .await??;
+        info!("Connected to {host}:{port}");
+        let framed_read = FramedRead::new(reader, BufferDecoder::default());
+        let framed_write = FramedWrite::new(writer, CommandEncoder);
+        Ok(Streams::Transport(framed_read, framed_write))
+    }
+
+    /// Let the decoder accept the inline replies of inline commands
+    fn accept_inline_replies(&mut self) {
+        match self {
+            Streams::Tcp(framed_read, _) => framed_read.decoder_mut().accept_inline_replies(),
+            #[cfg(feature = "tls")]
+            Streams::TcpTls(framed_read, _) => framed_read.decoder_mut().accept_inline_replies(),
+            Streams::Transport(framed_read, _) => framed_read.decoder_mut().accept_inline_replies(),
+        }
+    }
 }
 
+/// Maximum capacity of the encoding buffer kept between two batches
+const MAX_RETAINED_BUFFER_CAPACITY: usize = 64 * 1024;
+
 pub struct StandaloneConnection {
     host: String,
     port: u16,
@@ -61,7 +109,10 @@ pub struct StandaloneConnection {
     streams: Streams,
     buffer: BytesMut,
     version: String,
+    id: i64,
+    deprecated_commands: Option<DeprecatedCommands>,
     tag: String,
+    stats: Option<NodeStatsProbe>,
 }
 
 impl StandaloneConnection {
@@ -75,11 +126,14 @@ impl StandaloneConnection {
             streams,
             buffer: BytesMut::new(),
             version: String::new(),
+            id: 0,
+            deprecated_commands: None,
             tag: if config.connection_name.is_empty() {
                 format!("{}:{}", host, port)
             } else {
                 format!("{}:{}:{}", config.connection_name, host, port)
             },
+            stats: None,
         };
 
         connection.post_connect().await?;
@@ -91,41 +145,53 @@ impl StandaloneConnection {
         if log_enabled!(Level::Debug) {
             debug!("[{}] Sending {command:?}", self.tag);
         }
-        match &mut self.streams {
-            Streams::Tcp(_, framed_write) => framed_write.send(command).await,
-            #[cfg(feature = "tls")]
-            Streams::TcpTls(_, framed_write) => framed_write.send(command).await,
+
+        if let Some(deprecated_commands) = &mut self.deprecated_commands {
+            deprecated_commands.check(command, &self.tag);
         }
+
+        if is_encoded_inline(command) {
+            self.streams.accept_inline_replies();
+        }
+
+        self.buffer.clear();
+        let mut large_args = LargeArgs::new();
+        CommandEncoder.encode_vectored(command, &mut self.buffer, &mut large_args);
+        self.write_buffer(&large_args, [command]).await
     }
 
     pub async fn write_batch(
         &mut self,
-        commands: SmallVec<[&mut Command; 10]>,
+        mut commands: SmallVec<[&mut Command; 10]>,
         _retry_reasons: &[RetryReason],
     ) -> Result<()> {
-        self.buffer.clear();
-
-        let command_encoder = match &mut self.streams {
-            Streams::Tcp(_, framed_write) => framed_write.encoder_mut(),
-            #[cfg(feature = "tls")]
-            Streams::TcpTls(_, framed_write) => framed_write.encoder_mut(),
-        };
-
         #[cfg(debug_assertions)]
         let mut kill_connection = false;
 
-        for command in commands {
+        for command in &mut commands {
             if log_enabled!(Level::Debug) {
                 debug!("[{}] Sending {command:?}", self.tag);
             }
 
+            if let Some(deprecated_commands) = &mut self.deprecated_commands {
+                deprecated_commands.check(command, &self.tag);
+            }
+
+            if is_encoded_inline(command) {
+                self.streams.accept_inline_replies();
+            }
+
             #[cfg(debug_assertions)]
             if command.kill_connection_on_write > 0 {
                 kill_connection = true;
                 command.kill_connection_on_write -= 1;
             }
+        }
 
-            command_encoder.encode(command, &mut self.buffer)?;
+        self.buffer.clear();
+        let mut large_args = LargeArgs::new();
+        for command in &commands {
+            CommandEncoder.encode_vectored(command, &mut self.buffer, &mut large_args);
         }
 
         #[cfg(debug_assertions)]
@@ -140,12 +206,78 @@ impl StandaloneConnection {
                 .await?;
         }
 
-        match &mut self.streams {
-            Streams::Tcp(_, framed_write) => framed_write.get_mut().write_all(&self.buffer).await?,
-            #[cfg(feature = "tls")]
-            Streams::TcpTls(_, framed_write) => {
-                framed_write.get_mut().write_all(&self.buffer).await?
+        self.write_buffer(&large_args, commands.iter().map(|command| &**command))
+            .await
+    }
+
+    /// Write the encoding buffer to the stream,
+    /// interleaved with the large arguments of the encoded commands
+    async fn write_buffer<'c>(
+        &mut self,
+        large_args: &[(usize, LargeArg<'_>)],
+        commands: impl IntoIterator<Item = &'c Command>,
+    ) -> Result<()> {
+        if large_args
+            .iter()
+            .any(|(_, arg)| matches!(arg, LargeArg::Streamed(_)))
+        {
+            match &mut self.streams {
+                Streams::Tcp(_, framed_write) => {
+                    write_all_streamed(framed_write.get_mut(), &self.buffer, large_args).await?
+                }
+                #[cfg(feature = "tls")]
+                Streams::TcpTls(_, framed_write) => {
+                    write_all_streamed(framed_write.get_mut(), &self.buffer, large_args).await?
+                }
+                Streams::Transport(_, framed_write) => {
+                    write_all_streamed(framed_write.get_mut(), &self.buffer, large_args).await?
+                }
+            }
+        } else if large_args.is_empty() {
+            match &mut self.streams {
+                Streams::Tcp(_, framed_write) => {
+                    framed_write.get_mut().write_all(&self.buffer).await?
+                }
+                #[cfg(feature = "tls")]
+                Streams::TcpTls(_, framed_write) => {
+                    framed_write.get_mut().write_all(&self.buffer).await?
+                }
+                Streams::Transport(_, framed_write) => {
+                    framed_write.get_mut().write_all(&self.buffer).await?
+                }
             }
+        } else {
+            let mut slices = io_slices(&self.buffer, large_args);
+            match &mut self.streams {
+                Streams::Tcp(_, framed_write) => {
+                    write_all_vectored(framed_write.get_mut(), &mut slices).await?
+                }
+                #[cfg(feature = "tls")]
+                Streams::TcpTls(_, framed_write) => {
+                    write_all_vectored(framed_write.get_mut(), &mut slices).await?
+                }
+                Streams::Transport(_, framed_write) => {
+                    write_all_vectored(framed_write.get_mut(), &mut slices).await?
+                }
+            }
+        }
+
+        if let Some(stats) = &mut self.stats {
+            let num_bytes = self.buffer.len()
+                + large_args
+                    .iter()
+                    .map(|(_, arg)| match arg {
+                        LargeArg::Slice(slice) => slice.len(),
+                        LargeArg::Streamed(streamed_arg) => streamed_arg.len(),
+                    })
+                    .sum::<usize>();
+            stats.record_write(commands, num_bytes);
+        }
+
+        // the encoding buffer is reused across writes,
+        // unless a huge batch made it grow beyond a reasonable size
+        if self.buffer.capacity() > MAX_RETAINED_BUFFER_CAPACITY {
+            self.buffer = BytesMut::new();
         }
 
         Ok(())
@@ -156,6 +288,7 @@ impl StandaloneConnection {
             Streams::Tcp(framed_read, _) => framed_read.next().await,
             #[cfg(feature = "tls")]
             Streams::TcpTls(framed_read, _) => framed_read.next().await,
+            Streams::Transport(framed_read, _) => framed_read.next().await,
         } {
             if log_enabled!(Level::Debug) {
                 match &result {
@@ -163,6 +296,15 @@ impl StandaloneConnection {
                     Err(err) => debug!("[{}] Received result {err:?}", self.tag),
                 }
             }
+            if let Some(stats) = &mut self.stats {
+                match &result {
+                    Ok(resp_buf) => stats.record_reply(
+                        resp_buf,
+                        resp_buf.is_push_message() || resp_buf.is_monitor_message(),
+                    ),
+                    Err(_) => stats.record_error(),
+                }
+            }
             Some(result)
         } else {
             debug!("[{}] Socked is closed", self.tag);
@@ -172,6 +314,7 @@ impl StandaloneConnection {
 
     pub async fn reconnect(&mut self) -> Result<()> {
         self.streams = Streams::connect(&self.host, self.port, &self.config).await?;
+        self.record_reconnect();
         self.post_connect().await?;
 
         Ok(())
@@ -183,40 +326,152 @@ impl StandaloneConnection {
         // RESP3
         let mut hello_options = HelloOptions::new(3);
 
-        // authentication
-        if let Some(ref password) = self.config.password {
+        // authentication, the password file being read at each connection attempt
+        if let Some(password) = self.config.resolve_password()? {
             hello_options = hello_options.auth(
                 match &self.config.username {
                     Some(username) => username.clone(),
                     None => "default".to_owned(),
                 },
-                password.clone(),
+                password,
             );
         }
 
-        // connection name
-        if !self.config.connection_name.is_empty() {
-            hello_options = hello_options.set_name(self.config.connection_name.clone());
+        // connection name, with its metadata
+        let client_name = self.config.client_name();
+        if !client_name.is_empty() {
+            hello_options = hello_options.set_name(client_name);
         }
 
         let hello_result = self.hello(hello_options).await?;
         self.version = hello_result.version;
+        self.id = hello_result.id;
 
         // select database
         if self.config.database != 0 {
             self.select(self.config.database).await?;
         }
 
+        // library identification, for CLIENT LIST & CLIENT INFO
+        if Version::try_from(self.version.as_str())
+            .is_ok_and(|version| version >= Version::new(7, 2, 0))
+        {
+            if let Err(e) = self.set_lib_info().await {
+                debug!("[{}] Cannot set library info: {e}", self.tag);
+            }
+        }
+
+        if self.config.warn_deprecated_commands {
+            match DeprecatedCommands::fetch(self).await {
+                Ok(deprecated_commands) => self.deprecated_commands = Some(deprecated_commands),
+                Err(e) => warn!("[{}] Cannot retrieve deprecated commands: {e}", self.tag),
+            }
+        }
+
         Ok(())
     }
 
+    async fn set_lib_info(&mut self) -> Result<()> {
+        self.client_setinfo(ClientInfoAttribute::LibName, self.config.client_lib_name())
+            .await?;
+        self.client_setinfo(ClientInfoAttribute::LibVer, env!("CARGO_PKG_VERSION"))
+            .await
+    }
+
     pub fn get_version(&self) -> &str {
         &self.version
     }
 
+    pub fn get_id(&self) -> i64 {
+        self.id
+    }
+
+    /// `host:port` of the connected server
+    pub(crate) fn address(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+
     pub(crate) fn tag(&self) -> &str {
         &self.tag
     }
+
+    pub(crate) fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Halves of the connection stream, with the bytes received but not decoded yet,
+    /// to speak another protocol than request/reply once connected, e.g. the replication protocol
+    #[cfg(feature = "replication")]
+    pub(crate) fn into_raw_parts(self) -> (TransportReader, TransportWriter, BytesMut) {
+        match self.streams {
+            Streams::Tcp(framed_read, framed_write) => {
+                let parts = framed_read.into_parts();
+                (
+                    Box::new(parts.io),
+                    Box::new(framed_write.into_inner()),
+                    parts.read_buf,
+                )
+            }
+            #[cfg(feature = "tls")]
+            Streams::TcpTls(framed_read, framed_write) => {
+                let parts = framed_read.into_parts();
+                (
+                    Box::new(parts.io),
+                    Box::new(framed_write.into_inner()),
+                    parts.read_buf,
+                )
+            }
+            Streams::Transport(framed_read, framed_write) => {
+                let parts = framed_read.into_parts();
+                (parts.io, framed_write.into_inner(), parts.read_buf)
+            }
+        }
+    }
+
+    /// Record the counters of this connection in `registry`, if not already done
+    pub(crate) fn set_stats(&mut self, registry: &StatsRegistry) {
+        if self.stats.is_none() {
+            self.stats = Some(NodeStatsProbe::new(registry.node(&self.address())));
+        }
+    }
+
+    /// Connect to the cluster of the server, if `INFO cluster` reports that cluster mode is enabled,
+    /// see [`Config::auto_detect_cluster`]
+    ///
+    /// The server is the only seed node of the discovery of the cluster topology.
+    pub(crate) async fn connect_to_cluster(&mut self) -> Result<Option<ClusterConnection>> {
+        let info = match self.info(InfoSection::Cluster).await {
+            Ok(info) => info,
+            Err(e) => {
+                debug!("[{}] Cannot detect cluster mode: {e}", self.tag);
+                return Ok(None);
+            }
+        };
+
+        if !info.lines().any(|line| line.trim() == "cluster_enabled:1") {
+            return Ok(None);
+        }
+
+        info!(
+            "[{}] Cluster mode detected, upgrading to a cluster connection",
+            self.tag
+        );
+
+        let cluster_config = ClusterConfig {
+            nodes: vec![(self.host.clone(), self.port)],
+        };
+
+        ClusterConnection::connect(&cluster_config, &self.config)
+            .await
+            .map(Some)
+    }
+
+    /// Record a reconnection to the node, for connections replaced by a new one
+    pub(crate) fn record_reconnect(&mut self) {
+        if let Some(stats) = &mut self.stats {
+            stats.record_reconnect();
+        }
+    }
 }
 
 impl<'a, R> IntoFuture for PreparedCommand<'a, &'a mut StandaloneConnection, R>
@@ -243,3 +498,19 @@ impl<'a> ClusterCommands<'a> for &'a mut StandaloneConnection {}
 impl<'a> ConnectionCommands<'a> for &'a mut StandaloneConnection {}
 impl<'a> SentinelCommands<'a> for &'a mut StandaloneConnection {}
 impl<'a> ServerCommands<'a> for &'a mut StandaloneConnection {}
+
+/// Write all the slices to `writer`, without copying them into an intermediate buffer
+async fn write_all_vectored<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    mut slices: &mut [IoSlice<'_>],
+) -> Result<()> {
+    while !slices.is_empty() {
+        let written = writer.write_vectored(slices).await?;
+        if written == 0 {
+            return Err(std::io::Error::from(std::io::ErrorKind::WriteZero).into());
+        }
+        IoSlice::advance_slices(&mut slices, written);
+    }
+
+    Ok(())
+}
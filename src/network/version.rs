@@ -1,13 +1,34 @@
-use crate::Error;
+use crate::{resp::Command, Error};
+use std::fmt::{self, Display};
 
+/// Version of a Redis server
+///
+/// See [`Client::server_version`](crate::client::Client::server_version)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Version {
     pub major: u8,
-    #[allow(dead_code)]
     pub minor: u8,
-    #[allow(dead_code)]
     pub revision: u8,
 }
 
+impl Version {
+    /// Create a new version
+    #[must_use]
+    pub const fn new(major: u8, minor: u8, revision: u8) -> Self {
+        Self {
+            major,
+            minor,
+            revision,
+        }
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.revision)
+    }
+}
+
 impl TryFrom<&str> for Version {
     type Error = Error;
 
@@ -40,13 +61,129 @@ impl TryFrom<&str> for Version {
     }
 }
 
+/// Commands introduced after Redis 6.0, with the server version which introduced them
+const COMMANDS_SINCE: &[(&str, Version)] = &[
+    ("BLMOVE", Version::new(6, 2, 0)),
+    ("COPY", Version::new(6, 2, 0)),
+    ("FAILOVER", Version::new(6, 2, 0)),
+    ("GEOSEARCH", Version::new(6, 2, 0)),
+    ("GEOSEARCHSTORE", Version::new(6, 2, 0)),
+    ("GETDEL", Version::new(6, 2, 0)),
+    ("GETEX", Version::new(6, 2, 0)),
+    ("HRANDFIELD", Version::new(6, 2, 0)),
+    ("LMOVE", Version::new(6, 2, 0)),
+    ("RESET", Version::new(6, 2, 0)),
+    ("SMISMEMBER", Version::new(6, 2, 0)),
+    ("XAUTOCLAIM", Version::new(6, 2, 0)),
+    ("ZDIFF", Version::new(6, 2, 0)),
+    ("ZDIFFSTORE", Version::new(6, 2, 0)),
+    ("ZINTER", Version::new(6, 2, 0)),
+    ("ZRANDMEMBER", Version::new(6, 2, 0)),
+    ("ZRANGESTORE", Version::new(6, 2, 0)),
+    ("ZUNION", Version::new(6, 2, 0)),
+    ("BLMPOP", Version::new(7, 0, 0)),
+    ("BZMPOP", Version::new(7, 0, 0)),
+    ("EVAL_RO", Version::new(7, 0, 0)),
+    ("EVALSHA_RO", Version::new(7, 0, 0)),
+    ("EXPIRETIME", Version::new(7, 0, 0)),
+    ("FCALL", Version::new(7, 0, 0)),
+    ("FCALL_RO", Version::new(7, 0, 0)),
+    ("FUNCTION", Version::new(7, 0, 0)),
+    ("LMPOP", Version::new(7, 0, 0)),
+    ("PEXPIRETIME", Version::new(7, 0, 0)),
+    ("SINTERCARD", Version::new(7, 0, 0)),
+    ("SORT_RO", Version::new(7, 0, 0)),
+    ("SPUBLISH", Version::new(7, 0, 0)),
+    ("SSUBSCRIBE", Version::new(7, 0, 0)),
+    ("SUNSUBSCRIBE", Version::new(7, 0, 0)),
+    ("ZINTERCARD", Version::new(7, 0, 0)),
+    ("ZMPOP", Version::new(7, 0, 0)),
+    ("WAITAOF", Version::new(7, 2, 0)),
+    ("HEXPIRE", Version::new(7, 4, 0)),
+    ("HEXPIREAT", Version::new(7, 4, 0)),
+    ("HEXPIRETIME", Version::new(7, 4, 0)),
+    ("HPERSIST", Version::new(7, 4, 0)),
+    ("HPEXPIRE", Version::new(7, 4, 0)),
+    ("HPEXPIREAT", Version::new(7, 4, 0)),
+    ("HPEXPIRETIME", Version::new(7, 4, 0)),
+    ("HPTTL", Version::new(7, 4, 0)),
+    ("HTTL", Version::new(7, 4, 0)),
+    ("HGETDEL", Version::new(8, 0, 0)),
+    ("HGETEX", Version::new(8, 0, 0)),
+    ("HSETEX", Version::new(8, 0, 0)),
+    ("XACKDEL", Version::new(8, 2, 0)),
+    ("XDELEX", Version::new(8, 2, 0)),
+    ("VADD", Version::new(8, 0, 0)),
+    ("VCARD", Version::new(8, 0, 0)),
+    ("VDIM", Version::new(8, 0, 0)),
+    ("VEMB", Version::new(8, 0, 0)),
+    ("VGETATTR", Version::new(8, 0, 0)),
+    ("VREM", Version::new(8, 0, 0)),
+    ("VSETATTR", Version::new(8, 0, 0)),
+    ("VSIM", Version::new(8, 0, 0)),
+];
+
+/// Subcommands introduced after Redis 6.0, with the server version which introduced them
+const SUBCOMMANDS_SINCE: &[(&str, &str, Version)] = &[
+    ("ACL", "DRYRUN", Version::new(7, 0, 0)),
+    ("CLIENT", "INFO", Version::new(6, 2, 0)),
+    ("CLIENT", "TRACKINGINFO", Version::new(6, 2, 0)),
+    ("CLIENT", "UNPAUSE", Version::new(6, 2, 0)),
+    ("CLIENT", "NO-EVICT", Version::new(7, 0, 0)),
+    ("CLUSTER", "SHARDS", Version::new(7, 0, 0)),
+    ("CLUSTER", "MYSHARDID", Version::new(7, 2, 0)),
+    ("CLIENT", "NO-TOUCH", Version::new(7, 2, 0)),
+    ("CLIENT", "SETINFO", Version::new(7, 2, 0)),
+];
+
+/// Server version which introduced `command`, if the command is known to be more recent than Redis 6.0
+pub(crate) fn command_since(command: &Command) -> Option<Version> {
+    if let Some((_, since)) = COMMANDS_SINCE
+        .iter()
+        .find(|(name, _)| command.name.eq_ignore_ascii_case(name))
+    {
+        return Some(*since);
+    }
+
+    let subcommand = command.args.into_iter().next()?;
+    SUBCOMMANDS_SINCE
+        .iter()
+        .find(|(name, sub, _)| {
+            command.name.eq_ignore_ascii_case(name)
+                && subcommand.eq_ignore_ascii_case(sub.as_bytes())
+        })
+        .map(|(_, _, since)| *since)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Version;
+    use super::{command_since, Version};
+    use crate::resp::cmd;
 
     #[test]
     fn version() {
         let version: Version = "7.0.0".try_into().unwrap();
         assert_eq!((7, 0, 0), (version.major, version.minor, version.revision));
+        assert_eq!("7.0.0", version.to_string());
+        assert!(Version::new(6, 2, 14) < version);
+        assert!(Version::new(7, 2, 0) > version);
+    }
+
+    #[test]
+    fn since() {
+        assert_eq!(None, command_since(&cmd("GET").arg("key")));
+        assert_eq!(
+            Some(Version::new(6, 2, 0)),
+            command_since(&cmd("GETDEL").arg("key"))
+        );
+        assert_eq!(
+            Some(Version::new(7, 4, 0)),
+            command_since(&cmd("HEXPIRE").arg("key"))
+        );
+        assert_eq!(
+            Some(Version::new(7, 2, 0)),
+            command_since(&cmd("CLIENT").arg("NO-TOUCH").arg("ON"))
+        );
+        assert_eq!(None, command_since(&cmd("CLIENT").arg("ID")));
     }
 }
@@ -0,0 +1,314 @@
+use crate::{
+    client::{Client, JsonCodec},
+    commands::{
+        CallBuilder, GenericCommands, ScriptingCommands, SetCondition, SetExpiration,
+        StringCommands,
+    },
+    network::sleep,
+    resp::BulkString,
+    Error, Result,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    future::Future,
+    marker::PhantomData,
+    time::{Duration, Instant, SystemTime},
+};
+
+const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_LOCK_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Delete the lock key only if it is still owned by the caller
+const RELEASE_LOCK_SCRIPT: &str = r#"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('DEL', KEYS[1])
+end
+return 0
+"#;
+
+/// Codec used by a [`CacheAside`] to store values in Redis
+///
+/// Implement this trait to plug any serialization format (CBOR, MessagePack, ...).
+pub trait CacheCodec {
+    /// Serialize a value
+    ///
+    /// # Errors
+    /// Any serialization error
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>>;
+
+    /// Deserialize a value
+    ///
+    /// # Errors
+    /// Any deserialization error
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T>;
+}
+
+impl CacheCodec for JsonCodec {
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>> {
+        serde_json::to_vec(value).map_err(|e| Error::Client(e.to_string()))
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T> {
+        serde_json::from_slice(bytes).map_err(|e| Error::Client(e.to_string()))
+    }
+}
+
+/// Value stored with its soft expiration, in stale-while-revalidate mode
+#[derive(Serialize)]
+struct EntryRef<'a, T> {
+    fresh_until: u64,
+    value: &'a T,
+}
+
+#[derive(Deserialize)]
+struct Entry<T> {
+    fresh_until: u64,
+    value: T,
+}
+
+/// Implementation of the [cache-aside](https://redis.io/docs/manual/client-side-caching/) pattern.
+///
+/// [`get_or_compute`](CacheAside::get_or_compute) reads a value from the cache and,
+/// on a miss, computes it and stores it with a TTL.
+/// Concurrent misses on the same key are protected against stampedes by a lock key (`<key>:lock`):
+/// a single caller computes the value while the others wait for it to be cached.
+///
+/// In [stale-while-revalidate](CacheAside::stale_while_revalidate) mode,
+/// a value is stored with a soft TTL and a hard TTL:
+/// once the soft TTL has expired, the caller which acquires the lock recomputes the value
+/// while the other callers are served the stale value until the hard TTL expires.
+///
+/// Values are encoded in JSON by default, see [`with_codec`](CacheAside::with_codec) for other formats.
+///
+/// # Example
+/// ```
+/// use rustis::{client::Client, patterns::CacheAside, Result};
+/// use serde::{Deserialize, Serialize};
+/// use std::time::Duration;
+///
+/// #[derive(Debug, Serialize, Deserialize)]
+/// struct User {
+///     name: String,
+/// }
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let cache = CacheAside::<User>::new(Client::connect("127.0.0.1:6379").await?);
+///     cache.invalidate("user:12").await?;
+///
+///     let user = cache
+///         .get_or_compute("user:12", Duration::from_secs(60), || async {
+///             // e.g. load the user from a database
+///             Ok(User { name: "John".to_owned() })
+///         })
+///         .await?;
+///     assert_eq!("John", user.name);
+///
+///     Ok(())
+/// }
+/// ```
+pub struct CacheAside<T, C = JsonCodec> {
+    client: Client,
+    codec: C,
+    lock_timeout: Duration,
+    lock_poll_interval: Duration,
+    stale_ttl: Option<Duration>,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> CacheAside<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    /// Create a new cache, with values encoded in JSON
+    #[must_use]
+    pub fn new(client: Client) -> Self {
+        Self::with_codec(client, JsonCodec)
+    }
+}
+
+impl<T, C> CacheAside<T, C>
+where
+    T: Serialize + DeserializeOwned,
+    C: CacheCodec,
+{
+    /// Create a new cache, with values encoded with a custom [codec](CacheCodec)
+    #[must_use]
+    pub fn with_codec(client: Client, codec: C) -> Self {
+        Self {
+            client,
+            codec,
+            lock_timeout: DEFAULT_LOCK_TIMEOUT,
+            lock_poll_interval: DEFAULT_LOCK_POLL_INTERVAL,
+            stale_ttl: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Maximum time a value is expected to be computed (default 10s)
+    ///
+    /// Past this timeout, the lock expires and waiting callers compute the value themselves.
+    #[must_use]
+    pub fn lock_timeout(mut self, lock_timeout: Duration) -> Self {
+        self.lock_timeout = lock_timeout;
+        self
+    }
+
+    /// Enable the stale-while-revalidate mode
+    ///
+    /// The `ttl` given to [`get_or_compute`](CacheAside::get_or_compute) becomes the soft TTL,
+    /// after which the value is recomputed, and values are kept `stale_ttl` longer (hard TTL)
+    /// to be served while being recomputed.
+    ///
+    /// In this mode, values are stored along with their soft expiration,
+    /// and cannot be read by clients unaware of this format.
+    #[must_use]
+    pub fn stale_while_revalidate(mut self, stale_ttl: Duration) -> Self {
+        self.stale_ttl = Some(stale_ttl);
+        self
+    }
+
+    /// Get the value of `key`, or compute it with `compute` and cache it for `ttl`
+    /// if it is missing (or stale in stale-while-revalidate mode)
+    ///
+    /// # Errors
+    /// Any error returned by `compute` or by the cache
+    pub async fn get_or_compute<F, Fut>(&self, key: &str, ttl: Duration, compute: F) -> Result<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let lock_key = format!("{key}:lock");
+        let token = rand::random::<u64>().to_string();
+        let deadline = Instant::now() + self.lock_timeout;
+
+        loop {
+            let stale_value = match self.read(key).await? {
+                Some((value, true)) => return Ok(value),
+                Some((value, false)) => Some(value),
+                None => None,
+            };
+
+            let locked = self
+                .client
+                .set_with_options(
+                    &lock_key,
+                    &token,
+                    SetCondition::NX,
+                    SetExpiration::Px(self.lock_timeout.as_millis() as u64),
+                    false,
+                )
+                .await?;
+
+            if locked {
+                let result = self.compute_and_write(key, ttl, compute).await;
+                self.release_lock(&lock_key, &token).await?;
+                return result;
+            }
+
+            // another caller is computing the value
+            if let Some(stale_value) = stale_value {
+                return Ok(stale_value);
+            }
+
+            if Instant::now() >= deadline {
+                return self.compute_and_write(key, ttl, compute).await;
+            }
+
+            sleep(self.lock_poll_interval).await;
+        }
+    }
+
+    /// Get the value of `key`, even if stale, without computing it
+    pub async fn get(&self, key: &str) -> Result<Option<T>> {
+        Ok(self.read(key).await?.map(|(value, _)| value))
+    }
+
+    /// Get the value of `key` and remove it from the cache atomically,
+    /// with [`GETDEL`](https://redis.io/commands/getdel/)
+    pub async fn take(&self, key: &str) -> Result<Option<T>> {
+        let bytes: Option<BulkString> = self.client.getdel(key).await?;
+        bytes
+            .map(|bytes| self.decode(&bytes).map(|(value, _)| value))
+            .transpose()
+    }
+
+    /// Store `value` for `ttl`, replacing the cached value of `key`
+    pub async fn set(&self, key: &str, value: &T, ttl: Duration) -> Result<()> {
+        let (bytes, hard_ttl) = match self.stale_ttl {
+            Some(stale_ttl) => {
+                let entry = EntryRef {
+                    fresh_until: now_millis() + ttl.as_millis() as u64,
+                    value,
+                };
+                (self.codec.encode(&entry)?, ttl + stale_ttl)
+            }
+            None => (self.codec.encode(value)?, ttl),
+        };
+
+        self.client
+            .set_with_options(
+                key,
+                bytes,
+                SetCondition::None,
+                SetExpiration::Px(hard_ttl.as_millis() as u64),
+                false,
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Remove the cached value of `key`
+    ///
+    /// # Return
+    /// `false` if the key was not cached
+    pub async fn invalidate(&self, key: &str) -> Result<bool> {
+        let deleted: usize = self.client.del(key).await?;
+        Ok(deleted > 0)
+    }
+
+    /// Cached value with its freshness
+    async fn read(&self, key: &str) -> Result<Option<(T, bool)>> {
+        let bytes: Option<BulkString> = self.client.get(key).await?;
+        bytes.map(|bytes| self.decode(&bytes)).transpose()
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<(T, bool)> {
+        if self.stale_ttl.is_some() {
+            let entry: Entry<T> = self.codec.decode(bytes)?;
+            Ok((entry.value, now_millis() < entry.fresh_until))
+        } else {
+            Ok((self.codec.decode(bytes)?, true))
+        }
+    }
+
+    async fn compute_and_write<F, Fut>(&self, key: &str, ttl: Duration, compute: F) -> Result<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let value = compute().await?;
+        self.set(key, &value, ttl).await?;
+        Ok(value)
+    }
+
+    async fn release_lock(&self, lock_key: &str, token: &str) -> Result<()> {
+        let _released: usize = self
+            .client
+            .eval(
+                CallBuilder::script(RELEASE_LOCK_SCRIPT)
+                    .keys(lock_key)
+                    .args(token),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
@@ -0,0 +1,325 @@
+use crate::{
+    client::Client,
+    commands::{GenericCommands, ScanOptions, StringCommands},
+    network::sleep,
+    resp::BulkString,
+    Result,
+};
+use futures_util::{stream::BoxStream, Stream, StreamExt};
+use std::{
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+/// Keys warmed by a [`CacheWarmer`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WarmupKeys {
+    /// An explicit list of keys, warmed in order
+    List(Vec<String>),
+    /// The keys matching a glob-style pattern, as returned by [`SCAN`](https://redis.io/commands/scan/)
+    ///
+    /// `SCAN` may return a key more than once: such a key is warmed several times.
+    Pattern(String),
+}
+
+/// Command used by a [`CacheWarmer`] to access the keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WarmupMethod {
+    /// [`TOUCH`](https://redis.io/commands/touch/) the keys: their last access time is updated,
+    /// without transferring their values
+    Touch,
+    /// [`MGET`](https://redis.io/commands/mget/) the keys: their values are read and transferred,
+    /// which also loads them in the memory pages of the server.
+    ///
+    /// Only string keys are read, the other keys are reported as missing.
+    Get,
+}
+
+/// Progress of a [`Warmup`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WarmupProgress {
+    /// Number of keys accessed so far
+    pub processed_keys: usize,
+    /// Number of accessed keys which exist on the server
+    pub existing_keys: usize,
+    /// Total number of keys to access, `None` for a [`WarmupKeys::Pattern`]
+    pub total_keys: Option<usize>,
+    /// Time elapsed since the start of the warm-up
+    pub elapsed: Duration,
+}
+
+/// Handle to abort a [`Warmup`], possibly from another task
+#[derive(Debug, Clone, Default)]
+pub struct WarmupAbortHandle(Arc<AtomicBool>);
+
+impl WarmupAbortHandle {
+    /// Stop the warm-up before its next batch
+    pub fn abort(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// `true` if [`abort`](WarmupAbortHandle::abort) has been called
+    #[must_use]
+    pub fn is_aborted(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Running warm-up started by [`CacheWarmer::warm`]
+///
+/// A stream of the [progress](WarmupProgress) of the warm-up, after each batch of keys.
+/// The stream ends when all the keys have been accessed, when the warm-up is aborted,
+/// or after the first error.
+/// The warm-up stops when the stream is dropped.
+pub struct Warmup {
+    stream: BoxStream<'static, Result<WarmupProgress>>,
+    abort_handle: WarmupAbortHandle,
+}
+
+impl Warmup {
+    /// Handle to abort the warm-up
+    #[must_use]
+    pub fn abort_handle(&self) -> WarmupAbortHandle {
+        self.abort_handle.clone()
+    }
+}
+
+impl Stream for Warmup {
+    type Item = Result<WarmupProgress>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream.poll_next_unpin(cx)
+    }
+}
+
+/// Warm-up of the keys of a server before it takes traffic,
+/// typically a replica or a freshly failed-over master.
+///
+/// Keys are accessed by batches, at a limited rate so that the warm-up
+/// does not compete with the live traffic of the server.
+/// With the approximated LRU eviction of Redis, accessed keys are the last ones to be evicted.
+///
+/// `TOUCH` and `MGET` are read-only commands: a client connected to a replica can warm it up.
+/// A [`WarmupKeys::Pattern`] targets a standalone server, or a single node of a cluster.
+///
+/// # Example
+/// ```
+/// use futures_util::StreamExt;
+/// use rustis::{
+///     client::Client,
+///     commands::{FlushingMode, ServerCommands, StringCommands},
+///     patterns::{CacheWarmer, WarmupKeys, WarmupMethod},
+///     Result,
+/// };
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let client = Client::connect("127.0.0.1:6379").await?;
+///     client.flushdb(FlushingMode::Sync).await?;
+///     client.mset([("user:1", "alice"), ("user:2", "bob")]).await?;
+///
+///     let warmer = CacheWarmer::new(client)
+///         .method(WarmupMethod::Get)
+///         .batch_size(500)
+///         .max_keys_per_second(10_000);
+///     let mut warmup = warmer.warm(WarmupKeys::Pattern("user:*".to_owned()));
+///
+///     let mut last_progress = None;
+///     while let Some(progress) = warmup.next().await {
+///         last_progress = Some(progress?);
+///     }
+///     assert_eq!(Some(2), last_progress.map(|p| p.existing_keys));
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct CacheWarmer {
+    client: Client,
+    method: WarmupMethod,
+    batch_size: usize,
+    max_keys_per_second: Option<usize>,
+}
+
+impl CacheWarmer {
+    /// Create a new warmer touching the keys by batches of `100`, without rate limit
+    #[must_use]
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            method: WarmupMethod::Touch,
+            batch_size: 100,
+            max_keys_per_second: None,
+        }
+    }
+
+    /// Command used to access the keys (default [`WarmupMethod::Touch`])
+    #[must_use]
+    pub fn method(mut self, method: WarmupMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Number of keys accessed by each command (default `100`)
+    #[must_use]
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Maximum number of keys accessed per second (default unlimited)
+    #[must_use]
+    pub fn max_keys_per_second(mut self, max_keys_per_second: usize) -> Self {
+        self.max_keys_per_second = Some(max_keys_per_second.max(1));
+        self
+    }
+
+    /// Start the warm-up of `keys`
+    ///
+    /// Nothing happens until the returned [`Warmup`] is polled.
+    #[must_use]
+    pub fn warm(&self, keys: WarmupKeys) -> Warmup {
+        let abort_handle = WarmupAbortHandle::default();
+        let total_keys = match &keys {
+            WarmupKeys::List(keys) => Some(keys.len()),
+            WarmupKeys::Pattern(_) => None,
+        };
+        let source = match keys {
+            WarmupKeys::List(keys) => KeySource::List(keys.into_iter()),
+            WarmupKeys::Pattern(pattern) => KeySource::Scan {
+                pattern,
+                cursor: Some(0),
+            },
+        };
+        let state = WarmupState {
+            warmer: self.clone(),
+            source,
+            abort_handle: abort_handle.clone(),
+            progress: WarmupProgress {
+                processed_keys: 0,
+                existing_keys: 0,
+                total_keys,
+                elapsed: Duration::ZERO,
+            },
+            started_at: None,
+            failed: false,
+        };
+
+        let stream = futures_util::stream::unfold(state, |mut state| async move {
+            let result = state.next_batch().await.transpose()?;
+            Some((result, state))
+        })
+        .boxed();
+
+        Warmup {
+            stream,
+            abort_handle,
+        }
+    }
+
+    /// Access a batch of keys
+    ///
+    /// # Return
+    /// The number of existing keys of the batch
+    async fn access(&self, keys: Vec<String>) -> Result<usize> {
+        match self.method {
+            WarmupMethod::Touch => self.client.touch(keys).await,
+            WarmupMethod::Get => {
+                let values: Vec<Option<BulkString>> = self.client.mget(keys).await?;
+                Ok(values.iter().flatten().count())
+            }
+        }
+    }
+}
+
+enum KeySource {
+    List(std::vec::IntoIter<String>),
+    /// `cursor` is `None` once the scan is complete
+    Scan {
+        pattern: String,
+        cursor: Option<u64>,
+    },
+}
+
+struct WarmupState {
+    warmer: CacheWarmer,
+    source: KeySource,
+    abort_handle: WarmupAbortHandle,
+    progress: WarmupProgress,
+    started_at: Option<Instant>,
+    failed: bool,
+}
+
+impl WarmupState {
+    /// Warm the next batch of keys, `None` once the warm-up is over
+    async fn next_batch(&mut self) -> Result<Option<WarmupProgress>> {
+        if self.failed || self.abort_handle.is_aborted() {
+            return Ok(None);
+        }
+        let started_at = *self.started_at.get_or_insert_with(Instant::now);
+
+        let result = self.warm_batch(started_at).await;
+        self.failed = result.is_err();
+        result
+    }
+
+    async fn warm_batch(&mut self, started_at: Instant) -> Result<Option<WarmupProgress>> {
+        let keys = self.keys().await?;
+        if keys.is_empty() {
+            return Ok(None);
+        }
+
+        let num_keys = keys.len();
+        self.progress.existing_keys += self.warmer.access(keys).await?;
+        self.progress.processed_keys += num_keys;
+
+        if let Some(max_keys_per_second) = self.warmer.max_keys_per_second {
+            let target = Duration::from_secs_f64(
+                self.progress.processed_keys as f64 / max_keys_per_second as f64,
+            );
+            let elapsed = started_at.elapsed();
+            if target > elapsed {
+                sleep(target - elapsed).await;
+            }
+        }
+
+        self.progress.elapsed = started_at.elapsed();
+        Ok(Some(self.progress))
+    }
+
+    /// Next batch of keys, empty once all the keys have been returned
+    async fn keys(&mut self) -> Result<Vec<String>> {
+        let batch_size = self.warmer.batch_size;
+
+        match &mut self.source {
+            KeySource::List(keys) => Ok(keys.by_ref().take(batch_size).collect()),
+            KeySource::Scan { pattern, cursor } => {
+                while let Some(current_cursor) = *cursor {
+                    let (next_cursor, keys): (u64, Vec<String>) = self
+                        .warmer
+                        .client
+                        .scan(
+                            current_cursor,
+                            ScanOptions::default()
+                                .match_pattern(pattern.as_str())
+                                .count(batch_size),
+                        )
+                        .await?;
+                    *cursor = (next_cursor != 0).then_some(next_cursor);
+
+                    if !keys.is_empty() {
+                        return Ok(keys);
+                    }
+                }
+
+                Ok(Vec::new())
+            }
+        }
+    }
+}
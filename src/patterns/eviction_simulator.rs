@@ -0,0 +1,418 @@
+use crate::{
+    client::{BatchPreparedCommand, Client, Pipeline},
+    commands::{GenericCommands, MemoryUsageOptions, ScanOptions, ServerCommands},
+    Error, Result,
+};
+use std::{collections::HashSet, fmt, time::Duration};
+
+/// Number of keys inspected by each pipeline of a sampling
+const SAMPLE_BATCH_SIZE: usize = 100;
+
+/// Method used by an [`EvictionSimulator`] to pick the keys to inspect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeySampling {
+    /// Keys returned by [`RANDOMKEY`](https://redis.io/commands/randomkey/),
+    /// as the server does to pick eviction candidates.
+    ///
+    /// Keys returned more than once are inspected only once,
+    /// so that the sample can be smaller than requested.
+    RandomKey,
+    /// Keys returned by [`SCAN`](https://redis.io/commands/scan/), from the start of the keyspace
+    Scan,
+}
+
+/// Eviction policy simulated by [`EvictionSample::simulate`],
+/// see [`maxmemory-policy`](https://redis.io/docs/reference/eviction/)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// `allkeys-lru`: evict the least recently used keys
+    AllKeysLru,
+    /// `allkeys-lfu`: evict the least frequently used keys
+    AllKeysLfu,
+    /// `allkeys-random`: evict random keys
+    AllKeysRandom,
+    /// `volatile-lru`: evict the least recently used keys with an expiration
+    VolatileLru,
+    /// `volatile-lfu`: evict the least frequently used keys with an expiration
+    VolatileLfu,
+    /// `volatile-random`: evict random keys with an expiration
+    VolatileRandom,
+    /// `volatile-ttl`: evict the keys with an expiration and the shortest time to live
+    VolatileTtl,
+}
+
+impl EvictionPolicy {
+    /// Name of the policy, as set in the `maxmemory-policy` configuration parameter
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EvictionPolicy::AllKeysLru => "allkeys-lru",
+            EvictionPolicy::AllKeysLfu => "allkeys-lfu",
+            EvictionPolicy::AllKeysRandom => "allkeys-random",
+            EvictionPolicy::VolatileLru => "volatile-lru",
+            EvictionPolicy::VolatileLfu => "volatile-lfu",
+            EvictionPolicy::VolatileRandom => "volatile-random",
+            EvictionPolicy::VolatileTtl => "volatile-ttl",
+        }
+    }
+
+    fn is_volatile(self) -> bool {
+        matches!(
+            self,
+            EvictionPolicy::VolatileLru
+                | EvictionPolicy::VolatileLfu
+                | EvictionPolicy::VolatileRandom
+                | EvictionPolicy::VolatileTtl
+        )
+    }
+}
+
+impl fmt::Display for EvictionPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Eviction related information of a key, collected by an [`EvictionSimulator`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySample {
+    /// Name of the key
+    pub key: String,
+    /// Memory used by the key and its value, as reported by [`MEMORY USAGE`](https://redis.io/commands/memory-usage/)
+    pub size: usize,
+    /// Time to live of the key, `None` if the key has no expiration
+    pub ttl: Option<Duration>,
+    /// Time elapsed since the last access to the key, as reported by [`OBJECT IDLETIME`](https://redis.io/commands/object-idletime/)
+    ///
+    /// `None` when an LFU policy is configured on the server.
+    pub idle_time: Option<Duration>,
+    /// Logarithmic access frequency counter of the key, as reported by [`OBJECT FREQ`](https://redis.io/commands/object-freq/)
+    ///
+    /// `None` when no LFU policy is configured on the server.
+    pub freq: Option<usize>,
+}
+
+/// Keys sampled by an [`EvictionSimulator`]
+#[derive(Debug, Clone)]
+pub struct EvictionSample {
+    /// Sampled keys, in sampling order
+    pub keys: Vec<KeySample>,
+    /// Number of keys of the database at sampling time
+    pub db_size: usize,
+}
+
+impl EvictionSample {
+    /// Memory used by the keys of the database, extrapolated from the sample
+    #[must_use]
+    pub fn estimated_memory(&self) -> usize {
+        let sampled: usize = self.keys.iter().map(|k| k.size).sum();
+        (sampled as f64 * self.scale()) as usize
+    }
+
+    /// Estimate the keys `policy` would evict to free `bytes_to_free` bytes
+    ///
+    /// Candidates are sorted according to the policy, and evicted until the memory they use,
+    /// extrapolated to the whole database, reaches `bytes_to_free`.
+    /// The server approximates this ordering by picking its candidates among a few random keys
+    /// (see `maxmemory-samples`): the estimate is the behavior the policy converges to.
+    ///
+    /// # Errors
+    /// An [`Error::Client`] if the sample lacks the idle times (LRU policies) or the access frequencies (LFU policies):
+    /// the server only reports idle times without an LFU policy, and access frequencies with an LFU policy.
+    pub fn simulate(
+        &self,
+        policy: EvictionPolicy,
+        bytes_to_free: usize,
+    ) -> Result<EvictionEstimate> {
+        let mut candidates: Vec<&KeySample> = self
+            .keys
+            .iter()
+            .filter(|k| !policy.is_volatile() || k.ttl.is_some())
+            .collect();
+
+        match policy {
+            EvictionPolicy::AllKeysLru | EvictionPolicy::VolatileLru => {
+                if candidates.iter().any(|k| k.idle_time.is_none()) {
+                    return Err(Error::Client(format!(
+                        "Cannot simulate {policy} without idle times: an LFU policy is configured on the server"
+                    )));
+                }
+                candidates.sort_by_key(|k| std::cmp::Reverse(k.idle_time));
+            }
+            EvictionPolicy::AllKeysLfu | EvictionPolicy::VolatileLfu => {
+                if candidates.iter().any(|k| k.freq.is_none()) {
+                    return Err(Error::Client(format!(
+                        "Cannot simulate {policy} without access frequencies: no LFU policy is configured on the server"
+                    )));
+                }
+                candidates.sort_by_key(|k| k.freq);
+            }
+            EvictionPolicy::VolatileTtl => candidates.sort_by_key(|k| k.ttl),
+            // sampled keys are already in random order
+            EvictionPolicy::AllKeysRandom | EvictionPolicy::VolatileRandom => (),
+        }
+
+        let scale = self.scale();
+        let mut evicted_keys = Vec::new();
+        let mut freed_bytes = 0.;
+        for candidate in candidates {
+            if freed_bytes >= bytes_to_free as f64 {
+                break;
+            }
+            freed_bytes += candidate.size as f64 * scale;
+            evicted_keys.push(candidate.key.clone());
+        }
+
+        Ok(EvictionEstimate {
+            policy,
+            estimated_evicted_keys: (evicted_keys.len() as f64 * scale).round() as usize,
+            evicted_keys,
+            estimated_freed_bytes: freed_bytes as usize,
+            enough_candidates: freed_bytes >= bytes_to_free as f64,
+        })
+    }
+
+    /// Number of keys of the database represented by each sampled key
+    fn scale(&self) -> f64 {
+        if self.keys.is_empty() {
+            0.
+        } else {
+            self.db_size as f64 / self.keys.len() as f64
+        }
+    }
+}
+
+/// Outcome of an eviction simulated by [`EvictionSample::simulate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvictionEstimate {
+    /// Simulated policy
+    pub policy: EvictionPolicy,
+    /// Sampled keys which would be evicted, in eviction order
+    pub evicted_keys: Vec<String>,
+    /// Number of keys of the database which would be evicted, extrapolated from the sample
+    pub estimated_evicted_keys: usize,
+    /// Memory which would be freed, extrapolated from the sample
+    pub estimated_freed_bytes: usize,
+    /// `false` if evicting all the candidates of the policy would not free enough memory,
+    /// in which case the server rejects write commands with `OOM` errors
+    pub enough_candidates: bool,
+}
+
+/// Capacity planning tool estimating what an eviction policy would evict,
+/// based on a sample of the keys of a database.
+///
+/// For each sampled key, the simulator collects its size with [`MEMORY USAGE`](https://redis.io/commands/memory-usage/),
+/// its time to live, and its idle time or access frequency with [`OBJECT`](https://redis.io/commands/object/).
+/// The [sample](EvictionSample) can then be replayed against several [policies](EvictionPolicy)
+/// to compare their effects before changing the `maxmemory-policy` of the server.
+///
+/// Inspecting keys with `OBJECT` does not alter their idle time or access frequency.
+/// The simulator targets a standalone server, or a single node of a cluster.
+///
+/// # Example
+/// ```
+/// use rustis::{
+///     client::Client,
+///     commands::{FlushingMode, ServerCommands, SetCondition, SetExpiration, StringCommands},
+///     patterns::{EvictionPolicy, EvictionSimulator, KeySampling},
+///     Result,
+/// };
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let client = Client::connect("127.0.0.1:6379").await?;
+///     client.flushdb(FlushingMode::Sync).await?;
+///     client.set("user:1", "alice").await?;
+///     client
+///         .set_with_options("session:1", "token", SetCondition::None, SetExpiration::Ex(60), false)
+///         .await?;
+///
+///     let simulator = EvictionSimulator::new(client)
+///         .sample_size(1000)
+///         .sampling(KeySampling::Scan);
+///     let sample = simulator.sample().await?;
+///
+///     // free 10% of the memory
+///     let bytes_to_free = sample.estimated_memory() / 10;
+///     let estimate = sample.simulate(EvictionPolicy::VolatileTtl, bytes_to_free)?;
+///     assert_eq!(vec!["session:1".to_owned()], estimate.evicted_keys);
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct EvictionSimulator {
+    client: Client,
+    sample_size: usize,
+    sampling: KeySampling,
+}
+
+impl EvictionSimulator {
+    /// Create a new simulator sampling `1000` keys with [`RANDOMKEY`](KeySampling::RandomKey)
+    #[must_use]
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            sample_size: 1000,
+            sampling: KeySampling::RandomKey,
+        }
+    }
+
+    /// Number of keys to sample (default `1000`)
+    #[must_use]
+    pub fn sample_size(mut self, sample_size: usize) -> Self {
+        self.sample_size = sample_size;
+        self
+    }
+
+    /// Method used to pick the keys (default [`KeySampling::RandomKey`])
+    #[must_use]
+    pub fn sampling(mut self, sampling: KeySampling) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    /// Sample the keys of the database and collect their eviction related information
+    ///
+    /// Keys deleted while being sampled are ignored.
+    pub async fn sample(&self) -> Result<EvictionSample> {
+        let db_size = self.client.dbsize().await?;
+        let keys = match self.sampling {
+            KeySampling::RandomKey => self.random_keys().await?,
+            KeySampling::Scan => self.scanned_keys().await?,
+        };
+
+        let mut samples = Vec::with_capacity(keys.len());
+        for batch in keys.chunks(SAMPLE_BATCH_SIZE) {
+            samples.extend(self.inspect(batch).await?);
+        }
+
+        Ok(EvictionSample {
+            keys: samples,
+            db_size,
+        })
+    }
+
+    async fn random_keys(&self) -> Result<Vec<String>> {
+        let mut keys = Vec::new();
+        let mut unique_keys = HashSet::new();
+        let mut remaining = self.sample_size;
+
+        while remaining > 0 {
+            let batch_size = remaining.min(SAMPLE_BATCH_SIZE);
+            remaining -= batch_size;
+
+            let mut pipeline = self.client.create_pipeline();
+            for _ in 0..batch_size {
+                pipeline.randomkey::<String>().queue();
+            }
+            let batch: Vec<Option<String>> = if batch_size == 1 {
+                vec![pipeline.execute().await?]
+            } else {
+                pipeline.execute().await?
+            };
+
+            for key in batch {
+                // empty database
+                let Some(key) = key else {
+                    return Ok(keys);
+                };
+                if unique_keys.insert(key.clone()) {
+                    keys.push(key);
+                }
+            }
+        }
+
+        Ok(keys)
+    }
+
+    async fn scanned_keys(&self) -> Result<Vec<String>> {
+        let mut keys = Vec::new();
+        let mut cursor = 0;
+
+        loop {
+            let (next_cursor, batch): (u64, Vec<String>) = self
+                .client
+                .scan(cursor, ScanOptions::default().count(SAMPLE_BATCH_SIZE))
+                .await?;
+            keys.extend(batch);
+            cursor = next_cursor;
+
+            if cursor == 0 || keys.len() >= self.sample_size {
+                keys.truncate(self.sample_size);
+                return Ok(keys);
+            }
+        }
+    }
+
+    /// Collect the information of a batch of keys
+    async fn inspect(&self, keys: &[String]) -> Result<Vec<KeySample>> {
+        let mut pipeline = self.client.create_pipeline();
+        for key in keys {
+            pipeline
+                .memory_usage(key, MemoryUsageOptions::default())
+                .queue();
+            pipeline.pttl(key).queue();
+        }
+        let sizes_and_ttls: Vec<Option<i64>> = pipeline.execute().await?;
+
+        // each metric is only available for some eviction policies
+        let mut pipeline = self.client.create_pipeline();
+        for key in keys {
+            pipeline.object_idle_time(key).queue();
+        }
+        let idle_times = optional_metric(pipeline, keys.len()).await?;
+
+        let mut pipeline = self.client.create_pipeline();
+        for key in keys {
+            pipeline.object_freq(key).queue();
+        }
+        let freqs = optional_metric(pipeline, keys.len()).await?;
+
+        let samples = keys
+            .iter()
+            .enumerate()
+            .filter_map(|(i, key)| {
+                // the key has been deleted in the meantime
+                let size = sizes_and_ttls[2 * i]?;
+                let ttl = sizes_and_ttls[2 * i + 1]?;
+                if ttl == -2 {
+                    return None;
+                }
+
+                Some(KeySample {
+                    key: key.clone(),
+                    size: size as usize,
+                    ttl: (ttl >= 0).then(|| Duration::from_millis(ttl as u64)),
+                    idle_time: idle_times
+                        .as_ref()
+                        .and_then(|idle_times| idle_times[i])
+                        .map(|idle_time| Duration::from_secs(idle_time as u64)),
+                    freq: freqs
+                        .as_ref()
+                        .and_then(|freqs| freqs[i])
+                        .map(|freq| freq as usize),
+                })
+            })
+            .collect();
+
+        Ok(samples)
+    }
+}
+
+/// Results of a pipeline of `OBJECT` subcommands,
+/// `None` if the server does not report this metric with its current eviction policy
+async fn optional_metric(pipeline: Pipeline<'_>, len: usize) -> Result<Option<Vec<Option<i64>>>> {
+    let result = if len == 1 {
+        pipeline.execute().await.map(|value| vec![value])
+    } else {
+        pipeline.execute().await
+    };
+
+    match result {
+        Ok(values) => Ok(Some(values)),
+        Err(Error::Redis(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
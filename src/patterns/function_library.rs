@@ -0,0 +1,239 @@
+use crate::{
+    client::Client,
+    commands::{FunctionListOptions, ScriptingCommands},
+    Error, Result,
+};
+use std::fmt;
+
+/// Prefix of the header line holding the version of a [`FunctionLibrary`]
+const VERSION_PREFIX: &str = "-- version: ";
+
+/// Library of [Redis Functions](https://redis.io/docs/interact/programmability/functions-intro/)
+/// declared in Rust, with a version
+///
+/// The code loaded in Redis is the Lua source of the library,
+/// preceded by a generated header: the `#!lua name=<name>` shebang line,
+/// followed by a `-- version: <version>` comment line.
+/// [`ensure_loaded`](FunctionLibrary::ensure_loaded) reads back this header
+/// with [`FUNCTION LIST`](https://redis.io/commands/function-list/)
+/// to load the library only if it is missing, or to replace it if its version differs.
+///
+/// Functions require Redis 7.
+///
+/// # Example
+/// ```
+/// use rustis::{
+///     client::Client,
+///     commands::{CallBuilder, ScriptingCommands},
+///     patterns::FunctionLibrary,
+///     Result,
+/// };
+///
+/// const SOURCE: &str = r#"
+/// redis.register_function('counter_incr', function(keys, args)
+///     return redis.call('INCRBY', keys[1], args[1])
+/// end)
+/// "#;
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let client = Client::connect("127.0.0.1:6379").await?;
+///
+///     let library = FunctionLibrary::new("counters", "1.0.0", SOURCE)?;
+///     // typically called once when the application starts
+///     library.ensure_loaded(&client).await?;
+///     // nothing to load anymore
+///     let deployments = library.ensure_loaded(&client).await?;
+///     assert!(deployments.iter().all(|d| !d.loaded()));
+///
+///     let value: i64 = client
+///         .fcall(CallBuilder::function("counter_incr").keys("counter").args(10))
+///         .await?;
+///     assert!(value >= 10);
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FunctionLibrary {
+    name: String,
+    version: String,
+    source: String,
+}
+
+impl FunctionLibrary {
+    /// Declare the library `name`, with the Lua `source` of its functions
+    /// (without the `#!lua` shebang line, which is generated)
+    ///
+    /// `version` is an arbitrary tag (e.g. `1.2.0` or a commit hash):
+    /// any other version loaded in Redis is replaced.
+    ///
+    /// # Errors
+    /// An [`Error::Client`] if `name` is not a valid library name
+    /// (only letters, digits and underscores are allowed),
+    /// or if `version` holds a line break.
+    pub fn new(
+        name: impl Into<String>,
+        version: impl Into<String>,
+        source: impl Into<String>,
+    ) -> Result<Self> {
+        let name = name.into();
+        let version = version.into();
+
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(Error::Client(format!(
+                "Invalid function library name `{name}`"
+            )));
+        }
+        if version.contains(['\r', '\n']) {
+            return Err(Error::Client(format!(
+                "Invalid version `{version}` for function library `{name}`"
+            )));
+        }
+
+        Ok(Self {
+            name,
+            version,
+            source: source.into(),
+        })
+    }
+
+    /// Name of the library
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Version of the library
+    #[must_use]
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Code of the library, as loaded with [`FUNCTION LOAD`](https://redis.io/commands/function-load/):
+    /// the generated header followed by the Lua source
+    #[must_use]
+    pub fn code(&self) -> String {
+        format!(
+            "#!lua name={}\n{VERSION_PREFIX}{}\n{}",
+            self.name, self.version, self.source
+        )
+    }
+
+    /// Version of a library from its code, `None` if the code has no version header
+    #[must_use]
+    pub fn parse_version(code: &str) -> Option<&str> {
+        code.lines()
+            .nth(1)
+            .and_then(|line| line.strip_prefix(VERSION_PREFIX))
+    }
+
+    /// Load the library if it is missing, or replace it if its version differs,
+    /// on the connected server or on each master node of a cluster
+    ///
+    /// # Return
+    /// The deployment of the library on each node
+    ///
+    /// # Errors
+    /// Any Redis driver [`Error`] that occurs while listing or loading the library,
+    /// e.g. a Lua compilation error of the source
+    pub async fn ensure_loaded(&self, client: &Client) -> Result<Vec<LibraryDeployment>> {
+        let mut deployments = Vec::new();
+        for client in client.master_clients().await? {
+            deployments.push(self.ensure_loaded_on_node(&client).await?);
+        }
+        Ok(deployments)
+    }
+
+    async fn ensure_loaded_on_node(&self, client: &Client) -> Result<LibraryDeployment> {
+        let libraries = client
+            .function_list(
+                FunctionListOptions::default()
+                    .library_name_pattern(self.name.as_str())
+                    .with_code(),
+            )
+            .await?;
+
+        let previous = match libraries
+            .into_iter()
+            .find(|library| library.library_name == self.name)
+        {
+            None => LibraryStatus::Missing,
+            Some(library) => {
+                let version = library
+                    .library_code
+                    .as_deref()
+                    .and_then(Self::parse_version);
+                if version == Some(self.version.as_str()) {
+                    LibraryStatus::UpToDate
+                } else {
+                    LibraryStatus::Outdated {
+                        version: version.map(ToOwned::to_owned),
+                    }
+                }
+            }
+        };
+
+        if previous != LibraryStatus::UpToDate {
+            let replace = previous != LibraryStatus::Missing;
+            let _: String = client.function_load(replace, self.code()).await?;
+        }
+
+        Ok(LibraryDeployment {
+            node: client.server_address(),
+            previous,
+        })
+    }
+}
+
+/// Status of a [`FunctionLibrary`] on a node, before [`ensure_loaded`](FunctionLibrary::ensure_loaded)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LibraryStatus {
+    /// The library was not loaded
+    Missing,
+    /// Another version of the library was loaded,
+    /// `None` if the library was loaded without version header
+    Outdated {
+        /// Version of the loaded library
+        version: Option<String>,
+    },
+    /// The same version of the library was loaded
+    UpToDate,
+}
+
+/// Deployment of a [`FunctionLibrary`] on a node, reported by
+/// [`ensure_loaded`](FunctionLibrary::ensure_loaded)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LibraryDeployment {
+    /// `host:port` of the node, `None` if unknown
+    pub node: Option<String>,
+    /// Status of the library before the deployment
+    pub previous: LibraryStatus,
+}
+
+impl LibraryDeployment {
+    /// `true` if the library has been loaded or replaced on the node
+    #[must_use]
+    pub fn loaded(&self) -> bool {
+        self.previous != LibraryStatus::UpToDate
+    }
+}
+
+impl fmt::Display for LibraryDeployment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let node = self.node.as_deref().unwrap_or("?");
+        match &self.previous {
+            LibraryStatus::Missing => write!(f, "{node}: loaded"),
+            LibraryStatus::Outdated {
+                version: Some(version),
+            } => {
+                write!(f, "{node}: replaced version {version}")
+            }
+            LibraryStatus::Outdated { version: None } => {
+                write!(f, "{node}: replaced unversioned library")
+            }
+            LibraryStatus::UpToDate => write!(f, "{node}: up to date"),
+        }
+    }
+}
@@ -0,0 +1,369 @@
+use crate::{
+    client::Client,
+    commands::{ClientTrackingOptions, ClientTrackingStatus, ConnectionCommands, HashCommands},
+    resp::{BulkString, Value},
+    spawn, Error, Result,
+};
+use futures_channel::oneshot;
+use futures_util::{select, FutureExt, Stream, StreamExt};
+use log::{debug, warn};
+use serde::de::DeserializeOwned;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::broadcast::error::RecvError;
+
+/// Hits and misses of a [`HashFieldCache`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HashFieldCacheStats {
+    /// Number of fields read from the local cache
+    pub hits: u64,
+    /// Number of fields read from the server
+    pub misses: u64,
+}
+
+/// Value of a field, `None` if the field does not exist
+type FieldValue = Option<Vec<u8>>;
+
+/// Fields of a hash cached locally
+struct CachedKey {
+    /// Identifies this entry, so that a reply received after an invalidation of the key
+    /// is not cached in a newer entry
+    epoch: u64,
+    fields: HashMap<String, FieldValue>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    /// `false` while the keys read are not guaranteed to be tracked, e.g. during a reconnection
+    enabled: bool,
+    next_epoch: u64,
+    keys: HashMap<String, CachedKey>,
+    stats: HashFieldCacheStats,
+}
+
+impl CacheState {
+    /// Epoch of the entry of `key`, created if needed, or `None` if the cache is disabled
+    fn epoch(&mut self, key: &str) -> Option<u64> {
+        if !self.enabled {
+            return None;
+        }
+
+        if let Some(cached_key) = self.keys.get(key) {
+            return Some(cached_key.epoch);
+        }
+
+        self.next_epoch += 1;
+        self.keys.insert(
+            key.to_owned(),
+            CachedKey {
+                epoch: self.next_epoch,
+                fields: HashMap::new(),
+            },
+        );
+        Some(self.next_epoch)
+    }
+
+    /// Cache the `fields` read from the server, unless `key` has been invalidated in the meantime
+    fn insert(
+        &mut self,
+        key: &str,
+        epoch: u64,
+        fields: impl Iterator<Item = (String, FieldValue)>,
+    ) {
+        if let Some(cached_key) = self.keys.get_mut(key) {
+            if cached_key.epoch == epoch {
+                cached_key.fields.extend(fields);
+            }
+        }
+    }
+
+    /// Invalidate `keys`, or all the keys if `keys` is empty (`FLUSHALL`, `FLUSHDB`)
+    fn invalidate(&mut self, keys: Vec<String>) {
+        if keys.is_empty() {
+            self.keys.clear();
+        } else {
+            for key in keys {
+                self.keys.remove(&key);
+            }
+        }
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.keys.clear();
+    }
+}
+
+struct HashFieldCacheInner {
+    client: Client,
+    state: Arc<Mutex<CacheState>>,
+    /// Stops the invalidation task when the last clone of the cache is dropped
+    _stop_sender: oneshot::Sender<()>,
+}
+
+/// Near-cache of the fields of [hashes](https://redis.io/docs/data-types/hashes/),
+/// based on [client-side caching](https://redis.io/docs/manual/client-side-caching/).
+///
+/// The values read with [`hget`](HashFieldCache::hget) and [`hmget`](HashFieldCache::hmget)
+/// are cached locally by key and field, so that a field is only read from the server
+/// when it has not been read yet, even if other fields of the hash are already cached.
+///
+/// [`CLIENT TRACKING`](https://redis.io/commands/client-tracking/) is enabled on `client`,
+/// with invalidation messages redirected to `invalidation_client`:
+/// when a cached hash is modified, expired or evicted, all its cached fields are invalidated.
+/// Tracking is enabled again and the cache is cleared each time one of the clients reconnects.
+///
+/// All the keys read by `client` are tracked by the server,
+/// and `invalidation_client` cannot receive other invalidation messages:
+/// both clients should be dedicated to the cache.
+///
+/// The size of the cache is bounded by the tracking table of the server
+/// ([`tracking-table-max-keys`](https://redis.io/docs/manual/client-side-caching/#what-to-cache)),
+/// which invalidates keys when it is full.
+///
+/// Cluster clients are not supported.
+///
+/// # Example
+/// ```
+/// use rustis::{client::Client, commands::HashCommands, patterns::HashFieldCache, Result};
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let client = Client::connect("127.0.0.1:6379").await?;
+///     client
+///         .hset("session:1", [("user", "john"), ("theme", "dark")])
+///         .await?;
+///
+///     let cache = HashFieldCache::new(
+///         Client::connect("127.0.0.1:6379").await?,
+///         Client::connect("127.0.0.1:6379").await?,
+///     )
+///     .await?;
+///
+///     // read from the server
+///     let user: Option<String> = cache.hget("session:1", "user").await?;
+///     assert_eq!(Some("john".to_owned()), user);
+///
+///     // `user` read from the cache, `theme` from the server
+///     let values: Vec<Option<String>> = cache.hmget("session:1", &["user", "theme"]).await?;
+///     assert_eq!(vec![Some("john".to_owned()), Some("dark".to_owned())], values);
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct HashFieldCache {
+    inner: Arc<HashFieldCacheInner>,
+}
+
+impl HashFieldCache {
+    /// Enable the tracking of the keys read by `client`
+    /// and start receiving invalidation messages with `invalidation_client`
+    ///
+    /// # Errors
+    /// An [`Error::Client`] if one of the clients is connected to a cluster,
+    /// or any error while enabling the tracking.
+    pub async fn new(client: Client, invalidation_client: Client) -> Result<Self> {
+        if client.is_cluster() || invalidation_client.is_cluster() {
+            return Err(Error::Client(
+                "HashFieldCache does not support cluster clients".to_owned(),
+            ));
+        }
+
+        let invalidations = invalidation_client.create_client_tracking_invalidation_stream()?;
+        let client_reconnect = client.on_reconnect();
+        let invalidation_reconnect = invalidation_client.on_reconnect();
+
+        enable_tracking(&client, &invalidation_client).await?;
+
+        let state = Arc::new(Mutex::new(CacheState {
+            enabled: true,
+            ..Default::default()
+        }));
+        let (stop_sender, stop_receiver) = oneshot::channel();
+
+        let tracking = Tracking {
+            client: client.clone(),
+            invalidation_client,
+            state: state.clone(),
+        };
+        spawn(tracking.run(
+            invalidations,
+            client_reconnect,
+            invalidation_reconnect,
+            stop_receiver,
+        ));
+
+        Ok(Self {
+            inner: Arc::new(HashFieldCacheInner {
+                client,
+                state,
+                _stop_sender: stop_sender,
+            }),
+        })
+    }
+
+    /// Value of `field` in the hash stored at `key`, read from the cache if present
+    ///
+    /// Like [`HGET`](https://redis.io/commands/hget/), a missing field or key is read as nil,
+    /// i.e. `None` if `V` is an `Option`.
+    pub async fn hget<V: DeserializeOwned>(&self, key: &str, field: &str) -> Result<V> {
+        let mut values = self.hmget::<V>(key, &[field]).await?;
+        Ok(values.swap_remove(0))
+    }
+
+    /// Values of `fields` in the hash stored at `key`
+    ///
+    /// Only the fields which are not cached yet are read from the server,
+    /// with a single [`HMGET`](https://redis.io/commands/hmget/).
+    pub async fn hmget<V: DeserializeOwned>(&self, key: &str, fields: &[&str]) -> Result<Vec<V>> {
+        let mut values: Vec<Option<FieldValue>> = vec![None; fields.len()];
+
+        let epoch = {
+            let mut state = self.inner.state.lock().unwrap();
+            let epoch = state.epoch(key);
+            if let Some(cached_key) = state.keys.get(key) {
+                for (value, field) in values.iter_mut().zip(fields) {
+                    *value = cached_key.fields.get(*field).cloned();
+                }
+            }
+            let hits = values.iter().filter(|v| v.is_some()).count() as u64;
+            state.stats.hits += hits;
+            state.stats.misses += fields.len() as u64 - hits;
+            epoch
+        };
+
+        let missing_fields: Vec<&str> = fields
+            .iter()
+            .zip(&values)
+            .filter_map(|(field, value)| value.is_none().then_some(*field))
+            .collect();
+
+        if !missing_fields.is_empty() {
+            let fetched: Vec<Option<BulkString>> = self
+                .inner
+                .client
+                .hmget(key, missing_fields.as_slice())
+                .await?;
+            let fetched: Vec<FieldValue> = fetched
+                .into_iter()
+                .map(|value| value.map(Vec::from))
+                .collect();
+
+            if let Some(epoch) = epoch {
+                self.inner.state.lock().unwrap().insert(
+                    key,
+                    epoch,
+                    missing_fields
+                        .iter()
+                        .map(|field| (*field).to_owned())
+                        .zip(fetched.iter().cloned()),
+                );
+            }
+
+            let mut fetched = fetched.into_iter();
+            for value in values.iter_mut().filter(|v| v.is_none()) {
+                *value = fetched.next();
+            }
+        }
+
+        values
+            .into_iter()
+            .map(|value| match value.flatten() {
+                Some(bytes) => Value::BulkString(bytes).into(),
+                None => Value::Nil.into(),
+            })
+            .collect()
+    }
+
+    /// Remove the cached fields of `key`
+    pub fn invalidate(&self, key: &str) {
+        self.inner.state.lock().unwrap().keys.remove(key);
+    }
+
+    /// Remove all the cached fields
+    pub fn clear(&self) {
+        self.inner.state.lock().unwrap().keys.clear();
+    }
+
+    /// Hits and misses since the creation of the cache
+    #[must_use]
+    pub fn stats(&self) -> HashFieldCacheStats {
+        self.inner.state.lock().unwrap().stats
+    }
+}
+
+async fn enable_tracking(client: &Client, invalidation_client: &Client) -> Result<()> {
+    let invalidation_id = invalidation_client.client_id().await?;
+    client
+        .client_tracking(
+            ClientTrackingStatus::On,
+            ClientTrackingOptions::default().redirect(invalidation_id),
+        )
+        .await
+}
+
+/// Background task applying the invalidation messages to the cache
+struct Tracking {
+    client: Client,
+    invalidation_client: Client,
+    state: Arc<Mutex<CacheState>>,
+}
+
+impl Tracking {
+    async fn run(
+        self,
+        invalidations: impl Stream<Item = Vec<String>> + Unpin,
+        mut client_reconnect: tokio::sync::broadcast::Receiver<()>,
+        mut invalidation_reconnect: tokio::sync::broadcast::Receiver<()>,
+        stop_receiver: oneshot::Receiver<()>,
+    ) {
+        let mut invalidations = invalidations.fuse();
+        let mut stop_receiver = stop_receiver.fuse();
+
+        loop {
+            select! {
+                keys = invalidations.next() => match keys {
+                    Some(keys) => self.state.lock().unwrap().invalidate(keys),
+                    None => {
+                        warn!("[HashFieldCache] Invalidation stream closed, caching disabled");
+                        break;
+                    }
+                },
+                result = client_reconnect.recv().fuse() => {
+                    if !self.on_reconnect(result).await { break; }
+                },
+                result = invalidation_reconnect.recv().fuse() => {
+                    if !self.on_reconnect(result).await { break; }
+                },
+                _ = stop_receiver => break,
+            }
+        }
+
+        self.state.lock().unwrap().set_enabled(false);
+        debug!("[HashFieldCache] Invalidation task stopped");
+    }
+
+    /// Enable the tracking again on the new connection
+    async fn on_reconnect(&self, result: std::result::Result<(), RecvError>) -> bool {
+        if let Err(RecvError::Closed) = result {
+            return false;
+        }
+
+        self.state.lock().unwrap().set_enabled(false);
+        match enable_tracking(&self.client, &self.invalidation_client).await {
+            Ok(()) => {
+                debug!("[HashFieldCache] Tracking enabled after reconnection");
+                self.state.lock().unwrap().set_enabled(true);
+            }
+            Err(e) => {
+                warn!("[HashFieldCache] Cannot enable tracking after reconnection, caching disabled until the next one: {e}");
+            }
+        }
+
+        true
+    }
+}
@@ -0,0 +1,468 @@
+use crate::{
+    client::{BatchPreparedCommand, Client},
+    commands::{GenericCommands, SortedSetCommands, ZAddOptions, ZAggregate, ZRangeOptions},
+    network::sleep,
+    resp::SingleArg,
+    Error, Result,
+};
+use futures_util::{stream::BoxStream, StreamExt};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Time span of a board of a [`Leaderboard`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaderboardPeriod {
+    /// One board per day (UTC), where scores are recorded
+    Daily,
+    /// One board per week (UTC, starting on Monday), rolled up from the daily boards
+    Weekly,
+    /// One board per calendar month (UTC), rolled up from the daily boards
+    Monthly,
+}
+
+impl LeaderboardPeriod {
+    fn key_prefix(self) -> &'static str {
+        match self {
+            LeaderboardPeriod::Daily => "d",
+            LeaderboardPeriod::Weekly => "w",
+            LeaderboardPeriod::Monthly => "m",
+        }
+    }
+
+    /// Index of the board containing the day `day` (days since the Unix epoch)
+    fn index(self, day: u64) -> u64 {
+        match self {
+            LeaderboardPeriod::Daily => day,
+            // the Unix epoch is a Thursday
+            LeaderboardPeriod::Weekly => (day + 3) / 7,
+            LeaderboardPeriod::Monthly => {
+                let (year, month) = civil_from_days(day);
+                (year - 1970) * 12 + month - 1
+            }
+        }
+    }
+
+    /// First and last days of the board `index`
+    fn days(self, index: u64) -> (u64, u64) {
+        match self {
+            LeaderboardPeriod::Daily => (index, index),
+            LeaderboardPeriod::Weekly => ((index * 7).saturating_sub(3), index * 7 + 3),
+            LeaderboardPeriod::Monthly => {
+                let first_day = |index: u64| days_from_civil(1970 + index / 12, index % 12 + 1);
+                (first_day(index), first_day(index + 1) - 1)
+            }
+        }
+    }
+}
+
+/// How the scores of a member are combined
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaderboardScoring {
+    /// Scores are summed, on the daily boards with [`ZINCRBY`](https://redis.io/commands/zincrby/)
+    /// and on the rolled up boards with `ZUNIONSTORE ... AGGREGATE SUM`
+    Cumulative,
+    /// Only the best score is kept, on the daily boards with [`ZADD GT`](https://redis.io/commands/zadd/)
+    /// and on the rolled up boards with `ZUNIONSTORE ... AGGREGATE MAX`
+    Best,
+}
+
+/// Member of a [`Leaderboard`] with its rank and score
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeaderboardEntry {
+    /// Rank of the member, `0` being the highest score
+    pub rank: usize,
+    /// Member
+    pub member: String,
+    /// Score of the member
+    pub score: f64,
+}
+
+/// Leaderboards per day, week and month, backed by [sorted sets](https://redis.io/docs/data-types/sorted-sets/).
+///
+/// Scores are recorded in daily boards, stored in keys `{<name>}:d:<index>`,
+/// where `index` is the number of days elapsed since the Unix epoch.
+/// Weekly boards, `{<name>}:w:<index>`, and monthly boards, `{<name>}:m:<index>`,
+/// are [rolled up](Leaderboard::rollup) from the daily boards with
+/// [`ZUNIONSTORE`](https://redis.io/commands/zunionstore/).
+/// All the keys of a leaderboard share the same hash tag,
+/// so that these multi-key commands are served by a single node, even on a cluster.
+///
+/// Ranks are ordered by descending scores, `0` being the highest score.
+///
+/// # Example
+/// ```
+/// use rustis::{
+///     client::Client,
+///     patterns::{Leaderboard, LeaderboardPeriod},
+///     Result,
+/// };
+/// use std::time::SystemTime;
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let client = Client::connect("127.0.0.1:6379").await?;
+///     let leaderboard = Leaderboard::new(client, "game");
+///
+///     leaderboard.record("alice", 120.).await?;
+///     leaderboard.record("bob", 80.).await?;
+///     leaderboard.record("alice", 30.).await?;
+///
+///     let now = SystemTime::now();
+///     leaderboard.rollup(LeaderboardPeriod::Weekly, now).await?;
+///     let podium = leaderboard.top(LeaderboardPeriod::Weekly, now, 3).await?;
+///     assert_eq!("alice", podium[0].member);
+///
+///     // players ranked just before and after bob
+///     let _neighbours = leaderboard
+///         .around(LeaderboardPeriod::Daily, now, "bob", 2)
+///         .await?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Leaderboard {
+    client: Client,
+    name: String,
+    scoring: LeaderboardScoring,
+    decay: f64,
+}
+
+impl Leaderboard {
+    /// Create a new leaderboard, storing its boards under `name`,
+    /// with [cumulative](LeaderboardScoring::Cumulative) scores
+    #[must_use]
+    pub fn new(client: Client, name: impl Into<String>) -> Self {
+        Self {
+            client,
+            name: name.into(),
+            scoring: LeaderboardScoring::Cumulative,
+            decay: 1.,
+        }
+    }
+
+    /// How the scores of a member are combined
+    #[must_use]
+    pub fn scoring(mut self, scoring: LeaderboardScoring) -> Self {
+        self.scoring = scoring;
+        self
+    }
+
+    /// Weight factor applied per day of age when rolling up the daily boards
+    /// (`1` by default, all days weighing the same)
+    ///
+    /// The scores of the last day of a period are weighted `1`, those of the day before `decay`,
+    /// those of the day before `decay²`, and so on, so that recent scores prevail.
+    #[must_use]
+    pub fn decay(mut self, decay: f64) -> Self {
+        self.decay = decay;
+        self
+    }
+
+    /// Key of the board of `period` containing `timestamp`
+    ///
+    /// # Errors
+    /// An [`Error::Client`] if `timestamp` is before the Unix epoch.
+    pub fn board_key(&self, period: LeaderboardPeriod, timestamp: SystemTime) -> Result<String> {
+        Ok(self.key(period, period.index(day(timestamp)?)))
+    }
+
+    /// Key where [`store_top`](Leaderboard::store_top) stores the top of the board
+    /// of `period` containing `timestamp`
+    ///
+    /// # Errors
+    /// An [`Error::Client`] if `timestamp` is before the Unix epoch.
+    pub fn top_key(&self, period: LeaderboardPeriod, timestamp: SystemTime) -> Result<String> {
+        Ok(format!("{}:top", self.board_key(period, timestamp)?))
+    }
+
+    /// Record a score of `member` in the daily board of the current time
+    ///
+    /// # Return
+    /// The score of `member` in the daily board, according to the [scoring](Leaderboard::scoring)
+    pub async fn record<M>(&self, member: M, score: f64) -> Result<f64>
+    where
+        M: SingleArg + Clone,
+    {
+        self.record_at(SystemTime::now(), member, score).await
+    }
+
+    /// Record a score of `member` in the daily board containing `timestamp`
+    ///
+    /// # Return
+    /// The score of `member` in the daily board, according to the [scoring](Leaderboard::scoring)
+    pub async fn record_at<M>(&self, timestamp: SystemTime, member: M, score: f64) -> Result<f64>
+    where
+        M: SingleArg + Clone,
+    {
+        let key = self.board_key(LeaderboardPeriod::Daily, timestamp)?;
+
+        match self.scoring {
+            LeaderboardScoring::Cumulative => self.client.zincrby(key, score, member).await,
+            LeaderboardScoring::Best => {
+                let mut pipeline = self.client.create_pipeline();
+                pipeline
+                    .zadd(
+                        key.as_str(),
+                        [(score, member.clone())],
+                        ZAddOptions::default().gt(),
+                    )
+                    .forget();
+                pipeline.zscore(key.as_str(), member).queue();
+                let best: Option<f64> = pipeline.execute().await?;
+                Ok(best.unwrap_or(score))
+            }
+        }
+    }
+
+    /// Roll up the daily boards of the board of `period` containing `timestamp`
+    /// into this board, with `ZUNIONSTORE`, weighted by the [decay](Leaderboard::decay)
+    ///
+    /// The board is entirely recomputed, a rollup can be repeated as long as the period is not over.
+    ///
+    /// # Return
+    /// The number of members of the rolled up board
+    ///
+    /// # Errors
+    /// An [`Error::Client`] if `period` is [`Daily`](LeaderboardPeriod::Daily)
+    /// or if `timestamp` is before the Unix epoch.
+    pub async fn rollup(&self, period: LeaderboardPeriod, timestamp: SystemTime) -> Result<usize> {
+        if period == LeaderboardPeriod::Daily {
+            return Err(Error::Client(
+                "Daily boards are recorded, not rolled up".to_owned(),
+            ));
+        }
+
+        let index = period.index(day(timestamp)?);
+        let (first_day, last_day) = period.days(index);
+        let daily_keys = (first_day..=last_day)
+            .map(|day| self.key(LeaderboardPeriod::Daily, day))
+            .collect::<Vec<_>>();
+        let weights = (first_day..=last_day)
+            .map(|day| self.decay.powi((last_day - day) as i32))
+            .collect::<Vec<_>>();
+        let aggregate = match self.scoring {
+            LeaderboardScoring::Cumulative => ZAggregate::Sum,
+            LeaderboardScoring::Best => ZAggregate::Max,
+        };
+
+        self.client
+            .zunionstore(
+                self.key(period, index),
+                daily_keys,
+                Some(weights),
+                aggregate,
+            )
+            .await
+    }
+
+    /// Infinite stream of the number of members of the board of `period` containing the current time,
+    /// [rolled up](Leaderboard::rollup) every `interval`
+    ///
+    /// The first rollup happens immediately.
+    /// When a period is over, its board is rolled up a last time before the board of the new period.
+    /// A failed rollup produces an error item, the rollups go on.
+    ///
+    /// The rollups stop when the returned stream is dropped.
+    pub fn rollup_periodically(
+        &self,
+        period: LeaderboardPeriod,
+        interval: Duration,
+    ) -> BoxStream<'static, Result<usize>> {
+        let leaderboard = self.clone();
+
+        futures_util::stream::unfold(
+            (leaderboard, None::<u64>, true),
+            move |(leaderboard, last_index, first)| async move {
+                if !first {
+                    sleep(interval).await;
+                }
+
+                let now = SystemTime::now();
+                let result = async {
+                    let index = period.index(day(now)?);
+                    if let Some(last_index) = last_index.filter(|last_index| *last_index != index) {
+                        // last scores of the previous period
+                        let (_, last_day) = period.days(last_index);
+                        leaderboard
+                            .rollup(period, UNIX_EPOCH + Duration::from_secs(last_day * 86400))
+                            .await?;
+                    }
+                    let count = leaderboard.rollup(period, now).await?;
+                    Ok((index, count))
+                }
+                .await;
+
+                let (result, last_index) = match result {
+                    Ok((index, count)) => (Ok(count), Some(index)),
+                    Err(e) => (Err(e), last_index),
+                };
+                Some((result, (leaderboard, last_index, false)))
+            },
+        )
+        .boxed()
+    }
+
+    /// Members with the `count` highest scores of the board of `period` containing `timestamp`,
+    /// with [`ZRANGE ... REV WITHSCORES`](https://redis.io/commands/zrange/)
+    pub async fn top(
+        &self,
+        period: LeaderboardPeriod,
+        timestamp: SystemTime,
+        count: usize,
+    ) -> Result<Vec<LeaderboardEntry>> {
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+
+        self.range(&self.board_key(period, timestamp)?, 0, count - 1)
+            .await
+    }
+
+    /// Store the members with the `count` highest scores of the board of `period` containing `timestamp`
+    /// at [`top_key`](Leaderboard::top_key), with [`ZRANGESTORE`](https://redis.io/commands/zrangestore/),
+    /// e.g. to keep the podium of a period once its daily boards have expired
+    ///
+    /// # Return
+    /// The number of members stored
+    pub async fn store_top(
+        &self,
+        period: LeaderboardPeriod,
+        timestamp: SystemTime,
+        count: usize,
+    ) -> Result<usize> {
+        let key = self.board_key(period, timestamp)?;
+        let top_key = format!("{key}:top");
+        if count == 0 {
+            self.client.del(top_key).await?;
+            return Ok(0);
+        }
+
+        self.client
+            .zrangestore(
+                top_key,
+                key,
+                0,
+                count as isize - 1,
+                ZRangeOptions::default().reverse(),
+            )
+            .await
+    }
+
+    /// Rank of `member` in the board of `period` containing `timestamp`,
+    /// `None` if `member` has no score in this board
+    pub async fn rank(
+        &self,
+        period: LeaderboardPeriod,
+        timestamp: SystemTime,
+        member: impl SingleArg,
+    ) -> Result<Option<usize>> {
+        self.client
+            .zrevrank(self.board_key(period, timestamp)?, member)
+            .await
+    }
+
+    /// `member` and the members ranked up to `radius` places before and after it
+    /// in the board of `period` containing `timestamp`
+    ///
+    /// # Return
+    /// An empty list if `member` has no score in this board
+    pub async fn around(
+        &self,
+        period: LeaderboardPeriod,
+        timestamp: SystemTime,
+        member: impl SingleArg,
+        radius: usize,
+    ) -> Result<Vec<LeaderboardEntry>> {
+        let key = self.board_key(period, timestamp)?;
+        let Some(rank) = self.client.zrevrank(key.as_str(), member).await? else {
+            return Ok(Vec::new());
+        };
+
+        self.range(&key, rank.saturating_sub(radius), rank + radius)
+            .await
+    }
+
+    /// Percentile of `member` in the board of `period` containing `timestamp`:
+    /// the percentage of the members of the board ranked at or below it,
+    /// `100` for the first member
+    ///
+    /// # Return
+    /// `None` if `member` has no score in this board
+    pub async fn percentile(
+        &self,
+        period: LeaderboardPeriod,
+        timestamp: SystemTime,
+        member: impl SingleArg,
+    ) -> Result<Option<f64>> {
+        let key = self.board_key(period, timestamp)?;
+
+        let mut pipeline = self.client.create_pipeline();
+        pipeline.zrevrank(key.as_str(), member).queue();
+        pipeline.zcard(key.as_str()).queue();
+        let (rank, count): (Option<usize>, usize) = pipeline.execute().await?;
+
+        Ok(rank.map(|rank| (count - rank) as f64 * 100. / count as f64))
+    }
+
+    /// Entries ranked from `start` to `stop`, inclusive
+    async fn range(&self, key: &str, start: usize, stop: usize) -> Result<Vec<LeaderboardEntry>> {
+        let members: Vec<(String, f64)> = self
+            .client
+            .zrange_with_scores(
+                key,
+                start as isize,
+                stop as isize,
+                ZRangeOptions::default().reverse(),
+            )
+            .await?;
+
+        Ok(members
+            .into_iter()
+            .enumerate()
+            .map(|(i, (member, score))| LeaderboardEntry {
+                rank: start + i,
+                member,
+                score,
+            })
+            .collect())
+    }
+
+    fn key(&self, period: LeaderboardPeriod, index: u64) -> String {
+        format!("{{{}}}:{}:{index}", self.name, period.key_prefix())
+    }
+}
+
+/// Number of days elapsed between the Unix epoch and `timestamp`
+fn day(timestamp: SystemTime) -> Result<u64> {
+    let since_epoch = timestamp
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| Error::Client("Cannot record scores before the Unix epoch".to_owned()))?;
+    Ok(since_epoch.as_secs() / 86400)
+}
+
+/// Year and month of the day `days` (days since the Unix epoch),
+/// see <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>
+fn civil_from_days(days: u64) -> (u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month)
+}
+
+/// Days since the Unix epoch of the first day of `month` of `year`,
+/// see <http://howardhinnant.github.io/date_algorithms.html#days_from_civil>
+fn days_from_civil(year: u64, month: u64) -> u64 {
+    let year = year - u64::from(month <= 2);
+    let era = year / 400;
+    let yoe = year - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
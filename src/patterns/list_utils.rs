@@ -0,0 +1,167 @@
+use crate::{
+    client::Client,
+    commands::ListCommands,
+    patterns::MultiKeyAtomic,
+    resp::{CommandArgs, SingleArg},
+    Error, Result,
+};
+
+/// Push the element at the tail of the list, only if the list does not contain it yet
+const PUSH_UNIQUE_SCRIPT: &str = r#"
+if redis.call('LPOS', KEYS[1], ARGV[1]) then
+    return 0
+end
+redis.call('RPUSH', KEYS[1], ARGV[1])
+return 1
+"#;
+
+/// Move up to ARGV[1] elements from the head of the source list to the tail of the destination list
+const MOVE_CHUNK_SCRIPT: &str = r#"
+local moved = 0
+for i = 1, tonumber(ARGV[1]) do
+    if not redis.call('LMOVE', KEYS[1], KEYS[2], 'LEFT', 'RIGHT') then
+        break
+    end
+    moved = moved + 1
+end
+return moved
+"#;
+
+/// Shorten the list to its first ARGV[1] elements, returning the number of elements removed
+const TRUNCATE_SCRIPT: &str = r#"
+local len = redis.call('LLEN', KEYS[1])
+local max_len = tonumber(ARGV[1])
+if len <= max_len then
+    return 0
+end
+if max_len == 0 then
+    redis.call('DEL', KEYS[1])
+else
+    redis.call('LTRIM', KEYS[1], 0, max_len - 1)
+end
+return len - max_len
+"#;
+
+/// Maintenance operations on [lists](https://redis.io/docs/data-types/lists/)
+/// which are not provided by a single Redis command:
+///
+/// * [`push_unique`](ListUtils::push_unique) pushes an element only if the list does not contain it yet,
+///   to use a list as a deduplicating queue,
+/// * [`move_all`](ListUtils::move_all) moves all the elements of a list to another one, by chunks,
+/// * [`truncate`](ListUtils::truncate) shortens a list to a maximum length.
+///
+/// On a cluster, the source and destination lists of [`move_all`](ListUtils::move_all)
+/// must share the same hash tag.
+///
+/// # Example
+/// ```
+/// use rustis::{client::Client, commands::ListCommands, patterns::ListUtils, Result};
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let client = Client::connect("127.0.0.1:6379").await?;
+///     let list_utils = ListUtils::new(client.clone());
+///
+///     assert!(list_utils.push_unique("{jobs}:pending", "job1").await?);
+///     assert!(list_utils.push_unique("{jobs}:pending", "job2").await?);
+///     assert!(!list_utils.push_unique("{jobs}:pending", "job1").await?);
+///
+///     assert_eq!(2, list_utils.move_all("{jobs}:pending", "{jobs}:retry", 100).await?);
+///     assert_eq!(1, list_utils.truncate("{jobs}:retry", 1).await?);
+///
+///     let jobs: Vec<String> = client.lrange("{jobs}:retry", 0, -1).await?;
+///     assert_eq!(vec!["job1".to_owned()], jobs);
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct ListUtils {
+    client: Client,
+    push_unique: MultiKeyAtomic,
+    move_chunk: MultiKeyAtomic,
+    truncate: MultiKeyAtomic,
+}
+
+impl ListUtils {
+    /// Create a new set of list utilities
+    #[must_use]
+    pub fn new(client: Client) -> Self {
+        Self {
+            push_unique: MultiKeyAtomic::new(client.clone(), PUSH_UNIQUE_SCRIPT),
+            move_chunk: MultiKeyAtomic::new(client.clone(), MOVE_CHUNK_SCRIPT),
+            truncate: MultiKeyAtomic::new(client.clone(), TRUNCATE_SCRIPT),
+            client,
+        }
+    }
+
+    /// Push `element` at the tail of the list stored at `key`,
+    /// only if the list does not contain it yet
+    ///
+    /// The lookup ([`LPOS`](https://redis.io/commands/lpos/)) and the push are executed atomically
+    /// by a Lua script. The lookup is `O(N)`, `N` being the length of the list.
+    ///
+    /// # Return
+    /// `true` if `element` has been pushed, `false` if the list already contains it
+    pub async fn push_unique(&self, key: impl SingleArg, element: impl SingleArg) -> Result<bool> {
+        self.push_unique.execute(key, element).await
+    }
+
+    /// Move all the elements of the list stored at `source` to the tail of the list stored at `destination`,
+    /// preserving their order
+    ///
+    /// The elements are moved with [`LMOVE`](https://redis.io/commands/lmove/),
+    /// by chunks of at most `count` elements, each chunk being moved atomically by a Lua script,
+    /// so that the server is never blocked for long on a large list
+    /// (a `count` of `0` is handled as `1`).
+    /// At most the length of `source` when the move starts are moved:
+    /// elements pushed to `source` in the meantime are left in it.
+    ///
+    /// # Return
+    /// The number of elements moved
+    ///
+    /// # Errors
+    /// An [`Error::Client`] if `source` and `destination` are the same list,
+    /// or if the client is connected to a cluster
+    /// and `source` and `destination` do not share the same hash tag.
+    pub async fn move_all(
+        &self,
+        source: impl SingleArg,
+        destination: impl SingleArg,
+        count: usize,
+    ) -> Result<usize> {
+        let count = count.max(1);
+        let keys = CommandArgs::default().arg(source).arg(destination).build();
+        let keys: Vec<&[u8]> = keys.into_iter().collect();
+        if keys[0] == keys[1] {
+            return Err(Error::Client(
+                "Cannot move the elements of a list to itself".to_owned(),
+            ));
+        }
+
+        let len = self.client.llen(keys[0]).await?;
+        let mut total = 0;
+
+        while total < len {
+            let chunk = count.min(len - total);
+            let moved: usize = self.move_chunk.execute(keys.clone(), chunk).await?;
+            total += moved;
+            if moved < chunk {
+                break;
+            }
+        }
+
+        Ok(total)
+    }
+
+    /// Shorten the list stored at `key` to its first `max_len` elements
+    ///
+    /// The length is read and the list is trimmed atomically by a Lua script.
+    ///
+    /// # Return
+    /// The number of elements removed
+    pub async fn truncate(&self, key: impl SingleArg, max_len: usize) -> Result<usize> {
+        self.truncate.execute(key, max_len).await
+    }
+}
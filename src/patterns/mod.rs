@@ -0,0 +1,74 @@
+/*!
+Ready-to-use implementations of common Redis patterns, built on top of the [`Client`](crate::client::Client).
+
+* [`CacheAside`] - [Cache-aside](https://redis.io/docs/manual/client-side-caching/) helper
+  with stampede protection and optional stale-while-revalidate.
+* [`CacheWarmer`] - Rate-limited warm-up of the keys of a replica or of a freshly failed-over master,
+  with progress reporting and abort handle.
+* [`EvictionSimulator`] - Estimate of the keys an eviction policy would evict,
+  based on a sample of the keys of a database, for capacity planning.
+* [`FunctionLibrary`] - [Redis Functions](https://redis.io/docs/interact/programmability/functions-intro/)
+  library declared in Rust with a version, loaded or replaced only when outdated, on all the masters of a cluster.
+* [`HashFieldCache`] - Near-cache of the fields of [hashes](https://redis.io/docs/data-types/hashes/),
+  invalidated by [client-side caching](https://redis.io/docs/manual/client-side-caching/) tracking.
+* [`Leaderboard`] - Daily, weekly and monthly leaderboards backed by [sorted sets](https://redis.io/docs/data-types/sorted-sets/),
+  with rank windows around a member, percentiles and rollups.
+* [`ListUtils`] - Maintenance of [lists](https://redis.io/docs/data-types/lists/):
+  deduplicating push, chunked move of all the elements and truncation.
+* [`MultiKeyAtomic`] - Atomic operation on multiple keys, implemented by a Lua script
+  with cached SHA1 digest, for compare-and-swap like patterns.
+* [`MultiQueueConsumer`] - Consumer of multiple job queues with [`BLMPOP`](https://redis.io/commands/blmpop/),
+  by priority or in round-robin.
+* [`ReliableQueue`] - [Reliable queue](https://redis.io/commands/lmove/#pattern-reliable-queue)
+  where messages are kept in a processing list until acknowledged,
+  and are requeued if their consumer does not acknowledge them in time.
+* [`ReplayableChannel`] - Pub/Sub channel backed by a [stream](https://redis.io/docs/data-types/streams/),
+  whose subscribers replay the messages they have missed, for gap-free, at-least-once messaging.
+* [`SearchReindexer`] - (`redis-search` feature only) Zero-downtime reindexing
+  of a [RediSearch](https://redis.io/docs/stack/search/) index queried through an alias.
+* [`StreamMaintainer`] - Trimming of [streams](https://redis.io/docs/data-types/streams/)
+  by length or by age, one-shot or periodic.
+* [`StreamMonitor`] - Lag monitoring of the consumer groups of [streams](https://redis.io/docs/data-types/streams/),
+  and of their consumers.
+* [`TaggedQuery`] - Filtered listing of the members shared by multiple tag [sets](https://redis.io/docs/data-types/sets/),
+  counted with `SINTERCARD` and paginated from a temporary set.
+* [`UniqueCounter`] - Count of unique elements per hourly or daily bucket, with rolling unions,
+  backed by [HyperLogLogs](https://redis.io/docs/data-types/hyperloglogs/).
+*/
+
+mod cache_aside;
+mod cache_warmer;
+mod eviction_simulator;
+mod function_library;
+mod hash_field_cache;
+mod leaderboard;
+mod list_utils;
+mod multi_key_atomic;
+mod multi_queue_consumer;
+mod reliable_queue;
+mod replayable_channel;
+#[cfg(feature = "redis-search")]
+mod search_reindexer;
+mod stream_maintainer;
+mod stream_monitor;
+mod tagged_query;
+mod unique_counter;
+
+pub use cache_aside::*;
+pub use cache_warmer::*;
+pub use eviction_simulator::*;
+pub use function_library::*;
+pub use hash_field_cache::*;
+pub use leaderboard::*;
+pub use list_utils::*;
+pub use multi_key_atomic::*;
+pub use multi_queue_consumer::*;
+pub use reliable_queue::*;
+pub use replayable_channel::*;
+#[cfg(feature = "redis-search")]
+#[cfg_attr(docsrs, doc(cfg(feature = "redis-search")))]
+pub use search_reindexer::*;
+pub use stream_maintainer::*;
+pub use stream_monitor::*;
+pub use tagged_query::*;
+pub use unique_counter::*;
@@ -0,0 +1,163 @@
+use crate::{
+    client::Client,
+    cluster::extract_hash_tag,
+    commands::{CallBuilder, ScriptingCommands},
+    resp::{CommandArgs, SingleArg, SingleArgCollection},
+    Error, RedisErrorKind, Result,
+};
+use serde::de::DeserializeOwned;
+use std::sync::{Arc, Mutex};
+
+/// Atomic operation on multiple keys, implemented by a [Lua script](https://redis.io/docs/interact/programmability/eval-intro/).
+///
+/// The script is executed with [`EVALSHA`](https://redis.io/commands/evalsha/),
+/// the keys being bound to `KEYS` and the arguments to `ARGV`.
+/// The SHA1 digest of the script is cached on the first execution,
+/// and the script is sent again with [`EVAL`](https://redis.io/commands/eval/)
+/// if the server does not know it anymore (script cache flushed, or other node of a cluster).
+///
+/// On a cluster, all the keys of an execution must share the same hash tag (e.g. `{account:12}.balance`),
+/// so that they are stored in the same hash slot.
+///
+/// Cloning a `MultiKeyAtomic` shares its cached SHA1 digest.
+///
+/// # Example
+/// ```
+/// use rustis::{client::Client, commands::StringCommands, patterns::MultiKeyAtomic, Result};
+///
+/// /// Compare-and-swap: set `KEYS[1]` to `ARGV[2]` if it is equal to `ARGV[1]`,
+/// /// and keep track of the previous value in `KEYS[2]`
+/// const CAS_SCRIPT: &str = r#"
+/// if redis.call('GET', KEYS[1]) ~= ARGV[1] then return 0 end
+/// redis.call('SET', KEYS[2], ARGV[1])
+/// redis.call('SET', KEYS[1], ARGV[2])
+/// return 1
+/// "#;
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let client = Client::connect("127.0.0.1:6379").await?;
+///     client.set("{account:12}.balance", 100).await?;
+///
+///     let cas = MultiKeyAtomic::new(client.clone(), CAS_SCRIPT);
+///     let swapped: bool = cas
+///         .execute(["{account:12}.balance", "{account:12}.previous"], [100, 80])
+///         .await?;
+///     assert!(swapped);
+///
+///     let balance: i64 = client.get("{account:12}.balance").await?;
+///     assert_eq!(80, balance);
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct MultiKeyAtomic {
+    client: Client,
+    script: Arc<str>,
+    sha1: Arc<Mutex<Option<String>>>,
+}
+
+impl MultiKeyAtomic {
+    /// Create a new atomic operation executing the Lua `script`
+    #[must_use]
+    pub fn new(client: Client, script: impl Into<String>) -> Self {
+        Self {
+            client,
+            script: script.into().into(),
+            sha1: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Execute the script with the given `keys` and `args`
+    ///
+    /// # Return
+    /// The return value of the script, deserialized to `R`
+    ///
+    /// # Errors
+    /// An [`Error::Client`] if the client is connected to a cluster
+    /// and the keys do not share the same hash tag.
+    pub async fn execute<K, KK, A, AA, R>(&self, keys: KK, args: AA) -> Result<R>
+    where
+        K: SingleArg,
+        KK: SingleArgCollection<K>,
+        A: SingleArg,
+        AA: SingleArgCollection<A>,
+        R: DeserializeOwned + Send,
+    {
+        let keys = CommandArgs::default().arg(keys).build();
+        let args = CommandArgs::default().arg(args).build();
+        let keys: Vec<&[u8]> = keys.into_iter().collect();
+        let args: Vec<&[u8]> = args.into_iter().collect();
+
+        if self.client.is_cluster() {
+            check_hash_tags(&keys, "multi-key atomic operation")?;
+        }
+
+        let sha1 = self.sha1().await?;
+        let result = self
+            .client
+            .evalsha(
+                CallBuilder::sha1(sha1)
+                    .keys(keys.clone())
+                    .args(args.clone()),
+            )
+            .await;
+
+        match result {
+            Err(Error::Redis(e)) if e.kind == RedisErrorKind::NoScript => {
+                self.client
+                    .eval(
+                        CallBuilder::script(self.script.as_ref())
+                            .keys(keys)
+                            .args(args),
+                    )
+                    .await
+            }
+            result => result,
+        }
+    }
+
+    /// SHA1 digest of the script, loaded on the first call
+    async fn sha1(&self) -> Result<String> {
+        if let Some(sha1) = self.sha1.lock().unwrap().as_ref() {
+            return Ok(sha1.clone());
+        }
+
+        let sha1: String = self.client.script_load(self.script.as_ref()).await?;
+        *self.sha1.lock().unwrap() = Some(sha1.clone());
+        Ok(sha1)
+    }
+}
+
+/// Check that multiple keys share the same hash tag, as required by an `operation` on a cluster
+pub(crate) fn check_hash_tags(keys: &[&[u8]], operation: &str) -> Result<()> {
+    if keys.len() < 2 {
+        return Ok(());
+    }
+
+    let mut hash_tag = None;
+    for key in keys.iter().copied() {
+        let Some(key_hash_tag) = extract_hash_tag(key) else {
+            return Err(Error::Client(format!(
+                "Key `{}` has no hash tag: the keys of a {operation} must share the same hash tag on a cluster",
+                String::from_utf8_lossy(key)
+            )));
+        };
+
+        match hash_tag {
+            None => hash_tag = Some(key_hash_tag),
+            Some(hash_tag) if hash_tag != key_hash_tag => {
+                return Err(Error::Client(format!(
+                    "Key `{}` has a different hash tag than key `{}`: the keys of a {operation} must share the same hash tag on a cluster",
+                    String::from_utf8_lossy(key),
+                    String::from_utf8_lossy(keys[0])
+                )));
+            }
+            Some(_) => (),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,265 @@
+use crate::{
+    client::Client,
+    commands::{BlockingCommands, LMoveWhere},
+    network::sleep,
+    resp::PrimitiveResponse,
+    Result, Version,
+};
+use futures_util::{stream::BoxStream, StreamExt};
+use serde::de::DeserializeOwned;
+use std::{
+    marker::PhantomData,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+const DEFAULT_BLOCK_TIMEOUT: Duration = Duration::from_secs(1);
+/// Delay before popping again after a first error, doubled after each consecutive error
+const ERROR_BACKOFF: Duration = Duration::from_millis(100);
+const MAX_ERROR_BACKOFF: Duration = Duration::from_secs(10);
+/// First version supporting [`BLMPOP`](https://redis.io/commands/blmpop/)
+const BLMPOP_VERSION: Version = Version::new(7, 0, 0);
+
+/// Order in which the queues of a [`MultiQueueConsumer`] are checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QueueOrdering {
+    /// Queues are always checked in the order they were given:
+    /// an item is popped from a queue only when all the previous queues are empty
+    #[default]
+    Priority,
+    /// The first queue checked rotates at each pop,
+    /// so that a busy queue cannot starve the other ones
+    RoundRobin,
+}
+
+/// An item popped by a [`MultiQueueConsumer`], tagged with its source queue
+#[derive(Debug)]
+pub struct QueueItem<T> {
+    /// Key of the list the item was popped from
+    pub queue: String,
+    /// Popped item
+    pub payload: T,
+}
+
+/// Consumer of multiple [lists](https://redis.io/docs/data-types/lists/) used as job queues.
+///
+/// Items are popped with [`BLMPOP`](https://redis.io/commands/blmpop/),
+/// which blocks until one of the queues is not empty, and are tagged with their source queue.
+/// On servers older than 7.0, [`BLPOP`](https://redis.io/commands/blpop/)
+/// or [`BRPOP`](https://redis.io/commands/brpop/) is used instead, popping a single item at a time.
+///
+/// Several consumers can pop from the same queues: each item is delivered to a single consumer,
+/// the first one available.
+///
+/// In a cluster, all the queues must share the same hash slot, e.g. with a hash tag.
+///
+/// Blocking commands block the whole multiplexed connection of a [`Client`]:
+/// consumers should use a dedicated client.
+///
+/// # Example
+/// ```
+/// use futures_util::StreamExt;
+/// use rustis::{
+///     client::Client,
+///     commands::{GenericCommands, ListCommands},
+///     patterns::MultiQueueConsumer,
+///     Result,
+/// };
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let producer = Client::connect("127.0.0.1:6379").await?;
+///     producer.del(["jobs:high", "jobs:low"]).await?;
+///     producer.lpush("jobs:low", "job1").await?;
+///     producer.lpush("jobs:high", "job2").await?;
+///
+///     let client = Client::connect("127.0.0.1:6379").await?;
+///     let consumer = MultiQueueConsumer::<String>::new(client, ["jobs:high", "jobs:low"]);
+///
+///     let mut items = consumer.consume();
+///     let item = items.next().await.unwrap()?;
+///     assert_eq!("jobs:high", item.queue);
+///     assert_eq!("job2", item.payload);
+///
+///     Ok(())
+/// }
+/// ```
+pub struct MultiQueueConsumer<T> {
+    client: Client,
+    keys: Vec<String>,
+    ordering: QueueOrdering,
+    side: LMoveWhere,
+    batch_size: usize,
+    block_timeout: Duration,
+    /// index of the first queue to check, in round-robin ordering
+    next_queue: AtomicUsize,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for MultiQueueConsumer<T> {
+    fn clone(&self) -> Self {
+        Self {
+            client: self.client.clone(),
+            keys: self.keys.clone(),
+            ordering: self.ordering,
+            side: self.side,
+            batch_size: self.batch_size,
+            block_timeout: self.block_timeout,
+            next_queue: AtomicUsize::new(self.next_queue.load(Ordering::Relaxed)),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> MultiQueueConsumer<T>
+where
+    T: PrimitiveResponse + DeserializeOwned + Send + 'static,
+{
+    /// Create a new consumer of the lists `keys`, given by decreasing priority
+    #[must_use]
+    pub fn new<K: Into<String>>(client: Client, keys: impl IntoIterator<Item = K>) -> Self {
+        Self {
+            client,
+            keys: keys.into_iter().map(Into::into).collect(),
+            ordering: QueueOrdering::Priority,
+            side: LMoveWhere::Right,
+            batch_size: 1,
+            block_timeout: DEFAULT_BLOCK_TIMEOUT,
+            next_queue: AtomicUsize::new(0),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Order in which the queues are checked (default [`QueueOrdering::Priority`])
+    #[must_use]
+    pub fn ordering(mut self, ordering: QueueOrdering) -> Self {
+        self.ordering = ordering;
+        self
+    }
+
+    /// Side of the lists items are popped from (default [`LMoveWhere::Right`])
+    ///
+    /// Popping from the right side of lists filled with [`lpush`](crate::commands::ListCommands::lpush)
+    /// processes items in FIFO order.
+    #[must_use]
+    pub fn pop_from(mut self, side: LMoveWhere) -> Self {
+        self.side = side;
+        self
+    }
+
+    /// Maximum number of items popped at once from a queue by [`pop`](MultiQueueConsumer::pop) (default 1)
+    ///
+    /// Ignored on servers older than 7.0, which pop a single item at a time,
+    /// and by [`consume`](MultiQueueConsumer::consume).
+    #[must_use]
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Maximum blocking time of a single pop (default 1 second)
+    #[must_use]
+    pub fn block_timeout(mut self, block_timeout: Duration) -> Self {
+        self.block_timeout = block_timeout;
+        self
+    }
+
+    /// Wait for items, up to the [`block_timeout`](MultiQueueConsumer::block_timeout)
+    ///
+    /// # Return
+    /// Up to [`batch_size`](MultiQueueConsumer::batch_size) items popped from the same queue,
+    /// or an empty collection if the timeout has been reached
+    pub async fn pop(&self) -> Result<Vec<QueueItem<T>>> {
+        self.pop_count(self.batch_size).await
+    }
+
+    /// Infinite stream of popped items
+    ///
+    /// Items are popped one at a time, when the stream is polled,
+    /// so that no popped item is lost when the stream is dropped.
+    /// A failed pop produces an error item, the consumption goes on,
+    /// with a delay doubled after each consecutive error, up to 10 seconds.
+    pub fn consume(&self) -> BoxStream<'static, Result<QueueItem<T>>> {
+        futures_util::stream::unfold(
+            (self.clone(), None),
+            |(consumer, backoff): (Self, Option<Duration>)| async move {
+                if let Some(backoff) = backoff {
+                    sleep(backoff).await;
+                }
+
+                loop {
+                    match consumer.pop_count(1).await {
+                        Ok(mut popped) => {
+                            if let Some(item) = popped.pop() {
+                                return Some((Ok(item), (consumer, None)));
+                            }
+                        }
+                        Err(e) => {
+                            let backoff = backoff
+                                .map_or(ERROR_BACKOFF, |backoff| backoff.saturating_mul(2))
+                                .min(MAX_ERROR_BACKOFF);
+                            return Some((Err(e), (consumer, Some(backoff))));
+                        }
+                    }
+                }
+            },
+        )
+        .boxed()
+    }
+
+    async fn pop_count(&self, count: usize) -> Result<Vec<QueueItem<T>>> {
+        let keys = self.ordered_keys();
+        let timeout = self.block_timeout.as_secs_f64();
+
+        if self.supports_blmpop() {
+            let result: Option<(String, Vec<T>)> =
+                self.client.blmpop(timeout, keys, self.side, count).await?;
+
+            Ok(result
+                .map(|(queue, payloads)| {
+                    payloads
+                        .into_iter()
+                        .map(|payload| QueueItem {
+                            queue: queue.clone(),
+                            payload,
+                        })
+                        .collect()
+                })
+                .unwrap_or_default())
+        } else {
+            let result: Option<(String, T)> = match self.side {
+                LMoveWhere::Left => self.client.blpop(keys, timeout).await?,
+                LMoveWhere::Right => self.client.brpop(keys, timeout).await?,
+            };
+
+            Ok(result
+                .map(|(queue, payload)| QueueItem { queue, payload })
+                .into_iter()
+                .collect())
+        }
+    }
+
+    fn ordered_keys(&self) -> Vec<&str> {
+        let start = match self.ordering {
+            QueueOrdering::Priority => 0,
+            QueueOrdering::RoundRobin if self.keys.is_empty() => 0,
+            QueueOrdering::RoundRobin => {
+                self.next_queue.fetch_add(1, Ordering::Relaxed) % self.keys.len()
+            }
+        };
+
+        self.keys[start..]
+            .iter()
+            .chain(&self.keys[..start])
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// `BLMPOP` is assumed to be supported when the server version is unknown
+    fn supports_blmpop(&self) -> bool {
+        self.client
+            .server_version()
+            .is_none_or(|version| version >= BLMPOP_VERSION)
+    }
+}
@@ -0,0 +1,342 @@
+use crate::{
+    client::{BatchPreparedCommand, Client},
+    commands::{
+        BlockingCommands, CallBuilder, GenericCommands, LMoveWhere, ListCommands,
+        ScriptingCommands, SortedSetCommands, ZAddOptions,
+    },
+    Error, Result,
+};
+use futures_util::{stream::BoxStream, StreamExt};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    marker::PhantomData,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, SystemTime},
+};
+
+const DEFAULT_VISIBILITY_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_BLOCK_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Requeue the entries of the processing list whose deadline has expired.
+///
+/// Entries of the processing list without deadline
+/// (consumer which stopped between `BLMOVE` and `ZADD`) are given one.
+const RECOVER_SCRIPT: &str = r#"
+local now = tonumber(ARGV[1])
+local visibility_timeout = tonumber(ARGV[2])
+for _, entry in ipairs(redis.call('LRANGE', KEYS[2], 0, -1)) do
+    if not redis.call('ZSCORE', KEYS[3], entry) then
+        redis.call('ZADD', KEYS[3], now + visibility_timeout, entry)
+    end
+end
+local requeued = 0
+for _, entry in ipairs(redis.call('ZRANGEBYSCORE', KEYS[3], '-inf', now)) do
+    if redis.call('LREM', KEYS[2], 1, entry) > 0 then
+        redis.call('RPUSH', KEYS[1], entry)
+        requeued = requeued + 1
+    end
+    redis.call('ZREM', KEYS[3], entry)
+end
+return requeued
+"#;
+
+static ENTRY_ID_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Serialize, Deserialize)]
+struct Envelope<T> {
+    id: String,
+    payload: T,
+}
+
+/// A message delivered by a [`ReliableQueue`], to acknowledge once processed
+/// with [`ReliableQueue::ack`].
+#[derive(Debug)]
+pub struct Delivery<T> {
+    /// Payload of the message
+    pub payload: T,
+    /// Encoded entry in the processing list
+    entry: String,
+}
+
+/// Implementation of the [reliable queue](https://redis.io/commands/lmove/#pattern-reliable-queue) pattern.
+///
+/// * Producers push messages at the head of the queue list.
+/// * Consumers atomically move messages from the tail of the queue list to a processing list
+///   with [`BLMOVE`](https://redis.io/commands/blmove/), and acknowledge them once processed.
+/// * Messages which are not acknowledged before their visibility timeout
+///   (crashed or stuck consumer) are requeued by [`recover`](ReliableQueue::recover),
+///   which should be called periodically.
+/// * Messages which cannot be decoded are moved to a dead letter list
+///   instead of being requeued forever.
+///
+/// Messages are encoded in JSON, along with a unique identifier,
+/// so that identical payloads can be handled independently.
+///
+/// All the keys of a queue share the same hash tag, so the queue can be used with a cluster.
+///
+/// Blocking commands block the whole multiplexed connection of a [`Client`]:
+/// consumers should use a dedicated client.
+///
+/// # Example
+/// ```
+/// use futures_util::StreamExt;
+/// use rustis::{client::Client, patterns::ReliableQueue, Result};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, Serialize, Deserialize)]
+/// struct Job {
+///     id: u32,
+/// }
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let producer = ReliableQueue::<Job>::new(Client::connect("127.0.0.1:6379").await?, "jobs");
+///     let consumer = ReliableQueue::<Job>::new(Client::connect("127.0.0.1:6379").await?, "jobs");
+///     producer.purge().await?;
+///
+///     producer.push(&Job { id: 12 }).await?;
+///
+///     let mut deliveries = consumer.consume();
+///     if let Some(delivery) = deliveries.next().await {
+///         let delivery = delivery?;
+///         assert_eq!(12, delivery.payload.id);
+///         consumer.ack(&delivery).await?;
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+pub struct ReliableQueue<T> {
+    client: Client,
+    queue_key: String,
+    processing_key: String,
+    deadlines_key: String,
+    dead_key: String,
+    visibility_timeout: Duration,
+    block_timeout: Duration,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for ReliableQueue<T> {
+    fn clone(&self) -> Self {
+        Self {
+            client: self.client.clone(),
+            queue_key: self.queue_key.clone(),
+            processing_key: self.processing_key.clone(),
+            deadlines_key: self.deadlines_key.clone(),
+            dead_key: self.dead_key.clone(),
+            visibility_timeout: self.visibility_timeout,
+            block_timeout: self.block_timeout,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> ReliableQueue<T>
+where
+    T: Serialize + DeserializeOwned + Send + 'static,
+{
+    /// Create a new queue named `name`
+    ///
+    /// The queue uses the keys `{name}:queue`, `{name}:processing`, `{name}:deadlines` & `{name}:dead`
+    #[must_use]
+    pub fn new(client: Client, name: impl AsRef<str>) -> Self {
+        let name = name.as_ref();
+        Self {
+            client,
+            queue_key: format!("{{{name}}}:queue"),
+            processing_key: format!("{{{name}}}:processing"),
+            deadlines_key: format!("{{{name}}}:deadlines"),
+            dead_key: format!("{{{name}}}:dead"),
+            visibility_timeout: DEFAULT_VISIBILITY_TIMEOUT,
+            block_timeout: DEFAULT_BLOCK_TIMEOUT,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Maximum processing time of a message before it is requeued by [`recover`](ReliableQueue::recover)
+    /// (default 30 seconds)
+    #[must_use]
+    pub fn visibility_timeout(mut self, visibility_timeout: Duration) -> Self {
+        self.visibility_timeout = visibility_timeout;
+        self
+    }
+
+    /// Maximum blocking time of a single `BLMOVE` command (default 1 second)
+    #[must_use]
+    pub fn block_timeout(mut self, block_timeout: Duration) -> Self {
+        self.block_timeout = block_timeout;
+        self
+    }
+
+    /// Push a message in the queue
+    pub async fn push(&self, payload: &T) -> Result<()> {
+        let entry = serde_json::to_string(&Envelope {
+            id: new_entry_id(),
+            payload,
+        })
+        .map_err(|e| Error::Client(e.to_string()))?;
+
+        self.client.lpush(&self.queue_key, entry).await?;
+        Ok(())
+    }
+
+    /// Wait for a message, up to the [`block_timeout`](ReliableQueue::block_timeout)
+    ///
+    /// # Return
+    /// The delivered message, or `None` if the timeout has been reached
+    ///
+    /// # Errors
+    /// A message which cannot be decoded is moved to the dead letter list
+    /// (see [`dead_len`](ReliableQueue::dead_len)) and an [`Error::Client`] is returned.
+    pub async fn pop(&self) -> Result<Option<Delivery<T>>> {
+        let entry: Option<String> = self
+            .client
+            .blmove(
+                &self.queue_key,
+                &self.processing_key,
+                LMoveWhere::Right,
+                LMoveWhere::Left,
+                self.block_timeout.as_secs_f64(),
+            )
+            .await?;
+
+        let Some(entry) = entry else {
+            return Ok(None);
+        };
+
+        let deadline = now_millis() + self.visibility_timeout.as_millis() as f64;
+        self.client
+            .zadd(
+                &self.deadlines_key,
+                (deadline, entry.as_str()),
+                ZAddOptions::default(),
+            )
+            .await?;
+
+        let envelope: Envelope<T> = match serde_json::from_str(&entry) {
+            Ok(envelope) => envelope,
+            Err(e) => {
+                self.bury(&entry).await?;
+                return Err(Error::Client(e.to_string()));
+            }
+        };
+
+        Ok(Some(Delivery {
+            payload: envelope.payload,
+            entry,
+        }))
+    }
+
+    /// Infinite stream of delivered messages
+    ///
+    /// Each message must be acknowledged with [`ack`](ReliableQueue::ack) once processed.
+    pub fn consume(&self) -> BoxStream<'static, Result<Delivery<T>>> {
+        futures_util::stream::unfold(self.clone(), |queue| async move {
+            loop {
+                match queue.pop().await {
+                    Ok(Some(delivery)) => return Some((Ok(delivery), queue)),
+                    Ok(None) => continue,
+                    Err(e) => return Some((Err(e), queue)),
+                }
+            }
+        })
+        .boxed()
+    }
+
+    /// Acknowledge a processed message, removing it from the processing list
+    ///
+    /// # Return
+    /// `false` if the message was not in the processing list anymore
+    /// (already acknowledged, or requeued after its visibility timeout)
+    pub async fn ack(&self, delivery: &Delivery<T>) -> Result<bool> {
+        let mut transaction = self.client.create_transaction();
+        transaction
+            .lrem(&self.processing_key, 1, &delivery.entry)
+            .queue();
+        transaction
+            .zrem(&self.deadlines_key, &delivery.entry)
+            .forget();
+        let removed: usize = transaction.execute().await?;
+        Ok(removed > 0)
+    }
+
+    /// Move an undecodable entry from the processing list to the dead letter list,
+    /// so that it is never requeued by [`recover`](ReliableQueue::recover)
+    async fn bury(&self, entry: &str) -> Result<()> {
+        let mut transaction = self.client.create_transaction();
+        transaction.lrem(&self.processing_key, 1, entry).forget();
+        transaction.zrem(&self.deadlines_key, entry).forget();
+        transaction.rpush(&self.dead_key, entry).forget();
+        transaction.execute::<()>().await
+    }
+
+    /// Requeue the messages which have not been acknowledged before their visibility timeout
+    ///
+    /// This function should be called periodically, by any producer or consumer.
+    ///
+    /// # Return
+    /// The number of requeued messages
+    pub async fn recover(&self) -> Result<usize> {
+        self.client
+            .eval(
+                CallBuilder::script(RECOVER_SCRIPT)
+                    .keys([
+                        self.queue_key.as_str(),
+                        self.processing_key.as_str(),
+                        self.deadlines_key.as_str(),
+                    ])
+                    .args([
+                        now_millis().to_string(),
+                        self.visibility_timeout.as_millis().to_string(),
+                    ]),
+            )
+            .await
+    }
+
+    /// Number of messages waiting in the queue
+    pub async fn len(&self) -> Result<usize> {
+        self.client.llen(&self.queue_key).await
+    }
+
+    /// Number of messages delivered and not yet acknowledged
+    pub async fn processing_len(&self) -> Result<usize> {
+        self.client.llen(&self.processing_key).await
+    }
+
+    /// Number of messages which could not be decoded, in the dead letter list `{name}:dead`
+    pub async fn dead_len(&self) -> Result<usize> {
+        self.client.llen(&self.dead_key).await
+    }
+
+    /// Delete all the messages of the queue, including messages being processed
+    /// and dead letters
+    pub async fn purge(&self) -> Result<()> {
+        self.client
+            .del([
+                self.queue_key.as_str(),
+                self.processing_key.as_str(),
+                self.deadlines_key.as_str(),
+                self.dead_key.as_str(),
+            ])
+            .await?;
+        Ok(())
+    }
+}
+
+fn new_entry_id() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let seq = ENTRY_ID_SEQUENCE.fetch_add(1, Ordering::Relaxed);
+    format!("{nanos:x}-{:x}-{seq:x}", std::process::id())
+}
+
+fn now_millis() -> f64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as f64
+}
@@ -0,0 +1,315 @@
+use crate::{
+    client::{Client, PubSubStream},
+    commands::{
+        CallBuilder, GenericCommands, PubSubCommands, ScriptingCommands, StreamCommands,
+        StreamEntry,
+    },
+    Error, Result,
+};
+use futures_util::{stream::BoxStream, StreamExt};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{collections::VecDeque, marker::PhantomData};
+
+/// Number of entries read by each `XRANGE` command of a replay
+const REPLAY_BATCH_SIZE: usize = 100;
+
+/// Append the message to the stream and publish it, prefixed by its stream id.
+///
+/// `ARGV[2]` is the approximate maximum length of the stream, `0` for no trimming.
+const PUBLISH_SCRIPT: &str = r#"
+local id
+if ARGV[2] == '0' then
+    id = redis.call('XADD', KEYS[1], '*', 'payload', ARGV[1])
+else
+    id = redis.call('XADD', KEYS[1], 'MAXLEN', '~', ARGV[2], '*', 'payload', ARGV[1])
+end
+redis.call('PUBLISH', ARGV[3], id .. ' ' .. ARGV[1])
+return id
+"#;
+
+/// A message delivered by a [`ReplayableChannel`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelMessage<T> {
+    /// Id of the message in the backing stream,
+    /// to resume a subscription with [`ReplayableChannel::subscribe_from`]
+    pub id: String,
+    /// Payload of the message
+    pub payload: T,
+}
+
+/// Pub/Sub channel backed by a [stream](https://redis.io/docs/data-types/streams/),
+/// for gap-free, at-least-once messaging.
+///
+/// * Each published message is appended to the backing stream and published on the channel,
+///   atomically, by a Lua script.
+/// * A subscriber first subscribes to the channel, then replays with [`XRANGE`](https://redis.io/commands/xrange/)
+///   the messages of the stream following the last message it has seen,
+///   and finally switches to the messages received on the channel.
+///   Messages received both ways are delivered once, in the order of their stream id.
+/// * When the pub/sub connection is restored after a disconnection,
+///   the messages published in the meantime are replayed from the stream.
+///
+/// Messages are encoded in JSON. The stream can be capped with [`max_len`](ReplayableChannel::max_len):
+/// a subscriber resuming from a trimmed message misses the messages trimmed after it.
+///
+/// The channel uses the keys `{name}:stream` & the pub/sub channel `{name}:channel`.
+///
+/// # Example
+/// ```
+/// use futures_util::StreamExt;
+/// use rustis::{client::Client, patterns::ReplayableChannel, Result};
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let client = Client::connect("127.0.0.1:6379").await?;
+///     let channel = ReplayableChannel::<String>::new(client, "events");
+///     channel.purge().await?;
+///
+///     let id = channel.publish(&"created".to_owned()).await?;
+///
+///     // messages published before the subscription are replayed
+///     let mut messages = channel.subscribe_from("0").await?;
+///     channel.publish(&"updated".to_owned()).await?;
+///
+///     let message = messages.next().await.unwrap()?;
+///     assert_eq!(id, message.id);
+///     assert_eq!("created", message.payload);
+///
+///     let message = messages.next().await.unwrap()?;
+///     assert_eq!("updated", message.payload);
+///
+///     Ok(())
+/// }
+/// ```
+pub struct ReplayableChannel<T> {
+    client: Client,
+    stream_key: String,
+    channel: String,
+    max_len: usize,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for ReplayableChannel<T> {
+    fn clone(&self) -> Self {
+        Self {
+            client: self.client.clone(),
+            stream_key: self.stream_key.clone(),
+            channel: self.channel.clone(),
+            max_len: self.max_len,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> ReplayableChannel<T>
+where
+    T: Serialize + DeserializeOwned + Send + 'static,
+{
+    /// Create a new channel named `name`
+    #[must_use]
+    pub fn new(client: Client, name: impl AsRef<str>) -> Self {
+        let name = name.as_ref();
+        Self {
+            client,
+            stream_key: format!("{{{name}}}:stream"),
+            channel: format!("{{{name}}}:channel"),
+            max_len: 0,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Approximate maximum number of messages kept in the backing stream (default `0`, unlimited)
+    #[must_use]
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Publish a message
+    ///
+    /// # Return
+    /// The id of the message in the backing stream
+    pub async fn publish(&self, payload: &T) -> Result<String> {
+        let payload = serde_json::to_string(payload).map_err(|e| Error::Client(e.to_string()))?;
+        self.client
+            .eval(
+                CallBuilder::script(PUBLISH_SCRIPT)
+                    .keys([self.stream_key.as_str()])
+                    .args([payload, self.max_len.to_string(), self.channel.clone()]),
+            )
+            .await
+    }
+
+    /// Subscribe to the messages published from now on
+    ///
+    /// Messages published while the pub/sub connection is down are replayed from the backing stream.
+    pub async fn subscribe(&self) -> Result<BoxStream<'static, Result<ChannelMessage<T>>>> {
+        let last_entries: Vec<StreamEntry<String>> = self
+            .client
+            .xrevrange(&self.stream_key, "+", "-", Some(1))
+            .await?;
+        let last_id = last_entries
+            .into_iter()
+            .next()
+            .map_or_else(|| "0-0".to_owned(), |entry| entry.stream_id);
+
+        // replay the messages published before the subscription is effective
+        self.subscribe_from(last_id).await
+    }
+
+    /// Subscribe to the messages following the message `last_id`,
+    /// replaying first the messages already published
+    ///
+    /// `"0"` replays all the messages of the backing stream.
+    pub async fn subscribe_from(
+        &self,
+        last_id: impl AsRef<str>,
+    ) -> Result<BoxStream<'static, Result<ChannelMessage<T>>>> {
+        let pub_sub = self
+            .client
+            .subscribe(&self.channel)
+            .await?
+            .notify_interruptions(true);
+
+        let state = SubscriptionState {
+            channel: self.clone(),
+            pub_sub,
+            last_id: last_id.as_ref().to_owned(),
+            replaying: true,
+            replayed: VecDeque::new(),
+        };
+
+        Ok(futures_util::stream::unfold(state, |mut state| async move {
+            let result = state.next().await;
+            result.map(|result| (result, state))
+        })
+        .boxed())
+    }
+
+    /// Number of messages in the backing stream
+    pub async fn len(&self) -> Result<usize> {
+        self.client.xlen(&self.stream_key).await
+    }
+
+    /// Delete all the messages of the backing stream
+    pub async fn purge(&self) -> Result<()> {
+        self.client.del(&self.stream_key).await?;
+        Ok(())
+    }
+
+    /// Entries of the backing stream following `last_id`
+    async fn read_after(&self, last_id: &str) -> Result<Vec<ChannelMessage<T>>> {
+        let entries: Vec<StreamEntry<String>> = self
+            .client
+            .xrange(
+                &self.stream_key,
+                format!("({last_id}"),
+                "+",
+                Some(REPLAY_BATCH_SIZE),
+            )
+            .await?;
+
+        entries
+            .into_iter()
+            .map(|mut entry| {
+                let payload = entry.items.remove("payload").ok_or_else(|| {
+                    Error::Client(format!(
+                        "Missing payload in stream entry {}",
+                        entry.stream_id
+                    ))
+                })?;
+                Ok(ChannelMessage {
+                    id: entry.stream_id,
+                    payload: decode_payload(payload.as_bytes())?,
+                })
+            })
+            .collect()
+    }
+}
+
+struct SubscriptionState<T> {
+    channel: ReplayableChannel<T>,
+    pub_sub: PubSubStream,
+    /// Id of the last delivered message
+    last_id: String,
+    /// `true` while messages are read from the backing stream
+    replaying: bool,
+    replayed: VecDeque<ChannelMessage<T>>,
+}
+
+impl<T> SubscriptionState<T>
+where
+    T: Serialize + DeserializeOwned + Send + 'static,
+{
+    async fn next(&mut self) -> Option<Result<ChannelMessage<T>>> {
+        loop {
+            if let Some(message) = self.replayed.pop_front() {
+                self.last_id.clone_from(&message.id);
+                return Some(Ok(message));
+            }
+
+            if self.replaying {
+                match self.channel.read_after(&self.last_id).await {
+                    Ok(messages) => {
+                        self.replaying = messages.len() == REPLAY_BATCH_SIZE;
+                        self.replayed.extend(messages);
+                        continue;
+                    }
+                    Err(e) => return Some(Err(e)),
+                }
+            }
+
+            match self.pub_sub.next().await? {
+                Ok(message) => {
+                    let Some((id, payload)) = split_message(&message.payload) else {
+                        return Some(Err(Error::Client(
+                            "Cannot decode message without stream id".to_owned(),
+                        )));
+                    };
+
+                    if !is_after(id, &self.last_id) {
+                        // already replayed from the backing stream
+                        continue;
+                    }
+
+                    let message = decode_payload(payload).map(|payload| ChannelMessage {
+                        id: id.to_owned(),
+                        payload,
+                    });
+                    if let Ok(message) = &message {
+                        self.last_id.clone_from(&message.id);
+                    }
+                    return Some(message);
+                }
+                Err(Error::SubscriptionInterrupted { .. }) => {
+                    // catch up with the messages published while disconnected
+                    self.replaying = true;
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+fn decode_payload<T: DeserializeOwned>(payload: &[u8]) -> Result<T> {
+    serde_json::from_slice(payload).map_err(|e| Error::Client(e.to_string()))
+}
+
+/// Split a pub/sub message into its stream id and its payload
+pub(crate) fn split_message(message: &[u8]) -> Option<(&str, &[u8])> {
+    let separator = message.iter().position(|b| *b == b' ')?;
+    let id = std::str::from_utf8(&message[..separator]).ok()?;
+    Some((id, &message[separator + 1..]))
+}
+
+/// `true` if the stream id `id` is greater than `last_id`
+pub(crate) fn is_after(id: &str, last_id: &str) -> bool {
+    parse_stream_id(id) > parse_stream_id(last_id)
+}
+
+/// Parse a stream id `<ms>-<seq>` (`<ms>` alone meaning `<ms>-0`)
+fn parse_stream_id(id: &str) -> (u64, u64) {
+    let (millis, seq) = id.split_once('-').unwrap_or((id, "0"));
+    (millis.parse().unwrap_or(0), seq.parse().unwrap_or(0))
+}
@@ -0,0 +1,184 @@
+use crate::{
+    client::Client,
+    commands::{FtCreateOptions, FtFieldSchema, SearchCommands},
+    network::sleep,
+    resp::MultipleArgsCollection,
+    Error, Result,
+};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Outcome of a [`SearchReindexer::reindex`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reindex {
+    /// Name of the index the alias now points to
+    pub index: String,
+    /// Name of the index the alias pointed to before, which has been dropped
+    pub previous_index: Option<String>,
+}
+
+/// Zero-downtime reindexing of a [RediSearch](https://redis.io/docs/stack/search/) index
+/// queried through an [alias](https://redis.io/commands/ft.aliasadd/).
+///
+/// A [`reindex`](SearchReindexer::reindex):
+/// 1. creates a new index under a temporary name, `<alias>-<timestamp in milliseconds>`,
+/// 2. polls [`FT.INFO`](https://redis.io/commands/ft.info/) until the new index has scanned
+///    all the existing documents (`percent_indexed` reaching `1`),
+/// 3. atomically moves the alias to the new index with [`FT.ALIASUPDATE`](https://redis.io/commands/ft.aliasupdate/),
+/// 4. drops the index the alias pointed to before, without deleting its documents.
+///
+/// Queries sent to the alias are served by the previous index until the new one is ready.
+/// The alias must not be the name of an index.
+///
+/// # Example
+/// ```
+/// use rustis::{
+///     client::Client,
+///     commands::{FtCreateOptions, FtFieldSchema, FtFieldType, FtIndexDataType, SearchCommands},
+///     patterns::SearchReindexer,
+///     Result,
+/// };
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let client = Client::connect("127.0.0.1:6379").await?;
+///     let reindexer = SearchReindexer::new(client.clone());
+///
+///     let reindex = reindexer
+///         .reindex(
+///             "products",
+///             FtCreateOptions::default()
+///                 .on(FtIndexDataType::Hash)
+///                 .prefix("product:"),
+///             [FtFieldSchema::identifier("name").field_type(FtFieldType::Text)],
+///         )
+///         .await?;
+///
+///     // queries go through the alias
+///     let info = client.ft_info("products").await?;
+///     assert_eq!(reindex.index, info.index_name);
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct SearchReindexer {
+    client: Client,
+    poll_interval: Duration,
+    indexing_timeout: Option<Duration>,
+}
+
+impl SearchReindexer {
+    /// Create a new reindexer,
+    /// polling the indexing progress every 100ms without timeout
+    #[must_use]
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            poll_interval: Duration::from_millis(100),
+            indexing_timeout: None,
+        }
+    }
+
+    /// Interval between two polls of the indexing progress of the new index
+    #[must_use]
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Maximum time to wait for the new index to scan the existing documents.
+    ///
+    /// When expired, the new index is dropped and the alias is left untouched.
+    #[must_use]
+    pub fn indexing_timeout(mut self, indexing_timeout: Duration) -> Self {
+        self.indexing_timeout = Some(indexing_timeout);
+        self
+    }
+
+    /// Build a new index with `options` & `schema`, then move `alias` to it
+    /// and drop the index `alias` pointed to before
+    ///
+    /// # Errors
+    /// * An [`Error::Timeout`] if the new index has not scanned the existing documents
+    ///   within the [`indexing_timeout`](SearchReindexer::indexing_timeout)
+    /// * Any error returned by the server; the new index is then dropped
+    ///   if it has been created
+    pub async fn reindex<S>(
+        &self,
+        alias: &str,
+        options: FtCreateOptions,
+        schema: S,
+    ) -> Result<Reindex>
+    where
+        S: MultipleArgsCollection<FtFieldSchema>,
+    {
+        let previous_index = self.aliased_index(alias).await?;
+        let index = format!(
+            "{alias}-{}",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis()
+        );
+
+        self.client
+            .ft_create(index.as_str(), options, schema)
+            .await?;
+
+        let result = async {
+            self.wait_for_indexing(&index).await?;
+            self.client.ft_aliasupdate(alias, index.as_str()).await
+        }
+        .await;
+
+        if let Err(e) = result {
+            // leave the alias on the previous index
+            self.client.ft_dropindex(index.as_str(), false).await?;
+            return Err(e);
+        }
+
+        if let Some(previous_index) = &previous_index {
+            // documents are shared with the new index
+            self.client
+                .ft_dropindex(previous_index.as_str(), false)
+                .await?;
+        }
+
+        Ok(Reindex {
+            index,
+            previous_index,
+        })
+    }
+
+    /// Name of the index `alias` points to, `None` if the alias does not exist
+    async fn aliased_index(&self, alias: &str) -> Result<Option<String>> {
+        match self.client.ft_info(alias).await {
+            Ok(info) => Ok(Some(info.index_name)),
+            // unknown index or alias
+            Err(Error::Redis(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn wait_for_indexing(&self, index: &str) -> Result<()> {
+        let start = Instant::now();
+
+        loop {
+            let info = self.client.ft_info(index).await?;
+            if !info.indexing && info.percent_indexed >= 1. {
+                return Ok(());
+            }
+
+            if let Some(indexing_timeout) = self.indexing_timeout {
+                if start.elapsed() >= indexing_timeout {
+                    return Err(Error::Timeout(format!(
+                        "Index {index} has not been built within {indexing_timeout:?}"
+                    )));
+                }
+            }
+
+            sleep(self.poll_interval).await;
+        }
+    }
+}
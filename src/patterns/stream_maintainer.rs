@@ -0,0 +1,202 @@
+use crate::{
+    client::Client,
+    commands::{ServerCommands, StreamCommands, XTrimOperator, XTrimOptions},
+    network::sleep,
+    Result,
+};
+use futures_util::{stream::BoxStream, StreamExt};
+use rand::Rng;
+use std::time::Duration;
+
+/// Trimming strategy of a [`StreamMaintainer`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrimStrategy {
+    /// Keep the last `n` entries, with `XTRIM MAXLEN`
+    MaxLen(usize),
+    /// Evict the entries older than this age, with `XTRIM MINID`,
+    /// the minimum ID being computed from the server [`TIME`](https://redis.io/commands/time/)
+    MaxAge(Duration),
+    /// Evict the entries whose ID is lower than this ID, with `XTRIM MINID`
+    MinId(String),
+}
+
+/// Maintenance of the size of [streams](https://redis.io/docs/data-types/streams/)
+/// with [`XTRIM`](https://redis.io/commands/xtrim/), one-shot or periodic.
+///
+/// Streams are trimmed approximately (`~`) by default: the server only evicts whole
+/// macro nodes, which is much more efficient than an [exact](StreamMaintainer::exact) trimming
+/// but may keep a few more entries than requested.
+///
+/// # Example
+/// ```
+/// use futures_util::StreamExt;
+/// use rustis::{
+///     client::Client,
+///     commands::{StreamCommands, XAddOptions},
+///     patterns::{StreamMaintainer, TrimStrategy},
+///     Result,
+/// };
+/// use std::time::Duration;
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let client = Client::connect("127.0.0.1:6379").await?;
+///     let _id: String = client
+///         .xadd("events", "*", [("type", "signup")], XAddOptions::default())
+///         .await?;
+///
+///     let maintainer = StreamMaintainer::new(client);
+///     // entries older than a day
+///     let _trimmed = maintainer
+///         .trim_by_age("events", Duration::from_secs(24 * 3600))
+///         .await?;
+///
+///     // keep about the last 10,000 entries, every minute
+///     let mut trimmed = maintainer.trim_periodically(
+///         "events",
+///         TrimStrategy::MaxLen(10_000),
+///         Duration::from_secs(60),
+///     );
+///     let _trimmed = trimmed.next().await.unwrap()?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct StreamMaintainer {
+    client: Client,
+    exact: bool,
+    limit: Option<usize>,
+    max_backoff: Duration,
+}
+
+impl StreamMaintainer {
+    /// Create a new maintainer trimming approximately,
+    /// without limit on the number of entries evicted at once,
+    /// backing off up to 5 minutes on errors
+    #[must_use]
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            exact: false,
+            limit: None,
+            max_backoff: Duration::from_secs(300),
+        }
+    }
+
+    /// Trim exactly (`=`) instead of approximately (`~`)
+    #[must_use]
+    pub fn exact(mut self) -> Self {
+        self.exact = true;
+        self
+    }
+
+    /// Maximum number of entries evicted by a single `XTRIM` (`LIMIT` option),
+    /// to bound the time the server spends trimming
+    ///
+    /// The server rejects this option with an [exact](StreamMaintainer::exact) trimming.
+    #[must_use]
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Maximum delay between two trimmings of
+    /// [`trim_periodically`](StreamMaintainer::trim_periodically) after consecutive errors
+    #[must_use]
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Trim `stream` according to `strategy`
+    ///
+    /// # Return
+    /// The number of entries evicted from the stream
+    pub async fn trim(&self, stream: &str, strategy: &TrimStrategy) -> Result<usize> {
+        let operator = if self.exact {
+            XTrimOperator::Equal
+        } else {
+            XTrimOperator::Approximately
+        };
+        let options = match strategy {
+            TrimStrategy::MaxLen(max_len) => XTrimOptions::max_len(operator, *max_len as i64),
+            TrimStrategy::MaxAge(max_age) => {
+                XTrimOptions::min_id(operator, self.min_id(*max_age).await?)
+            }
+            TrimStrategy::MinId(min_id) => XTrimOptions::min_id(operator, min_id.as_str()),
+        };
+        let options = match self.limit {
+            Some(limit) => options.limit(limit),
+            None => options,
+        };
+
+        self.client.xtrim(stream, options).await
+    }
+
+    /// Evict the entries of `stream` older than `max_age`,
+    /// according to the server [`TIME`](https://redis.io/commands/time/)
+    ///
+    /// # Return
+    /// The number of entries evicted from the stream
+    pub async fn trim_by_age(&self, stream: &str, max_age: Duration) -> Result<usize> {
+        self.trim(stream, &TrimStrategy::MaxAge(max_age)).await
+    }
+
+    /// Infinite stream of the number of entries evicted from `stream`,
+    /// trimmed with [`trim`](StreamMaintainer::trim) about every `interval`
+    ///
+    /// The first trimming happens immediately.
+    /// Each delay varies randomly by up to 10% of `interval`,
+    /// so that several instances of an application do not trim at the same time.
+    /// A failed trimming produces an error item, the trimming goes on,
+    /// with a delay doubled after each consecutive error, up to the
+    /// [`max_backoff`](StreamMaintainer::max_backoff).
+    ///
+    /// The trimming stops when the returned stream is dropped.
+    pub fn trim_periodically(
+        &self,
+        stream: impl Into<String>,
+        strategy: TrimStrategy,
+        interval: Duration,
+    ) -> BoxStream<'static, Result<usize>> {
+        let maintainer = self.clone();
+        let stream = stream.into();
+
+        futures_util::stream::unfold(
+            (maintainer, stream, strategy, None, 0),
+            move |(maintainer, stream, strategy, delay, errors)| async move {
+                if let Some(delay) = delay {
+                    sleep(delay).await;
+                }
+                let result = maintainer.trim(&stream, &strategy).await;
+                let (delay, errors) = match &result {
+                    Ok(_) => (jittered(interval), 0),
+                    Err(_) => {
+                        let errors = errors + 1;
+                        let delay = interval
+                            .saturating_mul(2u32.saturating_pow(errors))
+                            .min(maintainer.max_backoff.max(interval));
+                        (delay, errors)
+                    }
+                };
+                Some((result, (maintainer, stream, strategy, Some(delay), errors)))
+            },
+        )
+        .boxed()
+    }
+
+    /// Minimum ID of the entries younger than `max_age`
+    async fn min_id(&self, max_age: Duration) -> Result<String> {
+        let (secs, micros) = self.client.time().await?;
+        let now_millis = u64::from(secs) * 1000 + u64::from(micros) / 1000;
+        let min_millis = now_millis.saturating_sub(max_age.as_millis() as u64);
+        Ok(min_millis.to_string())
+    }
+}
+
+/// `interval` varied randomly by up to 10%
+fn jittered(interval: Duration) -> Duration {
+    interval.mul_f64(rand::thread_rng().gen_range(0.9..=1.1))
+}
@@ -0,0 +1,178 @@
+use crate::{
+    client::Client,
+    commands::{StreamCommands, XConsumerInfo, XGroupInfo},
+    network::sleep,
+    Error, Result,
+};
+use futures_util::{stream::BoxStream, StreamExt};
+use std::time::Duration;
+
+/// Lag of a consumer group of a stream, sampled by a [`StreamMonitor`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupLag {
+    /// Name of the consumer group
+    pub group: String,
+    /// Number of entries of the stream still waiting to be delivered to the consumers of the group,
+    /// or `None` when the server cannot determine it
+    /// (e.g. after entries have been deleted in the middle of the stream)
+    pub lag: Option<usize>,
+    /// Logical read counter of the last entry delivered to the consumers of the group
+    pub entries_read: Option<usize>,
+    /// ID of the last entry delivered to the consumers of the group
+    pub last_delivered_id: String,
+    /// Number of entries delivered but not yet acknowledged
+    pub pending: usize,
+    /// Lag of each consumer of the group
+    pub consumers: Vec<ConsumerLag>,
+}
+
+/// Lag of a consumer of a consumer group, sampled by a [`StreamMonitor`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsumerLag {
+    /// Name of the consumer
+    pub name: String,
+    /// Number of entries delivered to the consumer but not yet acknowledged
+    pub pending: usize,
+    /// Time elapsed since the consumer last interacted with the server
+    pub idle: Duration,
+}
+
+/// Monitoring of the lag of the consumer groups of streams,
+/// based on [`XINFO GROUPS`](https://redis.io/commands/xinfo-groups/)
+/// and [`XINFO CONSUMERS`](https://redis.io/commands/xinfo-consumers/).
+///
+/// The `lag` & `entries-read` fields of consumer groups require Redis 7.0 or above.
+///
+/// # Example
+/// ```
+/// use futures_util::StreamExt;
+/// use rustis::{
+///     client::Client,
+///     commands::{GenericCommands, StreamCommands, XAddOptions, XGroupCreateOptions},
+///     patterns::StreamMonitor,
+///     Result,
+/// };
+/// use std::time::Duration;
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let client = Client::connect("127.0.0.1:6379").await?;
+///     client.del("events").await?;
+///     client
+///         .xgroup_create("events", "workers", "$", XGroupCreateOptions::default().mk_stream())
+///         .await?;
+///     let _id: String = client
+///         .xadd("events", "*", [("type", "signup")], XAddOptions::default())
+///         .await?;
+///
+///     let monitor = StreamMonitor::new(client);
+///     let group_lag = monitor.group_lag("events", "workers").await?;
+///     assert_eq!(Some(1), group_lag.lag);
+///
+///     let mut samples = monitor.sample_every("events", Duration::from_secs(10));
+///     let groups = samples.next().await.unwrap()?;
+///     assert_eq!("workers", groups[0].group);
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct StreamMonitor {
+    client: Client,
+}
+
+impl StreamMonitor {
+    /// Create a new monitor
+    #[must_use]
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Lag of the consumer group `group` of `stream`, and of its consumers
+    ///
+    /// # Errors
+    /// An [`Error::Client`] if the consumer group does not exist
+    pub async fn group_lag(&self, stream: &str, group: &str) -> Result<GroupLag> {
+        let group_info = self
+            .client
+            .xinfo_groups(stream)
+            .await?
+            .into_iter()
+            .find(|g| g.name == group)
+            .ok_or_else(|| {
+                Error::Client(format!(
+                    "Consumer group {group} does not exist on stream {stream}"
+                ))
+            })?;
+
+        self.to_group_lag(stream, group_info).await
+    }
+
+    /// Lag of all the consumer groups of `stream`, and of their consumers
+    pub async fn sample(&self, stream: &str) -> Result<Vec<GroupLag>> {
+        let group_infos = self.client.xinfo_groups(stream).await?;
+
+        let mut group_lags = Vec::with_capacity(group_infos.len());
+        for group_info in group_infos {
+            group_lags.push(self.to_group_lag(stream, group_info).await?);
+        }
+
+        Ok(group_lags)
+    }
+
+    /// Infinite stream of the lag of all the consumer groups of `stream`,
+    /// sampled with [`sample`](StreamMonitor::sample) every `interval`
+    ///
+    /// The first sample is produced immediately.
+    /// A failed sample produces an error item, the sampling goes on.
+    pub fn sample_every(
+        &self,
+        stream: impl Into<String>,
+        interval: Duration,
+    ) -> BoxStream<'static, Result<Vec<GroupLag>>> {
+        let monitor = self.clone();
+        let stream = stream.into();
+
+        futures_util::stream::unfold(
+            (monitor, stream, true),
+            move |(monitor, stream, first)| async move {
+                if !first {
+                    sleep(interval).await;
+                }
+                let sample = monitor.sample(&stream).await;
+                Some((sample, (monitor, stream, false)))
+            },
+        )
+        .boxed()
+    }
+
+    async fn to_group_lag(&self, stream: &str, group_info: XGroupInfo) -> Result<GroupLag> {
+        let consumers = self
+            .client
+            .xinfo_consumers(stream, &group_info.name)
+            .await?
+            .into_iter()
+            .map(
+                |XConsumerInfo {
+                     name,
+                     pending,
+                     idle_millis,
+                 }| ConsumerLag {
+                    name,
+                    pending,
+                    idle: Duration::from_millis(idle_millis),
+                },
+            )
+            .collect();
+
+        Ok(GroupLag {
+            group: group_info.name,
+            lag: group_info.lag,
+            entries_read: group_info.entries_read,
+            last_delivered_id: group_info.last_delivered_id,
+            pending: group_info.pending,
+            consumers,
+        })
+    }
+}
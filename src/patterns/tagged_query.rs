@@ -0,0 +1,209 @@
+use crate::{
+    client::{BatchPreparedCommand, Client},
+    cluster::extract_hash_tag,
+    commands::{ExpireOption, GenericCommands, SScanOptions, SetCommands},
+    patterns::multi_key_atomic::check_hash_tags,
+    resp::PrimitiveResponse,
+    Error, Result,
+};
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+
+const DEFAULT_RESULT_TTL: Duration = Duration::from_secs(60);
+const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Temporary set storing the members of a [`TaggedQuery`], built by [`TaggedQuery::execute`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaggedQueryResult {
+    /// Key of the temporary set, to be given back to [`TaggedQuery::page`]
+    /// to read the next pages, e.g. in a subsequent HTTP request
+    pub key: String,
+    /// Number of members of the result
+    pub len: usize,
+}
+
+/// Filtered listing of the members shared by multiple tag [sets](https://redis.io/docs/data-types/sets/)
+/// (e.g. the products tagged both `color:red` and `size:xl`).
+///
+/// * [`count`](TaggedQuery::count) cheaply counts the members of the intersection
+///   with [`SINTERCARD`](https://redis.io/commands/sintercard/), optionally up to a limit
+///   (e.g. to display "1000+ results"),
+/// * [`execute`](TaggedQuery::execute) stores the intersection in a temporary set
+///   with [`SINTERSTORE`](https://redis.io/commands/sinterstore/),
+///   which expires after a [time to live](TaggedQuery::result_ttl),
+/// * [`page`](TaggedQuery::page) paginates the temporary set with [`SSCAN`](https://redis.io/commands/sscan/),
+///   extending its time to live at each page.
+///
+/// As with any `SSCAN`, the size of a page is only a hint given to the server.
+///
+/// On a cluster, all the tag sets must share the same hash tag (e.g. `{products}:color:red`),
+/// the temporary set being stored in the same hash slot.
+///
+/// # Example
+/// ```
+/// use rustis::{client::Client, commands::SetCommands, patterns::TaggedQuery, Result};
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let client = Client::connect("127.0.0.1:6379").await?;
+///     client.sadd("{products}:color:red", ["p1", "p2", "p3"]).await?;
+///     client.sadd("{products}:size:xl", ["p2", "p3", "p4"]).await?;
+///
+///     let query = TaggedQuery::new(client, ["{products}:color:red", "{products}:size:xl"]);
+///     assert_eq!(2, query.count(0).await?);
+///
+///     let result = query.execute().await?;
+///     let mut products = Vec::<String>::new();
+///     let mut cursor = 0;
+///     loop {
+///         let (next_cursor, page) = query.page(&result.key, cursor).await?;
+///         products.extend(page);
+///         if next_cursor == 0 {
+///             break;
+///         }
+///         cursor = next_cursor;
+///     }
+///     products.sort();
+///     assert_eq!(vec!["p2".to_owned(), "p3".to_owned()], products);
+///
+///     query.discard(&result.key).await?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct TaggedQuery {
+    client: Client,
+    tags: Vec<String>,
+    result_ttl: Duration,
+    page_size: usize,
+}
+
+impl TaggedQuery {
+    /// Create a new query on the intersection of the sets stored at `tags`
+    #[must_use]
+    pub fn new<T: Into<String>>(client: Client, tags: impl IntoIterator<Item = T>) -> Self {
+        Self {
+            client,
+            tags: tags.into_iter().map(Into::into).collect(),
+            result_ttl: DEFAULT_RESULT_TTL,
+            page_size: DEFAULT_PAGE_SIZE,
+        }
+    }
+
+    /// Time to live of the temporary set of the result, extended at each page (default 60 seconds)
+    #[must_use]
+    pub fn result_ttl(mut self, result_ttl: Duration) -> Self {
+        self.result_ttl = result_ttl;
+        self
+    }
+
+    /// Number of members hinted to the server for each page (default 100)
+    #[must_use]
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    /// Number of members of the intersection, counted up to `limit` (`0` for an exact count)
+    ///
+    /// # Errors
+    /// An [`Error::Client`] if there is no tag,
+    /// or if the client is connected to a cluster and the tags do not share the same hash tag.
+    pub async fn count(&self, limit: usize) -> Result<usize> {
+        self.check_tags()?;
+        self.client.sintercard(self.tags.as_slice(), limit).await
+    }
+
+    /// Store the intersection in a temporary set
+    ///
+    /// # Errors
+    /// An [`Error::Client`] if there is no tag,
+    /// or if the client is connected to a cluster and the tags do not share the same hash tag.
+    pub async fn execute(&self) -> Result<TaggedQueryResult> {
+        let key = self.result_key(self.check_tags()?);
+
+        let mut pipeline = self.client.create_pipeline();
+        pipeline
+            .sinterstore(key.as_str(), self.tags.as_slice())
+            .queue();
+        pipeline
+            .pexpire(
+                key.as_str(),
+                self.result_ttl.as_millis() as u64,
+                ExpireOption::None,
+            )
+            .forget();
+        let len = pipeline.execute().await?;
+
+        Ok(TaggedQueryResult { key, len })
+    }
+
+    /// Read a page of the result stored at `result_key`, starting at `cursor` (`0` for the first page),
+    /// and extend the time to live of the result
+    ///
+    /// # Return
+    /// The cursor of the next page, `0` after the last page, and the members of the page
+    pub async fn page<M>(&self, result_key: &str, cursor: u64) -> Result<(u64, Vec<M>)>
+    where
+        M: PrimitiveResponse + DeserializeOwned + Send,
+    {
+        let mut pipeline = self.client.create_pipeline();
+        pipeline
+            .sscan::<_, M>(
+                result_key,
+                cursor,
+                SScanOptions::default().count(self.page_size),
+            )
+            .queue();
+        pipeline
+            .pexpire(
+                result_key,
+                self.result_ttl.as_millis() as u64,
+                ExpireOption::None,
+            )
+            .forget();
+        pipeline.execute().await
+    }
+
+    /// Delete the result stored at `result_key` before it expires
+    pub async fn discard(&self, result_key: &str) -> Result<()> {
+        self.client.del(result_key).await?;
+        Ok(())
+    }
+
+    /// Check the tags and return their common hash tag, if any
+    fn check_tags(&self) -> Result<Option<&[u8]>> {
+        let Some(first_tag) = self.tags.first() else {
+            return Err(Error::Client(
+                "A tagged query needs at least one tag".to_owned(),
+            ));
+        };
+
+        let hash_tag = extract_hash_tag(first_tag);
+        if self.client.is_cluster() {
+            let keys: Vec<&[u8]> = self.tags.iter().map(|tag| tag.as_bytes()).collect();
+            check_hash_tags(&keys, "tagged query")?;
+            if hash_tag.is_none() {
+                return Err(Error::Client(format!(
+                    "Key `{first_tag}` has no hash tag: the keys of a tagged query must have a hash tag on a cluster"
+                )));
+            }
+        }
+
+        Ok(hash_tag)
+    }
+
+    /// Unique key of a temporary set, in the hash slot of the tags
+    fn result_key(&self, hash_tag: Option<&[u8]>) -> String {
+        let id = rand::random::<u64>();
+        match hash_tag {
+            Some(hash_tag) => format!(
+                "{{{}}}:tagged-query:{id:016x}",
+                String::from_utf8_lossy(hash_tag)
+            ),
+            None => format!("tagged-query:{id:016x}"),
+        }
+    }
+}
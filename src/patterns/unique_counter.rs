@@ -0,0 +1,248 @@
+use crate::{
+    client::{BatchPreparedCommand, Client},
+    commands::{ExpireOption, GenericCommands, HyperLogLogCommands},
+    resp::{SingleArg, SingleArgCollection},
+    Error, Result,
+};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Time span of the buckets of a [`UniqueCounter`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UniqueCounterGranularity {
+    /// One bucket per hour
+    Hourly,
+    /// One bucket per day (UTC)
+    Daily,
+}
+
+impl UniqueCounterGranularity {
+    /// Duration of a bucket
+    #[must_use]
+    pub fn duration(self) -> Duration {
+        match self {
+            UniqueCounterGranularity::Hourly => Duration::from_secs(3600),
+            UniqueCounterGranularity::Daily => Duration::from_secs(86400),
+        }
+    }
+
+    fn key_prefix(self) -> &'static str {
+        match self {
+            UniqueCounterGranularity::Hourly => "h",
+            UniqueCounterGranularity::Daily => "d",
+        }
+    }
+}
+
+/// Approximated count of unique elements (visitors, devices, ...) per time bucket,
+/// backed by [HyperLogLogs](https://redis.io/docs/data-types/hyperloglogs/).
+///
+/// Each hourly or daily bucket is stored in its own key, `{<namespace>}:<h|d>:<index>`,
+/// where `index` is the number of hours or days elapsed since the Unix epoch.
+/// All the keys of a namespace share the same hash tag,
+/// so that unions of buckets can be computed with a single [`PFCOUNT`](https://redis.io/commands/pfcount/),
+/// even on a cluster.
+///
+/// With a [retention](UniqueCounter::retention), each bucket expires once the retention has elapsed after its end.
+///
+/// # Example
+/// ```
+/// use rustis::{
+///     client::Client,
+///     patterns::{UniqueCounter, UniqueCounterGranularity},
+///     Result,
+/// };
+/// use std::time::Duration;
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let client = Client::connect("127.0.0.1:6379").await?;
+///     let visitors = UniqueCounter::new(client, "visitors", UniqueCounterGranularity::Daily)
+///         .retention(Duration::from_secs(30 * 86400));
+///
+///     visitors.add(["alice", "bob"]).await?;
+///     visitors.add("alice").await?;
+///
+///     // unique visitors of the last 7 days
+///     let count = visitors.rolling_count(7).await?;
+///     assert!(count >= 2);
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct UniqueCounter {
+    client: Client,
+    namespace: String,
+    granularity: UniqueCounterGranularity,
+    retention: Option<Duration>,
+}
+
+impl UniqueCounter {
+    /// Create a new counter, storing its buckets under `namespace`
+    #[must_use]
+    pub fn new(
+        client: Client,
+        namespace: impl Into<String>,
+        granularity: UniqueCounterGranularity,
+    ) -> Self {
+        Self {
+            client,
+            namespace: namespace.into(),
+            granularity,
+            retention: None,
+        }
+    }
+
+    /// Time during which a bucket is kept after its end (kept forever by default)
+    #[must_use]
+    pub fn retention(mut self, retention: Duration) -> Self {
+        self.retention = Some(retention);
+        self
+    }
+
+    /// Key of the bucket containing `timestamp`
+    ///
+    /// # Errors
+    /// An [`Error::Client`] if `timestamp` is before the Unix epoch.
+    pub fn bucket_key(&self, timestamp: SystemTime) -> Result<String> {
+        Ok(self.key(self.bucket_index(timestamp)?))
+    }
+
+    /// Add elements to the bucket of the current time
+    ///
+    /// # Return
+    /// `true` if the approximated count of the bucket has changed
+    pub async fn add<E, EE>(&self, elements: EE) -> Result<bool>
+    where
+        E: SingleArg,
+        EE: SingleArgCollection<E>,
+    {
+        self.add_at(SystemTime::now(), elements).await
+    }
+
+    /// Add elements to the bucket containing `timestamp`
+    ///
+    /// # Return
+    /// `true` if the approximated count of the bucket has changed
+    pub async fn add_at<E, EE>(&self, timestamp: SystemTime, elements: EE) -> Result<bool>
+    where
+        E: SingleArg,
+        EE: SingleArgCollection<E>,
+    {
+        let index = self.bucket_index(timestamp)?;
+        let key = self.key(index);
+
+        let Some(retention) = self.retention else {
+            return self.client.pfadd(key, elements).await;
+        };
+
+        let bucket_end = Duration::from_secs(self.granularity.duration().as_secs() * (index + 1));
+        let expire_at = (bucket_end + retention).as_millis() as u64;
+
+        let mut pipeline = self.client.create_pipeline();
+        pipeline.pfadd(key.as_str(), elements).queue();
+        pipeline
+            .pexpireat(key.as_str(), expire_at, ExpireOption::None)
+            .forget();
+        pipeline.execute().await
+    }
+
+    /// Approximated number of unique elements of the bucket of the current time
+    pub async fn count(&self) -> Result<usize> {
+        self.count_at(SystemTime::now()).await
+    }
+
+    /// Approximated number of unique elements of the bucket containing `timestamp`
+    pub async fn count_at(&self, timestamp: SystemTime) -> Result<usize> {
+        self.client.pfcount(self.bucket_key(timestamp)?).await
+    }
+
+    /// Approximated number of unique elements of the last `buckets` buckets,
+    /// including the bucket of the current time
+    ///
+    /// # Errors
+    /// An [`Error::Client`] if `buckets` is `0`.
+    pub async fn rolling_count(&self, buckets: usize) -> Result<usize> {
+        self.rolling_count_at(SystemTime::now(), buckets).await
+    }
+
+    /// Approximated number of unique elements of the `buckets` buckets
+    /// ending with the bucket containing `end`
+    ///
+    /// # Errors
+    /// An [`Error::Client`] if `buckets` is `0`.
+    pub async fn rolling_count_at(&self, end: SystemTime, buckets: usize) -> Result<usize> {
+        let keys = self.keys(self.bucket_index(end)?, buckets)?;
+        self.client.pfcount(keys).await
+    }
+
+    /// Approximated number of the elements of the bucket of the current time
+    /// which have not been observed in the `previous_buckets` buckets before it
+    ///
+    /// # Errors
+    /// An [`Error::Client`] if `previous_buckets` is `0`.
+    pub async fn count_new(&self, previous_buckets: usize) -> Result<usize> {
+        self.count_new_at(SystemTime::now(), previous_buckets).await
+    }
+
+    /// Approximated number of the elements of the bucket containing `timestamp`
+    /// which have not been observed in the `previous_buckets` buckets before it
+    /// (e.g. new visitors of the day, compared to the last 30 days)
+    ///
+    /// The count is computed as the difference between the cardinality of the union of all these buckets
+    /// and the cardinality of the union of the previous buckets, and is approximated accordingly.
+    ///
+    /// # Errors
+    /// An [`Error::Client`] if `previous_buckets` is `0`.
+    pub async fn count_new_at(
+        &self,
+        timestamp: SystemTime,
+        previous_buckets: usize,
+    ) -> Result<usize> {
+        let index = self.bucket_index(timestamp)?;
+        if index == 0 {
+            return self.client.pfcount(self.key(index)).await;
+        }
+
+        let previous_keys = self.keys(index - 1, previous_buckets)?;
+        let mut all_keys = previous_keys.clone();
+        all_keys.push(self.key(index));
+
+        let mut pipeline = self.client.create_pipeline();
+        pipeline.pfcount(all_keys).queue();
+        pipeline.pfcount(previous_keys).queue();
+        let (all_count, previous_count): (usize, usize) = pipeline.execute().await?;
+
+        Ok(all_count.saturating_sub(previous_count))
+    }
+
+    fn bucket_index(&self, timestamp: SystemTime) -> Result<u64> {
+        let since_epoch = timestamp
+            .duration_since(UNIX_EPOCH)
+            .map_err(|_| Error::Client("Cannot count elements before the Unix epoch".to_owned()))?;
+        Ok(since_epoch.as_secs() / self.granularity.duration().as_secs())
+    }
+
+    fn key(&self, index: u64) -> String {
+        format!(
+            "{{{}}}:{}:{index}",
+            self.namespace,
+            self.granularity.key_prefix()
+        )
+    }
+
+    /// Keys of the `buckets` buckets ending with the bucket `last_index`
+    fn keys(&self, last_index: u64, buckets: usize) -> Result<Vec<String>> {
+        if buckets == 0 {
+            return Err(Error::Client(
+                "Cannot count elements over an empty range of buckets".to_owned(),
+            ));
+        }
+
+        let first_index = last_index.saturating_sub(buckets as u64 - 1);
+        Ok((first_index..=last_index)
+            .map(|index| self.key(index))
+            .collect())
+    }
+}
@@ -0,0 +1,56 @@
+/*!
+Replication client, speaking the [replication protocol](https://redis.io/docs/management/replication/)
+of a Redis replica.
+
+A [`ReplicationStream`] connects to a master with `PSYNC`, as a replica would,
+and receives the data set as typed [`ReplicationEvent`]s:
+* the RDB snapshot of a full synchronization, chunk by chunk,
+* then the stream of the write commands applied on the master.
+
+This is the building block of change-data-capture tools:
+the snapshot can be loaded by an RDB parser and the commands forwarded to another system.
+The [position](ReplicationPosition) of the stream can be saved to resume it later with a partial synchronization,
+as long as the master replication backlog still contains it.
+
+The replication client only reads the stream: it never applies the commands
+and is not listed as a replica able to be promoted.
+It acknowledges the processed offset with `REPLCONF ACK` every second,
+as expected by the master to keep the link alive.
+
+# Example
+```
+use rustis::{
+    replication::{ReplicationEvent, ReplicationStream},
+    Result,
+};
+
+#[cfg_attr(feature = "tokio-runtime", tokio::main)]
+#[cfg_attr(feature = "async-std-runtime", async_std::main)]
+async fn main() -> Result<()> {
+    let mut stream = ReplicationStream::connect("127.0.0.1:6379", None).await?;
+
+    while let Some(event) = stream.next_event().await? {
+        match event {
+            ReplicationEvent::FullResync { replication_id, offset } => {
+                println!("full resync of {replication_id} at offset {offset}")
+            }
+            ReplicationEvent::Continue { replication_id } => {
+                println!("partial resync of {replication_id}")
+            }
+            ReplicationEvent::RdbStart { len } => println!("snapshot of {len} bytes"),
+            ReplicationEvent::RdbChunk(_chunk) => (),
+            ReplicationEvent::RdbEnd => break,
+            ReplicationEvent::Command(command) => println!("{command:?}"),
+        }
+    }
+
+    Ok(())
+}
+```
+*/
+
+mod replication_event;
+mod replication_stream;
+
+pub use replication_event::*;
+pub use replication_stream::*;
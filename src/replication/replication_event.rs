@@ -0,0 +1,63 @@
+use bytes::Bytes;
+use std::fmt;
+
+/// Event received by a [`ReplicationStream`](crate::replication::ReplicationStream)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplicationEvent {
+    /// The master performs a full synchronization: an RDB snapshot follows,
+    /// then the commands applied after `offset`
+    FullResync {
+        /// Replication ID of the master
+        replication_id: String,
+        /// Replication offset of the snapshot
+        offset: u64,
+    },
+    /// The master accepted a partial synchronization:
+    /// the commands applied after the requested position follow
+    Continue {
+        /// Replication ID of the master, different from the requested one after a failover
+        replication_id: String,
+    },
+    /// Beginning of the RDB snapshot
+    RdbStart {
+        /// Length of the snapshot in bytes
+        len: usize,
+    },
+    /// Chunk of the RDB snapshot, in order
+    RdbChunk(Bytes),
+    /// End of the RDB snapshot
+    RdbEnd,
+    /// Write command applied on the master
+    Command(ReplicatedCommand),
+}
+
+/// Command of the replication stream
+#[derive(Clone, PartialEq, Eq)]
+pub struct ReplicatedCommand {
+    /// Uppercase name of the command
+    pub name: String,
+    /// Arguments of the command
+    pub args: Vec<Vec<u8>>,
+    /// Database the command applies to, as selected by the last `SELECT` of the stream
+    pub database: usize,
+    /// Replication offset at the end of the command
+    pub offset: u64,
+}
+
+impl fmt::Debug for ReplicatedCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReplicatedCommand")
+            .field("name", &self.name)
+            .field(
+                "args",
+                &self
+                    .args
+                    .iter()
+                    .map(|arg| String::from_utf8_lossy(arg))
+                    .collect::<Vec<_>>(),
+            )
+            .field("database", &self.database)
+            .field("offset", &self.offset)
+            .finish()
+    }
+}
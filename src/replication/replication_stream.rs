@@ -0,0 +1,345 @@
+use crate::{
+    client::{IntoConfig, ServerConfig, TransportReader, TransportWriter},
+    replication::{ReplicatedCommand, ReplicationEvent},
+    resp::{cmd, BufferDecoder, BulkString, Command, RespBuf, RespWriter},
+    Error, Result, StandaloneConnection,
+};
+use bytes::{Buf, BytesMut};
+use log::debug;
+use memchr::memmem;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncReadExt;
+use tokio_util::codec::Decoder;
+
+/// Interval between two acknowledgments of the processed offset, as sent by Redis replicas
+const ACK_INTERVAL: Duration = Duration::from_secs(1);
+/// Maximum size of the RDB chunks
+const RDB_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Position in the replication stream of a master
+///
+/// A position saved from a [`ReplicationStream`] can be given to [`ReplicationStream::connect`]
+/// to resume the stream with a partial synchronization.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplicationPosition {
+    /// Replication ID of the master
+    pub replication_id: String,
+    /// Offset of the last processed byte of the stream
+    pub offset: u64,
+    /// Database selected by the last `SELECT` of the stream
+    pub database: usize,
+}
+
+enum State {
+    /// Waiting for the reply to `PSYNC`
+    PsyncReply,
+    /// Waiting for the header of the RDB snapshot
+    RdbHeader,
+    /// Receiving the RDB snapshot
+    Rdb { remaining: usize },
+    /// Receiving the commands
+    Commands,
+}
+
+/// Replication stream of a master, received as a replica
+///
+/// See the [module-level documentation](crate::replication)
+pub struct ReplicationStream {
+    reader: TransportReader,
+    writer: RespWriter<TransportWriter>,
+    buffer: BytesMut,
+    decoder: BufferDecoder,
+    state: State,
+    position: Option<ReplicationPosition>,
+    last_ack: Instant,
+    tag: String,
+}
+
+impl ReplicationStream {
+    /// Connects to a master and starts its replication stream
+    ///
+    /// # Arguments
+    /// * `config` - Configuration of a standalone master, authentication included
+    /// * `position` - Position to resume from, the stream starts with a full synchronization if `None`
+    ///   or if the position is no longer in the replication backlog of the master
+    ///
+    /// # Errors
+    /// An error is returned if the connection fails or if the master rejects the replica,
+    /// e.g. because the user lacks the permissions to run `PSYNC`.
+    pub async fn connect(
+        config: impl IntoConfig,
+        position: Option<ReplicationPosition>,
+    ) -> Result<Self> {
+        let config = config.into_config()?;
+        let ServerConfig::Standalone { host, port } = &config.server else {
+            return Err(Error::Config(
+                "Replication requires a standalone server configuration".to_owned(),
+            ));
+        };
+
+        let mut connection = StandaloneConnection::connect(host, *port, &config).await?;
+        let tag = connection.tag().to_owned();
+
+        // no `eof` capability: the snapshot is always received with its length
+        let replconf = cmd("REPLCONF").arg("capa").arg("psync2");
+        connection.write(&replconf).await?;
+        match connection.read().await {
+            Some(resp_buf) => resp_buf?.to::<()>()?,
+            None => return Err(Error::Client("Disconnected from server".to_owned())),
+        }
+
+        let psync = match &position {
+            Some(position) => cmd("PSYNC")
+                .arg(position.replication_id.as_str())
+                .arg(position.offset + 1),
+            None => cmd("PSYNC").arg("?").arg(-1),
+        };
+        connection.write(&psync).await?;
+        debug!("[{tag}] Replication requested from {position:?}");
+
+        let (reader, writer, buffer) = connection.into_raw_parts();
+        Ok(Self::new(reader, writer, buffer, position, tag))
+    }
+
+    pub(crate) fn new(
+        reader: TransportReader,
+        writer: TransportWriter,
+        buffer: BytesMut,
+        position: Option<ReplicationPosition>,
+        tag: String,
+    ) -> Self {
+        Self {
+            reader,
+            writer: RespWriter::new(writer),
+            buffer,
+            decoder: BufferDecoder::default(),
+            state: State::PsyncReply,
+            position,
+            last_ack: Instant::now(),
+            tag,
+        }
+    }
+
+    /// Position of the last processed byte of the stream,
+    /// `None` until the master has accepted the synchronization
+    pub fn position(&self) -> Option<&ReplicationPosition> {
+        match self.state {
+            State::PsyncReply => None,
+            _ => self.position.as_ref(),
+        }
+    }
+
+    /// Receives the next event of the stream
+    ///
+    /// `PING` & `REPLCONF` commands, used by the master to check the link,
+    /// are handled internally and not returned as events.
+    ///
+    /// # Return
+    /// The next event or `None` if the master has closed the connection
+    pub async fn next_event(&mut self) -> Result<Option<ReplicationEvent>> {
+        loop {
+            let event = self.decode_event().await?;
+            if self.last_ack.elapsed() >= ACK_INTERVAL {
+                self.ack().await?;
+            }
+            if event.is_some() {
+                return Ok(event);
+            }
+
+            if self.reader.read_buf(&mut self.buffer).await? == 0 {
+                debug!("[{}] Replication stream closed", self.tag);
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Acknowledges the processed offset to the master with `REPLCONF ACK`
+    ///
+    /// This is done every second by [`next_event`](ReplicationStream::next_event),
+    /// as long as it is called.
+    pub async fn ack(&mut self) -> Result<()> {
+        match (&self.state, &self.position) {
+            (State::Commands, Some(position)) => {
+                let ack = cmd("REPLCONF").arg("ACK").arg(position.offset);
+                self.send(&ack).await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Decodes an event from the bytes already received, `None` if more bytes are needed
+    async fn decode_event(&mut self) -> Result<Option<ReplicationEvent>> {
+        match self.state {
+            State::PsyncReply => {
+                let Some(line) = self.read_line() else {
+                    return Ok(None);
+                };
+                self.decode_psync_reply(&line).map(Some)
+            }
+            State::RdbHeader => {
+                let Some(line) = self.read_line() else {
+                    return Ok(None);
+                };
+                let len = line
+                    .strip_prefix(b"$")
+                    .and_then(atoi::atoi::<usize>)
+                    .ok_or_else(|| {
+                        Error::Client(format!(
+                            "Unexpected RDB header: {}",
+                            String::from_utf8_lossy(&line)
+                        ))
+                    })?;
+                self.state = State::Rdb { remaining: len };
+                Ok(Some(ReplicationEvent::RdbStart { len }))
+            }
+            State::Rdb { remaining: 0 } => {
+                debug!("[{}] RDB snapshot received", self.tag);
+                self.state = State::Commands;
+                self.last_ack = Instant::now();
+                Ok(Some(ReplicationEvent::RdbEnd))
+            }
+            State::Rdb { remaining } => {
+                let len = remaining.min(self.buffer.len()).min(RDB_CHUNK_SIZE);
+                if len == 0 {
+                    return Ok(None);
+                }
+                self.state = State::Rdb {
+                    remaining: remaining - len,
+                };
+                let chunk = self.buffer.split_to(len).freeze();
+                Ok(Some(ReplicationEvent::RdbChunk(chunk)))
+            }
+            State::Commands => {
+                while let Some(frame) = self.decoder.decode(&mut self.buffer)? {
+                    if let Some(command) = self.decode_command(&frame).await? {
+                        return Ok(Some(ReplicationEvent::Command(command)));
+                    }
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    fn decode_psync_reply(&mut self, line: &[u8]) -> Result<ReplicationEvent> {
+        let reply = std::str::from_utf8(line)
+            .map_err(|_| Error::Client("Unexpected PSYNC reply".to_owned()))?;
+        let mut parts = reply.split(' ');
+
+        match parts.next() {
+            Some("+FULLRESYNC") => {
+                let (Some(replication_id), Some(offset)) = (
+                    parts.next(),
+                    parts.next().and_then(|offset| offset.parse::<u64>().ok()),
+                ) else {
+                    return Err(Error::Client(format!("Unexpected PSYNC reply: {reply}")));
+                };
+
+                debug!(
+                    "[{}] Full resync of {replication_id} at offset {offset}",
+                    self.tag
+                );
+                self.position = Some(ReplicationPosition {
+                    replication_id: replication_id.to_owned(),
+                    offset,
+                    database: 0,
+                });
+                self.state = State::RdbHeader;
+                Ok(ReplicationEvent::FullResync {
+                    replication_id: replication_id.to_owned(),
+                    offset,
+                })
+            }
+            Some("+CONTINUE") => {
+                let Some(position) = &mut self.position else {
+                    return Err(Error::Client(format!("Unexpected PSYNC reply: {reply}")));
+                };
+                if let Some(replication_id) = parts.next() {
+                    replication_id.clone_into(&mut position.replication_id);
+                }
+
+                debug!("[{}] Partial resync from {position:?}", self.tag);
+                self.state = State::Commands;
+                self.last_ack = Instant::now();
+                Ok(ReplicationEvent::Continue {
+                    replication_id: position.replication_id.clone(),
+                })
+            }
+            _ => {
+                // error replies are converted to `Error::Redis`
+                let mut reply = line.to_vec();
+                reply.extend_from_slice(b"\r\n");
+                RespBuf::from_slice(&reply).to::<()>()?;
+                Err(Error::Client(format!(
+                    "Unexpected PSYNC reply: {}",
+                    String::from_utf8_lossy(line)
+                )))
+            }
+        }
+    }
+
+    /// Decodes a command frame and updates the position,
+    /// `None` for the commands handled internally
+    async fn decode_command(&mut self, frame: &RespBuf) -> Result<Option<ReplicatedCommand>> {
+        let mut args: Vec<BulkString> = frame.to()?;
+        let Some(position) = &mut self.position else {
+            return Err(Error::Client(
+                "Replication command received before PSYNC reply".to_owned(),
+            ));
+        };
+
+        // the offset of `REPLCONF GETACK` is acknowledged without the command itself
+        let previous_offset = position.offset;
+        position.offset += frame.as_bytes().len() as u64;
+
+        if args.is_empty() {
+            return Err(Error::Client("Empty replication command".to_owned()));
+        }
+        let name = String::from_utf8_lossy(&args.remove(0)).to_uppercase();
+        let args: Vec<Vec<u8>> = args.into_iter().map(Into::into).collect();
+
+        match name.as_str() {
+            "PING" => return Ok(None),
+            "REPLCONF" => {
+                if args
+                    .first()
+                    .is_some_and(|arg| arg.eq_ignore_ascii_case(b"GETACK"))
+                {
+                    let ack = cmd("REPLCONF").arg("ACK").arg(previous_offset);
+                    self.send(&ack).await?;
+                }
+                return Ok(None);
+            }
+            "SELECT" => {
+                if let Some(database) = args.first().and_then(|db| atoi::atoi::<usize>(db)) {
+                    position.database = database;
+                }
+            }
+            _ => (),
+        }
+
+        Ok(Some(ReplicatedCommand {
+            name,
+            args,
+            database: position.database,
+            offset: position.offset,
+        }))
+    }
+
+    async fn send(&mut self, command: &Command) -> Result<()> {
+        self.writer.write_command_async(command).await?;
+        self.last_ack = Instant::now();
+        Ok(())
+    }
+
+    /// Reads a `\r\n` terminated line, skipping the newlines sent by the master
+    /// to keep the link alive while preparing the snapshot
+    fn read_line(&mut self) -> Option<BytesMut> {
+        let newlines = self.buffer.iter().take_while(|b| **b == b'\n').count();
+        self.buffer.advance(newlines);
+
+        let end = memmem::find(&self.buffer, b"\r\n")?;
+        let line = self.buffer.split_to(end);
+        self.buffer.advance(2);
+        Some(line)
+    }
+}
@@ -1,29 +1,176 @@
-use super::RespDeserializer;
-use crate::{resp::RespBuf, Error, Result};
-use bytes::BytesMut;
-use serde::{de::IgnoredAny, Deserialize};
+use crate::{
+    resp::{
+        RespBuf, ARRAY_TAG, ATTRIBUTE_TAG, BIG_NUMBER_TAG, BLOB_ERROR_TAG, BOOL_TAG,
+        BULK_STRING_TAG, DOUBLE_TAG, ERROR_TAG, INTEGER_TAG, MAP_TAG, NIL_TAG, PUSH_TAG, SET_TAG,
+        SIMPLE_STRING_TAG, VERBATIM_STRING_TAG,
+    },
+    Error, Result,
+};
+use bytes::{BufMut, BytesMut};
+use memchr::memchr;
 use tokio_util::codec::Decoder;
 
-pub(crate) struct BufferDecoder;
+/// Decoder splitting the bytes received from a connection into RESP frames
+///
+/// Frames are delimited without being deserialized.
+/// When a frame is incomplete, the scan is resumed where it stopped once more bytes are received,
+/// so that large multi-bulk replies, received in many reads, are scanned only once.
+///
+/// Once [inline replies](BufferDecoder::accept_inline_replies) are accepted,
+/// a reply line starting with an ASCII letter or digit instead of a RESP type tag is an inline reply,
+/// as returned by some proxies to [inline commands](crate::resp::Command::inline):
+/// it is decoded as a simple string.
+/// Otherwise, such a line is a protocol error.
+#[derive(Default)]
+pub struct BufferDecoder {
+    /// Position of the next element to scan in the current frame
+    pos: usize,
+    /// Number of elements left to scan in each aggregate of the current frame, from the outermost
+    remaining: Vec<usize>,
+    /// Minimum length of the buffer to complete the bulk string at `pos`
+    min_len: usize,
+    /// `true` once an inline command has been sent
+    inline_replies: bool,
+}
+
+impl BufferDecoder {
+    /// Accept inline replies from now on,
+    /// to be called when an [inline command](crate::resp::Command::inline) is sent
+    pub fn accept_inline_replies(&mut self) {
+        self.inline_replies = true;
+    }
+
+    /// Scan the element at `pos` and return its number of child elements,
+    /// or `None` if the element is incomplete
+    #[inline]
+    fn scan_element(&mut self, buf: &[u8]) -> Result<Option<usize>> {
+        let Some(idx) = memchr(b'\r', &buf[self.pos..]) else {
+            return Ok(None);
+        };
+
+        let line_end = self.pos + idx;
+        match buf.get(line_end + 1) {
+            Some(b'\n') => (),
+            _ => return Ok(None),
+        }
+
+        let tag = buf[self.pos];
+        let line = buf.get(self.pos + 1..line_end).unwrap_or_default();
+        let next = line_end + 2;
+
+        let children = match tag {
+            SIMPLE_STRING_TAG | ERROR_TAG | INTEGER_TAG | DOUBLE_TAG | NIL_TAG | BOOL_TAG
+            | BIG_NUMBER_TAG => 0,
+            BULK_STRING_TAG | BLOB_ERROR_TAG | VERBATIM_STRING_TAG => {
+                let end = next + parse_len(line)? + 2;
+                if buf.len() < end {
+                    self.min_len = end;
+                    return Ok(None);
+                }
+
+                if &buf[end - 2..end] != b"\r\n" {
+                    return Err(Error::Client(format!(
+                        "Expected \\r\\n after bulk string. Got '{}''{}'",
+                        buf[end - 2] as char,
+                        buf[end - 1] as char
+                    )));
+                }
+
+                self.pos = end;
+                return Ok(Some(0));
+            }
+            ARRAY_TAG | SET_TAG | PUSH_TAG => parse_len(line)?,
+            MAP_TAG => parse_len(line)? * 2,
+            // attributes are followed by the value they are attached to
+            ATTRIBUTE_TAG => parse_len(line)? * 2 + 1,
+            _ => return Err(Error::Client("Cannot parse tag".to_owned())),
+        };
+
+        self.pos = next;
+        Ok(Some(children))
+    }
+
+    /// Decode a reply line without RESP type tag into a simple string frame
+    fn decode_inline_reply(src: &mut BytesMut) -> Option<RespBuf> {
+        let line_end = memchr(b'\n', src)?;
+        let line = src.split_to(line_end + 1);
+        let line = line.strip_suffix(b"\n").unwrap_or_default();
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+
+        let mut frame = BytesMut::with_capacity(line.len() + 3);
+        frame.put_u8(SIMPLE_STRING_TAG);
+        frame.put(line);
+        frame.put(&b"\r\n"[..]);
+        Some(RespBuf::new(frame.freeze()))
+    }
+}
+
+/// Returns `true` if `byte` starts an inline reply
+#[inline]
+fn is_inline_reply_start(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric()
+}
 
 impl Decoder for BufferDecoder {
     type Item = RespBuf;
     type Error = Error;
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>> {
-        if src.is_empty() {
+        if src.len() < self.min_len {
+            src.reserve(self.min_len - src.len());
             return Ok(None);
         }
 
-        let bytes = src.as_ref();
-        let mut deserializer = RespDeserializer::new(bytes);
-        let result = IgnoredAny::deserialize(&mut deserializer);
-        match result {
-            Ok(_) => Ok(Some(RespBuf::new(
-                src.split_to(deserializer.get_pos()).freeze(),
-            ))),
-            Err(Error::EOF) => Ok(None),
-            Err(e) => Err(e),
+        // inline reply, at the beginning of a frame only
+        if self.inline_replies && self.pos == 0 && self.remaining.is_empty() {
+            if let Some(tag) = src.first() {
+                if is_inline_reply_start(*tag) {
+                    return Ok(Self::decode_inline_reply(src));
+                }
+            }
+        }
+
+        loop {
+            let Some(children) = self.scan_element(src)? else {
+                if src.len() < self.min_len {
+                    src.reserve(self.min_len - src.len());
+                }
+                return Ok(None);
+            };
+
+            if children > 0 {
+                self.remaining.push(children);
+                continue;
+            }
+
+            // the element is complete, and so are the aggregates it completes
+            loop {
+                match self.remaining.last_mut() {
+                    Some(1) => {
+                        self.remaining.pop();
+                    }
+                    Some(remaining) => {
+                        *remaining -= 1;
+                        break;
+                    }
+                    None => {
+                        let frame_len = self.pos;
+                        self.pos = 0;
+                        self.min_len = 0;
+                        return Ok(Some(RespBuf::new(src.split_to(frame_len).freeze())));
+                    }
+                }
+            }
         }
     }
 }
+
+#[inline]
+fn parse_len(line: &[u8]) -> Result<usize> {
+    atoi::atoi(line).ok_or_else(|| {
+        Error::Client(format!(
+            "Cannot parse integer from {}",
+            String::from_utf8_lossy(line)
+        ))
+    })
+}
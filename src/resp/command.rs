@@ -1,4 +1,4 @@
-use crate::resp::{CommandArgs, ToArgs};
+use crate::resp::{CommandArgs, StreamedArg, ToArgs};
 
 #[cfg(debug_assertions)]
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -24,6 +24,10 @@ pub struct Command {
     pub name: &'static str,
     /// Collection of arguments of the command.
     pub args: CommandArgs,
+    /// Streamed arguments, with their position in `args` where they are represented by an empty argument
+    pub(crate) streamed_args: Vec<(usize, StreamedArg)>,
+    /// Sent in the inline format instead of a RESP array, see [`inline`](Command::inline)
+    pub(crate) inline: bool,
     #[doc(hidden)]
     #[cfg(debug_assertions)]
     pub kill_connection_on_write: usize,
@@ -42,6 +46,8 @@ impl Command {
         Self {
             name,
             args: CommandArgs::default(),
+            streamed_args: Vec::new(),
+            inline: false,
             #[cfg(debug_assertions)]
             kill_connection_on_write: 0,
             #[cfg(debug_assertions)]
@@ -73,6 +79,62 @@ impl Command {
         self
     }
 
+    /// Builder function to add an argument streamed from an asynchronous source,
+    /// written chunk by chunk to the socket.
+    ///
+    /// See [`StreamedArg`]
+    #[must_use]
+    pub fn streamed_arg(mut self, arg: StreamedArg) -> Self {
+        self.streamed_args.push((self.args.len(), arg));
+        self.args.write_arg(&[]);
+        self
+    }
+
+    /// Builder function to send the command in the [inline format](https://redis.io/docs/reference/protocol-spec/#inline-commands),
+    /// `NAME arg1 arg2\r\n`, instead of a RESP array of bulk strings.
+    ///
+    /// Meant for interoperability testing and for minimal health probes through proxies
+    /// which only accept inline commands.
+    /// Arguments containing spaces, quotes or non-printable characters are sent between double quotes,
+    /// with escape sequences.
+    ///
+    /// Streamed arguments cannot be sent inline:
+    /// a command with [streamed arguments](Command::streamed_arg) is always sent as a RESP array.
+    ///
+    /// # Example
+    /// ```
+    /// use rustis::{client::Client, resp::cmd, Result};
+    ///
+    /// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+    /// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+    /// async fn main() -> Result<()> {
+    ///     let client = Client::connect("127.0.0.1:6379").await?;
+    ///     let pong: String = client.send(cmd("PING").inline(true), None).await?.to()?;
+    ///     assert_eq!("PONG", pong);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[must_use]
+    pub fn inline(mut self, inline: bool) -> Self {
+        self.inline = inline;
+        self
+    }
+
+    /// Returns `true` if the command is sent in the inline format, see [`inline`](Command::inline)
+    #[must_use]
+    #[inline]
+    pub fn is_inline(&self) -> bool {
+        self.inline
+    }
+
+    /// Streamed argument at position `index` in [`args`](Command::args), if any
+    #[inline]
+    pub(crate) fn get_streamed_arg(&self, index: usize) -> Option<&StreamedArg> {
+        self.streamed_args
+            .iter()
+            .find_map(|(i, arg)| (*i == index).then_some(arg))
+    }
+
     #[cfg(debug_assertions)]
     #[inline]
     pub fn kill_connection_on_write(mut self, num_kills: usize) -> Self {
@@ -1,12 +1,84 @@
 use smallvec::SmallVec;
 
 use crate::resp::ToArgs;
-use std::fmt;
+use std::{fmt, ops::Deref};
+
+/// Maximum size of an argument stored inline, without heap allocation.
+///
+/// With the `union` feature of `smallvec`, an inline argument of this size
+/// takes the same memory as an empty `Vec<u8>`.
+const INLINE_ARG_SIZE: usize = 16;
+
+/// Argument of a [`Command`](crate::resp::Command).
+///
+/// Small arguments (keys, integers, flags, ...) are stored inline,
+/// bigger ones are allocated on the heap.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct CommandArg(SmallVec<[u8; INLINE_ARG_SIZE]>);
+
+impl CommandArg {
+    /// Check if the argument is stored inline, without heap allocation
+    #[must_use]
+    #[inline]
+    pub fn is_inline(&self) -> bool {
+        !self.0.spilled()
+    }
+
+    /// Bytes of the argument
+    #[must_use]
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Copy the argument bytes into a `Vec<u8>`
+    #[must_use]
+    #[inline]
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.0.to_vec()
+    }
+}
+
+impl Deref for CommandArg {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for CommandArg {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<&[u8]> for CommandArg {
+    #[inline]
+    fn from(buf: &[u8]) -> Self {
+        Self(SmallVec::from_slice(buf))
+    }
+}
+
+impl PartialEq<[u8]> for CommandArg {
+    #[inline]
+    fn eq(&self, other: &[u8]) -> bool {
+        self.0.as_slice() == other
+    }
+}
+
+impl fmt::Debug for CommandArg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&String::from_utf8_lossy(&self.0), f)
+    }
+}
 
 /// Collection of arguments of [`Command`](crate::resp::Command).
 #[derive(Clone, Default)]
 pub struct CommandArgs {
-    args: SmallVec<[Vec<u8>; 10]>,
+    args: SmallVec<[CommandArg; 10]>,
 }
 
 impl CommandArgs {
@@ -68,7 +140,17 @@ impl CommandArgs {
 
     #[inline]
     pub(crate) fn write_arg(&mut self, buf: &[u8]) {
-        self.args.push(buf.to_vec());
+        self.args.push(CommandArg::from(buf));
+    }
+
+    #[inline]
+    #[cfg(any(
+        feature = "compression-gzip",
+        feature = "compression-zstd",
+        feature = "compression-lz4"
+    ))]
+    pub(crate) fn replace_arg(&mut self, index: usize, buf: Vec<u8>) {
+        self.args[index] = CommandArg(SmallVec::from_vec(buf));
     }
 
     pub(crate) fn retain<F>(&mut self, mut f: F)
@@ -93,7 +175,7 @@ impl<'a> IntoIterator for &'a CommandArgs {
 
 /// [`CommandArgs`] iterator
 pub struct CommandArgsIterator<'a> {
-    iter: std::slice::Iter<'a, Vec<u8>>,
+    iter: std::slice::Iter<'a, CommandArg>,
 }
 
 impl<'a> Iterator for CommandArgsIterator<'a> {
@@ -101,12 +183,12 @@ impl<'a> Iterator for CommandArgsIterator<'a> {
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        self.iter.next().map(|v| v.as_slice())
+        self.iter.next().map(|v| v.as_ref())
     }
 }
 
 impl std::ops::Deref for CommandArgs {
-    type Target = [Vec<u8>];
+    type Target = [CommandArg];
 
     #[inline]
     fn deref(&self) -> &Self::Target {
@@ -122,7 +204,7 @@ impl fmt::Debug for CommandArgs {
                 &self
                     .args
                     .iter()
-                    .map(|a| String::from_utf8_lossy(a.as_slice()))
+                    .map(|a| String::from_utf8_lossy(a))
                     .collect::<Vec<_>>(),
             )
             .finish()
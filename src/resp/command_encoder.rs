@@ -1,18 +1,103 @@
 use crate::{
-    resp::{Command, CommandArgs},
+    resp::{Command, CommandArgs, StreamedArg},
     Error, Result,
 };
 use bytes::{BufMut, BytesMut};
+use smallvec::SmallVec;
+use std::io::IoSlice;
 use tokio_util::codec::Encoder;
 
+/// Minimum size of an argument written straight from the command with vectored I/O,
+/// instead of being copied into the encoding buffer
+pub(crate) const VECTORED_ARG_MIN_SIZE: usize = 4 * 1024;
+
+/// Argument written apart from the encoding buffer
+pub(crate) enum LargeArg<'a> {
+    /// large argument, written with vectored I/O
+    Slice(&'a [u8]),
+    /// argument streamed from its source, chunk by chunk
+    Streamed(&'a StreamedArg),
+}
+
+/// Large arguments of encoded commands, with their position in the encoding buffer
+pub(crate) type LargeArgs<'a> = SmallVec<[(usize, LargeArg<'a>); 4]>;
+
 pub(crate) struct CommandEncoder;
 
+impl CommandEncoder {
+    /// Encode `command` into `buf`, except the content of its large arguments
+    /// which is referenced in `large_args`, to be written with [`io_slices`]
+    pub(crate) fn encode_vectored<'a>(
+        &mut self,
+        command: &'a Command,
+        buf: &mut BytesMut,
+        large_args: &mut LargeArgs<'a>,
+    ) {
+        if is_encoded_inline(command) {
+            encode_inline(command, buf);
+            return;
+        }
+
+        buf.reserve(calculate_buf_size(command, true));
+
+        buf.put_u8(b'*');
+        encode_integer(command.args.len() as i64 + 1, buf);
+        encode_crlf(buf);
+        encode_bulkstring(command.name.as_bytes(), buf);
+
+        for (index, arg) in command.args.iter().enumerate() {
+            if let Some(streamed_arg) = command.get_streamed_arg(index) {
+                buf.put_u8(b'$');
+                encode_integer(streamed_arg.len() as i64, buf);
+                encode_crlf(buf);
+                large_args.push((buf.len(), LargeArg::Streamed(streamed_arg)));
+                encode_crlf(buf);
+            } else if arg.len() >= VECTORED_ARG_MIN_SIZE {
+                buf.put_u8(b'$');
+                encode_integer(arg.len() as i64, buf);
+                encode_crlf(buf);
+                large_args.push((buf.len(), LargeArg::Slice(arg)));
+                encode_crlf(buf);
+            } else {
+                encode_bulkstring(arg, buf);
+            }
+        }
+    }
+}
+
+/// Interleave the encoding buffer with the large arguments referenced by [`CommandEncoder::encode_vectored`]
+///
+/// Streamed arguments are not supported and must be written apart.
+pub(crate) fn io_slices<'a>(
+    buf: &'a [u8],
+    large_args: &[(usize, LargeArg<'a>)],
+) -> Vec<IoSlice<'a>> {
+    let mut slices = Vec::with_capacity(large_args.len() * 2 + 1);
+    let mut start = 0;
+
+    for (position, arg) in large_args {
+        if let LargeArg::Slice(arg) = arg {
+            slices.push(IoSlice::new(&buf[start..*position]));
+            slices.push(IoSlice::new(arg));
+            start = *position;
+        }
+    }
+
+    slices.push(IoSlice::new(&buf[start..]));
+    slices
+}
+
 impl Encoder<&Command> for CommandEncoder {
     type Error = Error;
 
     #[inline]
     fn encode(&mut self, command: &Command, buf: &mut BytesMut) -> Result<()> {
-        buf.reserve(calculate_buf_size(command));
+        if is_encoded_inline(command) {
+            encode_inline(command, buf);
+            return Ok(());
+        }
+
+        buf.reserve(calculate_buf_size(command, false));
 
         buf.put_u8(b'*');
         encode_integer(command.args.len() as i64 + 1, buf);
@@ -23,8 +108,14 @@ impl Encoder<&Command> for CommandEncoder {
     }
 }
 
+/// Size of the RESP encoding of `command`, large arguments excluded if `vectored` is `true`
 #[inline]
-fn calculate_buf_size(command: &Command) -> usize {
+pub(crate) fn calculate_buf_size(command: &Command, vectored: bool) -> usize {
+    if is_encoded_inline(command) {
+        // <name> <arg>...\r\n, escape sequences excluded
+        return command.name.len() + command.args.iter().map(|a| a.len() + 3).sum::<usize>() + 2;
+    }
+
     let mut buf_size = 0;
 
     // *<num_args>\r\n
@@ -43,6 +134,9 @@ fn calculate_buf_size(command: &Command) -> usize {
         // $<arg_len>\r\n<arg>\r\n
         buf_size += if arg.len() <= 9 {
             6 + arg.len()
+        } else if vectored && arg.len() >= VECTORED_ARG_MIN_SIZE {
+            // only $<arg_len>\r\n & \r\n are copied into the buffer
+            5 + 20
         } else {
             7 + arg.len()
         };
@@ -51,6 +145,56 @@ fn calculate_buf_size(command: &Command) -> usize {
     buf_size
 }
 
+/// Inline commands with streamed arguments are encoded as RESP arrays
+#[inline]
+pub(crate) fn is_encoded_inline(command: &Command) -> bool {
+    command.inline && command.streamed_args.is_empty()
+}
+
+/// Encode `command` in the inline format: `<name> <arg>...\r\n`
+fn encode_inline(command: &Command, buf: &mut BytesMut) {
+    buf.reserve(calculate_buf_size(command, false));
+
+    buf.put(command.name.as_bytes());
+    for arg in &command.args {
+        buf.put_u8(b' ');
+        encode_inline_arg(arg, buf);
+    }
+    encode_crlf(buf);
+}
+
+/// Encode an argument of an inline command,
+/// between double quotes with escape sequences if it cannot be sent as is
+fn encode_inline_arg(arg: &[u8], buf: &mut BytesMut) {
+    if !arg.is_empty()
+        && arg
+            .iter()
+            .all(|b| b.is_ascii_graphic() && *b != b'"' && *b != b'\'')
+    {
+        buf.put(arg);
+        return;
+    }
+
+    buf.put_u8(b'"');
+    for b in arg {
+        match b {
+            b'"' => buf.put(&b"\\\""[..]),
+            b'\\' => buf.put(&b"\\\\"[..]),
+            b'\n' => buf.put(&b"\\n"[..]),
+            b'\r' => buf.put(&b"\\r"[..]),
+            b'\t' => buf.put(&b"\\t"[..]),
+            b' '..=b'~' => buf.put_u8(*b),
+            _ => {
+                const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+                buf.put(&b"\\x"[..]);
+                buf.put_u8(HEX_DIGITS[(b >> 4) as usize]);
+                buf.put_u8(HEX_DIGITS[(b & 0x0f) as usize]);
+            }
+        }
+    }
+    buf.put_u8(b'"');
+}
+
 #[inline]
 fn encode_bulkstring(arg: &[u8], buf: &mut BytesMut) {
     buf.put_u8(b'$');
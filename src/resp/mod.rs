@@ -14,6 +14,10 @@ deserializer implementation of a [`resp::Value`](Value) reference.
 
 This conversion is easily accessible through the associate function [`Value::into`](Value::into).
 
+For simple cases, strict accessors like [`Value::as_i64`], [`Value::as_map`] or [`Value::into_array`]
+and `TryFrom<Value>` implementations for common std types are also provided.
+Their errors name the variant actually encountered.
+
 # Command arguments
 
 **rustis** provides an idiomatic way to pass arguments to [commands](crate::commands).
@@ -273,6 +277,12 @@ The different command traits implementations ([`Client`](crate::client::Client),
  serde [`Deserialize`](https://docs.rs/serde/latest/serde/trait.Deserialize.html) trait,
  in order to deserialize it automatically from a RESP Buffer.
 
+Serde attributes which buffer the content of a map, like `#[serde(flatten)]`
+or internally tagged enums (`#[serde(tag = "...")]`), are supported.
+However, buffered values keep their RESP type:
+numeric fields must be received as RESP3 integers or doubles,
+and cannot be parsed from bulk strings (e.g. the values of [`hgetall`](crate::commands::HashCommands::hgetall)).
+
 Some more advanced traits allow to constraint more which Rust types are allowed for specific commands.
 
 For each trait, you can add your own implementations for your custom types
@@ -430,6 +440,12 @@ async fn main() -> Result<()> {
     Ok(())
 }
 ```
+
+# Writing RESP
+
+[`RespWriter`] writes commands and values in the RESP format into any [`std::io::Write`]
+or [`tokio::io::AsyncWrite`] sink, with the same encoder as the client connections.
+It can be used to build proxies, fixture generators or AOF files.
 */
 
 mod buffer_decoder;
@@ -441,15 +457,21 @@ mod resp_batch_deserializer;
 mod resp_buf;
 mod resp_deserializer;
 mod resp_serializer;
+mod resp_writer;
 mod response;
+mod streamed_arg;
 mod to_args;
 mod util;
 mod value;
+mod value_conversion;
 mod value_deserialize;
 mod value_deserializer;
+#[cfg(feature = "json")]
+mod value_json;
+mod value_pretty;
 mod value_serialize;
 
-pub(crate) use buffer_decoder::*;
+pub use buffer_decoder::*;
 pub use bulk_string::*;
 pub use command::*;
 pub use command_args::*;
@@ -458,8 +480,12 @@ pub(crate) use resp_batch_deserializer::*;
 pub use resp_buf::*;
 pub use resp_deserializer::*;
 pub use resp_serializer::*;
+pub use resp_writer::*;
 pub use response::*;
+pub use streamed_arg::*;
 pub use to_args::*;
 pub use util::*;
 pub use value::*;
 pub(crate) use value_deserialize::*;
+#[cfg(feature = "json")]
+pub use value_json::*;
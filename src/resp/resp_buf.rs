@@ -6,7 +6,7 @@ use crate::{
 };
 use bytes::{BufMut, Bytes, BytesMut};
 use serde::Deserialize;
-use std::{fmt, ops::Deref};
+use std::{collections::HashMap, fmt, ops::Deref};
 
 /// Represents a [RESP](https://redis.io/docs/reference/protocol-spec/) Buffer incoming from the network
 #[derive(Clone)]
@@ -68,6 +68,17 @@ impl RespBuf {
         T::deserialize(&mut deserializer)
     }
 
+    /// Convert the RESP Buffer to a Rust type `T` by using serde deserialization,
+    /// along with the [RESP3 attributes](RespDeserializer::take_attributes) met on the way
+    #[inline]
+    pub fn to_with_attributes<'de, T: Deserialize<'de>>(
+        &'de self,
+    ) -> Result<(T, Vec<HashMap<Value, Value>>)> {
+        let mut deserializer = RespDeserializer::new(&self.0);
+        let value = T::deserialize(&mut deserializer)?;
+        Ok((value, deserializer.take_attributes()))
+    }
+
     /// Returns the internal buffer as a byte slice
     #[inline]
     pub fn as_bytes(&self) -> &[u8] {
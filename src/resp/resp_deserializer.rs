@@ -1,10 +1,25 @@
-use crate::{resp::PUSH_FAKE_FIELD, Error, RedisError, Result};
+use crate::{
+    resp::{
+        FakeFieldMapAccess, Value, ATTRIBUTE_FAKE_FIELD, BIG_NUMBER_FAKE_FIELD, PUSH_FAKE_FIELD,
+        VALUE_FAKE_FIELD, VERBATIM_STRING_FAKE_FIELD,
+    },
+    Error, RedisError, Result,
+};
 use memchr::memchr;
 use serde::{
-    de::{DeserializeSeed, EnumAccess, IntoDeserializer, VariantAccess, Visitor},
-    forward_to_deserialize_any, Deserializer,
+    de::{
+        value::{
+            BorrowedBytesDeserializer, BorrowedStrDeserializer, MapAccessDeserializer,
+            SeqAccessDeserializer,
+        },
+        DeserializeSeed, EnumAccess, IntoDeserializer, VariantAccess, Visitor,
+    },
+    forward_to_deserialize_any, Deserialize, Deserializer,
+};
+use std::{
+    collections::HashMap,
+    str::{self, FromStr},
 };
-use std::str::{self, FromStr};
 
 pub(crate) const SIMPLE_STRING_TAG: u8 = b'+';
 pub(crate) const ERROR_TAG: u8 = b'-';
@@ -19,6 +34,8 @@ pub(crate) const BOOL_TAG: u8 = b'#';
 pub(crate) const VERBATIM_STRING_TAG: u8 = b'=';
 pub(crate) const PUSH_TAG: u8 = b'>';
 pub(crate) const BLOB_ERROR_TAG: u8 = b'!';
+pub(crate) const BIG_NUMBER_TAG: u8 = b'(';
+pub(crate) const ATTRIBUTE_TAG: u8 = b'|';
 
 #[inline(always)]
 fn eof<T>() -> Result<T> {
@@ -30,6 +47,7 @@ pub struct RespDeserializer<'de> {
     buf: &'de [u8],
     pos: usize,
     eat_error: bool,
+    attributes: Vec<HashMap<Value, Value>>,
 }
 
 impl<'de> RespDeserializer<'de> {
@@ -40,6 +58,7 @@ impl<'de> RespDeserializer<'de> {
             buf,
             pos: 0,
             eat_error: true,
+            attributes: Vec::new(),
         }
     }
 
@@ -49,11 +68,24 @@ impl<'de> RespDeserializer<'de> {
         self.pos
     }
 
+    /// Take the [RESP3 attributes](https://github.com/redis/redis-specifications/blob/master/protocol/RESP3.md#attribute-type)
+    /// met so far, in the order they were received
+    ///
+    /// When deserializing to a type other than [`Value`], attributes are collected on this side channel
+    /// instead of being dropped. A [`Value`] keeps its attributes in [`Value::Attribute`].
+    pub fn take_attributes(&mut self) -> Vec<HashMap<Value, Value>> {
+        std::mem::take(&mut self.attributes)
+    }
+
     // Look at the first byte in the input without consuming it.
+    // Attributes are moved to the side channel on the way.
     #[inline]
     fn peek(&mut self) -> Result<u8> {
         if let Some(&byte) = self.buf.get(self.pos) {
-            if self.eat_error {
+            if byte == ATTRIBUTE_TAG {
+                self.parse_attributes()?;
+                self.peek()
+            } else if self.eat_error {
                 match byte {
                     ERROR_TAG => {
                         self.advance();
@@ -252,7 +284,7 @@ impl<'de> RespDeserializer<'de> {
                     })
                 }
             }
-            SIMPLE_STRING_TAG => {
+            SIMPLE_STRING_TAG | BIG_NUMBER_TAG => {
                 let next_line = self.next_line()?;
                 atoi::atoi(next_line).ok_or_else(|| {
                     Error::Client(format!(
@@ -295,7 +327,7 @@ impl<'de> RespDeserializer<'de> {
                         .map_err(|_| Error::Client("Cannot parse number".to_owned()))
                 }
             }
-            SIMPLE_STRING_TAG => {
+            SIMPLE_STRING_TAG | BIG_NUMBER_TAG => {
                 let next_line = self.next_line()?;
                 fast_float::parse(next_line)
                     .map_err(|_| Error::Client("Cannot parse number".to_owned()))
@@ -306,6 +338,18 @@ impl<'de> RespDeserializer<'de> {
         }
     }
 
+    fn parse_attributes(&mut self) -> Result<()> {
+        self.advance();
+        let len = self.parse_integer::<usize>()?;
+        let attributes =
+            HashMap::<Value, Value>::deserialize(MapAccessDeserializer::new(MapAccess {
+                de: self,
+                len,
+            }))?;
+        self.attributes.push(attributes);
+        Ok(())
+    }
+
     #[inline]
     fn parse_error(&mut self) -> Result<RedisError> {
         let str = self.parse_string()?;
@@ -352,10 +396,18 @@ impl<'de> RespDeserializer<'de> {
     #[inline]
     fn ignore_value(&mut self) -> Result<()> {
         self.eat_error = false;
-        match self.next()? {
-            SIMPLE_STRING_TAG | ERROR_TAG | INTEGER_TAG | DOUBLE_TAG | NIL_TAG | BOOL_TAG => {
-                self.ignore_line()
+        if let Some(&ATTRIBUTE_TAG) = self.buf.get(self.pos) {
+            self.advance();
+            let len = self.parse_integer::<usize>()? * 2;
+            for _ in 0..len {
+                self.ignore_value()?;
             }
+            return self.ignore_value();
+        }
+
+        match self.next()? {
+            SIMPLE_STRING_TAG | ERROR_TAG | INTEGER_TAG | DOUBLE_TAG | NIL_TAG | BOOL_TAG
+            | BIG_NUMBER_TAG => self.ignore_line(),
             BULK_STRING_TAG | BLOB_ERROR_TAG | VERBATIM_STRING_TAG => self.ignore_bulk_string(),
             ARRAY_TAG | SET_TAG | PUSH_TAG => {
                 let len = self.parse_integer::<usize>()?;
@@ -407,6 +459,7 @@ impl<'de, 'a> Deserializer<'de> for &'a mut RespDeserializer<'de> {
             NIL_TAG => self.deserialize_option(visitor),
             BOOL_TAG => self.deserialize_bool(visitor),
             VERBATIM_STRING_TAG => self.deserialize_bytes(visitor),
+            BIG_NUMBER_TAG => self.deserialize_str(visitor),
             PUSH_TAG => visitor.visit_map(PushMapAccess::new(self)),
             ERROR_TAG => Err(Error::Redis(self.parse_error()?)),
             BLOB_ERROR_TAG => Err(Error::Redis(self.parse_blob_error()?)),
@@ -573,7 +626,7 @@ impl<'de, 'a> Deserializer<'de> for &'a mut RespDeserializer<'de> {
                 let bs = self.parse_verbatim_string()?;
                 str::from_utf8(bs)?
             }
-            SIMPLE_STRING_TAG => self.parse_string()?,
+            SIMPLE_STRING_TAG | BIG_NUMBER_TAG => self.parse_string()?,
             NIL_TAG => {
                 self.parse_nil()?;
                 ""
@@ -609,7 +662,7 @@ impl<'de, 'a> Deserializer<'de> for &'a mut RespDeserializer<'de> {
                 self.parse_nil()?;
                 String::from("")
             }
-            SIMPLE_STRING_TAG => self.parse_string()?.to_owned(),
+            SIMPLE_STRING_TAG | BIG_NUMBER_TAG => self.parse_string()?.to_owned(),
             ERROR_TAG => return Err(Error::Redis(self.parse_error()?)),
             BLOB_ERROR_TAG => return Err(Error::Redis(self.parse_blob_error()?)),
             _ => {
@@ -716,12 +769,49 @@ impl<'de, 'a> Deserializer<'de> for &'a mut RespDeserializer<'de> {
     // As is done here, serializers are encouraged to treat newtype structs as
     // insignificant wrappers around the data they contain. That means not
     // parsing anything other than the contained value.
+    //
+    // The only exception is `Value`, which needs RESP3 types without serde equivalent
+    // to be passed as fake field maps.
     #[inline]
-    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_newtype_struct(self)
+        if name != VALUE_FAKE_FIELD {
+            return visitor.visit_newtype_struct(self);
+        }
+
+        match self.buf.get(self.pos) {
+            Some(&ATTRIBUTE_TAG) => {
+                self.advance();
+                let len = self.parse_integer()?;
+                visitor.visit_map(FakeFieldMapAccess::new(
+                    ATTRIBUTE_FAKE_FIELD,
+                    SeqAccessDeserializer::new(AttributeSeqAccess {
+                        de: self,
+                        len,
+                        idx: 0,
+                    }),
+                ))
+            }
+            Some(&BIG_NUMBER_TAG) => {
+                self.advance();
+                let str = self.parse_string()?;
+                visitor.visit_map(FakeFieldMapAccess::new(
+                    BIG_NUMBER_FAKE_FIELD,
+                    BorrowedStrDeserializer::new(str),
+                ))
+            }
+            Some(&VERBATIM_STRING_TAG) => {
+                self.advance();
+                let bs = self.parse_bulk_string()?;
+                visitor.visit_map(FakeFieldMapAccess::new(
+                    VERBATIM_STRING_FAKE_FIELD,
+                    BorrowedBytesDeserializer::new(bs),
+                ))
+            }
+            _ => self.deserialize_any(visitor),
+        }
     }
 
     fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
@@ -1211,6 +1301,39 @@ impl<'de> Deserializer<'de> for PushFieldDeserializer {
     }
 }
 
+/// Attributes, then attributed value, of a RESP3 attribute
+struct AttributeSeqAccess<'de, 'a> {
+    de: &'a mut RespDeserializer<'de>,
+    len: usize,
+    idx: usize,
+}
+
+impl<'de, 'a> serde::de::SeqAccess<'de> for AttributeSeqAccess<'de, 'a> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.idx += 1;
+        match self.idx {
+            1 => seed
+                .deserialize(MapAccessDeserializer::new(MapAccess {
+                    de: &mut *self.de,
+                    len: self.len,
+                }))
+                .map(Some),
+            2 => seed.deserialize(&mut *self.de).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        Some(2usize.saturating_sub(self.idx))
+    }
+}
+
 struct PushDeserializer<'de, 'a> {
     de: &'a mut RespDeserializer<'de>,
 }
@@ -1,7 +1,8 @@
 use crate::{
     resp::{
-        ARRAY_TAG, BULK_STRING_TAG, DOUBLE_TAG, ERROR_TAG, INTEGER_TAG, MAP_TAG, PUSH_FAKE_FIELD,
-        PUSH_TAG, SET_TAG, SIMPLE_STRING_TAG,
+        ARRAY_TAG, ATTRIBUTE_FAKE_FIELD, ATTRIBUTE_TAG, BIG_NUMBER_FAKE_FIELD, BIG_NUMBER_TAG,
+        BULK_STRING_TAG, DOUBLE_TAG, ERROR_TAG, INTEGER_TAG, MAP_TAG, PUSH_FAKE_FIELD, PUSH_TAG,
+        SET_TAG, SIMPLE_STRING_TAG, VERBATIM_STRING_FAKE_FIELD, VERBATIM_STRING_TAG,
     },
     Error,
 };
@@ -22,7 +23,8 @@ pub(crate) const ERROR_FAKE_FIELD: &str = "---ERROR---";
 /// Serde serializer for [`RESP3`](https://github.com/redis/redis-specifications/blob/master/protocol/RESP3.md)
 pub struct RespSerializer {
     output: BytesMut,
-    is_error: bool,
+    /// Tag overriding the default one of the next string or byte buffer
+    tag: Option<u8>,
 }
 
 impl RespSerializer {
@@ -30,7 +32,7 @@ impl RespSerializer {
     pub fn new() -> Self {
         Self {
             output: BytesMut::new(),
-            is_error: false,
+            tag: None,
         }
     }
 
@@ -140,19 +142,16 @@ impl<'a> Serializer for &'a mut RespSerializer {
     }
 
     fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
-        if self.is_error {
-            self.is_error = false;
-            self.output.put_u8(ERROR_TAG);
-        } else {
-            self.output.put_u8(SIMPLE_STRING_TAG);
-        }
+        let tag = self.tag.take().unwrap_or(SIMPLE_STRING_TAG);
+        self.output.put_u8(tag);
         self.output.put_slice(v.as_bytes());
         self.output.put_slice(b"\r\n");
         Ok(())
     }
 
     fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
-        self.output.put_u8(BULK_STRING_TAG);
+        let tag = self.tag.take().unwrap_or(BULK_STRING_TAG);
+        self.output.put_u8(tag);
         self.serialize_raw_integer(v.len());
         self.output.put_slice(v);
         self.output.put_slice(b"\r\n");
@@ -196,8 +195,11 @@ impl<'a> Serializer for &'a mut RespSerializer {
     where
         T: serde::Serialize + ?Sized,
     {
-        if name == ERROR_FAKE_FIELD {
-            self.is_error = true;
+        match name {
+            ERROR_FAKE_FIELD => self.tag = Some(ERROR_TAG),
+            BIG_NUMBER_FAKE_FIELD => self.tag = Some(BIG_NUMBER_TAG),
+            VERBATIM_STRING_FAKE_FIELD => self.tag = Some(VERBATIM_STRING_TAG),
+            _ => (),
         }
         value.serialize(self)
     }
@@ -251,6 +253,12 @@ impl<'a> Serializer for &'a mut RespSerializer {
                 self.serialize_raw_integer(len);
                 Ok(self)
             }
+            // attributes entries as fields, followed by the attributed value
+            ATTRIBUTE_FAKE_FIELD => {
+                self.output.put_u8(ATTRIBUTE_TAG);
+                self.serialize_raw_integer(len);
+                Ok(self)
+            }
             _ => self.serialize_seq(Some(len)),
         }
     }
@@ -0,0 +1,217 @@
+use crate::{
+    resp::{Command, CommandEncoder, LargeArg, LargeArgs, RespSerializer, StreamedArg},
+    Error, Result,
+};
+use bytes::BytesMut;
+use futures_util::StreamExt;
+use serde::Serialize;
+use std::io::Write;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio_util::codec::Encoder;
+
+/// Writer of [`RESP`](https://github.com/redis/redis-specifications/blob/master/protocol/RESP3.md)
+/// commands and values into any sink
+///
+/// Commands are encoded exactly as **rustis** sends them to the server
+/// (RESP arrays of bulk strings, or the inline format for [inline commands](Command::inline)),
+/// and values as the server replies, with [`RespSerializer`].
+/// This makes it possible to write proxies, fixture generators or AOF files
+/// without reimplementing the protocol.
+///
+/// The sink is either a synchronous [`std::io::Write`] or an asynchronous [`tokio::io::AsyncWrite`].
+/// Commands with [streamed arguments](Command::streamed_arg) can only be written asynchronously,
+/// their source being consumed by the write.
+///
+/// # Example
+/// ```
+/// use rustis::{resp::{cmd, RespWriter, Value}, Result};
+///
+/// fn main() -> Result<()> {
+///     let mut writer = RespWriter::new(Vec::new());
+///     writer.write_command(&cmd("SET").arg("key").arg("value"))?;
+///     writer.write_value(&Value::SimpleString("OK".to_owned()))?;
+///
+///     assert_eq!(
+///         b"*3\r\n$3\r\nSET\r\n$3\r\nkey\r\n$5\r\nvalue\r\n+OK\r\n",
+///         writer.into_inner().as_slice()
+///     );
+///
+///     Ok(())
+/// }
+/// ```
+pub struct RespWriter<W> {
+    writer: W,
+    buf: BytesMut,
+}
+
+impl<W> RespWriter<W> {
+    /// Creates a new `RespWriter` writing into `writer`
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            buf: BytesMut::new(),
+        }
+    }
+
+    /// Reference to the underlying sink
+    #[inline]
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Mutable reference to the underlying sink
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Consumes the `RespWriter`, returning the underlying sink
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn encode_command(&mut self, command: &Command) -> Result<()> {
+        if !command.streamed_args.is_empty() {
+            return Err(Error::Client(
+                "Commands with streamed arguments can only be written asynchronously".to_owned(),
+            ));
+        }
+
+        self.buf.clear();
+        CommandEncoder.encode(command, &mut self.buf)
+    }
+}
+
+impl<W: Write> RespWriter<W> {
+    /// Writes the RESP encoding of `command`
+    ///
+    /// # Errors
+    /// An error is returned if `command` has [streamed arguments](Command::streamed_arg)
+    /// or if the sink fails.
+    pub fn write_command(&mut self, command: &Command) -> Result<()> {
+        self.encode_command(command)?;
+        self.writer.write_all(&self.buf)?;
+        Ok(())
+    }
+
+    /// Writes the RESP encoding of `value`
+    ///
+    /// `value` is usually a [`Value`](crate::resp::Value)
+    /// but can be any type supported by [`RespSerializer`].
+    pub fn write_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let mut serializer = RespSerializer::new();
+        value.serialize(&mut serializer)?;
+        self.writer.write_all(&serializer.get_output())?;
+        Ok(())
+    }
+
+    /// Flushes the underlying sink
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+impl<W: AsyncWrite + Unpin> RespWriter<W> {
+    /// Writes the RESP encoding of `command` asynchronously
+    ///
+    /// [Streamed arguments](Command::streamed_arg) are read from their source
+    /// and written chunk by chunk.
+    pub async fn write_command_async(&mut self, command: &Command) -> Result<()> {
+        if command.streamed_args.is_empty() {
+            self.encode_command(command)?;
+            self.writer.write_all(&self.buf).await?;
+            return Ok(());
+        }
+
+        self.buf.clear();
+        let mut large_args = LargeArgs::new();
+        CommandEncoder.encode_vectored(command, &mut self.buf, &mut large_args);
+        write_all_streamed(&mut self.writer, &self.buf, &large_args).await
+    }
+
+    /// Writes the RESP encoding of `value` asynchronously
+    ///
+    /// `value` is usually a [`Value`](crate::resp::Value)
+    /// but can be any type supported by [`RespSerializer`].
+    pub async fn write_value_async<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let mut serializer = RespSerializer::new();
+        value.serialize(&mut serializer)?;
+        self.writer.write_all(&serializer.get_output()).await?;
+        Ok(())
+    }
+
+    /// Flushes the underlying sink asynchronously
+    pub async fn flush_async(&mut self) -> Result<()> {
+        self.writer.flush().await?;
+        Ok(())
+    }
+}
+
+/// Write the encoding buffer interleaved with the large arguments, streamed arguments
+/// being written chunk by chunk as they are read from their source
+pub(crate) async fn write_all_streamed<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    buf: &[u8],
+    large_args: &[(usize, LargeArg<'_>)],
+) -> Result<()> {
+    let mut start = 0;
+
+    for (position, arg) in large_args {
+        writer.write_all(&buf[start..*position]).await?;
+        match arg {
+            LargeArg::Slice(arg) => writer.write_all(arg).await?,
+            LargeArg::Streamed(arg) => write_streamed_arg(writer, arg).await?,
+        }
+        start = *position;
+    }
+
+    writer.write_all(&buf[start..]).await?;
+    Ok(())
+}
+
+async fn write_streamed_arg<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    arg: &StreamedArg,
+) -> Result<()> {
+    // the bulk string header has already been written:
+    // failing from now on breaks the protocol, hence the I/O errors, which close the connection
+    let Some(mut source) = arg.take_source() else {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "streamed argument has already been consumed",
+        )
+        .into());
+    };
+
+    let mut remaining = arg.len();
+    while let Some(chunk) = source.next().await {
+        let chunk = chunk?;
+        if chunk.len() > remaining {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "streamed argument exceeds its length of {} bytes",
+                    arg.len()
+                ),
+            )
+            .into());
+        }
+        writer.write_all(&chunk).await?;
+        remaining -= chunk.len();
+    }
+
+    if remaining > 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            format!(
+                "streamed argument ended {remaining} bytes before its length of {} bytes",
+                arg.len()
+            ),
+        )
+        .into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use bytes::{Bytes, BytesMut};
+use futures_util::{stream::BoxStream, Stream, StreamExt};
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Size of the chunks read by [`StreamedArg::from_async_read`]
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+type ChunkStream = BoxStream<'static, std::io::Result<Bytes>>;
+
+/// Command argument of a known length, streamed from an asynchronous source
+/// straight to the socket, chunk by chunk, instead of being loaded in memory.
+///
+/// Useful for huge arguments, like the serialized value of a
+/// [`restore_streamed`](crate::commands::GenericCommands::restore_streamed).
+///
+/// A streamed argument can only be sent once:
+/// if a command has to be retried, after a reconnection for instance, it fails instead.
+///
+/// Streamed arguments are written by standalone, sentinel & cluster connections only,
+/// mock & recording connections just see an empty argument.
+///
+/// # Example
+/// ```
+/// use rustis::{
+///     client::Client,
+///     resp::{cmd, StreamedArg},
+///     Result,
+/// };
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let client = Client::connect("127.0.0.1:6379").await?;
+///
+///     let value = b"a value which could be read from a file".to_vec();
+///     let len = value.len();
+///
+///     client
+///         .send(
+///             cmd("SET")
+///                 .arg("key")
+///                 .streamed_arg(StreamedArg::from_async_read(len, std::io::Cursor::new(value))),
+///             None,
+///         )
+///         .await?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct StreamedArg {
+    len: usize,
+    source: Arc<Mutex<Option<ChunkStream>>>,
+}
+
+impl StreamedArg {
+    /// Streamed argument of `len` bytes, read from a stream of chunks
+    ///
+    /// The stream must yield exactly `len` bytes.
+    pub fn from_stream<S>(len: usize, stream: S) -> Self
+    where
+        S: Stream<Item = std::io::Result<Bytes>> + Send + 'static,
+    {
+        Self {
+            len,
+            source: Arc::new(Mutex::new(Some(stream.boxed()))),
+        }
+    }
+
+    /// Streamed argument of `len` bytes, read from `reader`
+    ///
+    /// `reader` must provide at least `len` bytes, extra bytes are not read.
+    pub fn from_async_read<R>(len: usize, reader: R) -> Self
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+    {
+        let stream = futures_util::stream::unfold(
+            (reader.take(len as u64), false),
+            |(mut reader, done)| async move {
+                if done {
+                    return None;
+                }
+
+                let mut chunk = BytesMut::with_capacity(READ_CHUNK_SIZE);
+                match reader.read_buf(&mut chunk).await {
+                    Ok(0) => None,
+                    Ok(_) => Some((Ok(chunk.freeze()), (reader, false))),
+                    Err(e) => Some((Err(e), (reader, true))),
+                }
+            },
+        );
+
+        Self::from_stream(len, stream)
+    }
+
+    /// Length of the argument in bytes
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// `true` if the argument is empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Take the source of the argument, which can only be consumed once
+    pub(crate) fn take_source(&self) -> Option<ChunkStream> {
+        self.source.lock().ok()?.take()
+    }
+}
+
+impl fmt::Debug for StreamedArg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamedArg")
+            .field("len", &self.len)
+            .finish()
+    }
+}
@@ -1,4 +1,4 @@
-use crate::resp::{BulkString, CommandArgs};
+use crate::resp::{BulkString, CommandArg, CommandArgs};
 use dtoa::Float;
 use itoa::Integer;
 use smallvec::SmallVec;
@@ -134,6 +134,13 @@ impl ToArgs for Vec<u8> {
     }
 }
 
+impl ToArgs for CommandArg {
+    #[inline]
+    fn write_args(&self, args: &mut CommandArgs) {
+        args.write_arg(self.as_slice());
+    }
+}
+
 impl ToArgs for &[u8] {
     #[inline]
     fn write_args(&self, args: &mut CommandArgs) {
@@ -424,6 +431,7 @@ impl<const N: usize> SingleArg for [u8; N] {}
 impl SingleArg for &[u8] {}
 impl SingleArg for Vec<u8> {}
 impl SingleArg for BulkString {}
+impl SingleArg for CommandArg {}
 impl<T: SingleArg> SingleArg for Option<T> {}
 
 /// Generic Marker for Collections of `ToArgs`
@@ -3,7 +3,7 @@ use serde::de::DeserializeOwned;
 use std::{
     collections::HashMap,
     fmt::{self, Display, Formatter, Write},
-    hash::{Hash, Hasher},
+    hash::{DefaultHasher, Hash, Hasher},
 };
 
 /// Generic Redis Object Model
@@ -31,6 +31,28 @@ pub enum Value {
     Push(Vec<Value>),
     /// [RESP Error](https://redis.io/docs/reference/protocol-spec/#resp-errors)
     Error(RedisError),
+    /// [RESP3](https://github.com/antirez/RESP3/blob/master/spec.md) Big number, in its decimal representation
+    BigNumber(String),
+    /// [RESP3](https://github.com/antirez/RESP3/blob/master/spec.md) Verbatim string
+    ///
+    /// # Breaking change
+    /// Verbatim strings, returned in RESP3 by commands like `INFO` or `LATENCY DOCTOR`,
+    /// used to be decoded as a [`Value::BulkString`] holding the text only.
+    /// Code matching on [`Value::BulkString`] must now also match this variant.
+    /// Conversions like [`Value::as_str`], [`Value::into_string`] or deserializing to a `String`
+    /// accept both variants and return the text without its format.
+    VerbatimString {
+        /// Format of the text, e.g. `txt` for plain text or `mkd` for markdown
+        format: String,
+        text: Vec<u8>,
+    },
+    /// [RESP3](https://github.com/antirez/RESP3/blob/master/spec.md) Attribute,
+    /// auxiliary data attached by the server to a reply
+    Attribute {
+        attributes: HashMap<Value, Value>,
+        /// The reply the attributes are attached to
+        value: Box<Value>,
+    },
     /// [RESP Null](https://redis.io/docs/reference/protocol-spec/#resp-bulk-strings)
     #[default]
     Nil,
@@ -58,12 +80,34 @@ impl Hash for Value {
             Value::Double(d) => d.to_string().hash(state),
             Value::BulkString(bs) => bs.hash(state),
             Value::Error(e) => e.hash(state),
+            Value::BigNumber(n) => n.hash(state),
+            Value::VerbatimString { text, .. } => text.hash(state),
+            Value::Boolean(b) => b.hash(state),
+            Value::Array(values) | Value::Set(values) | Value::Push(values) => values.hash(state),
+            Value::Map(values) => hash_map(values, state),
+            Value::Attribute { attributes, value } => {
+                hash_map(attributes, state);
+                value.hash(state);
+            }
             Value::Nil => "_\r\n".hash(state),
-            _ => unimplemented!("Hash not implemented for {self}"),
         }
     }
 }
 
+/// Hash the entries of `map` regardless of their iteration order
+fn hash_map<H: Hasher>(map: &HashMap<Value, Value>, state: &mut H) {
+    let entries_hash = map
+        .iter()
+        .map(|entry| {
+            let mut hasher = DefaultHasher::new();
+            entry.hash(&mut hasher);
+            hasher.finish()
+        })
+        .fold(0u64, u64::wrapping_add);
+    map.len().hash(state);
+    entries_hash.hash(state);
+}
+
 impl PartialEq for Value {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
@@ -71,11 +115,33 @@ impl PartialEq for Value {
             (Self::Integer(l0), Self::Integer(r0)) => l0 == r0,
             (Self::Double(l0), Self::Double(r0)) => l0 == r0,
             (Self::BulkString(l0), Self::BulkString(r0)) => l0 == r0,
+            (Self::Boolean(l0), Self::Boolean(r0)) => l0 == r0,
             (Self::Array(l0), Self::Array(r0)) => l0 == r0,
             (Self::Map(l0), Self::Map(r0)) => l0 == r0,
             (Self::Set(l0), Self::Set(r0)) => l0 == r0,
             (Self::Push(l0), Self::Push(r0)) => l0 == r0,
             (Self::Error(l0), Self::Error(r0)) => l0 == r0,
+            (Self::BigNumber(l0), Self::BigNumber(r0)) => l0 == r0,
+            (
+                Self::VerbatimString {
+                    format: l0,
+                    text: l1,
+                },
+                Self::VerbatimString {
+                    format: r0,
+                    text: r1,
+                },
+            ) => l0 == r0 && l1 == r1,
+            (
+                Self::Attribute {
+                    attributes: l0,
+                    value: l1,
+                },
+                Self::Attribute {
+                    attributes: r0,
+                    value: r1,
+                },
+            ) => l0 == r0 && l1 == r1,
             _ => core::mem::discriminant(self) == core::mem::discriminant(other),
         }
     }
@@ -142,6 +208,9 @@ impl Display for Value {
                 f.write_char(']')
             }
             Value::Error(e) => e.fmt(f),
+            Value::BigNumber(n) => n.fmt(f),
+            Value::VerbatimString { text, .. } => String::from_utf8_lossy(text).fmt(f),
+            Value::Attribute { value, .. } => value.fmt(f),
             Value::Nil => f.write_str("Nil"),
         }
     }
@@ -163,6 +232,17 @@ impl fmt::Debug for Value {
             Self::Set(arg0) => f.debug_tuple("Set").field(arg0).finish(),
             Self::Push(arg0) => f.debug_tuple("Push").field(arg0).finish(),
             Self::Error(arg0) => f.debug_tuple("Error").field(arg0).finish(),
+            Self::BigNumber(arg0) => f.debug_tuple("BigNumber").field(arg0).finish(),
+            Self::VerbatimString { format, text } => f
+                .debug_struct("VerbatimString")
+                .field("format", format)
+                .field("text", &String::from_utf8_lossy(text).into_owned())
+                .finish(),
+            Self::Attribute { attributes, value } => f
+                .debug_struct("Attribute")
+                .field("attributes", attributes)
+                .field("value", value)
+                .finish(),
             Self::Nil => write!(f, "Nil"),
         }
     }
@@ -0,0 +1,265 @@
+use crate::{resp::Value, Error, Result};
+use std::collections::HashMap;
+
+impl Value {
+    /// Name of the variant of the value, e.g. `"BulkString"`
+    #[must_use]
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            Value::SimpleString(_) => "SimpleString",
+            Value::Integer(_) => "Integer",
+            Value::Double(_) => "Double",
+            Value::BulkString(_) => "BulkString",
+            Value::Boolean(_) => "Boolean",
+            Value::Array(_) => "Array",
+            Value::Map(_) => "Map",
+            Value::Set(_) => "Set",
+            Value::Push(_) => "Push",
+            Value::Error(_) => "Error",
+            Value::BigNumber(_) => "BigNumber",
+            Value::VerbatimString { .. } => "VerbatimString",
+            Value::Attribute { .. } => "Attribute",
+            Value::Nil => "Nil",
+        }
+    }
+
+    /// Integer of a [`Value::Integer`]
+    #[must_use]
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Integer(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    /// Double of a [`Value::Double`]
+    #[must_use]
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Double(d) => Some(*d),
+            _ => None,
+        }
+    }
+
+    /// Boolean of a [`Value::Boolean`]
+    #[must_use]
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// String of a [`Value::SimpleString`], or of a valid UTF-8 [`Value::BulkString`] or [`Value::VerbatimString`]
+    #[must_use]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::SimpleString(s) => Some(s),
+            Value::BulkString(bs) | Value::VerbatimString { text: bs, .. } => {
+                std::str::from_utf8(bs).ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// Bytes of a [`Value::BulkString`]
+    #[must_use]
+    pub fn as_bulk_string(&self) -> Option<&[u8]> {
+        match self {
+            Value::BulkString(bs) => Some(bs),
+            _ => None,
+        }
+    }
+
+    /// Decimal representation of a [`Value::BigNumber`]
+    #[must_use]
+    pub fn as_big_number(&self) -> Option<&str> {
+        match self {
+            Value::BigNumber(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    /// Format and text of a [`Value::VerbatimString`]
+    #[must_use]
+    pub fn as_verbatim_string(&self) -> Option<(&str, &[u8])> {
+        match self {
+            Value::VerbatimString { format, text } => Some((format, text)),
+            _ => None,
+        }
+    }
+
+    /// Attributes of a [`Value::Attribute`]
+    #[must_use]
+    pub fn attributes(&self) -> Option<&HashMap<Value, Value>> {
+        match self {
+            Value::Attribute { attributes, .. } => Some(attributes),
+            _ => None,
+        }
+    }
+
+    /// Value stripped of its [`Value::Attribute`] wrappers, if any
+    #[must_use]
+    pub fn without_attributes(&self) -> &Value {
+        match self {
+            Value::Attribute { value, .. } => value.without_attributes(),
+            value => value,
+        }
+    }
+
+    /// Elements of a [`Value::Array`]
+    #[must_use]
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Entries of a [`Value::Map`]
+    #[must_use]
+    pub fn as_map(&self) -> Option<&HashMap<Value, Value>> {
+        match self {
+            Value::Map(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Consume a [`Value::BulkString`] into its bytes
+    ///
+    /// # Errors
+    /// An [`Error::Client`] naming the actual variant for any other variant,
+    /// or the [`Error::Redis`] held by a [`Value::Error`]
+    pub fn into_bulk_string(self) -> Result<Vec<u8>> {
+        match self {
+            Value::BulkString(bs) => Ok(bs),
+            value => Err(value.unexpected_variant("BulkString")),
+        }
+    }
+
+    /// Consume a [`Value::SimpleString`], or a valid UTF-8 [`Value::BulkString`] or [`Value::VerbatimString`], into a string
+    ///
+    /// # Errors
+    /// An [`Error::Client`] naming the actual variant, for any other variant,
+    /// or if the bulk string is not valid UTF-8
+    pub fn into_string(self) -> Result<String> {
+        match self {
+            Value::SimpleString(s) => Ok(s),
+            Value::BulkString(bs) | Value::VerbatimString { text: bs, .. } => String::from_utf8(bs)
+                .map_err(|e| {
+                    Error::Client(format!("Cannot convert Value::BulkString to String: {e}"))
+                }),
+            value => Err(value.unexpected_variant("SimpleString or BulkString")),
+        }
+    }
+
+    /// Consume a [`Value::Array`] into its elements
+    ///
+    /// # Errors
+    /// An [`Error::Client`] naming the actual variant for any other variant,
+    /// or the [`Error::Redis`] held by a [`Value::Error`]
+    pub fn into_array(self) -> Result<Vec<Value>> {
+        match self {
+            Value::Array(values) => Ok(values),
+            value => Err(value.unexpected_variant("Array")),
+        }
+    }
+
+    /// Consume a [`Value::Map`] into its entries
+    ///
+    /// # Errors
+    /// An [`Error::Client`] naming the actual variant for any other variant,
+    /// or the [`Error::Redis`] held by a [`Value::Error`]
+    pub fn into_map(self) -> Result<HashMap<Value, Value>> {
+        match self {
+            Value::Map(values) => Ok(values),
+            value => Err(value.unexpected_variant("Map")),
+        }
+    }
+
+    fn unexpected_variant(&self, expected: &str) -> Error {
+        match self {
+            Value::Error(e) => Error::Redis(e.clone()),
+            _ => Error::Client(format!(
+                "Expected Value::{expected}, got Value::{}",
+                self.variant_name()
+            )),
+        }
+    }
+}
+
+impl TryFrom<Value> for i64 {
+    type Error = Error;
+
+    /// Strict conversion from a [`Value::Integer`],
+    /// see [`Value::into`] for a lenient conversion
+    fn try_from(value: Value) -> Result<Self> {
+        match value {
+            Value::Integer(i) => Ok(i),
+            value => Err(value.unexpected_variant("Integer")),
+        }
+    }
+}
+
+impl TryFrom<Value> for f64 {
+    type Error = Error;
+
+    /// Strict conversion from a [`Value::Double`] or a [`Value::Integer`],
+    /// see [`Value::into`] for a lenient conversion
+    fn try_from(value: Value) -> Result<Self> {
+        match value {
+            Value::Double(d) => Ok(d),
+            Value::Integer(i) => Ok(i as f64),
+            value => Err(value.unexpected_variant("Double")),
+        }
+    }
+}
+
+impl TryFrom<Value> for bool {
+    type Error = Error;
+
+    /// Strict conversion from a [`Value::Boolean`],
+    /// see [`Value::into`] for a lenient conversion
+    fn try_from(value: Value) -> Result<Self> {
+        match value {
+            Value::Boolean(b) => Ok(b),
+            value => Err(value.unexpected_variant("Boolean")),
+        }
+    }
+}
+
+impl TryFrom<Value> for String {
+    type Error = Error;
+
+    /// See [`Value::into_string`]
+    fn try_from(value: Value) -> Result<Self> {
+        value.into_string()
+    }
+}
+
+impl TryFrom<Value> for Vec<u8> {
+    type Error = Error;
+
+    /// See [`Value::into_bulk_string`]
+    fn try_from(value: Value) -> Result<Self> {
+        value.into_bulk_string()
+    }
+}
+
+impl TryFrom<Value> for Vec<Value> {
+    type Error = Error;
+
+    /// See [`Value::into_array`]
+    fn try_from(value: Value) -> Result<Self> {
+        value.into_array()
+    }
+}
+
+impl TryFrom<Value> for HashMap<Value, Value> {
+    type Error = Error;
+
+    /// See [`Value::into_map`]
+    fn try_from(value: Value) -> Result<Self> {
+        value.into_map()
+    }
+}
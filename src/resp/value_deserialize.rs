@@ -1,11 +1,15 @@
-use crate::resp::Value;
+use crate::{resp::Value, Error};
 use serde::{
-    de::{MapAccess, SeqAccess, Visitor},
+    de::{value::BorrowedStrDeserializer, DeserializeSeed, MapAccess, SeqAccess, Visitor},
     Deserialize, Deserializer,
 };
-use std::fmt;
+use std::{collections::HashMap, fmt};
 
 pub(crate) const PUSH_FAKE_FIELD: &str = ">>>PUSH>>>";
+pub(crate) const VALUE_FAKE_FIELD: &str = "***VALUE***";
+pub(crate) const BIG_NUMBER_FAKE_FIELD: &str = "(((BIG_NUMBER(((";
+pub(crate) const VERBATIM_STRING_FAKE_FIELD: &str = "===VERBATIM_STRING===";
+pub(crate) const ATTRIBUTE_FAKE_FIELD: &str = "|||ATTRIBUTE|||";
 
 /// Implementation meant to be used with [`RespDeserializer`](crate::resp::RespDeserializer)
 impl<'de> Deserialize<'de> for Value {
@@ -14,7 +18,60 @@ impl<'de> Deserialize<'de> for Value {
     where
         D: Deserializer<'de>,
     {
-        deserializer.deserialize_any(ValueVisitor)
+        deserializer.deserialize_newtype_struct(VALUE_FAKE_FIELD, ValueVisitor)
+    }
+}
+
+/// Single entry map, keyed by a fake field, passing a RESP3 type without serde equivalent
+/// to the [`Value`] visitor
+pub(crate) struct FakeFieldMapAccess<D> {
+    field: Option<&'static str>,
+    value: Option<D>,
+}
+
+impl<D> FakeFieldMapAccess<D> {
+    #[inline]
+    pub fn new(field: &'static str, value: D) -> Self {
+        Self {
+            field: Some(field),
+            value: Some(value),
+        }
+    }
+}
+
+impl<'de, D> MapAccess<'de> for FakeFieldMapAccess<D>
+where
+    D: Deserializer<'de, Error = Error>,
+{
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.field.take() {
+            Some(field) => seed
+                .deserialize(BorrowedStrDeserializer::new(field))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        match self.value.take() {
+            Some(value) => seed.deserialize(value),
+            None => Err(Error::Client(
+                "Fake field value already consumed".to_owned(),
+            )),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        Some(1)
     }
 }
 
@@ -75,6 +132,14 @@ impl<'de> Visitor<'de> for ValueVisitor {
         Ok(Value::BulkString(v))
     }
 
+    #[inline]
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
     fn visit_seq<A>(self, mut seq: A) -> Result<Value, A::Error>
     where
         A: SeqAccess<'de>,
@@ -116,6 +181,30 @@ impl<'de> Visitor<'de> for ValueVisitor {
                             return Ok(Value::Push(values));
                         }
                     }
+                    Some(PushOrKey::BigNumber) => return Ok(Value::BigNumber(map.next_value()?)),
+                    Some(PushOrKey::VerbatimString) => {
+                        // `fmt:text`, the format being 3 bytes long
+                        let raw = map
+                            .next_value::<Value>()?
+                            .into_bulk_string()
+                            .unwrap_or_default();
+                        let (format, text) = match raw.get(3) {
+                            Some(b':') => (
+                                String::from_utf8_lossy(&raw[..3]).into_owned(),
+                                raw[4..].to_vec(),
+                            ),
+                            _ => (String::new(), raw),
+                        };
+                        return Ok(Value::VerbatimString { format, text });
+                    }
+                    Some(PushOrKey::Attribute) => {
+                        let (attributes, value): (HashMap<Value, Value>, Value) =
+                            map.next_value()?;
+                        return Ok(Value::Attribute {
+                            attributes,
+                            value: Box::new(value),
+                        });
+                    }
                     Some(PushOrKey::Key(value)) => values.push(value),
                 };
 
@@ -128,9 +217,25 @@ impl<'de> Visitor<'de> for ValueVisitor {
 
 enum PushOrKey {
     Push,
+    BigNumber,
+    VerbatimString,
+    Attribute,
     Key(Value),
 }
 
+impl PushOrKey {
+    #[inline]
+    fn fake_field(v: &str) -> Option<PushOrKey> {
+        match v {
+            PUSH_FAKE_FIELD => Some(PushOrKey::Push),
+            BIG_NUMBER_FAKE_FIELD => Some(PushOrKey::BigNumber),
+            VERBATIM_STRING_FAKE_FIELD => Some(PushOrKey::VerbatimString),
+            ATTRIBUTE_FAKE_FIELD => Some(PushOrKey::Attribute),
+            _ => None,
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for PushOrKey {
     #[inline]
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -171,8 +276,8 @@ impl<'de> Visitor<'de> for PushOrKeyVisitor {
 
     #[inline]
     fn visit_borrowed_str<E: serde::de::Error>(self, v: &'de str) -> Result<PushOrKey, E> {
-        if v == PUSH_FAKE_FIELD {
-            Ok(PushOrKey::Push)
+        if let Some(fake_field) = PushOrKey::fake_field(v) {
+            Ok(fake_field)
         } else {
             let value_visitor = ValueVisitor;
             value_visitor.visit_borrowed_str(v).map(PushOrKey::Key)
@@ -181,8 +286,8 @@ impl<'de> Visitor<'de> for PushOrKeyVisitor {
 
     #[inline]
     fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<PushOrKey, E> {
-        if v == PUSH_FAKE_FIELD {
-            Ok(PushOrKey::Push)
+        if let Some(fake_field) = PushOrKey::fake_field(v) {
+            Ok(fake_field)
         } else {
             let value_visitor = ValueVisitor;
             value_visitor.visit_str(v).map(PushOrKey::Key)
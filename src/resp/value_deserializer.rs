@@ -1,6 +1,18 @@
-use crate::{resp::Value, Error, Result};
+use crate::{
+    resp::{
+        FakeFieldMapAccess, Value, ATTRIBUTE_FAKE_FIELD, BIG_NUMBER_FAKE_FIELD, VALUE_FAKE_FIELD,
+        VERBATIM_STRING_FAKE_FIELD,
+    },
+    Error, Result,
+};
 use serde::{
-    de::{DeserializeSeed, EnumAccess, IntoDeserializer, VariantAccess, Visitor},
+    de::{
+        value::{
+            BorrowedStrDeserializer, BytesDeserializer, MapAccessDeserializer,
+            SeqAccessDeserializer,
+        },
+        DeserializeSeed, EnumAccess, IntoDeserializer, VariantAccess, Visitor,
+    },
     forward_to_deserialize_any, Deserialize, Deserializer,
 };
 use std::{
@@ -26,6 +38,9 @@ impl<'de> Deserializer<'de> for &'de Value {
             Value::Set(values) => visitor.visit_seq(SeqAccess::new(values)),
             Value::Push(values) => visitor.visit_seq(SeqAccess::new(values)),
             Value::Error(e) => Err(Error::Redis(e.clone())),
+            Value::BigNumber(n) => visitor.visit_borrowed_str(n),
+            Value::VerbatimString { text, .. } => visitor.visit_borrowed_bytes(text),
+            Value::Attribute { value, .. } => value.deserialize_any(visitor),
             Value::Nil => visitor.visit_none(),
         }
     }
@@ -35,13 +50,21 @@ impl<'de> Deserializer<'de> for &'de Value {
     where
         V: Visitor<'de>,
     {
-        let result = match self {
+        let result = match self.without_attributes() {
             Value::Integer(i) => *i != 0,
             Value::Double(d) => *d != 0.,
             Value::SimpleString(s) if s == "OK" => true,
             Value::Nil => false,
-            Value::BulkString(s) if s == b"0" || s == b"false" => false,
-            Value::BulkString(s) if s == b"1" || s == b"true" => true,
+            Value::BulkString(s) | Value::VerbatimString { text: s, .. }
+                if s == b"0" || s == b"false" =>
+            {
+                false
+            }
+            Value::BulkString(s) | Value::VerbatimString { text: s, .. }
+                if s == b"1" || s == b"true" =>
+            {
+                true
+            }
             Value::Boolean(b) => *b,
             Value::Error(e) => return Err(Error::Redis(e.clone())),
             _ => {
@@ -59,12 +82,14 @@ impl<'de> Deserializer<'de> for &'de Value {
     where
         V: Visitor<'de>,
     {
-        let result = match self {
+        let result = match self.without_attributes() {
             Value::Integer(i) => *i as i8,
             Value::Double(d) => *d as i8,
             Value::Nil => 0,
-            Value::BulkString(s) => str::from_utf8(s)?.parse::<i8>()?,
-            Value::SimpleString(s) => s.parse::<i8>()?,
+            Value::BulkString(s) | Value::VerbatimString { text: s, .. } => {
+                str::from_utf8(s)?.parse::<i8>()?
+            }
+            Value::SimpleString(s) | Value::BigNumber(s) => s.parse::<i8>()?,
             Value::Error(e) => return Err(Error::Redis(e.clone())),
             _ => {
                 return Err(Error::Client(format!(
@@ -81,12 +106,14 @@ impl<'de> Deserializer<'de> for &'de Value {
     where
         V: Visitor<'de>,
     {
-        let result = match self {
+        let result = match self.without_attributes() {
             Value::Integer(i) => *i as i16,
             Value::Double(d) => *d as i16,
             Value::Nil => 0,
-            Value::BulkString(s) => str::from_utf8(s)?.parse::<i16>()?,
-            Value::SimpleString(s) => s.parse::<i16>()?,
+            Value::BulkString(s) | Value::VerbatimString { text: s, .. } => {
+                str::from_utf8(s)?.parse::<i16>()?
+            }
+            Value::SimpleString(s) | Value::BigNumber(s) => s.parse::<i16>()?,
             Value::Error(e) => return Err(Error::Redis(e.clone())),
             _ => {
                 return Err(Error::Client(format!(
@@ -103,12 +130,14 @@ impl<'de> Deserializer<'de> for &'de Value {
     where
         V: Visitor<'de>,
     {
-        let result = match self {
+        let result = match self.without_attributes() {
             Value::Integer(i) => *i as i32,
             Value::Double(d) => *d as i32,
             Value::Nil => 0,
-            Value::BulkString(s) => str::from_utf8(s)?.parse::<i32>()?,
-            Value::SimpleString(s) => s.parse::<i32>()?,
+            Value::BulkString(s) | Value::VerbatimString { text: s, .. } => {
+                str::from_utf8(s)?.parse::<i32>()?
+            }
+            Value::SimpleString(s) | Value::BigNumber(s) => s.parse::<i32>()?,
             Value::Error(e) => return Err(Error::Redis(e.clone())),
             _ => {
                 return Err(Error::Client(format!(
@@ -126,12 +155,14 @@ impl<'de> Deserializer<'de> for &'de Value {
     where
         V: Visitor<'de>,
     {
-        let result = match self {
+        let result = match self.without_attributes() {
             Value::Integer(i) => *i,
             Value::Double(d) => *d as i64,
             Value::Nil => 0,
-            Value::BulkString(s) => str::from_utf8(s)?.parse::<i64>()?,
-            Value::SimpleString(s) => s.parse::<i64>()?,
+            Value::BulkString(s) | Value::VerbatimString { text: s, .. } => {
+                str::from_utf8(s)?.parse::<i64>()?
+            }
+            Value::SimpleString(s) | Value::BigNumber(s) => s.parse::<i64>()?,
             Value::Array(a) if a.len() == 1 => i64::deserialize(&a[0])?,
             Value::Error(e) => return Err(Error::Redis(e.clone())),
             _ => {
@@ -149,12 +180,14 @@ impl<'de> Deserializer<'de> for &'de Value {
     where
         V: Visitor<'de>,
     {
-        let result = match self {
+        let result = match self.without_attributes() {
             Value::Integer(i) => *i as u8,
             Value::Double(d) => *d as u8,
             Value::Nil => 0,
-            Value::BulkString(s) => str::from_utf8(s)?.parse::<u8>()?,
-            Value::SimpleString(s) => s.parse::<u8>()?,
+            Value::BulkString(s) | Value::VerbatimString { text: s, .. } => {
+                str::from_utf8(s)?.parse::<u8>()?
+            }
+            Value::SimpleString(s) | Value::BigNumber(s) => s.parse::<u8>()?,
             Value::Error(e) => return Err(Error::Redis(e.clone())),
             _ => {
                 return Err(Error::Client(format!(
@@ -171,12 +204,14 @@ impl<'de> Deserializer<'de> for &'de Value {
     where
         V: Visitor<'de>,
     {
-        let result = match self {
+        let result = match self.without_attributes() {
             Value::Integer(i) => *i as u16,
             Value::Double(d) => *d as u16,
             Value::Nil => 0,
-            Value::BulkString(s) => str::from_utf8(s)?.parse::<u16>()?,
-            Value::SimpleString(s) => s.parse::<u16>()?,
+            Value::BulkString(s) | Value::VerbatimString { text: s, .. } => {
+                str::from_utf8(s)?.parse::<u16>()?
+            }
+            Value::SimpleString(s) | Value::BigNumber(s) => s.parse::<u16>()?,
             Value::Error(e) => return Err(Error::Redis(e.clone())),
             _ => {
                 return Err(Error::Client(format!(
@@ -193,12 +228,14 @@ impl<'de> Deserializer<'de> for &'de Value {
     where
         V: Visitor<'de>,
     {
-        let result = match self {
+        let result = match self.without_attributes() {
             Value::Integer(i) => *i as u32,
             Value::Double(d) => *d as u32,
             Value::Nil => 0,
-            Value::BulkString(s) => str::from_utf8(s)?.parse::<u32>()?,
-            Value::SimpleString(s) => s.parse::<u32>()?,
+            Value::BulkString(s) | Value::VerbatimString { text: s, .. } => {
+                str::from_utf8(s)?.parse::<u32>()?
+            }
+            Value::SimpleString(s) | Value::BigNumber(s) => s.parse::<u32>()?,
             Value::Error(e) => return Err(Error::Redis(e.clone())),
             _ => {
                 return Err(Error::Client(format!(
@@ -215,12 +252,14 @@ impl<'de> Deserializer<'de> for &'de Value {
     where
         V: Visitor<'de>,
     {
-        let result = match self {
+        let result = match self.without_attributes() {
             Value::Integer(i) => *i as u64,
             Value::Double(d) => *d as u64,
             Value::Nil => 0,
-            Value::BulkString(s) => str::from_utf8(s)?.parse::<u64>()?,
-            Value::SimpleString(s) => s.parse::<u64>()?,
+            Value::BulkString(s) | Value::VerbatimString { text: s, .. } => {
+                str::from_utf8(s)?.parse::<u64>()?
+            }
+            Value::SimpleString(s) | Value::BigNumber(s) => s.parse::<u64>()?,
             Value::Array(a) if a.len() == 1 => u64::deserialize(&a[0])?,
             Value::Error(e) => return Err(Error::Redis(e.clone())),
             _ => {
@@ -238,12 +277,14 @@ impl<'de> Deserializer<'de> for &'de Value {
     where
         V: Visitor<'de>,
     {
-        let result = match self {
+        let result = match self.without_attributes() {
             Value::Integer(i) => *i as f32,
             Value::Double(d) => *d as f32,
-            Value::BulkString(bs) => str::from_utf8(bs)?.parse::<f32>()?,
+            Value::BulkString(bs) | Value::VerbatimString { text: bs, .. } => {
+                str::from_utf8(bs)?.parse::<f32>()?
+            }
             Value::Nil => 0.,
-            Value::SimpleString(s) => s.parse::<f32>()?,
+            Value::SimpleString(s) | Value::BigNumber(s) => s.parse::<f32>()?,
             Value::Error(e) => return Err(Error::Redis(e.clone())),
             _ => {
                 return Err(Error::Client(format!(
@@ -260,12 +301,14 @@ impl<'de> Deserializer<'de> for &'de Value {
     where
         V: Visitor<'de>,
     {
-        let result = match self {
+        let result = match self.without_attributes() {
             Value::Integer(i) => *i as f64,
             Value::Double(d) => *d,
-            Value::BulkString(bs) => str::from_utf8(bs)?.parse::<f64>()?,
+            Value::BulkString(bs) | Value::VerbatimString { text: bs, .. } => {
+                str::from_utf8(bs)?.parse::<f64>()?
+            }
             Value::Nil => 0.,
-            Value::SimpleString(s) => s.parse::<f64>()?,
+            Value::SimpleString(s) | Value::BigNumber(s) => s.parse::<f64>()?,
             Value::Error(e) => return Err(Error::Redis(e.clone())),
             _ => {
                 return Err(Error::Client(format!(
@@ -282,8 +325,8 @@ impl<'de> Deserializer<'de> for &'de Value {
     where
         V: Visitor<'de>,
     {
-        let result: char = match self {
-            Value::BulkString(bs) => {
+        let result: char = match self.without_attributes() {
+            Value::BulkString(bs) | Value::VerbatimString { text: bs, .. } => {
                 let str = str::from_utf8(bs)?;
                 if str.len() == 1 {
                     str.chars().next().unwrap()
@@ -310,10 +353,10 @@ impl<'de> Deserializer<'de> for &'de Value {
     where
         V: Visitor<'de>,
     {
-        let result = match self {
-            Value::BulkString(s) => str::from_utf8(s)?,
+        let result = match self.without_attributes() {
+            Value::BulkString(s) | Value::VerbatimString { text: s, .. } => str::from_utf8(s)?,
             Value::Nil => "",
-            Value::SimpleString(s) => s.as_str(),
+            Value::SimpleString(s) | Value::BigNumber(s) => s.as_str(),
             Value::Error(e) => return Err(Error::Redis(e.clone())),
             _ => {
                 return Err(Error::Client(format!(
@@ -331,11 +374,13 @@ impl<'de> Deserializer<'de> for &'de Value {
     where
         V: Visitor<'de>,
     {
-        let result = match self {
+        let result = match self.without_attributes() {
             Value::Double(d) => d.to_string(),
-            Value::BulkString(s) => str::from_utf8(s)?.to_owned(),
+            Value::BulkString(s) | Value::VerbatimString { text: s, .. } => {
+                str::from_utf8(s)?.to_owned()
+            }
             Value::Nil => String::from(""),
-            Value::SimpleString(s) => s.clone(),
+            Value::SimpleString(s) | Value::BigNumber(s) => s.clone(),
             Value::Error(e) => return Err(Error::Redis(e.clone())),
             _ => {
                 return Err(Error::Client(format!(
@@ -352,8 +397,8 @@ impl<'de> Deserializer<'de> for &'de Value {
     where
         V: Visitor<'de>,
     {
-        let result = match self {
-            Value::BulkString(s) => s.as_slice(),
+        let result = match self.without_attributes() {
+            Value::BulkString(s) | Value::VerbatimString { text: s, .. } => s.as_slice(),
             Value::Nil => &[],
             Value::SimpleString(s) => s.as_bytes(),
             Value::Error(e) => return Err(Error::Redis(e.clone())),
@@ -372,8 +417,8 @@ impl<'de> Deserializer<'de> for &'de Value {
     where
         V: Visitor<'de>,
     {
-        let result = match self {
-            Value::BulkString(s) => s.clone(),
+        let result = match self.without_attributes() {
+            Value::BulkString(s) | Value::VerbatimString { text: s, .. } => s.clone(),
             Value::Nil => vec![],
             Value::SimpleString(s) => s.as_bytes().to_vec(),
             Value::Error(e) => return Err(Error::Redis(e.clone())),
@@ -393,7 +438,7 @@ impl<'de> Deserializer<'de> for &'de Value {
     where
         V: Visitor<'de>,
     {
-        match self {
+        match self.without_attributes() {
             Value::Nil => visitor.visit_none(),
             Value::Array(values) if values.is_empty() => visitor.visit_none(),
             Value::Error(e) => Err(Error::Redis(e.clone())),
@@ -406,11 +451,13 @@ impl<'de> Deserializer<'de> for &'de Value {
     where
         V: Visitor<'de>,
     {
-        match self {
+        match self.without_attributes() {
             Value::Nil => visitor.visit_unit(),
             Value::Integer(_) => visitor.visit_unit(),
             Value::SimpleString(_) => visitor.visit_unit(),
-            Value::BulkString(bs) if bs.is_empty() => visitor.visit_unit(),
+            Value::BulkString(bs) | Value::VerbatimString { text: bs, .. } if bs.is_empty() => {
+                visitor.visit_unit()
+            }
             Value::Array(a) if a.is_empty() => visitor.visit_unit(),
             Value::Set(s) if s.is_empty() => visitor.visit_unit(),
             Value::Map(m) if m.is_empty() => visitor.visit_unit(),
@@ -432,18 +479,42 @@ impl<'de> Deserializer<'de> for &'de Value {
     // insignificant wrappers around the data they contain. That means not
     // parsing anything other than the contained value.
     #[inline]
-    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_newtype_struct(self)
+        if name != VALUE_FAKE_FIELD {
+            return visitor.visit_newtype_struct(self);
+        }
+
+        match self {
+            Value::BigNumber(n) => visitor.visit_map(FakeFieldMapAccess::new(
+                BIG_NUMBER_FAKE_FIELD,
+                BorrowedStrDeserializer::new(n),
+            )),
+            Value::VerbatimString { format, text } => {
+                let raw = [format.as_bytes(), b":", text].concat();
+                visitor.visit_map(FakeFieldMapAccess::new(
+                    VERBATIM_STRING_FAKE_FIELD,
+                    BytesDeserializer::new(&raw),
+                ))
+            }
+            Value::Attribute { attributes, value } => visitor.visit_map(FakeFieldMapAccess::new(
+                ATTRIBUTE_FAKE_FIELD,
+                SeqAccessDeserializer::new(AttributeSeqAccess {
+                    attributes: Some(attributes),
+                    value: Some(value),
+                }),
+            )),
+            _ => self.deserialize_any(visitor),
+        }
     }
 
     fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        match self {
+        match self.without_attributes() {
             Value::Nil => visitor.visit_seq(NilSeqAccess),
             Value::Array(values) | Value::Set(values) | Value::Push(values) => {
                 visitor.visit_seq(SeqAccess::new(values))
@@ -481,7 +552,7 @@ impl<'de> Deserializer<'de> for &'de Value {
     where
         V: Visitor<'de>,
     {
-        match self {
+        match self.without_attributes() {
             Value::Array(values) => visitor.visit_map(SeqAccess::new(values)),
             Value::Map(values) => visitor.visit_map(MapAccess::new(values)),
             Value::Error(e) => Err(Error::Redis(e.clone())),
@@ -508,7 +579,7 @@ impl<'de> Deserializer<'de> for &'de Value {
             }
         }
 
-        match self {
+        match self.without_attributes() {
             Value::Array(values) => {
                 if check_resp2_array(values, fields) {
                     visitor.visit_map(SeqAccess::new(values))
@@ -531,8 +602,8 @@ impl<'de> Deserializer<'de> for &'de Value {
     where
         V: Visitor<'de>,
     {
-        match self {
-            Value::BulkString(bs) => {
+        match self.without_attributes() {
+            Value::BulkString(bs) | Value::VerbatimString { text: bs, .. } => {
                 // Visit a unit variant.
                 let str = str::from_utf8(bs)?;
                 visitor.visit_enum(str.into_deserializer())
@@ -880,3 +951,27 @@ impl<'de> VariantAccess<'de> for &'de Value {
         self.deserialize_map(visitor)
     }
 }
+
+/// Attributes, then attributed value, of a [`Value::Attribute`]
+struct AttributeSeqAccess<'de> {
+    attributes: Option<&'de HashMap<Value, Value>>,
+    value: Option<&'de Value>,
+}
+
+impl<'de> serde::de::SeqAccess<'de> for AttributeSeqAccess<'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if let Some(attributes) = self.attributes.take() {
+            seed.deserialize(MapAccessDeserializer::new(MapAccess::new(attributes)))
+                .map(Some)
+        } else if let Some(value) = self.value.take() {
+            seed.deserialize(value).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
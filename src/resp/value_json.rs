@@ -0,0 +1,185 @@
+use crate::{resp::Value, Error, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{Map, Number};
+
+/// Encoding of the binary strings of a [`Value`] converted to JSON,
+/// see [`Value::to_json`] & [`Value::from_json`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JsonBinaryEncoding {
+    /// UTF-8 strings, invalid sequences being replaced by `U+FFFD`
+    #[default]
+    Utf8,
+    /// [Base64](https://datatracker.ietf.org/doc/html/rfc4648#section-4) strings, with padding
+    Base64,
+}
+
+impl Value {
+    /// Convert the value to JSON, for logging or for HTTP APIs exposing Redis replies
+    ///
+    /// * Bulk strings & verbatim strings are encoded according to `binary_encoding`,
+    ///   simple strings & big numbers are converted to JSON strings
+    /// * Integers & doubles are converted to JSON numbers,
+    ///   except for NaN & infinite doubles which are converted to strings (`"nan"`, `"inf"`, `"-inf"`)
+    /// * Arrays, sets & pushes are converted to JSON arrays
+    /// * Maps are converted to JSON objects, keys which are not strings nor numbers being encoded in JSON
+    /// * Errors are converted to `{"error": "<kind> <description>"}` objects
+    /// * Attributes are ignored, only the value they are attached to is converted
+    ///
+    /// The conversion with [`From`] uses [`JsonBinaryEncoding::Utf8`].
+    ///
+    /// # Example
+    /// ```
+    /// use rustis::resp::{JsonBinaryEncoding, Value};
+    /// use serde_json::json;
+    ///
+    /// let value = Value::Array(vec![
+    ///     Value::BulkString(b"hello".to_vec()),
+    ///     Value::Integer(12),
+    ///     Value::Nil,
+    /// ]);
+    ///
+    /// assert_eq!(json!(["hello", 12, null]), value.to_json(JsonBinaryEncoding::Utf8));
+    /// assert_eq!(json!(["aGVsbG8=", 12, null]), value.to_json(JsonBinaryEncoding::Base64));
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    #[must_use]
+    pub fn to_json(&self, binary_encoding: JsonBinaryEncoding) -> serde_json::Value {
+        match self {
+            Value::SimpleString(s) | Value::BigNumber(s) => serde_json::Value::String(s.clone()),
+            Value::Integer(i) => serde_json::Value::Number((*i).into()),
+            Value::Double(d) => match Number::from_f64(*d) {
+                Some(number) => serde_json::Value::Number(number),
+                None => serde_json::Value::String(d.to_string().to_lowercase()),
+            },
+            Value::BulkString(bytes) | Value::VerbatimString { text: bytes, .. } => {
+                serde_json::Value::String(encode_binary(bytes, binary_encoding))
+            }
+            Value::Boolean(b) => serde_json::Value::Bool(*b),
+            Value::Array(values) | Value::Set(values) | Value::Push(values) => {
+                serde_json::Value::Array(
+                    values
+                        .iter()
+                        .map(|value| value.to_json(binary_encoding))
+                        .collect(),
+                )
+            }
+            Value::Map(entries) => serde_json::Value::Object(
+                entries
+                    .iter()
+                    .map(|(key, value)| {
+                        (
+                            json_key(key.to_json(binary_encoding)),
+                            value.to_json(binary_encoding),
+                        )
+                    })
+                    .collect(),
+            ),
+            Value::Error(e) => {
+                let mut object = Map::new();
+                object.insert("error".to_owned(), serde_json::Value::String(e.to_string()));
+                serde_json::Value::Object(object)
+            }
+            Value::Attribute { value, .. } => value.to_json(binary_encoding),
+            Value::Nil => serde_json::Value::Null,
+        }
+    }
+
+    /// Convert a JSON value to a value
+    ///
+    /// * JSON strings are converted to bulk strings, decoded according to `binary_encoding`
+    /// * JSON numbers are converted to integers when they fit in an `i64`, to doubles otherwise
+    /// * JSON arrays are converted to arrays, JSON objects to maps with bulk string keys
+    /// * `null` is converted to [`Value::Nil`]
+    ///
+    /// The conversion with [`From`] uses [`JsonBinaryEncoding::Utf8`].
+    ///
+    /// # Errors
+    /// An [`Error::Client`] if a string is not valid Base64 with [`JsonBinaryEncoding::Base64`]
+    ///
+    /// # Example
+    /// ```
+    /// use rustis::resp::{JsonBinaryEncoding, Value};
+    /// use serde_json::json;
+    ///
+    /// let value = Value::from_json(json!(["aGVsbG8=", 12]), JsonBinaryEncoding::Base64).unwrap();
+    /// assert_eq!(
+    ///     Value::Array(vec![Value::BulkString(b"hello".to_vec()), Value::Integer(12)]),
+    ///     value
+    /// );
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn from_json(json: serde_json::Value, binary_encoding: JsonBinaryEncoding) -> Result<Self> {
+        Ok(match json {
+            serde_json::Value::Null => Value::Nil,
+            serde_json::Value::Bool(b) => Value::Boolean(b),
+            serde_json::Value::Number(number) => match number.as_i64() {
+                Some(i) => Value::Integer(i),
+                None => match number.as_u64() {
+                    // beyond i64::MAX
+                    Some(u) => Value::BigNumber(u.to_string()),
+                    None => Value::Double(number.as_f64().unwrap_or(f64::NAN)),
+                },
+            },
+            serde_json::Value::String(s) => Value::BulkString(decode_binary(s, binary_encoding)?),
+            serde_json::Value::Array(values) => Value::Array(
+                values
+                    .into_iter()
+                    .map(|value| Value::from_json(value, binary_encoding))
+                    .collect::<Result<_>>()?,
+            ),
+            serde_json::Value::Object(object) => Value::Map(
+                object
+                    .into_iter()
+                    .map(|(key, value)| {
+                        Ok((
+                            Value::BulkString(decode_binary(key, binary_encoding)?),
+                            Value::from_json(value, binary_encoding)?,
+                        ))
+                    })
+                    .collect::<Result<_>>()?,
+            ),
+        })
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+impl From<Value> for serde_json::Value {
+    fn from(value: Value) -> Self {
+        value.to_json(JsonBinaryEncoding::Utf8)
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+impl From<serde_json::Value> for Value {
+    fn from(json: serde_json::Value) -> Self {
+        match Value::from_json(json, JsonBinaryEncoding::Utf8) {
+            Ok(value) => value,
+            // UTF-8 decoding cannot fail
+            Err(_) => unreachable!(),
+        }
+    }
+}
+
+fn encode_binary(bytes: &[u8], binary_encoding: JsonBinaryEncoding) -> String {
+    match binary_encoding {
+        JsonBinaryEncoding::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+        JsonBinaryEncoding::Base64 => STANDARD.encode(bytes),
+    }
+}
+
+fn decode_binary(s: String, binary_encoding: JsonBinaryEncoding) -> Result<Vec<u8>> {
+    match binary_encoding {
+        JsonBinaryEncoding::Utf8 => Ok(s.into_bytes()),
+        JsonBinaryEncoding::Base64 => STANDARD
+            .decode(s)
+            .map_err(|e| Error::Client(format!("Cannot decode Base64 string: {e}"))),
+    }
+}
+
+/// Key of a JSON object from a converted map key
+fn json_key(key: serde_json::Value) -> String {
+    match key {
+        serde_json::Value::String(s) => s,
+        key => key.to_string(),
+    }
+}
@@ -0,0 +1,186 @@
+use crate::resp::Value;
+use std::fmt::Write;
+
+const INDENT: usize = 2;
+
+impl Value {
+    /// Structured, indented rendering of the value,
+    /// annotated with the RESP type of each element.
+    ///
+    /// Map entries are sorted by key to get a deterministic output.
+    ///
+    /// # Example
+    /// ```
+    /// use rustis::resp::Value;
+    ///
+    /// let value = Value::Array(vec![
+    ///     Value::BulkString(b"value".to_vec()),
+    ///     Value::Set(vec![Value::Integer(12)]),
+    /// ]);
+    ///
+    /// assert_eq!(
+    ///     "(array) [2]\n  0) (bulk-string) \"value\"\n  1) (set) ~[1]\n    0) (integer) 12",
+    ///     value.pretty()
+    /// );
+    /// ```
+    #[must_use]
+    pub fn pretty(&self) -> String {
+        let mut output = String::new();
+        self.write_pretty(&mut output, 0);
+        output
+    }
+
+    /// Find the first divergence between this value and `other`.
+    ///
+    /// # Return
+    /// `None` if both values are equal,
+    /// or a description of the first divergence, prefixed by its path in the value tree
+    /// (`[i]` for an element of an array, a set or a push, `{key}` for an entry of a map).
+    ///
+    /// # Example
+    /// ```
+    /// use rustis::resp::Value;
+    ///
+    /// let expected = Value::Array(vec![Value::Integer(1), Value::Integer(2)]);
+    /// let actual = Value::Array(vec![Value::Integer(1), Value::BulkString(b"2".to_vec())]);
+    ///
+    /// assert_eq!(None, expected.diff(&expected));
+    /// assert_eq!(
+    ///     Some("$[1]: (integer) 2 != (bulk-string) \"2\"".to_owned()),
+    ///     expected.diff(&actual)
+    /// );
+    /// ```
+    #[must_use]
+    pub fn diff(&self, other: &Value) -> Option<String> {
+        let mut path = String::from("$");
+        diff(self, other, &mut path)
+    }
+
+    fn write_pretty(&self, output: &mut String, indent: usize) {
+        match self {
+            Value::Array(values) => write_elements(output, "(array) ", "[", values, indent),
+            Value::Set(values) => write_elements(output, "(set) ", "~[", values, indent),
+            Value::Push(values) => write_elements(output, "(push) ", ">[", values, indent),
+            Value::Map(_) => {
+                let entries = sorted_entries(self);
+                let _ = write!(output, "(map) {{{}}}", entries.len());
+                for (key, value) in entries {
+                    new_line(output, indent + INDENT);
+                    output.push_str(&key.summary());
+                    output.push_str(" => ");
+                    value.write_pretty(output, indent + INDENT);
+                }
+            }
+            Value::Attribute { attributes, value } => {
+                let _ = write!(output, "(attribute) |{}", attributes.len());
+                let mut entries = attributes.iter().collect::<Vec<_>>();
+                entries.sort_by_cached_key(|(key, _)| key.summary());
+                for (key, value) in entries {
+                    new_line(output, indent + INDENT);
+                    output.push_str(&key.summary());
+                    output.push_str(" => ");
+                    value.write_pretty(output, indent + INDENT);
+                }
+                new_line(output, indent);
+                value.write_pretty(output, indent);
+            }
+            _ => output.push_str(&self.summary()),
+        }
+    }
+
+    /// One line rendering of the value: the full value for a scalar, the type & size for a collection
+    fn summary(&self) -> String {
+        match self {
+            Value::SimpleString(s) => format!("(simple-string) {s}"),
+            Value::Integer(i) => format!("(integer) {i}"),
+            Value::Double(d) => format!("(double) {d}"),
+            Value::BulkString(bs) => format!("(bulk-string) {:?}", String::from_utf8_lossy(bs)),
+            Value::Boolean(b) => format!("(boolean) {b}"),
+            Value::Array(values) => format!("(array) [{}]", values.len()),
+            Value::Map(values) => format!("(map) {{{}}}", values.len()),
+            Value::Set(values) => format!("(set) ~[{}]", values.len()),
+            Value::Push(values) => format!("(push) >[{}]", values.len()),
+            Value::Error(e) => format!("(error) {e}"),
+            Value::BigNumber(n) => format!("(big-number) {n}"),
+            Value::VerbatimString { format, text } => {
+                format!(
+                    "(verbatim-string) {format}:{:?}",
+                    String::from_utf8_lossy(text)
+                )
+            }
+            Value::Attribute { value, .. } => value.summary(),
+            Value::Nil => "(nil)".to_owned(),
+        }
+    }
+}
+
+fn write_elements(output: &mut String, name: &str, open: &str, values: &[Value], indent: usize) {
+    output.push_str(name);
+    output.push_str(open);
+    let _ = write!(output, "{}]", values.len());
+    for (i, value) in values.iter().enumerate() {
+        new_line(output, indent + INDENT);
+        let _ = write!(output, "{i}) ");
+        value.write_pretty(output, indent + INDENT);
+    }
+}
+
+fn new_line(output: &mut String, indent: usize) {
+    output.push('\n');
+    output.push_str(&" ".repeat(indent));
+}
+
+fn sorted_entries(value: &Value) -> Vec<(&Value, &Value)> {
+    let Value::Map(values) = value else {
+        return Vec::new();
+    };
+
+    let mut entries = values.iter().collect::<Vec<_>>();
+    entries.sort_by_cached_key(|(key, _)| key.summary());
+    entries
+}
+
+fn diff(left: &Value, right: &Value, path: &mut String) -> Option<String> {
+    match (left, right) {
+        (Value::Array(l), Value::Array(r))
+        | (Value::Set(l), Value::Set(r))
+        | (Value::Push(l), Value::Push(r)) => {
+            for (i, (l, r)) in l.iter().zip(r.iter()).enumerate() {
+                let len = path.len();
+                let _ = write!(path, "[{i}]");
+                if let Some(diff) = diff(l, r, path) {
+                    return Some(diff);
+                }
+                path.truncate(len);
+            }
+
+            (l.len() != r.len())
+                .then(|| format!("{path}: {} != {}", left.summary(), right.summary()))
+        }
+        (Value::Map(l), Value::Map(r)) => {
+            for (key, l) in sorted_entries(left) {
+                let len = path.len();
+                let _ = write!(path, "{{{key}}}");
+                let Some(r) = r.get(key) else {
+                    return Some(format!("{path}: missing on the right side"));
+                };
+                if let Some(diff) = diff(l, r, path) {
+                    return Some(diff);
+                }
+                path.truncate(len);
+            }
+
+            sorted_entries(right)
+                .into_iter()
+                .find(|(key, _)| !l.contains_key(key))
+                .map(|(key, _)| format!("{path}{{{key}}}: missing on the left side"))
+        }
+        // compare the inner values: `Value` equality only compares the variants of booleans
+        (Value::Boolean(l), Value::Boolean(r)) if l == r => None,
+        (Value::Boolean(_), Value::Boolean(_)) => {
+            Some(format!("{path}: {} != {}", left.summary(), right.summary()))
+        }
+        _ if left == right => None,
+        _ => Some(format!("{path}: {} != {}", left.summary(), right.summary())),
+    }
+}
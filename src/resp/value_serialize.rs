@@ -1,4 +1,7 @@
-use crate::resp::{Value, ERROR_FAKE_FIELD, PUSH_FAKE_FIELD, SET_FAKE_FIELD};
+use crate::resp::{
+    BulkString, Value, ATTRIBUTE_FAKE_FIELD, BIG_NUMBER_FAKE_FIELD, ERROR_FAKE_FIELD,
+    PUSH_FAKE_FIELD, SET_FAKE_FIELD, VERBATIM_STRING_FAKE_FIELD,
+};
 use serde::{
     ser::{SerializeMap, SerializeSeq, SerializeTupleStruct},
     Serialize,
@@ -46,6 +49,23 @@ impl Serialize for Value {
             Value::Error(e) => {
                 serializer.serialize_newtype_struct(ERROR_FAKE_FIELD, e.to_string().as_str())
             }
+            Value::BigNumber(n) => {
+                serializer.serialize_newtype_struct(BIG_NUMBER_FAKE_FIELD, n.as_str())
+            }
+            Value::VerbatimString { format, text } => serializer.serialize_newtype_struct(
+                VERBATIM_STRING_FAKE_FIELD,
+                &BulkString::new([format.as_bytes(), b":", text].concat()),
+            ),
+            Value::Attribute { attributes, value } => {
+                let mut ts =
+                    serializer.serialize_tuple_struct(ATTRIBUTE_FAKE_FIELD, attributes.len())?;
+                for (k, v) in attributes {
+                    ts.serialize_field(k)?;
+                    ts.serialize_field(v)?;
+                }
+                ts.serialize_field(value)?;
+                ts.end()
+            }
             Value::Nil => serializer.serialize_unit(),
         }
     }
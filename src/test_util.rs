@@ -0,0 +1,473 @@
+/*!
+Test utilities for applications built on **rustis**
+
+* [`SelectChurn`] - Commands raced against timers, the way `select!` does,
+  to check that cancelled futures never lose nor mix up replies.
+* [`SlotMigration`] - Migration of a hash slot between two master nodes of a cluster,
+  to write integration tests of the handling of `MOVED` & `ASK` redirections.
+*/
+
+use crate::{
+    client::Client,
+    cluster::drain_slot,
+    commands::{
+        ClusterCommands, ClusterSetSlotSubCommand, ConnectionCommands, GenericCommands,
+        MigrateOptions,
+    },
+    network::sleep,
+    resp::BulkString,
+    Error, Result,
+};
+use futures_util::future::{self, Either};
+use rand::Rng;
+use std::{
+    future::{Future, IntoFuture},
+    pin::pin,
+    task::Poll,
+    time::Duration,
+};
+
+/// Default number of keys moved by each `MIGRATE` command
+const MIGRATE_BATCH_SIZE: usize = 100;
+/// Timeout of each `MIGRATE` command, in milliseconds
+const MIGRATE_TIMEOUT: u64 = 5000;
+
+/// Master node involved in a [`SlotMigration`]
+struct MigrationNode {
+    id: String,
+    ip: String,
+    port: u16,
+    client: Client,
+}
+
+impl MigrationNode {
+    async fn connect(id: String, ip: String, port: u16) -> Result<Self> {
+        let client = Client::connect((ip.clone(), port)).await?;
+        Ok(Self {
+            id,
+            ip,
+            port,
+            client,
+        })
+    }
+
+    async fn set_slot(&self, slot: u16, subcommand: ClusterSetSlotSubCommand) -> Result<()> {
+        self.client.cluster_setslot(slot, subcommand).await
+    }
+
+    /// Move all the keys of `slot` to the node `to`
+    async fn drain_slot(&self, to: &MigrationNode, slot: u16) -> Result<()> {
+        drain_slot(
+            &self.client,
+            slot,
+            (&to.ip, to.port),
+            MIGRATE_BATCH_SIZE,
+            |_| (),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+/// Migration of a hash slot between two master nodes of a cluster,
+/// the way [`redis-cli --cluster reshard`](https://redis.io/docs/management/scaling/) does it.
+///
+/// The migration is driven step by step, so that commands can be issued at each stage:
+/// * [`start`](SlotMigration::start) - the slot is set in importing state on the destination node
+///   and in migrating state on the source node.
+///   Commands on keys already moved to the destination node are redirected with `ASK`.
+/// * [`migrate_keys`](SlotMigration::migrate_keys) - keys of the slot are moved with
+///   [`MIGRATE`](https://redis.io/commands/migrate/).
+/// * [`finish`](SlotMigration::finish) - the remaining keys are moved
+///   and the slot is assigned to the destination node.
+///   Commands on the keys of the slot are then redirected with `MOVED`.
+///
+/// A migration can be cancelled with [`abort`](SlotMigration::abort).
+///
+/// Nodes are reached directly by their announced IP address and port, without authentication nor TLS.
+///
+/// # Example
+/// ```
+/// use rustis::{
+///     client::Client, cluster::hash_slot, commands::StringCommands, test_util::SlotMigration,
+///     Result,
+/// };
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let client =
+///         Client::connect("redis+cluster://127.0.0.1:7000,127.0.0.1:7001,127.0.0.1:7002").await?;
+///     client.set("key", "value").await?;
+///
+///     let migration = SlotMigration::start(&client, hash_slot("key")).await?;
+///     let source_id = migration.source_id().to_owned();
+///
+///     // `ASK` redirection
+///     migration.migrate_keys(100).await?;
+///     let value: String = client.get("key").await?;
+///     assert_eq!("value", value);
+///
+///     // `MOVED` redirection
+///     migration.finish().await?;
+///     let value: String = client.get("key").await?;
+///     assert_eq!("value", value);
+///
+///     // migrate back
+///     SlotMigration::start_to(&client, hash_slot("key"), source_id)
+///         .await?
+///         .finish()
+///         .await?;
+///
+///     Ok(())
+/// }
+/// ```
+pub struct SlotMigration {
+    slot: u16,
+    source: MigrationNode,
+    destination: MigrationNode,
+    /// Master nodes which are neither the source nor the destination
+    other_masters: Vec<(String, u16)>,
+}
+
+impl SlotMigration {
+    /// Start the migration of `slot`, from its owner to another master node of the cluster
+    ///
+    /// # Errors
+    /// An error is returned if `client` is not connected to a cluster with at least 2 master nodes.
+    pub async fn start(client: &Client, slot: u16) -> Result<Self> {
+        Self::start_migration(client, slot, None).await
+    }
+
+    /// Start the migration of `slot`, from its owner to the master node `destination_id`
+    ///
+    /// # Errors
+    /// An error is returned if `client` is not connected to a cluster,
+    /// or if `destination_id` is not a master node of the cluster or already owns the slot.
+    pub async fn start_to(
+        client: &Client,
+        slot: u16,
+        destination_id: impl AsRef<str>,
+    ) -> Result<Self> {
+        Self::start_migration(client, slot, Some(destination_id.as_ref())).await
+    }
+
+    async fn start_migration(
+        client: &Client,
+        slot: u16,
+        destination_id: Option<&str>,
+    ) -> Result<Self> {
+        let mut source = None;
+        let mut destination = None;
+        let mut other_masters = Vec::new();
+
+        for shard in client.shard_info_list().await? {
+            let owns_slot = shard.slots.iter().any(|s| s.0 <= slot && slot <= s.1);
+            let Some(master) = shard.nodes.into_iter().find(|n| n.role == "master") else {
+                continue;
+            };
+            let port = master.get_port()?;
+
+            if owns_slot {
+                source = Some((master.id, master.ip, port));
+            } else if destination.is_none()
+                && (destination_id.is_none() || destination_id == Some(master.id.as_str()))
+            {
+                destination = Some((master.id, master.ip, port));
+            } else {
+                other_masters.push((master.ip, port));
+            }
+        }
+
+        let Some((source_id, source_ip, source_port)) = source else {
+            return Err(Error::Client(format!(
+                "No master node owns hash slot {slot}"
+            )));
+        };
+        let Some((destination_id, destination_ip, destination_port)) = destination else {
+            return Err(Error::Client(format!(
+                "Cannot find a destination master node for hash slot {slot}"
+            )));
+        };
+
+        let migration = Self {
+            slot,
+            source: MigrationNode::connect(source_id, source_ip, source_port).await?,
+            destination: MigrationNode::connect(destination_id, destination_ip, destination_port)
+                .await?,
+            other_masters,
+        };
+
+        migration
+            .destination
+            .set_slot(
+                slot,
+                ClusterSetSlotSubCommand::Importing {
+                    node_id: migration.source.id.clone(),
+                },
+            )
+            .await?;
+        migration
+            .source
+            .set_slot(
+                slot,
+                ClusterSetSlotSubCommand::Migrating {
+                    node_id: migration.destination.id.clone(),
+                },
+            )
+            .await?;
+
+        Ok(migration)
+    }
+
+    /// Migrated hash slot
+    #[must_use]
+    pub fn slot(&self) -> u16 {
+        self.slot
+    }
+
+    /// Id of the node owning the slot before the migration
+    #[must_use]
+    pub fn source_id(&self) -> &str {
+        &self.source.id
+    }
+
+    /// Id of the node owning the slot after the migration
+    #[must_use]
+    pub fn destination_id(&self) -> &str {
+        &self.destination.id
+    }
+
+    /// Move up to `count` keys of the slot from the source node to the destination node
+    ///
+    /// # Return
+    /// The number of moved keys, `0` once all the keys have been moved
+    pub async fn migrate_keys(&self, count: usize) -> Result<usize> {
+        move_keys(&self.source, &self.destination, self.slot, count).await
+    }
+
+    /// Move the remaining keys of the slot to the destination node
+    /// and assign the slot to the destination node
+    pub async fn finish(self) -> Result<()> {
+        self.source.drain_slot(&self.destination, self.slot).await?;
+
+        let node = || ClusterSetSlotSubCommand::Node {
+            node_id: self.destination.id.clone(),
+        };
+        self.destination.set_slot(self.slot, node()).await?;
+        self.source.set_slot(self.slot, node()).await?;
+
+        // propagate the new owner without waiting for the gossip protocol
+        for (ip, port) in &self.other_masters {
+            let client = Client::connect((ip.clone(), *port)).await?;
+            client.cluster_setslot(self.slot, node()).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Move back the keys already migrated to the source node
+    /// and clear the importing & migrating states of the slot
+    pub async fn abort(self) -> Result<()> {
+        self.destination.drain_slot(&self.source, self.slot).await?;
+
+        self.destination
+            .set_slot(self.slot, ClusterSetSlotSubCommand::Stable)
+            .await?;
+        self.source
+            .set_slot(self.slot, ClusterSetSlotSubCommand::Stable)
+            .await?;
+
+        Ok(())
+    }
+}
+
+async fn move_keys(
+    from: &MigrationNode,
+    to: &MigrationNode,
+    slot: u16,
+    count: usize,
+) -> Result<usize> {
+    let keys: Vec<BulkString> = from.client.cluster_keys_in_slot(slot, count).await?;
+    if keys.is_empty() {
+        return Ok(0);
+    }
+
+    let num_keys = keys.len();
+    from.client
+        .migrate(
+            to.ip.as_str(),
+            to.port,
+            "",
+            0,
+            MIGRATE_TIMEOUT,
+            MigrateOptions::default().keys(keys),
+        )
+        .await?;
+
+    Ok(num_keys)
+}
+
+/// Outcome of a [`SelectChurn`] run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SelectChurnReport {
+    /// Number of commands whose reply won the race
+    pub completed: usize,
+    /// Number of commands whose future has been dropped by the timer
+    pub cancelled: usize,
+    /// Number of replies received by a command which did not send the request,
+    /// expected to be `0`
+    pub mismatched: usize,
+    /// Number of commands which failed
+    pub failed: usize,
+}
+
+/// Regression harness simulating heavy `select!` churn on a client:
+/// commands are raced against random timers, the futures of the losers being dropped
+/// in the middle of their exchange with the server.
+///
+/// Half of the timers expire after a random delay, the other half after having been polled
+/// a random number of times, so that futures are also cancelled right after
+/// their request has been queued, whatever the latency of the server.
+///
+/// Each command is an [`ECHO`](https://redis.io/commands/echo/) of a unique message,
+/// so that a reply delivered to the wrong caller is detected.
+/// Cancelled futures never affect the connection: the network task still receives
+/// their replies and discards them, see [`Client`].
+///
+/// # Example
+/// ```
+/// use rustis::{client::Client, test_util::SelectChurn, Result};
+/// use std::time::Duration;
+///
+/// #[cfg_attr(feature = "tokio-runtime", tokio::main)]
+/// #[cfg_attr(feature = "async-std-runtime", async_std::main)]
+/// async fn main() -> Result<()> {
+///     let client = Client::connect("127.0.0.1:6379").await?;
+///
+///     let report = SelectChurn::new(client)
+///         .tasks(32)
+///         .iterations(100)
+///         .max_delay(Duration::from_micros(500))
+///         .run()
+///         .await?;
+///     assert_eq!(0, report.mismatched);
+///     assert_eq!(0, report.failed);
+///
+///     Ok(())
+/// }
+/// ```
+pub struct SelectChurn {
+    client: Client,
+    tasks: usize,
+    iterations: usize,
+    max_delay: Duration,
+}
+
+impl SelectChurn {
+    /// Create a new harness running 16 concurrent tasks of 100 iterations,
+    /// with timers of up to 1ms
+    #[must_use]
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            tasks: 16,
+            iterations: 100,
+            max_delay: Duration::from_millis(1),
+        }
+    }
+
+    /// Number of concurrent tasks
+    #[must_use]
+    pub fn tasks(mut self, tasks: usize) -> Self {
+        self.tasks = tasks;
+        self
+    }
+
+    /// Number of commands sent by each task
+    #[must_use]
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Maximum delay of the timed timers racing the commands,
+    /// each delay being drawn uniformly between zero and this delay
+    #[must_use]
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Run the tasks, then check that the client still correlates its replies
+    ///
+    /// # Errors
+    /// An [`Error::Client`] if a command sent after the churn
+    /// receives the reply of another command
+    pub async fn run(&self) -> Result<SelectChurnReport> {
+        let reports = future::join_all((0..self.tasks).map(|task| self.run_task(task))).await;
+
+        let report = reports
+            .into_iter()
+            .fold(SelectChurnReport::default(), |total, report| {
+                SelectChurnReport {
+                    completed: total.completed + report.completed,
+                    cancelled: total.cancelled + report.cancelled,
+                    mismatched: total.mismatched + report.mismatched,
+                    failed: total.failed + report.failed,
+                }
+            });
+
+        let message = "select-churn:check";
+        let reply: String = self.client.echo(message).await?;
+        if reply != message {
+            return Err(Error::Client(format!(
+                "Reply `{reply}` received for message `{message}` after select! churn"
+            )));
+        }
+
+        Ok(report)
+    }
+
+    async fn run_task(&self, task: usize) -> SelectChurnReport {
+        let mut report = SelectChurnReport::default();
+
+        for iteration in 0..self.iterations {
+            let message = format!("select-churn:{task}:{iteration}");
+            let timer = {
+                let mut rng = rand::thread_rng();
+                if rng.gen_bool(0.5) {
+                    Either::Left(sleep(self.max_delay.mul_f64(rng.gen_range(0.0..=1.0))))
+                } else {
+                    Either::Right(after_polls(rng.gen_range(0..4)))
+                }
+            };
+
+            let echo = pin!(self
+                .client
+                .echo::<_, String>(message.as_str())
+                .into_future());
+            match future::select(echo, pin!(timer)).await {
+                Either::Left((Ok(reply), _)) if reply == message => report.completed += 1,
+                Either::Left((Ok(_), _)) => report.mismatched += 1,
+                Either::Left((Err(_), _)) => report.failed += 1,
+                Either::Right(_) => report.cancelled += 1,
+            }
+        }
+
+        report
+    }
+}
+
+/// Future ready after having been polled `polls` times
+fn after_polls(mut polls: usize) -> impl Future<Output = ()> {
+    future::poll_fn(move |cx| {
+        if polls == 0 {
+            Poll::Ready(())
+        } else {
+            polls -= 1;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    })
+}
@@ -0,0 +1,99 @@
+use crate::{
+    client::{AsyncTransport, Client, Config, IntoConfig, TransportReader, TransportWriter},
+    commands::{ConnectionCommands, StringCommands},
+    resp::BufferDecoder,
+    spawn,
+    tests::log_try_init,
+    Future, Result,
+};
+use futures_util::StreamExt;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncWriteExt, DuplexStream};
+use tokio_util::codec::FramedRead;
+
+/// Transport serving each connection with an in-memory fake server
+#[derive(Debug, Default)]
+struct InMemoryTransport {
+    connections: Mutex<Vec<(String, u16)>>,
+}
+
+impl AsyncTransport for InMemoryTransport {
+    fn connect<'a>(
+        &'a self,
+        host: &'a str,
+        port: u16,
+        _config: &'a Config,
+    ) -> Future<'a, (TransportReader, TransportWriter)> {
+        Box::pin(async move {
+            self.connections
+                .lock()
+                .unwrap()
+                .push((host.to_owned(), port));
+            let (client_side, server_side) = tokio::io::duplex(4096);
+            spawn(fake_server(server_side));
+            let (reader, writer) = tokio::io::split(client_side);
+            Ok((
+                Box::new(reader) as TransportReader,
+                Box::new(writer) as TransportWriter,
+            ))
+        })
+    }
+}
+
+/// Reply to `HELLO`, `PING`, `SET` & `GET` with the same value for all keys
+async fn fake_server(stream: DuplexStream) {
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut framed_read = FramedRead::new(reader, BufferDecoder::default());
+    let mut value = String::new();
+
+    while let Some(Ok(resp_buf)) = framed_read.next().await {
+        let Ok(args) = resp_buf.to::<Vec<String>>() else {
+            break;
+        };
+        let reply = match args[0].to_ascii_uppercase().as_bytes() {
+            b"HELLO" => b"%7\r\n+server\r\n+redis\r\n+version\r\n+7.0.0\r\n+proto\r\n:3\r\n\
+                +id\r\n:1\r\n+mode\r\n+standalone\r\n+role\r\n+master\r\n+modules\r\n*0\r\n"
+                .to_vec(),
+            b"PING" => b"+PONG\r\n".to_vec(),
+            b"SET" => {
+                value = args[2].clone();
+                b"+OK\r\n".to_vec()
+            }
+            b"GET" => [
+                format!("${}\r\n", value.len()).as_bytes(),
+                value.as_bytes(),
+                b"\r\n",
+            ]
+            .concat(),
+            _ => b"-ERR unknown command\r\n".to_vec(),
+        };
+        if writer.write_all(&reply).await.is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn async_transport() -> Result<()> {
+    log_try_init();
+
+    let transport = Arc::new(InMemoryTransport::default());
+    let mut config = "redis://in-memory:1234".into_config()?;
+    config.transport = Some(transport.clone());
+
+    let client = Client::connect(config).await?;
+    client.ping::<()>(Default::default()).await?;
+    client.set("key", "value").await?;
+    let value: String = client.get("key").await?;
+    assert_eq!("value", value);
+
+    assert_eq!(
+        vec![("in-memory".to_owned(), 1234)],
+        *transport.connections.lock().unwrap()
+    );
+
+    client.close().await?;
+
+    Ok(())
+}
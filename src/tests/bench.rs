@@ -0,0 +1,46 @@
+use crate::{
+    bench::{run, BenchCommand, BenchConfig},
+    resp::cmd,
+    tests::{get_default_config, log_try_init},
+    Result,
+};
+use serial_test::serial;
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn bench() -> Result<()> {
+    log_try_init();
+
+    let bench_config = BenchConfig {
+        workload: vec![
+            (BenchCommand::Set, 1),
+            (BenchCommand::Get, 1),
+            (
+                BenchCommand::Custom(Box::new(
+                    cmd("INCRBY").arg("bench:counter").arg("__rand_int__"),
+                )),
+                1,
+            ),
+        ],
+        requests: 1_000,
+        connections: 4,
+        pipeline_depth: 8,
+        key_space: 100,
+        ..Default::default()
+    };
+
+    let report = run(get_default_config()?, bench_config).await?;
+    assert_eq!(1_000, report.requests);
+    assert_eq!(0, report.errors);
+    assert!(report.throughput > 0.0);
+    assert!(report.latency.min <= report.latency.p50);
+    assert!(report.latency.p50 <= report.latency.max);
+    assert_eq!(
+        1_000,
+        report.commands.values().map(|c| c.requests).sum::<usize>()
+    );
+    assert!(report.commands.contains_key("INCRBY"));
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+#[cfg(feature = "mock")]
+use crate::client::{BatchPreparedCommand, ClientPreparedCommand, MockServerBehavior};
+use crate::{
+    blocking::Client,
+    commands::{FlushingMode, ServerCommands, StringCommands},
+    tests::{get_default_addr, log_try_init},
+    Result,
+};
+
+#[cfg(feature = "mock")]
+#[test]
+fn blocking_commands() -> Result<()> {
+    log_try_init();
+    let client = Client::connect_mock(MockServerBehavior::new())?;
+
+    client.set("key", "value").execute()?;
+    let value: String = client.get("key").execute()?;
+    assert_eq!("value", value);
+
+    client.set("key2", "value2").forget()?;
+    let values: Vec<String> = client.mget(["key", "key2"]).execute()?;
+    assert_eq!(vec!["value".to_owned(), "value2".to_owned()], values);
+
+    let mut pipeline = client.create_pipeline();
+    pipeline.incr("counter").queue();
+    pipeline.incr("counter").queue();
+    let (c1, c2): (i64, i64) = pipeline.execute()?;
+    assert_eq!((1, 2), (c1, c2));
+
+    let mut transaction = client.create_transaction();
+    transaction.incr("counter").queue();
+    transaction.get::<_, String>("key").queue();
+    let (counter, value): (i64, String) = transaction.execute()?;
+    assert_eq!(3, counter);
+    assert_eq!("value", value);
+
+    client.close()?;
+
+    Ok(())
+}
+
+#[cfg(feature = "mock")]
+#[test]
+fn blocking_threads() -> Result<()> {
+    log_try_init();
+    let client = Client::connect_mock(MockServerBehavior::new())?;
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let client = client.clone();
+            std::thread::spawn(move || -> Result<()> {
+                for _ in 0..100 {
+                    client.incr("counter").execute()?;
+                }
+                Ok(())
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap()?;
+    }
+
+    let counter: i64 = client.get("counter").execute()?;
+    assert_eq!(400, counter);
+
+    Ok(())
+}
+
+#[test]
+fn blocking_client() -> Result<()> {
+    log_try_init();
+    let client = Client::connect(get_default_addr())?;
+    client.flushdb(FlushingMode::Sync).execute()?;
+
+    client.set("key", "value").execute()?;
+    let value: String = client.get("key").execute()?;
+    assert_eq!("value", value);
+
+    // async features through the internal runtime
+    let value: String = client.block_on(async { client.async_client().get("key").await })?;
+    assert_eq!("value", value);
+
+    Ok(())
+}
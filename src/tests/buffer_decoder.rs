@@ -4,7 +4,7 @@ use tokio_util::codec::Decoder;
 use crate::{resp::BufferDecoder, Result};
 
 fn decode(str: &str) -> Result<Option<Vec<u8>>> {
-    let mut buffer_decoder = BufferDecoder;
+    let mut buffer_decoder = BufferDecoder::default();
     let mut buf: BytesMut = str.into();
     buffer_decoder
         .decode(&mut buf)
@@ -211,3 +211,106 @@ fn map() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn attribute() -> Result<()> {
+    let result = decode("|1\r\n+ttl\r\n:3600\r\n(12\r\n")?;
+    assert_eq!(
+        Some("|1\r\n+ttl\r\n:3600\r\n(12\r\n".as_bytes().to_vec()),
+        result
+    );
+
+    let result = decode("|1\r\n+ttl\r\n:3600\r\n")?;
+    assert_eq!(None, result);
+
+    Ok(())
+}
+
+#[test]
+fn resumed_scan() -> Result<()> {
+    let frame =
+        "*3\r\n$5\r\nhello\r\n%1\r\n+key\r\n*1\r\n:12\r\n|1\r\n+ttl\r\n:3600\r\n$5\r\nworld\r\n";
+    let mut buffer_decoder = BufferDecoder::default();
+    let mut buf = BytesMut::new();
+
+    // bytes are received one by one
+    for (i, byte) in frame.as_bytes().iter().enumerate() {
+        buf.extend_from_slice(&[*byte]);
+        let result = buffer_decoder.decode(&mut buf)?;
+        if i < frame.len() - 1 {
+            assert!(result.is_none());
+        } else {
+            assert_eq!(Some(frame.as_bytes()), result.as_deref());
+        }
+    }
+    assert!(buf.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn multiple_frames() -> Result<()> {
+    let mut buffer_decoder = BufferDecoder::default();
+    let mut buf: BytesMut = "*2\r\n:1\r\n:2\r\n+OK\r\n$5\r\nhel".into();
+
+    let result = buffer_decoder.decode(&mut buf)?;
+    assert_eq!(Some(b"*2\r\n:1\r\n:2\r\n".as_slice()), result.as_deref());
+    let result = buffer_decoder.decode(&mut buf)?;
+    assert_eq!(Some(b"+OK\r\n".as_slice()), result.as_deref());
+    let result = buffer_decoder.decode(&mut buf)?;
+    assert_eq!(None, result.as_deref());
+
+    buf.extend_from_slice(b"lo\r\n*0\r\n");
+    let result = buffer_decoder.decode(&mut buf)?;
+    assert_eq!(Some(b"$5\r\nhello\r\n".as_slice()), result.as_deref());
+    let result = buffer_decoder.decode(&mut buf)?;
+    assert_eq!(Some(b"*0\r\n".as_slice()), result.as_deref());
+    assert!(buf.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn malformed() {
+    assert!(decode("?12\r\n").is_err());
+    assert!(decode("$a\r\nhello\r\n").is_err());
+    assert!(decode("$5\r\nhelloo\r\n").is_err());
+}
+
+fn decode_inline(str: &str) -> Result<Option<Vec<u8>>> {
+    let mut buffer_decoder = BufferDecoder::default();
+    buffer_decoder.accept_inline_replies();
+    let mut buf: BytesMut = str.into();
+    buffer_decoder
+        .decode(&mut buf)
+        .map(|b| b.map(|b| b.to_vec()))
+}
+
+#[test]
+fn inline_reply() -> Result<()> {
+    let result = decode_inline("PONG\r\n")?;
+    assert_eq!(Some("+PONG\r\n".as_bytes().to_vec()), result);
+
+    let result = decode_inline("OK\n")?;
+    assert_eq!(Some("+OK\r\n".as_bytes().to_vec()), result);
+
+    let result = decode_inline("PONG\r")?;
+    assert_eq!(None, result);
+
+    let mut buffer_decoder = BufferDecoder::default();
+    buffer_decoder.accept_inline_replies();
+    let mut buf: BytesMut = "OK\r\n:12\r\n".into();
+    let result = buffer_decoder.decode(&mut buf)?;
+    assert_eq!(Some(b"+OK\r\n".as_slice()), result.as_deref());
+    let result = buffer_decoder.decode(&mut buf)?;
+    assert_eq!(Some(b":12\r\n".as_slice()), result.as_deref());
+
+    Ok(())
+}
+
+#[test]
+fn stray_line_without_inline_command() {
+    // without any inline command sent, a line without RESP type tag is a protocol error
+    assert!(decode("PONG\r\n").is_err());
+    assert!(decode("1234\r\n").is_err());
+}
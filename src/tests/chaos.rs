@@ -0,0 +1,164 @@
+use crate::client::{ChaosConfig, ChaosFault};
+#[cfg(feature = "mock")]
+use crate::{
+    client::{BatchPreparedCommand, Client, Config, MockServerBehavior, RetryOn},
+    commands::StringCommands,
+    network::{ChaosConnection, Connection, MockConnection},
+    Result,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use std::time::Duration;
+
+fn draw_faults(config: &ChaosConfig, num_writes: u64) -> Vec<Option<ChaosFault>> {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    (0..num_writes)
+        .map(|write_index| config.draw_fault(write_index, &mut rng))
+        .collect()
+}
+
+#[cfg(feature = "mock")]
+fn connect_chaos_mock(chaos: ChaosConfig, retry_on: RetryOn) -> Client {
+    let connection = Connection::Chaos(ChaosConnection::new(
+        Connection::Mock(MockConnection::new(MockServerBehavior::new())),
+        chaos,
+    ));
+    let mut config = Config::default();
+    config.resilience.retry_on = retry_on;
+    Client::start(connection, config)
+}
+
+fn chaos_config(seed: u64) -> ChaosConfig {
+    ChaosConfig::new(seed)
+        .latency(0.2, Duration::from_millis(1), Duration::from_millis(5))
+        .drop_responses(0.1)
+        .disconnects(0.1)
+        .moved_errors(0.1)
+        .ask_errors(0.1)
+}
+
+#[test]
+fn chaos_schedule() {
+    let config = chaos_config(42);
+
+    // same seed, same schedule
+    let faults = draw_faults(&config, 1000);
+    assert_eq!(faults, draw_faults(&config, 1000));
+    assert_ne!(faults, draw_faults(&chaos_config(43), 1000));
+
+    for fault in [
+        ChaosFault::DropResponse,
+        ChaosFault::Disconnect,
+        ChaosFault::Moved,
+        ChaosFault::Ask,
+    ] {
+        let count = faults.iter().filter(|f| f.as_ref() == Some(&fault)).count();
+        assert!((50..150).contains(&count), "{fault:?}: {count}");
+    }
+    let latencies: Vec<Duration> = faults
+        .iter()
+        .filter_map(|f| match f {
+            Some(ChaosFault::Latency(latency)) => Some(*latency),
+            _ => None,
+        })
+        .collect();
+    assert!((100..300).contains(&latencies.len()));
+    assert!(latencies
+        .iter()
+        .all(|l| (Duration::from_millis(1)..=Duration::from_millis(5)).contains(l)));
+
+    // a pinned fault does not shift the rest of the schedule
+    let pinned = draw_faults(&config.clone().fault_at(3, ChaosFault::Disconnect), 1000);
+    assert_eq!(Some(ChaosFault::Disconnect), pinned[3]);
+    assert_eq!(faults[..3], pinned[..3]);
+    assert_eq!(faults[4..], pinned[4..]);
+
+    // no fault configured
+    assert!(draw_faults(&ChaosConfig::new(42), 1000)
+        .iter()
+        .all(Option::is_none));
+}
+
+#[cfg(feature = "mock")]
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn chaos_disconnect() -> Result<()> {
+    let chaos = ChaosConfig::new(0).fault_at(1, ChaosFault::Disconnect);
+    let client = connect_chaos_mock(chaos.clone(), RetryOn::Never);
+
+    client.set("key", "value").await?;
+    // the command is not sent
+    let result = client.set("key", "other").await;
+    assert!(result.is_err());
+    assert_eq!(vec![(1, ChaosFault::Disconnect)], chaos.injected_faults());
+
+    // the command is sent again after the reconnection
+    let chaos = ChaosConfig::new(0).fault_at(1, ChaosFault::Disconnect);
+    let client = connect_chaos_mock(chaos.clone(), RetryOn::NetworkError);
+
+    client.set("key", "value").await?;
+    client.set("key", "other").await?;
+    let value: String = client.get("key").await?;
+    assert_eq!("other", value);
+    assert_eq!(vec![(1, ChaosFault::Disconnect)], chaos.injected_faults());
+
+    Ok(())
+}
+
+#[cfg(feature = "mock")]
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn chaos_drop_response() -> Result<()> {
+    let chaos = ChaosConfig::new(0).fault_at(0, ChaosFault::DropResponse);
+    let client = connect_chaos_mock(chaos.clone(), RetryOn::Never);
+
+    // the command is executed but its reply is lost
+    let result = client.incr("counter").await;
+    assert!(result.is_err());
+    assert_eq!(vec![(0, ChaosFault::DropResponse)], chaos.injected_faults());
+
+    // the command is executed twice when sent again
+    let chaos = ChaosConfig::new(0).fault_at(0, ChaosFault::DropResponse);
+    let client = connect_chaos_mock(chaos.clone(), RetryOn::NetworkError);
+
+    assert_eq!(2, client.incr("counter").await?);
+    assert_eq!(vec![(0, ChaosFault::DropResponse)], chaos.injected_faults());
+
+    Ok(())
+}
+
+#[cfg(feature = "mock")]
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn chaos_transaction() -> Result<()> {
+    let chaos = ChaosConfig::new(0).fault_at(0, ChaosFault::DropResponse);
+    let client = connect_chaos_mock(chaos.clone(), RetryOn::Never);
+
+    let mut transaction = client.create_transaction();
+    transaction.set("key1", "value1").forget();
+    transaction.set("key2", "value2").forget();
+    let result: Result<()> = transaction.execute().await;
+    assert!(result.is_err());
+
+    // the connection is not left in a transaction
+    let values: Vec<String> = client.mget(["key1", "key2"]).retry_on_error(true).await?;
+    assert_eq!(vec!["value1".to_owned(), "value2".to_owned()], values);
+
+    Ok(())
+}
+
+#[cfg(feature = "mock")]
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn chaos_latency() -> Result<()> {
+    let chaos = ChaosConfig::new(0).latency(1., Duration::from_millis(1), Duration::from_millis(2));
+    let client = connect_chaos_mock(chaos.clone(), RetryOn::Never);
+
+    for i in 0..5 {
+        client.set("key", i).await?;
+    }
+    let value: i64 = client.get("key").await?;
+    assert_eq!(4, value);
+    assert_eq!(6, chaos.injected_faults().len());
+
+    Ok(())
+}
@@ -1,15 +1,27 @@
-use std::time::Duration;
+use std::{
+    future::IntoFuture,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use crate::{
-    client::{Client, IntoConfig},
+    client::{
+        BatchPreparedCommand, Client, CommandLayer, CommandPermission, DefaultOptions, IntoConfig,
+        KeyspaceEvents,
+    },
     commands::{
-        BlockingCommands, ClientKillOptions, ConnectionCommands, FlushingMode, LMoveWhere,
-        ListCommands, ServerCommands, StringCommands,
+        BlockingCommands, ClientKillOptions, ConnectionCommands, FlushingMode, GenericCommands,
+        InfoSection, LMoveWhere, ListCommands, ServerCommands, SetCondition, SetExpiration,
+        StringCommands,
     },
-    resp::cmd,
-    tests::{get_default_addr, get_test_client, log_try_init},
-    Error, Result,
+    resp::{cmd, Command, RespBuf},
+    tests::{
+        get_default_addr, get_default_config, get_test_client, get_test_client_with_config,
+        log_try_init,
+    },
+    Error, Result, Version,
 };
+use futures_util::future;
 use serial_test::serial;
 
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
@@ -39,6 +51,49 @@ async fn forget() -> Result<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn without_reply() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    client.send_without_reply(cmd("SET").arg("key1").arg("value1"), None)?;
+    let value: String = client.get("key1").await?;
+    assert_eq!("value1", value);
+
+    client
+        .send_batch_without_replies(
+            vec![
+                cmd("SET").arg("key2").arg("value2"),
+                cmd("SET").arg("key3").arg("value3"),
+            ],
+            None,
+        )
+        .await?;
+    let value: String = client.get("key3").await?;
+    assert_eq!("value3", value);
+
+    client.close().await?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn server_version() -> Result<()> {
+    let client = get_test_client().await?;
+
+    let version = client.server_version();
+    assert!(version.is_some());
+    assert!(version.unwrap() >= Version::new(6, 0, 0));
+
+    client.close().await?;
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
@@ -71,6 +126,7 @@ async fn on_reconnect() -> Result<()> {
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
+#[allow(deprecated)]
 async fn command_timeout() -> Result<()> {
     log_try_init();
 
@@ -95,6 +151,19 @@ async fn command_timeout() -> Result<()> {
         client.blmpop(5., "key", LMoveWhere::Left, 1).await;
     assert!(matches!(result, Err(Error::Timeout(_))));
 
+    // per command override of the timeout
+    let result: Option<(String, Vec<String>)> = client
+        .blmpop(0.1, "key", LMoveWhere::Left, 1)
+        .command_timeout(Duration::from_secs(1))
+        .await?;
+    assert_eq!(None, result);
+
+    let result: Result<Option<(String, Vec<String>)>> = client
+        .blmpop(0.1, "key", LMoveWhere::Left, 1)
+        .command_timeout(Duration::from_millis(10))
+        .await;
+    assert!(matches!(result, Err(Error::Timeout(_))));
+
     client.close().await?;
 
     Ok(())
@@ -121,6 +190,33 @@ async fn connection_name() -> Result<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn socket_options() -> Result<()> {
+    log_try_init();
+
+    let mut config = get_default_addr().into_config()?;
+    config.send_buffer_size = Some(64 * 1024);
+    config.recv_buffer_size = Some(64 * 1024);
+    config.tos = Some(0x10);
+    config.local_address = Some([127, 0, 0, 1].into());
+    #[cfg(target_os = "linux")]
+    {
+        config.tcp_user_timeout = Some(Duration::from_secs(5));
+    }
+
+    let client = Client::connect(config).await?;
+
+    client.set("key", "value").await?;
+    let value: String = client.get("key").await?;
+    assert_eq!("value", value);
+
+    client.close().await?;
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
@@ -163,3 +259,544 @@ async fn mget_mset() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn coalesce_reads() -> Result<()> {
+    log_try_init();
+
+    let mut config = get_default_addr().into_config()?;
+    config.coalesce_reads = true;
+    let client = Client::connect(config).await?;
+
+    client.flushall(FlushingMode::Sync).await?;
+    client.set("key", "value").await?;
+    client.config_resetstat().await?;
+
+    let values: Vec<String> =
+        future::try_join_all((0..100).map(|_| client.get::<_, String>("key").into_future()))
+            .await?;
+    assert!(values.iter().all(|v| v == "value"));
+
+    // a single GET has reached the server
+    let info = client.info([InfoSection::Commandstats]).await?;
+    assert!(info.contains("cmdstat_get:calls=1,"));
+
+    client.close().await?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn command_layers() -> Result<()> {
+    log_try_init();
+
+    /// Prefix the key of GET & SET commands
+    struct KeyPrefix;
+
+    impl CommandLayer for KeyPrefix {
+        fn on_command(&self, command: &mut Command) -> Result<()> {
+            if command.name == "GET" || command.name == "SET" {
+                let mut prefixed = cmd(command.name);
+                for (i, arg) in command.args.iter().enumerate() {
+                    if i == 0 {
+                        prefixed = prefixed.arg([b"app:", arg.as_slice()].concat());
+                    } else {
+                        prefixed = prefixed.arg(arg.as_slice());
+                    }
+                }
+                *command = prefixed;
+            }
+            Ok(())
+        }
+    }
+
+    struct ForbidFlushAll;
+
+    impl CommandLayer for ForbidFlushAll {
+        fn on_command(&self, command: &mut Command) -> Result<()> {
+            if command.name == "FLUSHALL" {
+                return Err(Error::Client("FLUSHALL is forbidden".to_owned()));
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct Audit(Mutex<Vec<(String, bool)>>);
+
+    impl CommandLayer for Arc<Audit> {
+        fn on_result(&self, command: &Command, result: &Result<RespBuf>) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((command.name.to_owned(), result.is_ok()));
+        }
+    }
+
+    let audit = Arc::new(Audit::default());
+    let mut config = get_default_addr().into_config()?;
+    config
+        .layer(ForbidFlushAll)
+        .layer(KeyPrefix)
+        .layer(audit.clone());
+    let client = Client::connect(config).await?;
+
+    client.set("key", "value").await?;
+    let value: String = client.get("key").await?;
+    assert_eq!("value", value);
+
+    // the key has been prefixed
+    let regular_client = get_test_client().await?;
+    let value: String = regular_client.get("app:key").await?;
+    assert_eq!("value", value);
+    regular_client.del("app:key").await?;
+
+    let result = client.flushall(FlushingMode::Sync).await;
+    assert!(matches!(result, Err(Error::Client(_))));
+
+    let mut pipeline = client.create_pipeline();
+    pipeline.set("key", "value2").forget();
+    pipeline.get::<_, String>("key").queue();
+    let value: String = pipeline.execute().await?;
+    assert_eq!("value2", value);
+    regular_client.del("app:key").await?;
+
+    assert_eq!(
+        vec![
+            ("SET".to_owned(), true),
+            ("GET".to_owned(), true),
+            ("SET".to_owned(), true),
+            ("GET".to_owned(), true)
+        ],
+        *audit.0.lock().unwrap()
+    );
+
+    client.close().await?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn stats() -> Result<()> {
+    let client = get_test_client().await?;
+
+    client.set("key", "value").await?;
+    let _value: String = client.get("key").await?;
+    let result = client.send(cmd("UNKNOWN_COMMAND"), None).await;
+    assert!(matches!(result, Err(Error::Redis(_))));
+
+    let stats = client.stats();
+    assert_eq!(1, stats.nodes.len());
+    let node = &stats.nodes[0];
+    assert_eq!(get_default_addr(), node.address);
+    assert!(node.commands_sent >= 3);
+    assert!(node.errors >= 1);
+    assert!(node.bytes_in > 0);
+    assert!(node.bytes_out > 0);
+    assert!(node.latency_p50.is_some());
+    assert!(node.latency_p50 <= node.latency_p99);
+
+    // counters are shared by clones
+    let clone = client.clone();
+    clone.del("key").await?;
+    assert!(client.stats().nodes[0].commands_sent > node.commands_sent);
+
+    client.close().await?;
+
+    Ok(())
+}
+
+#[test]
+fn default_options() -> Result<()> {
+    fn args(command: &Command) -> Vec<String> {
+        command
+            .args
+            .iter()
+            .map(|arg| String::from_utf8_lossy(arg).into_owned())
+            .collect()
+    }
+
+    let set_ttl = DefaultOptions::set_ttl(Duration::from_secs(60));
+
+    let mut command = cmd("SET").arg("key").arg("value");
+    set_ttl.on_command(&mut command)?;
+    assert_eq!(vec!["key", "value", "PX", "60000"], args(&command));
+
+    let mut command = cmd("SET").arg("key").arg("value").arg("ex").arg(10);
+    set_ttl.on_command(&mut command)?;
+    assert_eq!(vec!["key", "value", "ex", "10"], args(&command));
+
+    // positional arguments are not options
+    let mut command = cmd("SET").arg("keepttl").arg("px");
+    set_ttl.on_command(&mut command)?;
+    assert_eq!(vec!["keepttl", "px", "PX", "60000"], args(&command));
+
+    let mut command = cmd("GET").arg("key");
+    set_ttl.on_command(&mut command)?;
+    assert_eq!(vec!["key"], args(&command));
+
+    let scan_count = DefaultOptions::scan_count(100);
+
+    let mut command = cmd("HSCAN").arg("key").arg(0).arg("MATCH").arg("*");
+    scan_count.on_command(&mut command)?;
+    assert_eq!(
+        vec!["key", "0", "MATCH", "*", "COUNT", "100"],
+        args(&command)
+    );
+
+    let mut command = cmd("SCAN").arg(0).arg("COUNT").arg(10);
+    scan_count.on_command(&mut command)?;
+    assert_eq!(vec!["0", "COUNT", "10"], args(&command));
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn default_options_layer() -> Result<()> {
+    let mut config = get_default_addr().into_config()?;
+    config.default_options(DefaultOptions::set_ttl(Duration::from_secs(60)));
+    let client = Client::connect(config).await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    client.set("key1", "value").await?;
+    let ttl = client.ttl("key1").await?;
+    assert!(ttl > 0 && ttl <= 60);
+
+    client
+        .set_with_options(
+            "key2",
+            "value",
+            SetCondition::None,
+            SetExpiration::Ex(10),
+            false,
+        )
+        .await?;
+    let ttl = client.ttl("key2").await?;
+    assert!(ttl > 0 && ttl <= 10);
+
+    client.close().await?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn inline_command() -> Result<()> {
+    let client = get_test_client().await?;
+
+    let pong: String = client.send(cmd("PING").inline(true), None).await?.to()?;
+    assert_eq!("PONG", pong);
+
+    let value = "a \"quoted\" value\r\n\\";
+    client
+        .send(cmd("SET").arg("key").arg(value).inline(true), None)
+        .await?;
+    let result: String = client.get("key").await?;
+    assert_eq!(value, result);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn connect_lazy() -> Result<()> {
+    log_try_init();
+    let client = Client::connect_lazy(get_default_addr())?;
+    assert_eq!(None, client.server_version());
+
+    client.warm_up().await?;
+    assert!(client.server_version().is_some());
+
+    client.set("key", "value").await?;
+    let value: String = client.get("key").await?;
+    assert_eq!("value", value);
+
+    // the connection is also established by the first command
+    let client = Client::connect_lazy(get_default_addr())?;
+    let value: String = client.get("key").await?;
+    assert_eq!("value", value);
+
+    client.close().await?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn connect_lazy_failure() -> Result<()> {
+    log_try_init();
+    // nothing listens on port 1
+    let client = Client::connect_lazy("127.0.0.1:1")?;
+
+    assert!(client.warm_up().await.is_err());
+    // the connection is attempted again
+    assert!(client.warm_up().await.is_err());
+    assert!(client.send(cmd("PING"), None).await.is_err());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn error_context() -> Result<()> {
+    log_try_init();
+    // nothing listens on port 1
+    let client = Client::connect_lazy("redis://127.0.0.1:1?error_context=true")?;
+
+    let error = client.warm_up().await.unwrap_err();
+    let context = error.context().unwrap();
+    assert_eq!(Some("PING"), context.command.as_deref());
+    assert!(context.elapsed.is_some());
+    assert!(matches!(error.inner(), Error::IO(_)));
+    assert!(error.to_string().contains("command: PING"));
+
+    let error = client
+        .send_batch(
+            vec![cmd("SET").arg("key").arg("value"), cmd("GET").arg("key")],
+            None,
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        Some("SET"),
+        error
+            .context()
+            .and_then(|context| context.command.as_deref())
+    );
+
+    // disabled by default
+    let client = Client::connect_lazy("127.0.0.1:1")?;
+    let error = client.warm_up().await.unwrap_err();
+    assert!(error.context().is_none());
+    assert!(matches!(error, Error::IO(_)));
+
+    Ok(())
+}
+
+#[test]
+fn keyspace_events() -> Result<()> {
+    let events: KeyspaceEvents = "AKE".parse()?;
+    assert!(events.contains(KeyspaceEvents::KEYSPACE | KeyspaceEvents::EXPIRED));
+    assert!(!events.contains(KeyspaceEvents::NEW_KEY | KeyspaceEvents::KEY_MISS));
+    assert_eq!("KEg$lshzxetd", events.to_string());
+
+    let events: KeyspaceEvents = "xE".parse()?;
+    assert_eq!(KeyspaceEvents::KEYEVENT | KeyspaceEvents::EXPIRED, events);
+    assert_eq!("Ex", events.to_string());
+
+    assert!("".parse::<KeyspaceEvents>()?.is_empty());
+    assert_eq!("", KeyspaceEvents::NONE.to_string());
+    assert!(matches!(
+        "Ky".parse::<KeyspaceEvents>(),
+        Err(Error::Client(_))
+    ));
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn ensure_keyspace_notifications() -> Result<()> {
+    let client = get_test_client().await?;
+    client.config_set(("notify-keyspace-events", "Kg")).await?;
+
+    let report = client
+        .ensure_keyspace_notifications(KeyspaceEvents::KEYEVENT | KeyspaceEvents::EXPIRED)
+        .await?;
+    assert!(report.is_enabled());
+    assert_eq!(KeyspaceEvents::NONE, report.missing());
+    assert_eq!(1, report.nodes.len());
+    let node = &report.nodes[0];
+    assert_eq!(
+        KeyspaceEvents::KEYSPACE | KeyspaceEvents::GENERIC,
+        node.previous
+    );
+    assert_eq!("KEgx", node.enabled.to_string());
+    assert!(node.updated());
+    assert!(node.error.is_none());
+
+    // already enabled
+    let report = client
+        .ensure_keyspace_notifications(KeyspaceEvents::EXPIRED)
+        .await?;
+    assert!(report.is_enabled());
+    assert!(!report.nodes[0].updated());
+
+    client.config_set(("notify-keyspace-events", "")).await?;
+    client.close().await?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn with_database() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushall(FlushingMode::Sync).await?;
+
+    let database1 = client.with_database(1)?;
+    database1.set("key", "value1").await?;
+
+    let value: Option<String> = client.get("key").await?;
+    assert_eq!(None, value);
+    let value: String = database1.get("key").await?;
+    assert_eq!("value1", value);
+
+    // connection shared by the clones of the client
+    let value: String = client.clone().with_database(1)?.get("key").await?;
+    assert_eq!("value1", value);
+
+    // same database
+    client.set("key", "value0").await?;
+    let value: String = client.with_database(0)?.get("key").await?;
+    assert_eq!("value0", value);
+
+    client.flushall(FlushingMode::Sync).await?;
+    client.close().await?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn probe_permissions() -> Result<()> {
+    let client = get_test_client().await?;
+    client
+        .acl_setuser(
+            "PROBE",
+            [
+                "reset",
+                "on",
+                "nopass",
+                "+@connection",
+                "+acl|whoami",
+                "+acl|dryrun",
+                "+get",
+                "~cache:*",
+            ],
+        )
+        .await?;
+
+    let probe_client =
+        Client::connect(format!("redis://PROBE:password@{}", get_default_addr())).await?;
+    let report = probe_client
+        .probe_permissions([
+            cmd("GET").arg("cache:1"),
+            cmd("SET").arg("cache:1").arg("value"),
+            cmd("GET").arg("other:1"),
+            cmd("GET"),
+            cmd("CONFIG").arg("SET").arg("requirepass").arg("secret"),
+        ])
+        .await?;
+    assert_eq!("PROBE", report.user);
+    assert!(!report.is_allowed());
+    assert!(matches!(
+        report.commands[0].permission,
+        CommandPermission::Allowed
+    ));
+    assert_eq!("SET cache:1 value", report.commands[1].command);
+    assert!(matches!(
+        report.commands[1].permission,
+        CommandPermission::Denied(_)
+    ));
+    assert!(matches!(
+        report.commands[2].permission,
+        CommandPermission::Denied(_)
+    ));
+    assert!(matches!(
+        report.commands[3].permission,
+        CommandPermission::Invalid(_)
+    ));
+    assert_eq!("CONFIG *** *** ***", report.commands[4].command);
+    assert!(matches!(
+        report.commands[4].permission,
+        CommandPermission::Denied(_)
+    ));
+    assert_eq!(4, report.denied().count());
+    assert!(!report.to_string().contains("secret"));
+    log::debug!("{report}");
+    probe_client.close().await?;
+
+    // ACL DRYRUN denied
+    client.acl_setuser("PROBE", ["-acl|dryrun"]).await?;
+    let probe_client =
+        Client::connect(format!("redis://PROBE:password@{}", get_default_addr())).await?;
+    let result = probe_client
+        .probe_permissions([cmd("GET").arg("cache:1")])
+        .await;
+    assert!(matches!(result, Err(Error::Client(_))));
+    probe_client.close().await?;
+
+    client.acl_deluser("PROBE").await?;
+    client.close().await?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn extract_keys() -> Result<()> {
+    let mut config = get_default_config()?;
+    config.register_command_keys("MYMOD.MSET", 1, -1, 2);
+    let client = get_test_client_with_config(config).await?;
+
+    let keys = client
+        .extract_keys(
+            &cmd("MSET")
+                .arg("key1")
+                .arg("value1")
+                .arg("key2")
+                .arg("value2"),
+        )
+        .await?;
+    assert_eq!(vec!["key1".to_owned(), "key2".to_owned()], keys);
+
+    let keys = client.extract_keys(&cmd("PING")).await?;
+    assert!(keys.is_empty());
+
+    let keys = client
+        .extract_keys(&cmd("EVAL").arg("return 1").arg(2).arg("key1").arg("key2"))
+        .await?;
+    assert_eq!(vec!["key1".to_owned(), "key2".to_owned()], keys);
+
+    // server side extraction
+    let keys = client
+        .extract_keys(&cmd("SORT").arg("key").arg("STORE").arg("dest"))
+        .await?;
+    assert_eq!(vec!["key".to_owned(), "dest".to_owned()], keys);
+
+    // registered command
+    let keys = client
+        .extract_keys(
+            &cmd("MYMOD.MSET")
+                .arg("key1")
+                .arg("value1")
+                .arg("key2")
+                .arg("value2"),
+        )
+        .await?;
+    assert_eq!(vec!["key1".to_owned(), "key2".to_owned()], keys);
+
+    // the command table is shared by the clones
+    let keys = client.clone().extract_keys(&cmd("GET").arg("key")).await?;
+    assert_eq!(vec!["key".to_owned()], keys);
+
+    let result = client.extract_keys(&cmd("UNKNOWN").arg("key")).await;
+    assert!(matches!(result, Err(Error::Client(_))));
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use crate::{client::ShardSubscriptions, cluster::hash_slot, resp::CommandArgs};
+
+#[test]
+fn shard_subscriptions() {
+    let mut subscriptions = ShardSubscriptions::default();
+    assert!(subscriptions.is_empty());
+
+    subscriptions.subscribe(&CommandArgs::default().arg(["a{1}", "b{1}", "c{2}"]).build());
+    subscriptions.subscribe(&CommandArgs::default().arg("a{1}").build());
+    assert_eq!(3, subscriptions.len());
+
+    let mut slots = vec![hash_slot("{1}"), hash_slot("{2}")];
+    slots.sort_unstable();
+    assert_eq!(slots, subscriptions.slots().collect::<Vec<_>>());
+    assert_eq!(
+        vec![b"a{1}".as_slice(), b"b{1}".as_slice()],
+        subscriptions
+            .shard_channels(hash_slot("{1}"))
+            .collect::<Vec<_>>()
+    );
+
+    subscriptions.unsubscribe(&CommandArgs::default().arg(["c{2}", "unknown"]).build());
+    assert_eq!(
+        vec![hash_slot("{1}")],
+        subscriptions.slots().collect::<Vec<_>>()
+    );
+    assert_eq!(0, subscriptions.shard_channels(hash_slot("{2}")).count());
+
+    subscriptions.unsubscribe(&CommandArgs::default().arg(["a{1}", "b{1}"]).build());
+    assert!(subscriptions.is_empty());
+}
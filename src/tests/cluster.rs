@@ -1,5 +1,6 @@
 use crate::{
-    client::Client,
+    client::{Client, DeleteKeys, DeleteOptions, KeyspaceEvents},
+    cluster,
     commands::{
         CallBuilder, ClusterCommands, ClusterNodeResult,
         ClusterSetSlotSubCommand::{Importing, Migrating, Node},
@@ -7,10 +8,16 @@ use crate::{
         MigrateOptions, ScriptingCommands, ServerCommands, StringCommands,
     },
     network::{ClusterConnection, Version},
+    resp::cmd,
     sleep, spawn,
-    tests::{get_cluster_test_client, get_cluster_test_client_with_command_timeout},
+    tests::{
+        get_cluster_test_client, get_cluster_test_client_with_command_timeout, get_default_host,
+        log_try_init,
+    },
     Error, RedisError, RedisErrorKind, Result,
 };
+#[cfg(feature = "patterns")]
+use crate::{commands::SetCommands, patterns::TaggedQuery};
 use futures_util::try_join;
 use serial_test::serial;
 use std::{collections::HashSet, future::IntoFuture, time::Duration};
@@ -480,3 +487,443 @@ async fn get_loop() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn hash_slot() {
+    assert_eq!(13252, cluster::hash_slot("key0"));
+    assert_eq!(9189, cluster::hash_slot("key1"));
+    assert_eq!(4998, cluster::hash_slot(b"key2"));
+
+    assert_eq!(
+        Some(&b"user:1000"[..]),
+        cluster::extract_hash_tag("{user:1000}.following")
+    );
+    assert_eq!(
+        Some(&b"bar"[..]),
+        cluster::extract_hash_tag("foo{bar}{zap}")
+    );
+    assert_eq!(None, cluster::extract_hash_tag("foo{}{bar}"));
+    assert_eq!(None, cluster::extract_hash_tag("foo{bar"));
+    assert_eq!(None, cluster::extract_hash_tag("foo"));
+
+    assert_eq!(
+        cluster::hash_slot("user:1000"),
+        cluster::hash_slot("{user:1000}.following")
+    );
+    assert_ne!(cluster::hash_slot("foo{}{bar}"), cluster::hash_slot("bar"));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn node_for_key() -> Result<()> {
+    let client = get_cluster_test_client().await?;
+
+    for key in ["key0", "key1", "key2", "{key}1"] {
+        let slot = client.cluster_keyslot(key).await?;
+        assert_eq!(slot, cluster::hash_slot(key));
+
+        let (ip, port) = client.node_for_key(key).await?;
+        let shard_info_list: Vec<ClusterShardResult> = client.cluster_shards().await?;
+        let master_node = shard_info_list
+            .iter()
+            .find(|s| s.slots.iter().any(|s| s.0 <= slot && slot <= s.1))
+            .and_then(|s| s.nodes.iter().find(|n| n.role == "master"))
+            .unwrap();
+        assert_eq!(master_node.ip, ip);
+        assert_eq!(master_node.get_port()?, port);
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "test-util")]
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn slot_migration() -> Result<()> {
+    use crate::{cluster::hash_slot, test_util::SlotMigration};
+
+    let client = get_cluster_test_client().await?;
+    client.flushall(FlushingMode::Sync).await?;
+
+    let slot = hash_slot("{key}1");
+    client
+        .mset([("{key}1", "value1"), ("{key}2", "value2")])
+        .await?;
+
+    // ASK redirection on the migrated key
+    let migration = SlotMigration::start(&client, slot).await?;
+    assert_eq!(slot, migration.slot());
+    let source_id = migration.source_id().to_owned();
+    assert_eq!(1, migration.migrate_keys(1).await?);
+    let values: Vec<String> = client.mget(["{key}1"]).await?;
+    assert_eq!(vec!["value1".to_owned()], values);
+    let values: Vec<String> = client.mget(["{key}2"]).await?;
+    assert_eq!(vec!["value2".to_owned()], values);
+
+    // MOVED redirection
+    migration.finish().await?;
+    let values: Vec<String> = client.mget(["{key}1", "{key}2"]).await?;
+    assert_eq!(vec!["value1".to_owned(), "value2".to_owned()], values);
+
+    // aborted migration back
+    let migration = SlotMigration::start_to(&client, slot, &source_id).await?;
+    assert_eq!(source_id, migration.destination_id());
+    assert_eq!(1, migration.migrate_keys(1).await?);
+    migration.abort().await?;
+    let values: Vec<String> = client.mget(["{key}1", "{key}2"]).await?;
+    assert_eq!(vec!["value1".to_owned(), "value2".to_owned()], values);
+
+    // migrate back
+    SlotMigration::start_to(&client, slot, &source_id)
+        .await?
+        .finish()
+        .await?;
+    let values: Vec<String> = client.mget(["{key}1", "{key}2"]).await?;
+    assert_eq!(vec!["value1".to_owned(), "value2".to_owned()], values);
+
+    client.del(["{key}1", "{key}2"]).await?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn drain_slot() -> Result<()> {
+    let client = get_cluster_test_client().await?;
+    client.flushall(FlushingMode::Sync).await?;
+
+    let slot = cluster::hash_slot("{key}1");
+    client
+        .mset([
+            ("{key}1", "value1"),
+            ("{key}2", "value2"),
+            ("{key}3", "value3"),
+        ])
+        .await?;
+
+    let shard_info_list: Vec<ClusterShardResult> = client.cluster_shards().await?;
+    let src_node = &shard_info_list
+        .iter()
+        .find(|s| s.slots.iter().any(|s| s.0 <= slot && slot <= s.1))
+        .unwrap()
+        .nodes[0];
+    let dst_node = &shard_info_list
+        .iter()
+        .find(|s| s.slots.iter().all(|s| s.0 > slot || slot > s.1))
+        .unwrap()
+        .nodes[0];
+    let src_id = &src_node.id;
+    let dst_id = &dst_node.id;
+    let src_port = src_node.port.unwrap();
+    let dst_port = dst_node.port.unwrap();
+    let src_client = Client::connect((src_node.ip.clone(), src_port)).await?;
+    let dst_client = Client::connect((dst_node.ip.clone(), dst_port)).await?;
+
+    // migrate
+    dst_client
+        .cluster_setslot(
+            slot,
+            Importing {
+                node_id: src_id.clone(),
+            },
+        )
+        .await?;
+    src_client
+        .cluster_setslot(
+            slot,
+            Migrating {
+                node_id: dst_id.clone(),
+            },
+        )
+        .await?;
+
+    let mut progress = Vec::new();
+    let migrated_keys = cluster::drain_slot(&src_client, slot, (&dst_node.ip, dst_port), 2, |p| {
+        progress.push(p)
+    })
+    .await?;
+    assert_eq!(3, migrated_keys);
+    assert_eq!(
+        vec![
+            cluster::SlotDrainProgress {
+                slot,
+                migrated_keys: 2,
+                remaining_keys: 1
+            },
+            cluster::SlotDrainProgress {
+                slot,
+                migrated_keys: 3,
+                remaining_keys: 0
+            }
+        ],
+        progress
+    );
+    assert_eq!(3, dst_client.cluster_countkeysinslot(slot as usize).await?);
+
+    for node_client in [&dst_client, &src_client] {
+        node_client
+            .cluster_setslot(
+                slot,
+                Node {
+                    node_id: dst_id.clone(),
+                },
+            )
+            .await?;
+    }
+
+    let values: Vec<String> = client.mget(["{key}1", "{key}2", "{key}3"]).await?;
+    assert_eq!(3, values.len());
+
+    // migrate back
+    src_client
+        .cluster_setslot(
+            slot,
+            Importing {
+                node_id: dst_id.clone(),
+            },
+        )
+        .await?;
+    dst_client
+        .cluster_setslot(
+            slot,
+            Migrating {
+                node_id: src_id.clone(),
+            },
+        )
+        .await?;
+
+    let migrated_keys =
+        cluster::drain_slot(&dst_client, slot, (&src_node.ip, src_port), 100, |_| ()).await?;
+    assert_eq!(3, migrated_keys);
+
+    for node_client in [&src_client, &dst_client] {
+        node_client
+            .cluster_setslot(
+                slot,
+                Node {
+                    node_id: src_id.clone(),
+                },
+            )
+            .await?;
+    }
+
+    // empty batch
+    assert!(matches!(
+        cluster::drain_slot(&src_client, slot, (&dst_node.ip, dst_port), 0, |_| ()).await,
+        Err(Error::Client(_))
+    ));
+
+    client.del(["{key}1", "{key}2", "{key}3"]).await?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn idle_connection_timeout() -> Result<()> {
+    let host = get_default_host();
+    let client = Client::connect(format!(
+        "redis+cluster://{host}:7000,{host}:7001,{host}:7002?idle_connection_timeout=100"
+    ))
+    .await?;
+
+    // keys spread over all the master nodes
+    let keys = (0..10).map(|i| format!("key{i}")).collect::<Vec<_>>();
+    for key in &keys {
+        client.set(key, "value").await?;
+    }
+
+    // idle connections are closed on the next command and re-opened on demand
+    sleep(Duration::from_millis(200)).await;
+    for key in &keys {
+        let value: String = client.get(key).await?;
+        assert_eq!("value", value);
+    }
+
+    client.del(keys).await?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn ensure_keyspace_notifications() -> Result<()> {
+    let client = get_cluster_test_client().await?;
+    client.config_set(("notify-keyspace-events", "")).await?;
+
+    let report = client
+        .ensure_keyspace_notifications(KeyspaceEvents::KEYEVENT | KeyspaceEvents::EXPIRED)
+        .await?;
+    assert!(report.is_enabled());
+    assert_eq!(3, report.nodes.len());
+    assert!(report.nodes.iter().all(|node| node.updated()));
+    let nodes: HashSet<_> = report.nodes.iter().map(|node| &node.node).collect();
+    assert_eq!(3, nodes.len());
+
+    client.config_set(("notify-keyspace-events", "")).await?;
+    client.close().await?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn delete_keys() -> Result<()> {
+    let client = get_cluster_test_client().await?;
+    client.flushall(FlushingMode::Sync).await?;
+
+    // keys spread over several slots & nodes
+    for i in 0..200 {
+        client.set(format!("del:{i}"), "a").await?;
+        client.set(format!("keep:{i}"), "b").await?;
+    }
+
+    let mut last_progress = None;
+    let deleted = client
+        .delete_keys(
+            DeleteKeys::Pattern("del:*".to_owned()),
+            DeleteOptions {
+                batch: 50,
+                ..Default::default()
+            },
+            |progress| last_progress = Some(progress),
+        )
+        .await?;
+    assert_eq!(200, deleted);
+    assert_eq!(Some(200), last_progress.map(|p| p.deleted_keys));
+
+    let keys: Vec<String> = (0..200).map(|i| format!("keep:{i}")).collect();
+    assert_eq!(200, client.exists(keys.clone()).await?);
+    let deleted = client
+        .delete_keys(
+            DeleteKeys::List(keys.clone()),
+            DeleteOptions::default(),
+            |_| (),
+        )
+        .await?;
+    assert_eq!(200, deleted);
+    assert_eq!(0, client.exists(keys).await?);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn auto_detected_cluster() -> Result<()> {
+    log_try_init();
+    let client = Client::connect(format!(
+        "redis://{}:7000?auto_detect_cluster=true",
+        get_default_host()
+    ))
+    .await?;
+    assert!(client.is_cluster());
+    client.flushall(FlushingMode::Sync).await?;
+
+    // keys spread over several slots & nodes
+    let keys: Vec<String> = (0..100).map(|i| format!("key{i}")).collect();
+    for key in &keys {
+        client.set(key.as_str(), "value").await?;
+    }
+
+    let deleted = client
+        .delete_keys(
+            DeleteKeys::List(keys.clone()),
+            DeleteOptions::default(),
+            |_| (),
+        )
+        .await?;
+    assert_eq!(100, deleted);
+    assert_eq!(0, client.exists(keys).await?);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn ttl_many() -> Result<()> {
+    let client = get_cluster_test_client().await?;
+    client.flushall(FlushingMode::Sync).await?;
+
+    // keys spread over several slots & nodes
+    let keys: Vec<String> = (0..100).map(|i| format!("key{i}")).collect();
+    for key in &keys {
+        client.set(key.as_str(), "value").await?;
+    }
+
+    let results = client
+        .expire_many(
+            keys.iter()
+                .map(|key| (key.as_str(), Duration::from_secs(10))),
+        )
+        .await?;
+    assert!(results.iter().all(|result| *result));
+
+    let expiries = client.ttl_many(keys).await?;
+    assert_eq!(100, expiries.len());
+    assert!(expiries
+        .iter()
+        .all(|expiry| expiry.ttl_duration().is_some()));
+
+    Ok(())
+}
+
+#[cfg(feature = "patterns")]
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn tagged_query() -> Result<()> {
+    let client = get_cluster_test_client().await?;
+    client.flushall(FlushingMode::Sync).await?;
+
+    client
+        .sadd("{products}:color:red", ["p1", "p2", "p3"])
+        .await?;
+    client
+        .sadd("{products}:size:xl", ["p2", "p3", "p4"])
+        .await?;
+
+    let query = TaggedQuery::new(
+        client.clone(),
+        ["{products}:color:red", "{products}:size:xl"],
+    );
+    assert_eq!(2, query.count(0).await?);
+    let result = query.execute().await?;
+    assert_eq!(2, result.len);
+    assert!(result.key.starts_with("{products}:"));
+    let (cursor, mut page) = query.page::<String>(&result.key, 0).await?;
+    page.sort();
+    assert_eq!((0, vec!["p2".to_owned(), "p3".to_owned()]), (cursor, page));
+
+    // tags on different slots
+    let query = TaggedQuery::new(client.clone(), ["{products}:color:red", "size:xl"]);
+    assert!(matches!(query.count(0).await, Err(Error::Client(_))));
+    let query = TaggedQuery::new(client, ["color:red"]);
+    assert!(matches!(query.execute().await, Err(Error::Client(_))));
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn send_without_reply() -> Result<()> {
+    let client = get_cluster_test_client().await?;
+
+    let result = client.send_without_reply(cmd("SET").arg("key").arg("value"), None);
+    assert!(matches!(result, Err(Error::Client(_))));
+
+    let result = client
+        .send_batch_without_replies(vec![cmd("SET").arg("key").arg("value")], None)
+        .await;
+    assert!(matches!(result, Err(Error::Client(_))));
+
+    Ok(())
+}
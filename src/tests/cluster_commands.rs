@@ -4,6 +4,12 @@ use crate::{
     tests::log_try_init,
     Result,
 };
+#[cfg(feature = "mock")]
+use crate::{
+    client::MockServerBehavior,
+    commands::{ClusterFailoverOption, ClusterSetSlotSubCommand},
+    resp::Command,
+};
 use log::debug;
 use serial_test::serial;
 
@@ -46,3 +52,93 @@ async fn cluster_slots() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(feature = "mock")]
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn cluster_admin_commands_args() -> Result<()> {
+    let client = Client::connect_mock(MockServerBehavior::new()).await?;
+
+    let args = |command: Command| -> Vec<String> {
+        command
+            .args
+            .into_iter()
+            .map(|arg| String::from_utf8_lossy(arg).into_owned())
+            .collect()
+    };
+
+    assert_eq!(
+        vec!["FAILOVER"],
+        args(
+            client
+                .cluster_failover(ClusterFailoverOption::Default)
+                .command
+        )
+    );
+    assert_eq!(
+        vec!["FAILOVER", "FORCE"],
+        args(
+            client
+                .cluster_failover(ClusterFailoverOption::Force)
+                .command
+        )
+    );
+    assert_eq!(
+        vec!["FAILOVER", "TAKEOVER"],
+        args(
+            client
+                .cluster_failover(ClusterFailoverOption::Takeover)
+                .command
+        )
+    );
+    assert_eq!(
+        vec!["SETSLOT", "12", "IMPORTING", "abc"],
+        args(
+            client
+                .cluster_setslot(12, ClusterSetSlotSubCommand::importing("abc"))
+                .command
+        )
+    );
+    assert_eq!(
+        vec!["SETSLOT", "12", "MIGRATING", "abc"],
+        args(
+            client
+                .cluster_setslot(12, ClusterSetSlotSubCommand::migrating("abc"))
+                .command
+        )
+    );
+    assert_eq!(
+        vec!["SETSLOT", "12", "NODE", "abc"],
+        args(
+            client
+                .cluster_setslot(12, ClusterSetSlotSubCommand::node("abc"))
+                .command
+        )
+    );
+    assert_eq!(
+        vec!["SETSLOT", "12", "STABLE"],
+        args(
+            client
+                .cluster_setslot(12, ClusterSetSlotSubCommand::Stable)
+                .command
+        )
+    );
+    assert_eq!(
+        vec!["FORGET", "abc"],
+        args(client.cluster_forget("abc").command)
+    );
+    assert_eq!(
+        vec!["MEET", "127.0.0.1", "7000"],
+        args(client.cluster_meet("127.0.0.1", 7000, None).command)
+    );
+    assert_eq!(
+        vec!["MEET", "127.0.0.1", "7000", "17001"],
+        args(client.cluster_meet("127.0.0.1", 7000, Some(17001)).command)
+    );
+    assert_eq!(
+        vec!["REPLICATE", "abc"],
+        args(client.cluster_replicate("abc").command)
+    );
+
+    Ok(())
+}
@@ -1,11 +1,38 @@
 use crate::{
     commands::{GenericCommands, HashCommands, SetCommands},
+    resp::{CommandArg, CommandArgs},
     tests::get_test_client,
     Result,
 };
 use serial_test::serial;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
+#[test]
+fn inline_args() {
+    let large = "x".repeat(64);
+    let args = CommandArgs::default()
+        .arg("key")
+        .arg(12)
+        .arg(large.as_str())
+        .build();
+
+    assert_eq!(3, args.len());
+    assert!(args[0].is_inline());
+    assert!(args[1].is_inline());
+    assert!(!args[2].is_inline());
+    assert_eq!(b"key", args[0].as_slice());
+    assert_eq!(b"12", args[1].as_slice());
+    assert_eq!(large.as_bytes(), args[2].as_slice());
+    assert_eq!(
+        vec![&b"key"[..], &b"12"[..], large.as_bytes()],
+        args.into_iter().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        std::mem::size_of::<Vec<u8>>(),
+        std::mem::size_of::<CommandArg>()
+    );
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
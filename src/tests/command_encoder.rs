@@ -0,0 +1,90 @@
+use crate::resp::{
+    cmd, io_slices, CommandEncoder, LargeArg, LargeArgs, StreamedArg, VECTORED_ARG_MIN_SIZE,
+};
+use bytes::{Bytes, BytesMut};
+use tokio_util::codec::Encoder;
+
+#[test]
+fn encode_vectored() {
+    let large = vec![b'x'; VECTORED_ARG_MIN_SIZE];
+    let commands = [
+        cmd("SET").arg("key").arg(large.clone()),
+        cmd("GET").arg("key"),
+        cmd("RPUSH").arg("list").arg(large.clone()).arg(large),
+    ];
+
+    let mut expected = BytesMut::new();
+    let mut buf = BytesMut::new();
+    let mut large_args = LargeArgs::new();
+    for command in &commands {
+        CommandEncoder.encode(command, &mut expected).unwrap();
+        CommandEncoder.encode_vectored(command, &mut buf, &mut large_args);
+    }
+
+    assert_eq!(3, large_args.len());
+    assert!(buf.len() < 4 * VECTORED_ARG_MIN_SIZE);
+
+    let slices = io_slices(&buf, &large_args);
+    assert_eq!(7, slices.len());
+    assert_eq!(
+        expected.as_ref(),
+        slices
+            .iter()
+            .flat_map(|s| s.iter().copied())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn encode_streamed() {
+    let command = cmd("SET").arg("key").streamed_arg(StreamedArg::from_stream(
+        5,
+        futures_util::stream::iter([Ok(Bytes::from_static(b"value"))]),
+    ));
+
+    let mut buf = BytesMut::new();
+    let mut large_args = LargeArgs::new();
+    CommandEncoder.encode_vectored(&command, &mut buf, &mut large_args);
+
+    assert_eq!(1, large_args.len());
+    let (position, arg) = &large_args[0];
+    assert!(matches!(arg, LargeArg::Streamed(arg) if arg.len() == 5));
+    assert_eq!(b"*3\r\n$3\r\nSET\r\n$3\r\nkey\r\n$5\r\n", &buf[..*position]);
+    assert_eq!(b"\r\n", &buf[*position..]);
+}
+
+#[test]
+fn encode_inline() {
+    let command = cmd("SET")
+        .arg("key")
+        .arg(b"a \"quoted\" value\\\r\n\x01\xff".as_slice())
+        .arg("")
+        .inline(true);
+
+    let mut buf = BytesMut::new();
+    CommandEncoder.encode(&command, &mut buf).unwrap();
+    assert_eq!(
+        br#"SET key "a \"quoted\" value\\\r\n\x01\xff" """#.as_slice(),
+        &buf[..buf.len() - 2]
+    );
+    assert_eq!(b"\r\n", &buf[buf.len() - 2..]);
+
+    let mut vectored_buf = BytesMut::new();
+    let mut large_args = LargeArgs::new();
+    CommandEncoder.encode_vectored(&command, &mut vectored_buf, &mut large_args);
+    assert!(large_args.is_empty());
+    assert_eq!(buf, vectored_buf);
+
+    // streamed arguments are not sent inline
+    let command = cmd("SET")
+        .arg("key")
+        .streamed_arg(StreamedArg::from_stream(
+            5,
+            futures_util::stream::iter([Ok(Bytes::from_static(b"value"))]),
+        ))
+        .inline(true);
+    let mut buf = BytesMut::new();
+    let mut large_args = LargeArgs::new();
+    CommandEncoder.encode_vectored(&command, &mut buf, &mut large_args);
+    assert!(buf.starts_with(b"*3\r\n"));
+}
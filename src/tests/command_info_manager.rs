@@ -1,14 +1,17 @@
 use crate::{
-    client::IntoConfig,
+    client::{CommandInfoCache, Config, IntoConfig},
     commands::{
-        GenericCommands, MigrateOptions, SortOptions, SortOrder, SortedSetCommands, StreamCommands,
-        StringCommands, XReadGroupOptions, XReadOptions, ZAggregate,
+        BeginSearch, CommandInfo, FindKeys, GenericCommands, KeySpecification, MigrateOptions,
+        SortOptions, SortOrder, SortedSetCommands, StreamCommands, StringCommands,
+        XReadGroupOptions, XReadOptions, ZAggregate,
     },
     network::StandaloneConnection,
+    resp::cmd,
     tests::{get_default_addr, get_default_host, get_default_port, get_test_client},
-    CommandInfoManager, Result,
+    CommandInfoManager, Error, Result,
 };
 use serial_test::serial;
+use std::collections::HashMap;
 
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
@@ -21,7 +24,8 @@ async fn extract_keys() -> Result<()> {
         &get_default_addr().into_config()?,
     )
     .await?;
-    let command_info_manager = CommandInfoManager::initialize(&mut connection).await?;
+    let command_info_manager =
+        CommandInfoManager::initialize(&mut connection, None, &HashMap::new()).await?;
 
     // SET
     let keys = command_info_manager
@@ -135,3 +139,244 @@ async fn extract_keys() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn command_info_cache() -> Result<()> {
+    let client = get_test_client().await?;
+    let cache = client.command_info_cache().await?;
+    assert_eq!(client.server_version(), cache.version());
+
+    let cache = CommandInfoCache::from_bytes(cache.as_bytes().to_vec());
+    let mut connection = StandaloneConnection::connect(
+        &get_default_host(),
+        get_default_port(),
+        &get_default_addr().into_config()?,
+    )
+    .await?;
+    let command_info_manager =
+        CommandInfoManager::initialize(&mut connection, Some(&cache), &HashMap::new()).await?;
+
+    let keys = command_info_manager
+        .extract_keys(client.set("key", "value").command(), &mut connection)
+        .await?;
+    assert_eq!(1, keys.len());
+    assert_eq!("key", keys[0]);
+
+    // invalid cache, retrieved from the server
+    let cache = CommandInfoCache::from_bytes(format!("{}\ngarbage", cache.version().unwrap()));
+    let command_info_manager =
+        CommandInfoManager::initialize(&mut connection, Some(&cache), &HashMap::new()).await?;
+    assert!(command_info_manager
+        .get_command_info_by_name("SET")
+        .is_some());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn register_command_keys() -> Result<()> {
+    let mut connection = StandaloneConnection::connect(
+        &get_default_host(),
+        get_default_port(),
+        &get_default_addr().into_config()?,
+    )
+    .await?;
+
+    let mut config = Config::default();
+    config
+        .register_command_keys("mymod.do", 1, 2, 1)
+        .register_command_keys("MYMOD.MSET", 1, -1, 2)
+        // override of a command known to the server
+        .register_command_keys("GETRANGE", 0, 0, 0);
+    let command_info_manager =
+        CommandInfoManager::initialize(&mut connection, None, &config.command_keys).await?;
+
+    let keys = command_info_manager
+        .extract_keys(
+            &cmd("MYMOD.DO").arg("key1").arg("key2").arg("option"),
+            &mut connection,
+        )
+        .await?;
+    assert_eq!(vec!["key1", "key2"], keys.to_vec());
+
+    let keys = command_info_manager
+        .extract_keys(
+            &cmd("MYMOD.MSET")
+                .arg("key1")
+                .arg("value1")
+                .arg("key2")
+                .arg("value2"),
+            &mut connection,
+        )
+        .await?;
+    assert_eq!(vec!["key1", "key2"], keys.to_vec());
+
+    let keys = command_info_manager
+        .extract_keys(&cmd("GETRANGE").arg("key").arg(0).arg(1), &mut connection)
+        .await?;
+    assert!(keys.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn register_invalid_command_keys() -> Result<()> {
+    for (first_key, last_key, step) in [(1, 2, 0), (1, 0, 1), (2, 1, 1)] {
+        let result = Config::builder()
+            .register_command_keys("MYMOD.DO", first_key, last_key, step)
+            .build();
+        assert!(matches!(result, Err(Error::Config(_))));
+
+        let mut config = Config::default();
+        config.register_command_keys("MYMOD.DO", first_key, last_key, step);
+        let result = CommandInfoManager::new(
+            Vec::new(),
+            crate::Version::new(7, 2, 0),
+            &config.command_keys,
+        );
+        assert!(matches!(result, Err(Error::Config(_))));
+    }
+
+    // command without keys
+    Config::builder()
+        .register_command_keys("MYMOD.DO", 0, 0, 0)
+        .build()?;
+
+    // first key out of the arguments
+    let mut config = Config::default();
+    config.register_command_keys("MYMOD.DO", 2, 2, 1);
+    let command_info_manager = CommandInfoManager::new(
+        Vec::new(),
+        crate::Version::new(7, 2, 0),
+        &config.command_keys,
+    )?;
+    let result = command_info_manager.extract_keys_locally(&cmd("MYMOD.DO"));
+    assert!(matches!(result, Err(Error::Client(_))));
+
+    Ok(())
+}
+
+/// Command info with a single key specification, or only the legacy key positions
+fn command_info(
+    name: &str,
+    (first_key, last_key, step): (usize, isize, usize),
+    key_specification: Option<(BeginSearch, FindKeys)>,
+) -> CommandInfo {
+    CommandInfo {
+        name: name.to_owned(),
+        arity: -1,
+        flags: Vec::new(),
+        first_key,
+        last_key,
+        step,
+        acl_categories: Vec::new(),
+        command_tips: Vec::new(),
+        key_specifications: key_specification
+            .into_iter()
+            .map(|(begin_search, find_keys)| KeySpecification {
+                begin_search,
+                find_keys,
+                flags: Vec::new(),
+                notes: String::new(),
+            })
+            .collect(),
+        sub_commands: Vec::new(),
+    }
+}
+
+#[test]
+fn extract_keys_out_of_arguments() -> Result<()> {
+    let command_infos = vec![
+        command_info(
+            "GET",
+            (1, 1, 1),
+            Some((
+                BeginSearch::Index(1),
+                FindKeys::Range {
+                    last_key: 0,
+                    key_step: 1,
+                    limit: 0,
+                },
+            )),
+        ),
+        command_info(
+            "EVAL",
+            (0, 0, 0),
+            Some((
+                BeginSearch::Index(2),
+                FindKeys::KeyNum {
+                    key_num_idx: 0,
+                    first_key: 1,
+                    key_step: 1,
+                },
+            )),
+        ),
+        command_info("MSET", (1, -1, 2), None),
+    ];
+
+    let command_info_manager = CommandInfoManager::new(
+        command_infos.clone(),
+        crate::Version::new(7, 2, 0),
+        &HashMap::new(),
+    )?;
+
+    let result = command_info_manager.extract_keys_locally(&cmd("GET"));
+    assert!(matches!(result, Err(Error::Client(_))));
+
+    // numkeys greater than the number of keys
+    let result =
+        command_info_manager.extract_keys_locally(&cmd("EVAL").arg("script").arg(3).arg("key1"));
+    assert!(matches!(result, Err(Error::Client(_))));
+    let result = command_info_manager.extract_keys_locally(&cmd("EVAL"));
+    assert!(matches!(result, Err(Error::Client(_))));
+
+    let keys = command_info_manager.extract_keys_locally(
+        &cmd("EVAL")
+            .arg("script")
+            .arg(2)
+            .arg("key1")
+            .arg("key2")
+            .arg("arg"),
+    )?;
+    assert_eq!(
+        Some(vec!["key1".to_owned(), "key2".to_owned()]),
+        keys.map(|keys| keys.to_vec())
+    );
+    let keys = command_info_manager.extract_keys_locally(&cmd("EVAL").arg("script").arg(0))?;
+    assert_eq!(Some(0), keys.map(|keys| keys.len()));
+
+    // legacy key positions (Redis 6)
+    let command_info_manager =
+        CommandInfoManager::new(command_infos, crate::Version::new(6, 2, 0), &HashMap::new())?;
+
+    let result = command_info_manager.extract_keys_locally(&cmd("GET"));
+    assert!(matches!(result, Err(Error::Client(_))));
+
+    let keys = command_info_manager.extract_keys_locally(
+        &cmd("MSET")
+            .arg("key1")
+            .arg("value1")
+            .arg("key2")
+            .arg("value2"),
+    )?;
+    assert_eq!(
+        Some(vec!["key1".to_owned(), "key2".to_owned()]),
+        keys.map(|keys| keys.to_vec())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn command_info_cache_bytes() {
+    let cache = CommandInfoCache::from_bytes(&b"7.2.4\n*0\r\n"[..]);
+    assert_eq!(Some(crate::Version::new(7, 2, 4)), cache.version());
+    assert_eq!(b"7.2.4\n*0\r\n", cache.as_bytes());
+
+    let cache = CommandInfoCache::from_bytes(&b"garbage"[..]);
+    assert_eq!(None, cache.version());
+}
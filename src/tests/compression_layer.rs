@@ -0,0 +1,169 @@
+use crate::{
+    client::{
+        BatchPreparedCommand, Client, CommandLayer, CompressionCodec, CompressionLayer, IntoConfig,
+    },
+    commands::{FlushingMode, HashCommands, ListCommands, ServerCommands, StringCommands},
+    resp::{cmd, BulkString, Command, RespBuf},
+    tests::get_default_addr,
+    Result,
+};
+use serial_test::serial;
+use std::collections::HashMap;
+
+fn codecs() -> Vec<CompressionCodec> {
+    vec![
+        #[cfg(feature = "compression-gzip")]
+        CompressionCodec::Gzip,
+        #[cfg(feature = "compression-zstd")]
+        CompressionCodec::Zstd,
+        #[cfg(feature = "compression-lz4")]
+        CompressionCodec::Lz4,
+    ]
+}
+
+fn args(command: &Command) -> Vec<Vec<u8>> {
+    command.args.into_iter().map(|arg| arg.to_vec()).collect()
+}
+
+/// Reply of the server, as a bulk string
+fn bulk_string(value: &[u8]) -> Vec<u8> {
+    [format!("${}\r\n", value.len()).as_bytes(), value, b"\r\n"].concat()
+}
+
+#[test]
+fn compression_layer_round_trip() -> Result<()> {
+    let large = "value".repeat(100);
+
+    for codec in codecs() {
+        let layer = CompressionLayer::new()
+            .rule("doc:", codec, 100)
+            .exclude("doc:raw:");
+
+        // large value compressed
+        let mut command = cmd("SET").arg("doc:1").arg(large.as_str());
+        layer.on_command(&mut command)?;
+        let compressed = args(&command)[1].clone();
+        assert!(compressed.starts_with(b"\xFFRZ"));
+        assert!(compressed.len() < large.len());
+
+        // small value, excluded prefix & key without rule are not compressed
+        for (key, value) in [
+            ("doc:2", "small"),
+            ("doc:raw:1", large.as_str()),
+            ("other", large.as_str()),
+        ] {
+            let mut command = cmd("SET").arg(key).arg(value);
+            layer.on_command(&mut command)?;
+            assert_eq!(value.as_bytes(), args(&command)[1].as_slice());
+        }
+
+        // values of multi-value commands
+        let mut command = cmd("MSET")
+            .arg("doc:1")
+            .arg(large.as_str())
+            .arg("other")
+            .arg(large.as_str());
+        layer.on_command(&mut command)?;
+        let mset_args = args(&command);
+        assert_eq!(compressed, mset_args[1]);
+        assert_eq!(large.as_bytes(), mset_args[3].as_slice());
+
+        let mut command = cmd("HSET").arg("doc:1").arg("field").arg(large.as_str());
+        layer.on_command(&mut command)?;
+        assert_eq!(compressed, args(&command)[2]);
+
+        // decompression of nested values
+        let reply = [
+            b"*3\r\n".as_slice(),
+            &bulk_string(&compressed),
+            b"%1\r\n+field\r\n",
+            &bulk_string(&compressed),
+            b"_\r\n",
+        ]
+        .concat();
+        let reply = layer.on_reply(&cmd("MGET"), RespBuf::new(reply.into()))?;
+        let (value, map, nil) = reply.to::<(String, HashMap<String, String>, Option<String>)>()?;
+        assert_eq!(large, value);
+        assert_eq!(large, map["field"]);
+        assert_eq!(None, nil);
+
+        // replies without compressed value are untouched
+        let reply = RespBuf::new(bulk_string(b"value").into());
+        assert_eq!(
+            bulk_string(b"value"),
+            layer.on_reply(&cmd("GET"), reply)?.as_bytes()
+        );
+    }
+
+    // values starting with the header which cannot be decompressed are returned as is
+    for value in [b"\xFFRZ\x01garbage".as_slice(), b"\xFFRZ\x00unknown codec"] {
+        let reply = RespBuf::new(bulk_string(value).into());
+        assert_eq!(
+            bulk_string(value),
+            CompressionLayer::new()
+                .on_reply(&cmd("GET"), reply)?
+                .as_bytes()
+        );
+    }
+
+    // decompressed values are bounded
+    for codec in codecs() {
+        let mut command = cmd("SET").arg("doc:1").arg(large.as_str());
+        CompressionLayer::new()
+            .rule("doc:", codec, 100)
+            .on_command(&mut command)?;
+        let reply = RespBuf::new(bulk_string(&args(&command)[1]).into());
+        assert!(CompressionLayer::new()
+            .max_decompressed_size(large.len() - 1)
+            .on_reply(&cmd("GET"), reply)
+            .is_err());
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn compression_layer() -> Result<()> {
+    let codec = codecs()[0];
+    let mut config = get_default_addr().into_config()?;
+    config.layer(CompressionLayer::new().rule("doc:", codec, 100));
+    let client = Client::connect(config).await?;
+    let raw_client = Client::connect(get_default_addr()).await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    let large = "value".repeat(100);
+    client.set("doc:1", large.as_str()).await?;
+    client.hset("doc:2", ("field", large.as_str())).await?;
+    client.rpush("doc:3", [large.as_str(), "small"]).await?;
+
+    let value: String = client.get("doc:1").await?;
+    assert_eq!(large, value);
+    let values: HashMap<String, String> = client.hgetall("doc:2").await?;
+    assert_eq!(large, values["field"]);
+    let values: Vec<String> = client.lrange("doc:3", 0, -1).await?;
+    assert_eq!(vec![large.clone(), "small".to_owned()], values);
+
+    // stored compressed
+    let stored: BulkString = raw_client.get("doc:1").await?;
+    assert!(stored.starts_with(b"\xFFRZ"));
+    assert!(stored.len() < large.len());
+
+    // pipelines & transactions
+    let mut pipeline = client.create_pipeline();
+    pipeline.get::<_, String>("doc:1").queue();
+    pipeline.get::<_, String>("doc:1").queue();
+    let (value1, value2): (String, String) = pipeline.execute().await?;
+    assert_eq!(
+        (large.as_str(), large.as_str()),
+        (value1.as_str(), value2.as_str())
+    );
+
+    let mut transaction = client.create_transaction();
+    transaction.get::<_, String>("doc:1").queue();
+    let value: String = transaction.execute().await?;
+    assert_eq!(large, value);
+
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use crate::{
     client::{BatchPreparedCommand, Client, ClientPreparedCommand},
     commands::{
-        ClientCachingMode, ClientInfoAttribute, ClientKillOptions, ClientListOptions,
+        ClientCachingMode, ClientInfo, ClientInfoAttribute, ClientKillOptions, ClientListOptions,
         ClientPauseMode, ClientReplyMode, ClientTrackingOptions, ClientTrackingStatus,
         ClientUnblockMode, ConnectionCommands, FlushingMode, GenericCommands, HelloOptions,
         PingOptions, ServerCommands, StringCommands,
@@ -86,6 +86,7 @@ async fn client_id() -> Result<()> {
 
     let id = client.client_id().await?;
     assert!(id > 0);
+    assert_eq!(Some(id), client.connection_id());
 
     Ok(())
 }
@@ -99,6 +100,47 @@ async fn client_info() -> Result<()> {
     let client_info = client.client_info().await?;
     log::debug!("client_info: {client_info:?}");
     assert!(client_info.id != 0);
+    assert_eq!(Some(client_info.id), client.connection_id());
+    assert_eq!(3, client_info.resp);
+    assert_eq!("rustis", client_info.lib_name);
+    assert_eq!(env!("CARGO_PKG_VERSION"), client_info.lib_ver);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn client_info_connection_metadata() -> Result<()> {
+    let client = Client::connect(
+        "redis://127.0.0.1?connection_name=api&connection_metadata=team:payments,env:prod&lib_name=myframework",
+    )
+    .await?;
+
+    let client_info = client.client_info().await?;
+    assert_eq!("api(team=payments,env=prod)", client_info.name);
+    assert_eq!("myframework(team=payments,env=prod)", client_info.lib_name);
+
+    Ok(())
+}
+
+#[test]
+fn client_info_from_line() -> Result<()> {
+    let client_info = ClientInfo::from_line(
+        "id=3 addr=127.0.0.1:53690 laddr=127.0.0.1:6379 fd=8 name= age=0 idle=0 flags=N db=0 sub=0 psub=0 ssub=0 multi=-1 qbuf=26 qbuf-free=20448 argv-mem=10 multi-mem=0 rbs=1024 rbp=0 obl=0 oll=0 omem=0 tot-mem=22298 events=r cmd=client|info user=default redir=-1 resp=3 lib-name=rustis lib-ver=0.13.3\n",
+    )?;
+
+    assert_eq!(3, client_info.id);
+    assert_eq!("127.0.0.1:53690", client_info.addr);
+    assert_eq!("127.0.0.1:6379", client_info.laddr);
+    assert_eq!("N", client_info.flags);
+    assert_eq!(3, client_info.resp);
+    assert_eq!("rustis", client_info.lib_name);
+    assert_eq!("0.13.3", client_info.lib_ver);
+    assert_eq!(
+        Some(&"1024".to_owned()),
+        client_info.additional_arguments.get("rbs")
+    );
 
     Ok(())
 }
@@ -0,0 +1,77 @@
+use crate::{
+    network::{percentile, NodeStatsProbe, StatsRegistry},
+    resp::cmd,
+};
+use std::time::Duration;
+
+#[test]
+fn percentiles() {
+    assert_eq!(None, percentile(&[], 50));
+    assert_eq!(Some(Duration::from_micros(7)), percentile(&[7], 99));
+
+    let latencies = (1..=100).collect::<Vec<u64>>();
+    assert_eq!(Some(Duration::from_micros(50)), percentile(&latencies, 50));
+    assert_eq!(Some(Duration::from_micros(99)), percentile(&latencies, 99));
+}
+
+#[test]
+fn probe() {
+    let registry = StatsRegistry::new();
+    let mut probe = NodeStatsProbe::new(registry.node("127.0.0.1:6379"));
+
+    probe.record_write(
+        &[
+            cmd("SET").arg("key").arg("value"),
+            cmd("GET").arg("key"),
+            cmd("GET").arg("key"),
+        ],
+        42,
+    );
+    probe.record_reply(b"+OK\r\n", false);
+    probe.record_reply(b">3\r\n$7\r\nmessage\r\n$1\r\nc\r\n$1\r\nm\r\n", true);
+    probe.record_reply(b"-MOVED 3999 127.0.0.1:6381\r\n", false);
+    probe.record_reply(b"-ERR error\r\n", false);
+    probe.record_reconnect();
+
+    let stats = registry.snapshot();
+    assert_eq!(1, stats.nodes.len());
+    let node = &stats.nodes[0];
+    assert_eq!("127.0.0.1:6379", node.address);
+    assert_eq!(3, node.commands_sent);
+    assert_eq!(42, node.bytes_out);
+    assert_eq!(1, node.retries);
+    assert_eq!(1, node.errors);
+    assert_eq!(1, node.reconnects);
+    assert!(node.latency_p50.is_some());
+    assert!(node.latency_p99.is_some());
+    assert!(node.bytes_in > 0);
+}
+
+#[test]
+fn probe_commands_without_reply() {
+    let registry = StatsRegistry::new();
+    let mut probe = NodeStatsProbe::new(registry.node("127.0.0.1:6379"));
+
+    probe.record_write(
+        &[
+            cmd("SUBSCRIBE").arg("channel1").arg("channel2"),
+            cmd("CLIENT").arg("REPLY").arg("SKIP"),
+            cmd("GET").arg("key"),
+            cmd("CLIENT").arg("REPLY").arg("OFF"),
+            cmd("GET").arg("key"),
+            cmd("CLIENT").arg("REPLY").arg("ON"),
+            cmd("GET").arg("key"),
+        ],
+        42,
+    );
+    assert_eq!(2, probe.in_flight.len());
+
+    probe.record_reply(b">3\r\n$9\r\nsubscribe\r\n$8\r\nchannel1\r\n:1\r\n", true);
+    probe.record_reply(b">3\r\n$9\r\nsubscribe\r\n$8\r\nchannel2\r\n:2\r\n", true);
+    probe.record_reply(b"+OK\r\n", false);
+    probe.record_reply(b"$5\r\nvalue\r\n", false);
+    assert!(probe.in_flight.is_empty());
+
+    let stats = registry.snapshot();
+    assert_eq!(7, stats.nodes[0].commands_sent);
+}
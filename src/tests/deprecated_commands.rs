@@ -0,0 +1,43 @@
+use crate::{commands::CommandDoc, network::DeprecatedCommands, resp::cmd};
+use std::collections::HashMap;
+
+#[test]
+fn deprecated_commands() {
+    let docs = HashMap::from([
+        (
+            "hmset".to_owned(),
+            CommandDoc {
+                deprecated_since: "4.0.0".to_owned(),
+                replaced_by: "`HSET` with multiple field-value pairs".to_owned(),
+                ..Default::default()
+            },
+        ),
+        ("hset".to_owned(), CommandDoc::default()),
+        (
+            "cluster".to_owned(),
+            CommandDoc {
+                subcommands: HashMap::from([
+                    (
+                        "cluster|slots".to_owned(),
+                        CommandDoc {
+                            deprecated_since: "7.0.0".to_owned(),
+                            replaced_by: "`CLUSTER SHARDS`".to_owned(),
+                            ..Default::default()
+                        },
+                    ),
+                    ("cluster|shards".to_owned(), CommandDoc::default()),
+                ]),
+                ..Default::default()
+            },
+        ),
+    ]);
+
+    let mut deprecated_commands = DeprecatedCommands::from_docs(docs);
+
+    assert!(!deprecated_commands.check(&cmd("HSET").arg("key").arg("field").arg("value"), "test"));
+    assert!(deprecated_commands.check(&cmd("HMSET").arg("key").arg("field").arg("value"), "test"));
+    // warn only once
+    assert!(!deprecated_commands.check(&cmd("hmset").arg("key").arg("field").arg("value"), "test"));
+    assert!(!deprecated_commands.check(&cmd("CLUSTER").arg("SHARDS"), "test"));
+    assert!(deprecated_commands.check(&cmd("CLUSTER").arg("SLOTS"), "test"));
+}
@@ -185,6 +185,7 @@ async fn reconnection() -> Result<()> {
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
+#[allow(deprecated)]
 async fn kill_on_write() -> Result<()> {
     use crate::client::ReconnectionConfig;
 
@@ -1,14 +1,18 @@
 use crate::{
+    client::{DeleteKeys, DeleteOptions},
     commands::{
-        ConnectionCommands, ExpireOption, FlushingMode, GenericCommands, ListCommands,
+        ConnectionCommands, ExpireOption, Expiry, FlushingMode, GenericCommands, ListCommands,
         RestoreOptions, ScanOptions, ServerCommands, SetCommands, SortOptions, StringCommands,
     },
-    resp::Value,
+    resp::{cmd, StreamedArg, Value},
     tests::get_test_client,
     Result,
 };
 use serial_test::serial;
-use std::{collections::HashSet, time::SystemTime};
+use std::{
+    collections::HashSet,
+    time::{Duration, SystemTime},
+};
 
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
@@ -221,6 +225,158 @@ async fn expiretime() -> Result<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn expiry() -> Result<()> {
+    let client = get_test_client().await?;
+
+    // cleanup
+    client.del("key").await?;
+
+    assert_eq!(Expiry::None, client.expiry("key").await?);
+    assert_eq!(None, client.ttl_duration("key").await?);
+
+    client.set("key", "value").await?;
+    assert_eq!(Expiry::NoTtl, client.expiry("key").await?);
+    assert_eq!(None, client.ttl_duration("key").await?);
+
+    assert!(
+        client
+            .pexpireat("key", 33177117420000, ExpireOption::default())
+            .await?
+    );
+    assert_eq!(
+        Expiry::At(SystemTime::UNIX_EPOCH + Duration::from_millis(33177117420000)),
+        client.expiry("key").await?
+    );
+
+    client.expire("key", 10, ExpireOption::default()).await?;
+    let ttl = client.ttl_duration("key").await?.unwrap();
+    assert!(ttl > Duration::from_secs(9) && ttl <= Duration::from_secs(10));
+    let ttl = client.expiry("key").await?.ttl_duration().unwrap();
+    assert!(ttl > Duration::from_secs(9) && ttl <= Duration::from_secs(10));
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn ttl_many() -> Result<()> {
+    let client = get_test_client().await?;
+
+    // cleanup
+    client.del(["key1", "key2", "key3"]).await?;
+
+    client
+        .mset([("key1", "value1"), ("key2", "value2")])
+        .await?;
+
+    let results = client
+        .expire_many([
+            ("key1", Duration::from_secs(10)),
+            ("key3", Duration::from_secs(10)),
+        ])
+        .await?;
+    assert_eq!(vec![true, false], results);
+
+    let expiries = client.ttl_many(["key1", "key2", "key3"]).await?;
+    assert_eq!(3, expiries.len());
+    let ttl = expiries[0].ttl_duration().unwrap();
+    assert!(ttl > Duration::from_secs(9) && ttl <= Duration::from_secs(10));
+    assert_eq!(Expiry::NoTtl, expiries[1]);
+    assert_eq!(Expiry::None, expiries[2]);
+
+    let expiries = client.ttl_many("key2").await?;
+    assert_eq!(vec![Expiry::NoTtl], expiries);
+    let expiries = client.ttl_many(Vec::<String>::new()).await?;
+    assert!(expiries.is_empty());
+
+    let results = client.persist_many(["key1", "key2"]).await?;
+    assert_eq!(vec![true, false], results);
+    assert_eq!(Expiry::NoTtl, client.expiry("key1").await?);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn delete_keys() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    let items: Vec<(String, String)> = (0..250)
+        .flat_map(|i| {
+            [
+                (format!("del:{i}"), "a".to_owned()),
+                (format!("keep:{i}"), "b".to_owned()),
+            ]
+        })
+        .collect();
+    client.mset(items).await?;
+
+    let mut reports = Vec::new();
+    let deleted = client
+        .delete_keys(
+            DeleteKeys::Pattern("del:*".to_owned()),
+            DeleteOptions {
+                batch: 100,
+                ..Default::default()
+            },
+            |progress| reports.push(progress),
+        )
+        .await?;
+    assert_eq!(250, deleted);
+    assert!(reports.len() > 1);
+    let last = reports.last().unwrap();
+    assert_eq!(250, last.deleted_keys);
+    assert_eq!(None, last.total_keys);
+    assert_eq!(250, client.dbsize().await?);
+
+    // explicit list, with DEL & a rate limit
+    let keys: Vec<String> = (0..300).map(|i| format!("keep:{i}")).collect();
+    let mut reports = Vec::new();
+    let deleted = client
+        .delete_keys(
+            DeleteKeys::List(keys),
+            DeleteOptions {
+                unlink: false,
+                batch: 100,
+                rate_limit: Some(1000),
+            },
+            |progress| reports.push(progress),
+        )
+        .await?;
+    assert_eq!(250, deleted);
+    assert_eq!(
+        vec![(100, 100), (200, 200), (300, 250)],
+        reports
+            .iter()
+            .map(|p| (p.processed_keys, p.deleted_keys))
+            .collect::<Vec<_>>()
+    );
+    assert!(reports.iter().all(|p| p.total_keys == Some(300)));
+    // 300 keys at 1000 keys per second
+    assert!(reports[2].elapsed >= Duration::from_millis(200));
+    assert_eq!(0, client.dbsize().await?);
+
+    let result = client
+        .delete_keys(
+            DeleteKeys::List(vec!["key".to_owned()]),
+            DeleteOptions {
+                batch: 0,
+                ..Default::default()
+            },
+            |_| (),
+        )
+        .await;
+    assert!(result.is_err());
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
@@ -567,6 +723,47 @@ async fn restore() -> Result<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn restore_streamed() -> Result<()> {
+    let client = get_test_client().await?;
+
+    // big enough to be read in several chunks
+    let value = "x".repeat(200 * 1024);
+    client.set("key", &value).await?;
+
+    let dump = client.dump("key").await?;
+    client.del("key").await?;
+    client
+        .restore_streamed(
+            "key",
+            0,
+            StreamedArg::from_async_read(dump.0.len(), std::io::Cursor::new(dump.0)),
+            RestoreOptions::default(),
+        )
+        .await?;
+    let result: String = client.get("key").await?;
+    assert_eq!(value, result);
+
+    // the source must provide the announced length
+    let result = client
+        .send(
+            cmd("SET")
+                .arg("key")
+                .streamed_arg(StreamedArg::from_async_read(10, &b"short"[..])),
+            None,
+        )
+        .await;
+    assert!(result.is_err());
+
+    // the connection is usable again after reconnection
+    let result: String = client.get("key").await?;
+    assert_eq!(value, result);
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
@@ -585,6 +782,11 @@ async fn scan() -> Result<()> {
     assert!(keys.1.contains("key2"));
     assert!(keys.1.contains("key3"));
 
+    client.rpush("list", "element").await?;
+    let keys: (u64, HashSet<String>) = client.scan(0, ScanOptions::default().type_("list")).await?;
+    assert_eq!(1, keys.1.len());
+    assert!(keys.1.contains("list"));
+
     Ok(())
 }
 
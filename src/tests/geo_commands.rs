@@ -1,13 +1,114 @@
 use crate::{
     commands::{
-        GenericCommands, GeoAddCondition, GeoCommands, GeoSearchBy, GeoSearchFrom,
-        GeoSearchOptions, GeoSearchOrder, GeoSearchResult, GeoSearchStoreOptions, GeoUnit,
+        GenericCommands, GeoAddCondition, GeoCommands, GeoCoordinates, GeoPoint, GeoSearchBy,
+        GeoSearchFrom, GeoSearchOptions, GeoSearchOrder, GeoSearchResult, GeoSearchStoreOptions,
+        GeoUnit,
     },
+    resp::RespDeserializer,
     tests::get_test_client,
-    Result,
+    Error, Result,
 };
+use serde::Deserialize;
 use serial_test::serial;
 
+#[test]
+fn geo_point() -> Result<()> {
+    let point = GeoPoint::new(13.361389, 38.115556)?;
+    assert_eq!(13.361389, point.longitude());
+    assert_eq!(38.115556, point.latitude());
+    assert_eq!(point, GeoPoint::try_from((13.361389, 38.115556))?);
+    assert_eq!((13.361389, 38.115556), point.into());
+
+    assert!(GeoPoint::new(-180.0, -85.05112878).is_ok());
+    assert!(GeoPoint::new(180.0, 85.05112878).is_ok());
+    assert!(matches!(
+        GeoPoint::new(180.5, 0.0),
+        Err(Error::Client(e)) if e == "Invalid longitude 180.5: must be between -180 and 180"
+    ));
+    assert!(matches!(
+        GeoPoint::new(0.0, -86.0),
+        Err(Error::Client(e)) if e == "Invalid latitude -86: must be between -85.05112878 and 85.05112878"
+    ));
+    assert!(GeoPoint::new(f64::NAN, 0.0).is_err());
+    assert!(GeoPoint::try_from((0.0, 90.0)).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn geo_coordinates() -> Result<()> {
+    let resp = "*2\r\n*2\r\n$4\r\n13.5\r\n$4\r\n38.1\r\n_\r\n";
+    let positions =
+        Vec::<GeoCoordinates>::deserialize(&mut RespDeserializer::new(resp.as_bytes()))?;
+    assert_eq!(
+        vec![
+            GeoCoordinates::Position(GeoPoint::new(13.5, 38.1)?),
+            GeoCoordinates::Missing
+        ],
+        positions
+    );
+    assert_eq!(Some(GeoPoint::new(13.5, 38.1)?), positions[0].point());
+    assert_eq!(None, positions[1].point());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn geo_points() -> Result<()> {
+    let client = get_test_client().await?;
+
+    // cleanup
+    client.del("Sicily").await?;
+
+    let len = client
+        .geoadd_points(
+            "Sicily",
+            Default::default(),
+            false,
+            [
+                (GeoPoint::new(13.361389, 38.115556)?, "Palermo"),
+                (GeoPoint::new(15.087269, 37.502669)?, "Catania"),
+            ],
+        )
+        .await?;
+    assert_eq!(2, len);
+
+    let positions = client
+        .geopos_points("Sicily", ["Palermo", "NonExisting"])
+        .await?;
+    assert_eq!(
+        vec![
+            GeoCoordinates::Position(GeoPoint::new(13.361389338970184, 38.1155563954963)?),
+            GeoCoordinates::Missing
+        ],
+        positions
+    );
+
+    let results: Vec<GeoSearchResult<String>> = client
+        .geosearch(
+            "Sicily",
+            GeoSearchFrom::<&str>::from(GeoPoint::new(15.0, 37.0)?),
+            GeoSearchBy::ByRadius {
+                radius: 100.0,
+                unit: GeoUnit::Kilometers,
+            },
+            GeoSearchOptions::default()
+                .order(GeoSearchOrder::Asc)
+                .with_coord(),
+        )
+        .await?;
+    assert_eq!(1, results.len());
+    assert_eq!("Catania", results[0].member);
+    assert_eq!(
+        Some(GeoPoint::new(15.087267458438873, 37.50266842333162)?),
+        results[0].point()
+    );
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
@@ -391,3 +492,56 @@ async fn geosearchstore() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn georadius_readonly() -> Result<()> {
+    let client = get_test_client().await?;
+
+    // cleanup
+    client.del("Sicily").await?;
+
+    client
+        .geoadd(
+            "Sicily",
+            Default::default(),
+            false,
+            [
+                (13.361389, 38.115556, "Palermo"),
+                (15.087269, 37.502669, "Catania"),
+            ],
+        )
+        .await?;
+
+    let results: Vec<GeoSearchResult<String>> = client
+        .georadius_readonly(
+            "Sicily",
+            15.0,
+            37.0,
+            200.0,
+            GeoUnit::Kilometers,
+            GeoSearchOptions::default()
+                .order(GeoSearchOrder::Asc)
+                .with_dist(),
+        )
+        .await?;
+    assert_eq!(2, results.len());
+    assert_eq!("Catania", results[0].member);
+    assert!(results[0].distance.is_some());
+    assert_eq!("Palermo", results[1].member);
+
+    let results: Vec<GeoSearchResult<String>> = client
+        .georadiusbymember_readonly(
+            "Sicily",
+            "Palermo",
+            100.0,
+            GeoUnit::Kilometers,
+            GeoSearchOptions::default(),
+        )
+        .await?;
+    assert_eq!(1, results.len());
+    assert_eq!("Palermo", results[0].member);
+
+    Ok(())
+}
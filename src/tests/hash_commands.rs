@@ -266,6 +266,33 @@ async fn hscan() -> Result<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn hscan_novalues() -> Result<()> {
+    let client = get_test_client().await?;
+
+    // cleanup
+    client.del("key").await?;
+
+    let fields_and_values: Vec<_> = (1..21)
+        .map(|i| (format!("field{}", i), format!("value{}", i)))
+        .collect();
+
+    client.hset("key", fields_and_values).await?;
+
+    let (cursor, fields): (u64, Vec<String>) = client
+        .hscan_novalues("key", 0, HScanOptions::default().count(20))
+        .await?;
+
+    assert_eq!(0, cursor);
+    assert_eq!(20, fields.len());
+    assert_eq!("field1", fields[0]);
+    assert_eq!("field2", fields[1]);
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
@@ -1,5 +1,5 @@
 use crate::{
-    commands::{FlushingMode, HyperLogLogCommands, ServerCommands},
+    commands::{FlushingMode, GenericCommands, HyperLogLogCommands, ServerCommands},
     tests::get_test_client,
     Result,
 };
@@ -67,3 +67,58 @@ async fn pfmerge() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn pfcount_each() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    client.pfadd("key1", ["a", "b", "c"]).await?;
+    client.pfadd("key2", ["c", "d"]).await?;
+
+    let counts = client.pfcount_each(["key1", "key2", "key3"]).await?;
+    assert_eq!(vec![3, 2, 0], counts);
+
+    let counts = client.pfcount_each("key1").await?;
+    assert_eq!(vec![3], counts);
+
+    let counts = client.pfcount_each(Vec::<String>::new()).await?;
+    assert!(counts.is_empty());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn pfmerge_into_temp() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    client.pfadd("{hll}:1", ["foo", "bar", "zap", "a"]).await?;
+    client.pfadd("{hll}:2", ["a", "b", "c", "foo"]).await?;
+
+    let temp = client.pfmerge_into_temp(["{hll}:1", "{hll}:2"]).await?;
+    let key = temp.key().to_vec();
+    assert!(key.starts_with(b"{hll}:"));
+    assert_eq!(6, temp.count().await?);
+    assert!(client.pttl(key.as_slice()).await? > 0);
+
+    temp.delete().await?;
+    assert_eq!(0, client.exists(key.as_slice()).await?);
+
+    // deleted on drop
+    let temp = client.pfmerge_into_temp(["{hll}:1", "{hll}:2"]).await?;
+    let key = temp.key().to_vec();
+    drop(temp);
+    assert_eq!(0, client.exists(key.as_slice()).await?);
+
+    assert!(client
+        .pfmerge_into_temp(Vec::<String>::new())
+        .await
+        .is_err());
+
+    Ok(())
+}
@@ -1,9 +1,12 @@
 use crate::{
-    commands::{FlushingMode, JsonArrIndexOptions, JsonCommands, JsonGetOptions, ServerCommands},
-    resp::Value,
+    commands::{
+        FlushingMode, JsonArrIndexOptions, JsonCommands, JsonGetOptions, JsonSingle, ServerCommands,
+    },
+    resp::{RespDeserializer, Value},
     tests::get_redis_stack_test_client,
-    Result,
+    Error, Result,
 };
+use serde::Deserialize;
 use serial_test::serial;
 use smallvec::SmallVec;
 
@@ -631,3 +634,106 @@ async fn json_type() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn json_single() -> Result<()> {
+    fn deserialize<T: serde::de::DeserializeOwned>(resp: &str) -> Result<JsonSingle<T>> {
+        JsonSingle::<T>::deserialize(&mut RespDeserializer::new(resp.as_bytes()))
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Person {
+        name: String,
+        age: u32,
+    }
+
+    // JSON.GET
+    let person: JsonSingle<Person> = deserialize("$26\r\n[{\"name\":\"John\",\"age\":42}]\r\n")?;
+    assert_eq!(
+        Person {
+            name: "John".to_owned(),
+            age: 42
+        },
+        person.into_inner()
+    );
+    assert_eq!(
+        JsonSingle(None),
+        deserialize::<Option<u32>>("$2\r\n[]\r\n")?
+    );
+    assert_eq!(JsonSingle(None), deserialize::<Option<u32>>("_\r\n")?);
+    assert!(
+        matches!(deserialize::<u32>("$2\r\n[]\r\n"), Err(Error::Client(e)) if e == "JSONPath matched no value")
+    );
+    assert!(
+        matches!(deserialize::<u32>("$5\r\n[1,2]\r\n"), Err(Error::Client(e)) if e == "JSONPath matched 2 values, expected a single one")
+    );
+
+    // JSON.STRLEN, JSON.TYPE, ...
+    assert_eq!(
+        JsonSingle(Some(4)),
+        deserialize::<Option<usize>>("*1\r\n:4\r\n")?
+    );
+    assert_eq!(
+        JsonSingle(None),
+        deserialize::<Option<usize>>("*1\r\n_\r\n")?
+    );
+    assert_eq!(
+        JsonSingle("string".to_owned()),
+        deserialize::<String>("*1\r\n$6\r\nstring\r\n")?
+    );
+    assert!(
+        matches!(deserialize::<String>("*0\r\n"), Err(Error::Client(e)) if e == "JSONPath matched no value")
+    );
+    assert!(
+        matches!(deserialize::<usize>("*3\r\n:1\r\n:2\r\n:3\r\n"), Err(Error::Client(e)) if e == "JSONPath matched 3 values, expected a single one")
+    );
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn json_get_single() -> Result<()> {
+    let client = get_redis_stack_test_client().await?;
+    client.flushall(FlushingMode::Sync).await?;
+
+    client
+        .json_set(
+            "key",
+            "$",
+            r#"{"name":"John","tags":["a","b"],"nested":{"name":"Jane"}}"#,
+            Default::default(),
+        )
+        .await?;
+
+    let JsonSingle(name): JsonSingle<String> = client.json_get_single("key", "$.name").await?;
+    assert_eq!("John", name);
+
+    let JsonSingle(tags): JsonSingle<Vec<String>> = client.json_get_single("key", "$.tags").await?;
+    assert_eq!(vec!["a".to_owned(), "b".to_owned()], tags);
+
+    let JsonSingle(email): JsonSingle<Option<String>> =
+        client.json_get_single("key", "$.email").await?;
+    assert_eq!(None, email);
+
+    let result = client
+        .json_get_single::<_, _, String>("key", "$..name")
+        .await;
+    assert!(
+        matches!(result, Err(Error::Client(e)) if e == "JSONPath matched 2 values, expected a single one")
+    );
+
+    let result: Option<JsonSingle<String>> = client
+        .json_get("unknown", JsonGetOptions::default().path("$.name"))
+        .await?;
+    assert_eq!(None, result);
+
+    let JsonSingle(len) = client.json_strlen("key", "$.name").await?;
+    assert_eq!(Some(4), len);
+
+    let JsonSingle(json_type): JsonSingle<String> = client.json_type("key", "$.tags").await?;
+    assert_eq!("array", json_type);
+
+    Ok(())
+}
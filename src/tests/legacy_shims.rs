@@ -0,0 +1,103 @@
+use crate::{
+    client::{LegacyShim, LegacyShims},
+    resp::{cmd, Command},
+    Version,
+};
+
+fn args(command: &Command) -> Vec<String> {
+    std::iter::once(command.name.to_owned())
+        .chain(
+            command
+                .args
+                .into_iter()
+                .map(|arg| String::from_utf8_lossy(arg).into_owned()),
+        )
+        .collect()
+}
+
+fn shim(command: Command) -> Option<LegacyShim> {
+    LegacyShims::default().shim(&command, Version::new(6, 0, 16))
+}
+
+fn transaction(command: Command) -> Vec<Vec<String>> {
+    match shim(command) {
+        Some(LegacyShim::Transaction {
+            commands,
+            reply_index: 0,
+        }) => commands.iter().map(args).collect(),
+        shim => panic!("Unexpected shim {shim:?}"),
+    }
+}
+
+fn single(command: Command) -> Vec<String> {
+    match shim(command) {
+        Some(LegacyShim::Command(command)) => args(&command),
+        shim => panic!("Unexpected shim {shim:?}"),
+    }
+}
+
+#[test]
+fn recent_server() {
+    let shims = LegacyShims::default();
+    assert!(shims
+        .shim(&cmd("GETDEL").arg("key"), Version::new(6, 2, 0))
+        .is_none());
+    assert!(shim(cmd("GET").arg("key")).is_none());
+    assert!(shim(cmd("SET").arg("key").arg("get")).is_none());
+}
+
+#[test]
+fn getdel() {
+    assert_eq!(
+        vec![vec!["GET", "key"], vec!["DEL", "key"]],
+        transaction(cmd("GETDEL").arg("key"))
+    );
+}
+
+#[test]
+fn getex() {
+    assert_eq!(vec!["GET", "key"], single(cmd("GETEX").arg("key")));
+    assert_eq!(
+        vec![vec!["GET", "key"], vec!["PEXPIRE", "key", "1000"]],
+        transaction(cmd("GETEX").arg("key").arg("PX").arg(1000))
+    );
+    assert_eq!(
+        vec![vec!["GET", "key"], vec!["PERSIST", "key"]],
+        transaction(cmd("GETEX").arg("key").arg("persist"))
+    );
+    assert!(shim(cmd("GETEX").arg("key").arg("UNKNOWN").arg(1)).is_none());
+}
+
+#[test]
+fn set_get() {
+    assert_eq!(
+        vec![vec!["GET", "key"], vec!["SET", "key", "value", "EX", "10"]],
+        transaction(
+            cmd("SET")
+                .arg("key")
+                .arg("value")
+                .arg("EX")
+                .arg(10)
+                .arg("GET")
+        )
+    );
+}
+
+#[test]
+fn copy() {
+    let copy = single(cmd("COPY").arg("src").arg("dst").arg("REPLACE"));
+    assert_eq!("EVAL", copy[0]);
+    assert_eq!(vec!["2", "src", "dst", "REPLACE"], copy[2..]);
+    assert!(shim(cmd("COPY").arg("src").arg("dst").arg("DB").arg(1)).is_none());
+}
+
+#[test]
+fn lmove() {
+    assert_eq!(
+        vec!["RPOPLPUSH", "src", "dst"],
+        single(cmd("LMOVE").arg("src").arg("dst").arg("RIGHT").arg("LEFT"))
+    );
+    let lmove = single(cmd("LMOVE").arg("src").arg("dst").arg("LEFT").arg("RIGHT"));
+    assert_eq!("EVAL", lmove[0]);
+    assert_eq!(vec!["2", "src", "dst", "LPOP", "RPUSH"], lmove[2..]);
+}
@@ -343,6 +343,57 @@ async fn lpop() -> Result<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn lpop_one() -> Result<()> {
+    let client = get_test_client().await?;
+
+    // cleanup
+    client.del("mylist").await?;
+
+    let element: Option<String> = client.lpop_one("mylist").await?;
+    assert_eq!(None, element);
+
+    client.lpush("mylist", ["element1", "element2"]).await?;
+
+    let element: Option<String> = client.lpop_one("mylist").await?;
+    assert_eq!(Some("element2".to_owned()), element);
+
+    let element: Option<String> = client.lpop_one("mylist").await?;
+    assert_eq!(Some("element1".to_owned()), element);
+
+    let element: Option<String> = client.lpop_one("mylist").await?;
+    assert_eq!(None, element);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn lpop_count() -> Result<()> {
+    let client = get_test_client().await?;
+
+    // cleanup
+    client.del("mylist").await?;
+
+    let elements: Vec<String> = client.lpop_count("mylist", 2).await?;
+    assert!(elements.is_empty());
+
+    client
+        .lpush("mylist", ["element1", "element2", "element3"])
+        .await?;
+
+    let elements: Vec<String> = client.lpop_count("mylist", 2).await?;
+    assert_eq!(vec!["element3".to_owned(), "element2".to_owned()], elements);
+
+    let elements: Vec<String> = client.lpop_count("mylist", 2).await?;
+    assert_eq!(vec!["element1".to_owned()], elements);
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
@@ -540,6 +591,57 @@ async fn rpop() -> Result<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn rpop_one() -> Result<()> {
+    let client = get_test_client().await?;
+
+    // cleanup
+    client.del("mylist").await?;
+
+    let element: Option<String> = client.rpop_one("mylist").await?;
+    assert_eq!(None, element);
+
+    client.rpush("mylist", ["element1", "element2"]).await?;
+
+    let element: Option<String> = client.rpop_one("mylist").await?;
+    assert_eq!(Some("element2".to_owned()), element);
+
+    let element: Option<String> = client.rpop_one("mylist").await?;
+    assert_eq!(Some("element1".to_owned()), element);
+
+    let element: Option<String> = client.rpop_one("mylist").await?;
+    assert_eq!(None, element);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn rpop_count() -> Result<()> {
+    let client = get_test_client().await?;
+
+    // cleanup
+    client.del("mylist").await?;
+
+    let elements: Vec<String> = client.rpop_count("mylist", 2).await?;
+    assert!(elements.is_empty());
+
+    client
+        .rpush("mylist", ["element1", "element2", "element3"])
+        .await?;
+
+    let elements: Vec<String> = client.rpop_count("mylist", 2).await?;
+    assert_eq!(vec!["element3".to_owned(), "element2".to_owned()], elements);
+
+    let elements: Vec<String> = client.rpop_count("mylist", 2).await?;
+    assert_eq!(vec!["element1".to_owned()], elements);
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
@@ -0,0 +1,80 @@
+use crate::{client::Message, network::MessageScheduler, resp::cmd};
+use futures_channel::oneshot;
+
+fn message(client_id: usize, name: &'static str) -> Message {
+    let (result_sender, _) = oneshot::channel();
+    let mut message = Message::single(cmd(name), result_sender, false);
+    message.client_id = client_id;
+    message
+}
+
+fn batch(client_id: usize, num_commands: usize) -> Message {
+    let (results_sender, _) = oneshot::channel();
+    let mut message = Message::batch(
+        (0..num_commands).map(|_| cmd("BATCH")).collect(),
+        results_sender,
+        false,
+    );
+    message.client_id = client_id;
+    message
+}
+
+fn name(message: &Message) -> &'static str {
+    (&message.commands).into_iter().next().unwrap().name
+}
+
+#[test]
+fn round_robin() {
+    let mut scheduler = MessageScheduler::new(0);
+    scheduler.push(message(1, "A1"));
+    scheduler.push(message(1, "A2"));
+    scheduler.push(message(1, "A3"));
+    scheduler.push(message(2, "B1"));
+    scheduler.push(message(3, "C1"));
+    scheduler.push(message(2, "B2"));
+
+    let names = std::iter::from_fn(|| scheduler.pop())
+        .map(|m| name(&m))
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["A1", "B1", "C1", "A2", "B2", "A3"], names);
+    assert!(scheduler.is_empty());
+}
+
+#[test]
+fn max_in_flight_per_client() {
+    let mut scheduler = MessageScheduler::new(2);
+    scheduler.push(message(1, "A1"));
+    scheduler.push(message(1, "A2"));
+    scheduler.push(message(1, "A3"));
+    scheduler.push(message(2, "B1"));
+
+    let a1 = scheduler.pop().unwrap();
+    let b1 = scheduler.pop().unwrap();
+    let a2 = scheduler.pop().unwrap();
+    assert_eq!(("A1", "B1", "A2"), (name(&a1), name(&b1), name(&a2)));
+
+    // client 1 has reached its limit
+    assert!(scheduler.pop().is_none());
+    assert!(!scheduler.is_empty());
+
+    // a reply releases in-flight capacity
+    drop(a1);
+    let a3 = scheduler.pop().unwrap();
+    assert_eq!("A3", name(&a3));
+    assert!(scheduler.is_empty());
+}
+
+#[test]
+fn batch_larger_than_max_in_flight() {
+    let mut scheduler = MessageScheduler::new(2);
+    scheduler.push(message(1, "A1"));
+    scheduler.push(batch(1, 5));
+
+    let a1 = scheduler.pop().unwrap();
+    assert!(scheduler.pop().is_none());
+
+    // the large batch is sent alone
+    drop(a1);
+    let batch = scheduler.pop().unwrap();
+    assert_eq!(5, batch.commands.len());
+}
@@ -0,0 +1,506 @@
+#[cfg(feature = "test-util")]
+use crate::test_util::SelectChurn;
+use crate::{
+    client::{BatchPreparedCommand, Client, CommandOutcome, Config, MockServerBehavior},
+    commands::{
+        FlushingMode, GenericCommands, HashCommands, ListCommands, ServerCommands, SetCommands,
+        SetCondition, SetExpiration, StringCommands,
+    },
+    network::{Connection, MockConnection},
+    resp::{cmd, Value},
+    tests::get_default_config,
+    Error, Future, RedisError, RedisErrorKind, Result, Version,
+};
+use futures_util::poll;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serial_test::serial;
+#[cfg(feature = "test-util")]
+use std::time::Duration;
+use std::{
+    collections::{HashMap, HashSet},
+    future::IntoFuture,
+};
+use tokio::task::yield_now;
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn mock_strings() -> Result<()> {
+    let client = Client::connect_mock(MockServerBehavior::new()).await?;
+
+    client.set("key", "value").await?;
+    let value: String = client.get("key").await?;
+    assert_eq!("value", value);
+
+    let value: Option<String> = client.get("unknown").await?;
+    assert_eq!(None, value);
+
+    let is_set = client
+        .set_with_options(
+            "key",
+            "other",
+            SetCondition::NX,
+            SetExpiration::Ex(10),
+            false,
+        )
+        .await?;
+    assert!(!is_set);
+
+    assert_eq!(1, client.incr("counter").await?);
+    assert_eq!(11, client.incrby("counter", 10).await?);
+    assert_eq!(2, client.exists(["key", "counter", "unknown"]).await?);
+    assert_eq!(2, client.dbsize().await?);
+
+    client.flushdb(FlushingMode::Sync).await?;
+    assert_eq!(0, client.dbsize().await?);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn mock_collections() -> Result<()> {
+    let client = Client::connect_mock(MockServerBehavior::new()).await?;
+
+    assert_eq!(
+        2,
+        client
+            .hset("hash", [("field1", "value1"), ("field2", "value2")])
+            .await?
+    );
+    let values: HashMap<String, String> = client.hgetall("hash").await?;
+    assert_eq!(2, values.len());
+    assert_eq!(Some(&"value1".to_owned()), values.get("field1"));
+
+    assert_eq!(3, client.rpush("list", ["a", "b", "c"]).await?);
+    let elements: Vec<String> = client.lrange("list", 0, -1).await?;
+    assert_eq!(vec!["a", "b", "c"], elements);
+    let elements: Vec<String> = client.lpop("list", 3).await?;
+    assert_eq!(vec!["a", "b", "c"], elements);
+    assert_eq!(0, client.exists("list").await?);
+
+    assert_eq!(2, client.rpush("list", ["a", "b"]).await?);
+    let element: Option<String> = client.rpop_one("list").await?;
+    assert_eq!(Some("b".to_owned()), element);
+    let elements: Vec<String> = client.lpop_count("list", 2).await?;
+    assert_eq!(vec!["a"], elements);
+    let element: Option<String> = client.lpop_one("list").await?;
+    assert_eq!(None, element);
+    let elements: Vec<String> = client.rpop_count("list", 2).await?;
+    assert!(elements.is_empty());
+
+    assert_eq!(2, client.sadd("set", ["m1", "m2", "m1"]).await?);
+    let members: HashSet<String> = client.smembers("set").await?;
+    assert_eq!(HashSet::from(["m1".to_owned(), "m2".to_owned()]), members);
+
+    // wrong type
+    let result = client.lpush("hash", "element").await;
+    assert!(matches!(
+        result,
+        Err(Error::Redis(RedisError {
+            kind: RedisErrorKind::WrongType,
+            description: _
+        }))
+    ));
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn mock_batches() -> Result<()> {
+    let client = Client::connect_mock(MockServerBehavior::new()).await?;
+
+    let mut pipeline = client.create_pipeline();
+    pipeline.set("key1", "value1").forget();
+    pipeline.set("key2", "value2").forget();
+    pipeline.get::<_, ()>("key1").queue();
+    pipeline.get::<_, ()>("key2").queue();
+    let (value1, value2): (String, String) = pipeline.execute().await?;
+    assert_eq!("value1", value1);
+    assert_eq!("value2", value2);
+
+    let mut transaction = client.create_transaction();
+    transaction.incr("counter").queue();
+    transaction.incr("counter").queue();
+    let (counter1, counter2): (i64, i64) = transaction.execute().await?;
+    assert_eq!(1, counter1);
+    assert_eq!(2, counter2);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn mock_canned_replies() -> Result<()> {
+    let behavior = MockServerBehavior::new()
+        .reply(
+            "CONFIG",
+            Value::Map(HashMap::from([(
+                Value::BulkString(b"maxmemory".to_vec()),
+                Value::BulkString(b"1024".to_vec()),
+            )])),
+        )
+        .reply("get", Value::BulkString(b"canned".to_vec()))
+        .error(
+            "SET",
+            RedisErrorKind::Readonly,
+            "You can't write against a read only replica.",
+        );
+    let client = Client::connect_mock(behavior).await?;
+
+    let config: HashMap<String, String> = client.config_get("maxmemory").await?;
+    assert_eq!(Some(&"1024".to_owned()), config.get("maxmemory"));
+
+    // canned replies are consumed in order
+    let value: String = client.get("key").await?;
+    assert_eq!("canned", value);
+    let value: Option<String> = client.get("key").await?;
+    assert_eq!(None, value);
+
+    let result = client.set("key", "value").await;
+    assert!(matches!(
+        result,
+        Err(Error::Redis(RedisError {
+            kind: RedisErrorKind::Readonly,
+            description: _
+        }))
+    ));
+    client.set("key", "value").await?;
+
+    // unknown command
+    let result = client
+        .send(cmd("OBJECT").arg("ENCODING").arg("key"), None)
+        .await?;
+    assert!(result.is_error());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn mock_without_reply() -> Result<()> {
+    let client = Client::connect_mock(MockServerBehavior::new()).await?;
+
+    client.send_without_reply(cmd("SET").arg("key1").arg("value1"), None)?;
+    // replies of following commands must stay in sync
+    let value: String = client.get("key1").await?;
+    assert_eq!("value1", value);
+
+    client
+        .send_batch_without_replies(
+            vec![
+                cmd("SET").arg("key2").arg("value2"),
+                cmd("INCR").arg("key2"),
+                cmd("SET").arg("key3").arg("value3"),
+            ],
+            None,
+        )
+        .await?;
+    let values: Vec<String> = client.mget(["key2", "key3"]).await?;
+    assert_eq!(vec!["value2".to_owned(), "value3".to_owned()], values);
+
+    // CLIENT REPLY SKIP is ignored when replies are off
+    client
+        .send_batch_without_replies(
+            vec![
+                cmd("CLIENT").arg("REPLY").arg("SKIP"),
+                cmd("DEL").arg("key3"),
+            ],
+            None,
+        )
+        .await?;
+    assert_eq!(2, client.dbsize().await?);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn mock_server_version() -> Result<()> {
+    let client = Client::connect_mock(MockServerBehavior::new()).await?;
+    assert_eq!(None, client.server_version());
+    // no version, no check
+    client.send(cmd("GETDEL").arg("key"), None).await?;
+
+    let behavior = MockServerBehavior::new().server_version(Version::new(6, 0, 0));
+    let client = Client::connect_mock(behavior).await?;
+    assert_eq!(Some(Version::new(6, 0, 0)), client.server_version());
+    // not strict
+    client.send(cmd("GETDEL").arg("key"), None).await?;
+
+    let behavior = MockServerBehavior::new().server_version(Version::new(6, 0, 0));
+    let config = Config {
+        strict_server_version: true,
+        ..Default::default()
+    };
+    let client = Client::start(Connection::Mock(MockConnection::new(behavior)), config);
+    let result = client.send(cmd("GETDEL").arg("key"), None).await;
+    assert!(matches!(
+        result,
+        Err(Error::UnsupportedByServer { command, since }) if command == "GETDEL" && since == Version::new(6, 2, 0)
+    ));
+    client.set("key", "value").await?;
+    let value: String = client.get("key").await?;
+    assert_eq!("value", value);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn mock_legacy_command_shims() -> Result<()> {
+    let behavior = MockServerBehavior::new()
+        .server_version(Version::new(6, 0, 0))
+        .reply("GET", Value::BulkString(b"scripted".to_vec()));
+    let config = Config {
+        legacy_command_shims: true,
+        ..Default::default()
+    };
+    let client = Client::start(Connection::Mock(MockConnection::new(behavior)), config);
+
+    // GETDEL is emulated with GET & DEL in a transaction
+    client.set("key", "value").await?;
+    let value: Option<String> = client.getdel("key").await?;
+    assert_eq!(Some("scripted".to_owned()), value);
+    assert_eq!(0, client.exists("key").await?);
+    let value: Option<String> = client.getdel("key").await?;
+    assert_eq!(None, value);
+
+    // SET with the GET option is emulated with GET & SET in a transaction
+    client.set("key", "value1").await?;
+    let value: Option<String> = client
+        .send(cmd("SET").arg("key").arg("value2").arg("GET"), None)
+        .await?
+        .to()?;
+    assert_eq!(Some("value1".to_owned()), value);
+    let value: String = client.get("key").await?;
+    assert_eq!("value2", value);
+
+    // the commands are sent as is to a recent server
+    let behavior = MockServerBehavior::new()
+        .server_version(Version::new(6, 2, 0))
+        .reply("GETDEL", Value::BulkString(b"scripted".to_vec()));
+    let config = Config {
+        legacy_command_shims: true,
+        ..Default::default()
+    };
+    let client = Client::start(Connection::Mock(MockConnection::new(behavior)), config);
+    let value: String = client.getdel("key").await?;
+    assert_eq!("scripted", value);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn replay() -> Result<()> {
+    let path = std::env::temp_dir().join("rustis_replay.resp");
+    std::fs::write(
+        &path,
+        b">\r\n*2\r\n$3\r\nGET\r\n$3\r\nkey\r\n<\r\n$5\r\nvalue\r\n",
+    )?;
+
+    let client = Client::connect_replay(&path).await?;
+    let value: String = client.get("key").await?;
+    assert_eq!("value", value);
+
+    // commands must match the recording
+    let result = client.get::<_, String>("other").await;
+    assert!(matches!(result, Err(Error::Client(_))));
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn record_and_replay() -> Result<()> {
+    let path = std::env::temp_dir().join("rustis_record_and_replay.resp");
+
+    let client = Client::connect_recording(get_default_config()?, &path).await?;
+    client.set("key", "value").await?;
+    let mut pipeline = client.create_pipeline();
+    pipeline.get::<_, ()>("key").queue();
+    pipeline.del("key").queue();
+    let (value, deleted): (String, usize) = pipeline.execute().await?;
+    assert_eq!("value", value);
+    assert_eq!(1, deleted);
+    client.close().await?;
+
+    let client = Client::connect_replay(&path).await?;
+    client.set("key", "value").await?;
+    let mut pipeline = client.create_pipeline();
+    pipeline.get::<_, ()>("key").queue();
+    pipeline.del("key").queue();
+    let (value, deleted): (String, usize) = pipeline.execute().await?;
+    assert_eq!("value", value);
+    assert_eq!(1, deleted);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn mock_command_journal() -> Result<()> {
+    let behavior = MockServerBehavior::new()
+        .reply("AUTH", Value::SimpleString("OK".to_owned()))
+        .reply("auth", Value::SimpleString("OK".to_owned()))
+        .error("INCR", RedisErrorKind::Err, "not an integer");
+    let config = Config {
+        command_journal_size: 4,
+        ..Default::default()
+    };
+    let client = Client::start(Connection::Mock(MockConnection::new(behavior)), config);
+    let journal = client.command_journal().unwrap();
+
+    client.set("key", "value").await?;
+    client
+        .send(cmd("AUTH").arg("user").arg("password"), None)
+        .await?;
+    assert!(client.incr("key").await.is_err());
+    client.set("key", "a".repeat(100)).await?;
+    client
+        .send(cmd("auth").arg("user").arg("password"), None)
+        .await?;
+
+    // the oldest command has been evicted
+    let entries = journal.dump();
+    assert_eq!(4, entries.len());
+
+    assert_eq!("AUTH", entries[0].name);
+    assert_eq!(vec!["***", "***"], entries[0].args);
+    assert_eq!(CommandOutcome::Ok, entries[0].outcome);
+    assert!(entries[0].duration.is_some());
+
+    assert_eq!("INCR", entries[1].name);
+    assert!(
+        matches!(&entries[1].outcome, CommandOutcome::Error(e) if e.contains("not an integer"))
+    );
+
+    assert_eq!("SET", entries[2].name);
+    assert_eq!(format!("{}...", "a".repeat(64)), entries[2].args[1]);
+
+    // whatever the case of the command name
+    assert_eq!("auth", entries[3].name);
+    assert_eq!(vec!["***", "***"], entries[3].args);
+
+    // disabled by default
+    let client = Client::connect_mock(MockServerBehavior::new()).await?;
+    assert!(client.command_journal().is_none());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn mock_error_context() -> Result<()> {
+    let behavior = MockServerBehavior::new().error("INCR", RedisErrorKind::Err, "not an integer");
+    let config = Config {
+        error_context: true,
+        ..Default::default()
+    };
+    let client = Client::start(Connection::Mock(MockConnection::new(behavior)), config);
+
+    // errors returned by the server are not wrapped
+    let result = client.incr("key").await;
+    assert!(matches!(result, Err(Error::Redis(_))));
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn mock_cancelled_futures() -> Result<()> {
+    let client = Client::connect_mock(MockServerBehavior::new()).await?;
+    for i in 0..100 {
+        client.set(format!("key{i}"), i).await?;
+    }
+
+    // futures dropped after a random number of polls,
+    // the network task yielding at random points in between
+    let mut rng = StdRng::seed_from_u64(42);
+    for i in 0..300 {
+        let mut future: Future<i64> = match i % 3 {
+            0 => client.get(format!("key{}", i % 100)).into_future(),
+            1 => {
+                let mut pipeline = client.create_pipeline();
+                pipeline.get::<_, i64>(format!("key{}", i % 100)).queue();
+                pipeline.incr(format!("counter{}", i % 100)).forget();
+                Box::pin(pipeline.execute())
+            }
+            _ => {
+                let mut transaction = client.create_transaction();
+                transaction.get::<_, i64>(format!("key{}", i % 100)).queue();
+                Box::pin(transaction.execute())
+            }
+        };
+        for _ in 0..rng.gen_range(0..3) {
+            let _ = poll!(&mut future);
+            for _ in 0..rng.gen_range(0..3) {
+                yield_now().await;
+            }
+        }
+        drop(future);
+
+        // replies are still received by their own commands
+        let j = rng.gen_range(0..100);
+        let value: i64 = client.get(format!("key{j}")).await?;
+        assert_eq!(j, value);
+    }
+
+    // a transaction is never left open
+    let mut transaction = client.create_transaction();
+    transaction.set("key0", 0).forget();
+    transaction.get::<_, i64>("key0").queue();
+    assert_eq!(0, transaction.execute::<i64>().await?);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn mock_coalesced_read_your_writes() -> Result<()> {
+    let config = Config {
+        coalesce_reads: true,
+        ..Default::default()
+    };
+    let client = Client::start(
+        Connection::Mock(MockConnection::new(MockServerBehavior::new())),
+        config,
+    );
+    client.set("key", 0).await?;
+
+    // GET sent before the SET, its caller not polled again until the end
+    let mut stale: Future<i64> = client.get("key").into_future();
+    assert!(poll!(&mut stale).is_pending());
+    client.set("key", 1).await?;
+
+    // GET sent after the SET has been acknowledged
+    let mut fresh: Future<i64> = client.get("key").into_future();
+    let _ = poll!(&mut fresh);
+
+    assert_eq!(0, stale.await?);
+    assert_eq!(1, fresh.await?);
+
+    Ok(())
+}
+
+#[cfg(feature = "test-util")]
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn mock_select_churn() -> Result<()> {
+    let client = Client::connect_mock(MockServerBehavior::new()).await?;
+
+    let report = SelectChurn::new(client)
+        .tasks(8)
+        .iterations(50)
+        .max_delay(Duration::from_micros(100))
+        .run()
+        .await?;
+    assert_eq!(400, report.completed + report.cancelled);
+    assert!(report.cancelled > 0);
+    assert_eq!(0, report.mismatched);
+    assert_eq!(0, report.failed);
+
+    Ok(())
+}
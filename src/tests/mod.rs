@@ -1,19 +1,36 @@
+mod async_transport;
+#[cfg(feature = "bench")]
+mod bench;
 mod bitmap_commands;
+#[cfg(feature = "blocking")]
+mod blocking;
 #[cfg(feature = "redis-bloom")]
 mod bloom_commands;
 mod buffer_decoder;
+#[cfg(feature = "chaos")]
+mod chaos;
 mod client;
+mod client_state;
 mod cluster;
 mod cluster_commands;
 mod command_args;
+mod command_encoder;
 mod command_info_manager;
+#[cfg(any(
+    feature = "compression-gzip",
+    feature = "compression-zstd",
+    feature = "compression-lz4"
+))]
+mod compression_layer;
 mod config;
 mod connection_commands;
+mod connection_stats;
 #[cfg(feature = "redis-bloom")]
 mod count_min_sktech_commands;
 #[cfg(feature = "redis-bloom")]
 mod cuckoo_commands;
 mod debug_commands;
+mod deprecated_commands;
 mod error;
 mod from_value;
 mod generic_commands;
@@ -24,15 +41,29 @@ mod hash_commands;
 mod hyper_log_log_commands;
 #[cfg(feature = "redis-json")]
 mod json_commands;
+mod legacy_shims;
 mod list_commands;
+mod message_scheduler;
+#[cfg(feature = "mock")]
+mod mock;
 mod multiplexed_client;
+mod node_health;
+#[cfg(feature = "otel-metrics")]
+mod otel_metrics;
+#[cfg(feature = "patterns")]
+mod patterns;
+mod pending_commands;
 mod pipeline;
 #[cfg(feature = "pool")]
 mod pooled_client_manager;
 mod pub_sub_commands;
+mod request_coalescer;
+#[cfg(feature = "replication")]
+mod replication;
 mod resp3;
 mod resp_deserializer;
 mod resp_serializer;
+mod resp_writer;
 mod scripting_commands;
 #[cfg(feature = "redis-search")]
 mod search_commands;
@@ -55,5 +86,6 @@ mod value;
 mod value_deserialize;
 mod value_deserializer;
 mod value_serialize;
+mod vector_set_commands;
 
 pub(crate) use util::*;
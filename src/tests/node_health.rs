@@ -0,0 +1,55 @@
+use crate::{client::ConnectionEvent, NodeHealthTracker};
+use std::time::Duration;
+use tokio::{sync::broadcast, time};
+
+#[cfg(feature = "tokio-runtime")]
+#[tokio::test(start_paused = true)]
+async fn circuit_breaker() {
+    let (event_sender, mut event_receiver) = broadcast::channel(32);
+    let mut tracker = NodeHealthTracker::new(2, Duration::from_millis(50));
+    tracker.set_event_sender(event_sender);
+    let address = ("127.0.0.1".to_owned(), 7000);
+
+    assert!(!tracker.record_failure(&address));
+    assert!(!tracker.is_unhealthy(&address));
+    assert!(tracker.record_failure(&address));
+    assert!(tracker.is_unhealthy(&address));
+    assert!(tracker.has_unhealthy_nodes());
+    assert_eq!(
+        Ok(ConnectionEvent::ClusterNodeUnhealthy {
+            address: address.clone(),
+            failures: 2,
+            cooldown: Duration::from_millis(50)
+        }),
+        event_receiver.try_recv()
+    );
+
+    // still in cool-down just before its end
+    time::advance(Duration::from_millis(49)).await;
+    assert!(tracker.is_unhealthy(&address));
+
+    // end of cool-down: a single failure marks the node as unhealthy again
+    time::advance(Duration::from_millis(1)).await;
+    assert!(!tracker.is_unhealthy(&address));
+    assert!(tracker.record_failure(&address));
+    assert!(tracker.is_unhealthy(&address));
+    assert!(event_receiver.try_recv().is_ok());
+
+    tracker.record_success(&address);
+    assert!(!tracker.is_unhealthy(&address));
+    assert!(!tracker.has_unhealthy_nodes());
+    assert_eq!(
+        Ok(ConnectionEvent::ClusterNodeHealthy {
+            address: address.clone()
+        }),
+        event_receiver.try_recv()
+    );
+}
+
+#[test]
+fn disabled_circuit_breaker() {
+    let mut tracker = NodeHealthTracker::new(0, Duration::from_millis(50));
+    let address = ("127.0.0.1".to_owned(), 7000);
+    assert!(!tracker.record_failure(&address));
+    assert!(!tracker.is_unhealthy(&address));
+}
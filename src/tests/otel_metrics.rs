@@ -0,0 +1,106 @@
+use crate::{
+    client::{Client, CommandLayer, IntoConfig, OtelMetrics},
+    commands::{FlushingMode, ServerCommands, StringCommands},
+    resp::cmd,
+    tests::{get_default_addr, log_try_init},
+    Error, Result,
+};
+use opentelemetry::{metrics::MeterProvider, KeyValue};
+use opentelemetry_sdk::metrics::{
+    data::{AggregatedMetrics, MetricData},
+    InMemoryMetricExporter, PeriodicReader, SdkMeterProvider,
+};
+use serial_test::serial;
+use std::time::Duration;
+
+fn meter_provider() -> (SdkMeterProvider, InMemoryMetricExporter) {
+    let exporter = InMemoryMetricExporter::default();
+    let provider = SdkMeterProvider::builder()
+        .with_reader(PeriodicReader::builder(exporter.clone()).build())
+        .build();
+    (provider, exporter)
+}
+
+/// Data points `(attributes, value)` of the sum `name`
+fn sum_data_points(
+    provider: &SdkMeterProvider,
+    exporter: &InMemoryMetricExporter,
+    name: &str,
+) -> Vec<(Vec<KeyValue>, u64)> {
+    provider.force_flush().unwrap();
+    let metrics = exporter.get_finished_metrics().unwrap();
+    let metric = metrics
+        .iter()
+        .rev()
+        .flat_map(|resource_metrics| resource_metrics.scope_metrics())
+        .flat_map(|scope_metrics| scope_metrics.metrics())
+        .find(|metric| metric.name() == name)
+        .unwrap_or_else(|| panic!("Cannot find metric {name}"));
+
+    let AggregatedMetrics::U64(MetricData::Sum(sum)) = metric.data() else {
+        panic!("Metric {name} is not an u64 sum");
+    };
+    sum.data_points()
+        .map(|data_point| {
+            (
+                data_point.attributes().cloned().collect(),
+                data_point.value(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn command_metrics() {
+    let (provider, exporter) = meter_provider();
+    let metrics = OtelMetrics::new(provider.meter("test"));
+
+    metrics.on_timed_result(
+        &cmd("GET").arg("key"),
+        &Err(Error::Timeout("timeout".to_owned())),
+        Duration::from_millis(10),
+    );
+
+    let commands = sum_data_points(&provider, &exporter, "rustis.commands");
+    assert_eq!(
+        vec![(vec![KeyValue::new("db.operation.name", "GET")], 1)],
+        commands
+    );
+
+    let errors = sum_data_points(&provider, &exporter, "rustis.errors");
+    assert_eq!(1, errors.len());
+    assert_eq!(1, errors[0].1);
+    assert!(errors[0]
+        .0
+        .contains(&KeyValue::new("error.type", "timeout")));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn client_metrics() -> Result<()> {
+    log_try_init();
+    let (provider, exporter) = meter_provider();
+    let metrics = OtelMetrics::new(provider.meter("test"));
+
+    let mut config = get_default_addr().into_config()?;
+    config.layer(metrics.clone());
+    let client = Client::connect(config).await?;
+    metrics.observe_client(&client);
+
+    client.flushall(FlushingMode::Sync).await?;
+    client.set("key", "value").await?;
+    let _: String = client.get("key").await?;
+
+    let commands = sum_data_points(&provider, &exporter, "rustis.commands");
+    assert!(commands.contains(&(vec![KeyValue::new("db.operation.name", "SET")], 1)));
+    assert!(commands.contains(&(vec![KeyValue::new("db.operation.name", "GET")], 1)));
+
+    let bytes_sent = sum_data_points(&provider, &exporter, "rustis.node.bytes_sent");
+    assert_eq!(1, bytes_sent.len());
+    assert!(bytes_sent[0].1 > 0);
+
+    client.close().await?;
+
+    Ok(())
+}
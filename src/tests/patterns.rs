@@ -0,0 +1,1202 @@
+#[cfg(feature = "mock")]
+use crate::{
+    client::{Client, MockServerBehavior},
+    resp::Value,
+    RedisErrorKind,
+};
+use crate::{
+    commands::{
+        CallBuilder, FlushingMode, FunctionListOptions, GenericCommands, HashCommands,
+        ListCommands, ScriptingCommands, ServerCommands, SetCommands, SetCondition, SetExpiration,
+        SortedSetCommands, StreamCommands, StreamEntry, StringCommands, XAddOptions,
+        XGroupCreateOptions, XReadGroupOptions,
+    },
+    patterns::{
+        check_hash_tags, is_after, split_message, CacheAside, CacheWarmer, EvictionPolicy,
+        EvictionSample, EvictionSimulator, FunctionLibrary, HashFieldCache, HashFieldCacheStats,
+        KeySample, KeySampling, Leaderboard, LeaderboardEntry, LeaderboardPeriod,
+        LeaderboardScoring, LibraryStatus, ListUtils, MultiKeyAtomic, MultiQueueConsumer,
+        QueueOrdering, ReliableQueue, ReplayableChannel, StreamMaintainer, StreamMonitor,
+        TaggedQuery, TrimStrategy, UniqueCounter, UniqueCounterGranularity, WarmupKeys,
+        WarmupMethod, WarmupProgress,
+    },
+    sleep,
+    tests::get_test_client,
+    Error, Result,
+};
+#[cfg(feature = "redis-search")]
+use crate::{
+    commands::{
+        FtCreateOptions, FtFieldSchema, FtFieldType, FtIndexDataType, FtSearchOptions,
+        SearchCommands,
+    },
+    patterns::SearchReindexer,
+    tests::get_redis_stack_test_client,
+};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use serial_test::serial;
+use std::{
+    collections::HashSet,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Job {
+    id: u32,
+    name: String,
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn reliable_queue() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    let producer = ReliableQueue::<Job>::new(client, "jobs");
+    let consumer = ReliableQueue::<Job>::new(get_test_client().await?, "jobs")
+        .block_timeout(Duration::from_millis(100));
+
+    producer
+        .push(&Job {
+            id: 1,
+            name: "job1".to_owned(),
+        })
+        .await?;
+    producer
+        .push(&Job {
+            id: 2,
+            name: "job2".to_owned(),
+        })
+        .await?;
+    assert_eq!(2, producer.len().await?);
+
+    let mut deliveries = consumer.consume();
+    let delivery1 = deliveries.next().await.unwrap()?;
+    assert_eq!(1, delivery1.payload.id);
+    assert_eq!("job1", delivery1.payload.name);
+    assert_eq!(1, consumer.len().await?);
+    assert_eq!(1, consumer.processing_len().await?);
+
+    assert!(consumer.ack(&delivery1).await?);
+    assert!(!consumer.ack(&delivery1).await?);
+    assert_eq!(0, consumer.processing_len().await?);
+
+    let delivery2 = deliveries.next().await.unwrap()?;
+    assert_eq!(2, delivery2.payload.id);
+    assert!(consumer.pop().await?.is_none());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn reliable_queue_recover() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    let queue = ReliableQueue::<Job>::new(client, "jobs")
+        .visibility_timeout(Duration::from_millis(100))
+        .block_timeout(Duration::from_millis(100));
+
+    queue
+        .push(&Job {
+            id: 1,
+            name: "job1".to_owned(),
+        })
+        .await?;
+
+    let delivery = queue.pop().await?.unwrap();
+    assert_eq!(0, queue.recover().await?);
+    assert_eq!(1, queue.processing_len().await?);
+
+    sleep(Duration::from_millis(200)).await;
+
+    assert_eq!(1, queue.recover().await?);
+    assert_eq!(1, queue.len().await?);
+    assert_eq!(0, queue.processing_len().await?);
+    assert!(!queue.ack(&delivery).await?);
+
+    let redelivery = queue.pop().await?.unwrap();
+    assert_eq!(delivery.payload, redelivery.payload);
+    assert!(queue.ack(&redelivery).await?);
+
+    queue.purge().await?;
+    assert_eq!(0, queue.len().await?);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn reliable_queue_dead_letter() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    let queue = ReliableQueue::<Job>::new(client.clone(), "jobs")
+        .visibility_timeout(Duration::from_millis(100))
+        .block_timeout(Duration::from_millis(100));
+
+    client.lpush("{jobs}:queue", "not json").await?;
+
+    assert!(matches!(queue.pop().await, Err(Error::Client(_))));
+    assert_eq!(0, queue.processing_len().await?);
+    assert_eq!(1, queue.dead_len().await?);
+
+    sleep(Duration::from_millis(200)).await;
+
+    assert_eq!(0, queue.recover().await?);
+    assert_eq!(0, queue.len().await?);
+    assert!(queue.pop().await?.is_none());
+
+    queue.purge().await?;
+    assert_eq!(0, queue.dead_len().await?);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn stream_maintainer() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    // entries from 1970
+    for i in 1..=3 {
+        let _id: String = client
+            .xadd(
+                "mystream",
+                format!("{i}-0"),
+                ("i", i),
+                XAddOptions::default(),
+            )
+            .await?;
+    }
+    for i in 0..5 {
+        let _id: String = client
+            .xadd("mystream", "*", ("i", i), XAddOptions::default())
+            .await?;
+    }
+
+    let maintainer = StreamMaintainer::new(client.clone()).exact();
+    assert_eq!(
+        3,
+        maintainer
+            .trim_by_age("mystream", Duration::from_secs(3600))
+            .await?
+    );
+    assert_eq!(5, client.xlen("mystream").await?);
+
+    let mut trimmed = maintainer.trim_periodically(
+        "mystream",
+        TrimStrategy::MaxLen(2),
+        Duration::from_millis(10),
+    );
+    assert_eq!(3, trimmed.next().await.unwrap()?);
+    assert_eq!(0, trimmed.next().await.unwrap()?);
+    assert_eq!(2, client.xlen("mystream").await?);
+
+    // errors are reported, the trimming goes on
+    client.set("mystring", "value").await?;
+    let mut trimmed = maintainer.trim_periodically(
+        "mystring",
+        TrimStrategy::MinId("1-0".to_owned()),
+        Duration::from_millis(10),
+    );
+    assert!(trimmed.next().await.unwrap().is_err());
+    assert!(trimmed.next().await.unwrap().is_err());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn stream_monitor() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    client
+        .xgroup_create(
+            "mystream",
+            "mygroup",
+            "$",
+            XGroupCreateOptions::default().mk_stream(),
+        )
+        .await?;
+    for i in 0..3 {
+        let _id: String = client
+            .xadd("mystream", "*", ("i", i), XAddOptions::default())
+            .await?;
+    }
+
+    let monitor = StreamMonitor::new(client.clone());
+    let group_lag = monitor.group_lag("mystream", "mygroup").await?;
+    assert_eq!("mygroup", group_lag.group);
+    assert_eq!(Some(3), group_lag.lag);
+    assert_eq!(0, group_lag.pending);
+    assert!(group_lag.consumers.is_empty());
+
+    let results: Vec<(String, Vec<StreamEntry<String>>)> = client
+        .xreadgroup(
+            "mygroup",
+            "myconsumer",
+            XReadGroupOptions::default().count(2),
+            "mystream",
+            ">",
+        )
+        .await?;
+    assert_eq!(2, results[0].1.len());
+
+    let mut samples = monitor.sample_every("mystream", Duration::from_millis(10));
+    let group_lags = samples.next().await.unwrap()?;
+    assert_eq!(1, group_lags.len());
+    assert_eq!(Some(1), group_lags[0].lag);
+    assert_eq!(Some(2), group_lags[0].entries_read);
+    assert_eq!(2, group_lags[0].pending);
+    assert_eq!(1, group_lags[0].consumers.len());
+    assert_eq!("myconsumer", group_lags[0].consumers[0].name);
+    assert_eq!(2, group_lags[0].consumers[0].pending);
+    assert!(samples.next().await.unwrap().is_ok());
+
+    assert!(monitor.group_lag("mystream", "unknown").await.is_err());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn cache_aside() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    let cache = CacheAside::<Job>::new(client.clone());
+    let computations = AtomicUsize::new(0);
+    let compute = || async {
+        computations.fetch_add(1, Ordering::SeqCst);
+        sleep(Duration::from_millis(100)).await;
+        Ok(Job {
+            id: 1,
+            name: "job1".to_owned(),
+        })
+    };
+
+    // concurrent misses compute the value once
+    let (job1, job2) = futures_util::join!(
+        cache.get_or_compute("job", Duration::from_secs(10), compute),
+        cache.get_or_compute("job", Duration::from_secs(10), compute)
+    );
+    assert_eq!(job1?, job2?);
+    assert_eq!(1, computations.load(Ordering::SeqCst));
+
+    let job = cache
+        .get_or_compute("job", Duration::from_secs(10), compute)
+        .await?;
+    assert_eq!(1, job.id);
+    assert_eq!(1, computations.load(Ordering::SeqCst));
+
+    assert_eq!(Some(1), cache.take("job").await?.map(|j| j.id));
+    assert_eq!(None, cache.get("job").await?);
+    assert!(!cache.invalidate("job").await?);
+
+    // stale-while-revalidate
+    let cache =
+        CacheAside::<Job>::new(client.clone()).stale_while_revalidate(Duration::from_secs(10));
+    let job = Job {
+        id: 2,
+        name: "job2".to_owned(),
+    };
+    cache.set("job", &job, Duration::from_millis(50)).await?;
+    sleep(Duration::from_millis(100)).await;
+    assert_eq!(Some(2), cache.get("job").await?.map(|j| j.id));
+
+    let job = cache
+        .get_or_compute("job", Duration::from_secs(10), compute)
+        .await?;
+    assert_eq!(1, job.id);
+    assert_eq!(2, computations.load(Ordering::SeqCst));
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn multi_queue_consumer() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    client.lpush("queue:low", ["low1", "low2"]).await?;
+    client
+        .lpush("queue:high", ["high1", "high2", "high3"])
+        .await?;
+
+    // priority
+    let consumer =
+        MultiQueueConsumer::<String>::new(get_test_client().await?, ["queue:high", "queue:low"])
+            .batch_size(2)
+            .block_timeout(Duration::from_millis(100));
+
+    let items = consumer.pop().await?;
+    assert_eq!(2, items.len());
+    assert!(items.iter().all(|item| item.queue == "queue:high"));
+    assert_eq!("high1", items[0].payload);
+    assert_eq!("high2", items[1].payload);
+
+    let mut stream = consumer.consume();
+    let item = stream.next().await.unwrap()?;
+    assert_eq!(
+        ("queue:high", "high3"),
+        (item.queue.as_str(), item.payload.as_str())
+    );
+    let item = stream.next().await.unwrap()?;
+    assert_eq!(
+        ("queue:low", "low1"),
+        (item.queue.as_str(), item.payload.as_str())
+    );
+    drop(stream);
+    // not yielded items are not popped
+    assert_eq!(1, client.llen("queue:low").await?);
+
+    // round-robin
+    client.lpush("queue:high", ["high4", "high5"]).await?;
+    let consumer =
+        MultiQueueConsumer::<String>::new(get_test_client().await?, ["queue:high", "queue:low"])
+            .ordering(QueueOrdering::RoundRobin)
+            .block_timeout(Duration::from_millis(100));
+
+    let items = consumer.pop().await?;
+    assert_eq!("queue:high", items[0].queue);
+    let items = consumer.pop().await?;
+    assert_eq!("queue:low", items[0].queue);
+    assert_eq!("low2", items[0].payload);
+    let items = consumer.pop().await?;
+    assert_eq!("queue:high", items[0].queue);
+
+    // timeout
+    assert!(consumer.pop().await?.is_empty());
+
+    Ok(())
+}
+
+const CAS_SCRIPT: &str = r#"
+if redis.call('GET', KEYS[1]) ~= ARGV[1] then return 0 end
+redis.call('SET', KEYS[2], ARGV[1])
+redis.call('SET', KEYS[1], ARGV[2])
+return 1
+"#;
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn list_utils() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    let list_utils = ListUtils::new(client.clone());
+
+    assert!(list_utils.push_unique("src", "a").await?);
+    assert!(list_utils.push_unique("src", "b").await?);
+    assert!(!list_utils.push_unique("src", "a").await?);
+    assert!(list_utils.push_unique("src", 1).await?);
+    assert!(!list_utils.push_unique("src", "1").await?);
+    let elements: Vec<String> = client.lrange("src", 0, -1).await?;
+    assert_eq!(vec!["a", "b", "1"], elements);
+
+    let elements: Vec<String> = (0..25).map(|i| format!("e{i}")).collect();
+    client.rpush("src", elements.as_slice()).await?;
+    client.rpush("dst", "first").await?;
+
+    // 28 elements moved by chunks of 10, 10 & 8
+    assert_eq!(28, list_utils.move_all("src", "dst", 10).await?);
+    assert_eq!(0, client.llen("src").await?);
+    let moved: Vec<String> = client.lrange("dst", 0, -1).await?;
+    assert_eq!(29, moved.len());
+    assert_eq!(vec!["first", "a", "b", "1", "e0"], moved[..5]);
+    assert_eq!("e24", moved[28]);
+
+    assert_eq!(29, list_utils.move_all("dst", "src", 1).await?);
+    assert_eq!(0, list_utils.move_all("dst", "src", 0).await?);
+    assert_eq!(29, client.llen("src").await?);
+
+    let result = list_utils.move_all("src", "src", 10).await;
+    assert!(matches!(result, Err(Error::Client(_))));
+    assert_eq!(29, client.llen("src").await?);
+
+    assert_eq!(0, list_utils.truncate("src", 100).await?);
+    assert_eq!(19, list_utils.truncate("src", 10).await?);
+    let elements: Vec<String> = client.lrange("src", 0, -1).await?;
+    assert_eq!(10, elements.len());
+    assert_eq!("first", elements[0]);
+    assert_eq!("e5", elements[9]);
+
+    assert_eq!(10, list_utils.truncate("src", 0).await?);
+    assert_eq!(0, client.exists("src").await?);
+    assert_eq!(0, list_utils.truncate("unknown", 0).await?);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn multi_key_atomic() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+    client.set("{account}.balance", 100).await?;
+
+    let cas = MultiKeyAtomic::new(client.clone(), CAS_SCRIPT);
+
+    let swapped: bool = cas
+        .execute(["{account}.balance", "{account}.previous"], [100, 80])
+        .await?;
+    assert!(swapped);
+    let swapped: bool = cas
+        .execute(["{account}.balance", "{account}.previous"], [100, 60])
+        .await?;
+    assert!(!swapped);
+
+    // the script is sent again once flushed from the script cache
+    client.script_flush(FlushingMode::Sync).await?;
+    let swapped: bool = cas
+        .execute(["{account}.balance", "{account}.previous"], [80, 60])
+        .await?;
+    assert!(swapped);
+
+    let balance: i64 = client.get("{account}.balance").await?;
+    assert_eq!(60, balance);
+    let previous: i64 = client.get("{account}.previous").await?;
+    assert_eq!(80, previous);
+
+    Ok(())
+}
+
+#[cfg(feature = "mock")]
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn multi_key_atomic_script_cache() -> Result<()> {
+    let behavior = MockServerBehavior::new()
+        .reply("SCRIPT", Value::BulkString(b"sha1".to_vec()))
+        .error("EVALSHA", RedisErrorKind::NoScript, "No matching script.")
+        .reply("EVAL", Value::Integer(1))
+        .reply("EVALSHA", Value::Integer(0));
+    let client = Client::connect_mock(behavior).await?;
+
+    let cas = MultiKeyAtomic::new(client, CAS_SCRIPT);
+
+    // SCRIPT LOAD, then EVAL after NOSCRIPT
+    let swapped: bool = cas.execute(["key1", "key2"], ["a", "b"]).await?;
+    assert!(swapped);
+
+    // cached SHA1
+    let swapped: bool = cas.clone().execute(["key1", "key2"], ["a", "b"]).await?;
+    assert!(!swapped);
+
+    Ok(())
+}
+
+fn key_slices<'a>(keys: &[&'a str]) -> Vec<&'a [u8]> {
+    keys.iter().map(|k| k.as_bytes()).collect()
+}
+
+#[test]
+fn hash_tags() {
+    assert!(check_hash_tags(&key_slices(&[]), "test").is_ok());
+    assert!(check_hash_tags(&key_slices(&["key"]), "test").is_ok());
+    assert!(check_hash_tags(&key_slices(&["{user:1}.name", "{user:1}.email"]), "test").is_ok());
+    assert!(check_hash_tags(&key_slices(&["{user:1}.name", "email"]), "test").is_err());
+    assert!(check_hash_tags(&key_slices(&["{user:1}.name", "{user:2}.email"]), "test").is_err());
+    assert!(check_hash_tags(&key_slices(&["{}.name", "{}.email"]), "test").is_err());
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn unique_counter() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    let day = |day: u64| UNIX_EPOCH + Duration::from_secs(day * 86400 + 3600);
+    let counter = UniqueCounter::new(client.clone(), "visitors", UniqueCounterGranularity::Daily);
+
+    assert_eq!("{visitors}:d:20000", counter.bucket_key(day(20000))?);
+
+    assert!(counter.add_at(day(20000), ["alice", "bob"]).await?);
+    assert!(counter.add_at(day(20001), ["bob", "carol"]).await?);
+    assert!(!counter.add_at(day(20001), "carol").await?);
+    assert!(counter.add_at(day(20002), ["carol", "dave", "eve"]).await?);
+
+    assert_eq!(2, counter.count_at(day(20001)).await?);
+    assert_eq!(3, counter.rolling_count_at(day(20001), 2).await?);
+    assert_eq!(5, counter.rolling_count_at(day(20002), 3).await?);
+    assert_eq!(2, counter.count_new_at(day(20002), 2).await?);
+    assert!(counter.rolling_count_at(day(20002), 0).await.is_err());
+
+    // buckets expire once the retention has elapsed after their end
+    let counter = UniqueCounter::new(client.clone(), "devices", UniqueCounterGranularity::Hourly)
+        .retention(Duration::from_secs(3600));
+    counter.add_at(day(20000), "phone").await?;
+    assert_eq!(0, client.exists(counter.bucket_key(day(20000))?).await?);
+
+    assert!(counter.add(["phone", "tablet"]).await?);
+    assert_eq!(2, counter.count().await?);
+    assert!(client.pttl(counter.bucket_key(SystemTime::now())?).await? > 0);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn tagged_query() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    let red: Vec<String> = (0..500).map(|i| format!("p{i}")).collect();
+    let xl: Vec<String> = (250..1000).map(|i| format!("p{i}")).collect();
+    client.sadd("color:red", red).await?;
+    client.sadd("size:xl", xl).await?;
+
+    let query = TaggedQuery::new(client.clone(), ["color:red", "size:xl"])
+        .page_size(50)
+        .result_ttl(Duration::from_secs(10));
+    assert_eq!(250, query.count(0).await?);
+    assert_eq!(100, query.count(100).await?);
+
+    let result = query.execute().await?;
+    assert_eq!(250, result.len);
+    assert!(client.pttl(&result.key).await? > 0);
+
+    let mut members = HashSet::<String>::new();
+    let mut pages = 0;
+    let mut cursor = 0;
+    loop {
+        let (next_cursor, page) = query.page(&result.key, cursor).await?;
+        members.extend(page);
+        pages += 1;
+        if next_cursor == 0 {
+            break;
+        }
+        cursor = next_cursor;
+    }
+    assert_eq!(250, members.len());
+    assert!(members.contains("p250") && members.contains("p499"));
+    assert!(pages > 1);
+
+    query.discard(&result.key).await?;
+    assert_eq!(0, client.exists(&result.key).await?);
+
+    // empty intersection
+    let result = TaggedQuery::new(client.clone(), ["color:red", "color:blue"])
+        .execute()
+        .await?;
+    assert_eq!(0, result.len);
+    let (cursor, page) = query.page::<String>(&result.key, 0).await?;
+    assert_eq!((0, 0), (cursor, page.len()));
+
+    assert!(TaggedQuery::new(client, Vec::<String>::new())
+        .count(0)
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn hash_field_cache() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    client
+        .hset(
+            "session:1",
+            [("user", "john"), ("theme", "dark"), ("visits", "3")],
+        )
+        .await?;
+
+    let cache = HashFieldCache::new(get_test_client().await?, get_test_client().await?).await?;
+
+    let user: Option<String> = cache.hget("session:1", "user").await?;
+    assert_eq!(Some("john".to_owned()), user);
+    assert_eq!(HashFieldCacheStats { hits: 0, misses: 1 }, cache.stats());
+
+    // only the missing fields are read from the server
+    let values: Vec<Option<String>> = cache
+        .hmget("session:1", &["user", "theme", "unknown"])
+        .await?;
+    assert_eq!(
+        vec![Some("john".to_owned()), Some("dark".to_owned()), None],
+        values
+    );
+    assert_eq!(HashFieldCacheStats { hits: 1, misses: 3 }, cache.stats());
+
+    let values: Vec<Option<String>> = cache
+        .hmget("session:1", &["user", "theme", "unknown"])
+        .await?;
+    assert_eq!(
+        vec![Some("john".to_owned()), Some("dark".to_owned()), None],
+        values
+    );
+    assert_eq!(HashFieldCacheStats { hits: 4, misses: 3 }, cache.stats());
+
+    let visits: u32 = cache.hget("session:1", "visits").await?;
+    assert_eq!(3, visits);
+
+    // a modification of the hash invalidates all its cached fields
+    client.hset("session:1", ("theme", "light")).await?;
+    let mut theme = Option::<String>::None;
+    for _ in 0..100 {
+        theme = cache.hget("session:1", "theme").await?;
+        if theme.as_deref() == Some("light") {
+            break;
+        }
+        sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(Some("light".to_owned()), theme);
+
+    let misses = cache.stats().misses;
+    let user: Option<String> = cache.hget("session:1", "user").await?;
+    assert_eq!(Some("john".to_owned()), user);
+    assert_eq!(misses + 1, cache.stats().misses);
+
+    // a flush invalidates all the keys
+    client.flushdb(FlushingMode::Sync).await?;
+    let mut user = Some(String::new());
+    for _ in 0..100 {
+        user = cache.hget("session:1", "user").await?;
+        if user.is_none() {
+            break;
+        }
+        sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(None, user);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn leaderboard() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    let day = |day: u64| UNIX_EPOCH + Duration::from_secs(day * 86400 + 3600);
+    let entry = |rank: usize, member: &str, score: f64| LeaderboardEntry {
+        rank,
+        member: member.to_owned(),
+        score,
+    };
+    // 2024-02-26 (Monday), 2024-02-29, 2024-03-01 & 2024-03-03 (Sunday)
+    let (monday, feb29, mar1, sunday) = (day(19779), day(19782), day(19783), day(19785));
+
+    let leaderboard = Leaderboard::new(client.clone(), "game");
+    assert_eq!(
+        "{game}:d:19782",
+        leaderboard.board_key(LeaderboardPeriod::Daily, feb29)?
+    );
+    assert_eq!(
+        "{game}:w:2826",
+        leaderboard.board_key(LeaderboardPeriod::Weekly, sunday)?
+    );
+    assert_eq!(
+        "{game}:m:649",
+        leaderboard.board_key(LeaderboardPeriod::Monthly, feb29)?
+    );
+    assert_eq!(
+        "{game}:m:650",
+        leaderboard.board_key(LeaderboardPeriod::Monthly, mar1)?
+    );
+
+    assert_eq!(10., leaderboard.record_at(monday, "alice", 10.).await?);
+    leaderboard.record_at(monday, "bob", 5.).await?;
+    assert_eq!(15., leaderboard.record_at(feb29, "alice", 5.).await?);
+    leaderboard.record_at(feb29, "carol", 30.).await?;
+    leaderboard.record_at(feb29, "dave", 1.).await?;
+    leaderboard.record_at(mar1, "bob", 20.).await?;
+
+    assert!(leaderboard
+        .rollup(LeaderboardPeriod::Daily, feb29)
+        .await
+        .is_err());
+    assert_eq!(
+        4,
+        leaderboard.rollup(LeaderboardPeriod::Weekly, mar1).await?
+    );
+    assert_eq!(
+        4,
+        leaderboard
+            .rollup(LeaderboardPeriod::Monthly, feb29)
+            .await?
+    );
+    assert_eq!(
+        1,
+        leaderboard.rollup(LeaderboardPeriod::Monthly, mar1).await?
+    );
+
+    assert_eq!(
+        vec![entry(0, "carol", 30.), entry(1, "bob", 25.)],
+        leaderboard.top(LeaderboardPeriod::Weekly, mar1, 2).await?
+    );
+    assert_eq!(
+        vec![entry(0, "carol", 30.), entry(1, "alice", 15.)],
+        leaderboard
+            .top(LeaderboardPeriod::Monthly, feb29, 2)
+            .await?
+    );
+    assert_eq!(
+        Some(2),
+        leaderboard
+            .rank(LeaderboardPeriod::Weekly, mar1, "alice")
+            .await?
+    );
+    assert_eq!(
+        vec![
+            entry(1, "bob", 25.),
+            entry(2, "alice", 15.),
+            entry(3, "dave", 1.)
+        ],
+        leaderboard
+            .around(LeaderboardPeriod::Weekly, mar1, "alice", 1)
+            .await?
+    );
+    assert!(leaderboard
+        .around(LeaderboardPeriod::Weekly, mar1, "eve", 1)
+        .await?
+        .is_empty());
+    assert_eq!(
+        Some(100.),
+        leaderboard
+            .percentile(LeaderboardPeriod::Weekly, mar1, "carol")
+            .await?
+    );
+    assert_eq!(
+        Some(25.),
+        leaderboard
+            .percentile(LeaderboardPeriod::Weekly, mar1, "dave")
+            .await?
+    );
+    assert_eq!(
+        None,
+        leaderboard
+            .percentile(LeaderboardPeriod::Weekly, mar1, "eve")
+            .await?
+    );
+
+    assert_eq!(
+        2,
+        leaderboard
+            .store_top(LeaderboardPeriod::Weekly, mar1, 2)
+            .await?
+    );
+    assert_eq!(
+        2,
+        client
+            .zcard(leaderboard.top_key(LeaderboardPeriod::Weekly, mar1)?)
+            .await?
+    );
+
+    // best scores, recent days prevailing
+    let leaderboard = Leaderboard::new(client.clone(), "arcade")
+        .scoring(LeaderboardScoring::Best)
+        .decay(0.5);
+    assert_eq!(10., leaderboard.record_at(monday, "alice", 10.).await?);
+    assert_eq!(10., leaderboard.record_at(monday, "alice", 5.).await?);
+    assert_eq!(8., leaderboard.record_at(sunday, "bob", 8.).await?);
+
+    assert_eq!(
+        2,
+        leaderboard
+            .rollup(LeaderboardPeriod::Weekly, sunday)
+            .await?
+    );
+    assert_eq!(
+        vec![entry(0, "bob", 8.), entry(1, "alice", 10. / 64.)],
+        leaderboard
+            .top(LeaderboardPeriod::Weekly, sunday, 5)
+            .await?
+    );
+
+    let mut rollups =
+        leaderboard.rollup_periodically(LeaderboardPeriod::Daily, Duration::from_secs(1));
+    assert!(rollups.next().await.unwrap().is_err());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn replayable_channel() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    let channel = ReplayableChannel::<Job>::new(client, "jobs");
+    let job = |id: u32| Job {
+        id,
+        name: format!("job{id}"),
+    };
+
+    let id1 = channel.publish(&job(1)).await?;
+    let id2 = channel.publish(&job(2)).await?;
+    assert_eq!(2, channel.len().await?);
+
+    // replay from the first message, then live messages
+    let mut messages = channel.subscribe_from(&id1).await?;
+    let mut live_messages = channel.subscribe().await?;
+    let id3 = channel.publish(&job(3)).await?;
+
+    let message = messages.next().await.unwrap()?;
+    assert_eq!(id2, message.id);
+    assert_eq!(job(2), message.payload);
+    let message = messages.next().await.unwrap()?;
+    assert_eq!(id3, message.id);
+    assert_eq!(job(3), message.payload);
+
+    let message = live_messages.next().await.unwrap()?;
+    assert_eq!(id3, message.id);
+
+    // replay of the whole stream
+    let mut messages = channel.subscribe_from("0").await?;
+    for id in [id1, id2, id3] {
+        assert_eq!(id, messages.next().await.unwrap()?.id);
+    }
+
+    channel.purge().await?;
+    assert_eq!(0, channel.len().await?);
+
+    Ok(())
+}
+
+#[test]
+fn replayable_channel_stream_ids() {
+    assert!(is_after("1-1", "1-0"));
+    assert!(is_after("2-0", "1-5"));
+    assert!(is_after("10-0", "9-0"));
+    assert!(is_after("1-0", "0"));
+    assert!(!is_after("1-0", "1-0"));
+    assert!(!is_after("1-0", "1-1"));
+
+    assert_eq!(
+        Some(("1-0", &b"{\"a\": 1}"[..])),
+        split_message(b"1-0 {\"a\": 1}")
+    );
+    assert_eq!(None, split_message(b"payload"));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn eviction_simulator() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    for i in 0..10 {
+        client.set(format!("key{i}"), "value").await?;
+    }
+    client
+        .set_with_options(
+            "volatile",
+            "value",
+            SetCondition::None,
+            SetExpiration::Ex(60),
+            false,
+        )
+        .await?;
+
+    for sampling in [KeySampling::Scan, KeySampling::RandomKey] {
+        let sample = EvictionSimulator::new(client.clone())
+            .sample_size(100)
+            .sampling(sampling)
+            .sample()
+            .await?;
+        assert_eq!(11, sample.db_size);
+        assert!(!sample.keys.is_empty());
+        assert!(sample.keys.iter().all(|k| k.size > 0));
+        assert!(sample.estimated_memory() > 0);
+    }
+
+    let sample = EvictionSimulator::new(client.clone())
+        .sample_size(100)
+        .sampling(KeySampling::Scan)
+        .sample()
+        .await?;
+    assert_eq!(11, sample.keys.len());
+    let volatile = sample.keys.iter().find(|k| k.key == "volatile").unwrap();
+    assert!(volatile.ttl.is_some());
+
+    let estimate = sample.simulate(EvictionPolicy::VolatileTtl, usize::MAX)?;
+    assert_eq!(vec!["volatile".to_owned()], estimate.evicted_keys);
+    assert!(!estimate.enough_candidates);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn cache_warmer() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    for i in 0..10 {
+        client.set(format!("key{i}"), "value").await?;
+    }
+    client.set("other", "value").await?;
+
+    let mut keys: Vec<String> = (0..10).map(|i| format!("key{i}")).collect();
+    keys.push("missing".to_owned());
+
+    let warmer = CacheWarmer::new(client.clone())
+        .batch_size(4)
+        .max_keys_per_second(100);
+    let progress: Vec<WarmupProgress> = warmer
+        .warm(WarmupKeys::List(keys.clone()))
+        .map(|p| p.unwrap())
+        .collect()
+        .await;
+    assert_eq!(
+        vec![4, 8, 11],
+        progress
+            .iter()
+            .map(|p| p.processed_keys)
+            .collect::<Vec<_>>()
+    );
+    let last = progress.last().unwrap();
+    assert_eq!(10, last.existing_keys);
+    assert_eq!(Some(11), last.total_keys);
+    // rate limited
+    assert!(last.elapsed >= Duration::from_millis(100));
+
+    let progress: Vec<WarmupProgress> = CacheWarmer::new(client.clone())
+        .method(WarmupMethod::Get)
+        .warm(WarmupKeys::Pattern("key*".to_owned()))
+        .map(|p| p.unwrap())
+        .collect()
+        .await;
+    let last = progress.last().unwrap();
+    assert_eq!(10, last.processed_keys);
+    assert_eq!(10, last.existing_keys);
+    assert_eq!(None, last.total_keys);
+
+    let mut warmup = warmer.warm(WarmupKeys::List(keys));
+    let abort_handle = warmup.abort_handle();
+    assert_eq!(4, warmup.next().await.unwrap()?.processed_keys);
+    abort_handle.abort();
+    assert!(warmup.next().await.is_none());
+    assert!(abort_handle.is_aborted());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn function_library() -> Result<()> {
+    let client = get_test_client().await?;
+    client.function_flush(FlushingMode::Sync).await?;
+
+    let v1 = FunctionLibrary::new(
+        "rustis_counters",
+        "1",
+        "redis.register_function('rustis_incr', function(keys, args) return redis.call('INCRBY', keys[1], 1) end)",
+    )?;
+    let v2 = FunctionLibrary::new(
+        "rustis_counters",
+        "2",
+        "redis.register_function('rustis_incr', function(keys, args) return redis.call('INCRBY', keys[1], 2) end)",
+    )?;
+
+    let deployments = v1.ensure_loaded(&client).await?;
+    assert_eq!(1, deployments.len());
+    assert_eq!(LibraryStatus::Missing, deployments[0].previous);
+    assert!(deployments[0].loaded());
+
+    let deployments = v1.ensure_loaded(&client).await?;
+    assert_eq!(LibraryStatus::UpToDate, deployments[0].previous);
+    assert!(!deployments[0].loaded());
+
+    client.del("counter").await?;
+    let value: i64 = client
+        .fcall(CallBuilder::function("rustis_incr").keys("counter"))
+        .await?;
+    assert_eq!(1, value);
+
+    let deployments = v2.ensure_loaded(&client).await?;
+    assert_eq!(
+        LibraryStatus::Outdated {
+            version: Some("1".to_owned())
+        },
+        deployments[0].previous
+    );
+    let value: i64 = client
+        .fcall(CallBuilder::function("rustis_incr").keys("counter"))
+        .await?;
+    assert_eq!(3, value);
+
+    let libraries = client
+        .function_list(FunctionListOptions::default().with_code())
+        .await?;
+    assert_eq!(1, libraries.len());
+    assert_eq!(
+        Some("2"),
+        libraries[0]
+            .library_code
+            .as_deref()
+            .and_then(FunctionLibrary::parse_version)
+    );
+
+    // library loaded without version header
+    client.function_flush(FlushingMode::Sync).await?;
+    let _: String = client
+        .function_load(
+            false,
+            "#!lua name=rustis_counters\nredis.register_function('rustis_incr', function() return 0 end)",
+        )
+        .await?;
+    let deployments = v2.ensure_loaded(&client).await?;
+    assert_eq!(
+        LibraryStatus::Outdated { version: None },
+        deployments[0].previous
+    );
+
+    client.function_flush(FlushingMode::Sync).await?;
+
+    Ok(())
+}
+
+#[test]
+fn function_library_code() -> Result<()> {
+    let library = FunctionLibrary::new("my_lib", "1.2.0", "return 1")?;
+    assert_eq!(
+        "#!lua name=my_lib\n-- version: 1.2.0\nreturn 1",
+        library.code()
+    );
+    assert_eq!(
+        Some("1.2.0"),
+        FunctionLibrary::parse_version(&library.code())
+    );
+    assert_eq!(
+        None,
+        FunctionLibrary::parse_version("#!lua name=my_lib\nreturn 1")
+    );
+
+    assert!(FunctionLibrary::new("my-lib", "1", "").is_err());
+    assert!(FunctionLibrary::new("", "1", "").is_err());
+    assert!(FunctionLibrary::new("my_lib", "1\n2", "").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn eviction_simulation() -> Result<()> {
+    let key = |key: &str, ttl: Option<u64>, idle_time: u64| KeySample {
+        key: key.to_owned(),
+        size: 100,
+        ttl: ttl.map(Duration::from_secs),
+        idle_time: Some(Duration::from_secs(idle_time)),
+        freq: None,
+    };
+    // each sampled key stands for 10 keys
+    let sample = EvictionSample {
+        keys: vec![
+            key("recent", None, 1),
+            key("old", None, 1000),
+            key("expiring_soon", Some(10), 5),
+            key("expiring_later", Some(1000), 500),
+        ],
+        db_size: 40,
+    };
+    assert_eq!(4000, sample.estimated_memory());
+
+    let estimate = sample.simulate(EvictionPolicy::AllKeysLru, 1500)?;
+    assert_eq!(vec!["old", "expiring_later"], estimate.evicted_keys);
+    assert_eq!(20, estimate.estimated_evicted_keys);
+    assert_eq!(2000, estimate.estimated_freed_bytes);
+    assert!(estimate.enough_candidates);
+
+    let estimate = sample.simulate(EvictionPolicy::VolatileTtl, 1500)?;
+    assert_eq!(
+        vec!["expiring_soon", "expiring_later"],
+        estimate.evicted_keys
+    );
+
+    let estimate = sample.simulate(EvictionPolicy::VolatileLru, 3000)?;
+    assert_eq!(
+        vec!["expiring_later", "expiring_soon"],
+        estimate.evicted_keys
+    );
+    assert!(!estimate.enough_candidates);
+
+    // access frequencies are only reported with an LFU policy
+    assert!(sample.simulate(EvictionPolicy::AllKeysLfu, 1500).is_err());
+
+    Ok(())
+}
+
+#[cfg(feature = "redis-search")]
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn search_reindexer() -> Result<()> {
+    let client = get_redis_stack_test_client().await?;
+    client.flushall(FlushingMode::Sync).await?;
+    client
+        .hset("product:1", [("name", "apple"), ("color", "red")])
+        .await?;
+    client
+        .hset("product:2", [("name", "banana"), ("color", "yellow")])
+        .await?;
+
+    let options = || {
+        FtCreateOptions::default()
+            .on(FtIndexDataType::Hash)
+            .prefix("product:")
+    };
+    let reindexer = SearchReindexer::new(client.clone()).poll_interval(Duration::from_millis(10));
+
+    let first = reindexer
+        .reindex(
+            "products",
+            options(),
+            [FtFieldSchema::identifier("name").field_type(FtFieldType::Text)],
+        )
+        .await?;
+    assert_eq!(None, first.previous_index);
+
+    let second = reindexer
+        .reindex(
+            "products",
+            options(),
+            [
+                FtFieldSchema::identifier("name").field_type(FtFieldType::Text),
+                FtFieldSchema::identifier("color").field_type(FtFieldType::Tag),
+            ],
+        )
+        .await?;
+    assert_eq!(Some(first.index.clone()), second.previous_index);
+
+    // the alias points to the new index, which indexes the existing documents
+    let result = client
+        .ft_search("products", "@color:{yellow}", FtSearchOptions::default())
+        .await?;
+    assert_eq!(1, result.total_results);
+    assert_eq!("product:2", result.results[0].document_id);
+
+    // the previous index has been dropped, not its documents
+    let indexes: Vec<String> = client.ft_list().await?;
+    assert_eq!(vec![second.index], indexes);
+    assert_eq!(2, client.dbsize().await?);
+
+    client.ft_aliasdel("products").await?;
+
+    Ok(())
+}
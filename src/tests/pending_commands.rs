@@ -0,0 +1,66 @@
+use crate::{client::PendingCommands, Error, Result};
+use std::time::Duration;
+
+#[test]
+fn fail_on_queue_full() -> Result<()> {
+    let pending_commands = PendingCommands::new(3, true);
+
+    let guard1 = pending_commands.try_acquire(1)?;
+    let _guard2 = pending_commands.try_acquire(2)?;
+    assert_eq!(3, pending_commands.count());
+    assert!(matches!(
+        pending_commands.try_acquire(1),
+        Err(Error::QueueFull)
+    ));
+
+    drop(guard1);
+    assert_eq!(2, pending_commands.count());
+    let _guard3 = pending_commands.try_acquire(1)?;
+
+    Ok(())
+}
+
+#[test]
+fn unbounded() -> Result<()> {
+    let pending_commands = PendingCommands::new(0, true);
+
+    let guards = (0..100)
+        .map(|_| pending_commands.try_acquire(10))
+        .collect::<Result<Vec<_>>>()?;
+    assert_eq!(1000, pending_commands.count());
+
+    drop(guards);
+    assert_eq!(0, pending_commands.count());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn backpressure() -> Result<()> {
+    let pending_commands = PendingCommands::new(2, false);
+
+    // a batch larger than the bound takes the whole queue
+    let guard = pending_commands.acquire(5, Duration::ZERO).await?;
+    assert_eq!(5, pending_commands.count());
+
+    let result = crate::timeout(
+        Duration::from_millis(10),
+        pending_commands.acquire(1, Duration::ZERO),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::Timeout(_))));
+
+    // the wait is bounded by the command timeout
+    let result = pending_commands.acquire(1, Duration::from_millis(10)).await;
+    assert!(matches!(result, Err(Error::Timeout(_))));
+    assert_eq!(5, pending_commands.count());
+
+    drop(guard);
+    let _guard = pending_commands
+        .acquire(1, Duration::from_millis(10))
+        .await?;
+    assert_eq!(1, pending_commands.count());
+
+    Ok(())
+}
@@ -1,8 +1,10 @@
 use crate::{
-    client::BatchPreparedCommand,
+    client::{split_commands, BatchPreparedCommand},
     commands::{FlushingMode, ServerCommands, StringCommands},
     resp::{cmd, Value},
-    tests::{get_cluster_test_client, get_test_client},
+    tests::{
+        get_cluster_test_client, get_default_config, get_test_client, get_test_client_with_config,
+    },
     Result,
 };
 use serial_test::serial;
@@ -47,6 +49,47 @@ async fn error() -> Result<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn execute_chunked() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    let mut pipeline = client.create_pipeline();
+    for i in 0..10 {
+        pipeline.set(format!("key{i}"), i).forget();
+        pipeline.get::<_, ()>(format!("key{i}")).queue();
+    }
+
+    let values: Vec<u32> = pipeline.execute_chunked(3, 100).await?;
+    assert_eq!((0..10).collect::<Vec<_>>(), values);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn max_batch_commands() -> Result<()> {
+    let mut config = get_default_config()?;
+    config.max_batch_commands = 3;
+    let client = get_test_client_with_config(config).await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    let mut pipeline = client.create_pipeline();
+    pipeline.set("key1", "value1").forget();
+    pipeline.set("key2", "value2").forget();
+    pipeline.get::<_, ()>("key1").queue();
+    pipeline.get::<_, ()>("key2").queue();
+
+    let (value1, value2): (String, String) = pipeline.execute().await?;
+    assert_eq!("value1", value1);
+    assert_eq!("value2", value2);
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
@@ -66,3 +109,38 @@ async fn pipeline_on_cluster() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn split_into_chunks() {
+    let commands = || {
+        (0..5)
+            .map(|i| cmd("SET").arg(format!("key{i}")).arg("value"))
+            .collect::<Vec<_>>()
+    };
+
+    let chunks = split_commands(commands(), 0, 0);
+    assert_eq!(vec![5], chunks.iter().map(Vec::len).collect::<Vec<_>>());
+
+    let chunks = split_commands(commands(), 2, 0);
+    assert_eq!(
+        vec![2, 2, 1],
+        chunks.iter().map(Vec::len).collect::<Vec<_>>()
+    );
+
+    // "*3\r\n$3\r\nSET\r\n$4\r\nkey0\r\n$5\r\nvalue\r\n" => 35 bytes
+    let chunks = split_commands(commands(), 0, 70);
+    assert_eq!(
+        vec![2, 2, 1],
+        chunks.iter().map(Vec::len).collect::<Vec<_>>()
+    );
+
+    // a command bigger than max_bytes is sent alone
+    let chunks = split_commands(commands(), 0, 10);
+    assert_eq!(vec![1; 5], chunks.iter().map(Vec::len).collect::<Vec<_>>());
+
+    let chunks = split_commands(commands(), 4, 70);
+    assert_eq!(
+        vec![2, 2, 1],
+        chunks.iter().map(Vec::len).collect::<Vec<_>>()
+    );
+}
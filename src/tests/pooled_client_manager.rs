@@ -1,7 +1,11 @@
 use crate::{
-    client::PooledClientManager, commands::StringCommands, tests::get_default_addr, Result,
+    client::{Client, IntoConfig, PooledClientManager},
+    commands::{ClientListOptions, ConnectionCommands, StringCommands},
+    tests::{get_default_addr, get_test_client},
+    Result,
 };
 use serial_test::serial;
+use std::time::Duration;
 
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
@@ -17,3 +21,62 @@ async fn pooled_client_manager() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn sweep_orphans() -> Result<()> {
+    let mut config = get_default_addr().into_config()?;
+    config.connection_name = "sweep_orphans".to_owned();
+
+    // leaked connection, not returned to any pool
+    let leaked_client = Client::connect(config.clone()).await?;
+    let leaked_client_id = leaked_client.client_id().await?;
+    let other_client = get_test_client().await?;
+
+    // connection of another service whose name starts with the same prefix
+    let mut other_config = config.clone();
+    other_config.connection_name = "sweep_orphans2".to_owned();
+    let other_service_client = Client::connect(other_config).await?;
+    let other_service_client_id = other_service_client.client_id().await?;
+
+    let manager = PooledClientManager::new(config)?;
+    let num_killed = manager.sweep_orphans(Duration::ZERO).await?;
+    assert_eq!(1, num_killed);
+
+    let client_list = other_client
+        .client_list(ClientListOptions::default().client_ids([leaked_client_id]))
+        .await?;
+    assert!(client_list
+        .client_infos
+        .iter()
+        .all(|client_info| client_info.id != leaked_client_id));
+
+    let client_list = other_client
+        .client_list(ClientListOptions::default().client_ids([other_service_client_id]))
+        .await?;
+    assert_eq!(1, client_list.client_infos.len());
+
+    let manager = PooledClientManager::new(get_default_addr())?;
+    assert!(manager.sweep_orphans(Duration::ZERO).await.is_err());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn sweep_duplicates() -> Result<()> {
+    let mut config = get_default_addr().into_config()?;
+    config.connection_name = "sweep_duplicates".to_owned();
+
+    let _client1 = Client::connect(config.clone()).await?;
+    let _client2 = Client::connect(config.clone()).await?;
+    let _client3 = Client::connect(config.clone()).await?;
+
+    let manager = PooledClientManager::new(config)?;
+    assert_eq!(0, manager.sweep_duplicates(3).await?);
+    assert_eq!(2, manager.sweep_duplicates(1).await?);
+
+    Ok(())
+}
@@ -1,21 +1,25 @@
 use crate::{
-    client::{Client, IntoConfig, ReconnectionConfig},
+    client::{Backoff, Client, IntoConfig, PubSubCodec, ReconnectionConfig},
+    cluster::hash_slot,
     commands::{
-        ClientKillOptions, ClusterCommands, ClusterShardResult, ConnectionCommands, FlushingMode,
-        ListCommands, PubSubChannelsOptions, PubSubCommands, ServerCommands, StringCommands,
+        ClientKillOptions, ClientPauseMode, ClusterCommands, ClusterShardResult,
+        ConnectionCommands, FlushingMode, ListCommands, PubSubChannelsOptions, PubSubCommands,
+        ServerCommands, StringCommands,
     },
-    spawn,
+    sleep, spawn,
     tests::{
         get_cluster_test_client, get_default_addr, get_default_config, get_test_client,
         get_test_client_with_config, log_try_init,
     },
-    Result,
+    Error, Result,
 };
-use futures_util::{FutureExt, StreamExt, TryStreamExt};
+use futures_util::{poll, FutureExt, StreamExt, TryStreamExt};
+use serde::de::{value::StrDeserializer, DeserializeOwned};
 use serial_test::serial;
 use std::{
     collections::{HashMap, HashSet},
     future::IntoFuture,
+    time::Duration,
 };
 
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
@@ -61,6 +65,214 @@ async fn pubsub() -> Result<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn cancelled_subscribe() -> Result<()> {
+    log_try_init();
+
+    let pub_sub_client = get_test_client().await?;
+    let regular_client = get_test_client().await?;
+
+    // SUBSCRIBE is sent on the first poll, the future is dropped before its confirmation
+    let mut future = Box::pin(pub_sub_client.subscribe("mychannel"));
+    assert!(poll!(&mut future).is_pending());
+    drop(future);
+    sleep(Duration::from_millis(100)).await;
+
+    // the cancelled subscription does not prevent a new one
+    let mut pub_sub_stream = pub_sub_client.subscribe("mychannel").await?;
+    regular_client.publish("mychannel", "mymessage").await?;
+
+    let message = pub_sub_stream.next().await.unwrap()?;
+    assert_eq!(b"mychannel".to_vec(), message.channel);
+    assert_eq!(b"mymessage".to_vec(), message.payload);
+
+    pub_sub_stream.close().await?;
+
+    Ok(())
+}
+
+#[cfg(feature = "json")]
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn typed_pubsub() -> Result<()> {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Event {
+        id: u64,
+        name: String,
+    }
+
+    let pub_sub_client = get_test_client().await?;
+    let regular_client = get_test_client().await?;
+
+    regular_client.flushdb(FlushingMode::Sync).await?;
+
+    let mut stream = pub_sub_client
+        .subscribe("mychannel")
+        .await?
+        .into_typed::<Event>();
+    regular_client
+        .publish("mychannel", r#"{"id":1,"name":"first"}"#)
+        .await?;
+    regular_client.publish("mychannel", "not json").await?;
+    regular_client
+        .publish("mychannel", r#"{"id":2,"name":"second"}"#)
+        .await?;
+
+    let message = stream.next().await.unwrap()?;
+    assert_eq!(b"mychannel".to_vec(), message.channel);
+    assert_eq!(
+        Event {
+            id: 1,
+            name: "first".to_owned()
+        },
+        message.payload
+    );
+
+    // a bad payload does not terminate the stream
+    assert!(stream.next().await.unwrap().is_err());
+
+    let message = stream.next().await.unwrap()?;
+    assert_eq!(2, message.payload.id);
+
+    stream.close().await?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn typed_pubsub_with_codec() -> Result<()> {
+    struct Utf8Codec;
+
+    impl PubSubCodec for Utf8Codec {
+        fn decode<T: DeserializeOwned>(&self, payload: &[u8]) -> Result<T> {
+            let str = std::str::from_utf8(payload).map_err(|e| Error::Client(e.to_string()))?;
+            T::deserialize(StrDeserializer::<serde::de::value::Error>::new(str))
+                .map_err(|e| Error::Client(e.to_string()))
+        }
+    }
+
+    let pub_sub_client = get_test_client().await?;
+    let regular_client = get_test_client().await?;
+
+    regular_client.flushdb(FlushingMode::Sync).await?;
+
+    let mut stream = pub_sub_client
+        .subscribe("mychannel")
+        .await?
+        .into_typed_with::<String, _>(Utf8Codec);
+    regular_client
+        .publish("mychannel", b"\xFF".to_vec())
+        .await?;
+    regular_client.publish("mychannel", "mymessage").await?;
+
+    assert!(stream.next().await.unwrap().is_err());
+    let message = stream.next().await.unwrap()?;
+    assert_eq!("mymessage", message.payload);
+
+    stream.close().await?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn broadcast_pubsub() -> Result<()> {
+    let pub_sub_client = get_test_client().await?;
+    let regular_client = get_test_client().await?;
+
+    regular_client.flushdb(FlushingMode::Sync).await?;
+
+    let sender = pub_sub_client
+        .subscribe("mychannel")
+        .await?
+        .into_broadcast(2);
+    let mut receiver1 = sender.subscribe();
+    let mut receiver2 = sender.subscribe();
+
+    for i in 1..=3 {
+        regular_client.publish("mychannel", i).await?;
+        let message = receiver1.recv().await.unwrap()?;
+        assert_eq!(b"mychannel".to_vec(), message.channel);
+        assert_eq!(i.to_string().into_bytes(), message.payload);
+    }
+
+    // receiver2 did not keep up
+    assert!(matches!(
+        receiver2.recv().await,
+        Err(tokio::sync::broadcast::error::RecvError::Lagged(1))
+    ));
+    assert_eq!(b"2".to_vec(), receiver2.recv().await.unwrap()?.payload);
+    assert_eq!(b"3".to_vec(), receiver2.recv().await.unwrap()?.payload);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn broadcast_pubsub_receivers_only() -> Result<()> {
+    let pub_sub_client = get_test_client().await?;
+    let regular_client = get_test_client().await?;
+
+    regular_client.flushdb(FlushingMode::Sync).await?;
+
+    // the returned sender is dropped right away
+    let mut receiver = pub_sub_client
+        .subscribe("mychannel")
+        .await?
+        .into_broadcast(16)
+        .subscribe();
+
+    for i in 1..=2 {
+        regular_client.publish("mychannel", i).await?;
+        let message = receiver.recv().await.unwrap()?;
+        assert_eq!(i.to_string().into_bytes(), message.payload);
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn broadcast_pubsub_quiet_channel() -> Result<()> {
+    let pub_sub_client = get_test_client().await?;
+    let regular_client = get_test_client().await?;
+
+    regular_client.flushdb(FlushingMode::Sync).await?;
+
+    let receiver = pub_sub_client
+        .subscribe("mychannel")
+        .await?
+        .into_broadcast(16)
+        .subscribe();
+
+    let num_sub: HashMap<String, usize> = regular_client.pub_sub_numsub("mychannel").await?;
+    assert_eq!(Some(&1), num_sub.get("mychannel"));
+
+    // no message is published: the subscription is closed with the last receiver
+    drop(receiver);
+
+    let mut num_sub = 1;
+    for _ in 0..50 {
+        let num_subs: HashMap<String, usize> = regular_client.pub_sub_numsub("mychannel").await?;
+        num_sub = num_subs.get("mychannel").copied().unwrap_or_default();
+        if num_sub == 0 {
+            break;
+        }
+        sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(0, num_sub);
+
+    Ok(())
+}
+
 // #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 // #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 // #[serial]
@@ -350,6 +562,50 @@ async fn subscribe_to_multiple_shardchannels() -> Result<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn shard_subscriptions() -> Result<()> {
+    let pub_sub_client = get_cluster_test_client().await?;
+    let regular_client = get_cluster_test_client().await?;
+
+    let mut pub_sub_stream = pub_sub_client
+        .ssubscribe(["mychannel1{1}", "mychannel2{1}"])
+        .await?;
+
+    // routed to the master node owning the shard channel
+    let num_receivers = regular_client
+        .spublish("mychannel1{1}", "mymessage1")
+        .await?;
+    assert_eq!(1, num_receivers);
+
+    let slot = hash_slot("{1}");
+    {
+        let client_state = pub_sub_client.get_client_state();
+        let subscriptions = client_state.shard_subscriptions();
+        assert_eq!(vec![slot], subscriptions.slots().collect::<Vec<_>>());
+        assert_eq!(2, subscriptions.len());
+    }
+
+    pub_sub_stream.sunsubscribe("mychannel1{1}").await?;
+    assert_eq!(
+        vec![b"mychannel2{1}".as_slice()],
+        pub_sub_client
+            .get_client_state()
+            .shard_subscriptions()
+            .shard_channels(slot)
+            .collect::<Vec<_>>()
+    );
+
+    pub_sub_stream.close().await?;
+    assert!(pub_sub_client
+        .get_client_state()
+        .shard_subscriptions()
+        .is_empty());
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
@@ -537,17 +793,20 @@ async fn additional_sub() -> Result<()> {
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
+#[allow(deprecated)]
 async fn auto_resubscribe() -> Result<()> {
     let mut config = get_default_config()?;
     config.reconnection = ReconnectionConfig::new_constant(0, 100);
     let pub_sub_client = get_test_client_with_config(config).await?;
     let regular_client = get_test_client().await?;
 
-    let pub_sub_client_id = pub_sub_client.client_id().await?;
     let mut pub_sub_stream = pub_sub_client.subscribe("mychannel").await?;
     pub_sub_stream.psubscribe("o*").await?;
 
-    let mut on_reconnect = pub_sub_client.on_reconnect();
+    // subscriptions are made on the dedicated pub/sub connection
+    let dedicated_client = pub_sub_client.get_pub_sub_client().await?;
+    let pub_sub_client_id = dedicated_client.client_id().await?;
+    let mut on_reconnect = dedicated_client.on_reconnect();
 
     regular_client
         .client_kill(ClientKillOptions::default().id(pub_sub_client_id))
@@ -580,6 +839,95 @@ async fn auto_resubscribe() -> Result<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+#[allow(deprecated)]
+async fn notify_interruptions() -> Result<()> {
+    let mut config = get_default_config()?;
+    config.reconnection = ReconnectionConfig::new_constant(0, 100);
+    let pub_sub_client = get_test_client_with_config(config).await?;
+    let regular_client = get_test_client().await?;
+
+    let mut pub_sub_stream = pub_sub_client
+        .subscribe("mychannel")
+        .await?
+        .notify_interruptions(true);
+    pub_sub_stream.psubscribe("o*").await?;
+
+    let dedicated_client = pub_sub_client.get_pub_sub_client().await?;
+    let pub_sub_client_id = dedicated_client.client_id().await?;
+    let mut on_reconnect = dedicated_client.on_reconnect();
+
+    regular_client
+        .client_kill(ClientKillOptions::default().id(pub_sub_client_id))
+        .await?;
+
+    on_reconnect.recv().await.unwrap();
+
+    regular_client.publish("mychannel", "mymessage").await?;
+
+    // a single notification for the stream, before the new messages
+    let Some(Err(Error::SubscriptionInterrupted {
+        epoch,
+        since,
+        resumed_at,
+    })) = pub_sub_stream.next().await
+    else {
+        panic!("Expected a subscription interruption");
+    };
+    assert_eq!(1, epoch);
+    assert!(since <= resumed_at);
+
+    let message = pub_sub_stream.try_next().await?.unwrap();
+    assert_eq!(b"mychannel".to_vec(), message.channel);
+    assert_eq!(b"mymessage".to_vec(), message.payload);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn pub_sub_keepalive() -> Result<()> {
+    let mut config = get_default_config()?;
+    config.pub_sub_keepalive = Some(Duration::from_millis(100));
+    config.resilience.backoff = Backoff::constant(100);
+    let pub_sub_client = get_test_client_with_config(config).await?;
+    let regular_client = get_test_client().await?;
+
+    let mut pub_sub_stream = pub_sub_client
+        .subscribe("mychannel")
+        .await?
+        .notify_interruptions(true);
+    let dedicated_client = pub_sub_client.get_pub_sub_client().await?;
+    let mut on_reconnect = dedicated_client.on_reconnect();
+
+    // a quiet subscription is kept alive by the keepalive PINGs
+    sleep(Duration::from_millis(350)).await;
+    assert!(on_reconnect.try_recv().is_err());
+
+    regular_client.publish("mychannel", "mymessage").await?;
+    let message = pub_sub_stream.try_next().await?.unwrap();
+    assert_eq!(b"mymessage".to_vec(), message.payload);
+
+    // an unanswered PING triggers a reconnection
+    regular_client
+        .client_pause(500, ClientPauseMode::All)
+        .await?;
+    on_reconnect.recv().await.unwrap();
+
+    let Some(Err(Error::SubscriptionInterrupted { .. })) = pub_sub_stream.next().await else {
+        panic!("Expected a subscription interruption");
+    };
+
+    regular_client.publish("mychannel", "mymessage").await?;
+    let message = pub_sub_stream.try_next().await?.unwrap();
+    assert_eq!(b"mymessage".to_vec(), message.payload);
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
@@ -595,11 +943,13 @@ async fn no_auto_resubscribe() -> Result<()> {
     "regular".clone_into(&mut config.connection_name);
     let regular_client = Client::connect(config).await?;
 
-    let pub_sub_client_id = pub_sub_client.client_id().await?;
     let mut pub_sub_stream = pub_sub_client.subscribe("mychannel").await?;
     pub_sub_stream.psubscribe("o*").await?;
 
-    let mut on_reconnect = pub_sub_client.on_reconnect();
+    // subscriptions are made on the dedicated pub/sub connection
+    let dedicated_client = pub_sub_client.get_pub_sub_client().await?;
+    let pub_sub_client_id = dedicated_client.client_id().await?;
+    let mut on_reconnect = dedicated_client.on_reconnect();
 
     regular_client
         .client_kill(ClientKillOptions::default().id(pub_sub_client_id))
@@ -774,6 +1124,70 @@ async fn split() -> Result<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn split_subscription_count() -> Result<()> {
+    let pub_sub_client = get_test_client().await?;
+
+    let pub_sub_stream = pub_sub_client.create_pub_sub();
+    let (mut sink, _stream) = pub_sub_stream.split();
+
+    assert_eq!(0, sink.unsubscribe("mychannel1").await?);
+    assert_eq!(1, sink.subscribe("mychannel1").await?);
+    assert_eq!(3, sink.subscribe(["mychannel2", "mychannel3"]).await?);
+    assert_eq!(4, sink.psubscribe("o*").await?);
+    assert_eq!(3, sink.punsubscribe("o*").await?);
+    assert_eq!(1, sink.unsubscribe(["mychannel1", "mychannel2"]).await?);
+    assert_eq!(1, sink.ssubscribe("myshardchannel").await?);
+    assert_eq!(0, sink.sunsubscribe("myshardchannel").await?);
+    assert_eq!(0, sink.unsubscribe("mychannel3").await?);
+
+    sink.close().await?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn split_sunsubscribe() -> Result<()> {
+    let pub_sub_client = get_test_client().await?;
+    let regular_client = get_test_client().await?;
+
+    let pub_sub_stream = pub_sub_client.create_pub_sub();
+    let (mut sink, _stream) = pub_sub_stream.split();
+
+    sink.psubscribe("myshardchannel*").await?;
+    sink.ssubscribe(["myshardchannel1", "myshardchannel2"])
+        .await?;
+
+    let num_sub: HashMap<String, usize> = regular_client
+        .pub_sub_shardnumsub(["myshardchannel1", "myshardchannel2"])
+        .await?;
+    assert_eq!(Some(&1usize), num_sub.get("myshardchannel1"));
+    assert_eq!(Some(&1usize), num_sub.get("myshardchannel2"));
+
+    sink.sunsubscribe("myshardchannel1").await?;
+
+    // the shard channel is released while the pattern is kept
+    let num_sub: HashMap<String, usize> = regular_client
+        .pub_sub_shardnumsub(["myshardchannel1", "myshardchannel2"])
+        .await?;
+    assert_eq!(Some(&0usize), num_sub.get("myshardchannel1"));
+    assert_eq!(Some(&1usize), num_sub.get("myshardchannel2"));
+    assert_eq!(1, regular_client.pub_sub_numpat().await?);
+
+    sink.close().await?;
+
+    let num_sub: HashMap<String, usize> = regular_client
+        .pub_sub_shardnumsub("myshardchannel2")
+        .await?;
+    assert_eq!(Some(&0usize), num_sub.get("myshardchannel2"));
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
@@ -799,3 +1213,50 @@ async fn subscribe_multiple_times_to_the_same_channel() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn shared_pub_sub_connection() -> Result<()> {
+    let client = get_test_client().await?;
+    let clone = client.clone();
+
+    // cleanup
+    client.flushdb(FlushingMode::Sync).await?;
+
+    let mut pub_sub_stream1 = client.subscribe("mychannel1").await?;
+    let mut pub_sub_stream2 = clone.subscribe("mychannel2").await?;
+
+    // the connection of the client is still available for regular commands
+    client.set("key", "value").await?;
+    let value: String = clone.get("key").await?;
+    assert_eq!("value", value);
+
+    // all the clones share the same dedicated pub/sub connection
+    let client_id = client.client_id().await?;
+    let pub_sub_client_id = client.get_pub_sub_client().await?.client_id().await?;
+    assert_ne!(client_id, pub_sub_client_id);
+    assert_eq!(
+        pub_sub_client_id,
+        clone.get_pub_sub_client().await?.client_id().await?
+    );
+
+    client.publish("mychannel1", "message1").await?;
+    clone.publish("mychannel2", "message2").await?;
+    assert_eq!(
+        b"message1".to_vec(),
+        pub_sub_stream1.next().await.unwrap()?.payload
+    );
+    assert_eq!(
+        b"message2".to_vec(),
+        pub_sub_stream2.next().await.unwrap()?.payload
+    );
+
+    // the dedicated connection is closed with the last stream
+    pub_sub_stream1.close().await?;
+    pub_sub_stream2.close().await?;
+    let new_pub_sub_client_id = client.get_pub_sub_client().await?.client_id().await?;
+    assert_ne!(pub_sub_client_id, new_pub_sub_client_id);
+
+    Ok(())
+}
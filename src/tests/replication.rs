@@ -0,0 +1,191 @@
+use crate::{
+    commands::{FlushingMode, ServerCommands, StringCommands},
+    replication::{ReplicatedCommand, ReplicationEvent, ReplicationPosition, ReplicationStream},
+    tests::{get_default_config, get_test_client, log_try_init},
+    Result,
+};
+use bytes::Bytes;
+use serial_test::serial;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn replication_protocol() -> Result<()> {
+    log_try_init();
+
+    let (replica, mut master) = tokio::io::duplex(4096);
+    let (reader, writer) = tokio::io::split(replica);
+    let mut stream = ReplicationStream::new(
+        Box::new(reader),
+        Box::new(writer),
+        Default::default(),
+        None,
+        "test".to_owned(),
+    );
+
+    let ping = "*1\r\n$4\r\nPING\r\n";
+    let select = "*2\r\n$6\r\nSELECT\r\n$1\r\n2\r\n";
+    let set = "*3\r\n$3\r\nSET\r\n$3\r\nkey\r\n$5\r\nvalue\r\n";
+    let getack = "*3\r\n$8\r\nREPLCONF\r\n$6\r\nGETACK\r\n$1\r\n*\r\n";
+    master
+        .write_all(
+            format!("\n+FULLRESYNC 8de1d0f2 100\r\n\n\n$9\r\nREDIS0011{ping}{select}{set}{getack}")
+                .as_bytes(),
+        )
+        .await?;
+    drop(master.shutdown().await);
+
+    assert_eq!(
+        Some(ReplicationEvent::FullResync {
+            replication_id: "8de1d0f2".to_owned(),
+            offset: 100
+        }),
+        stream.next_event().await?
+    );
+    assert_eq!(
+        Some(ReplicationEvent::RdbStart { len: 9 }),
+        stream.next_event().await?
+    );
+
+    let mut rdb = Vec::new();
+    let event = loop {
+        match stream.next_event().await? {
+            Some(ReplicationEvent::RdbChunk(chunk)) => rdb.extend_from_slice(&chunk),
+            event => break event,
+        }
+    };
+    assert_eq!(b"REDIS0011", rdb.as_slice());
+    assert_eq!(Some(ReplicationEvent::RdbEnd), event);
+
+    // PING is skipped
+    let offset = 100 + (ping.len() + select.len()) as u64;
+    assert_eq!(
+        Some(ReplicationEvent::Command(ReplicatedCommand {
+            name: "SELECT".to_owned(),
+            args: vec![b"2".to_vec()],
+            database: 2,
+            offset,
+        })),
+        stream.next_event().await?
+    );
+
+    let offset = offset + set.len() as u64;
+    assert_eq!(
+        Some(ReplicationEvent::Command(ReplicatedCommand {
+            name: "SET".to_owned(),
+            args: vec![b"key".to_vec(), b"value".to_vec()],
+            database: 2,
+            offset,
+        })),
+        stream.next_event().await?
+    );
+
+    // GETACK is answered, then the stream ends
+    assert_eq!(None, stream.next_event().await?);
+    assert_eq!(
+        Some(&ReplicationPosition {
+            replication_id: "8de1d0f2".to_owned(),
+            offset: offset + getack.len() as u64,
+            database: 2,
+        }),
+        stream.position()
+    );
+
+    drop(stream);
+    let mut acks = String::new();
+    master.read_to_string(&mut acks).await?;
+    let ack_offset = offset.to_string();
+    assert_eq!(
+        format!(
+            "*3\r\n$8\r\nREPLCONF\r\n$3\r\nACK\r\n${}\r\n{ack_offset}\r\n",
+            ack_offset.len()
+        ),
+        acks
+    );
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn replication_psync_error() -> Result<()> {
+    log_try_init();
+
+    let (replica, mut master) = tokio::io::duplex(4096);
+    let (reader, writer) = tokio::io::split(replica);
+    let mut stream = ReplicationStream::new(
+        Box::new(reader),
+        Box::new(writer),
+        Default::default(),
+        None,
+        "test".to_owned(),
+    );
+
+    master
+        .write_all(b"-NOPERM this user has no permissions to run the 'psync' command\r\n")
+        .await?;
+
+    let result = stream.next_event().await;
+    assert!(matches!(result, Err(crate::Error::Redis(_))));
+    assert_eq!(None, stream.position());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn replication_stream() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushall(FlushingMode::Sync).await?;
+
+    let mut stream = ReplicationStream::connect(get_default_config()?, None).await?;
+    let Some(ReplicationEvent::FullResync { .. }) = stream.next_event().await? else {
+        panic!("full resync expected");
+    };
+    let Some(ReplicationEvent::RdbStart { len }) = stream.next_event().await? else {
+        panic!("RDB snapshot expected");
+    };
+
+    let mut rdb = Vec::<Bytes>::new();
+    loop {
+        match stream.next_event().await? {
+            Some(ReplicationEvent::RdbChunk(chunk)) => rdb.push(chunk),
+            Some(ReplicationEvent::RdbEnd) => break,
+            event => panic!("unexpected event {event:?}"),
+        }
+    }
+    assert_eq!(len, rdb.iter().map(Bytes::len).sum::<usize>());
+    assert!(rdb[0].starts_with(b"REDIS"));
+
+    client.set("key", "value").await?;
+    let command = loop {
+        match stream.next_event().await? {
+            Some(ReplicationEvent::Command(command)) if command.name == "SET" => break command,
+            Some(ReplicationEvent::Command(_)) => (),
+            event => panic!("unexpected event {event:?}"),
+        }
+    };
+    assert_eq!(vec![b"key".to_vec(), b"value".to_vec()], command.args);
+    assert_eq!(0, command.database);
+
+    // resume with a partial resync
+    let position = stream.position().cloned();
+    drop(stream);
+    client.set("key", "value2").await?;
+
+    let mut stream = ReplicationStream::connect(get_default_config()?, position).await?;
+    let Some(ReplicationEvent::Continue { .. }) = stream.next_event().await? else {
+        panic!("partial resync expected");
+    };
+    let command = loop {
+        match stream.next_event().await? {
+            Some(ReplicationEvent::Command(command)) if command.name == "SET" => break command,
+            Some(ReplicationEvent::Command(_)) => (),
+            event => panic!("unexpected event {event:?}"),
+        }
+    };
+    assert_eq!(vec![b"key".to_vec(), b"value2".to_vec()], command.args);
+
+    Ok(())
+}
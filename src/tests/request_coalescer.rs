@@ -0,0 +1,50 @@
+use crate::{
+    client::{CoalescedRequest, RequestCoalescer, COALESCABLE_COMMANDS},
+    resp::{cmd, RespBuf},
+};
+use std::sync::Arc;
+
+#[test]
+fn coalescable_commands_sorted() {
+    assert!(COALESCABLE_COMMANDS.windows(2).all(|w| w[0] < w[1]));
+}
+
+#[test]
+fn join() {
+    let coalescer = Arc::new(RequestCoalescer::new());
+
+    assert!(matches!(
+        coalescer.join(&cmd("SET").arg("key").arg("value")),
+        CoalescedRequest::None
+    ));
+
+    let CoalescedRequest::Leader(leader) = coalescer.join(&cmd("GET").arg("key")) else {
+        panic!("Expected leader");
+    };
+    let CoalescedRequest::Follower(mut follower) = coalescer.join(&cmd("GET").arg("key"))
+    else {
+        panic!("Expected follower");
+    };
+    assert!(matches!(
+        coalescer.join(&cmd("GET").arg("other_key")),
+        CoalescedRequest::Leader(_)
+    ));
+
+    leader.complete(&Ok(RespBuf::ok()));
+    assert!(matches!(follower.try_recv(), Ok(Some(Ok(_)))));
+
+    // cancelled leader
+    let CoalescedRequest::Leader(leader) = coalescer.join(&cmd("GET").arg("key")) else {
+        panic!("Expected leader");
+    };
+    let CoalescedRequest::Follower(mut follower) = coalescer.join(&cmd("GET").arg("key"))
+    else {
+        panic!("Expected follower");
+    };
+    drop(leader);
+    assert!(follower.try_recv().is_err());
+    assert!(matches!(
+        coalescer.join(&cmd("GET").arg("key")),
+        CoalescedRequest::Leader(_)
+    ));
+}
@@ -1,5 +1,7 @@
 use crate::{
-    resp::RespDeserializer, tests::log_try_init, Error, RedisError, RedisErrorKind, Result,
+    resp::{RespDeserializer, Value},
+    tests::log_try_init,
+    Error, RedisError, RedisErrorKind, Result,
 };
 use serde::Deserialize;
 use smallvec::SmallVec;
@@ -567,3 +569,135 @@ fn array_chunks() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn flatten() -> Result<()> {
+    log_try_init();
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Stats {
+        visits: u64,
+        ratio: f64,
+        active: bool,
+        last_seen: Option<u64>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct User {
+        id: u64,
+        #[serde(flatten)]
+        stats: Stats,
+        #[serde(flatten)]
+        others: HashMap<String, String>,
+    }
+
+    let expected = User {
+        id: 12,
+        stats: Stats {
+            visits: 3,
+            ratio: 0.5,
+            active: true,
+            last_seen: None,
+        },
+        others: HashMap::from([("name".to_owned(), "Mike".to_owned())]),
+    };
+
+    // {b"id": 12, b"visits": 3, b"ratio": 0.5, b"active": true, b"last_seen": nil, b"name": b"Mike"}
+    let result: User = deserialize(
+        "%6\r\n$2\r\nid\r\n:12\r\n$6\r\nvisits\r\n:3\r\n$5\r\nratio\r\n,0.5\r\n$6\r\nactive\r\n#t\r\n$9\r\nlast_seen\r\n_\r\n$4\r\nname\r\n$4\r\nMike\r\n",
+    )?;
+    assert_eq!(expected, result);
+
+    // [b"id", 12, b"visits", 3, b"ratio", 0.5, b"active", true, b"last_seen", nil, b"name", b"Mike"]
+    let result: User = deserialize(
+        "*12\r\n$2\r\nid\r\n:12\r\n$6\r\nvisits\r\n:3\r\n$5\r\nratio\r\n,0.5\r\n$6\r\nactive\r\n#t\r\n$9\r\nlast_seen\r\n_\r\n$4\r\nname\r\n$4\r\nMike\r\n",
+    )?;
+    assert_eq!(expected, result);
+
+    Ok(())
+}
+
+#[test]
+fn internally_tagged_enum() -> Result<()> {
+    log_try_init();
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Point {
+        x: i64,
+        y: i64,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(tag = "type")]
+    enum Shape {
+        Empty,
+        Circle { center: Point, radius: f64 },
+        Square(Point),
+    }
+
+    // {b"type": b"Empty"}
+    let result: Shape = deserialize("%1\r\n$4\r\ntype\r\n$5\r\nEmpty\r\n")?;
+    assert_eq!(Shape::Empty, result);
+
+    // {b"radius": 1.5, b"type": b"Circle", b"center": {b"x": 1, b"y": 2}}
+    let result: Shape = deserialize(
+        "%3\r\n$6\r\nradius\r\n,1.5\r\n$4\r\ntype\r\n$6\r\nCircle\r\n$6\r\ncenter\r\n%2\r\n$1\r\nx\r\n:1\r\n$1\r\ny\r\n:2\r\n",
+    )?;
+    assert_eq!(
+        Shape::Circle {
+            center: Point { x: 1, y: 2 },
+            radius: 1.5
+        },
+        result
+    );
+
+    // [{+type: +Square, +x: 3, +y: 4}, {+type: +Empty}]
+    let result: Vec<Shape> = deserialize(
+        "*2\r\n%3\r\n+type\r\n+Square\r\n+x\r\n:3\r\n+y\r\n:4\r\n%1\r\n+type\r\n+Empty\r\n",
+    )?;
+    assert_eq!(
+        vec![Shape::Square(Point { x: 3, y: 4 }), Shape::Empty],
+        result
+    );
+
+    Ok(())
+}
+
+#[test]
+fn resp3_types() -> Result<()> {
+    log_try_init();
+
+    let result: String = deserialize("(3492890328409238509324850943850943825024385\r\n")?;
+    assert_eq!("3492890328409238509324850943850943825024385", result);
+
+    let result: i64 = deserialize("(12\r\n")?;
+    assert_eq!(12, result);
+
+    let result: String = deserialize("=15\r\ntxt:Some string\r\n")?;
+    assert_eq!("Some string", result);
+
+    Ok(())
+}
+
+#[test]
+fn attributes() -> Result<()> {
+    log_try_init();
+
+    let buf = "|1\r\n+key-popularity\r\n%1\r\n$1\r\na\r\n,0.1923\r\n*2\r\n:2039123\r\n:9543892\r\n";
+    let mut deserializer = RespDeserializer::new(buf.as_bytes());
+    let result = Vec::<i64>::deserialize(&mut deserializer)?;
+    assert_eq!(vec![2039123, 9543892], result);
+
+    let attributes = deserializer.take_attributes();
+    assert_eq!(1, attributes.len());
+    assert_eq!(
+        Some(&Value::Array(vec![
+            Value::BulkString(b"a".to_vec()),
+            Value::Double(0.1923)
+        ])),
+        attributes[0].get(&Value::SimpleString("key-popularity".to_owned()))
+    );
+    assert!(deserializer.take_attributes().is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use crate::{
+    resp::{cmd, RespWriter, StreamedArg, Value},
+    tests::log_try_init,
+    Result,
+};
+
+#[test]
+fn write_command() -> Result<()> {
+    log_try_init();
+
+    let mut writer = RespWriter::new(Vec::new());
+    writer.write_command(&cmd("SET").arg("key").arg("value"))?;
+    writer.write_command(&cmd("PING"))?;
+    writer.write_command(&cmd("SET").arg("key").arg("hello world").inline(true))?;
+    writer.flush()?;
+
+    assert_eq!(
+        "*3\r\n$3\r\nSET\r\n$3\r\nkey\r\n$5\r\nvalue\r\n*1\r\n$4\r\nPING\r\nSET key \"hello world\"\r\n",
+        String::from_utf8(writer.into_inner())?
+    );
+
+    // large arguments are written as is
+    let large_value = vec![b'a'; 10 * 1024];
+    let mut writer = RespWriter::new(Vec::new());
+    writer.write_command(&cmd("SET").arg("key").arg(large_value.clone()))?;
+
+    let mut expected = b"*3\r\n$3\r\nSET\r\n$3\r\nkey\r\n$10240\r\n".to_vec();
+    expected.extend_from_slice(&large_value);
+    expected.extend_from_slice(b"\r\n");
+    assert_eq!(expected, writer.into_inner());
+
+    // streamed arguments need an asynchronous sink
+    let mut writer = RespWriter::new(Vec::new());
+    let result = writer.write_command(
+        &cmd("SET")
+            .arg("key")
+            .streamed_arg(StreamedArg::from_async_read(5, &b"value"[..])),
+    );
+    assert!(result.is_err());
+    assert!(writer.get_ref().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn write_value() -> Result<()> {
+    log_try_init();
+
+    let mut writer = RespWriter::new(Vec::new());
+    writer.write_value(&Value::SimpleString("OK".to_owned()))?;
+    writer.write_value(&Value::Integer(12))?;
+    writer.write_value(&Value::Array(vec![
+        Value::BulkString(b"value".to_vec()),
+        Value::Nil,
+    ]))?;
+    writer.write_value(&Value::Error(crate::RedisError {
+        kind: crate::RedisErrorKind::Err,
+        description: "unknown command".to_owned(),
+    }))?;
+    writer.write_value(&[1, 2])?;
+
+    assert_eq!(
+        "+OK\r\n:12\r\n*2\r\n$5\r\nvalue\r\n_\r\n-ERR unknown command\r\n*2\r\n:1\r\n:2\r\n",
+        String::from_utf8(writer.into_inner())?
+    );
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn write_async() -> Result<()> {
+    log_try_init();
+
+    let mut writer = RespWriter::new(Vec::new());
+    writer
+        .write_command_async(&cmd("SET").arg("key").arg("value"))
+        .await?;
+    writer
+        .write_command_async(
+            &cmd("SET")
+                .arg("key")
+                .streamed_arg(StreamedArg::from_async_read(5, &b"value"[..]))
+                .arg("EX")
+                .arg(10),
+        )
+        .await?;
+    writer
+        .write_value_async(&Value::SimpleString("OK".to_owned()))
+        .await?;
+    writer.flush_async().await?;
+
+    assert_eq!(
+        "*3\r\n$3\r\nSET\r\n$3\r\nkey\r\n$5\r\nvalue\r\n\
+         *5\r\n$3\r\nSET\r\n$3\r\nkey\r\n$5\r\nvalue\r\n$2\r\nEX\r\n$2\r\n10\r\n\
+         +OK\r\n",
+        String::from_utf8(writer.into_inner())?
+    );
+
+    // a streamed argument shorter than announced breaks the protocol
+    let mut writer = RespWriter::new(Vec::new());
+    let result = writer
+        .write_command_async(
+            &cmd("SET")
+                .arg("key")
+                .streamed_arg(StreamedArg::from_async_read(10, &b"short"[..])),
+        )
+        .await;
+    assert!(result.is_err());
+
+    Ok(())
+}
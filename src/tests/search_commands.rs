@@ -1,18 +1,21 @@
 use crate::{
     client::{BatchPreparedCommand, Client},
     commands::{
-        ClientReplyMode, ConnectionCommands, FlushingMode, FtAggregateOptions, FtCreateOptions,
-        FtFieldSchema, FtFieldType, FtFlatVectorFieldAttributes, FtIndexDataType, FtLanguage,
-        FtLoadAttribute, FtReducer, FtSearchOptions, FtSearchResult, FtSortBy, FtSpellCheckOptions,
-        FtSugAddOptions, FtSugGetOptions, FtTermType, FtVectorDistanceMetric,
-        FtVectorFieldAlgorithm, FtVectorType, FtWithCursorOptions, HashCommands, JsonCommands,
+        ClientReplyMode, ConnectionCommands, FlushingMode, FtAggregateOptions, FtConfigKey,
+        FtCreateOptions, FtFieldSchema, FtFieldType, FtFlatVectorFieldAttributes, FtIndexDataType,
+        FtLanguage, FtLoadAttribute, FtProfileDetails, FtProfileStage, FtReducer, FtSearchOptions,
+        FtSearchResult, FtSortBy, FtSpellCheckOptions, FtSugAddOptions, FtSugGetOptions,
+        FtTermType, FtVectorDistanceMetric, FtVectorFieldAlgorithm, FtVectorType,
+        FtWithCursorOptions, HashCommands, JsonCommands, ModuleConfigKey, ModuleConfigValue,
         SearchCommands, ServerCommands, SetCondition, SortOrder,
     },
     network::sleep,
+    resp::RespDeserializer,
     tests::get_redis_stack_test_client,
-    Result,
+    Error, Result,
 };
 use rand::{seq::SliceRandom, Rng};
+use serde::Deserialize;
 use serial_test::serial;
 use smallvec::SmallVec;
 use std::{
@@ -345,6 +348,229 @@ async fn ft_config_get_set() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn ft_profile_details() -> Result<()> {
+    fn bulk(s: &str) -> String {
+        format!("${}\r\n{s}\r\n", s.len())
+    }
+
+    fn array(items: &[String]) -> String {
+        format!("*{}\r\n{}", items.len(), items.concat())
+    }
+
+    fn map(entries: &[(&str, String)]) -> String {
+        let entries: Vec<String> = entries
+            .iter()
+            .map(|(name, value)| format!("{}{value}", bulk(name)))
+            .collect();
+        format!("%{}\r\n{}", entries.len(), entries.concat())
+    }
+
+    fn deserialize(resp: &str) -> Result<FtProfileDetails> {
+        FtProfileDetails::deserialize(&mut RespDeserializer::new(resp.as_bytes()))
+    }
+
+    fn check(details: &FtProfileDetails) {
+        assert_eq!(Duration::from_micros(500), details.total_time());
+
+        let union = details.iterators().unwrap();
+        assert_eq!("UNION", union._type);
+        assert_eq!(Some("UNION"), union.query_type.as_deref());
+        assert_eq!(2, union.counter);
+        assert_eq!(2, union.child_iterators.len());
+        assert_eq!(Some("bar"), union.child_iterators[1].term.as_deref());
+        assert_eq!(Some(1), union.child_iterators[1].size);
+        assert!((union.self_time() - 0.05).abs() < 1e-9);
+
+        let slowest = details.slowest_iterator().unwrap();
+        assert_eq!(Some("foo"), slowest.term.as_deref());
+
+        let rows = details.rows();
+        assert_eq!(
+            vec![
+                (FtProfileStage::Iterator, 0, "UNION"),
+                (FtProfileStage::Iterator, 1, "TEXT"),
+                (FtProfileStage::Iterator, 1, "TEXT"),
+                (FtProfileStage::ResultProcessor, 0, "Index"),
+                (FtProfileStage::ResultProcessor, 0, "Loader"),
+            ],
+            rows.iter()
+                .map(|row| (row.stage, row.depth, row._type.as_str()))
+                .collect::<Vec<_>>()
+        );
+
+        let table = details.to_string();
+        assert_eq!(6, table.lines().count());
+        assert!(table
+            .lines()
+            .nth(2)
+            .unwrap()
+            .starts_with("iterator    TEXT foo "));
+    }
+
+    // RESP2: child iterators are the trailing elements of their parent
+    let text_iterator = |term: &str, time: &str| {
+        array(&[
+            bulk("Type"),
+            bulk("TEXT"),
+            bulk("Term"),
+            bulk(term),
+            bulk("Time"),
+            bulk(time),
+            bulk("Counter"),
+            ":1\r\n".to_owned(),
+            bulk("Size"),
+            ":1\r\n".to_owned(),
+        ])
+    };
+    let processor = |_type: &str, time: &str| {
+        array(&[
+            bulk("Type"),
+            bulk(_type),
+            bulk("Time"),
+            bulk(time),
+            bulk("Counter"),
+            ":2\r\n".to_owned(),
+        ])
+    };
+    let resp2 = array(&[
+        array(&[bulk("Total profile time"), bulk("0.5")]),
+        array(&[bulk("Parsing time"), bulk("0.05")]),
+        array(&[bulk("Pipeline creation time"), bulk("0.02")]),
+        array(&[
+            bulk("Iterators profile"),
+            array(&[
+                bulk("Type"),
+                bulk("UNION"),
+                bulk("Query type"),
+                bulk("UNION"),
+                bulk("Time"),
+                bulk("0.3"),
+                bulk("Counter"),
+                ":2\r\n".to_owned(),
+                bulk("Child iterators"),
+                text_iterator("foo", "0.2"),
+                text_iterator("bar", "0.05"),
+            ]),
+        ]),
+        array(&[
+            bulk("Result processors profile"),
+            processor("Index", "0.1"),
+            processor("Loader", "0.01"),
+        ]),
+    ]);
+    check(&deserialize(&resp2)?);
+
+    // RESP3 (RediSearch 2.8+): maps, root iterator wrapped in an array
+    let text_iterator = |term: &str, time: &str| {
+        map(&[
+            ("Type", bulk("TEXT")),
+            ("Term", bulk(term)),
+            ("Time", format!(",{time}\r\n")),
+            ("Counter", ":1\r\n".to_owned()),
+            ("Size", ":1\r\n".to_owned()),
+        ])
+    };
+    let processor = |_type: &str, time: &str| {
+        map(&[
+            ("Type", bulk(_type)),
+            ("Time", format!(",{time}\r\n")),
+            ("Counter", ":2\r\n".to_owned()),
+        ])
+    };
+    let resp3 = map(&[
+        ("Total profile time", ",0.5\r\n".to_owned()),
+        ("Parsing time", ",0.05\r\n".to_owned()),
+        ("Pipeline creation time", ",0.02\r\n".to_owned()),
+        ("Warning", "+None\r\n".to_owned()),
+        (
+            "Iterators profile",
+            array(&[map(&[
+                ("Type", bulk("UNION")),
+                ("Query type", bulk("UNION")),
+                ("Time", ",0.3\r\n".to_owned()),
+                ("Counter", ":2\r\n".to_owned()),
+                (
+                    "Child iterators",
+                    array(&[text_iterator("foo", "0.2"), text_iterator("bar", "0.05")]),
+                ),
+            ])]),
+        ),
+        (
+            "Result processors profile",
+            array(&[processor("Index", "0.1"), processor("Loader", "0.01")]),
+        ),
+    ]);
+    check(&deserialize(&resp3)?);
+
+    // RESP3 (RediSearch 8+): profile of each shard
+    let resp3 = map(&[
+        ("Shards", array(&[resp3])),
+        ("Coordinator", "%0\r\n".to_owned()),
+    ]);
+    check(&deserialize(&resp3)?);
+
+    Ok(())
+}
+
+#[test]
+fn ft_config_key() -> Result<()> {
+    assert_eq!("search-timeout", FtConfigKey::Timeout.name());
+    assert_eq!(
+        Some(("FT.CONFIG", "TIMEOUT")),
+        FtConfigKey::Timeout.legacy()
+    );
+
+    assert_eq!(
+        ModuleConfigValue::Integer(500),
+        FtConfigKey::Timeout.parse_value("500")?
+    );
+    assert_eq!(
+        ModuleConfigValue::Enum("fail".to_owned()),
+        FtConfigKey::OnTimeout.parse_value("FAIL")?
+    );
+    // `FT.CONFIG` & `CONFIG` flavors of booleans
+    assert_eq!(
+        ModuleConfigValue::Boolean(true),
+        FtConfigKey::NoGc.parse_value("true")?
+    );
+    assert_eq!(
+        ModuleConfigValue::Boolean(false),
+        FtConfigKey::NoGc.parse_value("no")?
+    );
+
+    assert!(matches!(
+        FtConfigKey::Timeout.parse_value("abc"),
+        Err(Error::Client(_))
+    ));
+    assert!(matches!(
+        FtConfigKey::OnTimeout.parse_value("ignore"),
+        Err(Error::Client(_))
+    ));
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn module_config_get_set() -> Result<()> {
+    let client = get_redis_stack_test_client().await?;
+
+    client.module_config_set(FtConfigKey::Timeout, 42).await?;
+    let value = client.module_config_get(FtConfigKey::Timeout).await?;
+    assert_eq!(Some(ModuleConfigValue::Integer(42)), value);
+
+    // typed keys are also accepted by FT.CONFIG
+    let result: SmallVec<[(String, u64); 1]> = client.ft_config_get(FtConfigKey::Timeout).await?;
+    assert_eq!(("TIMEOUT".to_owned(), 42), result[0]);
+
+    let value = client.module_config_get(FtConfigKey::OnTimeout).await?;
+    assert!(matches!(value, Some(ModuleConfigValue::Enum(_))));
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
@@ -947,6 +1173,9 @@ async fn ft_profile() -> Result<()> {
         .await?;
 
     log::debug!("result: {result:?}");
+    log::debug!("profile:\n{}", result.profile_details);
+    assert!(result.profile_details.iterators().is_some());
+    assert!(!result.profile_details.rows().is_empty());
 
     Ok(())
 }
@@ -1063,6 +1292,91 @@ async fn ft_search() -> Result<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn ft_search_to() -> Result<()> {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Document {
+        title: String,
+        published_at: u32,
+    }
+
+    let client = get_redis_stack_test_client().await?;
+    client.flushall(FlushingMode::Sync).await?;
+
+    client
+        .hset("doc:1", [("title", "dogs"), ("published_at", "2019")])
+        .await?;
+    client
+        .json_set(
+            "json:1",
+            "$",
+            r#"{"title":"cats","published_at":2020}"#,
+            SetCondition::None,
+        )
+        .await?;
+
+    client
+        .ft_create(
+            "index",
+            FtCreateOptions::default()
+                .on(FtIndexDataType::Hash)
+                .prefix("doc"),
+            [
+                FtFieldSchema::identifier("title").field_type(FtFieldType::Text),
+                FtFieldSchema::identifier("published_at").field_type(FtFieldType::Numeric),
+            ],
+        )
+        .await?;
+    client
+        .ft_create(
+            "json_index",
+            FtCreateOptions::default()
+                .on(FtIndexDataType::Json)
+                .prefix("json"),
+            [
+                FtFieldSchema::identifier("$.title")
+                    .as_attribute("title")
+                    .field_type(FtFieldType::Text),
+                FtFieldSchema::identifier("$.published_at")
+                    .as_attribute("published_at")
+                    .field_type(FtFieldType::Numeric),
+            ],
+        )
+        .await?;
+    wait_for_index_scanned(&client, "index").await?;
+    wait_for_index_scanned(&client, "json_index").await?;
+
+    let result = client
+        .ft_search("index", "@title:dogs", FtSearchOptions::default())
+        .await?;
+    assert_eq!(1, result.results.len());
+    let document: Document = result.results[0].to()?;
+    assert_eq!(
+        Document {
+            title: "dogs".to_owned(),
+            published_at: 2019
+        },
+        document
+    );
+
+    let result = client
+        .ft_search("json_index", "@title:cats", FtSearchOptions::default())
+        .await?;
+    assert_eq!(1, result.results.len());
+    let document: Document = result.results[0].to()?;
+    assert_eq!(
+        Document {
+            title: "cats".to_owned(),
+            published_at: 2020
+        },
+        document
+    );
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
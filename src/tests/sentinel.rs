@@ -1,7 +1,7 @@
 use crate::{
     client::Client,
     commands::{ConnectionCommands, SentinelCommands, StringCommands},
-    network::sleep,
+    network::{sleep, SentinelHealth},
     tests::{get_sentinel_master_test_client, get_sentinel_test_client, log_try_init},
     Result,
 };
@@ -278,3 +278,34 @@ async fn get_loop() -> Result<()> {
 
 //     Ok(())
 // }
+
+#[test]
+fn sentinel_order() {
+    let instances = [
+        ("127.0.0.1".to_owned(), 26379),
+        ("127.0.0.1".to_owned(), 26380),
+        ("127.0.0.1".to_owned(), 26381),
+    ];
+    let ports = |health: &SentinelHealth, prefer_last_good: bool| {
+        health
+            .order(&instances, prefer_last_good)
+            .into_iter()
+            .map(|(_, port)| *port)
+            .collect::<Vec<_>>()
+    };
+
+    let mut health = SentinelHealth::default();
+    assert_eq!(vec![26379, 26380, 26381], ports(&health, true));
+
+    // failing instances are tried last
+    assert_eq!(1, health.record_failure(&instances[0]));
+    assert_eq!(2, health.record_failure(&instances[0]));
+    health.record_failure(&instances[1]);
+    assert_eq!(vec![26381, 26380, 26379], ports(&health, false));
+
+    // the last good instance is tried first, if preferred
+    health.record_success(&instances[0]);
+    health.record_success(&instances[2]);
+    assert_eq!(vec![26379, 26381, 26380], ports(&health, false));
+    assert_eq!(vec![26381, 26379, 26380], ports(&health, true));
+}
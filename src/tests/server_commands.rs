@@ -5,18 +5,23 @@ use crate::{
         BlockingCommands, ClientInfo, ClientKillOptions, CommandDoc, CommandHistogram,
         CommandListOptions, ConnectionCommands, FailOverOptions, FlushingMode, InfoSection,
         LatencyHistoryEvent, MemoryUsageOptions, ModuleInfo, ModuleLoadOptions, ReplicaOfOptions,
-        RoleResult, ServerCommands, SlowLogOptions, StringCommands,
+        Role, RoleResult, ServerCommands, SlowLogOptions, StringCommands,
     },
     resp::{cmd, Value},
-    spawn,
+    sleep, spawn,
     tests::{
-        get_default_config, get_sentinel_test_client, get_test_client, get_test_client_with_config,
+        get_default_config, get_default_host, get_default_port, get_sentinel_test_client,
+        get_test_client, get_test_client_with_config,
     },
     Error, RedisError, RedisErrorKind, Result,
 };
-use futures_util::StreamExt;
+use futures_util::{poll, StreamExt};
 use serial_test::serial;
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    io::{Read, Write},
+    time::Duration,
+};
 
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
@@ -331,6 +336,36 @@ async fn command_info() -> Result<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn read_only_commands() -> Result<()> {
+    let client = get_test_client().await?;
+
+    // commands of the `*_readonly` wrappers, safe on read-only replicas
+    let command_infos = client
+        .command_info([
+            "BITFIELD_RO",
+            "SORT_RO",
+            "GEORADIUS_RO",
+            "GEORADIUSBYMEMBER_RO",
+            "EVAL_RO",
+            "EVALSHA_RO",
+            "FCALL_RO",
+        ])
+        .await?;
+    assert_eq!(7, command_infos.len());
+    for command_info in command_infos {
+        assert!(
+            command_info.flags.iter().any(|f| f == "readonly"),
+            "{} is not read-only",
+            command_info.name
+        );
+    }
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
@@ -1050,6 +1085,28 @@ async fn monitor() -> Result<()> {
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
+async fn cancelled_monitor() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    // MONITOR is sent on the first poll, the future is dropped before its confirmation
+    let mut future = Box::pin(client.monitor());
+    assert!(poll!(&mut future).is_pending());
+    drop(future);
+    sleep(Duration::from_millis(100)).await;
+
+    // the connection has left the monitor mode
+    client.set("key", "value").await?;
+    let value: String = client.get("key").await?;
+    assert_eq!("value", value);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+#[allow(deprecated)]
 async fn auto_remonitor() -> Result<()> {
     let mut config = get_default_config()?;
     config.reconnection = ReconnectionConfig::new_constant(0, 100);
@@ -1165,6 +1222,57 @@ async fn role() -> Result<()> {
     Ok(())
 }
 
+/// Restore the master role of the test instance, even if the test panics,
+/// with a blocking connection since no runtime is guaranteed while unwinding
+struct RestoreMasterGuard;
+
+impl Drop for RestoreMasterGuard {
+    fn drop(&mut self) {
+        if let Ok(mut stream) =
+            std::net::TcpStream::connect((get_default_host(), get_default_port()))
+        {
+            let _ = stream.set_read_timeout(Some(Duration::from_secs(1)));
+            let _ = stream.write_all(b"*3\r\n$9\r\nREPLICAOF\r\n$2\r\nNO\r\n$3\r\nONE\r\n");
+            let mut reply = [0u8; 64];
+            let _ = stream.read(&mut reply);
+        }
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn wait_for_role() -> Result<()> {
+    let client = get_test_client().await?;
+
+    let role_result = client
+        .wait_for_role(Role::Master, Duration::from_secs(1))
+        .await?;
+    assert_eq!(Role::Master, role_result.role());
+    assert!(!role_result.is_master_link_up());
+    assert_eq!(None, role_result.master_link_state());
+
+    // the instance cannot replicate itself: the master link never gets up
+    let _guard = RestoreMasterGuard;
+    client
+        .replicaof(ReplicaOfOptions::master("127.0.0.1", 6379))
+        .await?;
+
+    let result = client
+        .wait_for_role(Role::Replica, Duration::from_millis(300))
+        .await;
+    assert!(matches!(result, Err(Error::Timeout(_))));
+
+    client.replicaof(ReplicaOfOptions::no_one()).await?;
+
+    let role_result = client
+        .wait_for_role(Role::Master, Duration::from_secs(1))
+        .await?;
+    assert!(role_result.replicas().is_empty());
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
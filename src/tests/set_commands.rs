@@ -239,6 +239,49 @@ async fn spop() -> Result<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn spop_one() -> Result<()> {
+    let client = get_test_client().await?;
+
+    // cleanup
+    client.del("key").await?;
+
+    let member: Option<String> = client.spop_one("key").await?;
+    assert_eq!(None, member);
+
+    client.sadd("key", "value1").await?;
+
+    let member: Option<String> = client.spop_one("key").await?;
+    assert_eq!(Some("value1".to_owned()), member);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn spop_count() -> Result<()> {
+    let client = get_test_client().await?;
+
+    // cleanup
+    client.del("key").await?;
+
+    let members: Vec<String> = client.spop_count("key", 2).await?;
+    assert!(members.is_empty());
+
+    client.sadd("key", ["value1", "value2", "value3"]).await?;
+
+    let members: Vec<String> = client.spop_count("key", 2).await?;
+    assert_eq!(2, members.len());
+
+    let members: Vec<String> = client.spop_count("key", 2).await?;
+    assert_eq!(1, members.len());
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
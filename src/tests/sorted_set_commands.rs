@@ -1,9 +1,10 @@
 use crate::{
     commands::{
         BZpopMinMaxResult, BlockingCommands, FlushingMode, GenericCommands, ServerCommands,
-        SortedSetCommands, ZAddOptions, ZRangeOptions, ZRangeSortBy, ZScanOptions, ZScanResult,
-        ZWhere,
+        SortedSetCommands, ZAddOptions, ZRangeBound, ZRangeOptions, ZRangeQuery, ZRangeSortBy,
+        ZScanOptions, ZScanResult, ZWhere,
     },
+    resp::cmd,
     sleep, spawn,
     tests::get_test_client,
     Result,
@@ -279,6 +280,128 @@ async fn zadd() -> Result<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn zadd_options() -> Result<()> {
+    let client = get_test_client().await?;
+
+    // cleanup
+    client.del("key").await?;
+
+    client
+        .zadd("key", [(1.0, "one"), (2.0, "two")], ZAddOptions::default())
+        .await?;
+
+    let len = client
+        .zadd(
+            "key",
+            [(3.0, "one"), (3.0, "three")],
+            ZAddOptions::default().nx(),
+        )
+        .await?;
+    assert_eq!(1, len);
+    let score = client.zscore("key", "one").await?;
+    assert_eq!(Some(1.0), score);
+
+    let len = client
+        .zadd(
+            "key",
+            [(0.0, "one"), (5.0, "two"), (4.0, "four")],
+            ZAddOptions::default().gt().xx().change(),
+        )
+        .await?;
+    assert_eq!(1, len);
+    let values: Vec<(String, f64)> = client
+        .zrange_with_scores("key", 0, -1, ZRangeOptions::default())
+        .await?;
+    assert_eq!(
+        vec![
+            ("one".to_owned(), 1.0),
+            ("three".to_owned(), 3.0),
+            ("two".to_owned(), 5.0)
+        ],
+        values
+    );
+
+    let len = client
+        .zadd(
+            "key",
+            [(0.0, "one"), (4.0, "four")],
+            ZAddOptions::default().lt().change(),
+        )
+        .await?;
+    assert_eq!(2, len);
+    let score = client.zscore("key", "one").await?;
+    assert_eq!(Some(0.0), score);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn zadd_incr() -> Result<()> {
+    let client = get_test_client().await?;
+
+    // cleanup
+    client.del("key").await?;
+
+    let score = client
+        .zadd_incr_with_options("key", 1.0, "one", ZAddOptions::default())
+        .await?;
+    assert_eq!(Some(1.0), score);
+
+    let score = client
+        .zadd_incr_with_options("key", 2.5, "one", ZAddOptions::default())
+        .await?;
+    assert_eq!(Some(3.5), score);
+
+    // aborted because the member already exists
+    let score = client
+        .zadd_incr_with_options("key", 1.0, "one", ZAddOptions::default().nx())
+        .await?;
+    assert_eq!(None, score);
+
+    // aborted because the member does not exist
+    let score = client
+        .zadd_incr_with_options("key", 1.0, "two", ZAddOptions::default().xx())
+        .await?;
+    assert_eq!(None, score);
+
+    // aborted because the new score would be lower
+    let score = client
+        .zadd_incr_with_options("key", -1.0, "one", ZAddOptions::default().gt())
+        .await?;
+    assert_eq!(None, score);
+
+    let score = client
+        .zadd_incr_with_options("key", 1.0, "one", ZAddOptions::default().gt().xx())
+        .await?;
+    assert_eq!(Some(4.5), score);
+
+    let score = client
+        .zadd_incr_with_options("key", 1.0, "one", ZAddOptions::default().xx().lt())
+        .await?;
+    assert_eq!(None, score);
+
+    // deprecated signature
+    #[allow(deprecated)]
+    let score = client
+        .zadd_incr(
+            "key",
+            crate::commands::ZAddCondition::XX,
+            crate::commands::ZAddComparison::GT,
+            false,
+            1.0,
+            "one",
+        )
+        .await?;
+    assert_eq!(Some(5.5), score);
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
@@ -684,6 +807,62 @@ async fn zpopmin() -> Result<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn zpopmax_one() -> Result<()> {
+    let client = get_test_client().await?;
+
+    // cleanup
+    client.del("key").await?;
+
+    let result: Option<(String, f64)> = client.zpopmax_one("key").await?;
+    assert_eq!(None, result);
+
+    client
+        .zadd("key", [(1.0, "one"), (2.0, "two")], ZAddOptions::default())
+        .await?;
+
+    let result: Option<(String, f64)> = client.zpopmax_one("key").await?;
+    assert_eq!(Some(("two".to_owned(), 2.0)), result);
+
+    let result: Option<(String, f64)> = client.zpopmax_one("key").await?;
+    assert_eq!(Some(("one".to_owned(), 1.0)), result);
+
+    let result: Option<(String, f64)> = client.zpopmax_one("key").await?;
+    assert_eq!(None, result);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn zpopmin_one() -> Result<()> {
+    let client = get_test_client().await?;
+
+    // cleanup
+    client.del("key").await?;
+
+    let result: Option<(String, f64)> = client.zpopmin_one("key").await?;
+    assert_eq!(None, result);
+
+    client
+        .zadd("key", [(1.0, "one"), (2.0, "two")], ZAddOptions::default())
+        .await?;
+
+    let result: Option<(String, f64)> = client.zpopmin_one("key").await?;
+    assert_eq!(Some(("one".to_owned(), 1.0)), result);
+
+    let result: Option<(String, f64)> = client.zpopmin_one("key").await?;
+    assert_eq!(Some(("two".to_owned(), 2.0)), result);
+
+    let result: Option<(String, f64)> = client.zpopmin_one("key").await?;
+    assert_eq!(None, result);
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
@@ -775,6 +954,130 @@ async fn zrange() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn zrange_query_args() {
+    fn args(query: ZRangeQuery) -> Vec<String> {
+        cmd("ZRANGE")
+            .arg(query)
+            .args
+            .iter()
+            .map(|arg| String::from_utf8_lossy(arg).into_owned())
+            .collect()
+    }
+
+    assert_eq!(vec!["0", "-1"], args(ZRangeQuery::by_index(0, -1)));
+    assert_eq!(
+        vec!["0", "-1", "REV"],
+        args(ZRangeQuery::by_index(0, -1).reverse().limit(0, 1))
+    );
+    assert_eq!(
+        vec!["(1.5", "+inf", "BYSCORE", "LIMIT", "0", "10"],
+        args(ZRangeQuery::by_score(ZRangeBound::Exclusive(1.5), ZRangeBound::Max).limit(0, 10))
+    );
+    assert_eq!(
+        vec!["2.0", "-inf", "BYSCORE", "REV"],
+        args(ZRangeQuery::by_score(ZRangeBound::Min, ZRangeBound::Inclusive(2.0)).reverse())
+    );
+    assert_eq!(
+        vec!["[a", "(c", "BYLEX"],
+        args(ZRangeQuery::by_lex(
+            ZRangeBound::Inclusive("a"),
+            ZRangeBound::Exclusive("c")
+        ))
+    );
+    assert_eq!(
+        vec!["+", "-", "BYLEX", "REV"],
+        args(ZRangeQuery::by_lex(ZRangeBound::<&str>::Min, ZRangeBound::Max).reverse())
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn zrange_query() -> Result<()> {
+    let client = get_test_client().await?;
+
+    // cleanup
+    client.del(["key", "lex", "out"]).await?;
+
+    client
+        .zadd(
+            "key",
+            [(1.0, "one"), (2.0, "two"), (3.0, "three"), (4.0, "four")],
+            ZAddOptions::default(),
+        )
+        .await?;
+
+    let values: Vec<String> = client
+        .zrange_query("key", ZRangeQuery::by_index(-2, -1))
+        .await?;
+    assert_eq!(vec!["three".to_owned(), "four".to_owned()], values);
+
+    let values: Vec<(String, f64)> = client
+        .zrange_query_with_scores(
+            "key",
+            ZRangeQuery::by_score(ZRangeBound::Exclusive(1.0), ZRangeBound::Inclusive(3.0)),
+        )
+        .await?;
+    assert_eq!(
+        vec![("two".to_owned(), 2.0), ("three".to_owned(), 3.0)],
+        values
+    );
+
+    // bounds are swapped in reverse order
+    let values: Vec<String> = client
+        .zrange_query(
+            "key",
+            ZRangeQuery::by_score(ZRangeBound::Min, ZRangeBound::Exclusive(4.0))
+                .reverse()
+                .limit(1, 1),
+        )
+        .await?;
+    assert_eq!(vec!["two".to_owned()], values);
+
+    let len = client
+        .zcount("key", ZRangeBound::Exclusive(1.0), ZRangeBound::<f64>::Max)
+        .await?;
+    assert_eq!(3, len);
+
+    let len = client
+        .zrangestore_query(
+            "out",
+            "key",
+            ZRangeQuery::by_score(ZRangeBound::Inclusive(2.0), ZRangeBound::Max),
+        )
+        .await?;
+    assert_eq!(3, len);
+
+    let len = client
+        .zremrangebyscore("out", ZRangeBound::Min, ZRangeBound::Exclusive(4.0))
+        .await?;
+    assert_eq!(2, len);
+
+    client
+        .zadd(
+            "lex",
+            [(0.0, "a"), (0.0, "b"), (0.0, "c"), (0.0, "d")],
+            ZAddOptions::default(),
+        )
+        .await?;
+
+    let values: Vec<String> = client
+        .zrange_query(
+            "lex",
+            ZRangeQuery::by_lex(ZRangeBound::Exclusive("a"), ZRangeBound::Inclusive("c")).reverse(),
+        )
+        .await?;
+    assert_eq!(vec!["c".to_owned(), "b".to_owned()], values);
+
+    let len = client
+        .zlexcount("lex", ZRangeBound::Inclusive("b"), ZRangeBound::<&str>::Max)
+        .await?;
+    assert_eq!(3, len);
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
@@ -1,8 +1,8 @@
 use crate::{
     commands::{
         FlushingMode, ServerCommands, StreamCommands, StreamEntry, XAddOptions, XAutoClaimOptions,
-        XAutoClaimResult, XGroupCreateOptions, XInfoStreamOptions, XPendingOptions,
-        XReadGroupOptions, XReadOptions, XTrimOperator, XTrimOptions,
+        XAutoClaimResult, XDelExResult, XGroupCreateOptions, XInfoStreamOptions, XPendingOptions,
+        XReadGroupOptions, XReadOptions, XRefPolicy, XSetIdOptions, XTrimOperator, XTrimOptions,
     },
     tests::get_test_client,
     Result,
@@ -94,6 +94,112 @@ async fn xdel() -> Result<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn xdelex() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    client
+        .xgroup_create(
+            "mystream",
+            "mygroup",
+            "$",
+            XGroupCreateOptions::default().mk_stream(),
+        )
+        .await?;
+    let id1: String = client
+        .xadd("mystream", "*", [("name", "John")], XAddOptions::default())
+        .await?;
+    let id2: String = client
+        .xadd("mystream", "*", [("name", "Jane")], XAddOptions::default())
+        .await?;
+
+    let _results: Vec<(String, Vec<StreamEntry<String>>)> = client
+        .xreadgroup(
+            "mygroup",
+            "Bob",
+            XReadGroupOptions::default(),
+            "mystream",
+            ">",
+        )
+        .await?;
+
+    // still pending in the group
+    let results = client
+        .xdelex("mystream", XRefPolicy::Acked, [id1.as_str(), "1-1"])
+        .await?;
+    assert_eq!(
+        vec![XDelExResult::NotDeleted, XDelExResult::NotFound],
+        results
+    );
+
+    let results = client
+        .xdelex("mystream", XRefPolicy::DelRef, [id1.as_str()])
+        .await?;
+    assert_eq!(vec![XDelExResult::Deleted], results);
+
+    let results = client
+        .xdelex("mystream", XRefPolicy::KeepRef, [id2.as_str()])
+        .await?;
+    assert_eq!(vec![XDelExResult::Deleted], results);
+
+    let len = client.xlen("mystream").await?;
+    assert_eq!(0, len);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn xackdel() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    client
+        .xgroup_create(
+            "mystream",
+            "mygroup",
+            "$",
+            XGroupCreateOptions::default().mk_stream(),
+        )
+        .await?;
+    let id1: String = client
+        .xadd("mystream", "*", [("name", "John")], XAddOptions::default())
+        .await?;
+    let id2: String = client
+        .xadd("mystream", "*", [("name", "Jane")], XAddOptions::default())
+        .await?;
+
+    let _results: Vec<(String, Vec<StreamEntry<String>>)> = client
+        .xreadgroup(
+            "mygroup",
+            "Bob",
+            XReadGroupOptions::default(),
+            "mystream",
+            ">",
+        )
+        .await?;
+
+    let results = client
+        .xackdel(
+            "mystream",
+            "mygroup",
+            XRefPolicy::Acked,
+            [id1.as_str(), "1-1"],
+        )
+        .await?;
+    assert_eq!(vec![XDelExResult::Deleted, XDelExResult::NotFound], results);
+
+    let results: Vec<StreamEntry<String>> = client.xrange("mystream", "-", "+", None).await?;
+    assert_eq!(1, results.len());
+    assert_eq!(id2, results[0].stream_id);
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
@@ -131,12 +237,61 @@ async fn xgroup() -> Result<()> {
     assert!(results[0].idle_millis < 100);
     assert_eq!(0, results[0].pending);
 
+    let _: String = client
+        .xadd("mystream", "1-0", ("name", "Sara"), XAddOptions::default())
+        .await?;
+    client
+        .xgroup_setid("mystream", "mygroup", "0-0", Some(0))
+        .await?;
+    let results = client.xinfo_groups("mystream").await?;
+    assert_eq!("0-0", results[0].last_delivered_id);
+    assert_eq!(Some(0), results[0].entries_read);
+    assert_eq!(Some(1), results[0].lag);
+
+    let result = client
+        .xgroup_delconsumer("mystream", "mygroup", "Bob")
+        .await?;
+    assert_eq!(0, result);
+    let results = client.xinfo_consumers("mystream", "mygroup").await?;
+    assert!(results.is_empty());
+
     let result = client.xgroup_destroy("mystream", "mygroup").await?;
     assert!(result);
 
     Ok(())
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn xsetid() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    let _: String = client
+        .xadd("mystream", "1-0", ("name", "Sara"), XAddOptions::default())
+        .await?;
+
+    client
+        .xsetid(
+            "mystream",
+            "10-0",
+            XSetIdOptions::default()
+                .entries_added(12)
+                .max_deleted_id("5-0"),
+        )
+        .await?;
+
+    let info = client
+        .xinfo_stream("mystream", XInfoStreamOptions::default())
+        .await?;
+    assert_eq!("10-0", info.last_generated_id);
+    assert_eq!(12, info.entries_added);
+    assert_eq!("5-0", info.max_deleted_entry_id);
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
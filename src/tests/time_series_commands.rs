@@ -2,10 +2,10 @@ use std::collections::HashMap;
 
 use crate::{
     commands::{
-        FlushingMode, ServerCommands, TimeSeriesCommands, TsAddOptions, TsAggregationType,
-        TsCreateOptions, TsCreateRuleOptions, TsDuplicatePolicy, TsGetOptions, TsGroupByOptions,
-        TsIncrByDecrByOptions, TsMGetOptions, TsMRangeOptions, TsRangeOptions, TsRangeSample,
-        TsSample,
+        FlushingMode, ModuleConfigKey, ModuleConfigValue, ServerCommands, TimeSeriesCommands,
+        TsAddOptions, TsAggregationType, TsConfigKey, TsCreateOptions, TsCreateRuleOptions,
+        TsDuplicatePolicy, TsGetOptions, TsGroupByOptions, TsIncrByDecrByOptions, TsMGetOptions,
+        TsMRangeOptions, TsRangeOptions, TsRangeSample, TsSample,
     },
     tests::get_redis_stack_test_client,
     Result,
@@ -734,3 +734,25 @@ async fn ts_revrange() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn ts_config_key() -> Result<()> {
+    assert_eq!("ts-retention-policy", TsConfigKey::RetentionPolicy.name());
+    assert_eq!(None, TsConfigKey::RetentionPolicy.legacy());
+
+    assert_eq!(
+        ModuleConfigValue::Integer(0),
+        TsConfigKey::RetentionPolicy.parse_value("0")?
+    );
+    assert_eq!(
+        ModuleConfigValue::Double(0.5),
+        TsConfigKey::IgnoreMaxValDiff.parse_value("0.5")?
+    );
+    assert_eq!(
+        ModuleConfigValue::Enum("block".to_owned()),
+        TsConfigKey::DuplicatePolicy.parse_value("BLOCK")?
+    );
+    assert!(TsConfigKey::Encoding.parse_value("gzip").is_err());
+
+    Ok(())
+}
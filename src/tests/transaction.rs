@@ -1,6 +1,9 @@
 use crate::{
     client::BatchPreparedCommand,
-    commands::{FlushingMode, ListCommands, ServerCommands, StringCommands, TransactionCommands},
+    commands::{
+        ClientKillOptions, ConnectionCommands, FlushingMode, ListCommands, ServerCommands,
+        StringCommands, TransactionCommands,
+    },
     resp::cmd,
     tests::{get_cluster_test_client, get_test_client},
     Error, RedisError, RedisErrorKind, Result,
@@ -161,6 +164,101 @@ async fn transaction_discard() -> Result<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn transaction_abort() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    client.set("key", 1).await?;
+    client.watch("key").await?;
+
+    let mut transaction = client.create_transaction();
+    transaction.set("key", 2).queue();
+    transaction.abort().await?;
+
+    // set key on another client after the abort
+    let client2 = get_test_client().await?;
+    client2.set("key", 3).await?;
+
+    // the key is not watched anymore
+    let mut transaction = client.create_transaction();
+    transaction.set("key", 4).queue();
+    transaction.execute::<()>().await?;
+
+    let value: i32 = client.get("key").await?;
+    assert_eq!(4, value);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn transaction_disconnection_between_multi_and_exec() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    let client_id = client.client_id().await?;
+
+    // connection left between MULTI and EXEC
+    client.send(cmd("MULTI"), None).await?;
+    let queued: String = client
+        .send(cmd("SET").arg("key").arg("value"), None)
+        .await?
+        .to()?;
+    assert_eq!("QUEUED", queued);
+
+    let client2 = get_test_client().await?;
+    client2
+        .client_kill(ClientKillOptions::default().id(client_id))
+        .await?;
+
+    // the new connection is not in a transaction anymore
+    let result = client.send(cmd("EXEC"), Some(true)).await;
+    assert!(matches!(
+        result,
+        Err(Error::Redis(RedisError {
+            kind: RedisErrorKind::Err,
+            description: _
+        }))
+    ));
+    let value: Option<String> = client.get("key").await?;
+    assert_eq!(None, value);
+
+    let mut transaction = client.create_transaction();
+    transaction.set("key", "value").forget();
+    transaction.get::<_, ()>("key").queue();
+    let value: String = transaction.execute().await?;
+    assert_eq!("value", value);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn transaction_execabort() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    let mut transaction = client.create_transaction();
+    transaction.set("key", "value").forget();
+    // wrong number of arguments: the transaction is aborted by EXEC
+    transaction.queue(cmd("GET"));
+    let result: Result<()> = transaction.execute().await;
+    assert!(matches!(result, Err(Error::Redis(_))));
+
+    // the transaction has been discarded by the server
+    let value: Option<String> = client.get("key").await?;
+    assert_eq!(None, value);
+    let result = client.send(cmd("DISCARD"), None).await;
+    assert!(result.is_err());
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[serial]
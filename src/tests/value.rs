@@ -2,8 +2,9 @@ use crate::{
     commands::{FlushingMode, ServerCommands, SetCommands},
     resp::Value,
     tests::{get_test_client, log_try_init},
-    RedisError, RedisErrorKind, Result,
+    Error, RedisError, RedisErrorKind, Result,
 };
+use serde::Deserialize;
 use serial_test::serial;
 use std::collections::{BTreeSet, HashMap, HashSet};
 
@@ -102,3 +103,261 @@ fn display() {
         ])
     );
 }
+
+#[test]
+fn pretty() {
+    let value = Value::Array(vec![
+        Value::SimpleString("OK".to_owned()),
+        Value::Map(HashMap::from([
+            (Value::BulkString(b"field2".to_vec()), Value::Double(12.12)),
+            (
+                Value::BulkString(b"field1".to_vec()),
+                Value::Push(vec![Value::Boolean(true), Value::Nil]),
+            ),
+        ])),
+        Value::Error(RedisError {
+            kind: RedisErrorKind::Err,
+            description: "MyError".to_owned(),
+        }),
+        Value::Array(vec![]),
+    ]);
+
+    assert_eq!(
+        r#"(array) [4]
+  0) (simple-string) OK
+  1) (map) {2}
+    (bulk-string) "field1" => (push) >[2]
+      0) (boolean) true
+      1) (nil)
+    (bulk-string) "field2" => (double) 12.12
+  2) (error) ERR MyError
+  3) (array) [0]"#,
+        value.pretty()
+    );
+}
+
+#[test]
+fn diff() {
+    let value = Value::Array(vec![
+        Value::Integer(12),
+        Value::Map(HashMap::from([(
+            Value::BulkString(b"field".to_vec()),
+            Value::Array(vec![Value::Boolean(true)]),
+        )])),
+    ]);
+
+    assert_eq!(None, value.diff(&value));
+
+    let other = Value::Array(vec![
+        Value::Integer(12),
+        Value::Map(HashMap::from([(
+            Value::BulkString(b"field".to_vec()),
+            Value::Array(vec![Value::Boolean(false)]),
+        )])),
+    ]);
+    assert_eq!(
+        Some("$[1]{field}[0]: (boolean) true != (boolean) false".to_owned()),
+        value.diff(&other)
+    );
+
+    let other = Value::Array(vec![
+        Value::Integer(12),
+        Value::Map(HashMap::from([(
+            Value::BulkString(b"other".to_vec()),
+            Value::Nil,
+        )])),
+    ]);
+    assert_eq!(
+        Some("$[1]{field}: missing on the right side".to_owned()),
+        value.diff(&other)
+    );
+
+    let other = Value::Array(vec![Value::Integer(12)]);
+    assert_eq!(
+        Some("$: (array) [2] != (array) [1]".to_owned()),
+        value.diff(&other)
+    );
+    assert_eq!(
+        Some("$: (array) [2] != (integer) 12".to_owned()),
+        value.diff(&Value::Integer(12))
+    );
+}
+
+#[test]
+fn conversions() -> Result<()> {
+    let value = Value::Integer(12);
+    assert_eq!(Some(12), value.as_i64());
+    assert_eq!(None, value.as_str());
+    assert_eq!(12, i64::try_from(value)?);
+    assert_eq!(1.5, f64::try_from(Value::Double(1.5))?);
+    assert!(bool::try_from(Value::Boolean(true))?);
+
+    let value = Value::BulkString(b"value".to_vec());
+    assert_eq!(Some("value"), value.as_str());
+    assert_eq!(Some(&b"value"[..]), value.as_bulk_string());
+    assert_eq!(b"value".to_vec(), value.into_bulk_string()?);
+    assert_eq!(
+        "value",
+        String::try_from(Value::BulkString(b"value".to_vec()))?
+    );
+    assert_eq!(
+        "value",
+        String::try_from(Value::SimpleString("value".to_owned()))?
+    );
+
+    let value = Value::Array(vec![Value::Integer(1), Value::Nil]);
+    assert_eq!(2, value.as_array().map(<[Value]>::len).unwrap_or_default());
+    assert_eq!(vec![Value::Integer(1), Value::Nil], value.into_array()?);
+
+    let value = Value::Map(HashMap::from([(
+        Value::BulkString(b"field".to_vec()),
+        Value::Integer(12),
+    )]));
+    assert_eq!(1, value.as_map().map(HashMap::len).unwrap_or_default());
+    assert_eq!(1, HashMap::<Value, Value>::try_from(value)?.len());
+
+    let result = Value::BulkString(b"12".to_vec()).into_array();
+    assert!(
+        matches!(result, Err(Error::Client(e)) if e == "Expected Value::Array, got Value::BulkString")
+    );
+    let result = i64::try_from(Value::Nil);
+    assert!(
+        matches!(result, Err(Error::Client(e)) if e == "Expected Value::Integer, got Value::Nil")
+    );
+    let result = Value::Error(RedisError {
+        kind: RedisErrorKind::Err,
+        description: "error".to_owned(),
+    })
+    .into_map();
+    assert!(matches!(result, Err(Error::Redis(_))));
+
+    Ok(())
+}
+
+#[test]
+fn verbatim_string_conversions() -> Result<()> {
+    let value = Value::VerbatimString {
+        format: "txt".to_owned(),
+        text: b"12".to_vec(),
+    };
+    assert_eq!(Some("12"), value.as_str());
+    assert_eq!(12, i64::deserialize(&value)?);
+    assert_eq!("12", value.into_string()?);
+
+    Ok(())
+}
+
+#[test]
+fn hash() {
+    let attributed = |value| Value::Attribute {
+        attributes: HashMap::from([(Value::SimpleString("ttl".to_owned()), Value::Integer(3600))]),
+        value: Box::new(value),
+    };
+
+    let set: HashSet<Value> = HashSet::from([
+        attributed(Value::Integer(12)),
+        Value::Array(vec![Value::Boolean(true), Value::Nil]),
+        Value::Map(HashMap::from([
+            (Value::SimpleString("a".to_owned()), Value::Integer(1)),
+            (Value::SimpleString("b".to_owned()), Value::Integer(2)),
+        ])),
+    ]);
+
+    assert!(set.contains(&attributed(Value::Integer(12))));
+    assert!(!set.contains(&attributed(Value::Integer(13))));
+    assert!(set.contains(&Value::Array(vec![Value::Boolean(true), Value::Nil])));
+    assert!(!set.contains(&Value::Array(vec![Value::Boolean(false), Value::Nil])));
+    assert!(set.contains(&Value::Map(HashMap::from([
+        (Value::SimpleString("b".to_owned()), Value::Integer(2)),
+        (Value::SimpleString("a".to_owned()), Value::Integer(1)),
+    ]))));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_conversions() -> Result<()> {
+    use crate::resp::JsonBinaryEncoding;
+    use serde_json::json;
+
+    let value = Value::Array(vec![
+        Value::SimpleString("OK".to_owned()),
+        Value::Integer(12),
+        Value::Double(1.5),
+        Value::Double(f64::INFINITY),
+        Value::BulkString(vec![0xff, b'a']),
+        Value::Boolean(true),
+        Value::Set(vec![Value::Integer(1)]),
+        Value::Map(HashMap::from([
+            (Value::BulkString(b"field".to_vec()), Value::Integer(1)),
+            (Value::Integer(2), Value::Nil),
+        ])),
+        Value::Error(RedisError {
+            kind: RedisErrorKind::Err,
+            description: "failure".to_owned(),
+        }),
+        Value::BigNumber("12345678901234567890".to_owned()),
+        Value::Attribute {
+            attributes: HashMap::new(),
+            value: Box::new(Value::Integer(3)),
+        },
+        Value::Nil,
+    ]);
+
+    assert_eq!(
+        json!([
+            "OK",
+            12,
+            1.5,
+            "inf",
+            "\u{fffd}a",
+            true,
+            [1],
+            {"field": 1, "2": null},
+            {"error": "ERR failure"},
+            "12345678901234567890",
+            3,
+            null
+        ]),
+        value.to_json(JsonBinaryEncoding::Utf8)
+    );
+    assert_eq!(
+        json!(["/2E=", {"ZmllbGQ=": 1}]),
+        Value::Array(vec![
+            Value::BulkString(vec![0xff, b'a']),
+            Value::Map(HashMap::from([(
+                Value::BulkString(b"field".to_vec()),
+                Value::Integer(1)
+            )])),
+        ])
+        .to_json(JsonBinaryEncoding::Base64)
+    );
+
+    let value: Value =
+        json!({"key": [1, -2, 2.5, 18446744073709551615u64, "value", true, null]}).into();
+    assert_eq!(
+        Value::Map(HashMap::from([(
+            Value::BulkString(b"key".to_vec()),
+            Value::Array(vec![
+                Value::Integer(1),
+                Value::Integer(-2),
+                Value::Double(2.5),
+                Value::BigNumber("18446744073709551615".to_owned()),
+                Value::BulkString(b"value".to_vec()),
+                Value::Boolean(true),
+                Value::Nil,
+            ])
+        )])),
+        value
+    );
+
+    let value = Value::from_json(json!("/2E="), JsonBinaryEncoding::Base64)?;
+    assert_eq!(Value::BulkString(vec![0xff, b'a']), value);
+    assert!(Value::from_json(json!("not base64!"), JsonBinaryEncoding::Base64).is_err());
+
+    // round trip
+    let value = Value::Array(vec![Value::BulkString(vec![0, 1, 2]), Value::Integer(1)]);
+    let json = value.to_json(JsonBinaryEncoding::Base64);
+    assert_eq!(value, Value::from_json(json, JsonBinaryEncoding::Base64)?);
+
+    Ok(())
+}
@@ -4,6 +4,7 @@ use crate::{
     Error, RedisError, RedisErrorKind, Result,
 };
 use serde::Deserialize;
+use std::collections::HashMap;
 
 fn deserialize_value(str: &str) -> Result<Value> {
     let buf = str.as_bytes();
@@ -216,3 +217,75 @@ fn blob_error() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn big_number() -> Result<()> {
+    log_try_init();
+
+    let result = deserialize_value("(3492890328409238509324850943850943825024385\r\n")?;
+    assert_eq!(
+        Value::BigNumber("3492890328409238509324850943850943825024385".to_owned()),
+        result
+    );
+
+    Ok(())
+}
+
+#[test]
+fn verbatim_string() -> Result<()> {
+    log_try_init();
+
+    let result = deserialize_value("=15\r\ntxt:Some string\r\n")?;
+    assert_eq!(
+        Value::VerbatimString {
+            format: "txt".to_owned(),
+            text: b"Some string".to_vec()
+        },
+        result
+    );
+
+    Ok(())
+}
+
+#[test]
+fn attribute() -> Result<()> {
+    log_try_init();
+
+    let result = deserialize_value(
+        "|1\r\n+key-popularity\r\n%1\r\n$1\r\na\r\n,0.1923\r\n*2\r\n:2039123\r\n:9543892\r\n",
+    )?;
+    assert_eq!(
+        Value::Attribute {
+            attributes: HashMap::from([(
+                Value::SimpleString("key-popularity".to_owned()),
+                Value::Array(vec![
+                    Value::BulkString(b"a".to_vec()),
+                    Value::Double(0.1923)
+                ])
+            )]),
+            value: Box::new(Value::Array(vec![
+                Value::Integer(2039123),
+                Value::Integer(9543892)
+            ]))
+        },
+        result
+    );
+
+    // nested attribute
+    let result = deserialize_value("*2\r\n|1\r\n+ttl\r\n:3600\r\n+a\r\n+b\r\n")?;
+    assert_eq!(
+        Value::Array(vec![
+            Value::Attribute {
+                attributes: HashMap::from([(
+                    Value::SimpleString("ttl".to_owned()),
+                    Value::Integer(3600)
+                )]),
+                value: Box::new(Value::SimpleString("a".to_owned()))
+            },
+            Value::SimpleString("b".to_owned())
+        ]),
+        result
+    );
+
+    Ok(())
+}
@@ -633,3 +633,29 @@ fn _enum() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn resp3_types() -> Result<()> {
+    log_try_init();
+
+    let result = u64::deserialize(&Value::BigNumber("18446744073709551615".to_owned()))?;
+    assert_eq!(u64::MAX, result);
+
+    let result = String::deserialize(&Value::VerbatimString {
+        format: "txt".to_owned(),
+        text: b"Some string".to_vec(),
+    })?;
+    assert_eq!("Some string", result);
+
+    let value = Value::Attribute {
+        attributes: HashMap::from([(Value::SimpleString("ttl".to_owned()), Value::Integer(3600))]),
+        value: Box::new(Value::Integer(12)),
+    };
+    let result = i64::deserialize(&value)?;
+    assert_eq!(12, result);
+
+    let result = Value::deserialize(&value)?;
+    assert_eq!(value, result);
+
+    Ok(())
+}
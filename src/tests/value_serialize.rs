@@ -4,6 +4,7 @@ use crate::{
     RedisError, RedisErrorKind, Result,
 };
 use serde::Serialize;
+use std::collections::HashMap;
 
 fn serialize(value: Value) -> Result<RespBuf> {
     let mut serializer = RespSerializer::new();
@@ -78,3 +79,30 @@ fn array() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn resp3_types() -> Result<()> {
+    log_try_init();
+
+    let resp_buf = serialize(Value::BigNumber("1234567890123456789012345".to_owned()))?;
+    assert_eq!(b"(1234567890123456789012345\r\n", resp_buf.as_bytes());
+
+    let resp_buf = serialize(Value::VerbatimString {
+        format: "txt".to_owned(),
+        text: b"Some string".to_vec(),
+    })?;
+    assert_eq!(b"=15\r\ntxt:Some string\r\n", resp_buf.as_bytes());
+
+    let value = || Value::Attribute {
+        attributes: HashMap::from([(
+            Value::SimpleString("ttl".to_owned()),
+            Value::Integer(3600),
+        )]),
+        value: Box::new(Value::SimpleString("OK".to_owned())),
+    };
+    let resp_buf = serialize(value())?;
+    assert_eq!(b"|1\r\n+ttl\r\n:3600\r\n+OK\r\n", resp_buf.as_bytes());
+    assert_eq!(value(), resp_buf.to::<Value>()?);
+
+    Ok(())
+}
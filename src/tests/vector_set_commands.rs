@@ -0,0 +1,144 @@
+use crate::{
+    commands::{
+        FlushingMode, ServerCommands, VAddOptions, VFilter, VQuantization, VSimOptions, VSimQuery,
+        VectorSetCommands,
+    },
+    tests::get_test_client,
+    Result,
+};
+use serial_test::serial;
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn vadd() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    let added = client
+        .vadd(
+            "points",
+            &[1.0, 1.0],
+            "pt:A",
+            VAddOptions::default().quantization(VQuantization::NoQuant),
+        )
+        .await?;
+    assert!(added);
+    let added = client
+        .vadd(
+            "points",
+            &[-1.0, -1.0],
+            "pt:B",
+            VAddOptions::default().quantization(VQuantization::NoQuant),
+        )
+        .await?;
+    assert!(added);
+
+    assert_eq!(2, client.vcard("points").await?);
+    assert_eq!(2, client.vdim("points").await?);
+
+    let vector: Vec<f32> = client.vemb("points", "pt:A").await?;
+    assert_eq!(vec![1.0, 1.0], vector);
+
+    assert!(client.vrem("points", "pt:B").await?);
+    assert!(!client.vrem("points", "pt:B").await?);
+    assert_eq!(1, client.vcard("points").await?);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[serial]
+async fn vsim() -> Result<()> {
+    let client = get_test_client().await?;
+    client.flushdb(FlushingMode::Sync).await?;
+
+    for (vector, element, year) in [
+        ([1.0, 1.0], "pt:A", 1975),
+        ([-1.0, -1.0], "pt:B", 1985),
+        ([-1.0, 1.0], "pt:C", 1995),
+        ([1.0, -1.0], "pt:D", 2005),
+        ([1.0, 0.0], "pt:E", 2015),
+    ] {
+        client
+            .vadd(
+                "points",
+                &vector,
+                element,
+                VAddOptions::default().setattr(format!(r#"{{"year": {year}}}"#)),
+            )
+            .await?;
+    }
+
+    let elements: Vec<String> = client
+        .vsim(
+            "points",
+            VSimQuery::vector(&[0.9, 0.1]),
+            VSimOptions::default().count(2),
+        )
+        .await?;
+    assert_eq!(vec!["pt:E".to_owned(), "pt:A".to_owned()], elements);
+
+    let elements: Vec<(String, f32)> = client
+        .vsim_with_scores(
+            "points",
+            VSimQuery::element("pt:A"),
+            VSimOptions::default().count(1),
+        )
+        .await?;
+    assert_eq!(1, elements.len());
+    assert_eq!("pt:A", elements[0].0);
+    assert!(elements[0].1 > 0.99);
+
+    let elements: Vec<String> = client
+        .vsim(
+            "points",
+            VSimQuery::element("pt:A"),
+            VSimOptions::default()
+                .filter(VFilter::gte("year", 1990).and(VFilter::lt("year", 2010))),
+        )
+        .await?;
+    assert_eq!(2, elements.len());
+    assert!(elements.contains(&"pt:C".to_owned()));
+    assert!(elements.contains(&"pt:D".to_owned()));
+
+    assert!(
+        client
+            .vsetattr("points", "pt:A", r#"{"year": 2020}"#)
+            .await?
+    );
+    let attributes = client.vgetattr("points", "pt:A").await?;
+    assert_eq!(Some(r#"{"year": 2020}"#.to_owned()), attributes);
+
+    Ok(())
+}
+
+#[test]
+fn vfilter() {
+    assert_eq!(
+        r#".genre == "sci-fi \"classic\"""#,
+        VFilter::eq("genre", r#"sci-fi "classic""#).to_string()
+    );
+    assert_eq!(
+        "(.year > 1980) and (.year <= 1990) and (.rating != 2.5)",
+        VFilter::gt("year", 1980)
+            .and(VFilter::lte("year", 1990))
+            .and(VFilter::ne("rating", 2.5))
+            .to_string()
+    );
+    assert_eq!(
+        "((.a == 1) or (.b == true)) and (.c in [1, 2])",
+        VFilter::eq("a", 1)
+            .or(VFilter::eq("b", true))
+            .and(VFilter::is_in("c", [1, 2]))
+            .to_string()
+    );
+    assert_eq!("!(.year < 2000)", (!VFilter::lt("year", 2000)).to_string());
+    assert_eq!(
+        "(.year > 1980 and .rating >= 4) or (.genre == \"drama\")",
+        VFilter::raw(".year > 1980 and .rating >= 4")
+            .or(VFilter::eq("genre", "drama"))
+            .to_string()
+    );
+}